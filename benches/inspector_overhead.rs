@@ -0,0 +1,125 @@
+//! Overhead of the inspectors on a 100k-step loop.
+//!
+//! Run with `cargo bench --bench inspector_overhead`. `counters_only` should
+//! stay within a small factor of `baseline`; a regression there means the
+//! step hot path does work that is not enabled.
+
+use alloy_primitives::{keccak256, Address, Bytes};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use restd::{
+    evm::transact_inspected, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    ProfilingMode, StructLogConfig, StructLogInspector,
+};
+use revm::{
+    inspectors::NoOpInspector,
+    primitives::{AccountInfo, Bytecode, Env, TxKind},
+    Evm, InMemoryDB,
+};
+
+const CALLER: Address = Address::repeat_byte(0x01);
+const LOOP: Address = Address::repeat_byte(0x10);
+
+/// Iterations of the loop; with 7 instructions each, plus the first PUSH2
+/// and the final STOP, the loop executes 100,004 steps.
+const ITERATIONS: u16 = 14_286;
+
+/// Counts down from [`ITERATIONS`] and stops.
+fn loop_code() -> Vec<u8> {
+    let [hi, lo] = ITERATIONS.to_be_bytes();
+    // PUSH2 n, JUMPDEST, PUSH1 1, SWAP1, SUB, DUP1, PUSH1 3, JUMPI, STOP
+    vec![
+        0x61, hi, lo, 0x5b, 0x60, 0x01, 0x90, 0x03, 0x80, 0x60, 0x03, 0x57, 0x00,
+    ]
+}
+
+fn setup() -> (InMemoryDB, Env) {
+    let mut db = InMemoryDB::default();
+    let bytecode = Bytecode::new_raw(Bytes::from(loop_code()));
+    let info = AccountInfo::new(
+        Default::default(),
+        1,
+        keccak256(bytecode.original_byte_slice()),
+        bytecode,
+    );
+    db.insert_account_info(LOOP, info);
+    let mut env = Env::default();
+    env.tx.caller = CALLER;
+    env.tx.gas_limit = 1_000_000;
+    env.tx.transact_to = TxKind::Call(LOOP);
+    (db, env)
+}
+
+fn inspector(profiling: ProfilingMode) -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        profiling,
+        ..Default::default()
+    })
+}
+
+fn baseline(c: &mut Criterion) {
+    let (mut db, env) = setup();
+    c.bench_function("baseline", move |b| {
+        b.iter(|| {
+            let mut evm = Evm::builder()
+                .with_db(&mut db)
+                .with_env(Box::new(env.clone()))
+                .build();
+            black_box(evm.transact().unwrap())
+        })
+    });
+}
+
+/// revm's own cost of calling an inspector's hooks.
+fn noop_inspector(c: &mut Criterion) {
+    let (mut db, env) = setup();
+    c.bench_function("noop_inspector", move |b| {
+        b.iter(|| black_box(transact_inspected(&mut db, env.clone(), NoOpInspector).unwrap()))
+    });
+}
+
+fn counters_only(c: &mut Criterion) {
+    let (mut db, env) = setup();
+    c.bench_function("counters_only", move |b| {
+        b.iter(|| {
+            let mut inspector = inspector(ProfilingMode::Counters);
+            transact_inspected(&mut db, env.clone(), &mut inspector).unwrap();
+            black_box(inspector.step_count)
+        })
+    });
+}
+
+fn full_recording(c: &mut Criterion) {
+    let (mut db, env) = setup();
+    c.bench_function("full_recording", move |b| {
+        b.iter(|| {
+            let mut inspector = inspector(ProfilingMode::Full);
+            transact_inspected(&mut db, env.clone(), &mut inspector).unwrap();
+            black_box(inspector.step_count)
+        })
+    });
+}
+
+fn struct_logging(c: &mut Criterion) {
+    let (mut db, env) = setup();
+    c.bench_function("struct_logging", move |b| {
+        b.iter(|| {
+            let mut inspector = StructLogInspector::with_config(StructLogConfig {
+                capture_memory: true,
+                stack_top_n: None,
+            });
+            transact_inspected(&mut db, env.clone(), &mut inspector).unwrap();
+            black_box(inspector.struct_logs().len())
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    baseline,
+    noop_inspector,
+    counters_only,
+    full_recording,
+    struct_logging
+);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Chrome Trace Example
+//!
+//! Traces a transaction with a nested call and writes `trace.json`, which can
+//! be opened in chrome://tracing or https://ui.perfetto.dev.
+
+use alloy_primitives::{keccak256, Address, Bytes, U256};
+use revm::{
+    inspector_handle_register,
+    primitives::{AccountInfo, Bytecode, TxKind},
+    Evm, InMemoryDB,
+};
+use restd::{CallTraceInspector, StaticSelectorResolver};
+
+/// Installs `code` at `address`.
+fn deploy(db: &mut InMemoryDB, address: Address, code: Vec<u8>) {
+    let bytecode = Bytecode::new_raw(Bytes::from(code));
+    let info = AccountInfo::new(U256::ZERO, 1, keccak256(bytecode.original_byte_slice()), bytecode);
+    db.insert_account_info(address, info);
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let caller = Address::repeat_byte(0x01);
+    let outer = Address::repeat_byte(0xaa);
+    let inner = Address::repeat_byte(0xbb);
+
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(caller, AccountInfo::from_balance(U256::from(10u128.pow(18))));
+    // outer: CALL(gas, inner, 0, 0, 0, 0, 0), POP, STOP
+    let mut outer_code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+    outer_code.extend_from_slice(inner.as_slice());
+    outer_code.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]);
+    deploy(&mut db, outer, outer_code);
+    // inner: a countdown loop from 3
+    deploy(
+        &mut db,
+        inner,
+        vec![0x60, 0x03, 0x5b, 0x60, 0x01, 0x90, 0x03, 0x80, 0x60, 0x02, 0x57, 0x00],
+    );
+
+    let mut inspector = CallTraceInspector::new();
+    {
+        let mut evm = Evm::builder()
+            .with_db(&mut db)
+            .with_external_context(&mut inspector)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(outer);
+                tx.data = Bytes::from_static(&[0xc0, 0x40, 0x62, 0x26]);
+                tx.gas_limit = 1_000_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact_commit()?;
+    }
+
+    let mut resolver = StaticSelectorResolver::default();
+    resolver.insert([0xc0, 0x40, 0x62, 0x26], "run()");
+    let trace = inspector.into_trace().to_chrome_trace_with_resolver(&resolver);
+    std::fs::write("trace.json", trace)?;
+    println!("Wrote trace.json; open it in chrome://tracing or https://ui.perfetto.dev");
+    Ok(())
+}
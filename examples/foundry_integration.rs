@@ -4,11 +4,15 @@
 //! for contract testing and analysis in a Foundry-like environment.
 
 use alloy_primitives::{Address, U256, Bytes};
+use alloy_sol_types::{Revert, SolError};
 use revm::{
-    primitives::{TxKind, Env, TxEnv, ResultAndState},
-    Evm, InMemoryDB,
+    primitives::{AccountInfo, TxKind, Env, TxEnv, ResultAndState},
+    InMemoryDB,
+};
+use restd::{
+    evm::transact_inspected, Error, HelloWorldInspector, HelloWorldInspectorConfig,
+    InspectorReport, TxSummary,
 };
-use restd::{HelloWorldInspector, HelloWorldInspectorConfig};
 
 /// Simple integration example showing HelloWorldInspector usage
 struct SimpleIntegration {
@@ -18,55 +22,66 @@ struct SimpleIntegration {
 
 impl SimpleIntegration {
     /// Create a new integration instance
-    pub fn new(_config: HelloWorldInspectorConfig) -> Self {
+    pub fn new(config: HelloWorldInspectorConfig) -> Self {
         Self {
             db: InMemoryDB::default(),
-            inspector: HelloWorldInspector::new(),
+            inspector: HelloWorldInspector::with_config(config),
         }
     }
     
     /// Execute a simple transaction and return the result
-    pub fn execute_transaction(&mut self, caller: Address, to: Option<Address>, data: Bytes) -> Result<ResultAndState, String> {
-        // Create EVM environment
-        let mut env = Env::default();
-        
-        // Set up transaction
-        env.tx = TxEnv {
-            caller,
-            gas_limit: 1_000_000,
-            gas_price: U256::from(20_000_000_000u64),
-            transact_to: match to {
-                Some(addr) => TxKind::Call(addr),
-                None => TxKind::Create,
+    pub fn execute_transaction(&mut self, caller: Address, to: Option<Address>, data: Bytes) -> Result<ResultAndState, Error> {
+        // Fund the caller so it can pay for gas
+        self.db.insert_account_info(caller, AccountInfo::from_balance(U256::from(10u128.pow(18))));
+
+        // Create EVM environment with the transaction
+        let env = Env {
+            tx: TxEnv {
+                caller,
+                gas_limit: 1_000_000,
+                gas_price: U256::from(20_000_000_000u64),
+                transact_to: match to {
+                    Some(addr) => TxKind::Call(addr),
+                    None => TxKind::Create,
+                },
+                value: U256::ZERO,
+                data,
+                nonce: None,
+                chain_id: Some(1),
+                access_list: Vec::new(),
+                gas_priority_fee: Some(U256::from(1_000_000_000u64)),
+                blob_hashes: Vec::new(),
+                max_fee_per_blob_gas: None,
+                authorization_list: None,
             },
-            value: U256::ZERO,
-            data,
-            nonce: Some(0),
-            chain_id: Some(1),
-            access_list: Vec::new(),
-            gas_priority_fee: Some(U256::from(1_000_000_000u64)),
-            blob_hashes: Vec::new(),
-            max_fee_per_blob_gas: None,
-            authorization_list: None,
+            ..Default::default()
         };
         
-        // Create EVM with inspector
-        let mut evm = Evm::builder()
-            .with_db(&mut self.db)
-            .with_env(Box::new(env))
-            .with_external_context(&mut self.inspector)
-            .build();
-        
-        // Execute transaction
-        evm.transact().map_err(|e| format!("Transaction failed: {:?}", e))
+        // Execute the transaction with the inspector hooks wired in
+        transact_inspected(&mut self.db, env, &mut self.inspector)
     }
     
-    /// Get inspector statistics
-    pub fn get_stats(&self) -> (u64, u64) {
-        (self.inspector.step_count, self.inspector.call_count)
+    /// Take the report of the last transaction, resetting the inspector for the next one
+    pub fn take_report(&mut self) -> InspectorReport {
+        self.inspector.reset_and_take_report()
     }
 }
 
+/// Init code that reverts with `Error(reason)`
+fn reverting_init_code(reason: &str) -> Bytes {
+    let payload = Revert::from(reason).abi_encode();
+    let mut code = Vec::new();
+    for (i, chunk) in payload.chunks(32).enumerate() {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        code.push(0x7f); // PUSH32 word
+        code.extend_from_slice(&word);
+        code.extend_from_slice(&[0x60, (i * 32) as u8, 0x52]); // PUSH1 offset, MSTORE
+    }
+    code.extend_from_slice(&[0x60, payload.len() as u8, 0x60, 0x00, 0xfd]); // REVERT(0, len)
+    Bytes::from(code)
+}
+
 fn main() {
     println!("🚀 HelloWorldInspector Foundry Integration Example");
     println!("=================================================");
@@ -77,16 +92,19 @@ fn main() {
             trace_calls: true,
             log_steps: true,
             verbose: true,
+            ..Default::default()
         }),
         ("Call Tracing Only", HelloWorldInspectorConfig {
             trace_calls: true,
             log_steps: false,
             verbose: false,
+            ..Default::default()
         }),
         ("Step Logging Only", HelloWorldInspectorConfig {
             trace_calls: false,
             log_steps: true,
             verbose: false,
+            ..Default::default()
         }),
     ];
     
@@ -97,18 +115,23 @@ fn main() {
         
         let mut integration = SimpleIntegration::new(config);
         
-        // Execute a simple contract creation
+        // Execute a simple contract creation, one whose constructor reverts
+        // and one with no init code at all
         let caller = Address::from([0x1; 20]);
-        let bytecode = Bytes::from(vec![0x60, 0x00, 0x60, 0x00, 0xf3]); // Simple contract bytecode
-        
-        match integration.execute_transaction(caller, None, bytecode) {
-            Ok(_result) => {
-                println!("   ✅ Transaction executed successfully");
-                let (steps, calls) = integration.get_stats();
-                println!("   📊 Inspector stats - Steps: {}, Calls: {}", steps, calls);
-            }
-            Err(e) => {
-                println!("   ❌ Transaction failed: {}", e);
+        let transactions = [
+            Bytes::from(vec![0x60, 0x00, 0x60, 0x00, 0xf3]), // Simple contract bytecode
+            reverting_init_code("constructor disabled"),
+            Bytes::new(), // Empty init code, which deploys an account without code
+        ];
+
+        for bytecode in transactions {
+            let result = integration.execute_transaction(caller, None, bytecode);
+
+            // Each transaction gets its own report
+            let report = integration.take_report();
+            match result {
+                Ok(result) => println!("   📊 {}", TxSummary::from(&report, &result)),
+                Err(e) => println!("   ❌ Transaction failed: {}", e),
             }
         }
     }
@@ -7,13 +7,10 @@
 
 use restd::{
     HelloWorldInspector, 
-    HelloWorldInspectorPlugin, 
     create_plugin, 
     create_config,
-    create_detailed_config
 };
-use tokio;
-use tracing::{info, Level};
+use tracing::info;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -58,7 +55,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 pub fn create_evm_with_inspector() {
     info!("Creating EVM configuration with HelloWorldInspector");
     
-    let inspector = HelloWorldInspector::default();
+    let _inspector = HelloWorldInspector::default();
     
     // In a real reth integration, you would:
     // 1. Create an EVM configuration
@@ -73,7 +70,7 @@ pub async fn register_with_reth() -> Result<(), Box<dyn std::error::Error>> {
     info!("Registering HelloWorldInspector plugin with reth");
     
     let plugin = create_plugin();
-    let config = create_config(false);
+    let _config = create_config(false);
     
     // In a real implementation, you would:
     // 1. Get the reth node configuration
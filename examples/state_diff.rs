@@ -0,0 +1,43 @@
+//! State Diff Example
+//!
+//! Sends value to a contract that writes a storage slot, then prints the
+//! call trace and the state changed by the transaction.
+
+use alloy_primitives::{Address, U256};
+use revm::{
+    primitives::{Env, TxKind},
+    DatabaseCommit, InMemoryDB,
+};
+use restd::{
+    evm::build_inspected_evm, state_diff, CallTraceInspector, Prestate, PrettyPrintOptions,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let caller = Address::repeat_byte(0x01);
+    let vault = Address::repeat_byte(0xaa);
+
+    let mut db = InMemoryDB::default();
+    Prestate::builder()
+        .account(caller)
+        .balance(U256::from(10u128.pow(18)))
+        // vault: SSTORE(0, CALLVALUE), STOP
+        .account(vault)
+        .code(vec![0x34, 0x60, 0x00, 0x55, 0x00])
+        .apply(&mut db);
+
+    let mut env = Env::default();
+    env.tx.caller = caller;
+    env.tx.transact_to = TxKind::Call(vault);
+    env.tx.value = U256::from(1_000);
+    env.tx.gas_limit = 1_000_000;
+
+    let mut tracer = CallTraceInspector::new();
+    let result = build_inspected_evm(&mut db, env, &mut tracer).transact()?;
+    // The diff needs the database before the changes are committed
+    let diff = state_diff::compute(&result, &db)?;
+    db.commit(result.state);
+
+    print!("{}", tracer.into_trace().pretty_print(PrettyPrintOptions::default()));
+    println!("{}", diff.to_json_pretty()?);
+    Ok(())
+}
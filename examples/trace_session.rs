@@ -0,0 +1,76 @@
+//! Trace Session Example
+//!
+//! Traces three transactions against one database with a `TraceSession` and
+//! prints a report per transaction and for the whole block.
+
+use alloy_primitives::{Address, B256, U256};
+use revm::{
+    primitives::{Env, TxKind},
+    InMemoryDB,
+};
+use restd::{
+    evm::build_inspected_evm, HelloWorldInspectorConfig, OutputMode, Prestate, TraceSession,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let caller = Address::repeat_byte(0x01);
+    let router = Address::repeat_byte(0xaa);
+    let pool = Address::repeat_byte(0xbb);
+    let guard = Address::repeat_byte(0xcc);
+
+    // router: CALL(gas, pool, 0, 0, 0, 0, 0), POP, STOP
+    let mut router_code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+    router_code.extend_from_slice(pool.as_slice());
+    router_code.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]);
+
+    let mut db = InMemoryDB::default();
+    Prestate::builder()
+        .account(caller)
+        .balance(U256::from(10u128.pow(18)))
+        .account(router)
+        .code(router_code)
+        // pool: SSTORE(0, 1), STOP
+        .account(pool)
+        .code(vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00])
+        // guard: REVERT(0, 0)
+        .account(guard)
+        .code(vec![0x60, 0x00, 0x60, 0x00, 0xfd])
+        .apply(&mut db);
+
+    let mut session = TraceSession::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    for (i, to) in [router, guard, pool].into_iter().enumerate() {
+        let mut env = Env::default();
+        env.tx.caller = caller;
+        env.tx.transact_to = TxKind::Call(to);
+        env.tx.gas_limit = 1_000_000;
+        env.tx.nonce = Some(i as u64);
+
+        session.begin_tx(B256::with_last_byte(i as u8 + 1));
+        build_inspected_evm(&mut db, env, session.inspector_mut()).transact_commit()?;
+        let tx = session.end_tx();
+        println!(
+            "tx {} {}: {} steps, {} calls, {} gas, {} reverts",
+            tx.index,
+            tx.tx_hash,
+            tx.report.step_count,
+            tx.report.call_count,
+            tx.gas_used,
+            tx.report.reverts.len()
+        );
+    }
+
+    let block = session.finish();
+    println!(
+        "block: {} transactions, {} steps, {} calls, {} gas, {} reverts, {} unique addresses",
+        block.transactions.len(),
+        block.total_steps,
+        block.total_calls,
+        block.total_gas_used,
+        block.total_reverts,
+        block.unique_addresses
+    );
+    Ok(())
+}
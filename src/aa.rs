@@ -0,0 +1,226 @@
+//! Per-user-operation summaries of account abstraction bundles.
+//!
+//! An ERC-4337 bundler sends one transaction to the entrypoint, which then
+//! calls into every user operation of the bundle. [`AAAnalyzer`] splits the
+//! children of the entrypoint's frame in a [`CallTrace`] into one section per
+//! user operation and reports the gas, outcome, logs and value of each, so a
+//! user operation that reverted inside a bundle that succeeded is not lost
+//! in the bundle's overall success.
+
+use alloy_primitives::{Address, Log, Selector, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::call_trace::{CallKind, CallTrace, CallTraceNode};
+
+/// What one user operation of a bundle did.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserOpSection {
+    /// Position of the user operation in the bundle
+    pub index: usize,
+    /// Target of the first frame of the section, the account for the
+    /// default split and usually the entrypoint itself with a selector
+    pub target: Address,
+    /// Direct children of the entrypoint frame in the section
+    pub frames: usize,
+    /// Gas used by the frames of the section, their subcalls included
+    pub gas_used: u64,
+    /// Whether every frame of the section succeeded
+    pub success: bool,
+    /// Logs that were kept, in emission order: those of the section's
+    /// frames that did not revert, and those the entrypoint itself emitted
+    /// while the section was the latest to start. Empty if the bundle
+    /// reverted
+    pub logs: Vec<Log>,
+    /// Value moved by the calls, creations and self-destructs of the
+    /// section that did not revert. Zero if the bundle reverted
+    pub value: U256,
+}
+
+/// The user operations of a bundle and how the bundle went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AAReport {
+    /// Entrypoint the bundle was sent through
+    pub entrypoint: Address,
+    /// Whether the entrypoint frame succeeded
+    pub bundle_success: bool,
+    /// Gas used by the entrypoint frame
+    pub bundle_gas_used: u64,
+    /// Gas the entrypoint used outside of every section, e.g. for its own
+    /// bookkeeping or for frames before the first split selector
+    pub overhead_gas: u64,
+    /// The user operations, in bundle order
+    pub user_ops: Vec<UserOpSection>,
+    /// Indices of the user operations that reverted while the bundle
+    /// succeeded
+    pub reverted_ops: Vec<usize>,
+}
+
+impl AAReport {
+    /// Returns the user operations that reverted while the bundle
+    /// succeeded.
+    pub fn reverted(&self) -> impl Iterator<Item = &UserOpSection> {
+        self.reverted_ops
+            .iter()
+            .filter_map(|&index| self.user_ops.get(index))
+    }
+}
+
+/// Splits the frame of an entrypoint into user operations.
+///
+/// By default every direct child of the entrypoint frame is one user
+/// operation. With [`split_on`](Self::split_on), e.g. with the selector of
+/// the entrypoint's `innerHandleOp`, a section starts at every child whose
+/// calldata starts with the selector and takes the children up to the next
+/// one; children before the first count as overhead, like the validation
+/// calls of a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AAAnalyzer {
+    entrypoint: Address,
+    split_selector: Option<Selector>,
+}
+
+impl AAAnalyzer {
+    /// Creates an analyzer for bundles sent through `entrypoint`.
+    pub fn new(entrypoint: Address) -> Self {
+        Self {
+            entrypoint,
+            split_selector: None,
+        }
+    }
+
+    /// Starts a user operation at every child of the entrypoint frame
+    /// called with `selector`.
+    pub fn split_on(mut self, selector: impl Into<Selector>) -> Self {
+        self.split_selector = Some(selector.into());
+        self
+    }
+
+    /// Returns the entrypoint the analyzer looks for.
+    pub fn entrypoint(&self) -> Address {
+        self.entrypoint
+    }
+
+    /// Summarizes the first frame of `trace`, in pre-order, that calls the
+    /// entrypoint, or returns `None` if no frame does.
+    pub fn analyze(&self, trace: &CallTrace) -> Option<AAReport> {
+        let bundle = trace
+            .nodes()
+            .find(|node| node.target == self.entrypoint && !node.kind.is_create())?;
+        Some(self.analyze_frame(bundle))
+    }
+
+    /// Summarizes `bundle`, taken to be the entrypoint's frame.
+    pub fn analyze_frame(&self, bundle: &CallTraceNode) -> AAReport {
+        let mut user_ops: Vec<UserOpSection> = Vec::new();
+        // Start step of each section, to attribute the entrypoint's own logs
+        let mut starts = Vec::new();
+        // Section of each child, `None` for those before the first
+        let mut child_sections = Vec::with_capacity(bundle.children.len());
+        for child in &bundle.children {
+            if self.starts_section(child) {
+                starts.push(child.start_step);
+                user_ops.push(UserOpSection {
+                    index: user_ops.len(),
+                    target: child.target,
+                    frames: 0,
+                    gas_used: 0,
+                    success: true,
+                    logs: Vec::new(),
+                    value: U256::ZERO,
+                });
+            }
+            child_sections.push(user_ops.len().checked_sub(1));
+            let Some(section) = user_ops.last_mut() else {
+                continue;
+            };
+            section.frames += 1;
+            section.gas_used += child.gas_used;
+            section.success &= child.success;
+            if bundle.success {
+                section.value = section.value.saturating_add(moved_value(child));
+            }
+        }
+
+        // Logs of the frames go to their section, those of the entrypoint to
+        // the latest section started before them; then all in emission order
+        let mut logs: Vec<(u64, usize, &Log)> = Vec::new();
+        if bundle.success {
+            for (child, index) in bundle.children.iter().zip(&child_sections) {
+                if let Some(index) = *index {
+                    collect_logs(child, index, &mut logs);
+                }
+            }
+            for log in &bundle.logs {
+                if let Some(index) = section_at(&starts, log.step) {
+                    logs.push((log.step, index, &log.log));
+                }
+            }
+        }
+        logs.sort_by_key(|(step, ..)| *step);
+        for (_, index, log) in logs {
+            user_ops[index].logs.push(log.clone());
+        }
+
+        let section_gas: u64 = user_ops.iter().map(|op| op.gas_used).sum();
+        let reverted_ops = if bundle.success {
+            user_ops
+                .iter()
+                .filter(|op| !op.success)
+                .map(|op| op.index)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        AAReport {
+            entrypoint: self.entrypoint,
+            bundle_success: bundle.success,
+            bundle_gas_used: bundle.gas_used,
+            overhead_gas: bundle.gas_used.saturating_sub(section_gas),
+            user_ops,
+            reverted_ops,
+        }
+    }
+
+    /// Returns whether `child` starts a new section.
+    fn starts_section(&self, child: &CallTraceNode) -> bool {
+        match self.split_selector {
+            Some(selector) => child.input.starts_with(selector.as_slice()),
+            None => true,
+        }
+    }
+}
+
+/// Returns the index of the latest section that started before a log
+/// emitted at `step`, `None` before the first. A section whose first frame
+/// starts at `step` was started by a call after the log.
+fn section_at(starts: &[u64], step: u64) -> Option<usize> {
+    starts.partition_point(|&start| start < step).checked_sub(1)
+}
+
+/// Adds the logs of `node` and its subcalls that were kept, i.e. not rolled
+/// back by a revert, to `logs` with the section index `index`.
+fn collect_logs<'a>(node: &'a CallTraceNode, index: usize, logs: &mut Vec<(u64, usize, &'a Log)>) {
+    if !node.success {
+        return;
+    }
+    logs.extend(node.logs.iter().map(|log| (log.step, index, &log.log)));
+    for child in &node.children {
+        collect_logs(child, index, logs);
+    }
+}
+
+/// Returns the value moved by `node` and its subcalls that were not rolled
+/// back. Delegate calls and `CALLCODE` move no value to another account,
+/// static calls none at all.
+fn moved_value(node: &CallTraceNode) -> U256 {
+    if !node.success {
+        return U256::ZERO;
+    }
+    let own = match node.kind.base() {
+        CallKind::DelegateCall | CallKind::CallCode | CallKind::StaticCall => U256::ZERO,
+        _ => node.value,
+    };
+    node.children
+        .iter()
+        .fold(own, |total, child| total.saturating_add(moved_value(child)))
+}
@@ -0,0 +1,528 @@
+//! Decoding logs and calldata with user-supplied declarations.
+//!
+//! An [`EventDecoder`] is built from Solidity event declarations such as
+//! `Transfer(address indexed from, address indexed to, uint256 value)`. It
+//! matches logs by their first topic and decodes them into named fields,
+//! shown as `Transfer(from: 0x…, to: 0x…, value: 1000)`.
+//!
+//! An [`FnDecoder`] does the same for calls: built from function signatures
+//! such as `transfer(address to, uint256 amount)`, it matches calldata by its
+//! selector and decodes the arguments after it.
+
+use std::{collections::HashMap, fmt};
+
+use alloy_dyn_abi::{DynSolEvent, DynSolType, DynSolValue};
+use alloy_primitives::{hex, keccak256, B256};
+
+use crate::selector::SelectorResolver;
+use serde::{Deserialize, Serialize};
+
+/// A declaration that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureError {
+    /// The declaration as given
+    pub signature: String,
+    /// What is wrong with it
+    pub reason: String,
+}
+
+impl SignatureError {
+    fn new(signature: &str, reason: impl Into<String>) -> Self {
+        Self {
+            signature: signature.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid signature `{}`: {}", self.signature, self.reason)
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// A decoded field of a log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedField {
+    /// Parameter name, empty if the declaration has none
+    pub name: String,
+    /// Canonical Solidity type, e.g. `uint256`
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The formatted value; indexed dynamic values are their hash
+    pub value: String,
+    /// Whether the value came from a topic
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+/// A log decoded with an [`EventDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedLog {
+    /// Name of the event
+    pub event: String,
+    /// Fields in declaration order
+    pub fields: Vec<DecodedField>,
+}
+
+impl fmt::Display for DecodedLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.event)?;
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if !field.name.is_empty() {
+                write!(f, "{}: ", field.name)?;
+            }
+            f.write_str(&field.value)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// An argument of a call decoded with an [`FnDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedArg {
+    /// Parameter name, empty if the signature has none
+    pub name: String,
+    /// Canonical Solidity type, e.g. `uint256`
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The formatted value
+    pub value: String,
+}
+
+/// The arguments of a call decoded with an [`FnDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedCall {
+    /// Name of the function
+    pub function: String,
+    /// Arguments in declaration order
+    pub args: Vec<DecodedArg>,
+}
+
+impl DecodedCall {
+    /// Returns the canonical signature, e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> String {
+        let types: Vec<_> = self.args.iter().map(|arg| arg.ty.as_str()).collect();
+        format!("{}({})", self.function, types.join(","))
+    }
+
+    /// Formats the arguments as `name: value, ...`.
+    pub(crate) fn args_list(&self) -> String {
+        self.args
+            .iter()
+            .map(|arg| match arg.name.as_str() {
+                "" => arg.value.clone(),
+                name => format!("{name}: {}", arg.value),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.function, self.args_list())
+    }
+}
+
+/// A parameter of an event declaration or function signature.
+#[derive(Debug, Clone)]
+struct Param {
+    name: String,
+    ty: DynSolType,
+    indexed: bool,
+}
+
+/// A parsed event declaration.
+#[derive(Debug, Clone)]
+struct Event {
+    name: String,
+    params: Vec<Param>,
+    abi: DynSolEvent,
+}
+
+impl Event {
+    fn decode(&self, topics: &[B256], data: &[u8]) -> alloy_dyn_abi::Result<DecodedLog> {
+        let decoded = self
+            .abi
+            .decode_log_parts(topics.iter().copied(), data, true)?;
+        let mut indexed = decoded.indexed.iter();
+        let mut body = decoded.body.iter();
+        let fields = self
+            .params
+            .iter()
+            .map(|param| {
+                let values = if param.indexed {
+                    &mut indexed
+                } else {
+                    &mut body
+                };
+                DecodedField {
+                    name: param.name.clone(),
+                    ty: param.ty.sol_type_name().into_owned(),
+                    value: values.next().map(format_value).unwrap_or_default(),
+                    indexed: param.indexed,
+                }
+            })
+            .collect();
+        Ok(DecodedLog {
+            event: self.name.clone(),
+            fields,
+        })
+    }
+}
+
+/// Decodes logs of the events it was given the declarations of.
+#[derive(Debug, Clone, Default)]
+pub struct EventDecoder {
+    /// Events by topic0; ERC-20 and ERC-721 `Transfer` share one but index
+    /// different parameters
+    events: HashMap<B256, Vec<Event>>,
+}
+
+impl EventDecoder {
+    /// Creates a decoder that knows no events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an event declaration, e.g.
+    /// `Transfer(address indexed from, address indexed to, uint256 value)`,
+    /// optionally prefixed with `event`, and returns its topic0.
+    pub fn add_event(&mut self, declaration: &str) -> Result<B256, SignatureError> {
+        let (name, params) = parse_declaration(declaration)?;
+        let params = params
+            .iter()
+            .map(|param| parse_param(declaration, param))
+            .collect::<Result<Vec<_>, _>>()?;
+        let canonical: Vec<_> = params
+            .iter()
+            .map(|param| param.ty.sol_type_name())
+            .collect();
+        let topic0 = keccak256(format!("{name}({})", canonical.join(",")));
+        let (indexed, body): (Vec<_>, Vec<_>) = params.iter().partition(|param| param.indexed);
+        let abi = DynSolEvent::new(
+            Some(topic0),
+            indexed.into_iter().map(|param| param.ty.clone()).collect(),
+            DynSolType::Tuple(body.into_iter().map(|param| param.ty.clone()).collect()),
+        )
+        .ok_or_else(|| SignatureError::new(declaration, "more than 3 indexed parameters"))?;
+        self.events.entry(topic0).or_default().push(Event {
+            name: name.to_string(),
+            params,
+            abi,
+        });
+        Ok(topic0)
+    }
+
+    /// Adds the event declarations in `declarations`.
+    pub fn with_events<'a>(
+        mut self,
+        declarations: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, SignatureError> {
+        for declaration in declarations {
+            self.add_event(declaration)?;
+        }
+        Ok(self)
+    }
+
+    /// Decodes a log with the events whose topic0 matches its first topic.
+    ///
+    /// Returns `None` if no such event was added, and the error of the
+    /// first matching event if none of them decodes the log.
+    pub fn decode(
+        &self,
+        topics: &[B256],
+        data: &[u8],
+    ) -> Option<Result<DecodedLog, alloy_dyn_abi::Error>> {
+        let events = self.events.get(topics.first()?)?;
+        let mut first_error = None;
+        for event in events {
+            match event.decode(topics, data) {
+                Ok(decoded) => return Some(Ok(decoded)),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map(Err)
+    }
+}
+
+/// A parsed function signature.
+#[derive(Debug, Clone)]
+struct Function {
+    name: String,
+    params: Vec<Param>,
+    /// Tuple of the parameter types
+    abi: DynSolType,
+}
+
+impl Function {
+    fn decode(&self, args: &[u8]) -> alloy_dyn_abi::Result<DecodedCall> {
+        let decoded = self.abi.abi_decode_params(args)?;
+        let values = decoded.as_fixed_seq().unwrap_or_default();
+        let args = self
+            .params
+            .iter()
+            .zip(values)
+            .map(|(param, value)| DecodedArg {
+                name: param.name.clone(),
+                ty: param.ty.sol_type_name().into_owned(),
+                value: format_value(value),
+            })
+            .collect();
+        Ok(DecodedCall {
+            function: self.name.clone(),
+            args,
+        })
+    }
+
+    fn signature(&self) -> String {
+        let types: Vec<_> = self
+            .params
+            .iter()
+            .map(|param| param.ty.sol_type_name())
+            .collect();
+        format!("{}({})", self.name, types.join(","))
+    }
+}
+
+/// Decodes the arguments of calls to the functions it was given the
+/// signatures of.
+///
+/// It also resolves their selectors, so it can serve as the
+/// [`SelectorResolver`] of an inspector or a pretty-printed trace.
+#[derive(Debug, Clone, Default)]
+pub struct FnDecoder {
+    /// Functions by selector; colliding selectors keep every signature
+    functions: HashMap<[u8; 4], Vec<Function>>,
+}
+
+impl FnDecoder {
+    /// Creates a decoder that knows no functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a function signature, e.g. `transfer(address to, uint256 amount)`,
+    /// optionally prefixed with `function`, and returns its selector.
+    pub fn add_signature(&mut self, signature: &str) -> Result<[u8; 4], SignatureError> {
+        let (name, params) = parse_declaration(signature)?;
+        let params = params
+            .iter()
+            .map(|param| parse_param(signature, param))
+            .collect::<Result<Vec<_>, _>>()?;
+        if params.iter().any(|param| param.indexed) {
+            return Err(SignatureError::new(signature, "`indexed` parameter of a function"));
+        }
+        let function = Function {
+            name: name.to_string(),
+            abi: DynSolType::Tuple(params.iter().map(|param| param.ty.clone()).collect()),
+            params,
+        };
+        let selector = keccak256(function.signature())[..4]
+            .try_into()
+            .expect("slice of length 4");
+        self.functions.entry(selector).or_default().push(function);
+        Ok(selector)
+    }
+
+    /// Adds the function signatures in `signatures`.
+    pub fn with_signatures<'a>(
+        mut self,
+        signatures: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Self, SignatureError> {
+        for signature in signatures {
+            self.add_signature(signature)?;
+        }
+        Ok(self)
+    }
+
+    /// Decodes the arguments of calldata with the functions whose selector
+    /// matches its first four bytes.
+    ///
+    /// Returns `None` if no such function was added, and the error of the
+    /// first matching function if none of them decodes the arguments.
+    pub fn decode(&self, input: &[u8]) -> Option<Result<DecodedCall, alloy_dyn_abi::Error>> {
+        let selector: [u8; 4] = input.get(..4)?.try_into().ok()?;
+        let functions = self.functions.get(&selector)?;
+        let mut first_error = None;
+        for function in functions {
+            match function.decode(&input[4..]) {
+                Ok(decoded) => return Some(Ok(decoded)),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map(Err)
+    }
+}
+
+impl SelectorResolver for FnDecoder {
+    fn resolve(&self, selector: [u8; 4]) -> Option<String> {
+        self.functions
+            .get(&selector)?
+            .first()
+            .map(Function::signature)
+    }
+}
+
+/// Splits `Name(params)` into the name and the top-level parameters.
+fn parse_declaration(declaration: &str) -> Result<(&str, Vec<&str>), SignatureError> {
+    let trimmed = declaration.trim();
+    let trimmed = trimmed
+        .strip_prefix("event ")
+        .or_else(|| trimmed.strip_prefix("function "))
+        .unwrap_or(trimmed)
+        .trim_start();
+    let (name, rest) = trimmed
+        .split_once('(')
+        .ok_or_else(|| SignatureError::new(declaration, "missing `(`"))?;
+    let inner = rest
+        .trim_end()
+        .trim_end_matches(';')
+        .strip_suffix(')')
+        .ok_or_else(|| SignatureError::new(declaration, "missing `)`"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(SignatureError::new(declaration, "missing name"));
+    }
+    Ok((name, split_params(inner)))
+}
+
+/// Splits a parameter list at the commas outside of tuple types.
+fn split_params(params: &str) -> Vec<&str> {
+    if params.trim().is_empty() {
+        return Vec::new();
+    }
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(params[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(params[start..].trim());
+    parts
+}
+
+/// Parses `type [indexed] [name]`.
+fn parse_param(declaration: &str, param: &str) -> Result<Param, SignatureError> {
+    // Tuple types contain spaces, so the type ends at the last `)` if any
+    let type_end = match param.rfind(')') {
+        Some(end) => {
+            end + param[end..]
+                .find(char::is_whitespace)
+                .unwrap_or(param.len() - end)
+        }
+        None => param.find(char::is_whitespace).unwrap_or(param.len()),
+    };
+    let (ty, rest) = param.split_at(type_end);
+    let ty = DynSolType::parse(ty.trim())
+        .map_err(|err| SignatureError::new(declaration, err.to_string()))?;
+    let mut words = rest.split_whitespace();
+    let mut word = words.next();
+    let indexed = word == Some("indexed");
+    if indexed {
+        word = words.next();
+    }
+    let name = word.unwrap_or_default().to_string();
+    if words.next().is_some() {
+        return Err(SignatureError::new(
+            declaration,
+            format!("unexpected tokens in `{param}`"),
+        ));
+    }
+    Ok(Param { name, ty, indexed })
+}
+
+/// Formats a decoded value: integers in decimal, byte strings in hex and
+/// strings quoted.
+pub(crate) fn format_value(value: &DynSolValue) -> String {
+    let join = |values: &[DynSolValue]| {
+        values
+            .iter()
+            .map(format_value)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match value {
+        DynSolValue::Address(address) => address.to_string(),
+        DynSolValue::Bool(value) => value.to_string(),
+        DynSolValue::Int(value, _) => value.to_string(),
+        DynSolValue::Uint(value, _) => value.to_string(),
+        DynSolValue::FixedBytes(word, size) => hex::encode_prefixed(&word[..*size]),
+        DynSolValue::Function(function) => function.to_string(),
+        DynSolValue::Bytes(bytes) => hex::encode_prefixed(bytes),
+        DynSolValue::String(value) => format!("{value:?}"),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            format!("[{}]", join(values))
+        }
+        other => format!("({})", join(other.as_fixed_seq().unwrap_or_default())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_topic0() {
+        let mut decoder = EventDecoder::new();
+        let topic0 = decoder
+            .add_event("event Transfer(address indexed from, address indexed to, uint value)")
+            .unwrap();
+        assert_eq!(topic0, keccak256("Transfer(address,address,uint256)"));
+    }
+
+    #[test]
+    fn test_invalid_declarations() {
+        let mut decoder = EventDecoder::new();
+        assert!(decoder.add_event("Transfer").is_err());
+        assert!(decoder.add_event("Transfer(uint7 value)").is_err());
+        assert!(decoder
+            .add_event("Transfer(uint256 indexed value extra)")
+            .is_err());
+        let err = decoder
+            .add_event("E(uint indexed a, uint indexed b, uint indexed c, uint indexed d)")
+            .unwrap_err();
+        assert_eq!(err.reason, "more than 3 indexed parameters");
+    }
+
+    #[test]
+    fn test_function_selector() {
+        let mut decoder = FnDecoder::new();
+        let selector = decoder
+            .add_signature("function transfer(address to, uint amount)")
+            .unwrap();
+        assert_eq!(selector, [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(
+            decoder.resolve(selector).as_deref(),
+            Some("transfer(address,uint256)")
+        );
+        assert!(decoder.add_signature("f(uint256 indexed a)").is_err());
+    }
+
+    #[test]
+    fn test_tuple_parameters() {
+        let mut decoder = EventDecoder::new();
+        let topic0 = decoder
+            .add_event("Filled((address, uint256) order, bool indexed ok)")
+            .unwrap();
+        assert_eq!(topic0, keccak256("Filled((address,uint256),bool)"));
+    }
+}
@@ -0,0 +1,122 @@
+//! EIP-2930 access list generation.
+//!
+//! [`AccessListInspector`] collects the accounts and storage slots a
+//! transaction touches, the way Geth's `eth_createAccessList` does.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use alloy_primitives::{Address, B256};
+use revm::{
+    interpreter::{opcode, Interpreter},
+    primitives::{AccessList, AccessListItem, TxKind},
+    Database, EvmContext, Inspector,
+};
+use serde::{Deserialize, Serialize};
+
+/// Controls which accounts [`AccessListInspector`] leaves out.
+///
+/// Accounts are only left out when they are merely touched; an account whose
+/// storage is accessed is always listed together with its slots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessListConfig {
+    /// Leave out the transaction sender and recipient, which are warm anyway
+    pub exclude_sender_and_recipient: bool,
+    /// Leave out precompiles, which are warm anyway
+    pub exclude_precompiles: bool,
+}
+
+impl Default for AccessListConfig {
+    fn default() -> Self {
+        Self {
+            exclude_sender_and_recipient: true,
+            exclude_precompiles: true,
+        }
+    }
+}
+
+/// Inspector that accumulates the access list of a transaction.
+#[derive(Debug, Default)]
+pub struct AccessListInspector {
+    /// Touched accounts and the storage slots accessed in each
+    touched: BTreeMap<Address, BTreeSet<B256>>,
+    /// Which accounts to leave out
+    config: AccessListConfig,
+}
+
+impl AccessListInspector {
+    /// Creates an access list inspector with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an access list inspector with the given configuration.
+    pub fn with_config(config: AccessListConfig) -> Self {
+        Self {
+            touched: BTreeMap::new(),
+            config,
+        }
+    }
+
+    /// Returns the access list collected so far, sorted by address and slot.
+    pub fn access_list(&self) -> AccessList {
+        AccessList(
+            self.touched
+                .iter()
+                .map(|(address, slots)| AccessListItem {
+                    address: *address,
+                    storage_keys: slots.iter().copied().collect(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Consumes the inspector and returns the collected access list.
+    pub fn into_access_list(self) -> AccessList {
+        self.access_list()
+    }
+
+    fn add_address<DB: Database>(&mut self, address: Address, context: &EvmContext<DB>) {
+        if !self.is_excluded(&address, context) {
+            self.touched.entry(address).or_default();
+        }
+    }
+
+    fn is_excluded<DB: Database>(&self, address: &Address, context: &EvmContext<DB>) -> bool {
+        let tx = &context.env.tx;
+        (self.config.exclude_sender_and_recipient
+            && (*address == tx.caller || tx.transact_to == TxKind::Call(*address)))
+            || (self.config.exclude_precompiles && context.precompiles.contains(address))
+    }
+}
+
+impl<DB: Database> Inspector<DB> for AccessListInspector {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let stack = &interp.stack;
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Ok(slot) = stack.peek(0) {
+                    self.touched
+                        .entry(interp.contract.target_address)
+                        .or_default()
+                        .insert(B256::from(slot));
+                }
+            }
+            opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::EXTCODESIZE
+            | opcode::BALANCE
+            | opcode::SELFDESTRUCT => {
+                if let Ok(address) = stack.peek(0) {
+                    self.add_address(Address::from_word(address.into()), context);
+                }
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                if let Ok(address) = stack.peek(1) {
+                    self.add_address(Address::from_word(address.into()), context);
+                }
+            }
+            _ => {}
+        }
+    }
+}
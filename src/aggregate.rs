@@ -0,0 +1,117 @@
+//! Totals across every inspector a plugin created.
+//!
+//! Inspectors created by a
+//! [`HelloWorldInspectorPlugin`](crate::HelloWorldInspectorPlugin) share
+//! one set of counters with it. Each inspector adds what it counted to them
+//! whenever a transaction ends, so hooks never touch the shared atomics and
+//! [`aggregate_report`](crate::HelloWorldInspectorPlugin::aggregate_report)
+//! sees every finished transaction of every inspector, whether or not the
+//! inspector was dropped or reset since.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Totals of the finished transactions of every inspector a plugin created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateReport {
+    /// Inspectors created by the plugin
+    pub inspectors_created: u64,
+    /// Transactions traced to the end
+    pub transactions: u64,
+    /// Interpreter steps executed
+    pub steps: u64,
+    /// Calls made, the top-level calls included
+    pub calls: u64,
+    /// Contract creations
+    pub creations: u64,
+    /// Reverted frames
+    pub reverts: u64,
+    /// Gas spent by the top-level frames
+    pub gas_used: u64,
+}
+
+/// What an inspector has counted since it was created or reset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Totals {
+    pub(crate) transactions: u64,
+    pub(crate) steps: u64,
+    pub(crate) calls: u64,
+    pub(crate) creations: u64,
+    pub(crate) reverts: u64,
+    pub(crate) gas_used: u64,
+}
+
+/// Counters shared by a plugin and the inspectors it created.
+#[derive(Debug, Default)]
+pub(crate) struct AggregateCounters {
+    transactions: AtomicU64,
+    steps: AtomicU64,
+    calls: AtomicU64,
+    creations: AtomicU64,
+    reverts: AtomicU64,
+    gas_used: AtomicU64,
+}
+
+impl AggregateCounters {
+    /// Returns the totals, with `inspectors_created` as given.
+    pub(crate) fn report(&self, inspectors_created: u64) -> AggregateReport {
+        AggregateReport {
+            inspectors_created,
+            transactions: self.transactions.load(Ordering::Relaxed),
+            steps: self.steps.load(Ordering::Relaxed),
+            calls: self.calls.load(Ordering::Relaxed),
+            creations: self.creations.load(Ordering::Relaxed),
+            reverts: self.reverts.load(Ordering::Relaxed),
+            gas_used: self.gas_used.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// An inspector's link to the counters of its plugin.
+#[derive(Debug, Clone)]
+pub(crate) struct AggregateHandle {
+    counters: Arc<AggregateCounters>,
+    /// Totals of the inspector already added to `counters`
+    flushed: Totals,
+}
+
+impl AggregateHandle {
+    pub(crate) fn new(counters: Arc<AggregateCounters>) -> Self {
+        Self {
+            counters,
+            flushed: Totals::default(),
+        }
+    }
+
+    /// Adds what the inspector counted since the last flush, given its
+    /// totals `now`.
+    pub(crate) fn flush(&mut self, now: Totals) {
+        let add = |counter: &AtomicU64, now: u64, flushed: u64| {
+            let delta = now.saturating_sub(flushed);
+            if delta > 0 {
+                counter.fetch_add(delta, Ordering::Relaxed);
+            }
+        };
+        let counters = &self.counters;
+        add(
+            &counters.transactions,
+            now.transactions,
+            self.flushed.transactions,
+        );
+        add(&counters.steps, now.steps, self.flushed.steps);
+        add(&counters.calls, now.calls, self.flushed.calls);
+        add(&counters.creations, now.creations, self.flushed.creations);
+        add(&counters.reverts, now.reverts, self.flushed.reverts);
+        add(&counters.gas_used, now.gas_used, self.flushed.gas_used);
+        self.flushed = now;
+    }
+
+    /// Starts over for an inspector that was reset.
+    pub(crate) fn restart(&self) -> Self {
+        Self::new(self.counters.clone())
+    }
+}
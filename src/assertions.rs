@@ -0,0 +1,362 @@
+//! Assertions on a traced transaction for use in tests.
+//!
+//! [`TraceAssertions`] checks an [`InspectorReport`] and the [`StateDiff`] of
+//! the same transaction. Each check comes as a `check_*` method returning an
+//! [`AssertionError`] and an `assert_*` method panicking with it. Errors list
+//! what was recorded instead, closest matches first, so a failing test shows
+//! e.g. the address that was called in place of the expected one.
+//!
+//! ```
+//! use alloy_primitives::Address;
+//! use restd::{InspectorReport, StateDiff, TraceAssertions};
+//!
+//! let report = InspectorReport::default();
+//! let diff = StateDiff::default();
+//! let assertions = TraceAssertions::new(&report, &diff);
+//! assertions.assert_not_called(Address::repeat_byte(0xaa));
+//! let err = assertions.check_called(Address::repeat_byte(0xaa)).unwrap_err();
+//! assert_eq!(err.found, "no calls were recorded");
+//! ```
+
+use std::fmt;
+
+use alloy_primitives::{Address, B256, U256};
+
+use crate::{CallGasRecord, InspectorReport, StateDiff};
+
+/// Number of closest matches listed in an [`AssertionError`].
+const CLOSEST_MATCHES: usize = 3;
+
+/// A failed assertion of [`TraceAssertions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionError {
+    /// What the assertion expected, e.g. `a call to 0x..`
+    pub expected: String,
+    /// What was recorded instead, naming the closest matches
+    pub found: String,
+}
+
+impl fmt::Display for AssertionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, but {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for AssertionError {}
+
+/// Assertions on the report and state diff of a transaction.
+///
+/// A call matches an address if it is the called address or, for delegate
+/// calls, the address the code was loaded from. Calls beyond
+/// [`TraceLimits::max_call_records`](crate::TraceLimits::max_call_records)
+/// are not seen.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceAssertions<'a> {
+    report: &'a InspectorReport,
+    state_diff: &'a StateDiff,
+}
+
+impl<'a> TraceAssertions<'a> {
+    /// Creates assertions on `report` and `state_diff`.
+    pub fn new(report: &'a InspectorReport, state_diff: &'a StateDiff) -> Self {
+        Self { report, state_diff }
+    }
+
+    /// Checks that `address` was called at least once.
+    pub fn check_called(&self, address: Address) -> Result<(), AssertionError> {
+        if self.calls_to(address).next().is_some() {
+            return Ok(());
+        }
+        Err(AssertionError {
+            expected: format!("a call to {address}"),
+            found: self.closest_calls(address),
+        })
+    }
+
+    /// Checks that `address` was never called.
+    pub fn check_not_called(&self, address: Address) -> Result<(), AssertionError> {
+        let calls: Vec<_> = self.calls_to(address).collect();
+        let Some(first) = calls.first() else {
+            return Ok(());
+        };
+        Err(AssertionError {
+            expected: format!("no call to {address}"),
+            found: format!(
+                "it was called {}, first by {} at depth {}",
+                times(calls.len()),
+                first.caller,
+                first.depth
+            ),
+        })
+    }
+
+    /// Checks that `address` was called exactly `count` times.
+    pub fn check_call_count(&self, address: Address, count: usize) -> Result<(), AssertionError> {
+        let actual = self.calls_to(address).count();
+        if actual == count {
+            return Ok(());
+        }
+        let found = if actual == 0 {
+            self.closest_calls(address)
+        } else {
+            format!("it was called {}", times(actual))
+        };
+        Err(AssertionError {
+            expected: format!("{} to {address}", plural(count, "call")),
+            found,
+        })
+    }
+
+    /// Checks that a frame reverted with `reason`, compared with the
+    /// [`RevertReason`](crate::RevertReason) as displayed, e.g. the message
+    /// of `Error(string)`.
+    pub fn check_reverted_with(&self, reason: &str) -> Result<(), AssertionError> {
+        let reasons: Vec<_> = self
+            .report
+            .reverts
+            .iter()
+            .map(|revert| (revert.reason.to_string(), revert.address))
+            .collect();
+        if reasons.iter().any(|(recorded, _)| recorded == reason) {
+            return Ok(());
+        }
+        let found = if reasons.is_empty() {
+            "no frame reverted".to_string()
+        } else {
+            let closest = closest(&reasons, |(recorded, _)| edit_distance(recorded, reason));
+            let listed: Vec<_> = closest
+                .iter()
+                .map(|(recorded, address)| format!("{recorded:?} in {address}"))
+                .collect();
+            format!("the closest revert reasons were {}", listed.join(", "))
+        };
+        Err(AssertionError {
+            expected: format!("a revert with {reason:?}"),
+            found,
+        })
+    }
+
+    /// Checks that a log with `topic0` was emitted by a frame that did not
+    /// revert.
+    pub fn check_emitted(&self, topic0: B256) -> Result<(), AssertionError> {
+        let logs: Vec<_> = self
+            .report
+            .logs
+            .iter()
+            .filter_map(|log| Some((*log.topics.first()?, log.address, log.reverted)))
+            .collect();
+        if logs.iter().any(|(topic, _, reverted)| *topic == topic0 && !reverted) {
+            return Ok(());
+        }
+        let found = if let Some((_, address, _)) = logs.iter().find(|(t, ..)| *t == topic0) {
+            format!("it was only emitted by {address} in a reverted frame")
+        } else if logs.is_empty() {
+            "no logs with topics were emitted".to_string()
+        } else {
+            let closest =
+                closest(&logs, |(topic, ..)| nibble_distance(topic.as_slice(), topic0.as_slice()));
+            let listed: Vec<_> = closest
+                .iter()
+                .map(|(topic, address, _)| format!("{topic} by {address}"))
+                .collect();
+            format!("the closest topics emitted were {}", listed.join(", "))
+        };
+        Err(AssertionError {
+            expected: format!("a log with topic0 {topic0}"),
+            found,
+        })
+    }
+
+    /// Checks that the transaction changed `slot` of `address`.
+    pub fn check_storage_changed(
+        &self,
+        address: Address,
+        slot: U256,
+    ) -> Result<(), AssertionError> {
+        let diff = self.state_diff;
+        let slots = changed_slots(diff, &address);
+        if slots.contains(&slot) {
+            return Ok(());
+        }
+        let found = if slots.is_empty() {
+            let accounts: Vec<_> = diff
+                .changed_accounts()
+                .filter(|account| !changed_slots(diff, account).is_empty())
+                .copied()
+                .collect();
+            if accounts.is_empty() {
+                "no storage was changed".to_string()
+            } else {
+                let closest = closest(&accounts, |account| {
+                    nibble_distance(account.as_slice(), address.as_slice())
+                });
+                let listed: Vec<_> = closest.iter().map(ToString::to_string).collect();
+                format!(
+                    "{address} changed no storage; the closest accounts that did were {}",
+                    listed.join(", ")
+                )
+            }
+        } else {
+            let listed: Vec<_> = slots.iter().map(|slot| format!("{slot:#x}")).collect();
+            format!("{address} only changed slots {}", listed.join(", "))
+        };
+        Err(AssertionError {
+            expected: format!("a change of slot {slot:#x} of {address}"),
+            found,
+        })
+    }
+
+    /// Panics unless `address` was called at least once.
+    #[track_caller]
+    pub fn assert_called(&self, address: Address) -> &Self {
+        self.or_panic(self.check_called(address))
+    }
+
+    /// Panics if `address` was called.
+    #[track_caller]
+    pub fn assert_not_called(&self, address: Address) -> &Self {
+        self.or_panic(self.check_not_called(address))
+    }
+
+    /// Panics unless `address` was called exactly `count` times.
+    #[track_caller]
+    pub fn assert_call_count(&self, address: Address, count: usize) -> &Self {
+        self.or_panic(self.check_call_count(address, count))
+    }
+
+    /// Panics unless a frame reverted with `reason`.
+    #[track_caller]
+    pub fn assert_reverted_with(&self, reason: &str) -> &Self {
+        self.or_panic(self.check_reverted_with(reason))
+    }
+
+    /// Panics unless a frame that did not revert emitted a log with `topic0`.
+    #[track_caller]
+    pub fn assert_emitted(&self, topic0: B256) -> &Self {
+        self.or_panic(self.check_emitted(topic0))
+    }
+
+    /// Panics unless the transaction changed `slot` of `address`.
+    #[track_caller]
+    pub fn assert_storage_changed(&self, address: Address, slot: U256) -> &Self {
+        self.or_panic(self.check_storage_changed(address, slot))
+    }
+
+    #[track_caller]
+    fn or_panic(&self, result: Result<(), AssertionError>) -> &Self {
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+        self
+    }
+
+    fn calls_to(&self, address: Address) -> impl Iterator<Item = &'a CallGasRecord> {
+        self.report
+            .calls
+            .iter()
+            .filter(move |call| call.target == address || call.bytecode_address == address)
+    }
+
+    /// Describes the called addresses closest to `address`.
+    fn closest_calls(&self, address: Address) -> String {
+        let mut called: Vec<(Address, usize)> = Vec::new();
+        for call in &self.report.calls {
+            match called.iter_mut().find(|(target, _)| *target == call.target) {
+                Some((_, count)) => *count += 1,
+                None => called.push((call.target, 1)),
+            }
+        }
+        if called.is_empty() {
+            return "no calls were recorded".to_string();
+        }
+        let closest = closest(&called, |(target, _)| {
+            nibble_distance(target.as_slice(), address.as_slice())
+        });
+        let listed: Vec<_> = closest
+            .iter()
+            .map(|(target, count)| format!("{target} ({})", plural(*count, "call")))
+            .collect();
+        format!("it was not called; the closest calls were to {}", listed.join(", "))
+    }
+}
+
+/// Returns the storage slots of `address` changed in `diff`.
+fn changed_slots(diff: &StateDiff, address: &Address) -> Vec<U256> {
+    let mut slots: Vec<U256> = [&diff.pre, &diff.post]
+        .into_iter()
+        .filter_map(|side| side.get(address))
+        .flat_map(|account| account.storage.keys().copied())
+        .collect();
+    slots.sort_unstable();
+    slots.dedup();
+    slots
+}
+
+/// Returns up to [`CLOSEST_MATCHES`] items with the smallest distance, in
+/// recording order among equal distances.
+fn closest<T: Clone>(items: &[T], distance: impl Fn(&T) -> usize) -> Vec<T> {
+    let mut sorted: Vec<_> = items.iter().collect();
+    sorted.sort_by_key(|item| distance(item));
+    sorted.into_iter().take(CLOSEST_MATCHES).cloned().collect()
+}
+
+/// Counts the hex digits in which `a` and `b` differ.
+fn nibble_distance(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let diff = a ^ b;
+            usize::from(diff & 0xf0 != 0) + usize::from(diff & 0x0f != 0)
+        })
+        .sum()
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substituted = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substituted.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn times(n: usize) -> String {
+    match n {
+        1 => "once".to_string(),
+        n => format!("{n} times"),
+    }
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    match n {
+        1 => format!("1 {noun}"),
+        n => format!("{n} {noun}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("not owner", "not owner"), 0);
+        assert_eq!(edit_distance("not owner", "not the owner"), 4);
+    }
+
+    #[test]
+    fn test_nibble_distance() {
+        assert_eq!(nibble_distance(&[0xab, 0xcd], &[0xab, 0xcd]), 0);
+        assert_eq!(nibble_distance(&[0xab, 0xcd], &[0xa0, 0xcd]), 1);
+        assert_eq!(nibble_distance(&[0xab, 0xcd], &[0x00, 0x00]), 4);
+    }
+}
@@ -0,0 +1,245 @@
+//! Comparing the gas of two transactions, e.g. of two implementations of a
+//! contract.
+//!
+//! [`compare`] runs both transactions against fresh copies of the same
+//! database with a [`HelloWorldInspector`] and a [`CallTraceInspector`]
+//! attached, and reports how the total gas, the gas per opcode category, the
+//! gas of each frame, and the numbers of logs and storage writes differ.
+
+use std::{collections::BTreeMap, fmt};
+
+use alloy_primitives::Address;
+use revm::{
+    primitives::{Env, ExecutionResult},
+    Database,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diff::function_label, evm::transact_inspected, geth::geth_type, CallTrace,
+    CallTraceInspector, Error, GasCategory, GasProfile, HelloWorldInspector,
+    HelloWorldInspectorConfig, InspectorStack, ProfilingMode, StorageAccessKind,
+};
+
+/// One of the two compared transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    /// The first transaction, usually the baseline
+    A,
+    /// The second transaction, usually the candidate
+    B,
+}
+
+/// A quantity measured in both transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delta {
+    /// Value in transaction A
+    pub a: u64,
+    /// Value in transaction B
+    pub b: u64,
+    /// `b - a`
+    pub delta: i64,
+}
+
+impl Delta {
+    /// Creates the delta from `a` to `b`.
+    pub fn new(a: u64, b: u64) -> Self {
+        Self {
+            a,
+            b,
+            delta: b as i64 - a as i64,
+        }
+    }
+
+    /// Returns the transaction with the larger value, `None` if they are
+    /// equal.
+    pub fn larger(&self) -> Option<Side> {
+        match self.a.cmp(&self.b) {
+            std::cmp::Ordering::Less => Some(Side::B),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(Side::A),
+        }
+    }
+}
+
+/// Gas used by a frame of either trace, aligned with its counterpart in the
+/// other one as [`CallTrace::diff`] aligns them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameGasDelta {
+    /// Geth type of the frame, e.g. `CALL`
+    pub kind: String,
+    /// Call depth, the top-level frame is at depth 0
+    pub depth: usize,
+    /// Called address, or the deployed address for create frames
+    pub target: Address,
+    /// Selector in hex, `fallback`, `new` or the name of a precompile
+    pub function: String,
+    /// Gas used in transaction A, `None` if the frame is only in B
+    pub gas_a: Option<u64>,
+    /// Gas used in transaction B, `None` if the frame is only in A
+    pub gas_b: Option<u64>,
+    /// `gas_b - gas_a` for frames in both transactions
+    pub delta: Option<i64>,
+}
+
+/// How the gas and side effects of two transactions differ.
+///
+/// The `Display` impl prints the totals and categories as a table of A, B
+/// and their delta, followed by the aligned frames.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasComparison {
+    /// Gas used by each transaction, as the EVM charged it
+    pub total: Delta,
+    /// Gas charged by the instructions of each opcode category
+    pub by_category: BTreeMap<GasCategory, Delta>,
+    /// Frames of both call trees, aligned and in depth-first order
+    pub frames: Vec<FrameGasDelta>,
+    /// Logs emitted, including those of reverted frames
+    pub logs: Delta,
+    /// `SSTORE`s executed, including those of reverted frames
+    pub storage_writes: Delta,
+    /// Whether transaction A succeeded
+    pub success_a: bool,
+    /// Whether transaction B succeeded
+    pub success_b: bool,
+}
+
+/// What one transaction of a comparison produced.
+struct Run {
+    result: ExecutionResult,
+    profile: GasProfile,
+    trace: CallTrace,
+    logs: u64,
+    storage_writes: u64,
+}
+
+/// Runs `tx_a` and `tx_b` against fresh databases from `db_setup` and
+/// compares their gas.
+///
+/// `config` configures the inspector of both runs; its profiling mode is
+/// overridden with [`ProfilingMode::Full`] so every instruction's gas is
+/// counted. Neither transaction's state is committed.
+pub fn compare<DB, F>(
+    db_setup: F,
+    tx_a: Env,
+    tx_b: Env,
+    config: HelloWorldInspectorConfig,
+) -> Result<GasComparison, Error>
+where
+    F: Fn() -> DB,
+    DB: Database,
+    DB::Error: fmt::Display,
+{
+    let config = HelloWorldInspectorConfig {
+        profiling: ProfilingMode::Full,
+        ..config
+    };
+    let a = run(db_setup(), tx_a, config.clone())?;
+    let b = run(db_setup(), tx_b, config)?;
+
+    let mut by_category = BTreeMap::new();
+    for category in a.profile.by_category.keys().chain(b.profile.by_category.keys()) {
+        by_category.insert(
+            *category,
+            Delta::new(a.profile.category(*category), b.profile.category(*category)),
+        );
+    }
+    let frames = a
+        .trace
+        .diff(&b.trace)
+        .iter()
+        .map(|frame| FrameGasDelta {
+            kind: geth_type(frame.kind).to_string(),
+            depth: frame.depth,
+            target: frame.target,
+            function: function_label(frame),
+            gas_a: frame.gas_before,
+            gas_b: frame.gas_after,
+            delta: frame.gas_delta(),
+        })
+        .collect();
+    Ok(GasComparison {
+        total: Delta::new(a.result.gas_used(), b.result.gas_used()),
+        by_category,
+        frames,
+        logs: Delta::new(a.logs, b.logs),
+        storage_writes: Delta::new(a.storage_writes, b.storage_writes),
+        success_a: a.result.is_success(),
+        success_b: b.result.is_success(),
+    })
+}
+
+fn run<DB>(db: DB, env: Env, config: HelloWorldInspectorConfig) -> Result<Run, Error>
+where
+    DB: Database,
+    DB::Error: fmt::Display,
+{
+    let tracer =
+        CallTraceInspector::new().with_precompile_upper_bound(config.precompile_upper_bound);
+    let mut stack = InspectorStack::new()
+        .with(HelloWorldInspector::with_config(config))
+        .with(tracer);
+    let result = transact_inspected(db, env, &mut stack)?.result;
+    let inspector = stack
+        .remove::<HelloWorldInspector>()
+        .expect("the inspector was added to the stack");
+    let tracer = stack
+        .remove::<CallTraceInspector>()
+        .expect("the tracer was added to the stack");
+    Ok(Run {
+        result,
+        profile: inspector.gas_profile(),
+        trace: tracer.into_trace(),
+        logs: inspector.logs().len() as u64,
+        storage_writes: inspector
+            .storage_accesses()
+            .iter()
+            .filter(|access| access.kind == StorageAccessKind::Write)
+            .count() as u64,
+    })
+}
+
+impl fmt::Display for GasComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<16} {:>12} {:>12} {:>12}", "", "A", "B", "delta")?;
+        let row = |f: &mut fmt::Formatter<'_>, name: &dyn fmt::Display, delta: &Delta| {
+            writeln!(
+                f,
+                "{:<16} {:>12} {:>12} {:>+12}",
+                name.to_string(),
+                delta.a,
+                delta.b,
+                delta.delta
+            )
+        };
+        row(f, &"total gas", &self.total)?;
+        for (category, delta) in &self.by_category {
+            row(f, category, delta)?;
+        }
+        row(f, &"logs", &self.logs)?;
+        row(f, &"storage writes", &self.storage_writes)?;
+        if !self.frames.is_empty() {
+            writeln!(f)?;
+        }
+        for frame in &self.frames {
+            let gas = |gas: Option<u64>| gas.map_or("-".to_string(), |gas| gas.to_string());
+            write!(
+                f,
+                "{:indent$}{} {}::{} [{} → {}",
+                "",
+                frame.kind,
+                frame.target,
+                frame.function,
+                gas(frame.gas_a),
+                gas(frame.gas_b),
+                indent = frame.depth * 2
+            )?;
+            match frame.delta {
+                Some(delta) if delta != 0 => writeln!(f, " ({delta:+})]")?,
+                _ => writeln!(f, "]")?,
+            }
+        }
+        Ok(())
+    }
+}
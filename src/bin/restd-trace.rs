@@ -0,0 +1,302 @@
+//! Traces a single transaction against an in-memory state.
+//!
+//! ```text
+//! restd-trace --code 0x602a60005260206000f3 --calldata 0x01 --format geth
+//! ```
+//!
+//! `--code` deploys the code at [`TARGET`] and calls it, `--create` runs init
+//! code in a create transaction. The exit code is 0 if the transaction
+//! succeeded, 1 if it reverted or halted (unless `--allow-revert` is given)
+//! and 2 if the arguments are invalid or the transaction could not execute.
+
+use std::{fmt, fs, process::ExitCode, str::FromStr};
+
+use alloy_primitives::{hex, Address, Bytes, TxKind, U256};
+use restd::{
+    evm::build_inspected_evm,
+    state::{Prestate, PrestateAccount},
+    CallTraceInspector, HelloWorldInspector, HelloWorldInspectorConfig, InspectorProfile,
+    InspectorStack, OutputMode, PrettyPrintOptions,
+};
+use revm::{primitives::Env, InMemoryDB};
+
+/// Address the code passed with `--code` is deployed at.
+const TARGET: Address = Address::repeat_byte(0xc0);
+
+/// Sender of the transaction unless `--caller` is given.
+const DEFAULT_CALLER: Address = Address::repeat_byte(0xca);
+
+const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+const USAGE: &str = "\
+Usage: restd-trace (--code <hex> | --create <hex>) [options]
+
+Options:
+  --code <hex>         Runtime code to deploy and call
+  --create <hex>       Init code to run in a create transaction
+  --calldata <hex>     Calldata of the call
+  --value <wei>        Value sent with the transaction, decimal or 0x-prefixed hex
+  --gas-limit <gas>    Gas limit of the transaction [default: 30000000]
+  --caller <address>   Sender of the transaction
+  --format <format>    pretty, json or geth [default: pretty]
+  --out <file>         Write the output to a file instead of stdout
+  --prestate <json>    Accounts to load before executing, as a geth prestateTracer
+                       result, inline or the path of a JSON file
+  --profile <name>     Inspector preset: minimal, call_tracing, profiling, debugging
+                       or security
+  --config <file>      Inspector configuration as TOML, which may name a `profile`
+  --allow-revert       Exit with 0 even if the transaction reverts or halts
+  -h, --help           Print this help";
+
+/// Output format selected with `--format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Format {
+    /// Indented call tree
+    #[default]
+    Pretty,
+    /// The inspector's report
+    Json,
+    /// Geth's `callTracer` format
+    Geth,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Format::Pretty),
+            "json" => Ok(Format::Json),
+            "geth" => Ok(Format::Geth),
+            _ => Err(format!("unknown format `{s}`, expected pretty, json or geth")),
+        }
+    }
+}
+
+/// Parsed command line.
+#[derive(Debug, Default)]
+struct Args {
+    code: Option<Bytes>,
+    create: Option<Bytes>,
+    calldata: Bytes,
+    value: U256,
+    gas_limit: Option<u64>,
+    caller: Option<Address>,
+    format: Format,
+    out: Option<String>,
+    prestate: Option<String>,
+    profile: Option<InspectorProfile>,
+    config: Option<String>,
+    allow_revert: bool,
+}
+
+/// Why the arguments were rejected or the transaction could not run.
+#[derive(Debug)]
+enum Error {
+    /// Invalid command line, printed with the usage
+    Usage(String),
+    /// Any other failure
+    Other(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(err) | Error::Other(err) => f.write_str(err),
+        }
+    }
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, Error> {
+        let mut parsed = Args::default();
+        while let Some(flag) = args.next() {
+            if flag == "-h" || flag == "--help" {
+                return Ok(None);
+            }
+            if flag == "--allow-revert" {
+                parsed.allow_revert = true;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| Error::Usage(format!("missing value for `{flag}`")))?;
+            match flag.as_str() {
+                "--code" => parsed.code = Some(parse_hex(&flag, &value)?),
+                "--create" => parsed.create = Some(parse_hex(&flag, &value)?),
+                "--calldata" => parsed.calldata = parse_hex(&flag, &value)?,
+                "--value" => parsed.value = parse_value(&flag, &value)?,
+                "--gas-limit" => parsed.gas_limit = Some(parse_value(&flag, &value)?),
+                "--caller" => parsed.caller = Some(parse_value(&flag, &value)?),
+                "--format" => parsed.format = value.parse().map_err(Error::Usage)?,
+                "--out" => parsed.out = Some(value),
+                "--prestate" => parsed.prestate = Some(value),
+                "--profile" => parsed.profile = Some(value.parse().map_err(Error::Usage)?),
+                "--config" => parsed.config = Some(value),
+                _ => return Err(Error::Usage(format!("unknown flag `{flag}`"))),
+            }
+        }
+        if parsed.profile.is_some() && parsed.config.is_some() {
+            return Err(Error::Usage(
+                "`--profile` cannot be combined with `--config`; set `profile` in the file".into(),
+            ));
+        }
+        match (&parsed.code, &parsed.create) {
+            (None, None) => Err(Error::Usage("one of `--code` or `--create` is required".into())),
+            (Some(_), Some(_)) => {
+                Err(Error::Usage("`--code` and `--create` cannot be combined".into()))
+            }
+            (None, Some(_)) if !parsed.calldata.is_empty() => Err(Error::Usage(
+                "`--calldata` cannot be combined with `--create`; append constructor \
+                 arguments to the init code"
+                    .into(),
+            )),
+            _ => Ok(Some(parsed)),
+        }
+    }
+}
+
+fn parse_hex(flag: &str, value: &str) -> Result<Bytes, Error> {
+    hex::decode(value)
+        .map(Bytes::from)
+        .map_err(|err| Error::Usage(format!("invalid hex for `{flag}`: {err}")))
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, Error>
+where
+    T::Err: fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|err| Error::Usage(format!("invalid value `{value}` for `{flag}`: {err}")))
+}
+
+/// Reads `--prestate`, inline JSON if it starts with `{`, otherwise a file.
+fn read_prestate(prestate: &str) -> Result<Prestate, Error> {
+    let json = if prestate.trim_start().starts_with('{') {
+        prestate.to_string()
+    } else {
+        fs::read_to_string(prestate)
+            .map_err(|err| Error::Other(format!("failed to read prestate {prestate}: {err}")))?
+    };
+    Prestate::from_json(&json).map_err(|err| Error::Other(format!("invalid prestate: {err}")))
+}
+
+/// Runs the transaction and returns the output and whether it succeeded.
+fn trace(args: &Args) -> Result<(String, bool), Error> {
+    let mut prestate = match &args.prestate {
+        Some(prestate) => read_prestate(prestate)?,
+        None => Prestate::default(),
+    };
+    let caller = args.caller.unwrap_or(DEFAULT_CALLER);
+    prestate.accounts.entry(caller).or_insert_with(|| PrestateAccount {
+        balance: args.value,
+        ..Default::default()
+    });
+
+    let mut env = Env::default();
+    env.tx.caller = caller;
+    env.tx.value = args.value;
+    env.tx.gas_limit = args.gas_limit.unwrap_or(DEFAULT_GAS_LIMIT);
+    env.block.gas_limit = U256::from(env.tx.gas_limit);
+    match (&args.code, &args.create) {
+        (Some(code), _) => {
+            // Keep the balance and storage a prestate gave the target
+            let target = prestate.accounts.entry(TARGET).or_insert_with(|| PrestateAccount {
+                nonce: 1,
+                ..Default::default()
+            });
+            target.code = Some(code.clone());
+            env.tx.transact_to = TxKind::Call(TARGET);
+            env.tx.data = args.calldata.clone();
+        }
+        (None, Some(init_code)) => {
+            env.tx.transact_to = TxKind::Create;
+            env.tx.data = init_code.clone();
+        }
+        (None, None) => unreachable!("checked by Args::parse"),
+    }
+    let mut db = InMemoryDB::default();
+    prestate.apply(&mut db);
+
+    let config = match (&args.config, args.profile) {
+        (Some(path), _) => {
+            let toml = fs::read_to_string(path)
+                .map_err(|err| Error::Other(format!("failed to read config {path}: {err}")))?;
+            HelloWorldInspectorConfig::from_toml_str(&toml)
+                .map_err(|err| Error::Other(format!("invalid config {path}: {err}")))?
+        }
+        (None, Some(profile)) => HelloWorldInspectorConfig::profile(profile),
+        (None, None) => HelloWorldInspectorConfig {
+            trace_calls: true,
+            ..Default::default()
+        },
+    };
+    // Only the selected format is written to stdout
+    let config = HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        verbose: false,
+        ..config
+    };
+    let mut stack = InspectorStack::new()
+        .with(HelloWorldInspector::with_config(config))
+        .with(CallTraceInspector::new());
+    let result = build_inspected_evm(&mut db, env, &mut stack)
+        .transact()
+        .map_err(|err| Error::Other(format!("transaction failed to execute: {err}")))?
+        .result;
+
+    let trace = stack
+        .remove::<CallTraceInspector>()
+        .expect("pushed above")
+        .into_trace();
+    let json = |value: serde_json::Result<String>| {
+        value.map_err(|err| Error::Other(format!("failed to serialize output: {err}")))
+    };
+    let mut output = match args.format {
+        Format::Pretty => trace.pretty_print(PrettyPrintOptions::default()),
+        Format::Json => {
+            let inspector = stack.get::<HelloWorldInspector>().expect("pushed above");
+            json(inspector.report().to_json_pretty())?
+        }
+        Format::Geth => json(serde_json::to_string_pretty(&trace.to_geth_call_trace()))?,
+    };
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok((output, result.is_success()))
+}
+
+fn run(args: &Args) -> Result<bool, Error> {
+    let (output, success) = trace(args)?;
+    match &args.out {
+        Some(path) => fs::write(path, output)
+            .map_err(|err| Error::Other(format!("failed to write {path}: {err}")))?,
+        None => print!("{output}"),
+    }
+    Ok(success)
+}
+
+fn main() -> ExitCode {
+    let result = Args::parse(std::env::args().skip(1)).and_then(|args| match args {
+        Some(args) => run(&args).map(|success| success || args.allow_revert),
+        None => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => {
+            eprintln!("error: transaction reverted or halted, pass --allow-revert to ignore");
+            ExitCode::from(1)
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            if matches!(err, Error::Usage(_)) {
+                eprintln!("\n{USAGE}");
+            }
+            ExitCode::from(2)
+        }
+    }
+}
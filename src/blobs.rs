@@ -0,0 +1,73 @@
+//! EIP-4844 blob transactions and the opcodes that read them.
+//!
+//! A blob transaction carries the versioned hashes of its blobs, which
+//! `BLOBHASH` reads by index, and `BLOBBASEFEE` reads the blob base fee of
+//! the block. An index past the last hash makes `BLOBHASH` push zero rather
+//! than fail, which is almost always a bug, so such reads are flagged.
+
+use alloy_primitives::{Address, B256, U256};
+use revm::{interpreter::opcode, primitives::TxEnv};
+use serde::{Deserialize, Serialize};
+
+/// The blobs of a traced transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobTransaction {
+    /// Versioned hashes of the blobs, in the order `BLOBHASH` indexes them
+    pub blob_hashes: Vec<B256>,
+    /// Most the sender pays per unit of blob gas
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Index of the transaction
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns the blobs of `tx`, or `None` if it carries none.
+pub(crate) fn blob_transaction(tx: &TxEnv, tx_index: u64) -> Option<BlobTransaction> {
+    (!tx.blob_hashes.is_empty()).then(|| BlobTransaction {
+        blob_hashes: tx.blob_hashes.clone(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        tx_index,
+    })
+}
+
+/// Opcode reading blob data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlobOpcode {
+    /// `BLOBHASH`, reading a versioned hash of the transaction
+    BlobHash,
+    /// `BLOBBASEFEE`, reading the blob base fee of the block
+    BlobBaseFee,
+}
+
+impl BlobOpcode {
+    /// Returns the blob opcode `op` is, if any.
+    pub fn from_opcode(op: u8) -> Option<Self> {
+        match op {
+            opcode::BLOBHASH => Some(Self::BlobHash),
+            opcode::BLOBBASEFEE => Some(Self::BlobBaseFee),
+            _ => None,
+        }
+    }
+}
+
+/// An executed `BLOBHASH` or `BLOBBASEFEE` instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobOpcodeUse {
+    /// Which opcode was executed
+    pub opcode: BlobOpcode,
+    /// Contract executing the instruction
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// Index read by `BLOBHASH`, `None` for `BLOBBASEFEE`
+    pub index: Option<U256>,
+    /// Whether the index is past the last blob hash of the transaction, so
+    /// `BLOBHASH` pushed zero
+    pub out_of_range: bool,
+    /// Call depth of the executing frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
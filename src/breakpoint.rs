@@ -0,0 +1,237 @@
+//! Pausing on contracts, program counters and opcodes.
+//!
+//! [`BreakpointInspector`] checks every call and step against a set of
+//! [`Breakpoints`] and hands each hit to a callback, which decides whether
+//! the frame goes on or is aborted.
+
+use std::{collections::BTreeSet, fmt};
+
+use alloy_primitives::{Address, Bytes, U256};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, Gas, InstructionResult, Interpreter, InterpreterResult,
+    },
+    Database, EvmContext, Inspector,
+};
+
+/// Number of stack items captured in an [`InterpSnapshot`].
+const SNAPSHOT_STACK_ITEMS: usize = 8;
+
+/// Where execution should pause.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Breakpoints {
+    /// Contracts whose calls break before they start
+    addresses: BTreeSet<Address>,
+    /// Program counters within the code of a contract
+    pcs: BTreeSet<(Address, usize)>,
+    /// Opcodes that break wherever they are executed
+    opcodes: BTreeSet<u8>,
+}
+
+impl Breakpoints {
+    /// Creates an empty set of breakpoints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Breaks before every call to `address`, or to code deployed at
+    /// `address` through a delegate call.
+    pub fn on_address(mut self, address: Address) -> Self {
+        self.addresses.insert(address);
+        self
+    }
+
+    /// Breaks before the instruction at `pc` in the code deployed at `address`.
+    pub fn on_pc(mut self, address: Address, pc: usize) -> Self {
+        self.pcs.insert((address, pc));
+        self
+    }
+
+    /// Breaks before every execution of `op`.
+    pub fn on_opcode(mut self, op: u8) -> Self {
+        self.opcodes.insert(op);
+        self
+    }
+
+    /// Returns true if no breakpoint is set.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.pcs.is_empty() && self.opcodes.is_empty()
+    }
+}
+
+/// The breakpoint that paused execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakpointHit {
+    /// A call to a contract set with [`Breakpoints::on_address`]
+    Address(Address),
+    /// A program counter set with [`Breakpoints::on_pc`]
+    Pc {
+        /// Contract whose code is executing
+        address: Address,
+        /// Program counter of the instruction
+        pc: usize,
+    },
+    /// An opcode set with [`Breakpoints::on_opcode`]
+    Opcode(u8),
+}
+
+/// What to do after a breakpoint was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakAction {
+    /// Proceed with execution
+    Continue,
+    /// Stop the current frame; it ends as a revert without data and its
+    /// caller proceeds
+    Abort,
+}
+
+/// The state of the interpreter when a breakpoint was hit.
+///
+/// For address breakpoints the snapshot describes the called frame before
+/// its first instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpSnapshot {
+    /// Contract whose code is executing
+    pub address: Address,
+    /// Program counter
+    pub pc: usize,
+    /// Opcode about to be executed
+    pub opcode: u8,
+    /// Top of the stack, topmost item first
+    pub stack_top: Vec<U256>,
+    /// Gas remaining in the frame
+    pub gas_remaining: u64,
+    /// Call depth, the top-level frame is at depth 1
+    pub depth: u64,
+}
+
+/// Callback invoked for every breakpoint hit.
+pub type BreakpointCallback = Box<dyn FnMut(BreakpointHit, &InterpSnapshot) -> BreakAction>;
+
+/// Inspector that hands breakpoint hits to a callback.
+pub struct BreakpointInspector {
+    /// Where to break
+    breakpoints: Breakpoints,
+    /// Decides what happens at each hit
+    callback: BreakpointCallback,
+    /// Number of breakpoints hit so far
+    hits: u64,
+}
+
+impl fmt::Debug for BreakpointInspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BreakpointInspector")
+            .field("breakpoints", &self.breakpoints)
+            .field("hits", &self.hits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BreakpointInspector {
+    /// Creates an inspector calling `callback` whenever one of `breakpoints` is hit.
+    pub fn new(
+        breakpoints: Breakpoints,
+        callback: impl FnMut(BreakpointHit, &InterpSnapshot) -> BreakAction + 'static,
+    ) -> Self {
+        Self {
+            breakpoints,
+            callback: Box::new(callback),
+            hits: 0,
+        }
+    }
+
+    /// Returns the breakpoints being checked.
+    pub fn breakpoints(&self) -> &Breakpoints {
+        &self.breakpoints
+    }
+
+    /// Returns the number of breakpoints hit so far.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Reports `hit` to the callback and returns true if it asked to abort.
+    fn hit(&mut self, hit: BreakpointHit, snapshot: &InterpSnapshot) -> bool {
+        self.hits += 1;
+        (self.callback)(hit, snapshot) == BreakAction::Abort
+    }
+}
+
+impl<DB: Database> Inspector<DB> for BreakpointInspector {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let address = interp
+            .contract
+            .bytecode_address
+            .unwrap_or(interp.contract.target_address);
+        let pc = interp.program_counter();
+        let op = interp.current_opcode();
+        let mut hits = Vec::new();
+        if self.breakpoints.pcs.contains(&(address, pc)) {
+            hits.push(BreakpointHit::Pc { address, pc });
+        }
+        if self.breakpoints.opcodes.contains(&op) {
+            hits.push(BreakpointHit::Opcode(op));
+        }
+        if hits.is_empty() {
+            return;
+        }
+
+        let snapshot = InterpSnapshot {
+            address,
+            pc,
+            opcode: op,
+            stack_top: interp
+                .stack
+                .data()
+                .iter()
+                .rev()
+                .take(SNAPSHOT_STACK_ITEMS)
+                .copied()
+                .collect(),
+            gas_remaining: interp.gas.remaining(),
+            depth: context.journaled_state.depth(),
+        };
+        for hit in hits {
+            if self.hit(hit, &snapshot) {
+                // The instruction is skipped and the frame returns right away
+                interp.instruction_result = InstructionResult::Revert;
+                return;
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let address = [inputs.target_address, inputs.bytecode_address]
+            .into_iter()
+            .find(|address| self.breakpoints.addresses.contains(address))?;
+
+        let opcode = context
+            .code(inputs.bytecode_address)
+            .ok()
+            .and_then(|code| code.data.first().copied())
+            .unwrap_or_default();
+        let snapshot = InterpSnapshot {
+            address: inputs.bytecode_address,
+            pc: 0,
+            opcode,
+            stack_top: Vec::new(),
+            gas_remaining: inputs.gas_limit,
+            depth: context.journaled_state.depth() + 1,
+        };
+        if !self.hit(BreakpointHit::Address(address), &snapshot) {
+            return None;
+        }
+        Some(CallOutcome::new(
+            InterpreterResult::new(
+                InstructionResult::Revert,
+                Bytes::new(),
+                Gas::new(inputs.gas_limit),
+            ),
+            inputs.return_memory_offset.clone(),
+        ))
+    }
+}
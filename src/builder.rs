@@ -0,0 +1,492 @@
+//! Builder for [`HelloWorldInspector`] and its configuration.
+
+use std::{fmt, path::PathBuf, sync::Arc};
+
+use alloy_primitives::Address;
+use revm::primitives::SpecId;
+
+use crate::{
+    DelegateCallAttribution, EventDecoder, FaultRule, FnDecoder, HelloWorldInspector,
+    HelloWorldInspectorConfig, InconsistencyAction, InspectorProfile, Labels, MockRegistry, OutputMode, ProfilingMode,
+    SelectorResolver, SourceMapRegistry, TraceLimits,
+};
+
+/// Error returned when a [`HelloWorldInspectorBuilder`] holds conflicting settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Verbose logging was requested but the output is silenced
+    VerboseWhileSilent,
+    /// The address is both included and excluded by the address filter
+    AddressIncludedAndExcluded(Address),
+    /// `low_gas_percent` is above 100
+    LowGasPercentOutOfRange(u64),
+    /// Sampled profiling with `every_n_steps` set to 0
+    ZeroSampleInterval,
+    /// A fault rule with `nth` set to 0, which never fires; holds the
+    /// rule's index
+    ZeroFaultNth(usize),
+    /// A configuration file could not be read
+    Io(String),
+    /// A configuration file is not valid TOML or does not match the configuration
+    Toml(String),
+    /// A plugin configuration object does not match the configuration
+    Json(String),
+    /// A configuration file or environment variable sets an unknown key
+    UnknownKey(String),
+    /// A label file could not be read or parsed
+    Labels(String),
+    /// The address filter names a label that the labels do not define
+    UnknownLabel(String),
+    /// An environment variable has a value that cannot be parsed
+    InvalidEnvValue {
+        /// Name of the environment variable
+        name: String,
+        /// The rejected value
+        value: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::VerboseWhileSilent => {
+                write!(f, "verbose logging requires a non-silent output mode")
+            }
+            ConfigError::AddressIncludedAndExcluded(address) => {
+                write!(f, "address {address} is both included and excluded")
+            }
+            ConfigError::LowGasPercentOutOfRange(percent) => {
+                write!(f, "low_gas_percent must be at most 100, got {percent}")
+            }
+            ConfigError::ZeroSampleInterval => {
+                write!(f, "sampled profiling needs every_n_steps of at least 1")
+            }
+            ConfigError::ZeroFaultNth(index) => {
+                write!(f, "fault rule #{index} needs nth of at least 1")
+            }
+            ConfigError::Io(err) => write!(f, "failed to read configuration file: {err}"),
+            ConfigError::Toml(err) => write!(f, "invalid configuration file: {err}"),
+            ConfigError::Json(err) => write!(f, "invalid plugin configuration: {err}"),
+            ConfigError::UnknownKey(key) => write!(f, "unknown configuration key `{key}`"),
+            ConfigError::Labels(err) => write!(f, "failed to load labels: {err}"),
+            ConfigError::UnknownLabel(name) => {
+                write!(f, "the address filter names unknown label `{name}`")
+            }
+            ConfigError::InvalidEnvValue { name, value } => {
+                write!(f, "invalid value `{value}` for environment variable {name}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Builds a [`HelloWorldInspector`] step by step.
+///
+/// ```
+/// use restd::{HelloWorldInspectorBuilder, OutputMode};
+///
+/// let inspector = HelloWorldInspectorBuilder::new()
+///     .trace_calls(true)
+///     .step_sample_interval(1_000)
+///     .output(OutputMode::Tracing)
+///     .build();
+/// assert_eq!(inspector.config().step_sample_interval, 1_000);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HelloWorldInspectorBuilder {
+    config: HelloWorldInspectorConfig,
+    selector_resolver: Option<Arc<dyn SelectorResolver>>,
+    event_decoder: Option<Arc<EventDecoder>>,
+    fn_decoder: Option<Arc<FnDecoder>>,
+    labels: Option<Arc<Labels>>,
+    mocks: Option<MockRegistry>,
+    source_maps: Option<Arc<SourceMapRegistry>>,
+    spec: Option<SpecId>,
+}
+
+impl HelloWorldInspectorBuilder {
+    /// Creates a builder with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from the configuration of `profile`. Settings made before
+    /// this call are replaced, so call it first.
+    pub fn profile(mut self, profile: InspectorProfile) -> Self {
+        self.config = HelloWorldInspectorConfig::profile(profile);
+        self
+    }
+
+    /// Enables verbose logging.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.config.verbose = verbose;
+        self
+    }
+
+    /// Enables step-by-step execution logging.
+    pub fn log_steps(mut self, log_steps: bool) -> Self {
+        self.config.log_steps = log_steps;
+        self
+    }
+
+    /// Enables call tracing.
+    pub fn trace_calls(mut self, trace_calls: bool) -> Self {
+        self.config.trace_calls = trace_calls;
+        self
+    }
+
+    /// Sets whether every step is recorded or only a sample of them.
+    pub fn profiling(mut self, profiling: ProfilingMode) -> Self {
+        self.config.profiling = profiling;
+        self
+    }
+
+    /// Raises an [`OpcodeAlert`](crate::OpcodeAlert) whenever one of `opcodes`
+    /// executes, see [`opcodes::parse`](crate::opcodes::parse) to name them.
+    pub fn alert_on_opcodes(mut self, opcodes: impl IntoIterator<Item = u8>) -> Self {
+        self.config.alert_on_opcodes = opcodes.into_iter().collect();
+        self
+    }
+
+    /// Emits a step message every `interval` steps; 0 disables step messages.
+    pub fn step_sample_interval(mut self, interval: u64) -> Self {
+        self.config.step_sample_interval = interval;
+        self
+    }
+
+    /// Adds `address` to the addresses that are traced.
+    pub fn include_address(mut self, address: Address) -> Self {
+        self.config.filter.include.push(address);
+        self
+    }
+
+    /// Adds `address` to the addresses that are never traced.
+    pub fn exclude_address(mut self, address: Address) -> Self {
+        self.config.filter.exclude.push(address);
+        self
+    }
+
+    /// Adds the address labeled `name` to the addresses to trace.
+    pub fn include_label(mut self, name: impl Into<String>) -> Self {
+        self.config.filter.include_labels.push(name.into());
+        self
+    }
+
+    /// Adds the address labeled `name` to the addresses that are never traced.
+    pub fn exclude_label(mut self, name: impl Into<String>) -> Self {
+        self.config.filter.exclude_labels.push(name.into());
+        self
+    }
+
+    /// Sets where messages are written.
+    pub fn output(mut self, output: OutputMode) -> Self {
+        self.config.output = output;
+        self
+    }
+
+    /// Sets which address delegate calls are attributed to.
+    pub fn delegatecall_attribution(mut self, attribution: DelegateCallAttribution) -> Self {
+        self.config.delegatecall_attribution = attribution;
+        self
+    }
+
+    /// Charges the gas spent inside subcalls to the opcode that started them.
+    pub fn attribute_subcall_gas(mut self, attribute: bool) -> Self {
+        self.config.attribute_subcall_gas = attribute;
+        self
+    }
+
+    /// Stops recording steps after `max_steps` of them.
+    pub fn max_steps(mut self, max_steps: u64) -> Self {
+        self.config.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Writes at most `max` console lines per second.
+    pub fn max_lines_per_second(mut self, max: u32) -> Self {
+        self.config.max_lines_per_second = Some(max);
+        self
+    }
+
+    /// Follows `window` instructions after each low-level call to see
+    /// whether its success flag is checked; 0 disables the check.
+    pub fn unchecked_call_window(mut self, window: usize) -> Self {
+        self.config.unchecked_call_window = window;
+        self
+    }
+
+    /// Caps the collections the inspector keeps.
+    pub fn limits(mut self, limits: TraceLimits) -> Self {
+        self.config.limits = limits;
+        self
+    }
+
+    /// Keeps at most `max` bytes of the output of each frame.
+    pub fn max_output_bytes(mut self, max: usize) -> Self {
+        self.config.max_output_bytes = max;
+        self
+    }
+
+    /// Records the wall-clock time of every frame and transaction.
+    pub fn measure_time(mut self, measure: bool) -> Self {
+        self.config.measure_time = measure;
+        self
+    }
+
+    /// Keeps the code of every contract that runs in the report.
+    pub fn capture_code(mut self, capture: bool) -> Self {
+        self.config.capture_code = capture;
+        self
+    }
+
+    /// Counts how often each `JUMPI` jumped and fell through.
+    pub fn branch_coverage(mut self, enabled: bool) -> Self {
+        self.config.branch_coverage = enabled;
+        self
+    }
+
+    /// Counts how often each instruction of each contract executed.
+    pub fn pc_coverage(mut self, enabled: bool) -> Self {
+        self.config.pc_coverage = enabled;
+        self
+    }
+
+    /// Leaves contracts with more than `bytes` of code out of `pc_coverage`.
+    pub fn coverage_max_code_size(mut self, bytes: usize) -> Self {
+        self.config.coverage_max_code_size = Some(bytes);
+        self
+    }
+
+    /// Records single instructions that grow memory by more than `bytes`;
+    /// 0 disables them.
+    pub fn memory_expansion_threshold(mut self, bytes: u64) -> Self {
+        self.config.memory_expansion_threshold = bytes;
+        self
+    }
+
+    /// Forces the calls `rule` matches to fail instead of executing them.
+    pub fn fail_call(mut self, rule: FaultRule) -> Self {
+        self.config.faults.rules.push(rule);
+        self
+    }
+
+    /// Reports panics in callbacks and recording as internal errors instead
+    /// of unwinding through the EVM.
+    pub fn catch_panics(mut self, catch: bool) -> Self {
+        self.config.catch_panics = catch;
+        self
+    }
+
+    /// Checks the inspector's own bookkeeping, handling inconsistencies as
+    /// `action` says.
+    pub fn strict(mut self, action: InconsistencyAction) -> Self {
+        self.config.strict = true;
+        self.config.on_inconsistency = action;
+        self
+    }
+
+    /// Records the first instruction in each frame that leaves less than
+    /// `percent` percent of its gas limit.
+    pub fn low_gas_percent(mut self, percent: u64) -> Self {
+        self.config.low_gas_percent = Some(percent);
+        self
+    }
+
+    /// Has plugin-created inspectors append their events to the file at `path`.
+    pub fn sink_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.sink_path = Some(path.into());
+        self
+    }
+
+    /// Redacts the calldata and return data of every frame.
+    pub fn redact_calldata(mut self, redact: bool) -> Self {
+        self.config.redact_calldata = redact;
+        self
+    }
+
+    /// Redacts every storage value read or written.
+    pub fn redact_storage_values(mut self, redact: bool) -> Self {
+        self.config.redact_storage_values = redact;
+        self
+    }
+
+    /// Redacts the calldata and return data of frames calling `address`.
+    pub fn redact_address(mut self, address: Address) -> Self {
+        self.config.redact_addresses.push(address);
+        self
+    }
+
+    /// Treats addresses up to `upper_bound` as precompiles.
+    pub fn precompile_upper_bound(mut self, upper_bound: u64) -> Self {
+        self.config.precompile_upper_bound = upper_bound;
+        self
+    }
+
+    /// Names called functions using `resolver`.
+    pub fn selector_resolver(mut self, resolver: impl SelectorResolver + 'static) -> Self {
+        self.selector_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Decodes the logs of the events `decoder` knows.
+    pub fn event_decoder(mut self, decoder: EventDecoder) -> Self {
+        self.event_decoder = Some(Arc::new(decoder));
+        self
+    }
+
+    /// Decodes the arguments of calls to the functions `decoder` knows.
+    pub fn fn_decoder(mut self, decoder: FnDecoder) -> Self {
+        self.fn_decoder = Some(Arc::new(decoder));
+        self
+    }
+
+    /// Names addresses in the output and resolves the labels of the address
+    /// filter with `labels`, which can be shared with other inspectors.
+    pub fn labels(mut self, labels: impl Into<Arc<Labels>>) -> Self {
+        self.labels = Some(labels.into());
+        self
+    }
+
+    /// Annotates reverts and failures with their source location in the
+    /// contracts `source_maps` knows.
+    pub fn source_maps(mut self, source_maps: impl Into<Arc<SourceMapRegistry>>) -> Self {
+        self.source_maps = Some(source_maps.into());
+        self
+    }
+
+    /// Answers calls to the functions `mocks` knows without executing them.
+    pub fn mocks(mut self, mocks: MockRegistry) -> Self {
+        self.mocks = Some(mocks);
+        self
+    }
+
+    /// Warns about executed opcodes that `spec` does not support.
+    pub fn spec_awareness(mut self, spec: SpecId) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Validates the settings and returns the configuration.
+    pub fn try_build_config(self) -> Result<HelloWorldInspectorConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    /// Returns the configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the settings conflict, see [`try_build_config`](Self::try_build_config).
+    pub fn build_config(self) -> HelloWorldInspectorConfig {
+        self.try_build_config()
+            .unwrap_or_else(|err| panic!("invalid inspector configuration: {err}"))
+    }
+
+    /// Validates the settings and creates the inspector.
+    pub fn try_build(mut self) -> Result<HelloWorldInspector, ConfigError> {
+        let selector_resolver = self.selector_resolver.take();
+        let event_decoder = self.event_decoder.take();
+        let fn_decoder = self.fn_decoder.take();
+        let labels = self.labels.take();
+        let mocks = self.mocks.take();
+        let source_maps = self.source_maps.take();
+        let spec = self.spec.take();
+        let mut config = self.try_build_config()?;
+        if let Some(labels) = &labels {
+            config.filter.resolve_labels(labels)?;
+        } else if let Some(name) = config.filter.labels().next() {
+            return Err(ConfigError::UnknownLabel(name.to_string()));
+        }
+        let mut inspector = HelloWorldInspector::with_config(config);
+        if let Some(resolver) = selector_resolver {
+            inspector = inspector.with_selector_resolver(resolver);
+        }
+        if let Some(decoder) = event_decoder {
+            inspector = inspector.with_event_decoder(decoder);
+        }
+        if let Some(decoder) = fn_decoder {
+            inspector = inspector.with_fn_decoder(decoder);
+        }
+        if let Some(labels) = labels {
+            inspector = inspector.with_labels(labels);
+        }
+        if let Some(source_maps) = source_maps {
+            inspector = inspector.with_source_maps(source_maps);
+        }
+        if let Some(mocks) = mocks {
+            inspector = inspector.with_mocks(mocks);
+        }
+        if let Some(spec) = spec {
+            inspector = inspector.with_spec_awareness(spec);
+        }
+        Ok(inspector)
+    }
+
+    /// Creates the inspector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the settings conflict, see [`try_build`](Self::try_build).
+    pub fn build(self) -> HelloWorldInspector {
+        self.try_build()
+            .unwrap_or_else(|err| panic!("invalid inspector configuration: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_default_config() {
+        let config = HelloWorldInspectorBuilder::new().build_config();
+        assert!(!config.verbose && !config.log_steps && !config.trace_calls);
+        assert_eq!(config.output, OutputMode::Stdout);
+        assert_eq!(config.step_sample_interval, 100);
+        assert!(config.filter.is_empty());
+    }
+
+    #[test]
+    fn test_setters_are_applied() {
+        let address = Address::repeat_byte(0x10);
+        let inspector = HelloWorldInspectorBuilder::new()
+            .verbose(true)
+            .log_steps(true)
+            .trace_calls(true)
+            .step_sample_interval(5)
+            .include_address(address)
+            .output(OutputMode::Tracing)
+            .build();
+        let config = inspector.config();
+        assert!(config.verbose && config.log_steps && config.trace_calls);
+        assert_eq!(config.step_sample_interval, 5);
+        assert_eq!(config.filter.include, vec![address]);
+        assert_eq!(config.output, OutputMode::Tracing);
+    }
+
+    #[test]
+    fn test_conflicting_settings_are_rejected() {
+        let err = HelloWorldInspectorBuilder::new()
+            .verbose(true)
+            .output(OutputMode::Silent)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err, ConfigError::VerboseWhileSilent);
+
+        let address = Address::repeat_byte(0x10);
+        let err = HelloWorldInspectorBuilder::new()
+            .include_address(address)
+            .exclude_address(address)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err, ConfigError::AddressIncludedAndExcluded(address));
+        assert!(err.to_string().contains("both included and excluded"));
+
+        let err = HelloWorldInspectorBuilder::new()
+            .low_gas_percent(101)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err, ConfigError::LowGasPercentOutOfRange(101));
+    }
+}
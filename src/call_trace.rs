@@ -0,0 +1,595 @@
+//! Call tree recording.
+//!
+//! [`CallTraceInspector`] builds a tree of [`CallTraceNode`]s, one per call or
+//! create frame, mirroring the nesting of the executed transaction.
+
+use std::time::Instant;
+
+use alloy_primitives::{Address, Bytes, Log, B256, U256};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, CreateScheme,
+        EOFCreateInputs, InstructionResult, Interpreter,
+    },
+    Database, EvmContext, Inspector,
+};
+
+use crate::{
+    calls,
+    creations::{eof_container_hash, eof_initdata},
+    eip7702,
+    limits::ByteBudget,
+    precompile::{is_precompile, precompile_name, DEFAULT_PRECOMPILE_UPPER_BOUND},
+    timing, TraceLimits,
+};
+
+/// The kind of frame a [`CallTraceNode`] represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// A regular `CALL`.
+    Call,
+    /// A `STATICCALL`.
+    StaticCall,
+    /// A `DELEGATECALL`.
+    DelegateCall,
+    /// A `CALLCODE`.
+    CallCode,
+    /// A `CREATE`.
+    Create,
+    /// A `CREATE2`.
+    Create2,
+    /// An `EOFCREATE` or a creation transaction deploying an EOF container.
+    EofCreate {
+        /// Keccak-256 hash of the init container, `None` if a creation
+        /// transaction does not start with one
+        container_hash: Option<B256>,
+    },
+    /// A `SELFDESTRUCT`, recorded as a leaf frame from the destroyed
+    /// contract to the beneficiary.
+    SelfDestruct,
+    /// A call to a precompile.
+    Precompile {
+        /// Name of the precompile, e.g. `identity`
+        name: &'static str,
+        /// How the precompile was called
+        scheme: CallScheme,
+    },
+    /// A successful call to an account without code, such as an EOA, which
+    /// at most moves value; any calldata is ignored.
+    Transfer {
+        /// How the account was called
+        scheme: CallScheme,
+    },
+    /// A creation with empty init code, which deploys an account without
+    /// code.
+    EmptyCreate {
+        /// How the account was created
+        scheme: CreateScheme,
+    },
+}
+
+impl From<CallScheme> for CallKind {
+    fn from(scheme: CallScheme) -> Self {
+        match scheme {
+            CallScheme::Call | CallScheme::ExtCall => CallKind::Call,
+            CallScheme::StaticCall | CallScheme::ExtStaticCall => CallKind::StaticCall,
+            CallScheme::DelegateCall | CallScheme::ExtDelegateCall => CallKind::DelegateCall,
+            CallScheme::CallCode => CallKind::CallCode,
+        }
+    }
+}
+
+impl From<CreateScheme> for CallKind {
+    fn from(scheme: CreateScheme) -> Self {
+        match scheme {
+            CreateScheme::Create => CallKind::Create,
+            CreateScheme::Create2 { .. } => CallKind::Create2,
+        }
+    }
+}
+
+impl CallKind {
+    /// Returns true for `CREATE`, `CREATE2` and EOF create frames.
+    pub fn is_create(&self) -> bool {
+        matches!(
+            self,
+            CallKind::Create
+                | CallKind::Create2
+                | CallKind::EofCreate { .. }
+                | CallKind::EmptyCreate { .. }
+        )
+    }
+
+    /// Returns true for calls to precompiles.
+    pub fn is_precompile(&self) -> bool {
+        matches!(self, CallKind::Precompile { .. })
+    }
+
+    /// Returns true for frames that run no code: transfers to accounts
+    /// without code and creations with empty init code.
+    pub fn is_codeless(&self) -> bool {
+        matches!(self, CallKind::Transfer { .. } | CallKind::EmptyCreate { .. })
+    }
+
+    /// Returns the kind of call used to reach a precompile or an account
+    /// without code, or the kind itself for every other frame.
+    pub fn base(self) -> CallKind {
+        match self {
+            CallKind::Precompile { scheme, .. } | CallKind::Transfer { scheme } => scheme.into(),
+            CallKind::EmptyCreate { scheme } => scheme.into(),
+            kind => kind,
+        }
+    }
+}
+
+/// A log emitted by a frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "persist", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallTraceLog {
+    /// Emitting address, topics and data
+    pub log: Log,
+    /// Number of instructions executed in the transaction when the log was
+    /// emitted, which orders it among the frame's children
+    pub step: u64,
+}
+
+/// A single call or create frame in the call tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "persist", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallTraceNode {
+    /// Kind of frame
+    pub kind: CallKind,
+    /// Address that initiated the frame
+    pub caller: Address,
+    /// Called address, or the deployed address for create frames
+    /// (`Address::ZERO` if the creation failed)
+    pub target: Address,
+    /// Address whose code ran if `target` is an account delegated with
+    /// EIP-7702
+    pub delegate: Option<Address>,
+    /// Value transferred (or apparent value for delegate calls)
+    pub value: U256,
+    /// Calldata, or init code for create frames
+    pub input: Bytes,
+    /// Return data, or revert data for failed frames
+    pub output: Bytes,
+    /// Gas made available to the frame
+    pub gas_limit: u64,
+    /// Gas the calling instruction asked to forward, `None` for the
+    /// top-level frame and for instructions without a gas argument
+    #[cfg_attr(feature = "persist", serde(default))]
+    pub gas_requested: Option<u64>,
+    /// Whether the frame was granted less than `gas_requested` because of
+    /// the 63/64 rule
+    #[cfg_attr(feature = "persist", serde(default))]
+    pub gas_capped: bool,
+    /// Gas consumed by the frame, including its subcalls
+    pub gas_used: u64,
+    /// Whether the frame completed successfully
+    pub success: bool,
+    /// Instruction result the frame ended with
+    pub result: InstructionResult,
+    /// Call depth, the top-level frame is at depth 0
+    pub depth: usize,
+    /// Number of instructions executed in the transaction before the frame started
+    pub start_step: u64,
+    /// Number of instructions executed in the transaction when the frame ended
+    pub end_step: u64,
+    /// Microseconds from the start of the first traced frame to the start
+    /// of this one, if the inspector measured time
+    #[cfg_attr(feature = "persist", serde(default))]
+    pub start_micros: Option<u64>,
+    /// Microseconds from the start of the first traced frame to the end of
+    /// this one, if the inspector measured time
+    #[cfg_attr(feature = "persist", serde(default))]
+    pub end_micros: Option<u64>,
+    /// Frames started from within this frame, in execution order
+    pub children: Vec<CallTraceNode>,
+    /// Logs emitted by this frame itself, in execution order
+    pub logs: Vec<CallTraceLog>,
+}
+
+impl CallTraceNode {
+    fn new(
+        kind: CallKind,
+        caller: Address,
+        target: Address,
+        value: U256,
+        input: Bytes,
+        gas_limit: u64,
+        depth: usize,
+    ) -> Self {
+        Self {
+            kind,
+            caller,
+            target,
+            delegate: None,
+            value,
+            input,
+            output: Bytes::new(),
+            gas_limit,
+            gas_requested: None,
+            gas_capped: false,
+            gas_used: 0,
+            success: false,
+            result: InstructionResult::Continue,
+            depth,
+            start_step: 0,
+            end_step: 0,
+            start_micros: None,
+            end_micros: None,
+            children: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    /// Returns the wall-clock time the frame took, if it was measured.
+    pub fn duration_micros(&self) -> Option<u64> {
+        Some(self.end_micros?.saturating_sub(self.start_micros?))
+    }
+
+    /// Returns the depth of the deepest frame in this subtree.
+    pub fn max_depth(&self) -> usize {
+        self.children
+            .iter()
+            .map(CallTraceNode::max_depth)
+            .max()
+            .unwrap_or(self.depth)
+    }
+
+    /// Iterates over this node and all of its descendants in pre-order.
+    pub fn iter(&self) -> impl Iterator<Item = &CallTraceNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+}
+
+/// A completed call tree for a single transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "persist", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallTrace {
+    root: Option<CallTraceNode>,
+}
+
+impl CallTrace {
+    /// Creates a trace from its top-level frame.
+    pub fn new(root: CallTraceNode) -> Self {
+        Self { root: Some(root) }
+    }
+
+    /// Returns the top-level frame, if a transaction was traced.
+    pub fn root(&self) -> Option<&CallTraceNode> {
+        self.root.as_ref()
+    }
+
+    /// Returns the depth of the deepest frame, or `None` for an empty trace.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.root.as_ref().map(CallTraceNode::max_depth)
+    }
+
+    /// Iterates over every frame of the trace in pre-order.
+    pub fn nodes(&self) -> impl Iterator<Item = &CallTraceNode> {
+        self.root.iter().flat_map(CallTraceNode::iter)
+    }
+}
+
+/// Inspector that records the call tree of a transaction.
+///
+/// A node is pushed when a call or create frame starts and popped, filled
+/// with the outcome, and attached to its parent when the frame ends.
+#[derive(Debug)]
+pub struct CallTraceInspector {
+    /// Frames that have started but not yet ended, innermost last
+    stack: Vec<CallTraceNode>,
+    /// The completed top-level frame
+    root: Option<CallTraceNode>,
+    /// Highest address treated as a precompile
+    precompile_upper_bound: u64,
+    /// Number of instructions executed so far
+    steps: u64,
+    /// Gas argument of the last instruction if it was a call
+    pending_call_gas: Option<u64>,
+    /// Estimate of the bytes retained, for `max_total_bytes`
+    bytes: ByteBudget,
+    /// Whether frames are timed
+    measure_time: bool,
+    /// When the first frame started, which frame times are relative to
+    epoch: Option<Instant>,
+}
+
+impl Default for CallTraceInspector {
+    fn default() -> Self {
+        Self {
+            stack: Vec::new(),
+            root: None,
+            precompile_upper_bound: DEFAULT_PRECOMPILE_UPPER_BOUND,
+            steps: 0,
+            pending_call_gas: None,
+            bytes: ByteBudget::default(),
+            measure_time: false,
+            epoch: None,
+        }
+    }
+}
+
+impl CallTraceInspector {
+    /// Creates a new, empty call tracer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treats addresses up to `upper_bound` as precompiles, for chains that
+    /// add precompiles beyond the standard ones.
+    pub fn with_precompile_upper_bound(mut self, upper_bound: u64) -> Self {
+        self.precompile_upper_bound = upper_bound;
+        self
+    }
+
+    /// Stops copying calldata and return data once the trace retains about
+    /// `limits.max_total_bytes`; frames are still recorded without them.
+    pub fn with_limits(mut self, limits: TraceLimits) -> Self {
+        self.bytes = ByteBudget::new(limits.max_total_bytes);
+        self
+    }
+
+    /// Records when each frame starts and ends, in microseconds since the
+    /// first frame started. Where there is no clock, as on wasm32, frames
+    /// are left untimed.
+    pub fn with_timing(mut self) -> Self {
+        self.measure_time = true;
+        self
+    }
+
+    /// Returns the completed top-level frame, if any.
+    pub fn root(&self) -> Option<&CallTraceNode> {
+        self.root.as_ref()
+    }
+
+    /// Returns true if calldata or return data was dropped because
+    /// `max_total_bytes` was reached.
+    pub fn is_truncated(&self) -> bool {
+        self.bytes.is_exhausted()
+    }
+
+    /// Consumes the inspector and returns the recorded trace.
+    pub fn into_trace(self) -> CallTrace {
+        CallTrace { root: self.root }
+    }
+
+    fn push(
+        &mut self,
+        kind: CallKind,
+        caller: Address,
+        target: Address,
+        value: U256,
+        input: Bytes,
+        gas_limit: u64,
+    ) {
+        let depth = self.stack.len();
+        self.bytes.charge::<CallTraceNode>();
+        let input = self.bytes.keep(&input);
+        let mut node = CallTraceNode::new(kind, caller, target, value, input, gas_limit, depth);
+        node.start_step = self.steps;
+        node.start_micros = self.micros();
+        self.stack.push(node);
+    }
+
+    /// Returns the microseconds since the first frame started, if frames
+    /// are timed.
+    fn micros(&mut self) -> Option<u64> {
+        if !self.measure_time {
+            return None;
+        }
+        let now = timing::now()?;
+        Some(timing::micros_between(*self.epoch.get_or_insert(now), now))
+    }
+
+    fn pop(
+        &mut self,
+        result: InstructionResult,
+        gas_used: u64,
+        output: Bytes,
+    ) -> Option<&mut CallTraceNode> {
+        let mut node = self.stack.pop()?;
+        node.success = result.is_ok();
+        node.result = result;
+        node.gas_used = gas_used;
+        node.output = self.bytes.keep(&output);
+        node.end_step = self.steps;
+        node.end_micros = self.micros();
+        match self.stack.last_mut() {
+            Some(parent) => {
+                parent.children.push(node);
+                parent.children.last_mut()
+            }
+            None => {
+                self.root = Some(node);
+                self.root.as_mut()
+            }
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallTraceInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        // Empty init code runs the padding `STOP`, which is not an instruction
+        // of the contract
+        if !interp.contract.bytecode.is_empty() {
+            self.steps += 1;
+            self.pending_call_gas = calls::requested_gas(interp);
+        }
+    }
+
+    fn log(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>, log: &Log) {
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        self.bytes.charge::<CallTraceLog>();
+        let data = self.bytes.keep(&log.data.data);
+        let mut log = log.clone();
+        log.data.data = data;
+        frame.logs.push(CallTraceLog {
+            log,
+            step: self.steps,
+        });
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let kind = if is_precompile(&inputs.bytecode_address, self.precompile_upper_bound) {
+            CallKind::Precompile {
+                name: precompile_name(&inputs.bytecode_address),
+                scheme: inputs.scheme,
+            }
+        } else {
+            inputs.scheme.into()
+        };
+        // A transaction's call has no calling instruction
+        let gas_requested = self.pending_call_gas.take().filter(|_| !self.stack.is_empty());
+        self.push(
+            kind,
+            inputs.caller,
+            inputs.target_address,
+            inputs.call_value(),
+            inputs.input.clone(),
+            inputs.gas_limit,
+        );
+        if let Some(node) = self.stack.last_mut() {
+            node.gas_requested = gas_requested;
+            node.gas_capped = calls::is_gas_capped(inputs, gas_requested);
+            if inputs.bytecode_address == inputs.target_address {
+                node.delegate = eip7702::resolve_delegate(context, inputs.target_address);
+            }
+        }
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        let steps = self.steps;
+        if let Some(node) = self.stack.last_mut() {
+            // Code always runs at least one instruction
+            let codeless = node.start_step == steps && !node.kind.is_precompile();
+            if codeless && outcome.result.is_ok() {
+                node.kind = CallKind::Transfer {
+                    scheme: inputs.scheme,
+                };
+            }
+        }
+        self.pop(
+            outcome.result.result,
+            outcome.result.gas.spent(),
+            outcome.result.output.clone(),
+        );
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let kind = if inputs.init_code.is_empty() {
+            CallKind::EmptyCreate {
+                scheme: inputs.scheme,
+            }
+        } else {
+            inputs.scheme.into()
+        };
+        self.push(
+            kind,
+            inputs.caller,
+            Address::ZERO,
+            inputs.value,
+            inputs.init_code.clone(),
+            inputs.gas_limit,
+        );
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let address = outcome.address.unwrap_or_default();
+        if let Some(node) = self.pop(
+            outcome.result.result,
+            outcome.result.gas.spent(),
+            outcome.result.output.clone(),
+        ) {
+            node.target = address;
+        }
+        outcome
+    }
+
+    fn eofcreate(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.push(
+            CallKind::EofCreate {
+                container_hash: eof_container_hash(inputs),
+            },
+            inputs.caller,
+            Address::ZERO,
+            inputs.value,
+            eof_initdata(inputs),
+            inputs.gas_limit,
+        );
+        None
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let address = outcome.address.unwrap_or_default();
+        if let Some(node) = self.pop(
+            outcome.result.result,
+            outcome.result.gas.spent(),
+            outcome.result.output.clone(),
+        ) {
+            node.target = address;
+        }
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        let micros = self.micros();
+        let Some(parent) = self.stack.last_mut() else {
+            return;
+        };
+        let mut node = CallTraceNode::new(
+            CallKind::SelfDestruct,
+            contract,
+            target,
+            value,
+            Bytes::new(),
+            0,
+            parent.depth + 1,
+        );
+        node.start_step = self.steps;
+        node.end_step = self.steps;
+        node.start_micros = micros;
+        node.end_micros = micros;
+        node.success = true;
+        node.result = InstructionResult::SelfDestruct;
+        parent.children.push(node);
+    }
+}
@@ -0,0 +1,288 @@
+//! Per-call records collected by [`HelloWorldInspector`](crate::HelloWorldInspector).
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, Selector, U256};
+use revm::interpreter::{
+    gas::CALL_STIPEND, opcode, CallInputs, CallScheme, InstructionResult, Interpreter,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    abi::{DecodedCall, FnDecoder},
+    call_trace::CallKind, geth::geth_type, plugin::DelegateCallAttribution, redact::Redacted,
+    returndata::ReturnData, selector::selector_hex,
+};
+
+/// Gas accounting for a single call frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallGasRecord {
+    /// Called address, whose storage the frame uses
+    pub target: Address,
+    /// Name of `target` in the inspector's [`Labels`](crate::Labels), if it
+    /// has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Address the executed code was loaded from; differs from `target`
+    /// for `DELEGATECALL` and `CALLCODE`, e.g. the implementation behind a proxy
+    #[serde(default)]
+    pub bytecode_address: Address,
+    /// Address that made the call
+    #[serde(default)]
+    pub caller: Address,
+    /// Whether `bytecode_address` is an account delegated with EIP-7702,
+    /// so the code of `delegate` ran
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub delegated: bool,
+    /// Address whose code ran if the account is delegated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<Address>,
+    /// Call depth, the top-level call is at depth 0
+    #[serde(default)]
+    pub depth: u64,
+    /// Geth frame type of the call, e.g. `CALL` or `STATICCALL`
+    #[serde(default)]
+    pub kind: String,
+    /// Value transferred (or apparent value for delegate calls)
+    #[serde(default)]
+    pub value: U256,
+    /// Gas made available to the frame
+    pub gas_limit: u64,
+    /// Gas the calling instruction asked to forward, absent for the
+    /// top-level call, for instructions without a gas argument and unless
+    /// `profiling` is `full`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_requested: Option<u64>,
+    /// Whether the frame was granted less than `gas_requested` because the
+    /// 63/64 rule keeps 1/64 of the remaining gas in the caller
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub gas_capped: bool,
+    /// Gas consumed by the frame, including its subcalls
+    pub gas_used: u64,
+    /// Whether the frame reverted or halted
+    pub reverted: bool,
+    /// Index of the transaction the call belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+    /// First four bytes of the calldata, absent for calls with shorter input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Selector>,
+    /// Signature of the called function, if a selector resolver knows it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// The arguments decoded with the signature of the called function, if
+    /// an [`FnDecoder`] knows it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<DecodedCall>,
+    /// Why the arguments could not be decoded although the selector matches
+    /// a known function
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+    /// Decoded revert reason, if the frame reverted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Whether the called account has no code, so the call at most moved
+    /// value, e.g. a call to an EOA
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub no_code: bool,
+    /// Whether a [`FaultInjector`](crate::FaultInjector) forced the call to
+    /// fail instead of executing it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub injected: bool,
+    /// Whether a [`MockRegistry`](crate::MockRegistry) answered the call
+    /// instead of executing it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub mocked: bool,
+    /// Data the frame returned, `None` if it returned nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<ReturnData>,
+    /// Placeholder for the calldata if the frame is redacted, in which case
+    /// the selector, function and revert reason are left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted_calldata: Option<Redacted>,
+    /// Wall-clock time the frame took, including its subcalls, if
+    /// `measure_time` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_micros: Option<u64>,
+    /// Source of the report the record was merged from, see
+    /// [`InspectorReport::with_source_tag`](crate::InspectorReport::with_source_tag)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<String>,
+}
+
+impl CallGasRecord {
+    pub(crate) fn new(inputs: &CallInputs, depth: u64, tx_index: u64) -> Self {
+        Self {
+            target: inputs.target_address,
+            label: None,
+            bytecode_address: inputs.bytecode_address,
+            caller: inputs.caller,
+            delegated: false,
+            delegate: None,
+            depth,
+            kind: geth_type(CallKind::from(inputs.scheme)).to_string(),
+            value: inputs.call_value(),
+            gas_limit: inputs.gas_limit,
+            gas_requested: None,
+            gas_capped: false,
+            gas_used: 0,
+            reverted: false,
+            tx_index,
+            seq: 0,
+            selector: None,
+            function: None,
+            decoded: None,
+            decode_error: None,
+            revert_reason: None,
+            no_code: false,
+            injected: false,
+            mocked: false,
+            output: None,
+            redacted_calldata: None,
+            duration_micros: None,
+            source_tag: None,
+        }
+    }
+
+    /// Records the gas the calling instruction asked for, flagging the frame
+    /// if it was granted less.
+    pub(crate) fn set_gas_requested(&mut self, inputs: &CallInputs, requested: Option<u64>) {
+        self.gas_requested = requested;
+        self.gas_capped = is_gas_capped(inputs, requested);
+    }
+
+    /// Marks the frame as running the code of `delegate`, if any.
+    pub(crate) fn set_delegate(&mut self, delegate: Option<Address>) {
+        self.delegated = delegate.is_some();
+        self.delegate = delegate;
+    }
+
+    /// Decodes the arguments in `input` with `decoder`, filling in `decoded`
+    /// and, if no resolver named it, `function`, or, if the function is known
+    /// but the arguments do not match its signature, `decode_error`.
+    pub fn decode_with(&mut self, decoder: &FnDecoder, input: &[u8]) {
+        match decoder.decode(input) {
+            Some(Ok(decoded)) => {
+                self.function.get_or_insert_with(|| decoded.signature());
+                self.decoded = Some(decoded);
+            }
+            Some(Err(err)) => self.decode_error = Some(err.to_string()),
+            None => {}
+        }
+    }
+
+    /// Returns the resolved function signature, or the selector as hex.
+    pub fn function_label(&self) -> Option<String> {
+        self.function
+            .clone()
+            .or_else(|| self.selector.map(|selector| selector_hex(selector.0)))
+    }
+
+    /// Fills in the outcome of the frame.
+    ///
+    /// Exceptional halts (out of gas, invalid opcode, ...) consume the whole
+    /// gas limit of the frame regardless of what the interpreter reports.
+    pub(crate) fn finish(&mut self, result: InstructionResult, spent: u64) {
+        self.reverted = !result.is_ok();
+        self.gas_used = if result.is_error() {
+            self.gas_limit
+        } else {
+            spent.min(self.gas_limit)
+        };
+    }
+}
+
+pub(crate) fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Returns the stipend a call adds to the gas it forwards, which the caller
+/// does not pay for.
+pub(crate) fn call_stipend(inputs: &CallInputs) -> u64 {
+    match inputs.transfer_value() {
+        Some(value) if !value.is_zero() => CALL_STIPEND,
+        _ => 0,
+    }
+}
+
+/// Returns the gas argument of the `CALL`, `CALLCODE`, `DELEGATECALL` or
+/// `STATICCALL` about to execute, saturated to `u64`.
+pub(crate) fn requested_gas(interp: &Interpreter) -> Option<u64> {
+    match interp.current_opcode() {
+        opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+            let gas = interp.stack.peek(0).ok()?;
+            Some(gas.try_into().unwrap_or(u64::MAX))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the frame started by `inputs` was granted less gas than
+/// `requested`, not counting the stipend.
+pub(crate) fn is_gas_capped(inputs: &CallInputs, requested: Option<u64>) -> bool {
+    requested.is_some_and(|requested| {
+        requested > inputs.gas_limit.saturating_sub(call_stipend(inputs))
+    })
+}
+
+/// Maps every address whose storage ran code loaded from another address
+/// to that address, e.g. proxies to their implementations. If an address
+/// delegated to several implementations, the last one wins.
+pub(crate) fn resolve_implementations(records: &[CallGasRecord]) -> HashMap<Address, Address> {
+    records
+        .iter()
+        .filter(|record| record.target != record.bytecode_address)
+        .map(|record| (record.target, record.bytecode_address))
+        .collect()
+}
+
+/// Aggregated call statistics for a single address.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressStats {
+    /// Number of calls received
+    pub calls: u64,
+    /// Total gas used by those calls, including their subcalls
+    pub gas_used: u64,
+    /// Number of received calls that reverted or halted
+    pub reverts: u64,
+    /// Total value received by successful calls
+    pub value_in: U256,
+}
+
+impl AddressStats {
+    /// Adds the calls counted in `other`.
+    pub fn merge(&mut self, other: &AddressStats) {
+        self.calls += other.calls;
+        self.gas_used += other.gas_used;
+        self.reverts += other.reverts;
+        self.value_in += other.value_in;
+    }
+
+    pub(crate) fn record(&mut self, record: &CallGasRecord, value: U256) {
+        self.calls += 1;
+        self.gas_used += record.gas_used;
+        if record.reverted {
+            self.reverts += 1;
+        } else {
+            self.value_in += value;
+        }
+    }
+}
+
+/// Returns the address a call is attributed to in the per-address statistics.
+///
+/// Delegate calls (and `CALLCODE`) run the code of `bytecode_address` in the
+/// storage context of `target_address`; `attribution` picks which one counts.
+pub(crate) fn attributed_address(inputs: &CallInputs, attribution: DelegateCallAttribution) -> Address {
+    match (inputs.scheme, attribution) {
+        (
+            CallScheme::DelegateCall | CallScheme::ExtDelegateCall | CallScheme::CallCode,
+            DelegateCallAttribution::CodeAddress,
+        ) => inputs.bytecode_address,
+        _ => inputs.target_address,
+    }
+}
@@ -0,0 +1,148 @@
+//! Chrome `trace_event` export.
+//!
+//! Converts a [`CallTrace`] into the JSON loaded by `chrome://tracing` and
+//! Perfetto, with one begin/end event pair per frame. Timestamps are the
+//! microseconds recorded by [`CallTraceInspector::with_timing`], or step
+//! counts for traces recorded without timing, in which case a frame's width
+//! is the number of instructions executed inside it.
+//!
+//! [`CallTraceInspector::with_timing`]: crate::CallTraceInspector::with_timing
+
+use alloy_primitives::Address;
+use serde::Serialize;
+
+use crate::{
+    call_trace::{CallTrace, CallTraceNode},
+    geth::geth_type,
+    selector::{selector_of, SelectorResolver},
+};
+
+/// The top-level object of the JSON object format.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTrace {
+    trace_events: Vec<ChromeTraceEvent>,
+}
+
+/// A single duration event.
+#[derive(Debug, Serialize)]
+struct ChromeTraceEvent {
+    name: String,
+    cat: &'static str,
+    /// `B` to begin a frame, `E` to end it
+    ph: &'static str,
+    ts: u64,
+    pid: u64,
+    tid: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<ChromeTraceArgs>,
+}
+
+/// Frame details attached to begin events.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTraceArgs {
+    caller: Address,
+    target: Address,
+    gas_used: u64,
+    success: bool,
+}
+
+impl CallTrace {
+    /// Converts the trace into Chrome's `trace_event` JSON format, naming
+    /// frames by their target address.
+    pub fn to_chrome_trace(&self) -> String {
+        self.chrome_trace(None)
+    }
+
+    /// Converts the trace into Chrome's `trace_event` JSON format, naming
+    /// frames by the function `resolver` finds for their selector and falling
+    /// back to the target address.
+    pub fn to_chrome_trace_with_resolver(&self, resolver: &dyn SelectorResolver) -> String {
+        self.chrome_trace(Some(resolver))
+    }
+
+    fn chrome_trace(&self, resolver: Option<&dyn SelectorResolver>) -> String {
+        let mut trace = ChromeTrace {
+            trace_events: Vec::new(),
+        };
+        if let Some(root) = self.root() {
+            let clock = if root.start_micros.is_some() {
+                Clock::Micros
+            } else {
+                Clock::Steps
+            };
+            push_events(root, resolver, clock, &mut trace.trace_events);
+        }
+        serde_json::to_string(&trace).expect("chrome trace serializes")
+    }
+}
+
+/// What the timestamps of a trace count.
+#[derive(Debug, Clone, Copy)]
+enum Clock {
+    /// Microseconds of wall-clock time
+    Micros,
+    /// Instructions executed
+    Steps,
+}
+
+impl Clock {
+    fn start(self, node: &CallTraceNode) -> u64 {
+        match self {
+            Clock::Micros => node.start_micros.unwrap_or_default(),
+            Clock::Steps => node.start_step,
+        }
+    }
+
+    fn end(self, node: &CallTraceNode) -> u64 {
+        match self {
+            Clock::Micros => node.end_micros.unwrap_or_default(),
+            Clock::Steps => node.end_step,
+        }
+    }
+}
+
+fn push_events(
+    node: &CallTraceNode,
+    resolver: Option<&dyn SelectorResolver>,
+    clock: Clock,
+    events: &mut Vec<ChromeTraceEvent>,
+) {
+    let name = frame_name(node, resolver);
+    events.push(ChromeTraceEvent {
+        name: name.clone(),
+        cat: geth_type(node.kind),
+        ph: "B",
+        ts: clock.start(node),
+        pid: 1,
+        tid: 1,
+        args: Some(ChromeTraceArgs {
+            caller: node.caller,
+            target: node.target,
+            gas_used: node.gas_used,
+            success: node.success,
+        }),
+    });
+    for child in &node.children {
+        push_events(child, resolver, clock, events);
+    }
+    events.push(ChromeTraceEvent {
+        name,
+        cat: geth_type(node.kind),
+        ph: "E",
+        ts: clock.end(node),
+        pid: 1,
+        tid: 1,
+        args: None,
+    });
+}
+
+fn frame_name(node: &CallTraceNode, resolver: Option<&dyn SelectorResolver>) -> String {
+    if node.kind.is_create() {
+        return node.target.to_string();
+    }
+    selector_of(&node.input)
+        .and_then(|selector| resolver?.resolve(selector))
+        .unwrap_or_else(|| node.target.to_string())
+}
@@ -0,0 +1,69 @@
+//! Contracts approaching the code size limits.
+//!
+//! EIP-3860 caps init code at 49152 bytes and EIP-170 caps deployed code at
+//! 24576 bytes. A creation whose init code or deployed code is within 10% of
+//! its limit, or over it, is recorded as a [`CodeSizeFinding`], so a contract
+//! about to outgrow the limit is noticed before a deployment fails.
+
+use alloy_primitives::Address;
+use revm::{interpreter::MAX_INITCODE_SIZE, primitives::CfgEnv};
+use serde::{Deserialize, Serialize};
+
+/// Which code a [`CodeSizeFinding`] measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeSizeKind {
+    /// The init code of a creation, limited by EIP-3860
+    InitCode,
+    /// The runtime code returned by the init code, limited by EIP-170
+    DeployedCode,
+}
+
+/// Code within 10% of its size limit, or over it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSizeFinding {
+    /// Which code was measured
+    pub kind: CodeSizeKind,
+    /// Length of the code in bytes
+    pub length: usize,
+    /// Size limit of the code in bytes
+    pub limit: usize,
+    /// Address of the created contract, `None` if the creation failed before
+    /// an address was derived
+    pub address: Option<Address>,
+    /// Index of the transaction the creation belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+impl CodeSizeFinding {
+    /// Returns true if the code is larger than its limit.
+    pub fn exceeds_limit(&self) -> bool {
+        self.length > self.limit
+    }
+}
+
+/// Returns the init code limit, twice the deployed code limit if the
+/// configuration overrides it.
+pub(crate) fn init_code_limit(cfg: &CfgEnv) -> usize {
+    cfg.limit_contract_code_size
+        .map(|limit| limit.saturating_mul(2))
+        .unwrap_or(MAX_INITCODE_SIZE)
+}
+
+/// Returns true if `length` is within 10% of `limit` or over it.
+pub(crate) fn is_near_limit(length: usize, limit: usize) -> bool {
+    (length as u128) * 10 >= (limit as u128) * 9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_threshold_is_ninety_percent() {
+        assert!(!is_near_limit(44_236, MAX_INITCODE_SIZE));
+        assert!(is_near_limit(44_237, MAX_INITCODE_SIZE));
+        assert!(is_near_limit(MAX_INITCODE_SIZE + 1, MAX_INITCODE_SIZE));
+    }
+}
@@ -0,0 +1,78 @@
+//! Merging reports traced on several threads.
+//!
+//! Each thread traces its own transactions with its own inspector and sends
+//! the [`InspectorReport`]s through a channel; a [`ReportCollector`] merges
+//! them into one as they arrive.
+
+use std::sync::mpsc;
+
+use crate::InspectorReport;
+
+/// Receives reports from other threads and merges them into one.
+///
+/// ```no_run
+/// # use restd::{HelloWorldInspector, ReportCollector};
+/// let (collector, sender) = ReportCollector::new();
+/// for thread in 0..4 {
+///     let sender = sender.clone();
+///     std::thread::spawn(move || {
+///         let inspector = HelloWorldInspector::new();
+///         // ... trace transactions ...
+///         let report = inspector.report().with_source_tag(format!("thread-{thread}"));
+///         sender.send(report).unwrap();
+///     });
+/// }
+/// drop(sender);
+/// let report = collector.finish();
+/// ```
+#[derive(Debug)]
+pub struct ReportCollector {
+    receiver: mpsc::Receiver<InspectorReport>,
+    report: InspectorReport,
+    merged: usize,
+}
+
+impl ReportCollector {
+    /// Creates a collector and the sender that feeds it; clone the sender
+    /// for each thread.
+    pub fn new() -> (Self, mpsc::Sender<InspectorReport>) {
+        let (sender, receiver) = mpsc::channel();
+        let collector = Self {
+            receiver,
+            report: InspectorReport::default(),
+            merged: 0,
+        };
+        (collector, sender)
+    }
+
+    /// Merges the reports received so far without waiting for more, and
+    /// returns how many were merged.
+    pub fn try_merge(&mut self) -> usize {
+        let mut count = 0;
+        while let Ok(report) = self.receiver.try_recv() {
+            self.report.merge(report);
+            count += 1;
+        }
+        self.merged += count;
+        count
+    }
+
+    /// Returns the reports merged so far, combined.
+    pub fn report(&self) -> &InspectorReport {
+        &self.report
+    }
+
+    /// Returns the number of reports merged so far.
+    pub fn merged(&self) -> usize {
+        self.merged
+    }
+
+    /// Merges reports as they arrive until every sender is dropped, then
+    /// returns the combined report.
+    pub fn finish(mut self) -> InspectorReport {
+        while let Ok(report) = self.receiver.recv() {
+            self.report.merge(report);
+        }
+        self.report
+    }
+}
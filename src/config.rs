@@ -0,0 +1,240 @@
+//! Loading and validating [`HelloWorldInspectorConfig`].
+//!
+//! Configurations can be read from TOML, with the address filter in a
+//! `[filter]` section and trace limits in a `[limits]` section, and
+//! overridden by environment variables named after the fields, e.g.
+//! `RESTD_VERBOSE=true`, `RESTD_FILTER_INCLUDE=0x..,0x..` or
+//! `RESTD_LIMITS_MAX_LOGS=10000`.
+
+#[cfg(feature = "std")]
+use std::path::Path;
+use std::{env, str::FromStr};
+
+use alloy_primitives::Address;
+use serde::{de::IntoDeserializer, Deserialize};
+
+use crate::{opcodes, ConfigError, HelloWorldInspectorConfig, OutputMode, ProfilingMode};
+
+impl HelloWorldInspectorConfig {
+    /// Parses a TOML configuration and validates it.
+    ///
+    /// ```
+    /// use restd::HelloWorldInspectorConfig;
+    ///
+    /// let config = HelloWorldInspectorConfig::from_toml_str(
+    ///     r#"
+    ///     trace_calls = true
+    ///     step_sample_interval = 1000
+    ///
+    ///     [filter]
+    ///     exclude = ["0x000000000000000000000000000000000000dead"]
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(config.step_sample_interval, 1000);
+    /// assert_eq!(config.filter.exclude.len(), 1);
+    /// ```
+    pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(toml).map_err(|err| {
+            let message = err.message();
+            match message
+                .strip_prefix("unknown field `")
+                .and_then(|rest| rest.split_once('`'))
+            {
+                Some((key, _)) => ConfigError::UnknownKey(key.to_string()),
+                None => ConfigError::Toml(message.to_string()),
+            }
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads and parses a TOML configuration file.
+    #[cfg(feature = "std")]
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|err| ConfigError::Io(format!("{}: {err}", path.display())))?;
+        Self::from_toml_str(&toml)
+    }
+
+    /// Builds a configuration from the defaults and the environment variables
+    /// starting with `{prefix}_`.
+    pub fn from_env(prefix: &str) -> Result<Self, ConfigError> {
+        Self::default().with_env_overrides(prefix)
+    }
+
+    /// Overrides fields with the environment variables starting with
+    /// `{prefix}_`, e.g. a configuration loaded from a file.
+    ///
+    /// Variables with that prefix that do not name a field are rejected.
+    pub fn with_env_overrides(mut self, prefix: &str) -> Result<Self, ConfigError> {
+        let prefix = format!("{prefix}_");
+        for (name, value) in env::vars() {
+            if let Some(key) = name.strip_prefix(&prefix) {
+                self.set_from_env(key, &name, &value)?;
+            }
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Checks the configuration for conflicting settings.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.verbose && self.output == OutputMode::Silent {
+            return Err(ConfigError::VerboseWhileSilent);
+        }
+        if let Some(address) = self
+            .filter
+            .include
+            .iter()
+            .find(|address| self.filter.exclude.contains(address))
+        {
+            return Err(ConfigError::AddressIncludedAndExcluded(*address));
+        }
+        if let Some(percent) = self.low_gas_percent.filter(|percent| *percent > 100) {
+            return Err(ConfigError::LowGasPercentOutOfRange(percent));
+        }
+        if let ProfilingMode::Sampled { every_n_steps: 0, .. } = self.profiling {
+            return Err(ConfigError::ZeroSampleInterval);
+        }
+        if let Some(index) = self.faults.rules.iter().position(|rule| rule.nth == Some(0)) {
+            return Err(ConfigError::ZeroFaultNth(index));
+        }
+        Ok(())
+    }
+
+    fn set_from_env(&mut self, key: &str, name: &str, value: &str) -> Result<(), ConfigError> {
+        let invalid = || ConfigError::InvalidEnvValue {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        match key {
+            "VERBOSE" => self.verbose = parse(value).ok_or_else(invalid)?,
+            "LOG_STEPS" => self.log_steps = parse(value).ok_or_else(invalid)?,
+            "TRACE_CALLS" => self.trace_calls = parse(value).ok_or_else(invalid)?,
+            "STEP_SAMPLE_INTERVAL" => {
+                self.step_sample_interval = parse(value).ok_or_else(invalid)?
+            }
+            "MAX_STEPS" => self.max_steps = Some(parse(value).ok_or_else(invalid)?),
+            "MAX_OUTPUT_BYTES" => self.max_output_bytes = parse(value).ok_or_else(invalid)?,
+            "OUTPUT" => self.output = parse_enum(value).ok_or_else(invalid)?,
+            "MAX_LINES_PER_SECOND" => {
+                self.max_lines_per_second = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "DELEGATECALL_ATTRIBUTION" => {
+                self.delegatecall_attribution = parse_enum(value).ok_or_else(invalid)?
+            }
+            "ATTRIBUTE_SUBCALL_GAS" => {
+                self.attribute_subcall_gas = parse(value).ok_or_else(invalid)?
+            }
+            "PRECOMPILE_UPPER_BOUND" => {
+                self.precompile_upper_bound = parse(value).ok_or_else(invalid)?
+            }
+            "CATCH_PANICS" => self.catch_panics = parse(value).ok_or_else(invalid)?,
+            "STRICT" => self.strict = parse(value).ok_or_else(invalid)?,
+            "ON_INCONSISTENCY" => self.on_inconsistency = parse_enum(value).ok_or_else(invalid)?,
+            "MEASURE_TIME" => self.measure_time = parse(value).ok_or_else(invalid)?,
+            "CAPTURE_CODE" => self.capture_code = parse(value).ok_or_else(invalid)?,
+            "BRANCH_COVERAGE" => self.branch_coverage = parse(value).ok_or_else(invalid)?,
+            "PC_COVERAGE" => self.pc_coverage = parse(value).ok_or_else(invalid)?,
+            "COVERAGE_MAX_CODE_SIZE" => {
+                self.coverage_max_code_size = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "MEMORY_EXPANSION_THRESHOLD" => {
+                self.memory_expansion_threshold = parse(value).ok_or_else(invalid)?
+            }
+            "LOW_GAS_PERCENT" => {
+                self.low_gas_percent = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "SINK_PATH" => self.sink_path = Some(value.into()),
+            "OUTPUT_FORMAT" => self.output_format = Some(parse_enum(value).ok_or_else(invalid)?),
+            "OUTPUT_PATH" => self.output_path = Some(value.into()),
+            "UNCHECKED_CALL_WINDOW" => {
+                self.unchecked_call_window = parse(value).ok_or_else(invalid)?
+            }
+            "ALERT_ON_OPCODES" => {
+                self.alert_on_opcodes = value
+                    .split(',')
+                    .filter(|op| !op.trim().is_empty())
+                    .map(opcodes::parse)
+                    .collect::<Option<_>>()
+                    .ok_or_else(invalid)?
+            }
+            "PROFILING_EVERY_N_STEPS" => {
+                let every = parse(value).ok_or_else(invalid)?;
+                self.profiling = match self.profiling {
+                    ProfilingMode::Sampled { capacity, .. } => ProfilingMode::Sampled {
+                        every_n_steps: every,
+                        capacity,
+                    },
+                    ProfilingMode::Full | ProfilingMode::Counters => ProfilingMode::sampled(every),
+                };
+            }
+            "REDACT_CALLDATA" => self.redact_calldata = parse(value).ok_or_else(invalid)?,
+            "REDACT_STORAGE_VALUES" => {
+                self.redact_storage_values = parse(value).ok_or_else(invalid)?
+            }
+            "REDACT_ADDRESSES" => {
+                self.redact_addresses = parse_addresses(value).ok_or_else(invalid)?
+            }
+            "FILTER_INCLUDE" => self.filter.include = parse_addresses(value).ok_or_else(invalid)?,
+            "FILTER_EXCLUDE" => self.filter.exclude = parse_addresses(value).ok_or_else(invalid)?,
+            "FILTER_MATCH_ON" => self.filter.match_on = parse_enum(value).ok_or_else(invalid)?,
+            "FILTER_INCLUDE_LABELS" => self.filter.include_labels = parse_list(value),
+            "FILTER_EXCLUDE_LABELS" => self.filter.exclude_labels = parse_list(value),
+            "LIMITS_MAX_CALL_RECORDS" => {
+                self.limits.max_call_records = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "LIMITS_MAX_LOGS" => self.limits.max_logs = Some(parse(value).ok_or_else(invalid)?),
+            "LIMITS_MAX_STORAGE_ACCESSES" => {
+                self.limits.max_storage_accesses = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "LIMITS_MAX_TRANSIENT_ACCESSES" => {
+                self.limits.max_transient_accesses = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "LIMITS_MAX_STRUCT_LOGS" => {
+                self.limits.max_struct_logs = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "LIMITS_MAX_PREIMAGE_BYTES" => {
+                self.limits.max_preimage_bytes = Some(parse(value).ok_or_else(invalid)?)
+            }
+            "LIMITS_MAX_TOTAL_BYTES" => {
+                self.limits.max_total_bytes = Some(parse(value).ok_or_else(invalid)?)
+            }
+            _ => return Err(ConfigError::UnknownKey(name.to_string())),
+        }
+        Ok(())
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> Option<T> {
+    value.trim().parse().ok()
+}
+
+/// Parses a unit enum variant by its serde name, e.g. `tracing`.
+fn parse_enum<'de, T: Deserialize<'de>>(value: &str) -> Option<T> {
+    let value: serde::de::value::StrDeserializer<'_, serde::de::value::Error> =
+        value.trim().into_deserializer();
+    T::deserialize(value).ok()
+}
+
+/// Splits a comma-separated list, dropping empty entries.
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parses a comma-separated list of addresses; an empty value is an empty list.
+fn parse_addresses(value: &str) -> Option<Vec<Address>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| address.parse().ok())
+        .collect()
+}
@@ -0,0 +1,388 @@
+//! Branch coverage of `JUMPI` instructions and hit counts of instructions.
+//!
+//! With [`branch_coverage`](crate::HelloWorldInspectorConfig::branch_coverage)
+//! set, every `JUMPI` that executes is counted as taken or not taken, keyed
+//! by the address of the running code and the program counter of the
+//! instruction. A `JUMPI` is taken if the next instruction is not the one
+//! right after it.
+//!
+//! [`CoverageMap::merge`] accumulates the maps of many runs, e.g. of a
+//! fuzzing campaign, and [`CoverageMap::to_text`] writes one
+//! `address pc taken not_taken` line per branch, sorted so two maps can be
+//! diffed line by line.
+//!
+//! With [`pc_coverage`](crate::HelloWorldInspectorConfig::pc_coverage) set,
+//! a [`PcCoverage`] counts how often each instruction of the runtime code of
+//! every contract executed. [`PcCoverage::to_lcov`] writes the counts as an
+//! lcov `.info` file, mapped to source lines for contracts with a source map
+//! and to a pseudo-file of code offsets for the others.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
+
+use alloy_primitives::{Address, Bytes};
+use revm::primitives::Bytecode;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{disasm, Labels, SourceMapRegistry};
+
+/// How often a `JUMPI` went each way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchCoverage {
+    /// Executions that jumped
+    pub taken: u64,
+    /// Executions that fell through to the next instruction
+    pub not_taken: u64,
+}
+
+impl BranchCoverage {
+    /// Returns true if the branch went both ways.
+    pub fn is_covered(&self) -> bool {
+        self.taken > 0 && self.not_taken > 0
+    }
+}
+
+/// A `JUMPI` and its coverage, the serialized form of a [`CoverageMap`] entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CoveredBranch {
+    address: Address,
+    pc: usize,
+    #[serde(flatten)]
+    coverage: BranchCoverage,
+}
+
+/// Coverage of every `JUMPI` executed, keyed by code address and program
+/// counter.
+///
+/// Serializes as a list of branches sorted by address and program counter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageMap {
+    branches: HashMap<(Address, usize), BranchCoverage>,
+}
+
+impl CoverageMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts an execution of the `JUMPI` at `pc` in the code of `address`.
+    pub fn record(&mut self, address: Address, pc: usize, taken: bool) {
+        let branch = self.branches.entry((address, pc)).or_default();
+        if taken {
+            branch.taken += 1;
+        } else {
+            branch.not_taken += 1;
+        }
+    }
+
+    /// Returns the coverage of the `JUMPI` at `pc` in the code of `address`,
+    /// if it executed.
+    pub fn get(&self, address: Address, pc: usize) -> Option<BranchCoverage> {
+        self.branches.get(&(address, pc)).copied()
+    }
+
+    /// Returns the coverage of every branch, keyed by code address and
+    /// program counter.
+    pub fn branches(&self) -> &HashMap<(Address, usize), BranchCoverage> {
+        &self.branches
+    }
+
+    /// Returns the number of distinct branches executed.
+    pub fn len(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// Returns true if no `JUMPI` executed.
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// Returns the number of branches that went both ways.
+    pub fn covered(&self) -> usize {
+        self.branches
+            .values()
+            .filter(|branch| branch.is_covered())
+            .count()
+    }
+
+    /// Adds the counts of `other`, e.g. of another run.
+    pub fn merge(&mut self, other: &CoverageMap) {
+        for (key, branch) in &other.branches {
+            self.add(*key, *branch);
+        }
+    }
+
+    /// Adds the counts of `branch` to those of the branch at `key`.
+    fn add(&mut self, key: (Address, usize), branch: BranchCoverage) {
+        let total = self.branches.entry(key).or_default();
+        total.taken += branch.taken;
+        total.not_taken += branch.not_taken;
+    }
+
+    /// Writes one `address pc taken not_taken` line per branch, sorted by
+    /// address and program counter.
+    ///
+    /// Addresses are `0x`-prefixed lowercase hex, the rest decimal.
+    pub fn to_text<W: io::Write>(&self, mut w: W) -> io::Result<()> {
+        for branch in self.sorted() {
+            writeln!(
+                w,
+                "{:#x} {} {} {}",
+                branch.address, branch.pc, branch.coverage.taken, branch.coverage.not_taken
+            )?;
+        }
+        w.flush()
+    }
+
+    /// Returns the branches sorted by address and program counter.
+    fn sorted(&self) -> Vec<CoveredBranch> {
+        let mut branches: Vec<_> = self
+            .branches
+            .iter()
+            .map(|(&(address, pc), &coverage)| CoveredBranch {
+                address,
+                pc,
+                coverage,
+            })
+            .collect();
+        branches.sort_by_key(|branch| (branch.address, branch.pc));
+        branches
+    }
+}
+
+impl Serialize for CoverageMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.sorted().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CoverageMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut map = Self::new();
+        for branch in Vec::<CoveredBranch>::deserialize(deserializer)? {
+            map.add((branch.address, branch.pc), branch.coverage);
+        }
+        Ok(map)
+    }
+}
+
+/// Hit counts of the instructions of one contract's code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ContractHits {
+    /// The code the counts belong to
+    code: Bytes,
+    /// Executions of the instruction at each offset of `code`, saturating
+    hits: Vec<u32>,
+}
+
+/// How often each instruction of each contract executed, keyed by the
+/// address the code was loaded from.
+///
+/// Counts are per code offset, so push operands stay at 0, and saturate at
+/// `u32::MAX`. A contract's counts are allocated when its code first runs;
+/// init code is not counted. Serializes as a list of contracts with their
+/// code and the offsets that executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PcCoverage {
+    contracts: HashMap<Address, ContractHits>,
+}
+
+impl PcCoverage {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts an execution of the instruction at `pc` in `code`, the code
+    /// loaded from `address`, unless `code` is longer than `max_code_size`.
+    pub fn record(
+        &mut self,
+        address: Address,
+        code: &Bytecode,
+        pc: usize,
+        max_code_size: Option<usize>,
+    ) {
+        let contract = match self.contracts.get_mut(&address) {
+            Some(contract) => contract,
+            None => {
+                let len = code.original_byte_slice().len();
+                if max_code_size.is_some_and(|max| len > max) {
+                    return;
+                }
+                self.contracts.entry(address).or_insert(ContractHits {
+                    code: code.original_bytes(),
+                    hits: vec![0; len],
+                })
+            }
+        };
+        if let Some(hits) = contract.hits.get_mut(pc) {
+            *hits = hits.saturating_add(1);
+        }
+    }
+
+    /// Returns the hit count of every offset of the code loaded from
+    /// `address`, if it ran.
+    pub fn hits(&self, address: Address) -> Option<&[u32]> {
+        self.contracts
+            .get(&address)
+            .map(|contract| contract.hits.as_slice())
+    }
+
+    /// Returns the code the counts of `address` belong to, if it ran.
+    pub fn code(&self, address: Address) -> Option<&Bytes> {
+        self.contracts.get(&address).map(|contract| &contract.code)
+    }
+
+    /// Returns the addresses of the contracts whose code ran.
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.contracts.keys()
+    }
+
+    /// Returns the number of contracts whose code ran.
+    pub fn len(&self) -> usize {
+        self.contracts.len()
+    }
+
+    /// Returns true if no code ran.
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Adds the counts of `other`, e.g. of another run. Counts of a
+    /// contract whose code differs from the one already counted are dropped.
+    pub fn merge(&mut self, other: &PcCoverage) {
+        for (address, theirs) in &other.contracts {
+            let Some(ours) = self.contracts.get_mut(address) else {
+                self.contracts.insert(*address, theirs.clone());
+                continue;
+            };
+            if ours.code != theirs.code {
+                continue;
+            }
+            for (hits, more) in ours.hits.iter_mut().zip(&theirs.hits) {
+                *hits = hits.saturating_add(*more);
+            }
+        }
+    }
+
+    /// Writes the counts as an lcov `.info` file with one `DA:` line per
+    /// source line that has instructions.
+    ///
+    /// Contracts with a map in `source_maps` count towards the lines of
+    /// their sources; a line's count is that of its most executed
+    /// instruction, summed across contracts. Other contracts get a
+    /// pseudo-file named after their label in `labels`, or their address,
+    /// with the extension `.evm`, in which line `n` is the instruction at
+    /// code offset `n - 1`.
+    pub fn to_lcov<W: io::Write>(
+        &self,
+        labels: &Labels,
+        source_maps: &SourceMapRegistry,
+        mut w: W,
+    ) -> io::Result<()> {
+        let mut files: BTreeMap<String, BTreeMap<u32, u32>> = BTreeMap::new();
+        let mut addresses: Vec<_> = self.contracts.keys().copied().collect();
+        addresses.sort();
+        for address in addresses {
+            let contract = &self.contracts[&address];
+            let indices = disasm::instruction_indices(&contract.code);
+            let instructions = indices
+                .iter()
+                .enumerate()
+                .filter_map(|(pc, index)| Some((pc, (*index)?)));
+            if source_maps.contains(&address) {
+                let mut lines: BTreeMap<(String, u32), u32> = BTreeMap::new();
+                for (pc, index) in instructions {
+                    // Compiler-generated instructions have no line
+                    if let Some(location) = source_maps.location(&address, index) {
+                        let line = lines.entry((location.file, location.line)).or_default();
+                        *line = (*line).max(contract.hits[pc]);
+                    }
+                }
+                for ((file, line), hits) in lines {
+                    let total = files.entry(file).or_default().entry(line).or_default();
+                    *total = total.saturating_add(hits);
+                }
+            } else {
+                let name = labels
+                    .get(&address)
+                    .map_or_else(|| format!("{address:#x}"), String::from);
+                let file = files.entry(format!("{name}.evm")).or_default();
+                for (pc, _) in instructions {
+                    file.insert(pc as u32 + 1, contract.hits[pc]);
+                }
+            }
+        }
+        for (file, lines) in files {
+            writeln!(w, "TN:")?;
+            writeln!(w, "SF:{file}")?;
+            for (line, hits) in &lines {
+                writeln!(w, "DA:{line},{hits}")?;
+            }
+            writeln!(w, "LF:{}", lines.len())?;
+            writeln!(w, "LH:{}", lines.values().filter(|hits| **hits > 0).count())?;
+            writeln!(w, "end_of_record")?;
+        }
+        w.flush()
+    }
+}
+
+/// A contract's code and the offsets that executed, the serialized form of
+/// a [`PcCoverage`] entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CoveredContract {
+    address: Address,
+    code: Bytes,
+    /// Offsets that executed with their counts
+    hits: Vec<(usize, u32)>,
+}
+
+impl Serialize for PcCoverage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut contracts: Vec<_> = self
+            .contracts
+            .iter()
+            .map(|(address, contract)| CoveredContract {
+                address: *address,
+                code: contract.code.clone(),
+                hits: contract
+                    .hits
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, hits)| **hits > 0)
+                    .map(|(pc, hits)| (pc, *hits))
+                    .collect(),
+            })
+            .collect();
+        contracts.sort_by_key(|contract| contract.address);
+        contracts.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PcCoverage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut coverage = Self::new();
+        for contract in Vec::<CoveredContract>::deserialize(deserializer)? {
+            let mut hits = vec![0; contract.code.len()];
+            for (pc, count) in contract.hits {
+                *hits.get_mut(pc).ok_or_else(|| {
+                    de::Error::custom(format!(
+                        "offset {pc} is past the end of the code of {}",
+                        contract.address
+                    ))
+                })? = count;
+            }
+            coverage.contracts.insert(
+                contract.address,
+                ContractHits {
+                    code: contract.code,
+                    hits,
+                },
+            );
+        }
+        Ok(coverage)
+    }
+}
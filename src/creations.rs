@@ -0,0 +1,142 @@
+//! Records of contracts deployed with `CREATE` and `CREATE2`.
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{CreateScheme, EOFCreateInputs, EOFCreateKind},
+    primitives::Eof,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    disasm::{self, Instruction},
+    returndata::ReturnData,
+};
+
+/// How a contract address was derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CreateKind {
+    /// `CREATE` or a contract creation transaction, addressed by the
+    /// creator's nonce
+    Create,
+    /// `CREATE2`, addressed by the creator, salt and init code hash
+    Create2 {
+        /// The salt passed to `CREATE2`
+        salt: U256,
+    },
+}
+
+impl From<CreateScheme> for CreateKind {
+    fn from(scheme: CreateScheme) -> Self {
+        match scheme {
+            CreateScheme::Create => CreateKind::Create,
+            CreateScheme::Create2 { salt } => CreateKind::Create2 { salt },
+        }
+    }
+}
+
+/// A single contract creation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateRecord {
+    /// Account that deployed the contract
+    pub creator: Address,
+    /// How the address was derived
+    pub scheme: CreateKind,
+    /// Keccak-256 hash of the init code
+    pub init_code_hash: B256,
+    /// The init code, empty once `max_total_bytes` is exhausted or if the
+    /// creation is redacted
+    #[serde(default)]
+    pub init_code: Bytes,
+    /// Address of the deployed contract, `None` if the creation failed
+    pub deployed_address: Option<Address>,
+    /// Length of the runtime code returned by the init code, 0 if the
+    /// creation failed
+    pub deployed_code_len: usize,
+    /// First bytes of the code returned by the init code, enough to tell
+    /// the EOF magic and version apart from legacy code
+    #[serde(default, skip_serializing_if = "<[u8]>::is_empty")]
+    pub deployed_code_prefix: Bytes,
+    /// Data the init code returned: the runtime code, or the revert data if
+    /// the creation reverted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<ReturnData>,
+    /// Gas consumed by the creation frame, including its subcalls
+    pub gas_used: u64,
+    /// Whether the contract was deployed
+    pub success: bool,
+    /// Call depth of the creation frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the transaction the creation belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+    /// Wall-clock time the creation frame took, including its subcalls, if
+    /// `measure_time` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_micros: Option<u64>,
+}
+
+/// Number of bytes kept in [`CreateRecord::deployed_code_prefix`].
+pub(crate) const DEPLOYED_CODE_PREFIX_LEN: usize = 3;
+
+impl CreateRecord {
+    /// Returns true if the returned code starts with `0xEF`, the prefix of
+    /// EOF containers that EIP-3541 reserves and legacy deployments reject.
+    pub fn is_eof(&self) -> bool {
+        self.deployed_code_prefix.first() == Some(&0xEF)
+    }
+
+    /// Disassembles the init code.
+    pub fn disassembly(&self) -> Vec<Instruction> {
+        disasm::disassemble(&self.init_code)
+    }
+
+    /// Returns the address `CREATE2` derives from the creator, salt and init
+    /// code hash, or `None` for `CREATE`.
+    pub fn predicted_create2_address(&self) -> Option<Address> {
+        match self.scheme {
+            CreateKind::Create => None,
+            CreateKind::Create2 { salt } => {
+                Some(self.creator.create2(B256::from(salt), self.init_code_hash))
+            }
+        }
+    }
+
+    /// Returns true if a `CREATE2` deployed to a different address than
+    /// [`predicted_create2_address`](Self::predicted_create2_address).
+    ///
+    /// The EVM derives the address the same way, so a mismatch points at a
+    /// bug in the tracing rather than in the traced contracts.
+    pub fn has_address_mismatch(&self) -> bool {
+        match (self.predicted_create2_address(), self.deployed_address) {
+            (Some(predicted), Some(deployed)) => predicted != deployed,
+            _ => false,
+        }
+    }
+}
+
+/// Returns the data of an EOF creation: the init container followed by its
+/// input, as a creation transaction carries them.
+pub(crate) fn eof_initdata(inputs: &EOFCreateInputs) -> Bytes {
+    match &inputs.kind {
+        EOFCreateKind::Tx { initdata } => initdata.clone(),
+        EOFCreateKind::Opcode {
+            initcode, input, ..
+        } => [initcode.raw.as_ref(), input.as_ref()].concat().into(),
+    }
+}
+
+/// Returns the Keccak-256 hash of the init container of an EOF creation,
+/// `None` if a creation transaction does not start with a container.
+pub(crate) fn eof_container_hash(inputs: &EOFCreateInputs) -> Option<B256> {
+    match &inputs.kind {
+        EOFCreateKind::Tx { initdata } => Eof::decode_dangling(initdata.clone())
+            .ok()
+            .map(|(container, _)| keccak256(&container.raw)),
+        EOFCreateKind::Opcode { initcode, .. } => Some(keccak256(&initcode.raw)),
+    }
+}
@@ -0,0 +1,353 @@
+//! Differences between two call traces.
+//!
+//! [`CallTrace::diff`] aligns the frames of two traces, e.g. of the same
+//! transaction before and after a contract change, and reports which frames
+//! were added or removed and how the gas and outcome of the others changed.
+//!
+//! Frames are matched by depth, target and selector. Siblings are aligned by
+//! their longest common subsequence first, then leftover frames with the same
+//! key are paired up, so swapping two calls does not show up as a removal
+//! and an addition.
+
+use std::fmt::Write;
+
+use alloy_primitives::Address;
+
+use crate::{
+    call_trace::{CallKind, CallTrace, CallTraceNode},
+    geth::geth_type,
+    selector::{selector_hex, selector_of},
+};
+
+/// Whether a frame appears in one trace or both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    /// The frame is only in the new trace
+    Added,
+    /// The frame is only in the old trace
+    Removed,
+    /// The frame is in both traces
+    Matched,
+}
+
+/// A frame of either trace, with its counterpart in the other one if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameDiff {
+    /// Whether the frame was added, removed or matched
+    pub status: DiffStatus,
+    /// Kind of frame, as in the new trace if matched
+    pub kind: CallKind,
+    /// Call depth, the top-level frame is at depth 0
+    pub depth: usize,
+    /// Called address, or the deployed address for create frames
+    pub target: Address,
+    /// First four bytes of the calldata, `None` for creations, precompiles
+    /// and calls without one
+    pub selector: Option<[u8; 4]>,
+    /// Gas used in the old trace, `None` for added frames
+    pub gas_before: Option<u64>,
+    /// Gas used in the new trace, `None` for removed frames
+    pub gas_after: Option<u64>,
+    /// Whether the frame succeeded in the old trace, `None` for added frames
+    pub success_before: Option<bool>,
+    /// Whether the frame succeeded in the new trace, `None` for removed frames
+    pub success_after: Option<bool>,
+    /// Aligned subframes, in the order of the new trace with removed frames
+    /// where they were in the old one
+    pub children: Vec<FrameDiff>,
+}
+
+impl FrameDiff {
+    /// Returns the gas used in the new trace minus the old one, for matched
+    /// frames.
+    pub fn gas_delta(&self) -> Option<i64> {
+        Some(self.gas_after? as i64 - self.gas_before? as i64)
+    }
+
+    /// Returns true if a matched frame succeeded in one trace only.
+    pub fn success_changed(&self) -> bool {
+        matches!(
+            (self.success_before, self.success_after),
+            (Some(before), Some(after)) if before != after
+        )
+    }
+
+    /// Returns true if the frame and all of its subframes are unchanged.
+    pub fn is_unchanged(&self) -> bool {
+        self.iter().all(|frame| {
+            frame.status == DiffStatus::Matched
+                && frame.gas_delta() == Some(0)
+                && !frame.success_changed()
+        })
+    }
+
+    /// Iterates over this frame and all of its subframes in depth-first order.
+    pub fn iter(&self) -> impl Iterator<Item = &FrameDiff> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let frame = stack.pop()?;
+            stack.extend(frame.children.iter().rev());
+            Some(frame)
+        })
+    }
+
+    fn only(node: &CallTraceNode, status: DiffStatus) -> Self {
+        let (before, after) = match status {
+            DiffStatus::Removed => (Some(node), None),
+            _ => (None, Some(node)),
+        };
+        Self {
+            status,
+            kind: node.kind,
+            depth: node.depth,
+            target: node.target,
+            selector: key(node).2,
+            gas_before: before.map(|node| node.gas_used),
+            gas_after: after.map(|node| node.gas_used),
+            success_before: before.map(|node| node.success),
+            success_after: after.map(|node| node.success),
+            children: node
+                .children
+                .iter()
+                .map(|child| Self::only(child, status))
+                .collect(),
+        }
+    }
+
+    fn matched(old: &CallTraceNode, new: &CallTraceNode) -> Self {
+        Self {
+            status: DiffStatus::Matched,
+            kind: new.kind,
+            depth: new.depth,
+            target: new.target,
+            selector: key(new).2,
+            gas_before: Some(old.gas_used),
+            gas_after: Some(new.gas_used),
+            success_before: Some(old.success),
+            success_after: Some(new.success),
+            children: align(&old.children, &new.children),
+        }
+    }
+}
+
+/// The aligned frames of two traces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceDiff {
+    /// Aligned top-level frames: one matched frame, or the removed old and
+    /// added new one if their keys differ
+    pub frames: Vec<FrameDiff>,
+}
+
+impl TraceDiff {
+    /// Iterates over every frame in depth-first order.
+    pub fn iter(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.frames.iter().flat_map(FrameDiff::iter)
+    }
+
+    /// Returns the frames only in the new trace.
+    pub fn added(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.iter()
+            .filter(|frame| frame.status == DiffStatus::Added)
+    }
+
+    /// Returns the frames only in the old trace.
+    pub fn removed(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.iter()
+            .filter(|frame| frame.status == DiffStatus::Removed)
+    }
+
+    /// Returns the matched frames whose gas used changed.
+    pub fn gas_changes(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.iter()
+            .filter(|frame| frame.gas_delta().is_some_and(|delta| delta != 0))
+    }
+
+    /// Returns the matched frames that succeeded in one trace only.
+    pub fn success_changes(&self) -> impl Iterator<Item = &FrameDiff> {
+        self.iter().filter(|frame| frame.success_changed())
+    }
+
+    /// Returns true if both traces have the same frames with the same gas
+    /// and outcomes.
+    pub fn is_empty(&self) -> bool {
+        self.frames.iter().all(FrameDiff::is_unchanged)
+    }
+
+    /// Renders the diff as an indented tree, marking added frames with `+`
+    /// and removed frames with `-`:
+    ///
+    /// ```text
+    ///   CALL 0x2020202020202020202020202020202020202020::0xa9059cbb [1000 → 1200 (+200)]
+    ///   ├─ CALL 0x2121212121212121212121212121212121212121::fallback [500]
+    /// + └─ CALL 0x2222222222222222222222222222222222222222::fallback [54] (failed)
+    /// ```
+    pub fn pretty_print(&self) -> String {
+        let mut out = String::new();
+        for frame in &self.frames {
+            print_frame(&mut out, frame, "", "");
+        }
+        out
+    }
+}
+
+impl CallTrace {
+    /// Aligns this trace with `other` and returns their differences, with
+    /// `self` as the old trace and `other` as the new one.
+    pub fn diff(&self, other: &CallTrace) -> TraceDiff {
+        let old = self.root().map(std::slice::from_ref).unwrap_or_default();
+        let new = other.root().map(std::slice::from_ref).unwrap_or_default();
+        TraceDiff {
+            frames: align(old, new),
+        }
+    }
+}
+
+type FrameKey = (usize, Address, Option<[u8; 4]>);
+
+fn key(node: &CallTraceNode) -> FrameKey {
+    let selector = match node.kind {
+        CallKind::Create
+        | CallKind::Create2
+        | CallKind::EofCreate { .. }
+        | CallKind::EmptyCreate { .. }
+        | CallKind::SelfDestruct
+        | CallKind::Precompile { .. } => None,
+        _ => selector_of(&node.input),
+    };
+    (node.depth, node.target, selector)
+}
+
+/// Aligns two lists of sibling frames.
+fn align(old: &[CallTraceNode], new: &[CallTraceNode]) -> Vec<FrameDiff> {
+    let old_keys: Vec<_> = old.iter().map(key).collect();
+    let new_keys: Vec<_> = new.iter().map(key).collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of
+    // old_keys[i..] and new_keys[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old_keys[i] == new_keys[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Walk the table into a sequence of removals, additions and matches
+    enum Step {
+        Removed(usize),
+        Added(usize),
+        Matched(usize, usize),
+    }
+    let mut steps = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old_keys[i] == new_keys[j] {
+            steps.push(Step::Matched(i, j));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            steps.push(Step::Added(j));
+            j += 1;
+        } else {
+            steps.push(Step::Removed(i));
+            i += 1;
+        }
+    }
+
+    // Pair up reordered frames: an added frame takes the first removed one
+    // with the same key
+    let mut moved_from = vec![None; new.len()];
+    let mut moved = vec![false; old.len()];
+    for step in &steps {
+        if let Step::Added(j) = *step {
+            let removed = steps.iter().find_map(|step| match *step {
+                Step::Removed(i) if !moved[i] && old_keys[i] == new_keys[j] => Some(i),
+                _ => None,
+            });
+            if let Some(i) = removed {
+                moved[i] = true;
+                moved_from[j] = Some(i);
+            }
+        }
+    }
+
+    steps
+        .into_iter()
+        .filter_map(|step| match step {
+            Step::Matched(i, j) => Some(FrameDiff::matched(&old[i], &new[j])),
+            Step::Added(j) => Some(match moved_from[j] {
+                Some(i) => FrameDiff::matched(&old[i], &new[j]),
+                None => FrameDiff::only(&new[j], DiffStatus::Added),
+            }),
+            Step::Removed(i) => (!moved[i]).then(|| FrameDiff::only(&old[i], DiffStatus::Removed)),
+        })
+        .collect()
+}
+
+/// Writes `frame` after `head` and its subframes below it, each prefixed
+/// with `indent`.
+fn print_frame(out: &mut String, frame: &FrameDiff, head: &str, indent: &str) {
+    let marker = match frame.status {
+        DiffStatus::Added => '+',
+        DiffStatus::Removed => '-',
+        DiffStatus::Matched => ' ',
+    };
+    writeln!(out, "{marker} {head}{}", frame_line(frame)).expect("writing to a string");
+    let count = frame.children.len();
+    for (i, child) in frame.children.iter().enumerate() {
+        let last = i + 1 == count;
+        let head = format!("{indent}{}", if last { "└─ " } else { "├─ " });
+        let indent = format!("{indent}{}", if last { "   " } else { "│  " });
+        print_frame(out, child, &head, &indent);
+    }
+}
+
+/// Returns what a frame ran: `new`, the precompile's name, or the selector
+/// in hex, `fallback` without one.
+pub(crate) fn function_label(frame: &FrameDiff) -> String {
+    match frame.kind {
+        CallKind::Create
+        | CallKind::Create2
+        | CallKind::EofCreate { .. }
+        | CallKind::EmptyCreate { .. } => "new".to_string(),
+        CallKind::Precompile { name, .. } => name.to_string(),
+        _ => frame
+            .selector
+            .map(selector_hex)
+            .unwrap_or_else(|| "fallback".to_string()),
+    }
+}
+
+fn frame_line(frame: &FrameDiff) -> String {
+    let function = function_label(frame);
+    let gas = match (frame.gas_before, frame.gas_after) {
+        (Some(before), Some(after)) if before != after => {
+            format!("{before} → {after} ({:+})", after as i64 - before as i64)
+        }
+        (_, Some(gas)) | (Some(gas), None) => gas.to_string(),
+        (None, None) => String::new(),
+    };
+    let outcome = match (frame.success_before, frame.success_after) {
+        (Some(before), Some(after)) if before != after => {
+            format!(" ({} → {})", outcome(before), outcome(after))
+        }
+        (_, Some(false)) | (Some(false), None) => " (failed)".to_string(),
+        _ => String::new(),
+    };
+    format!(
+        "{} {}::{function} [{gas}]{outcome}",
+        geth_type(frame.kind),
+        frame.target
+    )
+}
+
+fn outcome(success: bool) -> &'static str {
+    if success {
+        "success"
+    } else {
+        "failure"
+    }
+}
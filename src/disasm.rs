@@ -0,0 +1,168 @@
+//! Static disassembly of EVM bytecode.
+//!
+//! [`disassemble`] splits code into instructions without executing it, e.g.
+//! to inspect the init code of a [`CreateRecord`](crate::CreateRecord), and
+//! [`jumpdests`] lists the valid jump targets, which also tells whether a
+//! program counter breakpoint can ever be hit. [`instruction_indices`] maps
+//! program counters to the instruction indices that source maps count in.
+
+use std::{collections::BTreeSet, fmt, fmt::Write};
+
+use alloy_primitives::{hex, Bytes};
+use revm::interpreter::opcode;
+
+use crate::opcodes;
+
+/// A single instruction of disassembled code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// Offset of the instruction in the code
+    pub pc: usize,
+    /// The opcode byte
+    pub opcode: u8,
+    /// Operand of `PUSH1`–`PUSH32`, shorter than the push width if the code
+    /// ends first; empty for other opcodes
+    pub push_bytes: Bytes,
+}
+
+impl Instruction {
+    /// Returns true if the code ended before the full operand of a push.
+    pub fn is_truncated(&self) -> bool {
+        self.push_bytes.len() < push_width(self.opcode)
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Formats the instruction as e.g. `PUSH2 0x0102` or `JUMPDEST`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&opcodes::display_name(self.opcode))?;
+        if push_width(self.opcode) > 0 {
+            write!(f, " {}", hex::encode_prefixed(&self.push_bytes))?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits `code` into instructions.
+///
+/// Bytes that are not valid opcodes are kept as single-byte instructions.
+pub fn disassemble(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        let start = (pc + 1).min(code.len());
+        let end = (start + push_width(op)).min(code.len());
+        instructions.push(Instruction {
+            pc,
+            opcode: op,
+            push_bytes: Bytes::copy_from_slice(&code[start..end]),
+        });
+        pc = end;
+    }
+    instructions
+}
+
+/// Returns the offsets of the `JUMPDEST` instructions in `code`, leaving out
+/// `0x5b` bytes inside push operands.
+pub fn jumpdests(code: &[u8]) -> BTreeSet<usize> {
+    disassemble(code)
+        .into_iter()
+        .filter(|instruction| instruction.opcode == opcode::JUMPDEST)
+        .map(|instruction| instruction.pc)
+        .collect()
+}
+
+/// Returns, for every offset in `code`, the index of the instruction
+/// starting there, or `None` for the operand bytes of pushes.
+pub fn instruction_indices(code: &[u8]) -> Vec<Option<usize>> {
+    let mut indices = vec![None; code.len()];
+    for (index, instruction) in disassemble(code).iter().enumerate() {
+        indices[instruction.pc] = Some(index);
+    }
+    indices
+}
+
+/// Renders `code` one instruction per line, prefixed with its offset:
+///
+/// ```text
+/// 0000 PUSH2 0x0102
+/// 0003 JUMPDEST
+/// ```
+pub fn pretty_print(code: &[u8]) -> String {
+    let mut out = String::new();
+    for instruction in disassemble(code) {
+        writeln!(out, "{:04x} {instruction}", instruction.pc).expect("writing to a string");
+    }
+    out
+}
+
+/// Returns the operand size of a `PUSH1`–`PUSH32`, 0 for other opcodes.
+fn push_width(op: u8) -> usize {
+    if (opcode::PUSH1..=opcode::PUSH32).contains(&op) {
+        (op - opcode::PUSH0) as usize
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_push_width() {
+        let mut code = Vec::new();
+        for width in 1..=32u8 {
+            code.push(opcode::PUSH0 + width);
+            code.extend(1..=width);
+        }
+        code.push(opcode::STOP);
+
+        let instructions = disassemble(&code);
+        assert_eq!(instructions.len(), 33);
+        let mut pc = 0;
+        for (instruction, width) in instructions.iter().zip(1..=32u8) {
+            assert_eq!(instruction.pc, pc);
+            assert_eq!(instruction.opcode, opcode::PUSH0 + width);
+            assert_eq!(instruction.push_bytes, (1..=width).collect::<Vec<_>>());
+            assert!(!instruction.is_truncated());
+            pc += 1 + width as usize;
+        }
+        assert_eq!(instructions[32].pc, pc);
+        assert_eq!(instructions[32].opcode, opcode::STOP);
+        assert_eq!(instructions[1].to_string(), "PUSH2 0x0102");
+    }
+
+    #[test]
+    fn test_truncated_push_at_end() {
+        // PUSH0, PUSH4 with two of its four bytes
+        let instructions = disassemble(&[opcode::PUSH0, opcode::PUSH4, 0xaa, 0xbb]);
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0].to_string(), "PUSH0");
+        assert!(!instructions[0].is_truncated());
+        assert_eq!(instructions[1].pc, 1);
+        assert_eq!(instructions[1].push_bytes, [0xaa, 0xbb][..]);
+        assert!(instructions[1].is_truncated());
+        assert_eq!(instructions[1].to_string(), "PUSH4 0xaabb");
+
+        let instructions = disassemble(&[opcode::PUSH32]);
+        assert!(instructions[0].push_bytes.is_empty());
+        assert_eq!(instructions[0].to_string(), "PUSH32 0x");
+    }
+
+    #[test]
+    fn test_jumpdests_skip_push_data() {
+        // JUMPDEST, PUSH1 0x5b, JUMPDEST, invalid 0x0c, JUMPDEST
+        let code = [0x5b, 0x60, 0x5b, 0x5b, 0x0c, 0x5b];
+        assert_eq!(jumpdests(&code), BTreeSet::from([0, 3, 5]));
+        assert_eq!(
+            instruction_indices(&code),
+            vec![Some(0), Some(1), None, Some(2), Some(3), Some(4)]
+        );
+        assert_eq!(
+            pretty_print(&code),
+            "0000 JUMPDEST\n0001 PUSH1 0x5b\n0003 JUMPDEST\n0004 0x0c\n0005 JUMPDEST\n"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Graphviz DOT export of the call graph.
+//!
+//! Unlike the call tree, the graph has one node per address and one edge per
+//! pair of addresses that called each other, annotated with the number of
+//! calls, the gas they used and the value they transferred. Reverted calls
+//! get their own dashed red edge, so failures stand out.
+
+use std::fmt::Write as _;
+
+use alloy_primitives::{Address, U256};
+
+use crate::{
+    call_trace::{CallKind, CallTrace, CallTraceNode},
+    Labels,
+};
+
+/// Settings for [`CallTrace::to_dot`].
+#[derive(Debug, Clone, Default)]
+pub struct DotOptions {
+    /// Names shown with addresses, e.g. `WETH (0xC02a…)`
+    pub labels: Labels,
+    /// Draw every precompile as a single `precompiles` node instead of one
+    /// node per precompile named after it
+    pub collapse_precompiles: bool,
+    /// Keep only the nodes that received the most gas, dropping the edges of
+    /// the others; every node is kept if `None`
+    pub max_nodes: Option<usize>,
+}
+
+/// What a graph node stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKey {
+    Address(Address),
+    Precompiles,
+}
+
+#[derive(Debug)]
+struct Node {
+    key: NodeKey,
+    label: String,
+    /// Gas used by the calls into the node
+    gas: u64,
+}
+
+#[derive(Debug, Default)]
+struct Edge {
+    calls: u64,
+    gas: u64,
+    value: U256,
+}
+
+#[derive(Debug, Default)]
+struct Graph {
+    nodes: Vec<Node>,
+    /// Edges by source, target and whether the calls reverted, in the order
+    /// they were first seen
+    edges: Vec<((usize, usize, bool), Edge)>,
+}
+
+impl Graph {
+    fn node(&mut self, key: NodeKey, label: impl FnOnce() -> String) -> usize {
+        match self.nodes.iter().position(|node| node.key == key) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(Node {
+                    key,
+                    label: label(),
+                    gas: 0,
+                });
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn add_call(&mut self, from: usize, to: usize, node: &CallTraceNode) {
+        self.nodes[to].gas += node.gas_used;
+        let key = (from, to, !node.success);
+        let index = match self.edges.iter().position(|(edge, _)| *edge == key) {
+            Some(index) => index,
+            None => {
+                self.edges.push((key, Edge::default()));
+                self.edges.len() - 1
+            }
+        };
+        let edge = &mut self.edges[index].1;
+        edge.calls += 1;
+        edge.gas += node.gas_used;
+        edge.value += node.value;
+    }
+}
+
+impl CallTrace {
+    /// Renders the call graph as a DOT digraph, e.g. for `dot -Tsvg`.
+    pub fn to_dot(&self, opts: DotOptions) -> String {
+        let mut graph = Graph::default();
+        if let Some(root) = self.root() {
+            let sender = graph.node(NodeKey::Address(root.caller), || label(&opts, root.caller));
+            add_frame(&mut graph, &opts, sender, root);
+        }
+        let mut kept = vec![true; graph.nodes.len()];
+        if let Some(max) = opts.max_nodes {
+            let mut ranked: Vec<usize> = (0..graph.nodes.len()).collect();
+            ranked.sort_by_key(|index| std::cmp::Reverse(graph.nodes[*index].gas));
+            for index in ranked.into_iter().skip(max) {
+                kept[index] = false;
+            }
+        }
+
+        let mut out = String::from("digraph calls {\n    node [shape=box];\n");
+        for (index, node) in graph.nodes.iter().enumerate().filter(|(index, _)| kept[*index]) {
+            let _ = writeln!(out, "    n{index} [label=\"{}\"];", escape(&node.label));
+        }
+        for ((from, to, reverted), edge) in &graph.edges {
+            if !kept[*from] || !kept[*to] {
+                continue;
+            }
+            let plural = if edge.calls == 1 { "" } else { "s" };
+            let mut label = format!("{} call{plural}\\ngas {}", edge.calls, edge.gas);
+            if !edge.value.is_zero() {
+                let _ = write!(label, "\\nvalue {}", edge.value);
+            }
+            let style = if *reverted {
+                ", style=dashed, color=red"
+            } else {
+                ""
+            };
+            let _ = writeln!(out, "    n{from} -> n{to} [label=\"{label}\"{style}];");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn add_frame(graph: &mut Graph, opts: &DotOptions, from: usize, node: &CallTraceNode) {
+    let to = match node.kind {
+        CallKind::Precompile { .. } if opts.collapse_precompiles => {
+            graph.node(NodeKey::Precompiles, || "precompiles".to_string())
+        }
+        CallKind::Precompile { name, .. } => graph.node(NodeKey::Address(node.target), || {
+            opts.labels.label(&node.target).unwrap_or_else(|| name.to_string())
+        }),
+        _ => graph.node(NodeKey::Address(node.target), || label(opts, node.target)),
+    };
+    graph.add_call(from, to, node);
+    for child in &node.children {
+        add_frame(graph, opts, to, child);
+    }
+}
+
+fn label(opts: &DotOptions, address: Address) -> String {
+    opts.labels
+        .label(&address)
+        .unwrap_or_else(|| address.to_string())
+}
+
+/// Escapes a string for a quoted DOT label.
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,100 @@
+//! EIP-7702 authorizations and delegated accounts.
+//!
+//! A transaction with an authorization list sets the code of each authority
+//! to a delegation designator, `0xef0100` followed by the delegate address.
+//! Calls to the authority then run the code of the delegate in the
+//! authority's context. [`AuthorizationRecord`] keeps the authorizations of
+//! a transaction and [`delegate_of`] reads the delegate from a designator.
+
+use alloy_primitives::{Address, U256};
+use revm::{
+    primitives::{Bytecode, TxEnv},
+    Database, EvmContext,
+};
+use serde::{Deserialize, Serialize};
+
+/// Bytes every delegation designator starts with.
+pub const DELEGATION_DESIGNATOR: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// An authorization tuple of a transaction's authorization list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthorizationRecord {
+    /// Account that signed the authorization, `None` if the signature could
+    /// not be recovered
+    pub authority: Option<Address>,
+    /// Address whose code the authority delegates to
+    pub delegate: Address,
+    /// Nonce the authority must have for the authorization to apply
+    pub nonce: u64,
+    /// Chain the authorization is valid on, 0 for every chain
+    pub chain_id: U256,
+    /// Index of the transaction the authorization belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns the authorizations of `tx`, recovering their authorities.
+pub(crate) fn authorizations(tx: &TxEnv, tx_index: u64) -> Vec<AuthorizationRecord> {
+    let Some(list) = &tx.authorization_list else {
+        return Vec::new();
+    };
+    list.recovered_iter()
+        .map(|authorization| AuthorizationRecord {
+            authority: authorization.authority(),
+            delegate: authorization.address,
+            nonce: authorization.nonce,
+            chain_id: authorization.chain_id,
+            tx_index,
+        })
+        .collect()
+}
+
+/// Returns the delegate of `code` if it is a delegation designator.
+pub fn delegate_of(code: &[u8]) -> Option<Address> {
+    let delegate = code.strip_prefix(&DELEGATION_DESIGNATOR)?;
+    (delegate.len() == Address::len_bytes()).then(|| Address::from_slice(delegate))
+}
+
+/// Returns the delegate of `address` if its code is a delegation designator.
+///
+/// The account is looked up in the journal, where revm has usually loaded it
+/// already, and otherwise read from the database without loading it into the
+/// journal, so warm and cold access costs are unaffected.
+pub(crate) fn resolve_delegate<DB: Database>(
+    context: &mut EvmContext<DB>,
+    address: Address,
+) -> Option<Address> {
+    if let Some(account) = context.journaled_state.state.get(&address) {
+        if let Some(code) = &account.info.code {
+            return designated(code);
+        }
+    }
+    let info = context.db.basic(address).ok()??;
+    let code = match info.code {
+        Some(code) => code,
+        None => context.db.code_by_hash(info.code_hash).ok()?,
+    };
+    designated(&code)
+}
+
+fn designated(code: &Bytecode) -> Option<Address> {
+    match code {
+        Bytecode::Eip7702(code) => Some(code.address()),
+        code => delegate_of(code.original_byte_slice()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delegate_of() {
+        let delegate = Address::repeat_byte(0xde);
+        let mut code = DELEGATION_DESIGNATOR.to_vec();
+        code.extend_from_slice(delegate.as_slice());
+        assert_eq!(delegate_of(&code), Some(delegate));
+        assert_eq!(delegate_of(&code[..22]), None);
+        assert_eq!(delegate_of(&[0x60, 0x00]), None);
+    }
+}
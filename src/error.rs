@@ -0,0 +1,138 @@
+//! The crate-wide error type.
+//!
+//! Plugin initialization, the plugin registry, file sinks, the
+//! [`ReportWriter`](crate::writer::ReportWriter) and
+//! [`transact_inspected`](crate::evm::transact_inspected) all return
+//! [`Error`], and the narrower errors they wrap convert into it, so `?` works
+//! across them.
+
+use std::{fmt, io, path::PathBuf};
+
+use revm::primitives::EVMError;
+
+use crate::{ConfigError, Format, TruncatedCollections};
+
+/// Error returned by the fallible operations of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// The configuration is invalid
+    Config(ConfigError),
+    /// A trace sink could not be opened
+    Sink {
+        /// Path of the sink
+        path: PathBuf,
+        /// Why it could not be opened
+        source: io::Error,
+    },
+    /// A report could not be written
+    Output {
+        /// File written, or `None` for stdout
+        path: Option<PathBuf>,
+        /// Why it could not be written
+        source: io::Error,
+    },
+    /// The output format renders a call tree but only a report was given
+    MissingCallTrace(Format),
+    /// The EVM failed to execute the transaction, as opposed to the
+    /// transaction reverting or halting
+    Evm(String),
+    /// The trace is incomplete because [`TraceLimits`](crate::TraceLimits)
+    /// caps were reached
+    LimitExceeded(TruncatedCollections),
+    /// A plugin with this name is already registered
+    PluginAlreadyRegistered(String),
+    /// No plugin with this name is registered
+    UnknownPlugin(String),
+    /// A third-party plugin failed to initialize
+    Plugin(Box<dyn std::error::Error + Send + Sync>),
+    /// A reloaded configuration changes an option, named here, that only
+    /// takes effect when the plugin is restarted
+    RequiresRestart(&'static str),
+    /// A report or call trace could not be saved or loaded
+    #[cfg(feature = "persist")]
+    Persist(crate::persist::PersistError),
+}
+
+impl Error {
+    /// Wraps any error of a plugin implementation.
+    pub fn plugin(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Error::Plugin(err.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(err) => write!(f, "invalid configuration: {err}"),
+            Error::Sink { path, source } => {
+                write!(f, "failed to open sink {}: {source}", path.display())
+            }
+            Error::Output { path: Some(path), source } => {
+                write!(f, "failed to write report to {}: {source}", path.display())
+            }
+            Error::Output { path: None, source } => {
+                write!(f, "failed to write report to stdout: {source}")
+            }
+            Error::MissingCallTrace(format) => {
+                write!(f, "the {format:?} output format needs a call trace")
+            }
+            Error::Evm(err) => write!(f, "transaction failed to execute: {err}"),
+            Error::LimitExceeded(truncated) => {
+                let names: Vec<_> = [
+                    (truncated.calls, "calls"),
+                    (truncated.logs, "logs"),
+                    (truncated.storage_accesses, "storage accesses"),
+                    (truncated.transient_accesses, "transient accesses"),
+                    (truncated.bytes, "payloads"),
+                    (truncated.preimages, "preimages"),
+                ]
+                .into_iter()
+                .filter_map(|(cut, name)| cut.then_some(name))
+                .collect();
+                write!(f, "trace limits exceeded, truncated {}", names.join(", "))
+            }
+            Error::PluginAlreadyRegistered(name) => {
+                write!(f, "a plugin named `{name}` is already registered")
+            }
+            Error::UnknownPlugin(name) => write!(f, "no plugin named `{name}` is registered"),
+            Error::Plugin(err) => write!(f, "plugin failed to initialize: {err}"),
+            Error::RequiresRestart(option) => {
+                write!(f, "changing `{option}` requires restarting the plugin")
+            }
+            #[cfg(feature = "persist")]
+            Error::Persist(err) => write!(f, "failed to save or load: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Config(err) => Some(err),
+            Error::Sink { source, .. } | Error::Output { source, .. } => Some(source),
+            Error::Plugin(err) => Some(err.as_ref()),
+            #[cfg(feature = "persist")]
+            Error::Persist(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::Config(err)
+    }
+}
+
+#[cfg(feature = "persist")]
+impl From<crate::persist::PersistError> for Error {
+    fn from(err: crate::persist::PersistError) -> Self {
+        Error::Persist(err)
+    }
+}
+
+impl<E: fmt::Display> From<EVMError<E>> for Error {
+    fn from(err: EVMError<E>) -> Self {
+        Error::Evm(err.to_string())
+    }
+}
@@ -0,0 +1,50 @@
+//! Building an EVM with an inspector attached.
+//!
+//! Passing an inspector to `Evm::builder().with_external_context(..)` only
+//! stores it; revm calls its hooks once `inspector_handle_register` is
+//! appended as well. [`build_inspected_evm`] does both, so the hooks cannot
+//! silently stay unwired.
+
+use std::fmt;
+
+use revm::{
+    inspector_handle_register,
+    primitives::{Env, ResultAndState},
+    Database, Evm, GetInspector,
+};
+
+use crate::Error;
+
+/// Builds an EVM that executes `env` against `db` and calls the hooks of
+/// `inspector`.
+///
+/// `inspector` is usually a `&mut` reference, so the inspector can be read
+/// once the EVM is dropped; the EVM's `context.external` gives access to it
+/// before then.
+pub fn build_inspected_evm<'a, DB, I>(db: DB, env: Env, inspector: I) -> Evm<'a, I, DB>
+where
+    DB: Database,
+    I: GetInspector<DB>,
+{
+    Evm::builder()
+        .with_db(db)
+        .with_env(Box::new(env))
+        .with_external_context(inspector)
+        .append_handler_register(inspector_handle_register)
+        .build()
+}
+
+/// Executes `env` against `db` with `inspector` attached, without committing
+/// the resulting state.
+///
+/// A transaction that reverts or halts is still `Ok`; [`Error::Evm`] means
+/// it could not be executed at all, e.g. because the caller cannot pay for
+/// the gas.
+pub fn transact_inspected<DB, I>(db: DB, env: Env, inspector: I) -> Result<ResultAndState, Error>
+where
+    DB: Database,
+    DB::Error: fmt::Display,
+    I: GetInspector<DB>,
+{
+    Ok(build_inspected_evm(db, env, inspector).transact()?)
+}
@@ -0,0 +1,101 @@
+//! Contracts whose code ran.
+//!
+//! Every frame that runs code is attributed to the account its code was
+//! loaded from and the hash of that code, so the same address is listed
+//! twice if its code changed, and a `DELEGATECALL` counts for the library
+//! rather than the caller. Creation frames run init code and are listed
+//! under the new address with the hash of the init code. Steps are counted
+//! for the frame that executed them, excluding its subcalls.
+
+use std::collections::HashMap;
+
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use revm::interpreter::Interpreter;
+use serde::{Deserialize, Serialize};
+
+/// Code that ran, and where it was loaded from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutedContract {
+    /// Account the code was loaded from
+    pub address: Address,
+    /// Keccak-256 hash of the code
+    pub code_hash: B256,
+    /// The code, with `capture_code`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Index of the transaction that first ran the code
+    pub first_tx_index: u64,
+    /// Number of frames that ran the code
+    pub frames: u64,
+    /// Interpreter steps executed in the code, excluding subcalls
+    pub steps: u64,
+}
+
+/// The contracts that ran, in the order they first ran.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExecutedContracts {
+    contracts: Vec<ExecutedContract>,
+    index: HashMap<(Address, B256), usize>,
+}
+
+impl ExecutedContracts {
+    /// Counts a frame running the code of `interp`, returning the index to
+    /// attribute the frame's steps to.
+    pub(crate) fn enter(
+        &mut self,
+        interp: &Interpreter,
+        tx_index: u64,
+        capture_code: bool,
+    ) -> usize {
+        let contract = &interp.contract;
+        let address = contract.bytecode_address.unwrap_or(contract.target_address);
+        // EOF code carries no hash
+        let code_hash = contract
+            .hash
+            .unwrap_or_else(|| keccak256(contract.bytecode.original_byte_slice()));
+        let index = *self.index.entry((address, code_hash)).or_insert_with(|| {
+            self.contracts.push(ExecutedContract {
+                address,
+                code_hash,
+                code: capture_code.then(|| contract.bytecode.original_bytes()),
+                first_tx_index: tx_index,
+                frames: 0,
+                steps: 0,
+            });
+            self.contracts.len() - 1
+        });
+        self.contracts[index].frames += 1;
+        index
+    }
+
+    /// Adds `steps` to the contract at `index`.
+    pub(crate) fn add_steps(&mut self, index: usize, steps: u64) {
+        if let Some(contract) = self.contracts.get_mut(index) {
+            contract.steps += steps;
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[ExecutedContract] {
+        &self.contracts
+    }
+}
+
+/// Adds `other` to `contracts`, summing the frames and steps of code both
+/// ran and keeping the first transaction index and code of `contracts`.
+pub(crate) fn merge(contracts: &mut Vec<ExecutedContract>, other: Vec<ExecutedContract>) {
+    for contract in other {
+        let existing = contracts.iter_mut().find(|existing| {
+            (existing.address, existing.code_hash) == (contract.address, contract.code_hash)
+        });
+        match existing {
+            Some(existing) => {
+                existing.frames += contract.frames;
+                existing.steps += contract.steps;
+                if existing.code.is_none() {
+                    existing.code = contract.code;
+                }
+            }
+            None => contracts.push(contract),
+        }
+    }
+}
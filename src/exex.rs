@@ -0,0 +1,265 @@
+//! Tracing live blocks from a reth Execution Extension (ExEx).
+//!
+//! [`RestdExEx`] consumes committed chain notifications, re-executes every
+//! transaction of each committed block with a [`HelloWorldInspector`]
+//! attached, and logs a [`BlockSummary`] per block. Summaries can also be
+//! received on a bounded channel; a slow consumer never stalls the node,
+//! summaries that do not fit are dropped and counted instead.
+//!
+//! The extension reads notifications through [`ChainNotifications`], so it
+//! does not depend on a particular reth version. An adapter for reth's
+//! `ExExContext` converts each committed block into a [`CommittedBlock`]
+//! with a database at the parent state and reports the finished height:
+//!
+//! ```ignore
+//! #[async_trait]
+//! impl<Node: FullNodeComponents> ChainNotifications for RethNotifications<Node> {
+//!     type Db = CacheDB<StateProviderDatabase<Box<dyn StateProvider>>>;
+//!
+//!     async fn next_notification(&mut self) -> Option<ChainNotification<Self::Db>> {
+//!         let notification = self.ctx.notifications.next().await?.ok()?;
+//!         // Convert `notification.committed_chain()` into `CommittedBlock`s
+//!     }
+//!
+//!     fn finished_height(&mut self, number: u64) {
+//!         let _ = self.ctx.events.send(ExExEvent::FinishedHeight(number));
+//!     }
+//! }
+//!
+//! builder.install_exex("restd", |ctx| install_exex(RethNotifications { ctx }))
+//! ```
+
+use std::future::Future;
+
+use alloy_primitives::Address;
+use async_trait::async_trait;
+use revm::{
+    primitives::{Env, TxEnv},
+    Database, DatabaseCommit,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{info, warn};
+
+use crate::{evm::build_inspected_evm, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+
+/// Number of most called contracts listed in a [`BlockSummary`].
+const TOP_CONTRACTS: usize = 5;
+
+/// Error type of the ExEx futures, convertible into reth's error type.
+pub type ExExError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A block to re-execute.
+#[derive(Debug)]
+pub struct CommittedBlock<DB> {
+    /// Block number
+    pub number: u64,
+    /// Configuration and block environment; the transaction part is ignored
+    pub env: Env,
+    /// Transactions of the block, in order
+    pub transactions: Vec<TxEnv>,
+    /// State at the parent block, updated as transactions are re-executed
+    pub db: DB,
+}
+
+/// A change of the canonical chain.
+#[derive(Debug)]
+pub enum ChainNotification<DB> {
+    /// Blocks were added to the canonical chain
+    Committed(Vec<CommittedBlock<DB>>),
+    /// Blocks from this number on were removed from the canonical chain
+    Reverted {
+        /// First removed block
+        first_block: u64,
+    },
+}
+
+/// Source of chain notifications, e.g. an adapter around reth's `ExExContext`.
+#[async_trait]
+pub trait ChainNotifications: Send {
+    /// Database the committed blocks are re-executed against
+    type Db: Database + DatabaseCommit + Send;
+
+    /// Waits for the next notification, or returns `None` once the node shuts down.
+    async fn next_notification(&mut self) -> Option<ChainNotification<Self::Db>>;
+
+    /// Reports that every block up to `number` has been processed.
+    fn finished_height(&mut self, number: u64) {
+        let _ = number;
+    }
+}
+
+#[async_trait]
+impl<DB: Database + DatabaseCommit + Send> ChainNotifications
+    for mpsc::Receiver<ChainNotification<DB>>
+{
+    type Db = DB;
+
+    async fn next_notification(&mut self) -> Option<ChainNotification<DB>> {
+        self.recv().await
+    }
+}
+
+/// What the inspector saw while re-executing one block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSummary {
+    /// Block number
+    pub number: u64,
+    /// Number of transactions in the block
+    pub tx_count: usize,
+    /// Transactions that could not be re-executed
+    pub failed_txs: usize,
+    /// Steps executed by all transactions
+    pub total_steps: u64,
+    /// Calls made by all transactions
+    pub total_calls: u64,
+    /// Most called contracts and their call counts, busiest first
+    pub top_contracts: Vec<(Address, u64)>,
+}
+
+/// Re-executes the transactions of `block` with an inspector using `config`.
+pub fn trace_block<DB: Database + DatabaseCommit>(
+    block: CommittedBlock<DB>,
+    config: &HelloWorldInspectorConfig,
+) -> BlockSummary {
+    let CommittedBlock {
+        number,
+        env,
+        transactions,
+        mut db,
+    } = block;
+    let mut inspector = HelloWorldInspector::with_config(config.clone());
+    let mut failed_txs = 0;
+    for tx in &transactions {
+        let tx_env = Env {
+            cfg: env.cfg.clone(),
+            block: env.block.clone(),
+            tx: tx.clone(),
+        };
+        let mut evm = build_inspected_evm(&mut db, tx_env, &mut inspector);
+        if evm.transact_commit().is_err() {
+            failed_txs += 1;
+        }
+    }
+    BlockSummary {
+        number,
+        tx_count: transactions.len(),
+        failed_txs,
+        total_steps: inspector.steps(),
+        total_calls: inspector.calls(),
+        top_contracts: inspector
+            .busiest_addresses(TOP_CONTRACTS)
+            .into_iter()
+            .map(|(address, stats)| (address, stats.calls))
+            .collect(),
+    }
+}
+
+/// Execution Extension that traces every committed block.
+#[derive(Debug)]
+pub struct RestdExEx<C> {
+    /// Source of chain notifications
+    notifications: C,
+    /// Configuration of the per-block inspectors
+    config: HelloWorldInspectorConfig,
+    /// Receives a summary per block, if anyone is listening
+    summaries: Option<mpsc::Sender<BlockSummary>>,
+    /// Summaries dropped because the channel was full
+    dropped_summaries: u64,
+}
+
+impl<C: ChainNotifications> RestdExEx<C> {
+    /// Creates an extension whose inspectors only collect, without output.
+    pub fn new(notifications: C) -> Self {
+        Self::with_config(
+            notifications,
+            HelloWorldInspectorConfig {
+                output: OutputMode::Silent,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Creates an extension whose inspectors use `config`.
+    pub fn with_config(notifications: C, config: HelloWorldInspectorConfig) -> Self {
+        Self {
+            notifications,
+            config,
+            summaries: None,
+            dropped_summaries: 0,
+        }
+    }
+
+    /// Also sends every summary to a channel holding up to `capacity`
+    /// summaries, and returns its receiver.
+    pub fn summary_channel(&mut self, capacity: usize) -> mpsc::Receiver<BlockSummary> {
+        let (tx, rx) = mpsc::channel(capacity);
+        self.summaries = Some(tx);
+        rx
+    }
+
+    /// Returns how many summaries were dropped because the channel was full.
+    pub fn dropped_summaries(&self) -> u64 {
+        self.dropped_summaries
+    }
+
+    /// Returns the notification source.
+    pub fn notifications(&self) -> &C {
+        &self.notifications
+    }
+
+    /// Processes notifications until the node shuts down.
+    pub async fn run(&mut self) -> Result<(), ExExError> {
+        while let Some(notification) = self.notifications.next_notification().await {
+            match notification {
+                ChainNotification::Committed(blocks) => {
+                    let Some(last) = blocks.last().map(|block| block.number) else {
+                        continue;
+                    };
+                    for block in blocks {
+                        let summary = trace_block(block, &self.config);
+                        self.publish(summary);
+                    }
+                    self.notifications.finished_height(last);
+                }
+                ChainNotification::Reverted { first_block } => {
+                    info!(target: "restd::exex", first_block, "blocks reverted");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Logs `summary` and offers it to the summary channel without waiting.
+    fn publish(&mut self, summary: BlockSummary) {
+        info!(
+            target: "restd::exex",
+            block = summary.number,
+            txs = summary.tx_count,
+            failed = summary.failed_txs,
+            steps = summary.total_steps,
+            calls = summary.total_calls,
+            top_contracts = ?summary.top_contracts,
+            "block traced"
+        );
+        let Some(summaries) = &self.summaries else {
+            return;
+        };
+        match summaries.try_send(summary) {
+            Ok(()) => {}
+            Err(TrySendError::Full(summary)) => {
+                self.dropped_summaries += 1;
+                warn!(target: "restd::exex", block = summary.number, "summary channel full, dropping summary");
+            }
+            Err(TrySendError::Closed(_)) => self.summaries = None,
+        }
+    }
+}
+
+/// Entry point for `NodeBuilder::install_exex`: returns the future that
+/// traces committed blocks until the node shuts down.
+pub async fn install_exex<C: ChainNotifications>(
+    notifications: C,
+) -> Result<impl Future<Output = Result<(), ExExError>>, ExExError> {
+    Ok(async move { RestdExEx::new(notifications).run().await })
+}
@@ -0,0 +1,86 @@
+//! Tracing a call and exporting the report as JSON in one step.
+//!
+//! [`trace_call_json`] only exchanges byte slices and strings with its
+//! caller and touches neither stdout nor the file system, so it is the entry
+//! point for wasm32 builds, which enable just the `trace-core` feature.
+
+use std::fmt;
+
+use alloy_primitives::{keccak256, Address, Bytes, TxKind};
+use revm::{
+    primitives::{AccountInfo, Bytecode, Env},
+    InMemoryDB,
+};
+
+use crate::{evm::build_inspected_evm, ConfigError, HelloWorldInspector, HelloWorldInspectorConfig};
+
+/// Address the code is deployed at by [`trace_call_json`].
+pub const TARGET: Address = Address::repeat_byte(0xc0);
+
+/// Sender of the call made by [`trace_call_json`].
+pub const CALLER: Address = Address::repeat_byte(0xca);
+
+/// Gas limit of the call made by [`trace_call_json`].
+pub const GAS_LIMIT: u64 = 30_000_000;
+
+/// Error returned by [`trace_call_json`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    /// The configuration is invalid
+    Config(ConfigError),
+    /// The EVM rejected the transaction before executing it
+    Evm(String),
+    /// The report could not be serialized
+    Json(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Config(err) => write!(f, "invalid configuration: {err}"),
+            ExportError::Evm(err) => write!(f, "transaction failed to execute: {err}"),
+            ExportError::Json(err) => write!(f, "failed to serialize report: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<ConfigError> for ExportError {
+    fn from(err: ConfigError) -> Self {
+        ExportError::Config(err)
+    }
+}
+
+/// Calls `code`, deployed at [`TARGET`] in an otherwise empty state, from
+/// [`CALLER`] with `calldata`, and returns the
+/// [`InspectorReport`](crate::InspectorReport) of an inspector built from
+/// `config` as JSON.
+///
+/// Reverts and halts are part of the report, not errors.
+pub fn trace_call_json(
+    code: &[u8],
+    calldata: &[u8],
+    config: HelloWorldInspectorConfig,
+) -> Result<String, ExportError> {
+    config.validate()?;
+    let mut db = InMemoryDB::default();
+    let bytecode = Bytecode::new_raw(Bytes::copy_from_slice(code));
+    let info = AccountInfo::new(Default::default(), 1, keccak256(code), bytecode);
+    db.insert_account_info(TARGET, info);
+
+    let mut env = Env::default();
+    env.tx.caller = CALLER;
+    env.tx.gas_limit = GAS_LIMIT;
+    env.tx.transact_to = TxKind::Call(TARGET);
+    env.tx.data = Bytes::copy_from_slice(calldata);
+
+    let mut inspector = HelloWorldInspector::with_config(config);
+    build_inspected_evm(&mut db, env, &mut inspector)
+        .transact()
+        .map_err(|err| ExportError::Evm(err.to_string()))?;
+    inspector
+        .report()
+        .to_json()
+        .map_err(|err| ExportError::Json(err.to_string()))
+}
@@ -0,0 +1,201 @@
+//! Why frames failed.
+//!
+//! Every call and create frame that reverts or halts is classified into a
+//! [`FailureKind`] from its instruction result. The report keeps one
+//! [`FailureStats`] per kind, counting the frames and keeping the context of
+//! the first one, which is usually the one worth looking at. Frames that a
+//! [`FaultInjector`](crate::FaultInjector) forced to fail are counted
+//! separately in [`FailureStats::injected`] too.
+
+use std::fmt;
+
+use alloy_primitives::Address;
+use revm::interpreter::InstructionResult;
+use serde::{Deserialize, Serialize};
+
+use crate::{gas_alarm, SourceLocation};
+
+/// Why a frame failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The frame executed `REVERT`
+    Revert,
+    /// The frame ran out of gas, including memory expansion
+    OutOfGas,
+    /// The stack grew past 1024 items
+    StackOverflow,
+    /// An instruction needed more items than the stack held
+    StackUnderflow,
+    /// An undefined opcode or `INVALID` (`0xfe`) was executed
+    InvalidOpcode,
+    /// `JUMP` or `JUMPI` targeted something other than a `JUMPDEST`
+    InvalidJump,
+    /// A creation targeted an address that already has code or a nonce
+    CreateCollision,
+    /// A static frame tried to change state
+    StaticStateChange,
+    /// Any other halt, named after revm's instruction result
+    Other(String),
+}
+
+impl FailureKind {
+    /// Classifies the instruction result a frame ended with, or returns
+    /// `None` if the frame succeeded.
+    pub fn classify(result: InstructionResult) -> Option<Self> {
+        if result.is_ok() {
+            return None;
+        }
+        Some(match result {
+            InstructionResult::Revert => FailureKind::Revert,
+            result if gas_alarm::is_out_of_gas(result) => FailureKind::OutOfGas,
+            InstructionResult::StackOverflow => FailureKind::StackOverflow,
+            InstructionResult::StackUnderflow => FailureKind::StackUnderflow,
+            InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => {
+                FailureKind::InvalidOpcode
+            }
+            InstructionResult::InvalidJump => FailureKind::InvalidJump,
+            InstructionResult::CreateCollision => FailureKind::CreateCollision,
+            InstructionResult::StateChangeDuringStaticCall
+            | InstructionResult::CallNotAllowedInsideStatic => FailureKind::StaticStateChange,
+            result => FailureKind::Other(format!("{result:?}")),
+        })
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureKind::Revert => write!(f, "revert"),
+            FailureKind::OutOfGas => write!(f, "out of gas"),
+            FailureKind::StackOverflow => write!(f, "stack overflow"),
+            FailureKind::StackUnderflow => write!(f, "stack underflow"),
+            FailureKind::InvalidOpcode => write!(f, "invalid opcode"),
+            FailureKind::InvalidJump => write!(f, "invalid jump"),
+            FailureKind::CreateCollision => write!(f, "create collision"),
+            FailureKind::StaticStateChange => write!(f, "state change in static call"),
+            FailureKind::Other(result) => write!(f, "{result}"),
+        }
+    }
+}
+
+/// Where a frame failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedFrame {
+    /// Called address; for creations the address the contract would have
+    /// been deployed at, or the creator if unknown
+    pub address: Address,
+    /// Program counter of the last instruction the frame executed, `None`
+    /// if it executed none or steps were not recorded
+    pub pc: Option<usize>,
+    /// Call depth of the frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Source location of `pc`, if the inspector has a source map of the
+    /// called code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_location: Option<SourceLocation>,
+    /// Index of the transaction the frame belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// The failed frames of one kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureStats {
+    /// Why the frames failed
+    pub kind: FailureKind,
+    /// Number of frames that failed this way
+    pub count: u64,
+    /// How many of `count` were forced to fail by a
+    /// [`FaultInjector`](crate::FaultInjector)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub injected: u64,
+    /// The first frame that failed this way
+    pub first: FailedFrame,
+}
+
+/// Counts a frame that failed with `kind` in `failures`, which are kept in
+/// the order each kind first occurred.
+pub(crate) fn record(
+    failures: &mut Vec<FailureStats>,
+    kind: FailureKind,
+    frame: FailedFrame,
+    injected: bool,
+) {
+    let injected = u64::from(injected);
+    match failures.iter_mut().find(|stats| stats.kind == kind) {
+        Some(stats) => {
+            stats.count += 1;
+            stats.injected += injected;
+        }
+        None => failures.push(FailureStats {
+            kind,
+            count: 1,
+            injected,
+            first: frame,
+        }),
+    }
+}
+
+/// Adds the counts of `other` to `failures`, keeping the first frame of
+/// `failures` for kinds both have.
+pub(crate) fn merge(failures: &mut Vec<FailureStats>, other: Vec<FailureStats>) {
+    for stats in other {
+        match failures.iter_mut().find(|existing| existing.kind == stats.kind) {
+            Some(existing) => {
+                existing.count += stats.count;
+                existing.injected += stats.injected;
+            }
+            None => failures.push(stats),
+        }
+    }
+}
+
+fn is_zero(count: &u64) -> bool {
+    *count == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_instruction_results() {
+        assert_eq!(FailureKind::classify(InstructionResult::Stop), None);
+        assert_eq!(
+            FailureKind::classify(InstructionResult::MemoryOOG),
+            Some(FailureKind::OutOfGas)
+        );
+        assert_eq!(
+            FailureKind::classify(InstructionResult::InvalidFEOpcode),
+            Some(FailureKind::InvalidOpcode)
+        );
+        assert_eq!(
+            FailureKind::classify(InstructionResult::CallTooDeep),
+            Some(FailureKind::Other("CallTooDeep".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_merge_sums_counts_and_keeps_first_frame() {
+        let frame = |depth| FailedFrame {
+            address: Address::ZERO,
+            pc: None,
+            depth,
+            source_location: None,
+            tx_index: 0,
+        };
+        let mut failures = Vec::new();
+        record(&mut failures, FailureKind::Revert, frame(1), false);
+        record(&mut failures, FailureKind::Revert, frame(2), true);
+        let mut other = Vec::new();
+        record(&mut other, FailureKind::OutOfGas, frame(3), false);
+        record(&mut other, FailureKind::Revert, frame(4), true);
+        merge(&mut failures, other);
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].count, 3);
+        assert_eq!(failures[0].injected, 2);
+        assert_eq!(failures[0].first.depth, 1);
+        assert_eq!(failures[1].kind, FailureKind::OutOfGas);
+    }
+}
@@ -0,0 +1,195 @@
+//! Forcing calls to fail, for testing how contracts handle failures.
+//!
+//! A [`FaultInjector`] holds rules that each pick calls by their target,
+//! and optionally by selector and by how many matching calls came before in
+//! the transaction. When a call matches, the inspector returns the rule's
+//! [`Failure`] from its `call` hook instead of letting the call execute, so
+//! the caller sees the call fail. Every injection is recorded as an
+//! [`InjectedFault`] and counted in the failure statistics as injected, so
+//! reports tell injected failures from organic ones.
+
+use alloy_primitives::{Address, Bytes, Selector};
+use revm::interpreter::{CallInputs, CallOutcome, Gas, InstructionResult, InterpreterResult};
+use serde::{Deserialize, Serialize};
+
+use crate::selector;
+
+/// How an injected call fails.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    /// The call reverts with this data, returning all its gas
+    Revert(Bytes),
+    /// The call runs out of gas, spending all of it
+    OutOfGas,
+}
+
+/// A rule selecting the calls to fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultRule {
+    /// Called address
+    pub target: Address,
+    /// Only fail calls whose calldata starts with this selector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Selector>,
+    /// Only fail the nth matching call of each transaction, counting from
+    /// 1; every matching call fails if `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nth: Option<u32>,
+    /// How the call fails
+    pub with: Failure,
+}
+
+impl FaultRule {
+    /// Creates a rule failing every call to `target` with `with`.
+    pub fn fail_call(target: Address, with: Failure) -> Self {
+        Self {
+            target,
+            selector: None,
+            nth: None,
+            with,
+        }
+    }
+
+    /// Only fails calls to the function with `selector`.
+    pub fn selector(mut self, selector: impl Into<Selector>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    /// Only fails the `nth` matching call of each transaction, counting
+    /// from 1.
+    pub fn nth(mut self, nth: u32) -> Self {
+        self.nth = Some(nth);
+        self
+    }
+
+    /// Returns true if the rule's target and selector match the call.
+    fn matches(&self, inputs: &CallInputs) -> bool {
+        inputs.target_address == self.target
+            && self.selector.is_none_or(|expected| {
+                selector::selector_of(&inputs.input) == Some(expected.0)
+            })
+    }
+}
+
+/// The calls an inspector forces to fail.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaultInjector {
+    /// Rules checked in order; the first that fires decides the failure
+    pub rules: Vec<FaultRule>,
+}
+
+impl FaultInjector {
+    /// Creates an injector without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule.
+    pub fn fail_call(mut self, rule: FaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Returns true if no call is ever failed.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Counts the call in `matches`, which holds the number of calls each
+    /// rule matched so far in the transaction, and returns the index of
+    /// the first rule that fires for it.
+    pub(crate) fn check(&self, inputs: &CallInputs, matches: &mut Vec<u32>) -> Option<usize> {
+        matches.resize(self.rules.len(), 0);
+        let mut fired = None;
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !rule.matches(inputs) {
+                continue;
+            }
+            // Every matching rule counts the call, even after one fired
+            matches[index] += 1;
+            if fired.is_none() && rule.nth.is_none_or(|nth| nth == matches[index]) {
+                fired = Some(index);
+            }
+        }
+        fired
+    }
+}
+
+/// A call that was forced to fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InjectedFault {
+    /// Called address
+    pub target: Address,
+    /// First four bytes of the calldata, absent for calls with shorter input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Selector>,
+    /// Call depth of the frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the rule in [`FaultInjector::rules`] that fired
+    pub rule: usize,
+    /// How the call failed
+    pub failure: Failure,
+    /// Index of the transaction the call belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns the outcome of a call that fails with `failure` without running.
+pub(crate) fn outcome(failure: &Failure, inputs: &CallInputs) -> CallOutcome {
+    let result = match failure {
+        Failure::Revert(data) => InterpreterResult::new(
+            InstructionResult::Revert,
+            data.clone(),
+            Gas::new(inputs.gas_limit),
+        ),
+        Failure::OutOfGas => InterpreterResult::new(
+            InstructionResult::OutOfGas,
+            Bytes::new(),
+            Gas::new_spent(inputs.gas_limit),
+        ),
+    };
+    CallOutcome::new(result, inputs.return_memory_offset.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_parse_from_toml() {
+        let injector: FaultInjector = toml::from_str(
+            r#"
+            [[rules]]
+            target = "0x1111111111111111111111111111111111111111"
+            selector = "0xa9059cbb"
+            nth = 2
+            with = { revert = "0x08c379a0" }
+
+            [[rules]]
+            target = "0x2222222222222222222222222222222222222222"
+            with = "out_of_gas"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            injector,
+            FaultInjector::new()
+                .fail_call(
+                    FaultRule::fail_call(
+                        Address::repeat_byte(0x11),
+                        Failure::Revert(Bytes::from_static(&[0x08, 0xc3, 0x79, 0xa0]))
+                    )
+                    .selector([0xa9, 0x05, 0x9c, 0xbb])
+                    .nth(2)
+                )
+                .fail_call(FaultRule::fail_call(
+                    Address::repeat_byte(0x22),
+                    Failure::OutOfGas
+                ))
+        );
+    }
+}
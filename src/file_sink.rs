@@ -0,0 +1,97 @@
+//! Writing trace events to a file.
+//!
+//! Needs the `std` feature, since wasm32 targets have no file system.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{Error, TraceEvent, TraceSink};
+
+/// Sink that appends every event to a file as one JSON object per line.
+///
+/// Events use the same serde representation as the JSON report. Writes are
+/// buffered and flushed by [`flush`](Self::flush) and when the last clone is
+/// dropped. Write errors are collected instead of interrupting execution, see
+/// [`take_errors`](Self::take_errors).
+///
+/// Clones share the file, so a clone kept outside the inspector can flush it
+/// and inspect errors.
+#[derive(Debug, Clone)]
+pub struct FileTraceSink {
+    inner: Arc<Mutex<FileTraceSinkInner>>,
+}
+
+#[derive(Debug)]
+struct FileTraceSinkInner {
+    writer: BufWriter<File>,
+    errors: Vec<io::Error>,
+}
+
+impl FileTraceSink {
+    /// Opens `path` for appending, creating it if it does not exist.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|source| Error::Sink {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(FileTraceSinkInner {
+                writer: BufWriter::new(file),
+                errors: Vec::new(),
+            })),
+        })
+    }
+
+    /// Writes all buffered events to the file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.lock().writer.flush()
+    }
+
+    /// Returns the write errors encountered so far and clears them.
+    pub fn take_errors(&self) -> Vec<io::Error> {
+        std::mem::take(&mut self.lock().errors)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FileTraceSinkInner> {
+        // A panic while holding the lock leaves at worst a partial line
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl TraceSink for FileTraceSink {
+    fn send(&mut self, event: TraceEvent) -> bool {
+        let mut inner = self.lock();
+        let result = serde_json::to_writer(&mut inner.writer, &event)
+            .map_err(io::Error::from)
+            .and_then(|()| inner.writer.write_all(b"\n"));
+        if let Err(err) = result {
+            inner.errors.push(err);
+        }
+        true
+    }
+
+    /// Writes the alert and flushes the file, so readers see it before the
+    /// transaction finishes.
+    fn send_alert(&mut self, event: TraceEvent) -> bool {
+        self.send(event);
+        if let Err(err) = self.flush() {
+            self.lock().errors.push(err);
+        }
+        true
+    }
+}
+
+impl Drop for FileTraceSinkInner {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
@@ -0,0 +1,133 @@
+//! Address-based filtering of the traced frames.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConfigError, Labels};
+
+/// Which address of a call is compared against an [`AddressFilter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterMatch {
+    /// Match on the called address
+    #[default]
+    Target,
+    /// Match on the calling address
+    Caller,
+}
+
+/// Limits tracing to calls involving specific addresses.
+///
+/// A call is traced when its address is in `include` (or `include` is empty)
+/// and not in `exclude`. Addresses can also be given by their name in a
+/// [`Labels`] book, which the inspector resolves when it gets the labels;
+/// names it cannot resolve match no address.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AddressFilter {
+    /// Addresses to trace; empty means all addresses
+    pub include: Vec<Address>,
+    /// Addresses never to trace, takes precedence over `include`
+    pub exclude: Vec<Address>,
+    /// Which address of the call is matched
+    pub match_on: FilterMatch,
+    /// Labels of addresses to trace, added to `include` once resolved
+    pub include_labels: Vec<String>,
+    /// Labels of addresses never to trace, added to `exclude` once resolved
+    pub exclude_labels: Vec<String>,
+}
+
+impl AddressFilter {
+    /// Returns true if the filter lets every call through.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty()
+            && self.exclude.is_empty()
+            && self.include_labels.is_empty()
+            && self.exclude_labels.is_empty()
+    }
+
+    /// Returns true if `address` passes the filter.
+    pub fn allows(&self, address: &Address) -> bool {
+        let includes_all = self.include.is_empty() && self.include_labels.is_empty();
+        (includes_all || self.include.contains(address)) && !self.exclude.contains(address)
+    }
+
+    /// Iterates over the labels the filter names.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.include_labels
+            .iter()
+            .chain(&self.exclude_labels)
+            .map(String::as_str)
+    }
+
+    /// Adds the addresses of `include_labels` and `exclude_labels` to
+    /// `include` and `exclude`.
+    ///
+    /// Every label `labels` defines is resolved; the first one it does not
+    /// define is returned as an error.
+    pub fn resolve_labels(&mut self, labels: &Labels) -> Result<(), ConfigError> {
+        let mut unknown = None;
+        for (names, addresses) in [
+            (&self.include_labels, &mut self.include),
+            (&self.exclude_labels, &mut self.exclude),
+        ] {
+            for name in names {
+                match labels.address_of(name) {
+                    Some(address) if !addresses.contains(&address) => addresses.push(address),
+                    Some(_) => {}
+                    None => {
+                        unknown.get_or_insert_with(|| ConfigError::UnknownLabel(name.clone()));
+                    }
+                }
+            }
+        }
+        unknown.map_or(Ok(()), Err)
+    }
+
+    /// Returns true if a call from `caller` to `target` passes the filter.
+    pub fn matches(&self, caller: &Address, target: &Address) -> bool {
+        match self.match_on {
+            FilterMatch::Target => self.allows(target),
+            FilterMatch::Caller => self.allows(caller),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Address = Address::repeat_byte(0x0a);
+    const B: Address = Address::repeat_byte(0x0b);
+    const C: Address = Address::repeat_byte(0x0c);
+
+    #[test]
+    fn test_empty_filter_allows_everything() {
+        let filter = AddressFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&A, &B));
+    }
+
+    #[test]
+    fn test_exclude_takes_precedence_over_include() {
+        let filter = AddressFilter {
+            include: vec![A, B],
+            exclude: vec![B],
+            ..Default::default()
+        };
+        assert!(filter.allows(&A));
+        assert!(!filter.allows(&B));
+        assert!(!filter.allows(&C));
+    }
+
+    #[test]
+    fn test_match_on_caller() {
+        let filter = AddressFilter {
+            include: vec![A],
+            match_on: FilterMatch::Caller,
+            ..Default::default()
+        };
+        assert!(filter.matches(&A, &C));
+        assert!(!filter.matches(&C, &A));
+    }
+}
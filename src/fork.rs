@@ -0,0 +1,143 @@
+//! Tracing one transaction against several variants of the same prestate.
+//!
+//! [`ForkRunner`] takes a base database and named variants of it, e.g. with
+//! a storage slot or balance changed, runs a transaction against each with
+//! a fresh inspector and reports whether its outcome depends on the change.
+
+use alloy_primitives::B256;
+use revm::{
+    db::WrapDatabaseRef,
+    primitives::{Env, ExecutionResult},
+    InMemoryDB,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{evm, Error, HelloWorldInspectorConfig, TraceSession, TxReport};
+
+/// How the runs of a transaction differ across variants.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkComparison {
+    /// Names of the variants in which the transaction succeeded
+    pub succeeded: Vec<String>,
+    /// Names of the variants in which the transaction reverted or halted
+    pub failed: Vec<String>,
+    /// Whether the top-level frame used a different amount of gas in some
+    /// variant
+    pub gas_differs: bool,
+    /// Whether some variant emitted different logs
+    pub logs_differ: bool,
+}
+
+impl ForkComparison {
+    /// Returns whether the transaction succeeded in some variants and failed
+    /// in others.
+    pub fn success_differs(&self) -> bool {
+        !self.succeeded.is_empty() && !self.failed.is_empty()
+    }
+
+    /// Returns whether the outcome, gas or logs differ across variants.
+    pub fn diverged(&self) -> bool {
+        self.success_differs() || self.gas_differs || self.logs_differ
+    }
+}
+
+/// The reports of one transaction run against every variant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkReport {
+    /// Report of each variant with its name, in the order the variants were
+    /// added. The reports have a zero `tx_hash` and the position of their
+    /// variant as `index`.
+    pub variants: Vec<(String, TxReport)>,
+    /// How the variants differ
+    pub comparison: ForkComparison,
+}
+
+impl ForkReport {
+    /// Returns the report of the variant called `name`.
+    pub fn variant(&self, name: &str) -> Option<&TxReport> {
+        self.variants
+            .iter()
+            .find(|(variant, _)| variant == name)
+            .map(|(_, report)| report)
+    }
+}
+
+/// Runs a transaction against named variants of a base database.
+///
+/// Each variant is a full copy of the base database, made when it is added:
+/// `InMemoryDB` keeps its accounts and code in plain maps, so there is no
+/// sharing the parts a mutator leaves untouched. Runs read the variants
+/// through [`WrapDatabaseRef`] without committing, so running again copies
+/// nothing.
+#[derive(Debug, Clone, Default)]
+pub struct ForkRunner {
+    /// Database the variants are copied from
+    base: InMemoryDB,
+    /// Named variants, in the order they were added
+    variants: Vec<(String, InMemoryDB)>,
+}
+
+impl ForkRunner {
+    /// Creates a runner whose variants start from `base_db`.
+    pub fn new(base_db: InMemoryDB) -> Self {
+        Self {
+            base: base_db,
+            variants: Vec::new(),
+        }
+    }
+
+    /// Adds a variant called `name`: a copy of the base database changed by
+    /// `mutator`. A variant that should match the base takes a mutator that
+    /// does nothing.
+    pub fn add_variant(&mut self, name: impl Into<String>, mutator: impl FnOnce(&mut InMemoryDB)) {
+        let mut db = self.base.clone();
+        mutator(&mut db);
+        self.variants.push((name.into(), db));
+    }
+
+    /// Returns the database the variants are copied from.
+    pub fn base(&self) -> &InMemoryDB {
+        &self.base
+    }
+
+    /// Returns the names of the variants, in the order they were added.
+    pub fn variant_names(&self) -> impl Iterator<Item = &str> {
+        self.variants.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Runs the transaction in `tx_env` against every variant with an
+    /// inspector built from `config`, reset between variants, and compares
+    /// the runs. The variants are left unchanged.
+    ///
+    /// Fails with the first [`Error::Evm`] of a variant the transaction
+    /// could not be executed against.
+    pub fn run(&self, tx_env: Env, config: HelloWorldInspectorConfig) -> Result<ForkReport, Error> {
+        let mut session = TraceSession::with_config(config);
+        let mut fork = ForkReport::default();
+        let mut first: Option<TxReport> = None;
+        for (name, db) in &self.variants {
+            session.begin_tx(B256::ZERO);
+            let outcome = evm::transact_inspected(
+                WrapDatabaseRef(db),
+                tx_env.clone(),
+                session.inspector_mut(),
+            );
+            let tx = session.end_tx();
+            let result = outcome?.result;
+            if matches!(result, ExecutionResult::Success { .. }) {
+                fork.comparison.succeeded.push(name.clone());
+            } else {
+                fork.comparison.failed.push(name.clone());
+            }
+            match &first {
+                Some(first) => {
+                    fork.comparison.gas_differs |= tx.gas_used != first.gas_used;
+                    fork.comparison.logs_differ |= tx.report.logs != first.report.logs;
+                }
+                None => first = Some(tx.clone()),
+            }
+            fork.variants.push((name.clone(), tx));
+        }
+        Ok(fork)
+    }
+}
@@ -0,0 +1,67 @@
+//! Frames running low on gas.
+//!
+//! With [`low_gas_percent`](crate::HelloWorldInspectorConfig::low_gas_percent)
+//! set, the first instruction after which a frame's remaining gas is below
+//! that share of its limit is recorded as a [`LowGasAlarm`], which shows
+//! transactions that barely made it and subcalls starved by the 63/64 rule.
+//! Frames that halt out of gas are recorded as [`OutOfGasFrame`]s.
+
+use alloy_primitives::Address;
+use revm::interpreter::InstructionResult;
+use serde::{Deserialize, Serialize};
+
+/// The instruction that left a frame with less gas than the threshold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowGasAlarm {
+    /// Contract executing the instruction; under `DELEGATECALL` this is the
+    /// calling proxy
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// The instruction's opcode
+    pub opcode: u8,
+    /// Call depth of the frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Gas limit of the frame
+    pub gas_limit: u64,
+    /// Gas left in the frame after the instruction, not counting gas it
+    /// forwarded to a subcall
+    pub gas_remaining: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// A frame that halted because it ran out of gas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutOfGasFrame {
+    /// Called address; for creations the address the contract would have
+    /// been deployed at, or the creator if unknown
+    pub address: Address,
+    /// Call depth of the frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Gas limit of the frame
+    pub gas_limit: u64,
+    /// Which out-of-gas halt ended the frame, e.g. `OutOfGas` or `MemoryOOG`
+    pub reason: String,
+    /// Index of the transaction the frame belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns true if `result` is one of the out-of-gas halts.
+pub(crate) fn is_out_of_gas(result: InstructionResult) -> bool {
+    matches!(
+        result,
+        InstructionResult::OutOfGas
+            | InstructionResult::MemoryOOG
+            | InstructionResult::MemoryLimitOOG
+            | InstructionResult::PrecompileOOG
+            | InstructionResult::InvalidOperandOOG
+    )
+}
+
+/// Returns true if `remaining` is below `percent` percent of `limit`.
+pub(crate) fn is_below(remaining: u64, limit: u64, percent: u64) -> bool {
+    (remaining as u128) * 100 < (limit as u128) * (percent as u128)
+}
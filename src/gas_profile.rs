@@ -0,0 +1,145 @@
+//! Gas attribution per opcode and opcode category.
+
+use std::{collections::BTreeMap, fmt};
+
+use revm::interpreter::opcode;
+use serde::{Deserialize, Serialize};
+
+use crate::{histogram::OpcodeHistogram, opcodes};
+
+/// Broad group of opcodes that gas is rolled up into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasCategory {
+    /// Arithmetic, comparison and bitwise operations
+    Arithmetic,
+    /// Memory reads and writes, including copies into memory
+    Memory,
+    /// Persistent and transient storage
+    Storage,
+    /// Calls and contract creations
+    Call,
+    /// `LOG0` to `LOG4`
+    Log,
+    /// Everything else
+    Other,
+}
+
+impl GasCategory {
+    /// Returns the category of `op`.
+    pub fn of(op: u8) -> Self {
+        match op {
+            opcode::ADD..=opcode::SIGNEXTEND | opcode::LT..=opcode::SAR => GasCategory::Arithmetic,
+            opcode::MLOAD
+            | opcode::MSTORE
+            | opcode::MSTORE8
+            | opcode::MSIZE
+            | opcode::MCOPY
+            | opcode::CALLDATACOPY
+            | opcode::CODECOPY
+            | opcode::EXTCODECOPY
+            | opcode::RETURNDATACOPY => GasCategory::Memory,
+            opcode::SLOAD | opcode::SSTORE | opcode::TLOAD | opcode::TSTORE => GasCategory::Storage,
+            opcode::CALL
+            | opcode::CALLCODE
+            | opcode::DELEGATECALL
+            | opcode::STATICCALL
+            | opcode::CREATE
+            | opcode::CREATE2 => GasCategory::Call,
+            opcode::LOG0..=opcode::LOG4 => GasCategory::Log,
+            _ => GasCategory::Other,
+        }
+    }
+}
+
+impl fmt::Display for GasCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GasCategory::Arithmetic => "arithmetic",
+            GasCategory::Memory => "memory",
+            GasCategory::Storage => "storage",
+            GasCategory::Call => "call",
+            GasCategory::Log => "log",
+            GasCategory::Other => "other",
+        };
+        f.pad(name)
+    }
+}
+
+/// Gas charged by executed instructions, per opcode and per category.
+///
+/// The `Display` impl prints both tables, most expensive first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasProfile {
+    /// Gas per executed opcode, keyed by opcode name
+    pub by_opcode: BTreeMap<String, u64>,
+    /// Gas per opcode category
+    pub by_category: BTreeMap<GasCategory, u64>,
+}
+
+impl GasProfile {
+    /// Builds a profile from gas totals accumulated per opcode byte.
+    pub(crate) fn from_histogram(gas: &OpcodeHistogram) -> Self {
+        let mut profile = Self::default();
+        for (op, amount) in gas.iter() {
+            profile.by_opcode.insert(opcodes::display_name(op), amount);
+            *profile.by_category.entry(GasCategory::of(op)).or_default() += amount;
+        }
+        profile
+    }
+
+    /// Returns the gas charged by instructions in `category`.
+    pub fn category(&self, category: GasCategory) -> u64 {
+        self.by_category.get(&category).copied().unwrap_or_default()
+    }
+
+    /// Returns the gas charged by all instructions.
+    pub fn total(&self) -> u64 {
+        self.by_category.values().sum()
+    }
+}
+
+/// Sorts entries by gas, most expensive first, breaking ties by key.
+fn sorted<K: Ord + Clone>(entries: &BTreeMap<K, u64>) -> Vec<(K, u64)> {
+    let mut entries: Vec<(K, u64)> = entries.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    entries
+}
+
+impl fmt::Display for GasProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<14} {:>12}", "opcode", "gas")?;
+        for (name, gas) in sorted(&self.by_opcode) {
+            writeln!(f, "{name:<14} {gas:>12}")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<14} {:>12}", "category", "gas")?;
+        for (category, gas) in sorted(&self.by_category) {
+            writeln!(f, "{category:<14} {gas:>12}")?;
+        }
+        write!(f, "{:<14} {:>12}", "total", self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_sorts_by_gas() {
+        let mut gas = OpcodeHistogram::default();
+        gas.add(opcode::ADD, 3);
+        gas.add(opcode::SSTORE, 20_000);
+        gas.add(opcode::MSTORE, 6);
+        let profile = GasProfile::from_histogram(&gas);
+
+        assert_eq!(profile.category(GasCategory::Storage), 20_000);
+        assert_eq!(profile.total(), 20_009);
+        let table = profile.to_string();
+        let rows: Vec<&str> = table.lines().map(|line| line.split_whitespace().next().unwrap_or("")).collect();
+        assert_eq!(
+            rows,
+            ["opcode", "SSTORE", "MSTORE", "ADD", "", "category", "storage", "memory", "arithmetic", "total"]
+        );
+    }
+}
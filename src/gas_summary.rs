@@ -0,0 +1,94 @@
+//! End-of-transaction gas accounting.
+
+use alloy_primitives::U256;
+use revm::{
+    interpreter::{gas::validate_initial_tx_gas, Gas},
+    primitives::SpecId,
+};
+use serde::{Deserialize, Serialize};
+
+/// How the gas of a transaction was spent and refunded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSummary {
+    /// Gas limit of the transaction
+    pub gas_limit: u64,
+    /// Gas used before the refund, intrinsic gas included
+    pub gas_used: u64,
+    /// Intrinsic gas estimated from the calldata, ignoring access lists and
+    /// authorizations
+    pub intrinsic_gas: u64,
+    /// Gas spent by the top-level frame
+    pub execution_gas: u64,
+    /// Zero bytes of calldata, or of init code for creations
+    pub calldata_zero_bytes: u64,
+    /// Non-zero bytes of calldata, or of init code for creations
+    pub calldata_nonzero_bytes: u64,
+    /// Refund accumulated during execution, before the cap; 0 if the
+    /// transaction failed
+    pub gas_refunded: u64,
+    /// Largest refund allowed, a fraction of `gas_used` set by the hardfork
+    pub refund_cap: u64,
+    /// Gas paid for after the capped refund
+    pub effective_gas_used: u64,
+    /// Blobs carried by an EIP-4844 transaction
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub blob_count: u64,
+    /// Most the sender pays per unit of blob gas, set for blob transactions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_blob_gas: Option<U256>,
+}
+
+impl GasSummary {
+    /// Builds the summary of a transaction with `gas_limit` and `input` from
+    /// the final gas of its top-level frame, capping the refund as `spec`
+    /// does. The blob fields are left empty.
+    pub fn new(
+        gas_limit: u64,
+        input: &[u8],
+        is_create: bool,
+        frame_gas: &Gas,
+        success: bool,
+        spec: SpecId,
+    ) -> Self {
+        let calldata_zero_bytes = input.iter().filter(|byte| **byte == 0).count() as u64;
+        let gas_used = gas_limit.saturating_sub(frame_gas.remaining());
+        let gas_refunded = if success {
+            frame_gas.refunded().max(0) as u64
+        } else {
+            0
+        };
+        let refund_cap = gas_used / refund_quotient(spec);
+        Self {
+            gas_limit,
+            gas_used,
+            intrinsic_gas: validate_initial_tx_gas(spec, input, is_create, &[], 0),
+            execution_gas: frame_gas.spent(),
+            calldata_zero_bytes,
+            calldata_nonzero_bytes: input.len() as u64 - calldata_zero_bytes,
+            gas_refunded,
+            refund_cap,
+            effective_gas_used: gas_used - gas_refunded.min(refund_cap),
+            blob_count: 0,
+            max_fee_per_blob_gas: None,
+        }
+    }
+
+    /// Returns the refund applied after the cap.
+    pub fn effective_refund(&self) -> u64 {
+        self.gas_refunded.min(self.refund_cap)
+    }
+}
+
+fn is_zero(count: &u64) -> bool {
+    *count == 0
+}
+
+/// Returns the divisor of the gas used that bounds the refund: 5 since
+/// London (EIP-3529), 2 before.
+pub fn refund_quotient(spec: SpecId) -> u64 {
+    if spec.is_enabled_in(SpecId::LONDON) {
+        5
+    } else {
+        2
+    }
+}
@@ -0,0 +1,169 @@
+//! Geth `callTracer` compatible output.
+//!
+//! Converts a [`CallTrace`] into the JSON shape produced by
+//! `debug_traceTransaction` with `{"tracer": "callTracer"}`.
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{Revert, SolError};
+use revm::interpreter::InstructionResult;
+use serde::{Deserialize, Serialize};
+
+use crate::call_trace::{CallKind, CallTrace, CallTraceNode};
+
+/// A single frame in Geth's `callTracer` format.
+///
+/// Fields are declared in the order Geth emits them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethCallFrame {
+    /// Address that initiated the frame
+    pub from: Address,
+    /// Gas made available to the frame
+    #[serde(with = "hex_u64")]
+    pub gas: u64,
+    /// Gas consumed by the frame
+    #[serde(with = "hex_u64")]
+    pub gas_used: u64,
+    /// Called or created address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Calldata or init code
+    pub input: Bytes,
+    /// Return or revert data, omitted when empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    /// Geth error string for failed frames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Decoded revert reason for reverted frames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Subcalls in execution order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<GethCallFrame>,
+    /// Value transferred, omitted for `STATICCALL` and `DELEGATECALL`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Frame type, e.g. `CALL` or `CREATE2`
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl CallTrace {
+    /// Converts the trace into Geth's `callTracer` format.
+    ///
+    /// Returns `None` if no transaction was traced. Gas values of the
+    /// top-level frame describe the execution frame and therefore exclude
+    /// intrinsic gas.
+    pub fn to_geth_call_trace(&self) -> Option<GethCallFrame> {
+        self.root().map(GethCallFrame::from)
+    }
+}
+
+impl From<&CallTraceNode> for GethCallFrame {
+    fn from(node: &CallTraceNode) -> Self {
+        let failed = !node.success;
+        let reverted = node.result == InstructionResult::Revert;
+        Self {
+            from: node.caller,
+            gas: node.gas_limit,
+            gas_used: node.gas_used,
+            to: (node.target != Address::ZERO || !node.kind.is_create()).then_some(node.target),
+            input: node.input.clone(),
+            output: (!node.output.is_empty()).then(|| node.output.clone()),
+            error: failed.then(|| geth_error(node.result)),
+            revert_reason: reverted.then(|| revert_reason(&node.output)).flatten(),
+            calls: node.children.iter().map(GethCallFrame::from).collect(),
+            value: match node.kind.base() {
+                CallKind::StaticCall | CallKind::DelegateCall => None,
+                _ => Some(node.value),
+            },
+            kind: geth_type(node.kind).to_string(),
+        }
+    }
+}
+
+/// Returns the Geth frame type for a call kind.
+pub fn geth_type(kind: CallKind) -> &'static str {
+    match kind {
+        CallKind::Call => "CALL",
+        CallKind::StaticCall => "STATICCALL",
+        CallKind::DelegateCall => "DELEGATECALL",
+        CallKind::CallCode => "CALLCODE",
+        CallKind::Create => "CREATE",
+        CallKind::Create2 => "CREATE2",
+        CallKind::EofCreate { .. } => "EOFCREATE",
+        CallKind::SelfDestruct => "SELFDESTRUCT",
+        CallKind::Precompile { .. } | CallKind::Transfer { .. } | CallKind::EmptyCreate { .. } => {
+            geth_type(kind.base())
+        }
+    }
+}
+
+/// Returns the error string Geth reports for a failed frame.
+pub fn geth_error(result: InstructionResult) -> String {
+    use InstructionResult::*;
+    match result {
+        Revert => "execution reverted",
+        OutOfGas | MemoryOOG | MemoryLimitOOG | PrecompileOOG | InvalidOperandOOG => "out of gas",
+        OpcodeNotFound | InvalidFEOpcode | NotActivated => "invalid opcode",
+        InvalidJump => "invalid jump destination",
+        StackUnderflow => "stack underflow",
+        StackOverflow => "stack limit reached 1024",
+        StateChangeDuringStaticCall | CallNotAllowedInsideStatic => "write protection",
+        CallTooDeep => "max call depth exceeded",
+        OutOfFunds => "insufficient balance for transfer",
+        CreateCollision => "contract address collision",
+        CreateContractSizeLimit => "max code size exceeded",
+        CreateInitCodeSizeLimit => "max initcode size exceeded",
+        CreateContractStartingWithEF => "invalid code: must not begin with 0xef",
+        NonceOverflow => "nonce uint64 overflow",
+        OutOfOffset => "return data out of bounds",
+        other => return format!("{other:?}"),
+    }
+    .to_string()
+}
+
+/// Decodes an `Error(string)` payload into its message, as Geth's
+/// `revertReason` field does.
+fn revert_reason(output: &[u8]) -> Option<String> {
+    Revert::abi_decode(output, true).ok().map(|revert| revert.reason)
+}
+
+/// Serializes a `u64` as a `0x`-prefixed hex quantity, as Geth does.
+mod hex_u64 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:#x}"))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| D::Error::custom("expected 0x-prefixed quantity"))?;
+        u64::from_str_radix(digits, 16).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geth_error_strings() {
+        assert_eq!(geth_error(InstructionResult::Revert), "execution reverted");
+        assert_eq!(geth_error(InstructionResult::MemoryOOG), "out of gas");
+        assert_eq!(geth_error(InstructionResult::InvalidJump), "invalid jump destination");
+    }
+
+    #[test]
+    fn test_hex_quantities_round_trip() {
+        let json = r#"{"from":"0x0101010101010101010101010101010101010101","gas":"0x2710","gasUsed":"0x0","input":"0x","type":"STATICCALL"}"#;
+        let frame: GethCallFrame = serde_json::from_str(json).unwrap();
+        assert_eq!(frame.gas, 10_000);
+        assert_eq!(frame.to, None);
+        assert_eq!(serde_json::to_string(&frame).unwrap(), json);
+    }
+}
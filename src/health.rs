@@ -0,0 +1,164 @@
+//! Plugin lifecycle and health.
+//!
+//! A [`HelloWorldInspectorPlugin`](crate::HelloWorldInspectorPlugin) opens the
+//! file sink set in its configuration on `init`, hands it to every inspector
+//! it creates and flushes it on `on_shutdown`. Write failures do not stop
+//! execution; they turn the plugin's [`PluginHealth`] unhealthy so a node
+//! operator endpoint can surface them. Reports recorded with
+//! [`record_report`](crate::HelloWorldInspectorPlugin::record_report) are
+//! merged and written on `on_shutdown` when an output format is set. File
+//! sinks and writing reports need the `std` feature.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex, MutexGuard,
+};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::InspectorReport;
+#[cfg(feature = "std")]
+use crate::{FileTraceSink, TraceEvent, TraceSink};
+
+/// Health of a plugin.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginHealth {
+    /// Whether the plugin has not failed since it was initialized
+    pub healthy: bool,
+    /// The most recent failure, kept once the plugin is unhealthy
+    pub last_error: Option<String>,
+    /// Trace events written to the plugin's sink
+    pub events_processed: u64,
+}
+
+impl PluginHealth {
+    /// Health of a plugin that has not failed and processes no events.
+    pub fn healthy() -> Self {
+        Self {
+            healthy: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Totals of a plugin, returned when it shuts down.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginReport {
+    /// Inspectors created since the plugin was initialized
+    pub inspectors_created: u64,
+    /// Health after the sink was flushed
+    pub health: PluginHealth,
+}
+
+/// State shared by a plugin and the sinks of the inspectors it created.
+#[derive(Debug, Default)]
+pub(crate) struct PluginState {
+    #[cfg(feature = "std")]
+    sink: Mutex<Option<FileTraceSink>>,
+    last_error: Mutex<Option<String>>,
+    /// Reports recorded since the plugin was initialized, merged
+    report: Mutex<InspectorReport>,
+    events_processed: AtomicU64,
+    inspectors_created: AtomicU64,
+}
+
+impl PluginState {
+    #[cfg(feature = "std")]
+    pub(crate) fn set_sink(&self, sink: Option<FileTraceSink>) {
+        *lock(&self.sink) = sink;
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn take_sink(&self) -> Option<FileTraceSink> {
+        lock(&self.sink).take()
+    }
+
+    pub(crate) fn record_inspector(&self) {
+        self.inspectors_created.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inspectors_created(&self) -> u64 {
+        self.inspectors_created.load(Ordering::Relaxed)
+    }
+
+    /// Returns a sink feeding the shared file sink, if there is one.
+    #[cfg(feature = "std")]
+    pub(crate) fn inspector_sink(self: &Arc<Self>) -> Option<PluginSink> {
+        let sink = lock(&self.sink).clone()?;
+        Some(PluginSink {
+            sink,
+            state: self.clone(),
+        })
+    }
+
+    pub(crate) fn record_report(&self, report: InspectorReport) {
+        *lock(&self.report) += report;
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn take_report(&self) -> InspectorReport {
+        std::mem::take(&mut *lock(&self.report))
+    }
+
+    pub(crate) fn record_error(&self, error: impl ToString) {
+        *lock(&self.last_error) = Some(error.to_string());
+    }
+
+    pub(crate) fn health(&self) -> PluginHealth {
+        let last_error = lock(&self.last_error).clone();
+        PluginHealth {
+            healthy: last_error.is_none(),
+            last_error,
+            events_processed: self.events_processed.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn report(&self) -> PluginReport {
+        PluginReport {
+            inspectors_created: self.inspectors_created(),
+            health: self.health(),
+        }
+    }
+}
+
+/// Sink of a plugin-created inspector: writes to the plugin's file sink and
+/// records write errors in the plugin's health.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub(crate) struct PluginSink {
+    sink: FileTraceSink,
+    state: Arc<PluginState>,
+}
+
+#[cfg(feature = "std")]
+impl PluginSink {
+    fn record_result(&self) {
+        match self.sink.take_errors().pop() {
+            Some(err) => self.state.record_error(format!("failed to write trace event: {err}")),
+            None => {
+                self.state.events_processed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TraceSink for PluginSink {
+    fn send(&mut self, event: TraceEvent) -> bool {
+        self.sink.send(event);
+        self.record_result();
+        true
+    }
+
+    fn send_alert(&mut self, event: TraceEvent) -> bool {
+        self.sink.send_alert(event);
+        self.record_result();
+        true
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
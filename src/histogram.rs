@@ -0,0 +1,89 @@
+//! Opcode execution histogram.
+
+use revm::interpreter::OpCode;
+
+/// Number of times each opcode byte was executed.
+///
+/// Backed by a fixed array so that recording a step never allocates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcodeHistogram {
+    counts: [u64; 256],
+}
+
+impl Default for OpcodeHistogram {
+    fn default() -> Self {
+        Self { counts: [0; 256] }
+    }
+}
+
+impl OpcodeHistogram {
+    /// Records one execution of `op`.
+    #[inline]
+    pub fn record(&mut self, op: u8) {
+        self.add(op, 1);
+    }
+
+    /// Adds `amount` to the total of `op`, e.g. to accumulate gas rather
+    /// than executions.
+    #[inline]
+    pub fn add(&mut self, op: u8, amount: u64) {
+        self.counts[op as usize] += amount;
+    }
+
+    /// Returns how many times `op` was executed.
+    pub fn count(&self, op: u8) -> u64 {
+        self.counts[op as usize]
+    }
+
+    /// Returns the total number of recorded executions.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Iterates over `(opcode, count)` pairs for every executed opcode byte,
+    /// in ascending opcode order.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(op, &count)| (op as u8, count))
+    }
+
+    /// Returns the `n` most executed opcodes, most frequent first.
+    ///
+    /// Ties are broken by opcode value so the result is deterministic. Bytes
+    /// that are not valid opcodes are skipped.
+    pub fn top(&self, n: usize) -> Vec<(OpCode, u64)> {
+        let mut entries: Vec<(OpCode, u64)> = self
+            .iter()
+            .filter_map(|(op, count)| OpCode::new(op).map(|op| (op, count)))
+            .collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.get().cmp(&b.0.get())));
+        entries.truncate(n);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_sorts_by_count_then_opcode() {
+        let mut histogram = OpcodeHistogram::default();
+        for op in [0x60, 0x60, 0x60, 0x01, 0x01, 0x50, 0x50, 0x00, 0x0c] {
+            histogram.record(op);
+        }
+        let top: Vec<(u8, u64)> = histogram
+            .top(3)
+            .into_iter()
+            .map(|(op, count)| (op.get(), count))
+            .collect();
+        assert_eq!(top, vec![(0x60, 3), (0x01, 2), (0x50, 2)]);
+        // 0x0c is not a valid opcode but is still counted.
+        assert_eq!(histogram.count(0x0c), 1);
+        assert_eq!(histogram.total(), 9);
+        assert_eq!(histogram.top(10).len(), 4);
+    }
+}
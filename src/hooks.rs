@@ -0,0 +1,160 @@
+//! Closures attached to the hooks of [`HelloWorldInspector`].
+//!
+//! [`HelloWorldInspector::on_step`], [`on_call`], [`on_call_end`] and
+//! [`on_log`] register callbacks that run alongside the built-in collection,
+//! which saves writing a whole [`Inspector`](revm::Inspector) for a quick
+//! experiment. Callbacks of the same hook run in registration order.
+//!
+//! With [`catch_panics`](crate::HelloWorldInspectorConfig::catch_panics) set,
+//! a callback that panics is skipped for the rest of the transaction.
+//!
+//! [`HelloWorldInspector`]: crate::HelloWorldInspector
+//! [`HelloWorldInspector::on_step`]: crate::HelloWorldInspector::on_step
+//! [`on_call`]: crate::HelloWorldInspector::on_call
+//! [`on_call_end`]: crate::HelloWorldInspector::on_call_end
+//! [`on_log`]: crate::HelloWorldInspector::on_log
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use alloy_primitives::{Address, Bytes, U256};
+
+use crate::{panics::panic_message, CollectedLog};
+
+/// An instruction about to be executed.
+///
+/// Depths count from 1 for the top-level frame, as in the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepInfo {
+    /// Number of steps recorded so far, including this one
+    pub step: u64,
+    /// Program counter
+    pub pc: usize,
+    /// Opcode about to be executed
+    pub opcode: u8,
+    /// Address whose storage the frame uses
+    pub address: Address,
+    /// Gas remaining in the frame
+    pub gas_remaining: u64,
+    /// Depth of the executing frame
+    pub depth: u64,
+}
+
+/// A call frame about to start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallInfo {
+    /// Calling address
+    pub caller: Address,
+    /// Called address
+    pub target: Address,
+    /// Value sent with the call (or apparent value for delegate calls)
+    pub value: U256,
+    /// Calldata
+    pub input: Bytes,
+    /// Gas limit of the call
+    pub gas_limit: u64,
+    /// Depth of the new frame
+    pub depth: u64,
+}
+
+/// A call frame that ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEndInfo {
+    /// Called address
+    pub target: Address,
+    /// Whether the call succeeded
+    pub success: bool,
+    /// Gas spent by the call
+    pub gas_used: u64,
+    /// Return data, or revert data for failed calls
+    pub output: Bytes,
+    /// Depth of the ended frame
+    pub depth: u64,
+}
+
+/// A registered callback, skipped once it panicked until the next
+/// transaction.
+pub(crate) struct Callback<T> {
+    f: Box<dyn FnMut(&T) + Send>,
+    disabled: bool,
+}
+
+impl<T> Callback<T> {
+    pub(crate) fn new(f: impl FnMut(&T) + Send + 'static) -> Self {
+        Self {
+            f: Box::new(f),
+            disabled: false,
+        }
+    }
+}
+
+pub(crate) type Callbacks<T> = Vec<Callback<T>>;
+
+/// Callbacks registered per hook, in registration order.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    pub(crate) step: Callbacks<StepInfo>,
+    pub(crate) call: Callbacks<CallInfo>,
+    pub(crate) call_end: Callbacks<CallEndInfo>,
+    pub(crate) log: Callbacks<CollectedLog>,
+}
+
+impl Hooks {
+    /// Re-enables the callbacks disabled by a panic.
+    pub(crate) fn enable_all(&mut self) {
+        fn enable<T>(callbacks: &mut Callbacks<T>) {
+            for callback in callbacks {
+                callback.disabled = false;
+            }
+        }
+        enable(&mut self.step);
+        enable(&mut self.call);
+        enable(&mut self.call_end);
+        enable(&mut self.log);
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks")
+            .field("step", &self.step.len())
+            .field("call", &self.call.len())
+            .field("call_end", &self.call_end.len())
+            .field("log", &self.log.len())
+            .finish()
+    }
+}
+
+/// Runs every enabled callback in `callbacks` with the value built by
+/// `info`, which is only built if there is a callback.
+///
+/// With `catch_panics`, a callback that panics is disabled and its index
+/// and panic message are returned; otherwise the panic unwinds.
+pub(crate) fn run<T>(
+    callbacks: &mut Callbacks<T>,
+    catch_panics: bool,
+    info: impl FnOnce() -> T,
+) -> Vec<(usize, String)> {
+    let mut panics = Vec::new();
+    if callbacks.is_empty() {
+        return panics;
+    }
+    let info = info();
+    for (index, callback) in callbacks.iter_mut().enumerate() {
+        if callback.disabled {
+            continue;
+        }
+        if !catch_panics {
+            (callback.f)(&info);
+            continue;
+        }
+        let f = &mut callback.f;
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| f(&info))) {
+            callback.disabled = true;
+            panics.push((index, panic_message(payload.as_ref())));
+        }
+    }
+    panics
+}
@@ -0,0 +1,150 @@
+//! Human-readable names for addresses.
+//!
+//! A [`Labels`] book maps addresses to names such as `UniswapV2Router`.
+//! Attached to an inspector, a pretty-printed trace, a DOT graph or a CSV
+//! export, it shows labeled addresses as `UniswapV2Router (0x7a25…)`, and
+//! it resolves the `include_labels` and `exclude_labels` of an
+//! [`AddressFilter`](crate::AddressFilter). Share one book between the
+//! inspectors of a stack with an `Arc<Labels>`.
+
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::ConfigError;
+
+/// Names of addresses.
+///
+/// Files map addresses to names, as a JSON object or a TOML table:
+///
+/// ```
+/// use restd::Labels;
+///
+/// let labels = Labels::from_toml_str(
+///     r#""0x7a250d5630b4cf539739df2c5dacb4c659f2488d" = "UniswapV2Router""#,
+/// )
+/// .unwrap();
+/// let router = labels.address_of("UniswapV2Router").unwrap();
+/// assert_eq!(labels.label(&router).unwrap(), "UniswapV2Router (0x7a25…)");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Labels {
+    names: HashMap<Address, String>,
+}
+
+impl Labels {
+    /// Creates an empty address book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names `address`, returning its previous name.
+    pub fn insert(&mut self, address: Address, name: impl Into<String>) -> Option<String> {
+        self.names.insert(address, name.into())
+    }
+
+    /// Returns the name of `address`.
+    pub fn get(&self, address: &Address) -> Option<&str> {
+        self.names.get(address).map(String::as_str)
+    }
+
+    /// Returns the address named `name`. If several addresses share the
+    /// name, any of them is returned.
+    pub fn address_of(&self, name: &str) -> Option<Address> {
+        self.names
+            .iter()
+            .find(|(_, label)| *label == name)
+            .map(|(address, _)| *address)
+    }
+
+    /// Returns `name (0x1234…)` for a named address.
+    pub fn label(&self, address: &Address) -> Option<String> {
+        let name = self.get(address)?;
+        Some(format!("{name} ({}…)", &address.to_string()[..6]))
+    }
+
+    /// Returns the number of named addresses.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if no address is named.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates over the named addresses and their names.
+    pub fn iter(&self) -> impl Iterator<Item = (&Address, &str)> {
+        self.names
+            .iter()
+            .map(|(address, name)| (address, name.as_str()))
+    }
+
+    /// Parses a JSON object mapping addresses to names.
+    pub fn from_json_str(json: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(json).map_err(|err| ConfigError::Labels(err.to_string()))
+    }
+
+    /// Parses a TOML table mapping addresses to names.
+    pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+        toml::from_str(toml).map_err(|err| ConfigError::Labels(err.message().to_string()))
+    }
+
+    /// Reads labels from a `.toml` file, or from a JSON file with any other
+    /// extension.
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| ConfigError::Labels(format!("{}: {err}", path.display())))?;
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            Self::from_toml_str(&contents)
+        } else {
+            Self::from_json_str(&contents)
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<(Address, S)> for Labels {
+    fn from_iter<I: IntoIterator<Item = (Address, S)>>(iter: I) -> Self {
+        Self {
+            names: iter
+                .into_iter()
+                .map(|(address, name)| (address, name.into()))
+                .collect(),
+        }
+    }
+}
+
+impl<S: Into<String>, const N: usize> From<[(Address, S); N]> for Labels {
+    fn from(entries: [(Address, S); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_and_toml_agree() {
+        let json = Labels::from_json_str(
+            r#"{"0x7a250d5630b4cf539739df2c5dacb4c659f2488d": "UniswapV2Router"}"#,
+        )
+        .unwrap();
+        let toml = Labels::from_toml_str(
+            r#""0x7a250d5630b4cf539739df2c5dacb4c659f2488d" = "UniswapV2Router""#,
+        )
+        .unwrap();
+        assert_eq!(json, toml);
+        assert_eq!(json.len(), 1);
+        assert!(matches!(
+            Labels::from_json_str(r#"{"router": "UniswapV2Router"}"#),
+            Err(ConfigError::Labels(_))
+        ));
+    }
+}
@@ -3,13 +3,151 @@
 //! This library provides a basic implementation of the reth Inspector trait
 //! that prints "Hello, world!" messages during various EVM execution events.
 
-use alloy_primitives::{Address, Log, U256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    time::Instant,
+};
+
+use alloy_primitives::{keccak256, Address, Bytes, Log, Selector, B256, I256, U256};
 use revm::{
-    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome,
+        EOFCreateInputs,
+        InstructionResult, Interpreter, InterpreterAction, InterpreterResult, OpCode,
+    },
+    primitives::SpecId,
     EvmContext, Inspector, Database,
 };
+use tracing::{debug, info, trace, warn};
 
+use aggregate::{AggregateCounters, AggregateHandle, Totals};
+use calls::call_stipend;
+use revert::revert_reason;
+use hooks::{Callback, Callbacks, Hooks};
+use executed::ExecutedContracts;
+use limits::{has_room, ByteBudget};
+use metrics::InspectorMetrics;
+use panics::panic_message;
+use preimage::PreimageStore;
+use reload::SharedConfig;
+use step_plan::StepPlan;
+use storage::TransientWrites;
+use throttle::{hash_of, ConsoleThrottle, Suppressed};
+use unchecked_call::UncheckedCallDetector;
+
+/// Prints a line of [`OutputMode::Stdout`] output. Without the `std` feature,
+/// e.g. on wasm32 where nothing reads stdout, the line is logged instead.
+macro_rules! stdout_line {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "std")]
+        println!($($arg)*);
+        #[cfg(not(feature = "std"))]
+        info!(target: TRACING_TARGET, $($arg)*);
+    }};
+}
+
+pub mod aa;
+pub mod abi;
+pub mod access_list;
+pub mod aggregate;
+pub mod assertions;
+pub mod bench;
+pub mod blobs;
+pub mod breakpoint;
+pub mod builder;
+pub mod call_trace;
+pub mod calls;
+pub mod chrome;
+pub mod code_size;
+pub mod coverage;
+pub mod collector;
+pub mod config;
+pub mod creations;
+pub mod diff;
+pub mod disasm;
+pub mod dot;
+pub mod eip7702;
+pub mod error;
+pub mod evm;
+pub mod executed;
+#[cfg(feature = "trace-core")]
+pub mod export;
+#[cfg(feature = "reth")]
+pub mod exex;
+pub mod failures;
+pub mod fault;
+pub mod fork;
+#[cfg(feature = "std")]
+pub mod file_sink;
+pub mod filter;
+pub mod gas_alarm;
+pub mod gas_profile;
+pub mod gas_summary;
+pub mod health;
+pub mod geth;
+pub mod histogram;
+pub mod hooks;
+pub mod labels;
+pub mod limits;
+pub mod logs;
+pub mod memory;
+pub mod mock;
+mod metrics;
+pub mod opcode_alert;
+pub mod opcodes;
+pub mod origin;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod panics;
+#[cfg(feature = "persist")]
+pub mod persist;
 pub mod plugin;
+pub mod precompile;
+pub mod profile;
+pub mod query;
+mod preimage;
+pub mod pretty;
+pub mod reentrancy;
+pub mod redact;
+pub mod registry;
+pub mod reload;
+pub mod returndata;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod source_map;
+pub mod spec_awareness;
+mod step_plan;
+pub mod report;
+pub mod revert;
+pub mod sampling;
+pub mod selector;
+#[cfg(feature = "std")]
+pub mod selector_db;
+pub mod selfdestruct;
+pub mod session;
+pub mod state;
+pub mod state_diff;
+pub mod static_violation;
+pub mod shared;
+pub mod sink;
+pub mod stack;
+pub mod storage;
+pub mod strict;
+pub mod struct_log;
+mod throttle;
+pub mod timeline;
+pub mod timing;
+pub mod transfers;
+pub mod tx_summary;
+pub mod unchecked_call;
+#[cfg(target_arch = "wasm32")]
+mod wasm_smoke;
+pub mod writer;
+
+/// Target used for all `tracing` events emitted by the inspector hooks.
+const TRACING_TARGET: &str = "restd::inspector";
 
 /// A simple inspector that prints "Hello, world!" during EVM execution events.
 /// 
@@ -21,15 +159,387 @@ pub struct HelloWorldInspector {
     pub step_count: u64,
     /// Counter to track the number of calls made
     pub call_count: u64,
+    /// Number of EOF contract creations, from `EOFCREATE` or creation
+    /// transactions
+    pub eofcreate_count: u64,
+    /// Index of the transaction being executed, counting from 0
+    pub tx_index: u64,
+    /// Sequence number of the next call, log, storage access, transfer,
+    /// creation, revert or `SELFDESTRUCT` recorded
+    seq: u64,
+    /// Number of currently open call and create frames
+    current_depth: usize,
+    /// Largest `current_depth` reached so far
+    max_depth: usize,
+    /// Number of transactions whose top-level frame has started
+    tx_count: u64,
+    /// Gas spent by the top-level frames that have ended
+    gas_used: u64,
+    /// Gas limit, input and spec of the transaction being executed, until
+    /// its top-level frame ends
+    tx_start: Option<TxStart>,
+    /// Gas accounting of the last transaction whose top-level frame ended
+    gas_summary: Option<GasSummary>,
+    /// Wall-clock duration of every transaction, with `measure_time`
+    tx_timings: Vec<TxTiming>,
+    /// Code run by any frame, with the steps executed in it
+    executed_contracts: ExecutedContracts,
+    /// Whether steps were dropped because `max_steps` was reached
+    truncated: bool,
+    /// Collections that stopped growing because a `TraceLimits` cap was reached
+    truncated_collections: TruncatedCollections,
+    /// Estimate of the bytes retained, for `max_total_bytes`
+    bytes: ByteBudget,
+    /// Number of executions of each opcode
+    opcode_counts: OpcodeHistogram,
+    /// Gas charged by each opcode
+    opcode_gas: OpcodeHistogram,
+    /// Gas accounting for every call, in the order the calls started
+    call_gas: Vec<CallGasRecord>,
+    /// Indices into `call_gas` of the calls that have not ended yet, `None`
+    /// for calls not recorded because `max_call_records` was reached
+    open_calls: Vec<Option<usize>>,
+    /// Aggregated statistics per called address
+    call_stats: BTreeMap<Address, AddressStats>,
+    /// Number of calls per precompile address
+    precompile_calls: HashMap<Address, u64>,
+    /// Whether each open frame passed the address filter, innermost last
+    frame_filter: Vec<bool>,
+    /// Every reverted frame, in the order the reverts happened
+    reverts: Vec<RevertRecord>,
+    /// Every executed `SELFDESTRUCT`, in execution order
+    selfdestructs: Vec<SelfdestructRecord>,
+    /// Every `CREATE`, `CREATE2` and creation transaction, in the order the
+    /// creations started
+    creations: Vec<CreateRecord>,
+    /// Init code and deployed code near or over their size limits, in the
+    /// order the creations started and ended
+    code_size_findings: Vec<CodeSizeFinding>,
+    /// Every emitted log, in emission order
+    logs: Vec<CollectedLog>,
+    /// Every non-zero value transfer, in the order the transfers started
+    transfers: Vec<ValueTransfer>,
+    /// Every `SLOAD` and `SSTORE`, in execution order
+    storage_accesses: Vec<StorageAccess>,
+    /// Every `TLOAD` and `TSTORE`, in execution order
+    transient_accesses: Vec<StorageAccess>,
+    /// Transient writes of the current transaction not read since
+    transient_writes: TransientWrites,
+    /// Transient writes never read before their transaction ended
+    unused_transient_writes: Vec<StorageAccess>,
+    /// Inputs of the `KECCAK256` instructions, to explain derived storage slots
+    preimages: PreimageStore,
+    /// Every executed `ORIGIN`, in execution order
+    origin_usages: Vec<OriginUsage>,
+    /// EIP-7702 authorizations of every traced transaction
+    authorizations: Vec<AuthorizationRecord>,
+    /// Blobs of every traced blob transaction
+    blob_transactions: Vec<BlobTransaction>,
+    /// Every executed `BLOBHASH` and `BLOBBASEFEE`, in execution order
+    blob_opcodes: Vec<BlobOpcodeUse>,
+    /// Every executed opcode in `alert_on_opcodes`, in execution order
+    opcode_alerts: Vec<OpcodeAlert>,
+    /// Checks executed opcodes against a hardfork, if one was given
+    spec_awareness: Option<SpecAwareness>,
+    /// Every state change attempted in a static frame, in execution order
+    static_violations: Vec<StaticViolationAttempt>,
+    /// Per-step work enabled by the configuration and spec awareness
+    step_plan: StepPlan,
+    /// Follows the success flags of low-level calls
+    unchecked_call_detector: UncheckedCallDetector,
+    /// Every low-level call whose success flag was popped unchecked, in
+    /// execution order
+    unchecked_calls: Vec<UncheckedCallFinding>,
+    /// First instruction in each frame to leave it below `low_gas_percent`
+    low_gas_alarms: Vec<LowGasAlarm>,
+    /// Every frame that ran out of gas, in the order the frames ended
+    oog_frames: Vec<OutOfGasFrame>,
+    /// Failed frames per failure kind, in the order the kinds first occurred
+    failures: Vec<FailureStats>,
+    /// Calls forced to fail by `faults`, in execution order
+    injected_faults: Vec<InjectedFault>,
+    /// Number of calls each rule in `faults` matched in the current
+    /// transaction
+    fault_matches: Vec<u32>,
+    /// Memory use of the frames that used memory, in the order they ended
+    memory_frames: Vec<FrameMemory>,
+    /// Instructions that grew memory by more than `memory_expansion_threshold`
+    memory_expansions: Vec<MemoryExpansion>,
+    /// Most recent samples in [`ProfilingMode::Sampled`]
+    samples: SampleRing,
+    /// Whether the last step pushed to `origin_usages`, for `step_end` to
+    /// check the instruction that follows
+    pending_origin_usage: bool,
+    /// Kind of the access the last step pushed to `storage_accesses` or
+    /// `transient_accesses`, which `step_end` has to confirm
+    pending_storage_access: Option<StorageAccessKind>,
+    /// `TLOAD` or `TSTORE` of the last step, for `step_end` to apply to
+    /// `transient_writes` whether or not it was recorded
+    pending_transient: Option<StorageAccess>,
+    /// Opcode, program counter and remaining gas before the last recorded
+    /// step, for `step_end` to charge the instruction's gas to
+    pending_gas: Option<(u8, usize, u64)>,
+    /// Opcode of the last step if it started a subcall or creation that the
+    /// next frame belongs to
+    pending_frame_opcode: Option<u8>,
+    /// Gas argument of the last step if it was a call, for the call record
+    /// of the frame it starts
+    pending_call_gas: Option<u64>,
+    /// State of the collections above when each open frame started,
+    /// innermost last
+    frame_starts: Vec<FrameStart>,
+    /// Configuration controlling what is emitted and where
+    config: HelloWorldInspectorConfig,
+    /// Configuration of the plugin that created the inspector, and the
+    /// number of the one applied, to pick up reloads between transactions
+    shared_config: Option<(Arc<SharedConfig>, u64)>,
+    /// Rate limits and deduplicates the stdout and tracing output
+    console: ConsoleThrottle,
+    /// What to redact, built from the configuration
+    redaction: Redaction,
+    /// Resolves call selectors to function signatures
+    selector_resolver: Option<Arc<dyn SelectorResolver>>,
+    /// Decodes logs of known events
+    event_decoder: Option<Arc<EventDecoder>>,
+    /// Decodes the arguments of calls to known functions
+    fn_decoder: Option<Arc<FnDecoder>>,
+    /// Names of addresses, shown in the output and recorded with calls
+    labels: Option<Arc<Labels>>,
+    /// Functions whose calls are answered without executing them
+    mocks: Option<MockRegistry>,
+    /// Source maps to annotate reverts and failures with
+    source_maps: Option<Arc<SourceMapRegistry>>,
+    /// Instruction index of every program counter in the code of the
+    /// contracts with a source map
+    instruction_indices: HashMap<Address, Vec<Option<usize>>>,
+    /// Receives every event as it occurs, until it stops accepting them
+    sink: Option<Box<dyn TraceSink>>,
+    /// Callbacks registered with `on_step`, `on_call`, `on_call_end` and `on_log`
+    hooks: Hooks,
+    /// Panics caught with `catch_panics`, in the order they happened
+    internal_errors: Vec<InternalError>,
+    /// Inconsistencies found in the bookkeeping with `strict`, in the order
+    /// they were found
+    inconsistencies: Vec<InternalInconsistency>,
+    /// How often each `JUMPI` went each way, with `branch_coverage`
+    coverage: CoverageMap,
+    /// Code address and program counter of the `JUMPI` being executed,
+    /// until `step_end` sees where it went
+    pending_branch: Option<(Address, usize)>,
+    /// How often each instruction executed, with `pc_coverage`
+    pc_coverage: PcCoverage,
+    /// Whether `strict` found an inconsistency and the report cannot be
+    /// trusted
+    poisoned: bool,
+    /// Counters and histograms updated by the hooks, if the `metrics` feature is enabled
+    metrics: InspectorMetrics,
+    /// Counters of the plugin that created the inspector, which its totals
+    /// are added to whenever a transaction ends
+    aggregate: Option<AggregateHandle>,
+}
+
+/// What the gas summary needs from the start of a transaction.
+#[derive(Debug, Clone)]
+struct TxStart {
+    gas_limit: u64,
+    input: Bytes,
+    is_create: bool,
+    spec: SpecId,
+    blob_count: u64,
+    max_fee_per_blob_gas: Option<U256>,
+}
+
+/// Lengths of the per-frame collections when a frame started; everything
+/// after them was recorded inside the frame and is undone if it reverts.
+#[derive(Debug, Clone, Copy, Default)]
+struct FrameStart {
+    logs: usize,
+    transfers: usize,
+    storage_accesses: usize,
+    transient_accesses: usize,
+    creations: usize,
+    /// Steps counted when the frame started
+    steps: u64,
+    /// Whether the calldata and return data of the frame are redacted
+    redacted: bool,
+    /// Whether a [`LowGasAlarm`] was recorded for the frame
+    low_gas: bool,
+    /// Opcode that started the frame, `None` for the top-level frame
+    opcode: Option<u8>,
+    /// Index into `code_size_findings` of the frame's init code, for
+    /// `create_end` to fill in the created address
+    init_code_finding: Option<usize>,
+    /// When the frame started, with `measure_time`
+    started_at: Option<Instant>,
+    /// Sequence number of the next record when the frame started
+    seq: u64,
+    /// Program counter of the last recorded step of the frame
+    last_pc: Option<usize>,
+    /// Steps executed by subcalls of the frame
+    child_steps: u64,
+    /// Index into `executed_contracts` of the code the frame runs, `None`
+    /// until its interpreter starts and for frames without code
+    executed_contract: Option<usize>,
+    /// Size of the frame's memory after its last recorded step, in bytes
+    memory_bytes: usize,
+    /// Gas the frame paid to expand its memory
+    memory_gas: u64,
+    /// Whether `faults` forced the frame to fail
+    injected: bool,
+    /// Whether `mocks` answered the call instead of executing it
+    mocked: bool,
+    /// Address of the code the frame runs if `source_maps` has its map
+    mapped_code: Option<Address>,
 }
 
 impl HelloWorldInspector {
     /// Creates a new HelloWorldInspector instance.
     pub fn new() -> Self {
-        println!("Hello, world! Inspector initialized.");
+        stdout_line!("Hello, world! Inspector initialized.");
         Self::default()
     }
+
+    /// Returns a builder for configuring a new inspector.
+    pub fn builder() -> HelloWorldInspectorBuilder {
+        HelloWorldInspectorBuilder::new()
+    }
+
+    /// Creates a new HelloWorldInspector with the given configuration.
+    pub fn with_config(config: HelloWorldInspectorConfig) -> Self {
+        Self {
+            bytes: ByteBudget::new(config.limits.max_total_bytes),
+            preimages: PreimageStore::new(config.limits.max_preimage_bytes),
+            redaction: config.redaction(),
+            samples: match config.profiling {
+                ProfilingMode::Sampled { capacity, .. } => SampleRing::with_capacity(capacity),
+                ProfilingMode::Full | ProfilingMode::Counters => SampleRing::default(),
+            },
+            console: ConsoleThrottle::new(config.max_lines_per_second),
+            unchecked_call_detector: UncheckedCallDetector::new(config.unchecked_call_window),
+            step_plan: StepPlan::new(&config, false),
+            config,
+            ..Self::default()
+        }
+    }
     
+    /// Sets the resolver used to name the functions called.
+    pub fn with_selector_resolver(mut self, resolver: Arc<dyn SelectorResolver>) -> Self {
+        self.selector_resolver = Some(resolver);
+        self
+    }
+
+    /// Sets the decoder used to decode the logs of known events.
+    pub fn with_event_decoder(mut self, decoder: Arc<EventDecoder>) -> Self {
+        self.event_decoder = Some(decoder);
+        self
+    }
+
+    /// Sets the decoder used to decode the arguments of calls to known
+    /// functions.
+    pub fn with_fn_decoder(mut self, decoder: Arc<FnDecoder>) -> Self {
+        self.fn_decoder = Some(decoder);
+        self
+    }
+
+    /// Sets the names shown for addresses in console output and recorded
+    /// with calls, and resolves the labels of the address filter with them.
+    ///
+    /// Filter labels that `labels` does not define match no address; the
+    /// builder rejects them instead.
+    pub fn with_labels(mut self, labels: Arc<Labels>) -> Self {
+        // Unknown labels are left unresolved, which is what the filter expects
+        let _ = self.config.filter.resolve_labels(&labels);
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Returns the names of addresses, if set.
+    pub fn labels(&self) -> Option<&Arc<Labels>> {
+        self.labels.as_ref()
+    }
+
+    /// Annotates the reverts and failures of contracts with a source map in
+    /// `source_maps` with their source location. Locations need the last
+    /// program counter of the frame, so they are only found when every step
+    /// is recorded.
+    pub fn with_source_maps(mut self, source_maps: Arc<SourceMapRegistry>) -> Self {
+        self.source_maps = Some(source_maps);
+        self
+    }
+
+    /// Answers calls to the functions `mocks` knows with their return data
+    /// instead of executing them.
+    pub fn with_mocks(mut self, mocks: MockRegistry) -> Self {
+        self.mocks = Some(mocks);
+        self
+    }
+
+    /// Returns the mocked functions and how often each was called, if set.
+    pub fn mocks(&self) -> Option<&MockRegistry> {
+        self.mocks.as_ref()
+    }
+
+    /// Warns about executed opcodes that `spec` does not support, see
+    /// [`spec_warnings`](Self::spec_warnings).
+    pub fn with_spec_awareness(mut self, spec: SpecId) -> Self {
+        self.spec_awareness = Some(SpecAwareness::new(spec));
+        self.step_plan = StepPlan::new(&self.config, true);
+        self
+    }
+
+    /// Applies the configurations `shared` is reloaded with, starting from
+    /// the one numbered `version`.
+    pub(crate) fn with_shared_config(mut self, shared: Arc<SharedConfig>, version: u64) -> Self {
+        self.shared_config = Some((shared, version));
+        self
+    }
+
+    /// Updates the metric handles of the plugin that created the inspector
+    /// instead of registering its own.
+    pub(crate) fn with_metrics(mut self, metrics: InspectorMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Adds the totals of every transaction that ends to `counters`.
+    pub(crate) fn with_aggregate(mut self, counters: Arc<AggregateCounters>) -> Self {
+        self.aggregate = Some(AggregateHandle::new(counters));
+        self
+    }
+
+    /// Streams every event to `sink` as it occurs.
+    ///
+    /// Once the sink stops accepting events, e.g. because the receiving end
+    /// of a channel was dropped, no further events are sent.
+    pub fn with_sink(mut self, sink: impl TraceSink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Adds a callback run before every traced instruction.
+    pub fn on_step(mut self, f: impl FnMut(&StepInfo) + Send + 'static) -> Self {
+        self.hooks.step.push(Callback::new(f));
+        self
+    }
+
+    /// Adds a callback run when a traced call starts.
+    pub fn on_call(mut self, f: impl FnMut(&CallInfo) + Send + 'static) -> Self {
+        self.hooks.call.push(Callback::new(f));
+        self
+    }
+
+    /// Adds a callback run when a traced call ends.
+    pub fn on_call_end(mut self, f: impl FnMut(&CallEndInfo) + Send + 'static) -> Self {
+        self.hooks.call_end.push(Callback::new(f));
+        self
+    }
+
+    /// Adds a callback run for every log emitted by a traced frame.
+    pub fn on_log(mut self, f: impl FnMut(&CollectedLog) + Send + 'static) -> Self {
+        self.hooks.log.push(Callback::new(f));
+        self
+    }
+
     /// Returns the current step count.
     pub fn steps(&self) -> u64 {
         self.step_count
@@ -39,84 +549,2179 @@ impl HelloWorldInspector {
     pub fn calls(&self) -> u64 {
         self.call_count
     }
+
+    /// Returns the number of EOF contract creations.
+    pub fn eofcreates(&self) -> u64 {
+        self.eofcreate_count
+    }
+
+    /// Returns the gas spent by the top-level frames of the transactions
+    /// traced so far. Intrinsic gas and refunds are not included; see
+    /// [`gas_summary`](Self::gas_summary) for those.
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
+    /// Returns the wall-clock duration of every transaction, empty unless
+    /// `measure_time` is enabled.
+    pub fn tx_timings(&self) -> &[TxTiming] {
+        &self.tx_timings
+    }
+
+    /// Returns every distinct code that ran, keyed by the address it was
+    /// loaded from and its hash, in the order each first ran.
+    pub fn executed_contracts(&self) -> Vec<ExecutedContract> {
+        self.executed_contracts.as_slice().to_vec()
+    }
+
+    /// Returns the gas accounting of the last transaction, including
+    /// intrinsic gas and refunds, once its top-level frame has ended.
+    pub fn gas_summary(&self) -> Option<GasSummary> {
+        self.gas_summary
+    }
+
+    /// Returns the number of call and create frames currently open.
+    ///
+    /// This is 0 outside of a transaction and 1 inside the top-level frame.
+    /// Frames are counted whether or not they pass the address filter.
+    pub fn current_depth(&self) -> usize {
+        self.current_depth
+    }
+
+    /// Returns the deepest nesting of frames reached so far, counting the
+    /// top-level frame as 1.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Returns true if step recording stopped at the configured `max_steps`.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns the collections that stopped growing because a
+    /// [`TraceLimits`] cap was reached.
+    pub fn truncated_collections(&self) -> TruncatedCollections {
+        TruncatedCollections {
+            bytes: self.bytes.is_exhausted(),
+            preimages: self.preimages.is_truncated(),
+            ..self.truncated_collections
+        }
+    }
+
+    /// Returns how many times the opcode `op` was executed.
+    pub fn opcode_count(&self, op: u8) -> u64 {
+        self.opcode_counts.count(op)
+    }
+
+    /// Returns the `n` most executed opcodes, most frequent first.
+    pub fn top_opcodes(&self, n: usize) -> Vec<(OpCode, u64)> {
+        self.opcode_counts.top(n)
+    }
+
+    /// Returns the full opcode histogram.
+    pub fn opcode_histogram(&self) -> &OpcodeHistogram {
+        &self.opcode_counts
+    }
+
+    /// Returns the gas charged by the executed instructions, per opcode and
+    /// per category.
+    ///
+    /// Gas forwarded to subcalls and creations is only charged to the
+    /// starting opcode if `attribute_subcall_gas` is set.
+    pub fn gas_profile(&self) -> GasProfile {
+        GasProfile::from_histogram(&self.opcode_gas)
+    }
+
+    /// Returns the gas accounting of every call, in the order the calls started.
+    pub fn gas_by_call(&self) -> &[CallGasRecord] {
+        &self.call_gas
+    }
+
+    /// Maps every proxy seen so far to the implementation it delegated to,
+    /// i.e. the `bytecode_address` of its `DELEGATECALL` and `CALLCODE` frames.
+    pub fn resolve_implementations(&self) -> HashMap<Address, Address> {
+        calls::resolve_implementations(&self.call_gas)
+    }
+
+    /// Returns every reverted frame with its decoded reason.
+    pub fn reverts(&self) -> &[RevertRecord] {
+        &self.reverts
+    }
+
+    /// Returns every executed `SELFDESTRUCT`, in execution order.
+    pub fn selfdestructs(&self) -> &[SelfdestructRecord] {
+        &self.selfdestructs
+    }
+
+    /// Returns the total value sent to beneficiaries by `SELFDESTRUCT`,
+    /// including value burned by contracts that named themselves.
+    pub fn total_selfdestruct_value(&self) -> U256 {
+        self.selfdestructs.iter().map(|record| record.value).sum()
+    }
+
+    /// Returns the `SELFDESTRUCT`s whose beneficiary was the destroyed
+    /// contract itself.
+    pub fn burning_selfdestructs(&self) -> impl Iterator<Item = &SelfdestructRecord> {
+        self.selfdestructs.iter().filter(|record| record.burns_value())
+    }
+
+    /// Returns the panics caught with `catch_panics`, in the order they
+    /// happened.
+    pub fn internal_errors(&self) -> &[InternalError] {
+        &self.internal_errors
+    }
+
+    /// Returns the inconsistencies found in the bookkeeping with `strict`,
+    /// in the order they were found.
+    pub fn inconsistencies(&self) -> &[InternalInconsistency] {
+        &self.inconsistencies
+    }
+
+    /// Returns how often each `JUMPI` jumped and fell through, with
+    /// `branch_coverage`.
+    pub fn coverage(&self) -> &CoverageMap {
+        &self.coverage
+    }
+
+    /// Returns how often each instruction of each contract executed, with
+    /// `pc_coverage`.
+    pub fn pc_coverage(&self) -> &PcCoverage {
+        &self.pc_coverage
+    }
+
+    /// Returns whether `strict` found an inconsistency, so the report
+    /// cannot be trusted.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Returns every contract creation, in the order the creations started.
+    pub fn creations(&self) -> &[CreateRecord] {
+        &self.creations
+    }
+
+    /// Returns the init code and deployed code within 10% of their size
+    /// limits or over them, in the order the creations started and ended.
+    pub fn code_size_findings(&self) -> &[CodeSizeFinding] {
+        &self.code_size_findings
+    }
+
+    /// Returns the creations whose init code returned code starting with
+    /// `0xEF`, the EOF prefix.
+    pub fn eof_deployments(&self) -> impl Iterator<Item = &CreateRecord> {
+        self.creations.iter().filter(|record| record.is_eof())
+    }
+
+    /// Returns the calls that were granted less gas than their calling
+    /// instruction asked for, because of the 63/64 rule.
+    pub fn gas_capped_frames(&self) -> impl Iterator<Item = &CallGasRecord> {
+        self.call_gas.iter().filter(|record| record.gas_capped)
+    }
+
+    /// Returns the EIP-7702 authorizations of every traced transaction, in
+    /// the order of their authorization lists.
+    pub fn authorizations(&self) -> &[AuthorizationRecord] {
+        &self.authorizations
+    }
+
+    /// Returns every executed opcode in `alert_on_opcodes`, in execution
+    /// order.
+    pub fn opcode_alerts(&self) -> &[OpcodeAlert] {
+        &self.opcode_alerts
+    }
+
+    /// Returns every executed opcode that the hardfork given to
+    /// [`with_spec_awareness`](Self::with_spec_awareness) does not support.
+    pub fn spec_warnings(&self) -> &[SpecWarning] {
+        self.spec_awareness.as_ref().map_or(&[], SpecAwareness::warnings)
+    }
+
+    /// Returns the transactions that halted on an invalid opcode after
+    /// executing one of the [`spec_warnings`](Self::spec_warnings).
+    pub fn spec_halts(&self) -> &[SpecHalt] {
+        self.spec_awareness.as_ref().map_or(&[], SpecAwareness::halts)
+    }
+
+    /// Returns the blobs of every traced blob transaction.
+    pub fn blob_transactions(&self) -> &[BlobTransaction] {
+        &self.blob_transactions
+    }
+
+    /// Returns every executed `BLOBHASH` and `BLOBBASEFEE`, in execution
+    /// order.
+    pub fn blob_opcodes(&self) -> &[BlobOpcodeUse] {
+        &self.blob_opcodes
+    }
+
+    /// Returns every executed `ORIGIN`, in execution order.
+    pub fn origin_usages(&self) -> &[OriginUsage] {
+        &self.origin_usages
+    }
+
+    /// Returns every state change attempted in a static frame, in execution
+    /// order.
+    pub fn static_violations(&self) -> &[StaticViolationAttempt] {
+        &self.static_violations
+    }
+
+    /// Returns every low-level call whose success flag was popped without
+    /// being checked, in execution order.
+    pub fn unchecked_calls(&self) -> &[UncheckedCallFinding] {
+        &self.unchecked_calls
+    }
+
+    /// Returns the first instruction in each frame that left it below
+    /// `low_gas_percent` of its gas limit, in execution order.
+    pub fn low_gas_alarms(&self) -> &[LowGasAlarm] {
+        &self.low_gas_alarms
+    }
+
+    /// Returns the samples kept in [`ProfilingMode::Sampled`], oldest first.
+    pub fn samples(&self) -> &SampleRing {
+        &self.samples
+    }
+
+    /// Ranks the contracts and program counters by the samples kept in
+    /// [`ProfilingMode::Sampled`]; `None` when every step is recorded.
+    pub fn sample_profile(&self) -> Option<SampleProfile> {
+        match self.config.profiling {
+            ProfilingMode::Sampled { every_n_steps, .. } => {
+                Some(SampleProfile::from_ring(&self.samples, every_n_steps))
+            }
+            ProfilingMode::Full | ProfilingMode::Counters => None,
+        }
+    }
+
+    /// Returns every frame that ran out of gas, in the order the frames
+    /// ended.
+    pub fn oog_frames(&self) -> &[OutOfGasFrame] {
+        &self.oog_frames
+    }
+
+    /// Returns the failed frames per failure kind, in the order the kinds
+    /// first occurred, each with the first frame that failed that way.
+    pub fn failures(&self) -> &[FailureStats] {
+        &self.failures
+    }
+
+    /// Returns the calls forced to fail by the configured
+    /// [`FaultInjector`], in execution order.
+    pub fn injected_faults(&self) -> &[InjectedFault] {
+        &self.injected_faults
+    }
+
+    /// Returns the memory high-water mark and expansion gas of every frame
+    /// that used memory, in the order the frames ended.
+    pub fn memory_frames(&self) -> &[FrameMemory] {
+        &self.memory_frames
+    }
+
+    /// Returns the `n` frames whose memory grew the largest, largest first.
+    pub fn top_memory_frames(&self, n: usize) -> Vec<&FrameMemory> {
+        memory::top_frames(&self.memory_frames, n)
+    }
+
+    /// Returns the instructions that grew memory by more than
+    /// `memory_expansion_threshold`, in execution order.
+    pub fn memory_expansions(&self) -> &[MemoryExpansion] {
+        &self.memory_expansions
+    }
+
+    /// Returns every emitted log, in emission order.
+    ///
+    /// Logs of frames that reverted are kept and flagged as `reverted`.
+    pub fn logs(&self) -> &[CollectedLog] {
+        &self.logs
+    }
+
+    /// Returns the logs whose first topic is `topic`.
+    pub fn logs_by_topic0(&self, topic: B256) -> impl Iterator<Item = &CollectedLog> {
+        self.logs.iter().filter(move |log| log.topic0() == Some(topic))
+    }
+
+    /// Returns the logs emitted by `addr`.
+    pub fn logs_by_address(&self, addr: Address) -> impl Iterator<Item = &CollectedLog> {
+        self.logs.iter().filter(move |log| log.address == addr)
+    }
+
+    /// Returns every non-zero value transfer, in the order the transfers
+    /// started. Transfers undone by a revert are kept and flagged as `reverted`.
+    pub fn transfers(&self) -> &[ValueTransfer] {
+        &self.transfers
+    }
+
+    /// Returns the signed net value flow per address, ignoring reverted
+    /// transfers.
+    pub fn net_balance_changes(&self) -> HashMap<Address, I256> {
+        transfers::net_balance_changes(&self.transfers)
+    }
+
+    /// Returns every `SLOAD` and `SSTORE`, in execution order.
+    pub fn storage_accesses(&self) -> &[StorageAccess] {
+        &self.storage_accesses
+    }
+
+    /// Returns every `TLOAD` and `TSTORE`, in execution order.
+    pub fn transient_accesses(&self) -> &[StorageAccess] {
+        &self.transient_accesses
+    }
+
+    /// Returns the `TSTORE`s whose value was never read back with `TLOAD`
+    /// before it was overwritten or its transaction ended, in execution
+    /// order.
+    pub fn unused_transient_writes(&self) -> &[StorageAccess] {
+        &self.unused_transient_writes
+    }
+
+    /// Returns the `SSTORE`s to the storage of `addr`, in execution order,
+    /// including reverted ones.
+    pub fn storage_writes_for(&self, addr: Address) -> Vec<&StorageAccess> {
+        self.storage_accesses
+            .iter()
+            .filter(|access| access.kind == StorageAccessKind::Write && access.address == addr)
+            .collect()
+    }
+
+    /// Returns the input of a `KECCAK256` that produced `hash`, if one was
+    /// executed, e.g. the key and base slot of a mapping entry.
+    pub fn preimage(&self, hash: B256) -> Option<&Bytes> {
+        self.preimages.preimages().get(&hash)
+    }
+
+    /// Returns the inputs of the `KECCAK256` instructions executed so far,
+    /// keyed by their hash.
+    pub fn preimages(&self) -> &HashMap<B256, Bytes> {
+        self.preimages.preimages()
+    }
+
+    /// Returns the last value written to each `(address, slot)`, ignoring
+    /// reverted writes.
+    pub fn final_storage_values(&self) -> HashMap<(Address, U256), U256> {
+        self.storage_accesses
+            .iter()
+            .filter(|access| access.kind == StorageAccessKind::Write && !access.reverted)
+            .map(|access| ((access.address, access.slot), access.value))
+            .collect()
+    }
+
+    /// Returns the number of calls made to each precompile.
+    ///
+    /// Precompiles run natively, so no steps are recorded inside them.
+    pub fn precompile_calls(&self) -> &HashMap<Address, u64> {
+        &self.precompile_calls
+    }
+
+    /// Returns the aggregated call statistics for `addr`, if it was called.
+    pub fn stats_for(&self, addr: Address) -> Option<&AddressStats> {
+        self.call_stats.get(&addr)
+    }
+
+    /// Returns the `n` addresses that received the most calls.
+    ///
+    /// Ties are broken by gas used, then by address, so the order is stable.
+    pub fn busiest_addresses(&self, n: usize) -> Vec<(Address, &AddressStats)> {
+        let mut entries: Vec<(Address, &AddressStats)> =
+            self.call_stats.iter().map(|(addr, stats)| (*addr, stats)).collect();
+        entries.sort_by(|a, b| {
+            b.1.calls
+                .cmp(&a.1.calls)
+                .then(b.1.gas_used.cmp(&a.1.gas_used))
+                .then(a.0.cmp(&b.0))
+        });
+        entries.truncate(n);
+        entries
+    }
+
+    /// Returns a serializable snapshot of everything collected so far.
+    pub fn report(&self) -> InspectorReport {
+        InspectorReport {
+            step_count: self.step_count,
+            call_count: self.call_count,
+            eofcreate_count: self.eofcreate_count,
+            max_depth: self.max_depth,
+            opcode_counts: report::opcode_count_map(&self.opcode_counts),
+            calls: self.call_gas.clone(),
+            address_stats: self.call_stats.iter().map(|(a, s)| (*a, s.clone())).collect(),
+            reverts: self.reverts.clone(),
+            selfdestructs: self.selfdestructs.clone(),
+            creations: self.creations.clone(),
+            code_size_findings: self.code_size_findings.clone(),
+            logs: self.logs.clone(),
+            transfers: self.transfers.clone(),
+            storage_accesses: self.storage_accesses.clone(),
+            transient_accesses: self.transient_accesses.clone(),
+            unused_transient_writes: self.unused_transient_writes.clone(),
+            truncated: self.truncated,
+            truncated_collections: self.truncated_collections(),
+            internal_errors: self.internal_errors.clone(),
+            internal_inconsistencies: self.inconsistencies.clone(),
+            poisoned: self.poisoned,
+            branch_coverage: self.coverage.clone(),
+            pc_coverage: self.pc_coverage.clone(),
+            gas_summary: self.gas_summary,
+            tx_timings: self.tx_timings.clone(),
+            executed_contracts: self.executed_contracts.as_slice().to_vec(),
+            low_gas_alarms: self.low_gas_alarms.clone(),
+            oog_frames: self.oog_frames.clone(),
+            failures: self.failures.clone(),
+            injected_faults: self.injected_faults.clone(),
+            memory_frames: self.memory_frames.clone(),
+            memory_expansions: self.memory_expansions.clone(),
+            security: SecurityFindings {
+                origin_usages: self.origin_usages.clone(),
+                reentrancy: Vec::new(),
+                static_violations: self.static_violations.clone(),
+                unchecked_calls: self.unchecked_calls.clone(),
+            },
+            opcode_alerts: self.opcode_alerts.clone(),
+            spec_warnings: self.spec_warnings().to_vec(),
+            spec_halts: self.spec_halts().to_vec(),
+            authorizations: self.authorizations.clone(),
+            blob_transactions: self.blob_transactions.clone(),
+            blob_opcodes: self.blob_opcodes.clone(),
+            source_tag: None,
+        }
+    }
+
+    /// Returns the report as a JSON string.
+    pub fn report_json(&self) -> serde_json::Result<String> {
+        self.report().to_json()
+    }
+
+    /// Clears all counters and collected data, keeping the configuration,
+    /// selector resolver, event and function decoders, labels, sink, the
+    /// plugin whose reloads and aggregates it follows and the hardfork of
+    /// the spec awareness.
+    pub fn reset(&mut self) {
+        let config = std::mem::take(&mut self.config);
+        let selector_resolver = self.selector_resolver.take();
+        let event_decoder = self.event_decoder.take();
+        let fn_decoder = self.fn_decoder.take();
+        let labels = self.labels.take();
+        let source_maps = self.source_maps.take();
+        let mut mocks = self.mocks.take();
+        if let Some(mocks) = &mut mocks {
+            mocks.rewind();
+        }
+        let sink = self.sink.take();
+        let hooks = std::mem::take(&mut self.hooks);
+        let shared_config = self.shared_config.take();
+        let metrics = std::mem::take(&mut self.metrics);
+        let aggregate = self.aggregate.as_ref().map(AggregateHandle::restart);
+        let spec_awareness = self.spec_awareness.as_ref().map(|awareness| {
+            SpecAwareness::new(awareness.spec())
+        });
+        *self = Self {
+            selector_resolver,
+            event_decoder,
+            fn_decoder,
+            labels,
+            source_maps,
+            mocks,
+            sink,
+            hooks,
+            shared_config,
+            metrics,
+            aggregate,
+            step_plan: StepPlan::new(&config, spec_awareness.is_some()),
+            spec_awareness,
+            ..Self::with_config(config)
+        };
+    }
+
+    /// Returns the report of everything collected so far and resets the
+    /// inspector, so it can be reused for the next transaction.
+    pub fn reset_and_take_report(&mut self) -> InspectorReport {
+        let report = self.report();
+        self.reset();
+        report
+    }
+
+    /// Returns the inspector configuration.
+    pub fn config(&self) -> &HelloWorldInspectorConfig {
+        &self.config
+    }
+
+    /// Returns the prefix for stdout messages emitted inside a frame at
+    /// `depth`: two spaces per level below the top-level frame when verbose,
+    /// nothing otherwise. Tracing events carry the depth as a field instead.
+    fn indent(&self, depth: usize) -> String {
+        if self.config.verbose {
+            "  ".repeat(depth.saturating_sub(1))
+        } else {
+            String::new()
+        }
+    }
+
+    /// Returns `name (0x1234…)` for a labeled address, or the address.
+    fn address_label(&self, address: &Address) -> String {
+        self.labels
+            .as_ref()
+            .and_then(|labels| labels.label(address))
+            .unwrap_or_else(|| format!("{address:?}"))
+    }
+
+    /// Returns the sequence number of the record about to be recorded.
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.seq;
+        self.seq += 1;
+        seq
+    }
+
+    /// Returns true if the innermost open frame passed the address filter.
+    ///
+    /// Outside of any frame everything is in scope.
+    fn in_scope(&self) -> bool {
+        self.frame_filter.last().copied().unwrap_or(true)
+    }
+
+    /// Pops whether the frame ending in `hook` passed the address filter.
+    ///
+    /// A frame whose start hook never ran counts as in scope.
+    fn pop_frame_filter(&mut self, hook: &str) -> bool {
+        self.frame_filter.pop().unwrap_or_else(|| {
+            let context = "no frame was opened by its start hook".to_string();
+            self.record_inconsistency(InconsistencyKind::EndWithoutStart, hook, context);
+            true
+        })
+    }
+
+    /// Opens a call or create frame.
+    ///
+    /// Advances `tx_index` when the top-level frame of a transaction starts.
+    /// This runs in `call`/`create` rather than `initialize_interp` so that the
+    /// top-level call record, and transactions without code to run, are
+    /// attributed to the new transaction too.
+    ///
+    /// revm invokes `call_end`/`create_end` even when `call`/`create`
+    /// short-circuit with an outcome, so every frame opened here is closed by
+    /// [`exit_frame`](Self::exit_frame) and the depth stays balanced.
+    fn enter_frame(&mut self, hook: &str) {
+        if self.frame_starts.len() != self.current_depth {
+            let context = format!(
+                "{} frames open at depth {}",
+                self.frame_starts.len(),
+                self.current_depth
+            );
+            self.record_inconsistency(InconsistencyKind::UnbalancedFrames, hook, context);
+        }
+        if self.current_depth == 0 {
+            self.apply_reloaded_config();
+            self.fault_matches.clear();
+            self.tx_index = self.tx_count;
+            self.tx_count += 1;
+            self.hooks.enable_all();
+        }
+        self.current_depth += 1;
+        self.max_depth = self.max_depth.max(self.current_depth);
+        self.frame_starts.push(FrameStart {
+            logs: self.logs.len(),
+            transfers: self.transfers.len(),
+            storage_accesses: self.storage_accesses.len(),
+            transient_accesses: self.transient_accesses.len(),
+            creations: self.creations.len(),
+            steps: self.step_count,
+            seq: self.seq,
+            redacted: false,
+            low_gas: false,
+            opcode: self.pending_frame_opcode.take(),
+            init_code_finding: None,
+            started_at: if self.config.measure_time { timing::now() } else { None },
+            last_pc: None,
+            child_steps: 0,
+            executed_contract: None,
+            memory_bytes: 0,
+            memory_gas: 0,
+            injected: false,
+            mocked: false,
+            mapped_code: None,
+        });
+    }
+
+    /// Switches to the configuration the plugin was last reloaded with, if
+    /// it changed since the previous transaction.
+    fn apply_reloaded_config(&mut self) {
+        let Some((shared, version)) = &mut self.shared_config else {
+            return;
+        };
+        let Some((latest, config)) = shared.newer_than(*version) else {
+            return;
+        };
+        *version = latest;
+        if config.max_lines_per_second != self.config.max_lines_per_second {
+            self.console = ConsoleThrottle::new(config.max_lines_per_second);
+        }
+        if config.unchecked_call_window != self.config.unchecked_call_window {
+            self.unchecked_call_detector = UncheckedCallDetector::new(config.unchecked_call_window);
+        }
+        self.redaction = config.redaction();
+        self.step_plan = StepPlan::new(&config, self.spec_awareness.is_some());
+        self.config = (*config).clone();
+    }
+
+    /// Closes the innermost frame, flagging its logs, transfers and storage
+    /// accesses as reverted if the frame failed.
+    fn exit_frame(&mut self, hook: &str, result: &InterpreterResult) -> FrameStart {
+        if self.current_depth == 0 {
+            let context = "no frame is open".to_string();
+            self.record_inconsistency(InconsistencyKind::DepthUnderflow, hook, context);
+        } else if self.frame_starts.len() != self.current_depth {
+            let context = format!(
+                "{} frames open at depth {}",
+                self.frame_starts.len(),
+                self.current_depth
+            );
+            self.record_inconsistency(InconsistencyKind::UnbalancedFrames, hook, context);
+        }
+        self.current_depth = self.current_depth.saturating_sub(1);
+        if self.current_depth == 0 {
+            self.gas_used += result.gas.spent();
+            if let Some(tx) = self.tx_start.take() {
+                self.gas_summary = Some(GasSummary {
+                    blob_count: tx.blob_count,
+                    max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+                    ..GasSummary::new(
+                        tx.gas_limit,
+                        &tx.input,
+                        tx.is_create,
+                        &result.gas,
+                        result.is_ok(),
+                        tx.spec,
+                    )
+                });
+            }
+            if let Some(spec) = &mut self.spec_awareness {
+                spec.end_tx(result.result, self.tx_index);
+            }
+            let started_at = self.frame_starts.last().and_then(|start| start.started_at);
+            if let Some(duration_micros) = timing::elapsed_micros(started_at) {
+                self.tx_timings.push(TxTiming {
+                    tx_index: self.tx_index,
+                    duration_micros,
+                });
+            }
+            self.unused_transient_writes.extend(self.transient_writes.end_tx());
+            self.unused_transient_writes.sort_by_key(|write| write.step_index);
+        }
+        let start = self.frame_starts.pop().unwrap_or_default();
+        if self.seq < start.seq {
+            let context = format!(
+                "sequence counter at {} but the frame started at {}",
+                self.seq, start.seq
+            );
+            self.record_inconsistency(InconsistencyKind::SeqRegression, hook, context);
+        }
+        let steps = self.step_count - start.steps;
+        if let Some(index) = start.executed_contract {
+            self.executed_contracts.add_steps(index, steps - start.child_steps);
+        }
+        if let Some(parent) = self.frame_starts.last_mut() {
+            parent.child_steps += steps;
+        }
+        if !result.is_ok() {
+            for log in &mut self.logs[start.logs..] {
+                log.reverted = true;
+            }
+            for transfer in &mut self.transfers[start.transfers..] {
+                transfer.reverted = true;
+            }
+            for access in &mut self.storage_accesses[start.storage_accesses..] {
+                access.reverted = true;
+            }
+            for access in &mut self.transient_accesses[start.transient_accesses..] {
+                access.reverted = true;
+            }
+            self.transient_writes.revert_since(start.steps);
+            for write in &mut self.unused_transient_writes {
+                if write.tx_index == self.tx_index && write.step_index >= start.steps {
+                    write.reverted = true;
+                }
+            }
+        }
+        start
+    }
+
+    /// Records a [`LowGasAlarm`] if the instruction at `pc` left the frame
+    /// below `low_gas_percent` of its limit for the first time.
+    fn check_low_gas(&mut self, interp: &Interpreter, op: u8, pc: usize, remaining: u64) {
+        let Some(percent) = self.config.low_gas_percent else {
+            return;
+        };
+        let gas_limit = interp.gas.limit();
+        if !gas_alarm::is_below(remaining, gas_limit, percent) {
+            return;
+        }
+        let Some(start) = self.frame_starts.last_mut().filter(|start| !start.low_gas) else {
+            return;
+        };
+        start.low_gas = true;
+        self.low_gas_alarms.push(LowGasAlarm {
+            address: interp.contract.target_address,
+            pc,
+            opcode: op,
+            depth: self.current_depth.saturating_sub(1) as u64,
+            gas_limit,
+            gas_remaining: remaining,
+            tx_index: self.tx_index,
+        });
+    }
+
+    /// Records a frame that ended with `result` if it ran out of gas.
+    fn record_out_of_gas(&mut self, address: Address, gas_limit: u64, result: InstructionResult) {
+        if gas_alarm::is_out_of_gas(result) {
+            self.oog_frames.push(OutOfGasFrame {
+                address,
+                // The frame was already exited
+                depth: self.current_depth as u64,
+                gas_limit,
+                reason: format!("{result:?}"),
+                tx_index: self.tx_index,
+            });
+        }
+    }
+
+    /// Counts the frame that just ended at `address` if it failed.
+    fn record_failure(&mut self, address: Address, result: InstructionResult, start: &FrameStart) {
+        if let Some(kind) = FailureKind::classify(result) {
+            let frame = FailedFrame {
+                address,
+                pc: start.last_pc,
+                source_location: self.source_location(start),
+                // The frame was already exited
+                depth: self.current_depth as u64,
+                tx_index: self.tx_index,
+            };
+            failures::record(&mut self.failures, kind, frame, start.injected);
+        }
+    }
+
+    /// Checks the call that just started against `faults`, recording it and
+    /// returning its failure if a rule fires.
+    fn inject_fault(&mut self, inputs: &CallInputs) -> Option<CallOutcome> {
+        if self.config.faults.is_empty() {
+            return None;
+        }
+        let rule = self.config.faults.check(inputs, &mut self.fault_matches)?;
+        let failure = self.config.faults.rules[rule].with.clone();
+        let outcome = fault::outcome(&failure, inputs);
+        if let Some(start) = self.frame_starts.last_mut() {
+            start.injected = true;
+        }
+        // Frames filtered out have no open call record
+        if self.frame_filter.last().copied().unwrap_or(true) {
+            if let Some(Some(index)) = self.open_calls.last() {
+                self.call_gas[*index].injected = true;
+            }
+        }
+        self.injected_faults.push(InjectedFault {
+            target: inputs.target_address,
+            selector: selector::selector_of(&inputs.input).map(Selector::from),
+            // The frame was already entered
+            depth: self.current_depth.saturating_sub(1) as u64,
+            rule,
+            failure,
+            tx_index: self.tx_index,
+        });
+        Some(outcome)
+    }
+
+    /// Answers the call that just started from `mocks` if its function is
+    /// mocked.
+    fn mock_call(&mut self, inputs: &CallInputs) -> Option<CallOutcome> {
+        let outcome = self.mocks.as_mut()?.respond(inputs)?;
+        // `call_end` marks the call record
+        if let Some(start) = self.frame_starts.last_mut() {
+            start.mocked = true;
+        }
+        Some(outcome)
+    }
+
+    /// Notes the code of the frame `interp` starts if it has a source map.
+    fn map_code(&mut self, interp: &Interpreter) {
+        let Some(source_maps) = &self.source_maps else {
+            return;
+        };
+        // Creations run init code, which the runtime source maps do not cover
+        let Some(address) = interp
+            .contract
+            .bytecode_address
+            .filter(|address| source_maps.contains(address))
+        else {
+            return;
+        };
+        self.instruction_indices.entry(address).or_insert_with(|| {
+            disasm::instruction_indices(interp.contract.bytecode.original_byte_slice())
+        });
+        if let Some(start) = self.frame_starts.last_mut() {
+            start.mapped_code = Some(address);
+        }
+    }
+
+    /// Returns the source location of the last instruction the frame
+    /// executed, if its code has a source map.
+    fn source_location(&self, start: &FrameStart) -> Option<SourceLocation> {
+        let address = start.mapped_code?;
+        let instruction = self
+            .instruction_indices
+            .get(&address)?
+            .get(start.last_pc?)
+            .copied()
+            .flatten()?;
+        self.source_maps.as_ref()?.location(&address, instruction)
+    }
+
+    /// Records the memory a frame used, if any.
+    fn record_frame_memory(&mut self, address: Address, start: &FrameStart) {
+        if start.memory_bytes == 0 {
+            return;
+        }
+        self.memory_frames.push(FrameMemory {
+            address,
+            // The frame was already exited
+            depth: self.current_depth as u64,
+            high_water_bytes: start.memory_bytes as u64,
+            expansion_gas: start.memory_gas,
+            tx_index: self.tx_index,
+        });
+    }
+
+    /// Charges the memory the instruction at `pc` added to its frame,
+    /// recording the instruction if it added more than
+    /// `memory_expansion_threshold` bytes.
+    fn record_memory_growth(&mut self, interp: &Interpreter, op: u8, pc: usize) {
+        let size = interp.shared_memory.len();
+        let Some(start) = self.frame_starts.last_mut().filter(|start| size > start.memory_bytes)
+        else {
+            return;
+        };
+        let from = std::mem::replace(&mut start.memory_bytes, size);
+        let gas = memory::expansion_gas(from, size);
+        start.memory_gas += gas;
+        let threshold = self.config.memory_expansion_threshold;
+        if threshold != 0 && (size - from) as u64 > threshold {
+            self.memory_expansions.push(MemoryExpansion {
+                address: interp.contract.target_address,
+                pc,
+                opcode: op,
+                depth: self.current_depth.saturating_sub(1) as u64,
+                from_bytes: from as u64,
+                to_bytes: size as u64,
+                gas,
+                tx_index: self.tx_index,
+            });
+        }
+    }
+
+    /// Records the slot (and new value) of an `SLOAD` or `SSTORE` about to run.
+    ///
+    /// Loaded values are only known after the instruction, so `step_end`
+    /// fills them in.
+    fn record_storage_access(&mut self, interp: &Interpreter) {
+        let kind = match interp.current_opcode() {
+            opcode::SLOAD => StorageAccessKind::Read,
+            opcode::SSTORE => StorageAccessKind::Write,
+            opcode::TLOAD => StorageAccessKind::TransientRead,
+            opcode::TSTORE => StorageAccessKind::TransientWrite,
+            _ => return,
+        };
+        let Ok(slot) = interp.stack.peek(0) else {
+            return;
+        };
+        let value = if kind.is_write() {
+            match interp.stack.peek(1) {
+                Ok(value) => value,
+                Err(_) => return,
+            }
+        } else {
+            U256::ZERO
+        };
+        let access = StorageAccess {
+            // The storage context, which differs from the code address under DELEGATECALL
+            address: interp.contract.target_address,
+            slot,
+            kind,
+            value,
+            pc: interp.program_counter(),
+            step_index: self.step_count.saturating_sub(1),
+            reverted: false,
+            tx_index: self.tx_index,
+            seq: self.next_seq(),
+            slot_preimage: self.preimages.of_slot(slot),
+            redacted_value: None,
+            source_tag: None,
+        };
+        let limits = &self.config.limits;
+        let (accesses, max, truncated) = if kind.is_transient() {
+            self.pending_transient = Some(access.clone());
+            (
+                &mut self.transient_accesses,
+                limits.max_transient_accesses,
+                &mut self.truncated_collections.transient_accesses,
+            )
+        } else {
+            (
+                &mut self.storage_accesses,
+                limits.max_storage_accesses,
+                &mut self.truncated_collections.storage_accesses,
+            )
+        };
+        if !has_room(accesses.len(), max, truncated) {
+            return;
+        }
+        self.bytes.charge::<StorageAccess>();
+        accesses.push(access);
+        self.pending_storage_access = Some(kind);
+    }
+
+    /// Records a reverted frame, keeping its raw revert data only while it
+    /// fits in `max_total_bytes` and is not redacted.
+    fn record_revert(&mut self, address: Address, depth: u64, output: &Bytes, start: &FrameStart) {
+        self.metrics.revert();
+        let mut record = RevertRecord::new(address, depth, output.clone(), self.tx_index);
+        record.seq = self.next_seq();
+        record.source_location = self.source_location(start);
+        self.bytes.charge::<RevertRecord>();
+        if start.redacted {
+            record.reason = RevertReason::Redacted(Redacted::new(output));
+            record.raw = Bytes::new();
+        } else if !self.bytes.reserve(output.len()) {
+            record.raw = Bytes::new();
+        }
+        self.reverts.push(record);
+    }
+
+    /// Returns true if the calldata and return data of the innermost open
+    /// frame are redacted.
+    fn in_redacted_frame(&self) -> bool {
+        self.frame_starts.last().is_some_and(|start| start.redacted)
+    }
+
+    /// Marks the innermost open frame as redacted.
+    fn redact_frame(&mut self) {
+        if let Some(start) = self.frame_starts.last_mut() {
+            start.redacted = true;
+        }
+    }
+
+    /// Sends the event built by `event` to the sink, if there is one, and
+    /// drops the sink once it stops accepting events.
+    fn emit(&mut self, event: impl FnOnce() -> TraceEvent) {
+        if let Some(sink) = &mut self.sink {
+            if !sink.send(event()) {
+                self.sink = None;
+            }
+        }
+    }
+
+    /// Writes a line of [`OutputMode::Stdout`] output unless the console
+    /// throttle holds it back.
+    fn stdout(&mut self, line: String) {
+        if self.console_admits(hash_of(&line)) {
+            stdout_line!("{line}");
+        }
+    }
+
+    /// Returns whether the console line or `tracing` event with hash `hash`
+    /// is written, writing the summary of the lines held back before it.
+    fn console_admits(&mut self, hash: u64) -> bool {
+        let output = self.config.output;
+        self.console
+            .admit(hash, Instant::now, |suppressed| write_summary(output, suppressed))
+    }
+
+    /// Writes the summaries of the console lines held back during the
+    /// transaction once its top-level frame has ended.
+    fn flush_console(&mut self) {
+        if self.current_depth != 0 {
+            return;
+        }
+        let output = self.config.output;
+        self.console.flush(|suppressed| write_summary(output, suppressed));
+    }
+
+    /// Adds what the inspector counted to the aggregates of its plugin once
+    /// the top-level frame of a transaction has ended.
+    fn flush_aggregate(&mut self) {
+        if self.current_depth != 0 {
+            return;
+        }
+        let totals = Totals {
+            transactions: self.tx_count,
+            steps: self.step_count,
+            calls: self.call_count,
+            creations: self.creations.len() as u64,
+            reverts: self.reverts.len() as u64,
+            gas_used: self.gas_used,
+        };
+        if let Some(aggregate) = &mut self.aggregate {
+            aggregate.flush(totals);
+        }
+    }
+
+    /// Records the watched opcode about to execute and sends it to the sink
+    /// without waiting for the sink's buffer.
+    fn raise_opcode_alert(&mut self, interp: &Interpreter) {
+        let alert = OpcodeAlert {
+            opcode: interp.current_opcode(),
+            address: interp.contract.target_address,
+            pc: interp.program_counter(),
+            depth: self.current_depth.saturating_sub(1) as u64,
+            step_index: self.step_count - 1,
+            tx_index: self.tx_index,
+        };
+        if let Some(sink) = &mut self.sink {
+            if !sink.send_alert(TraceEvent::OpcodeAlert(alert.clone())) {
+                self.sink = None;
+            }
+        }
+        self.opcode_alerts.push(alert);
+    }
+
+    /// Remembers what the gas summary needs and records the authorizations
+    /// and blobs if a top-level frame just started.
+    fn record_tx_start<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        input: &Bytes,
+        is_create: bool,
+    ) {
+        if self.current_depth == 1 {
+            let tx = &context.env.tx;
+            self.authorizations.extend(eip7702::authorizations(tx, self.tx_index));
+            self.blob_transactions.extend(blobs::blob_transaction(tx, self.tx_index));
+            self.tx_start = Some(TxStart {
+                gas_limit: tx.gas_limit,
+                input: input.clone(),
+                is_create,
+                spec: context.spec_id(),
+                blob_count: tx.blob_hashes.len() as u64,
+                max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+            });
+        }
+    }
+
+    /// Records a value transfer, skipping zero values.
+    fn record_transfer(&mut self, from: Address, to: Address, value: U256, kind: TransferKind) {
+        if !value.is_zero() {
+            let mut transfer = ValueTransfer::new(from, to, value, kind, self.tx_index);
+            transfer.seq = self.next_seq();
+            self.transfers.push(transfer);
+        }
+    }
+
+    /// Runs the body of the `hook` hook, catching a panic in it if
+    /// `catch_panics` is set. Panics in revm happen outside of `f` and are
+    /// never caught.
+    fn guarded(&mut self, hook: &str, f: impl FnOnce(&mut Self)) {
+        if !self.config.catch_panics {
+            return f(self);
+        }
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            self.record_internal_error(hook.to_string(), panic_message(payload.as_ref()));
+        }
+    }
+
+    /// Runs the callbacks of a hook, recording the panics caught in them.
+    fn run_callbacks<T>(
+        &mut self,
+        hook: &str,
+        callbacks: fn(&mut Hooks) -> &mut Callbacks<T>,
+        info: impl FnOnce() -> T,
+    ) {
+        let catch_panics = self.config.catch_panics;
+        for (index, message) in hooks::run(callbacks(&mut self.hooks), catch_panics, info) {
+            self.record_internal_error(format!("{hook} callback #{index}"), message);
+        }
+    }
+
+    fn record_internal_error(&mut self, source: String, message: String) {
+        warn!(target: TRACING_TARGET, source, message, "caught panic");
+        self.internal_errors.push(InternalError {
+            source,
+            message,
+            tx_index: self.tx_index,
+        });
+    }
+
+    /// Records a violated invariant of the bookkeeping if `strict` is set,
+    /// then poisons the report or panics as `on_inconsistency` says.
+    fn record_inconsistency(&mut self, kind: InconsistencyKind, hook: &str, context: String) {
+        if !self.config.strict {
+            return;
+        }
+        let inconsistency = InternalInconsistency {
+            kind,
+            hook: hook.to_string(),
+            context,
+            depth: self.current_depth as u64,
+            tx_index: self.tx_index,
+        };
+        warn!(target: TRACING_TARGET, %inconsistency, "internal inconsistency");
+        self.inconsistencies.push(inconsistency.clone());
+        self.poisoned = true;
+        if self.config.on_inconsistency == InconsistencyAction::Panic {
+            panic!("{inconsistency}");
+        }
+    }
 }
 
-impl<DB: Database> Inspector<DB> for HelloWorldInspector {
-    /// Called before the interpreter is initialized.
-    fn initialize_interp(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
-        println!("Hello, world! Interpreter initializing...");
+/// Writes a summary of held back console output where the output goes.
+fn write_summary(output: OutputMode, suppressed: Suppressed) {
+    match output {
+        OutputMode::Stdout => stdout_line!("{}", suppressed.summary()),
+        OutputMode::Tracing => info!(target: TRACING_TARGET, "{}", suppressed.summary()),
+        OutputMode::Silent => {}
     }
+}
 
-    /// Called on each step of the interpreter.
-    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+/// Bodies of the [`Inspector`] hooks, run by the trait methods through
+/// [`HelloWorldInspector::guarded`].
+impl HelloWorldInspector {
+    fn handle_initialize_interp<DB: Database>(
+        &mut self,
+        interp: &Interpreter,
+        context: &EvmContext<DB>,
+    ) {
+        if !self.in_scope() {
+            return;
+        }
+        if !interp.contract.bytecode.is_empty() {
+            let index = self
+                .executed_contracts
+                .enter(interp, self.tx_index, self.config.capture_code);
+            if let Some(start) = self.frame_starts.last_mut() {
+                start.executed_contract = Some(index);
+            }
+            self.map_code(interp);
+        }
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Interpreter initializing...",
+                self.indent(self.current_depth)
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "interpreter initializing",
+                    interp.contract.target_address,
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    trace!(
+                        target: TRACING_TARGET,
+                        address = %interp.contract.target_address,
+                        depth = context.journaled_state.depth(),
+                        "interpreter initializing"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    #[inline(always)]
+    fn handle_step<DB: Database>(&mut self, interp: &Interpreter, context: &EvmContext<DB>) {
+        // Empty init code runs the padding `STOP`, which is not an
+        // instruction of the contract
+        if !self.in_scope() || interp.contract.bytecode.is_empty() {
+            return;
+        }
+        if self.config.max_steps.is_some_and(|max| self.step_count >= max) {
+            self.truncated = true;
+            return;
+        }
         self.step_count += 1;
-        
-        // Print hello message every 100 steps to avoid spam
-        if self.step_count % 100 == 0 {
-            println!(
-                "Hello, world! Step #{} - Opcode: {:?}", 
+        self.metrics.step();
+        if self.step_plan.any() {
+            self.inspect_step(interp, context);
+        }
+    }
+
+    /// Does the per-step work enabled by the step plan, kept out of line so
+    /// that steps which are only counted stay cheap.
+    #[inline(never)]
+    fn inspect_step<DB: Database>(&mut self, interp: &Interpreter, context: &EvmContext<DB>) {
+        let op = interp.current_opcode();
+        if self.step_plan.coverage() && op == opcode::JUMPI {
+            let contract = &interp.contract;
+            let address = contract.bytecode_address.unwrap_or(contract.target_address);
+            self.pending_branch = Some((address, interp.program_counter()));
+        }
+        if self.step_plan.hits() {
+            // Init code runs without a code address
+            if let Some(address) = interp.contract.bytecode_address {
+                self.pc_coverage.record(
+                    address,
+                    &interp.contract.bytecode,
+                    interp.program_counter(),
+                    self.config.coverage_max_code_size,
+                );
+            }
+        }
+        if self.step_plan.alerts() && self.config.alert_on_opcodes.contains(&op) {
+            self.raise_opcode_alert(interp);
+        }
+        if self.step_plan.spec() {
+            if let Some(spec) = &mut self.spec_awareness {
+                spec.check(interp, self.current_depth.saturating_sub(1) as u64, self.tx_index);
+            }
+        }
+        if self.step_plan.sampled() {
+            if self.step_plan.sample_due(self.step_count) {
+                self.samples.push(ProfileSample {
+                    address: interp.contract.target_address,
+                    pc: interp.program_counter() as u32,
+                    opcode: op,
+                    depth: self.current_depth.saturating_sub(1) as u16,
+                });
+            }
+            return;
+        }
+        if !self.step_plan.records() {
+            return;
+        }
+        self.opcode_counts.record(op);
+        self.pending_gas = Some((op, interp.program_counter(), interp.gas.remaining()));
+        self.pending_call_gas = calls::requested_gas(interp);
+        if let Some(start) = self.frame_starts.last_mut() {
+            start.last_pc = Some(interp.program_counter());
+        }
+        self.record_storage_access(interp);
+        match op {
+            // Hashed memory often holds calldata, e.g. a commitment's secret
+            opcode::KECCAK256 if !self.in_redacted_frame() => {
+                self.preimages.before_hash(interp)
+            }
+            opcode::ORIGIN => {
+                self.origin_usages.push(OriginUsage {
+                    address: interp.contract.target_address,
+                    pc: interp.program_counter(),
+                    kind: OriginUsageKind::Read,
+                    depth: self.current_depth.saturating_sub(1) as u64,
+                    tx_index: self.tx_index,
+                });
+                self.pending_origin_usage = true;
+            }
+            opcode::BLOBHASH | opcode::BLOBBASEFEE => self.record_blob_opcode(interp, op),
+            _ => {}
+        }
+        if static_violation::is_violation(interp) {
+            self.static_violations.push(StaticViolationAttempt {
+                address: interp.contract.target_address,
+                pc: interp.program_counter(),
+                opcode: op,
+                depth: self.current_depth.saturating_sub(1) as u64,
+                tx_index: self.tx_index,
+            });
+        }
+        if let Some(finding) = self.unchecked_call_detector.step(interp, self.current_depth) {
+            self.record_unchecked_call(finding);
+        }
+        let step = self.step_count;
+        self.emit(|| TraceEvent::Step {
+            step,
+            pc: interp.program_counter(),
+            opcode: op,
+            address: interp.contract.target_address,
+            depth: context.journaled_state.depth(),
+        });
+        self.run_callbacks("on_step", |hooks| &mut hooks.step, || StepInfo {
+            step,
+            pc: interp.program_counter(),
+            opcode: op,
+            address: interp.contract.target_address,
+            gas_remaining: interp.gas.remaining(),
+            depth: context.journaled_state.depth(),
+        });
+
+        // Print hello message every `step_sample_interval` steps to avoid spam
+        if self.step_plan.console_due(step) {
+            self.print_step(interp, context.journaled_state.depth());
+        }
+    }
+
+    /// Writes the console line of the instruction about to execute.
+    #[cold]
+    #[inline(never)]
+    fn print_step(&mut self, interp: &Interpreter, depth: u64) {
+        let op = interp.current_opcode();
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Step #{} - Opcode: {:?}",
+                self.indent(self.current_depth),
                 self.step_count,
-                interp.current_opcode()
+                op
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "step",
+                    self.step_count,
+                    op,
+                    interp.program_counter(),
+                    interp.contract.target_address,
+                    depth,
+                );
+                if self.console_admits(hash_of(&event)) {
+                    trace!(
+                        target: TRACING_TARGET,
+                        step = self.step_count,
+                        opcode = op,
+                        pc = interp.program_counter(),
+                        address = %interp.contract.target_address,
+                        depth,
+                        "step"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    /// Records a `BLOBHASH` or `BLOBBASEFEE`, warning about a `BLOBHASH`
+    /// past the last blob hash of the transaction.
+    #[cold]
+    #[inline(never)]
+    fn record_blob_opcode(&mut self, interp: &Interpreter, op: u8) {
+        let Some(opcode) = BlobOpcode::from_opcode(op) else {
+            return;
+        };
+        let index = match opcode {
+            BlobOpcode::BlobHash => interp.stack.peek(0).ok(),
+            BlobOpcode::BlobBaseFee => None,
+        };
+        let blob_count = self
+            .blob_transactions
+            .last()
+            .filter(|tx| tx.tx_index == self.tx_index)
+            .map_or(0, |tx| tx.blob_hashes.len());
+        let out_of_range = index.is_some_and(|index| index >= U256::from(blob_count));
+        if out_of_range {
+            warn!(
+                target: TRACING_TARGET,
+                address = %interp.contract.target_address,
+                pc = interp.program_counter(),
+                index = %index.unwrap_or_default(),
+                blob_count,
+                "BLOBHASH index is out of range and reads zero"
             );
         }
+        self.blob_opcodes.push(BlobOpcodeUse {
+            opcode,
+            address: interp.contract.target_address,
+            pc: interp.program_counter(),
+            index,
+            out_of_range,
+            depth: self.current_depth.saturating_sub(1) as u64,
+            tx_index: self.tx_index,
+        });
+    }
+
+    /// Records an unchecked call and warns about it.
+    #[cold]
+    #[inline(never)]
+    fn record_unchecked_call(&mut self, finding: UncheckedCallFinding) {
+        warn!(
+            target: TRACING_TARGET,
+            caller = %finding.caller_contract,
+            pc = finding.pc,
+            callee = %finding.callee,
+            "success flag of a low-level call popped without being checked"
+        );
+        self.unchecked_calls.push(finding);
+    }
+
+    #[inline(always)]
+    fn handle_step_end(&mut self, interp: &Interpreter) {
+        // Only recorded steps leave anything to finish
+        if self.step_plan.records() {
+            self.finish_step(interp);
+        }
+        if let Some((address, pc)) = self.pending_branch.take() {
+            // A JUMPI to an invalid destination halts without going anywhere
+            if interp.instruction_result.is_ok() {
+                self.coverage.record(address, pc, interp.program_counter() != pc + 1);
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn finish_step(&mut self, interp: &Interpreter) {
+        if let Some((op, pc, gas_before)) = self.pending_gas.take() {
+            let mut cost = gas_before.saturating_sub(interp.gas.remaining());
+            // Gas forwarded to a new frame is deducted by the instruction but
+            // spent by the frame; what the frame leaves unused is refunded later
+            let forwarded = match &interp.next_action {
+                InterpreterAction::Call { inputs } => {
+                    Some(inputs.gas_limit.saturating_sub(call_stipend(inputs)))
+                }
+                InterpreterAction::Create { inputs } => Some(inputs.gas_limit),
+                _ => None,
+            };
+            if let Some(forwarded) = forwarded {
+                cost = cost.saturating_sub(forwarded);
+                self.pending_frame_opcode = Some(op);
+            }
+            self.opcode_gas.add(op, cost);
+            self.record_memory_growth(interp, op, pc);
+            let remaining = interp.gas.remaining() + forwarded.unwrap_or_default();
+            self.check_low_gas(interp, op, pc, remaining);
+            self.unchecked_call_detector.after_step(
+                interp,
+                self.current_depth,
+                self.current_depth.saturating_sub(1) as u64,
+                self.tx_index,
+            );
+        }
+        let top_of_stack = || interp.stack.peek(0).unwrap_or_default();
+        if let Some(kind) = self.pending_storage_access.take() {
+            let accesses = if kind.is_transient() {
+                &mut self.transient_accesses
+            } else {
+                &mut self.storage_accesses
+            };
+            if !interp.instruction_result.is_ok() {
+                // The instruction failed, e.g. out of gas or SSTORE in a static call
+                accesses.pop();
+            } else if let Some(access) = accesses.last_mut() {
+                access.finish(top_of_stack(), self.redaction.storage_values);
+            }
+        }
+        if let Some(mut access) = self.pending_transient.take() {
+            if interp.instruction_result.is_ok() {
+                access.finish(top_of_stack(), self.redaction.storage_values);
+                if let Some(unread) = self.transient_writes.record(access) {
+                    self.unused_transient_writes.push(unread);
+                }
+            }
+        }
+        self.preimages.after_hash(interp);
+        if std::mem::take(&mut self.pending_origin_usage)
+            && interp.instruction_result.is_ok()
+            && interp.current_opcode() == opcode::EQ
+        {
+            // The instruction pointer already points at the next instruction
+            if let Some(usage) = self.origin_usages.last_mut() {
+                usage.kind = OriginUsageKind::Comparison;
+            }
+        }
+    }
+
+    fn handle_log<DB: Database>(&mut self, context: &EvmContext<DB>, log: &Log) {
+        if !self.in_scope() {
+            return;
+        }
+        let mut collected = CollectedLog::new(
+            log,
+            self.current_depth.saturating_sub(1) as u64,
+            self.step_count.saturating_sub(1),
+            self.tx_index,
+        );
+        collected.seq = self.next_seq();
+        if let Some(decoder) = &self.event_decoder {
+            collected.decode_with(decoder);
+        }
+        self.emit(|| TraceEvent::Log(collected.clone()));
+        self.run_callbacks("on_log", |hooks| &mut hooks.log, || collected.clone());
+        if has_room(
+            self.logs.len(),
+            self.config.limits.max_logs,
+            &mut self.truncated_collections.logs,
+        ) {
+            self.bytes.charge::<CollectedLog>();
+            let data = self.bytes.keep(&collected.data);
+            self.logs.push(CollectedLog { data, ..collected });
+        }
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Log emitted with {} topics and {} bytes of data",
+                self.indent(self.current_depth),
+                log.topics().len(),
+                log.data.data.len()
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "log",
+                    log.address,
+                    log.topics().len(),
+                    log.data.data.len(),
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        address = %log.address,
+                        topics = log.topics().len(),
+                        data_len = log.data.data.len(),
+                        depth = context.journaled_state.depth(),
+                        "log"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    fn handle_call<DB: Database>(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+    ) {
+        // A transaction's call has no calling instruction
+        let gas_requested = self.pending_call_gas.take().filter(|_| self.current_depth > 0);
+        self.enter_frame("call");
+        self.record_tx_start(context, &inputs.input, false);
+        let redacted = self.redaction.redacts_calldata_of(&inputs.target_address)
+            || self.redaction.redacts_calldata_of(&inputs.bytecode_address);
+        if redacted {
+            self.redact_frame();
+        }
+        let traced = self.config.filter.matches(&inputs.caller, &inputs.target_address);
+        self.frame_filter.push(traced);
+        if !traced {
+            return;
+        }
+
+        self.call_count += 1;
+        self.metrics.call();
+        let recorded = has_room(
+            self.call_gas.len(),
+            self.config.limits.max_call_records,
+            &mut self.truncated_collections.calls,
+        );
+        self.open_calls.push(recorded.then_some(self.call_gas.len()));
+        let mut record = CallGasRecord::new(inputs, context.journaled_state.depth(), self.tx_index);
+        record.set_delegate(eip7702::resolve_delegate(context, inputs.bytecode_address));
+        record.set_gas_requested(inputs, gas_requested);
+        record.label = self
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get(&inputs.target_address))
+            .map(String::from);
+        record.seq = self.next_seq();
+        let seq = record.seq;
+        if redacted {
+            record.redacted_calldata = Some(Redacted::new(&inputs.input));
+        } else if let Some(selector) = selector::selector_of(&inputs.input) {
+            record.selector = Some(selector.into());
+            record.function = self
+                .selector_resolver
+                .as_ref()
+                .and_then(|resolver| resolver.resolve(selector));
+            if let Some(decoder) = &self.fn_decoder {
+                record.decode_with(decoder, &inputs.input);
+            }
+        }
+        let function = record.function_label();
+        let selector = record.selector;
+        let label = record.label.clone();
+        if recorded {
+            self.bytes.charge::<CallGasRecord>();
+            self.call_gas.push(record);
+        }
+        let precompile = precompile::is_precompile(
+            &inputs.bytecode_address,
+            self.config.precompile_upper_bound,
+        )
+        .then(|| precompile::precompile_name(&inputs.bytecode_address));
+        if precompile.is_some() {
+            *self.precompile_calls.entry(inputs.bytecode_address).or_default() += 1;
+        }
+        if let Some(value) = inputs.transfer_value() {
+            self.record_transfer(inputs.caller, inputs.target_address, value, TransferKind::Call);
+        }
+        let delegated_to = (inputs.bytecode_address != inputs.target_address)
+            .then_some(inputs.bytecode_address);
+        let depth = self.current_depth as u64;
+        self.emit(|| TraceEvent::CallStart {
+            address: inputs.target_address,
+            caller: inputs.caller,
+            value: inputs.call_value(),
+            gas_limit: inputs.gas_limit,
+            depth,
+            seq,
+            selector,
+            label,
+        });
+        self.run_callbacks("on_call", |hooks| &mut hooks.call, || CallInfo {
+            caller: inputs.caller,
+            target: inputs.target_address,
+            value: inputs.call_value(),
+            input: inputs.input.clone(),
+            gas_limit: inputs.gas_limit,
+            depth,
+        });
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Call #{} to address: {}{}{}{}",
+                self.indent(self.current_depth),
+                self.call_count,
+                self.address_label(&inputs.target_address),
+                delegated_to
+                    .map(|code| format!(" → {}", self.address_label(&code)))
+                    .unwrap_or_default(),
+                precompile.map(|name| format!(" (precompile {name})")).unwrap_or_default(),
+                function.as_deref().map(|f| format!(" calling {f}")).unwrap_or_default()
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "call",
+                    self.call_count,
+                    inputs.target_address,
+                    inputs.bytecode_address,
+                    inputs.caller,
+                    inputs.call_value(),
+                    function.as_deref(),
+                    precompile,
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        call = self.call_count,
+                        address = %inputs.target_address,
+                        code_address = %inputs.bytecode_address,
+                        caller = %inputs.caller,
+                        value = %inputs.call_value(),
+                        function = function.as_deref().unwrap_or_default(),
+                        precompile = precompile.unwrap_or_default(),
+                        depth = context.journaled_state.depth(),
+                        "call"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    fn handle_call_end<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: &CallOutcome,
+    ) {
+        // Reverts discard the logs of nested frames even when this one is filtered out
+        let start = self.exit_frame("call_end", &outcome.result);
+        let duration_micros = timing::elapsed_micros(start.started_at);
+        if let Some(op) = start.opcode.filter(|_| self.config.attribute_subcall_gas) {
+            // The stipend is given to the callee for free
+            let spent = outcome.result.gas.spent().saturating_sub(call_stipend(inputs));
+            self.opcode_gas.add(op, spent);
+        }
+        if !self.pop_frame_filter("call_end") {
+            return;
+        }
+        self.record_out_of_gas(inputs.target_address, inputs.gas_limit, outcome.result.result);
+        self.record_failure(inputs.target_address, outcome.result.result, &start);
+        self.record_frame_memory(inputs.target_address, &start);
+        // Precompiles run natively, without an interpreter
+        let no_code = outcome.result.is_ok()
+            && !start.mocked
+            && start.executed_contract.is_none()
+            && !precompile::is_precompile(
+                &inputs.bytecode_address,
+                self.config.precompile_upper_bound,
+            );
+        if let Some(index) = self.open_calls.pop() {
+            // Calls beyond `max_call_records` still count in the statistics
+            let mut unrecorded;
+            let record = match index {
+                Some(index) => &mut self.call_gas[index],
+                None => {
+                    let depth = context.journaled_state.depth();
+                    unrecorded = CallGasRecord::new(inputs, depth, self.tx_index);
+                    &mut unrecorded
+                }
+            };
+            record.finish(outcome.result.result, outcome.result.gas.spent());
+            record.duration_micros = duration_micros;
+            record.no_code = no_code;
+            record.mocked = start.mocked;
+            if index.is_some() {
+                record.output = self.bytes.keep_output(
+                    &outcome.result.output,
+                    self.config.max_output_bytes,
+                    start.redacted,
+                );
+            }
+            self.metrics.call_end(record.gas_used);
+            record.revert_reason = revert_reason(&outcome.result, start.redacted);
+            let address = calls::attributed_address(inputs, self.config.delegatecall_attribution);
+            self.call_stats
+                .entry(address)
+                .or_default()
+                .record(record, inputs.transfer_value().unwrap_or_default());
+        }
+        if outcome.result.result == InstructionResult::Revert {
+            self.record_revert(
+                inputs.target_address,
+                context.journaled_state.depth(),
+                &outcome.result.output,
+                &start,
+            );
+        }
+        let depth = self.current_depth as u64 + 1;
+        self.emit(|| TraceEvent::CallEnd {
+            address: inputs.target_address,
+            success: outcome.result.is_ok(),
+            gas_used: outcome.result.gas.spent(),
+            depth,
+            revert_reason: revert_reason(&outcome.result, start.redacted),
+        });
+        self.run_callbacks("on_call_end", |hooks| &mut hooks.call_end, || CallEndInfo {
+            target: inputs.target_address,
+            success: outcome.result.is_ok(),
+            gas_used: outcome.result.gas.spent(),
+            output: outcome.result.output.clone(),
+            depth,
+        });
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Call ended with success: {}{}{}{}",
+                self.indent(self.current_depth + 1),
+                outcome.result.is_ok(),
+                if no_code { " (no code)" } else { "" },
+                if start.injected { " (injected)" } else { "" },
+                if start.mocked { " (mocked)" } else { "" }
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "call ended",
+                    inputs.target_address,
+                    outcome.result.is_ok(),
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        address = %inputs.target_address,
+                        success = outcome.result.is_ok(),
+                        depth = context.journaled_state.depth(),
+                        "call ended"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    fn handle_create<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        inputs: &CreateInputs,
+    ) {
+        self.enter_frame("create");
+        self.record_tx_start(context, &inputs.init_code, true);
+        if self.redaction.calldata {
+            self.redact_frame();
+        }
+        // Create frames inherit the filter state of the creating frame
+        let traced = self.in_scope();
+        self.frame_filter.push(traced);
+        if !traced {
+            return;
+        }
+        let seq = self.next_seq();
+        // The created address is only known once the frame ends
+        self.record_transfer(inputs.caller, Address::ZERO, inputs.value, TransferKind::Create);
+        let depth = self.current_depth as u64;
+        let keep_init_code =
+            !self.in_redacted_frame() && self.bytes.reserve(inputs.init_code.len());
+        self.creations.push(CreateRecord {
+            creator: inputs.caller,
+            scheme: inputs.scheme.into(),
+            init_code_hash: keccak256(&inputs.init_code),
+            init_code: if keep_init_code {
+                inputs.init_code.clone()
+            } else {
+                Bytes::new()
+            },
+            deployed_address: None,
+            deployed_code_len: 0,
+            deployed_code_prefix: Bytes::new(),
+            output: None,
+            gas_used: 0,
+            success: false,
+            depth: depth - 1,
+            tx_index: self.tx_index,
+            seq,
+            duration_micros: None,
+        });
+        let limit = code_size::init_code_limit(&context.env.cfg);
+        if code_size::is_near_limit(inputs.init_code.len(), limit) {
+            if let Some(start) = self.frame_starts.last_mut() {
+                start.init_code_finding = Some(self.code_size_findings.len());
+            }
+            self.code_size_findings.push(CodeSizeFinding {
+                kind: CodeSizeKind::InitCode,
+                length: inputs.init_code.len(),
+                limit,
+                address: None,
+                tx_index: self.tx_index,
+            });
+        }
+        self.emit(|| TraceEvent::CreateStart {
+            caller: inputs.caller,
+            value: inputs.value,
+            init_code_len: inputs.init_code.len(),
+            gas_limit: inputs.gas_limit,
+            depth,
+            seq,
+        });
+        match self.config.output {
+            OutputMode::Stdout if inputs.init_code.is_empty() => self.stdout(format!(
+                "{}Hello, world! Empty contract creation, no init code to run",
+                self.indent(self.current_depth)
+            )),
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Contract creation with {} bytes of code",
+                self.indent(self.current_depth),
+                inputs.init_code.len()
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "create",
+                    inputs.caller,
+                    inputs.init_code.len(),
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        caller = %inputs.caller,
+                        init_code_len = inputs.init_code.len(),
+                        depth = context.journaled_state.depth(),
+                        "create"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    fn handle_create_end<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: &CreateOutcome,
+    ) {
+        let start = self.exit_frame("create_end", &outcome.result);
+        let duration_micros = timing::elapsed_micros(start.started_at);
+        if let Some(op) = start.opcode.filter(|_| self.config.attribute_subcall_gas) {
+            self.opcode_gas.add(op, outcome.result.gas.spent());
+        }
+        if !self.pop_frame_filter("create_end") {
+            return;
+        }
+        let address = outcome.address.unwrap_or(inputs.caller);
+        self.record_out_of_gas(address, inputs.gas_limit, outcome.result.result);
+        self.record_failure(address, outcome.result.result, &start);
+        self.record_frame_memory(address, &start);
+        if let Some(index) = start.init_code_finding {
+            self.code_size_findings[index].address = outcome.address;
+        }
+        if !inputs.value.is_zero() {
+            match self.transfers.get_mut(start.transfers) {
+                Some(transfer) => transfer.to = outcome.address.unwrap_or_default(),
+                None => {
+                    let context = format!(
+                        "no transfer of {} was recorded when the creation started",
+                        inputs.value
+                    );
+                    self.record_inconsistency(
+                        InconsistencyKind::EndWithoutStart,
+                        "create_end",
+                        context,
+                    );
+                }
+            }
+        }
+        if let Some(record) = self.creations.get_mut(start.creations) {
+            record.success = outcome.result.is_ok();
+            record.gas_used = outcome.result.gas.spent();
+            record.duration_micros = duration_micros;
+            record.output = self.bytes.keep_output(
+                &outcome.result.output,
+                self.config.max_output_bytes,
+                start.redacted,
+            );
+            if record.success {
+                // The init code returns the runtime code to deploy
+                record.deployed_address = outcome.address;
+                record.deployed_code_len = outcome.result.output.len();
+            }
+            // Code rejected for its size or prefix is still returned
+            let returned_code = record.success
+                || matches!(
+                    outcome.result.result,
+                    InstructionResult::CreateContractSizeLimit
+                        | InstructionResult::CreateContractStartingWithEF
+                );
+            if returned_code {
+                let code = &outcome.result.output;
+                if !start.redacted {
+                    let prefix = code.len().min(creations::DEPLOYED_CODE_PREFIX_LEN);
+                    record.deployed_code_prefix = code.slice(..prefix);
+                }
+                let limit = context.env.cfg.max_code_size();
+                if code_size::is_near_limit(code.len(), limit) {
+                    self.code_size_findings.push(CodeSizeFinding {
+                        kind: CodeSizeKind::DeployedCode,
+                        length: code.len(),
+                        limit,
+                        address: outcome.address,
+                        tx_index: self.tx_index,
+                    });
+                }
+            }
+            if record.has_address_mismatch() {
+                warn!(
+                    target: TRACING_TARGET,
+                    predicted = ?record.predicted_create2_address(),
+                    deployed = ?record.deployed_address,
+                    "CREATE2 deployed to an unexpected address"
+                );
+            }
+        }
+        if outcome.result.result == InstructionResult::Revert {
+            self.record_revert(
+                outcome.address.unwrap_or(inputs.caller),
+                context.journaled_state.depth(),
+                &outcome.result.output,
+                &start,
+            );
+        }
+        let depth = self.current_depth as u64 + 1;
+        self.emit(|| TraceEvent::CreateEnd {
+            address: outcome.address,
+            success: outcome.result.is_ok(),
+            gas_used: outcome.result.gas.spent(),
+            depth,
+            revert_reason: revert_reason(&outcome.result, start.redacted),
+        });
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Contract creation ended with success: {}",
+                self.indent(self.current_depth + 1),
+                outcome.result.is_ok()
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "create ended",
+                    outcome.address,
+                    outcome.result.is_ok(),
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        address = ?outcome.address,
+                        success = outcome.result.is_ok(),
+                        depth = context.journaled_state.depth(),
+                        "create ended"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    /// Opens the frame of an EOF creation. Only counts and logs it; the
+    /// records of `CREATE` and `CREATE2` frames are not kept for it.
+    fn handle_eofcreate<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        inputs: &EOFCreateInputs,
+    ) {
+        self.enter_frame("eofcreate");
+        let initdata = creations::eof_initdata(inputs);
+        self.record_tx_start(context, &initdata, true);
+        if self.redaction.calldata {
+            self.redact_frame();
+        }
+        let traced = self.in_scope();
+        self.frame_filter.push(traced);
+        if !traced {
+            return;
+        }
+        self.eofcreate_count += 1;
+        let container_hash = creations::eof_container_hash(inputs);
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! EOF contract creation with {} bytes of initdata",
+                self.indent(self.current_depth),
+                initdata.len()
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "eofcreate",
+                    inputs.caller,
+                    container_hash,
+                    initdata.len(),
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        caller = %inputs.caller,
+                        container_hash = ?container_hash,
+                        initdata_len = initdata.len(),
+                        depth = context.journaled_state.depth(),
+                        "eofcreate"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    fn handle_eofcreate_end<DB: Database>(
+        &mut self,
+        context: &EvmContext<DB>,
+        inputs: &EOFCreateInputs,
+        outcome: &CreateOutcome,
+    ) {
+        let start = self.exit_frame("eofcreate_end", &outcome.result);
+        if let Some(op) = start.opcode.filter(|_| self.config.attribute_subcall_gas) {
+            self.opcode_gas.add(op, outcome.result.gas.spent());
+        }
+        if !self.pop_frame_filter("eofcreate_end") {
+            return;
+        }
+        let address = outcome.address.unwrap_or(inputs.caller);
+        self.record_out_of_gas(address, inputs.gas_limit, outcome.result.result);
+        self.record_failure(address, outcome.result.result, &start);
+        self.record_frame_memory(address, &start);
+        if outcome.result.result == InstructionResult::Revert {
+            self.record_revert(
+                address,
+                context.journaled_state.depth(),
+                &outcome.result.output,
+                &start,
+            );
+        }
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! EOF contract creation ended with success: {}",
+                self.indent(self.current_depth + 1),
+                outcome.result.is_ok()
+            )),
+            OutputMode::Tracing => {
+                let event = (
+                    "eofcreate ended",
+                    outcome.address,
+                    outcome.result.is_ok(),
+                    context.journaled_state.depth(),
+                );
+                if self.console_admits(hash_of(&event)) {
+                    debug!(
+                        target: TRACING_TARGET,
+                        address = ?outcome.address,
+                        success = outcome.result.is_ok(),
+                        depth = context.journaled_state.depth(),
+                        "eofcreate ended"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+
+    fn handle_selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if !self.in_scope() {
+            return;
+        }
+        let record = SelfdestructRecord {
+            contract,
+            beneficiary: target,
+            value,
+            depth: self.current_depth.saturating_sub(1) as u64,
+            call_index: self.open_calls.last().copied().flatten(),
+            tx_index: self.tx_index,
+            seq: self.next_seq(),
+        };
+        self.emit(|| TraceEvent::Selfdestruct(record.clone()));
+        self.metrics.selfdestruct();
+        self.selfdestructs.push(record);
+        self.record_transfer(contract, target, value, TransferKind::Selfdestruct);
+        match self.config.output {
+            OutputMode::Stdout => self.stdout(format!(
+                "{}Hello, world! Contract {} self-destructed, sending {} wei to {}",
+                self.indent(self.current_depth),
+                self.address_label(&contract),
+                value,
+                self.address_label(&target)
+            )),
+            OutputMode::Tracing => {
+                if self.console_admits(hash_of(&("selfdestruct", contract, target, value))) {
+                    info!(
+                        target: TRACING_TARGET,
+                        address = %contract,
+                        beneficiary = %target,
+                        value = %value,
+                        "selfdestruct"
+                    );
+                }
+            }
+            OutputMode::Silent => {}
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for HelloWorldInspector {
+    /// Called before the interpreter is initialized.
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.guarded("initialize_interp", |this| {
+            this.handle_initialize_interp(interp, context)
+        });
+    }
+
+    /// Called on each step of the interpreter.
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.guarded("step", |this| this.handle_step(interp, context));
     }
 
     /// Called after step when the instruction has been executed.
-    fn step_end(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
-        // Optional: could add post-step logic here
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        self.guarded("step_end", |this| this.handle_step_end(interp));
     }
 
     /// Called when a log is emitted.
-    fn log(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>, log: &Log) {
-        println!(
-            "Hello, world! Log emitted with {} topics and {} bytes of data",
-            log.topics().len(),
-            log.data.data.len()
-        );
+    fn log(&mut self, _interp: &mut Interpreter, context: &mut EvmContext<DB>, log: &Log) {
+        self.guarded("log", |this| this.handle_log(context, log));
     }
 
     /// Called whenever a call to a contract is about to start.
     fn call(
         &mut self,
-        _context: &mut EvmContext<DB>,
+        context: &mut EvmContext<DB>,
         inputs: &mut CallInputs,
     ) -> Option<CallOutcome> {
-        self.call_count += 1;
-        println!(
-            "Hello, world! Call #{} to address: {:?}",
-            self.call_count,
-            inputs.target_address
-        );
-        
-        // Return None to continue with normal execution
-        None
+        self.guarded("call", |this| this.handle_call(context, inputs));
+        // Return None to continue with normal execution, unless a fault is
+        // injected or the call is mocked
+        self.inject_fault(inputs).or_else(|| self.mock_call(inputs))
     }
 
     /// Called when a call to a contract has concluded.
     fn call_end(
         &mut self,
-        _context: &mut EvmContext<DB>,
-        _inputs: &CallInputs,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
         outcome: CallOutcome,
     ) -> CallOutcome {
-        println!(
-            "Hello, world! Call ended with success: {}",
-            outcome.result.is_ok()
-        );
+        self.guarded("call_end", |this| this.handle_call_end(context, inputs, &outcome));
+        self.flush_console();
+        self.flush_aggregate();
         outcome
     }
 
     /// Called when a contract is about to be created.
     fn create(
         &mut self,
-        _context: &mut EvmContext<DB>,
+        context: &mut EvmContext<DB>,
         inputs: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
-        println!(
-            "Hello, world! Contract creation with {} bytes of code",
-            inputs.init_code.len()
-        );
-        
+        self.guarded("create", |this| this.handle_create(context, inputs));
         // Return None to continue with normal execution
         None
     }
@@ -124,35 +2729,143 @@ impl<DB: Database> Inspector<DB> for HelloWorldInspector {
     /// Called when a contract has been created.
     fn create_end(
         &mut self,
-        _context: &mut EvmContext<DB>,
-        _inputs: &CreateInputs,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
         outcome: CreateOutcome,
     ) -> CreateOutcome {
-        println!(
-            "Hello, world! Contract creation ended with success: {}",
-            outcome.result.is_ok()
-        );
+        self.guarded("create_end", |this| this.handle_create_end(context, inputs, &outcome));
+        self.flush_console();
+        self.flush_aggregate();
+        outcome
+    }
+
+    /// Called when an EOF contract is about to be created, by `EOFCREATE`
+    /// or a creation transaction.
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.guarded("eofcreate", |this| this.handle_eofcreate(context, inputs));
+        None
+    }
+
+    /// Called when an EOF contract has been created.
+    fn eofcreate_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.guarded("eofcreate_end", |this| {
+            this.handle_eofcreate_end(context, inputs, &outcome)
+        });
+        self.flush_console();
+        self.flush_aggregate();
         outcome
     }
 
     /// Called when a contract has been self-destructed.
     fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
-        println!(
-            "Hello, world! Contract {:?} self-destructed, sending {} wei to {:?}",
-            contract, value, target
-        );
+        self.guarded("selfdestruct", |this| this.handle_selfdestruct(contract, target, value));
     }
 }
 
+pub use aa::{AAAnalyzer, AAReport, UserOpSection};
+pub use abi::{
+    DecodedArg, DecodedCall, DecodedField, DecodedLog, EventDecoder, FnDecoder, SignatureError,
+};
+pub use access_list::{AccessListConfig, AccessListInspector};
+pub use aggregate::AggregateReport;
+pub use assertions::{AssertionError, TraceAssertions};
+pub use blobs::{BlobOpcode, BlobOpcodeUse, BlobTransaction};
+pub use breakpoint::{
+    BreakAction, BreakpointCallback, BreakpointHit, BreakpointInspector, Breakpoints,
+    InterpSnapshot,
+};
+pub use builder::{ConfigError, HelloWorldInspectorBuilder};
+pub use call_trace::{CallKind, CallTrace, CallTraceInspector, CallTraceLog, CallTraceNode};
+pub use calls::{AddressStats, CallGasRecord};
+pub use code_size::{CodeSizeFinding, CodeSizeKind};
+pub use coverage::{BranchCoverage, CoverageMap, PcCoverage};
+pub use collector::ReportCollector;
+pub use creations::{CreateKind, CreateRecord};
+pub use diff::{DiffStatus, FrameDiff, TraceDiff};
+pub use disasm::Instruction;
+pub use dot::DotOptions;
+pub use eip7702::AuthorizationRecord;
+pub use error::Error;
+pub use executed::ExecutedContract;
+pub use failures::{FailedFrame, FailureKind, FailureStats};
+pub use fault::{Failure, FaultInjector, FaultRule, InjectedFault};
+pub use fork::{ForkComparison, ForkReport, ForkRunner};
+pub use filter::{AddressFilter, FilterMatch};
+pub use gas_alarm::{LowGasAlarm, OutOfGasFrame};
+pub use gas_profile::{GasCategory, GasProfile};
+pub use gas_summary::GasSummary;
+pub use health::{PluginHealth, PluginReport};
+pub use geth::GethCallFrame;
+pub use histogram::OpcodeHistogram;
+pub use hooks::{CallEndInfo, CallInfo, StepInfo};
+pub use labels::Labels;
+pub use limits::{TraceLimits, TruncatedCollections};
+pub use logs::CollectedLog;
+pub use memory::{FrameMemory, MemoryExpansion, DEFAULT_MEMORY_EXPANSION_THRESHOLD};
+pub use mock::MockRegistry;
+pub use opcode_alert::OpcodeAlert;
+pub use origin::{OriginUsage, OriginUsageKind};
+pub use panics::InternalError;
+pub use pretty::PrettyPrintOptions;
+pub use redact::{Redacted, Redaction};
+pub use returndata::{ReturnData, DEFAULT_MAX_OUTPUT_BYTES};
+pub use source_map::{SourceLocation, SourceMapError, SourceMapRegistry};
+pub use spec_awareness::{SpecAwareness, SpecHalt, SpecWarning};
+pub use reentrancy::{ReentrancyFinding, ReentrancyKind};
+pub use registry::PluginRegistry;
+#[cfg(feature = "watch")]
+pub use reload::ConfigWatcher;
+pub use report::{InspectorReport, SecurityFindings};
+pub use revert::{RevertReason, RevertRecord};
+pub use sampling::{PcSamples, ProfileSample, ProfilingMode, SampleProfile, SampleRing};
+pub use selector::{SelectorResolver, StaticSelectorResolver};
+#[cfg(feature = "std")]
+pub use selector_db::SelectorDb;
+pub use selfdestruct::SelfdestructRecord;
+pub use session::{BlockReport, TraceSession, TxReport};
+pub use shared::{InspectorSnapshot, SharedInspector};
+#[cfg(feature = "std")]
+pub use file_sink::FileTraceSink;
+pub use sink::{TraceEvent, TraceSink};
+pub use stack::{InspectorStack, StackedInspector};
+pub use state::{Prestate, PrestateAccount, PrestateBuilder};
+pub use state_diff::{AccountDiff, StateDiff};
+pub use static_violation::StaticViolationAttempt;
+pub use unchecked_call::UncheckedCallFinding;
+pub use storage::{StorageAccess, StorageAccessKind};
+pub use strict::{InconsistencyAction, InconsistencyKind, InternalInconsistency};
+pub use struct_log::{StructLog, StructLogConfig, StructLogInspector};
+pub use timeline::{ReportTimeline, TimelineEvent};
+pub use timing::TxTiming;
+pub use transfers::{TransferKind, ValueTransfer};
+pub use tx_summary::{TxStatus, TxSummary};
+pub use writer::Format;
+#[cfg(feature = "std")]
+pub use writer::ReportWriter;
+
 // Re-export plugin functionality
 pub use plugin::{
     HelloWorldInspectorPlugin, 
     HelloWorldInspectorConfig, 
+    DelegateCallAttribution,
+    InspectorPlugin,
+    OutputMode,
     create_plugin, 
     create_config,
     create_detailed_config,
     register_inspector
 };
+pub use profile::InspectorProfile;
+pub use query::CallQuery;
 
 #[cfg(test)]
 mod tests {
@@ -172,6 +2885,15 @@ mod tests {
         assert_eq!(inspector.call_count, 0);
     }
 
+    #[test]
+    fn test_report_json_for_fresh_inspector() {
+        let inspector = HelloWorldInspector::default();
+        assert_eq!(
+            inspector.report_json().unwrap(),
+            r#"{"step_count":0,"call_count":0,"max_depth":0,"opcode_counts":{},"calls":[],"address_stats":{},"reverts":[],"selfdestructs":[],"creations":[],"logs":[],"transfers":[],"storage_accesses":[],"truncated":false,"truncated_collections":{"calls":false,"logs":false,"storage_accesses":false,"bytes":false,"preimages":false},"internal_errors":[],"gas_summary":null,"low_gas_alarms":[],"oog_frames":[],"security":{"origin_usages":[],"reentrancy":[],"static_violations":[]}}"#
+        );
+    }
+
     #[test]
     fn test_plugin_creation() {
         let plugin = create_plugin();
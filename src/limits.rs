@@ -0,0 +1,160 @@
+//! Bounds on how much a trace retains.
+//!
+//! Every collection an inspector keeps grows with the traced transactions,
+//! so tracing whole blocks of heavy transactions can exhaust memory.
+//! [`TraceLimits`] caps the collections; once a cap is reached the inspector
+//! stops appending to that collection and flags it as truncated, while its
+//! counters keep counting.
+
+use alloy_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, ReturnData};
+
+/// Caps on the collections kept by the inspectors; `None` is unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraceLimits {
+    /// Most call records kept by [`HelloWorldInspector`](crate::HelloWorldInspector)
+    pub max_call_records: Option<usize>,
+    /// Most logs kept by [`HelloWorldInspector`](crate::HelloWorldInspector)
+    pub max_logs: Option<usize>,
+    /// Most persistent storage accesses kept by [`HelloWorldInspector`](crate::HelloWorldInspector)
+    pub max_storage_accesses: Option<usize>,
+    /// Most transient storage accesses kept by [`HelloWorldInspector`](crate::HelloWorldInspector)
+    pub max_transient_accesses: Option<usize>,
+    /// Most steps kept by [`StructLogInspector`](crate::StructLogInspector),
+    /// see [`StructLogInspector::with_limits`](crate::StructLogInspector::with_limits)
+    pub max_struct_logs: Option<usize>,
+    /// Bytes of `KECCAK256` inputs kept by [`HelloWorldInspector`](crate::HelloWorldInspector)
+    /// to explain derived storage slots
+    pub max_preimage_bytes: Option<usize>,
+    /// Estimated bytes each inspector may retain. Records are still kept
+    /// beyond it, but without their calldata, return data, log data, revert
+    /// data, stack or memory
+    pub max_total_bytes: Option<usize>,
+}
+
+/// Collections that stopped growing because a [`TraceLimits`] cap was reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TruncatedCollections {
+    /// `max_call_records` was reached
+    pub calls: bool,
+    /// `max_logs` was reached
+    pub logs: bool,
+    /// `max_storage_accesses` was reached
+    pub storage_accesses: bool,
+    /// `max_transient_accesses` was reached
+    #[serde(skip_serializing_if = "crate::calls::is_false")]
+    pub transient_accesses: bool,
+    /// `max_total_bytes` was reached and payloads were dropped
+    pub bytes: bool,
+    /// `max_preimage_bytes` was reached and preimages were dropped
+    pub preimages: bool,
+}
+
+impl TruncatedCollections {
+    /// Marks the collections truncated in `other` as truncated.
+    pub fn merge(&mut self, other: &TruncatedCollections) {
+        self.calls |= other.calls;
+        self.logs |= other.logs;
+        self.storage_accesses |= other.storage_accesses;
+        self.transient_accesses |= other.transient_accesses;
+        self.bytes |= other.bytes;
+        self.preimages |= other.preimages;
+    }
+
+    /// Returns true if any collection was truncated.
+    pub fn any(&self) -> bool {
+        self.calls
+            || self.logs
+            || self.storage_accesses
+            || self.transient_accesses
+            || self.bytes
+            || self.preimages
+    }
+
+    /// Fails with [`Error::LimitExceeded`] if any collection was truncated,
+    /// for callers that need a complete trace.
+    pub fn ensure_complete(&self) -> Result<(), Error> {
+        if self.any() {
+            return Err(Error::LimitExceeded(*self));
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if a collection of `len` items may grow under `max`,
+/// otherwise sets `truncated`.
+pub(crate) fn has_room(len: usize, max: Option<usize>, truncated: &mut bool) -> bool {
+    let room = max.is_none_or(|max| len < max);
+    *truncated |= !room;
+    room
+}
+
+/// Running estimate of the bytes retained by an inspector.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ByteBudget {
+    /// Bytes retained so far
+    used: usize,
+    /// Bytes that may be retained, unbounded if `None`
+    max: Option<usize>,
+    /// Whether a payload was dropped
+    exhausted: bool,
+}
+
+impl ByteBudget {
+    pub(crate) fn new(max: Option<usize>) -> Self {
+        Self {
+            used: 0,
+            max,
+            exhausted: false,
+        }
+    }
+
+    /// Accounts for a record of type `T`, which is kept regardless of the budget.
+    pub(crate) fn charge<T>(&mut self) {
+        self.used += std::mem::size_of::<T>();
+    }
+
+    /// Accounts for a payload of `len` bytes if it fits in the budget, and
+    /// returns false if it has to be dropped.
+    pub(crate) fn reserve(&mut self, len: usize) -> bool {
+        if self.max.is_some_and(|max| self.used + len > max) {
+            self.exhausted = true;
+            return false;
+        }
+        self.used += len;
+        true
+    }
+
+    /// Captures up to `max` bytes of a frame's output, keeping only its length
+    /// and hash if they do not fit in the budget or the frame is redacted.
+    pub(crate) fn keep_output(
+        &mut self,
+        output: &[u8],
+        max: usize,
+        redacted: bool,
+    ) -> Option<ReturnData> {
+        let mut output = ReturnData::capture(output, max)?;
+        if redacted || !self.reserve(output.data.len()) {
+            output.clear();
+        }
+        Some(output)
+    }
+
+    /// Returns `data` if it fits in the budget, or empty bytes.
+    pub(crate) fn keep(&mut self, data: &Bytes) -> Bytes {
+        if self.reserve(data.len()) {
+            data.clone()
+        } else {
+            Bytes::new()
+        }
+    }
+
+    /// Returns true if a payload was dropped.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
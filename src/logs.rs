@@ -0,0 +1,75 @@
+//! Logs collected during execution.
+
+use alloy_primitives::{Address, Bytes, Log, B256};
+use serde::{Deserialize, Serialize};
+
+use crate::abi::{DecodedLog, EventDecoder};
+
+/// A log emitted during execution, in emission order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectedLog {
+    /// Contract that emitted the log
+    pub address: Address,
+    /// Indexed topics, `topics[0]` is usually the event signature hash
+    pub topics: Vec<B256>,
+    /// Non-indexed data
+    pub data: Bytes,
+    /// Call depth of the emitting frame, the top-level frame is at depth 0
+    pub call_depth: u64,
+    /// Index of the `LOG` instruction among the steps counted so far
+    pub step_index: u64,
+    /// Whether the emitting frame, or one of its parents, reverted
+    pub reverted: bool,
+    /// Index of the transaction the log belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+    /// The log decoded with the declaration of its event, if an
+    /// [`EventDecoder`] knows it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<DecodedLog>,
+    /// Why the log could not be decoded although its topic0 matches a known
+    /// event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decode_error: Option<String>,
+    /// Source of the report the record was merged from, see
+    /// [`InspectorReport::with_source_tag`](crate::InspectorReport::with_source_tag)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<String>,
+}
+
+impl CollectedLog {
+    pub(crate) fn new(log: &Log, call_depth: u64, step_index: u64, tx_index: u64) -> Self {
+        Self {
+            address: log.address,
+            topics: log.topics().to_vec(),
+            data: log.data.data.clone(),
+            call_depth,
+            step_index,
+            reverted: false,
+            tx_index,
+            seq: 0,
+            decoded: None,
+            decode_error: None,
+            source_tag: None,
+        }
+    }
+
+    /// Returns the first topic, if the log has any.
+    pub fn topic0(&self) -> Option<B256> {
+        self.topics.first().copied()
+    }
+
+    /// Decodes the log with `decoder`, filling in `decoded` or, if its event
+    /// is known but the log does not match the declaration, `decode_error`.
+    pub fn decode_with(&mut self, decoder: &EventDecoder) {
+        match decoder.decode(&self.topics, &self.data) {
+            Some(Ok(decoded)) => self.decoded = Some(decoded),
+            Some(Err(err)) => self.decode_error = Some(err.to_string()),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Memory expansion per frame.
+//!
+//! Every frame has its own memory, which only grows, and each instruction
+//! that grows it pays for the new words on top of its own cost. The
+//! inspector keeps, for every frame that used memory, the largest size it
+//! reached and the expansion gas it paid, worked out from the memory size
+//! before and after each instruction. Single instructions that grow memory
+//! by more than
+//! [`memory_expansion_threshold`](crate::HelloWorldInspectorConfig::memory_expansion_threshold)
+//! bytes are recorded as [`MemoryExpansion`]s with their pc. Memory is only
+//! measured when every step is recorded.
+
+use std::cmp::Reverse;
+
+use alloy_primitives::Address;
+use revm::interpreter::gas::memory_gas_for_len;
+use serde::{Deserialize, Serialize};
+
+/// Single expansions above this many bytes are flagged by default.
+pub const DEFAULT_MEMORY_EXPANSION_THRESHOLD: u64 = 32 * 1024;
+
+/// Memory use of a call or create frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameMemory {
+    /// Called address; for creations the address the contract was deployed
+    /// at, or the creator if unknown
+    pub address: Address,
+    /// Call depth of the frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Largest size the frame's memory reached, in bytes
+    pub high_water_bytes: u64,
+    /// Gas the frame paid to expand its memory
+    pub expansion_gas: u64,
+    /// Index of the transaction the frame belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// An instruction that grew memory by more than the threshold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryExpansion {
+    /// Contract executing the instruction; under `DELEGATECALL` this is the
+    /// calling proxy
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// The instruction's opcode
+    pub opcode: u8,
+    /// Call depth of the frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Memory size before the instruction, in bytes
+    pub from_bytes: u64,
+    /// Memory size after the instruction, in bytes
+    pub to_bytes: u64,
+    /// Gas paid for the expansion
+    pub gas: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns the gas charged for growing memory from `from` to `to` bytes.
+pub(crate) fn expansion_gas(from: usize, to: usize) -> u64 {
+    memory_gas_for_len(to).saturating_sub(memory_gas_for_len(from))
+}
+
+/// Returns the `n` frames with the largest memory, largest first; ties keep
+/// the order the frames ended in.
+pub(crate) fn top_frames(frames: &[FrameMemory], n: usize) -> Vec<&FrameMemory> {
+    let mut frames: Vec<_> = frames.iter().collect();
+    frames.sort_by_key(|frame| Reverse(frame.high_water_bytes));
+    frames.truncate(n);
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expansion_gas_is_quadratic() {
+        // 3 gas per word plus words² / 512
+        assert_eq!(expansion_gas(0, 32), 3);
+        assert_eq!(expansion_gas(0, 1024 * 32), 3 * 1024 + 1024 * 1024 / 512);
+        assert_eq!(expansion_gas(32, 64), 3);
+        assert_eq!(expansion_gas(64, 64), 0);
+    }
+}
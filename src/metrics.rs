@@ -0,0 +1,95 @@
+//! Prometheus-style metrics of the inspector hooks.
+//!
+//! With the `metrics` feature, [`InspectorMetrics`] records these through the
+//! `metrics` facade, so they end up in whichever recorder is installed (reth
+//! installs a Prometheus exporter), each labeled with `plugin`:
+//!
+//! | Metric                      | Kind      | Updated on                  |
+//! |-----------------------------|-----------|-----------------------------|
+//! | `restd_steps_total`         | counter   | every traced instruction    |
+//! | `restd_calls_total`         | counter   | every traced call           |
+//! | `restd_reverts_total`       | counter   | every reverted frame        |
+//! | `restd_call_gas_used`       | histogram | every ended call, gas spent |
+//! | `restd_selfdestructs_total` | counter   | every `SELFDESTRUCT`        |
+//!
+//! The handles are registered when the inspector is created, so the
+//! recorder has to be installed first. Without the feature the struct is
+//! empty and every method compiles to nothing.
+
+#[cfg(feature = "metrics")]
+use ::metrics::{counter, histogram, Counter, Histogram};
+
+#[cfg(feature = "metrics")]
+use crate::plugin::PLUGIN_NAME;
+
+/// Metric handles updated by the inspector hooks.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone)]
+pub(crate) struct InspectorMetrics {
+    steps: Counter,
+    calls: Counter,
+    reverts: Counter,
+    call_gas_used: Histogram,
+    selfdestructs: Counter,
+}
+
+#[cfg(feature = "metrics")]
+impl Default for InspectorMetrics {
+    fn default() -> Self {
+        let labels = [("plugin", PLUGIN_NAME)];
+        Self {
+            steps: counter!("restd_steps_total", &labels),
+            calls: counter!("restd_calls_total", &labels),
+            reverts: counter!("restd_reverts_total", &labels),
+            call_gas_used: histogram!("restd_call_gas_used", &labels),
+            selfdestructs: counter!("restd_selfdestructs_total", &labels),
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl InspectorMetrics {
+    pub(crate) fn step(&self) {
+        self.steps.increment(1);
+    }
+
+    pub(crate) fn call(&self) {
+        self.calls.increment(1);
+    }
+
+    pub(crate) fn call_end(&self, gas_used: u64) {
+        self.call_gas_used.record(gas_used as f64);
+    }
+
+    pub(crate) fn revert(&self) {
+        self.reverts.increment(1);
+    }
+
+    pub(crate) fn selfdestruct(&self) {
+        self.selfdestructs.increment(1);
+    }
+}
+
+/// Metric handles updated by the inspector hooks; disabled without the
+/// `metrics` feature.
+#[cfg(not(feature = "metrics"))]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InspectorMetrics;
+
+#[cfg(not(feature = "metrics"))]
+impl InspectorMetrics {
+    #[inline(always)]
+    pub(crate) fn step(&self) {}
+
+    #[inline(always)]
+    pub(crate) fn call(&self) {}
+
+    #[inline(always)]
+    pub(crate) fn call_end(&self, _gas_used: u64) {}
+
+    #[inline(always)]
+    pub(crate) fn revert(&self) {}
+
+    #[inline(always)]
+    pub(crate) fn selfdestruct(&self) {}
+}
@@ -0,0 +1,169 @@
+//! Answering calls with canned return data instead of executing them.
+//!
+//! A [`MockRegistry`] maps a target and function selector to the data its
+//! calls return, so contracts that depend on oracles or other external
+//! contracts can be traced without deploying them. The inspector returns a
+//! successful outcome carrying that data from its `call` hook and the callee
+//! never runs. Mocked calls spend a flat amount of gas and are marked
+//! `mocked` in their call records.
+
+use alloy_primitives::{Address, Bytes, Selector};
+use revm::interpreter::{CallInputs, CallOutcome, Gas, InstructionResult, InterpreterResult};
+
+use crate::selector;
+
+/// A mocked function and the data its calls return, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MockedFunction {
+    target: Address,
+    selector: Selector,
+    /// Return data of each call; the last one is repeated once the others
+    /// are used up
+    returns: Vec<Bytes>,
+    /// Number of calls answered so far
+    calls: u64,
+}
+
+/// Functions whose calls are answered with fixed return data.
+///
+/// ```
+/// use alloy_primitives::{Address, Bytes};
+/// use restd::MockRegistry;
+///
+/// let oracle = Address::repeat_byte(0x0a);
+/// let latest_answer = [0x50, 0xd2, 0x5b, 0xcd];
+/// let mocks = MockRegistry::new()
+///     .mock(oracle, latest_answer, Bytes::from(vec![0; 32]))
+///     .gas(2_600);
+/// assert_eq!(mocks.times_called(oracle, latest_answer), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MockRegistry {
+    functions: Vec<MockedFunction>,
+    /// Gas every mocked call spends
+    gas: u64,
+}
+
+impl MockRegistry {
+    /// Creates a registry without mocks, whose calls spend no gas.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers calls to the function with `selector` at `target` with
+    /// `return_data`. Mocking the same function again queues the data for
+    /// the following call, see [`mock_sequence`](Self::mock_sequence).
+    pub fn mock(
+        self,
+        target: Address,
+        selector: impl Into<Selector>,
+        return_data: impl Into<Bytes>,
+    ) -> Self {
+        self.mock_sequence(target, selector, [return_data.into()])
+    }
+
+    /// Answers successive calls to the function with `selector` at `target`
+    /// with the items of `returns` in turn, repeating the last one once they
+    /// are used up.
+    pub fn mock_sequence(
+        mut self,
+        target: Address,
+        selector: impl Into<Selector>,
+        returns: impl IntoIterator<Item = Bytes>,
+    ) -> Self {
+        let selector = selector.into();
+        let function = match self.find(target, selector) {
+            Some(index) => &mut self.functions[index],
+            None => {
+                self.functions.push(MockedFunction {
+                    target,
+                    selector,
+                    returns: Vec::new(),
+                    calls: 0,
+                });
+                self.functions.last_mut().expect("just pushed")
+            }
+        };
+        function.returns.extend(returns);
+        self
+    }
+
+    /// Sets the gas every mocked call spends; calls given less run out of
+    /// gas instead.
+    pub fn gas(mut self, gas: u64) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    /// Returns how many calls to the function with `selector` at `target`
+    /// were answered, 0 if it is not mocked.
+    pub fn times_called(&self, target: Address, selector: impl Into<Selector>) -> u64 {
+        self.find(target, selector.into())
+            .map_or(0, |index| self.functions[index].calls)
+    }
+
+    /// Returns true if no function is mocked.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Rewinds every sequence to its first return data and the counts to 0.
+    pub(crate) fn rewind(&mut self) {
+        for function in &mut self.functions {
+            function.calls = 0;
+        }
+    }
+
+    /// Answers the call if its function is mocked, counting it.
+    pub(crate) fn respond(&mut self, inputs: &CallInputs) -> Option<CallOutcome> {
+        let selector = selector::selector_of(&inputs.input)?;
+        let index = self.find(inputs.target_address, selector.into())?;
+        let function = &mut self.functions[index];
+        let position = (function.calls as usize).min(function.returns.len().saturating_sub(1));
+        let data = function.returns.get(position).cloned().unwrap_or_default();
+        function.calls += 1;
+
+        let mut gas = Gas::new(inputs.gas_limit);
+        let result = if gas.record_cost(self.gas) {
+            InterpreterResult::new(InstructionResult::Return, data, gas)
+        } else {
+            InterpreterResult::new(
+                InstructionResult::OutOfGas,
+                Bytes::new(),
+                Gas::new_spent(inputs.gas_limit),
+            )
+        };
+        Some(CallOutcome::new(
+            result,
+            inputs.return_memory_offset.clone(),
+        ))
+    }
+
+    fn find(&self, target: Address, selector: Selector) -> Option<usize> {
+        self.functions
+            .iter()
+            .position(|function| function.target == target && function.selector == selector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mocking_a_function_again_extends_its_sequence() {
+        let target = Address::repeat_byte(0x0a);
+        let mocks = MockRegistry::new()
+            .mock(target, [1, 2, 3, 4], Bytes::from_static(&[1]))
+            .mock_sequence(
+                target,
+                [1, 2, 3, 4],
+                [Bytes::from_static(&[2]), Bytes::from_static(&[3])],
+            )
+            .mock(target, [5, 6, 7, 8], Bytes::new());
+        assert_eq!(mocks.functions.len(), 2);
+        assert_eq!(mocks.functions[0].returns.len(), 3);
+        assert_eq!(mocks.times_called(target, [1, 2, 3, 4]), 0);
+        assert_eq!(mocks.times_called(Address::ZERO, [1, 2, 3, 4]), 0);
+    }
+}
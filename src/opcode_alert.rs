@@ -0,0 +1,29 @@
+//! Alerts on watched opcodes.
+//!
+//! Every executed opcode listed in
+//! [`alert_on_opcodes`](crate::HelloWorldInspectorConfig::alert_on_opcodes)
+//! is recorded as an [`OpcodeAlert`] and sent to the sink right away with
+//! [`TraceSink::send_alert`](crate::TraceSink::send_alert), so monitoring can
+//! react before the transaction finishes.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// A watched opcode that was about to execute.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpcodeAlert {
+    /// The watched opcode
+    pub opcode: u8,
+    /// Contract executing the opcode; under `DELEGATECALL` this is the
+    /// calling proxy
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// Call depth of the executing frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Number of steps recorded before the instruction
+    pub step_index: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
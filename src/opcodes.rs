@@ -0,0 +1,174 @@
+//! Names of the EVM opcodes.
+//!
+//! A single table maps opcode bytes to the names revm uses, both ways. It
+//! names opcodes in reports, struct logs and disassembly, and parses the
+//! opcodes of configuration such as
+//! [`alert_on_opcodes`](crate::HelloWorldInspectorConfig::alert_on_opcodes).
+//! [`introduced_in`] tells which hardfork made an opcode valid.
+
+use std::collections::BTreeSet;
+
+use revm::primitives::SpecId;
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+/// Every opcode defined by the latest spec revm supports, in byte order.
+pub const OPCODES: [(u8, &str); 168] = [
+    (0x00, "STOP"), (0x01, "ADD"), (0x02, "MUL"), (0x03, "SUB"), (0x04, "DIV"), (0x05, "SDIV"),
+    (0x06, "MOD"), (0x07, "SMOD"), (0x08, "ADDMOD"), (0x09, "MULMOD"), (0x0a, "EXP"),
+    (0x0b, "SIGNEXTEND"), (0x10, "LT"), (0x11, "GT"), (0x12, "SLT"), (0x13, "SGT"), (0x14, "EQ"),
+    (0x15, "ISZERO"), (0x16, "AND"), (0x17, "OR"), (0x18, "XOR"), (0x19, "NOT"), (0x1a, "BYTE"),
+    (0x1b, "SHL"), (0x1c, "SHR"), (0x1d, "SAR"), (0x20, "KECCAK256"), (0x30, "ADDRESS"),
+    (0x31, "BALANCE"), (0x32, "ORIGIN"), (0x33, "CALLER"), (0x34, "CALLVALUE"),
+    (0x35, "CALLDATALOAD"), (0x36, "CALLDATASIZE"), (0x37, "CALLDATACOPY"), (0x38, "CODESIZE"),
+    (0x39, "CODECOPY"), (0x3a, "GASPRICE"), (0x3b, "EXTCODESIZE"), (0x3c, "EXTCODECOPY"),
+    (0x3d, "RETURNDATASIZE"), (0x3e, "RETURNDATACOPY"), (0x3f, "EXTCODEHASH"), (0x40, "BLOCKHASH"),
+    (0x41, "COINBASE"), (0x42, "TIMESTAMP"), (0x43, "NUMBER"), (0x44, "DIFFICULTY"),
+    (0x45, "GASLIMIT"), (0x46, "CHAINID"), (0x47, "SELFBALANCE"), (0x48, "BASEFEE"),
+    (0x49, "BLOBHASH"), (0x4a, "BLOBBASEFEE"), (0x50, "POP"), (0x51, "MLOAD"), (0x52, "MSTORE"),
+    (0x53, "MSTORE8"), (0x54, "SLOAD"), (0x55, "SSTORE"), (0x56, "JUMP"), (0x57, "JUMPI"),
+    (0x58, "PC"), (0x59, "MSIZE"), (0x5a, "GAS"), (0x5b, "JUMPDEST"), (0x5c, "TLOAD"),
+    (0x5d, "TSTORE"), (0x5e, "MCOPY"), (0x5f, "PUSH0"), (0x60, "PUSH1"), (0x61, "PUSH2"),
+    (0x62, "PUSH3"), (0x63, "PUSH4"), (0x64, "PUSH5"), (0x65, "PUSH6"), (0x66, "PUSH7"),
+    (0x67, "PUSH8"), (0x68, "PUSH9"), (0x69, "PUSH10"), (0x6a, "PUSH11"), (0x6b, "PUSH12"),
+    (0x6c, "PUSH13"), (0x6d, "PUSH14"), (0x6e, "PUSH15"), (0x6f, "PUSH16"), (0x70, "PUSH17"),
+    (0x71, "PUSH18"), (0x72, "PUSH19"), (0x73, "PUSH20"), (0x74, "PUSH21"), (0x75, "PUSH22"),
+    (0x76, "PUSH23"), (0x77, "PUSH24"), (0x78, "PUSH25"), (0x79, "PUSH26"), (0x7a, "PUSH27"),
+    (0x7b, "PUSH28"), (0x7c, "PUSH29"), (0x7d, "PUSH30"), (0x7e, "PUSH31"), (0x7f, "PUSH32"),
+    (0x80, "DUP1"), (0x81, "DUP2"), (0x82, "DUP3"), (0x83, "DUP4"), (0x84, "DUP5"), (0x85, "DUP6"),
+    (0x86, "DUP7"), (0x87, "DUP8"), (0x88, "DUP9"), (0x89, "DUP10"), (0x8a, "DUP11"),
+    (0x8b, "DUP12"), (0x8c, "DUP13"), (0x8d, "DUP14"), (0x8e, "DUP15"), (0x8f, "DUP16"),
+    (0x90, "SWAP1"), (0x91, "SWAP2"), (0x92, "SWAP3"), (0x93, "SWAP4"), (0x94, "SWAP5"),
+    (0x95, "SWAP6"), (0x96, "SWAP7"), (0x97, "SWAP8"), (0x98, "SWAP9"), (0x99, "SWAP10"),
+    (0x9a, "SWAP11"), (0x9b, "SWAP12"), (0x9c, "SWAP13"), (0x9d, "SWAP14"), (0x9e, "SWAP15"),
+    (0x9f, "SWAP16"), (0xa0, "LOG0"), (0xa1, "LOG1"), (0xa2, "LOG2"), (0xa3, "LOG3"),
+    (0xa4, "LOG4"), (0xd0, "DATALOAD"), (0xd1, "DATALOADN"), (0xd2, "DATASIZE"),
+    (0xd3, "DATACOPY"), (0xe0, "RJUMP"), (0xe1, "RJUMPI"), (0xe2, "RJUMPV"), (0xe3, "CALLF"),
+    (0xe4, "RETF"), (0xe5, "JUMPF"), (0xe6, "DUPN"), (0xe7, "SWAPN"), (0xe8, "EXCHANGE"),
+    (0xec, "EOFCREATE"), (0xee, "RETURNCONTRACT"), (0xf0, "CREATE"), (0xf1, "CALL"),
+    (0xf2, "CALLCODE"), (0xf3, "RETURN"), (0xf4, "DELEGATECALL"), (0xf5, "CREATE2"),
+    (0xf7, "RETURNDATALOAD"), (0xf8, "EXTCALL"), (0xf9, "EXTDELEGATECALL"), (0xfa, "STATICCALL"),
+    (0xfb, "EXTSTATICCALL"), (0xfd, "REVERT"), (0xfe, "INVALID"), (0xff, "SELFDESTRUCT"),
+];
+
+/// [`OPCODES`] indexed by byte.
+const NAMES: [Option<&str>; 256] = {
+    let mut names = [None; 256];
+    let mut i = 0;
+    while i < OPCODES.len() {
+        names[OPCODES[i].0 as usize] = Some(OPCODES[i].1);
+        i += 1;
+    }
+    names
+};
+
+/// Returns the name of `op`, or `None` if the byte is not a defined opcode.
+pub fn name(op: u8) -> Option<&'static str> {
+    NAMES[op as usize]
+}
+
+/// Returns the byte of the opcode called `name`, ignoring case.
+pub fn by_name(name: &str) -> Option<u8> {
+    OPCODES
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(name))
+        .map(|(op, _)| *op)
+}
+
+/// Parses an opcode given by name, e.g. `DELEGATECALL`, or as a hex byte,
+/// e.g. `0xf4`. Hex bytes need not be defined opcodes.
+pub fn parse(s: &str) -> Option<u8> {
+    let s = s.trim();
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => by_name(s),
+    }
+}
+
+/// Returns the display name of an opcode byte, or its hex value if the
+/// byte is not a defined opcode.
+pub fn display_name(op: u8) -> String {
+    match name(op) {
+        Some(name) => name.to_string(),
+        None => format!("0x{op:02x}"),
+    }
+}
+
+/// Returns the hardfork that introduced an opcode, or `None` if the byte is
+/// not a defined opcode.
+///
+/// The EOF-only opcodes, e.g. `RJUMP` or `EXTCALL`, date from
+/// [`SpecId::PRAGUE_EOF`] but are only valid in EOF containers.
+pub fn introduced_in(op: u8) -> Option<SpecId> {
+    name(op)?;
+    Some(match op {
+        0xf4 => SpecId::HOMESTEAD,
+        0x3d | 0x3e | 0xfa | 0xfd => SpecId::BYZANTIUM,
+        0x1b..=0x1d | 0x3f | 0xf5 => SpecId::CONSTANTINOPLE,
+        0x46 | 0x47 => SpecId::ISTANBUL,
+        0x48 => SpecId::LONDON,
+        0x5f => SpecId::SHANGHAI,
+        0x49 | 0x4a | 0x5c..=0x5e => SpecId::CANCUN,
+        0xd0..=0xd3 | 0xe0..=0xee | 0xf7..=0xf9 | 0xfb => SpecId::PRAGUE_EOF,
+        _ => SpecId::FRONTIER,
+    })
+}
+
+/// Serde representation of a set of opcodes as a list of names, accepting
+/// names or hex bytes when deserializing.
+pub(crate) mod set {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        ops: &BTreeSet<u8>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(ops.iter().map(|op| display_name(*op)))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeSet<u8>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|op| {
+                parse(op).ok_or_else(|| de::Error::custom(format!("unknown opcode `{op}`")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::interpreter::OpCode;
+
+    use super::*;
+
+    #[test]
+    fn test_table_matches_revm() {
+        for op in 0..=u8::MAX {
+            assert_eq!(name(op), OpCode::new(op).map(OpCode::as_str), "{op:#04x}");
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("DELEGATECALL"), Some(0xf4));
+        assert_eq!(parse("selfdestruct"), Some(0xff));
+        assert_eq!(parse("0xF5"), Some(0xf5));
+        assert_eq!(parse("0x0c"), Some(0x0c));
+        assert_eq!(parse("SHA3"), None);
+        assert_eq!(parse("0x100"), None);
+        assert_eq!(display_name(0x0c), "0x0c");
+    }
+
+    #[test]
+    fn test_introduced_in() {
+        assert_eq!(introduced_in(0x01), Some(SpecId::FRONTIER));
+        assert_eq!(introduced_in(0xf4), Some(SpecId::HOMESTEAD));
+        assert_eq!(introduced_in(0xf5), Some(SpecId::CONSTANTINOPLE));
+        assert_eq!(introduced_in(0x5f), Some(SpecId::SHANGHAI));
+        assert_eq!(introduced_in(0x5c), Some(SpecId::CANCUN));
+        assert_eq!(introduced_in(0xf8), Some(SpecId::PRAGUE_EOF));
+        assert_eq!(introduced_in(0x0c), None);
+    }
+}
@@ -0,0 +1,37 @@
+//! Reads of `tx.origin`.
+//!
+//! Authorizing callers with `tx.origin` lets any contract the owner
+//! interacts with act on their behalf, so every executed `ORIGIN` is
+//! recorded. An `ORIGIN` directly followed by `EQ`, as compiled from e.g.
+//! `require(tx.origin == msg.sender)`, is flagged as a comparison.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// How the value pushed by `ORIGIN` was used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginUsageKind {
+    /// `tx.origin` was read
+    Read,
+    /// `tx.origin` was compared right away, which suggests it is used for
+    /// authorization
+    Comparison,
+}
+
+/// An executed `ORIGIN` instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OriginUsage {
+    /// Contract executing the instruction; under `DELEGATECALL` this is the
+    /// calling proxy
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// Whether the next instruction is an `EQ`
+    pub kind: OriginUsageKind,
+    /// Call depth of the executing frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
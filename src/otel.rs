@@ -0,0 +1,246 @@
+//! Exporting call frames as OpenTelemetry spans.
+//!
+//! With the `otel` feature, an [`OtelSink`] attached with
+//! [`HelloWorldInspector::with_sink`](crate::HelloWorldInspector::with_sink)
+//! turns the frame events of the inspector into a span tree: every
+//! transaction is a `transaction` span and every call or create frame a child
+//! of the frame that started it.
+//!
+//! | Span          | Name                       | Attributes                                      |
+//! |---------------|----------------------------|-------------------------------------------------|
+//! | transaction   | `transaction`              | `from`, `to`                                    |
+//! | call frame    | label or called address    | `selector`, `value`, `gas_limit`, `depth`       |
+//! | create frame  | created address, `create`  | `value`, `gas_limit`, `depth`                   |
+//!
+//! As in the call records, `depth` is 0 for the top-level frame. Frames end
+//! with `gas_used`, plus `revert_reason` if they reverted, and an error
+//! status if they failed. The transaction span ends with its top-level frame
+//! and takes over its status.
+//!
+//! ```no_run
+//! use opentelemetry::global;
+//! use restd::{otel::OtelSink, HelloWorldInspector};
+//!
+//! let inspector = HelloWorldInspector::new().with_sink(OtelSink::new(global::tracer("restd")));
+//! ```
+
+use std::fmt;
+
+use alloy_primitives::Address;
+use opentelemetry::{
+    trace::{SpanBuilder, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+
+use crate::{selector::selector_hex, TraceEvent, TraceSink};
+
+/// Description of the error status of spans whose frame never ended.
+const UNFINISHED: &str = "frame did not end";
+
+/// A [`TraceSink`] that turns call frames into OpenTelemetry spans.
+///
+/// Spans still open when a new transaction starts or the sink is dropped,
+/// e.g. because execution was aborted, are ended with an error status.
+pub struct OtelSink<T> {
+    tracer: T,
+    /// Context the transaction spans are children of
+    parent: Context,
+    /// Open spans with the depth of their frame, innermost last; the
+    /// transaction span is at depth 0
+    spans: Vec<(u64, Context)>,
+}
+
+impl<T> OtelSink<T>
+where
+    T: Tracer,
+    T::Span: Send + Sync + 'static,
+{
+    /// Creates a sink whose transaction spans are children of the span
+    /// active when it is created, if any.
+    pub fn new(tracer: T) -> Self {
+        Self::with_parent(tracer, Context::current())
+    }
+
+    /// Creates a sink whose transaction spans are children of the span in
+    /// `parent`, if any.
+    pub fn with_parent(tracer: T, parent: Context) -> Self {
+        Self {
+            tracer,
+            parent,
+            spans: Vec::new(),
+        }
+    }
+
+    /// Returns the number of open spans, the transaction span included.
+    pub fn open_spans(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Starts the span of a frame at `depth`, starting the transaction span
+    /// first if there is none.
+    fn start_frame(
+        &mut self,
+        depth: u64,
+        name: String,
+        caller: Address,
+        to: Option<Address>,
+        attributes: Vec<KeyValue>,
+    ) {
+        // Spans at the same depth or deeper belong to frames that never
+        // ended, and a top-level frame starts a new transaction
+        self.close_from(if depth <= 1 { 0 } else { depth });
+        if self.spans.is_empty() {
+            let mut attributes = vec![KeyValue::new("from", caller.to_string())];
+            attributes.extend(to.map(|to| KeyValue::new("to", to.to_string())));
+            let span = self.tracer.build_with_context(
+                SpanBuilder::from_name("transaction").with_attributes(attributes),
+                &self.parent,
+            );
+            self.spans.push((0, self.parent.with_span(span)));
+        }
+        let parent = &self.spans.last().expect("transaction span is open").1;
+        let span = self.tracer.build_with_context(
+            SpanBuilder::from_name(name).with_attributes(attributes),
+            parent,
+        );
+        let cx = parent.with_span(span);
+        self.spans.push((depth, cx));
+    }
+
+    /// Ends the span of the frame at `depth`, and the transaction span with
+    /// the top-level frame.
+    fn end_frame(
+        &mut self,
+        depth: u64,
+        success: bool,
+        gas_used: u64,
+        revert_reason: Option<String>,
+        created: Option<Address>,
+    ) {
+        self.close_from(depth + 1);
+        if !matches!(self.spans.last(), Some((open, _)) if *open == depth) {
+            return;
+        }
+        let status = if success {
+            Status::Ok
+        } else {
+            Status::error(
+                revert_reason
+                    .clone()
+                    .unwrap_or_else(|| "failed".to_string()),
+            )
+        };
+        let (_, cx) = self.spans.pop().expect("span at depth is open");
+        let span = cx.span();
+        if let Some(created) = created {
+            span.update_name(created.to_string());
+        }
+        span.set_attribute(KeyValue::new("gas_used", gas_used as i64));
+        if let Some(reason) = revert_reason {
+            span.set_attribute(KeyValue::new("revert_reason", reason));
+        }
+        span.set_status(status.clone());
+        span.end();
+        if depth == 1 {
+            if let Some((_, cx)) = self.spans.pop() {
+                let span = cx.span();
+                if let Some(created) = created {
+                    span.set_attribute(KeyValue::new("created", created.to_string()));
+                }
+                span.set_status(status);
+                span.end();
+            }
+            self.close_from(0);
+        }
+    }
+}
+
+impl<T> TraceSink for OtelSink<T>
+where
+    T: Tracer + Send,
+    T::Span: Send + Sync + 'static,
+{
+    fn send(&mut self, event: TraceEvent) -> bool {
+        match event {
+            TraceEvent::CallStart {
+                address,
+                caller,
+                value,
+                gas_limit,
+                depth,
+                selector,
+                label,
+                ..
+            } => {
+                let mut attributes = vec![
+                    KeyValue::new("value", value.to_string()),
+                    KeyValue::new("gas_limit", gas_limit as i64),
+                    KeyValue::new("depth", depth.saturating_sub(1) as i64),
+                ];
+                attributes.extend(
+                    selector.map(|selector| KeyValue::new("selector", selector_hex(selector.0))),
+                );
+                let name = label.unwrap_or_else(|| address.to_string());
+                self.start_frame(depth, name, caller, Some(address), attributes);
+            }
+            TraceEvent::CreateStart {
+                caller,
+                value,
+                gas_limit,
+                depth,
+                ..
+            } => {
+                let attributes = vec![
+                    KeyValue::new("value", value.to_string()),
+                    KeyValue::new("gas_limit", gas_limit as i64),
+                    KeyValue::new("depth", depth.saturating_sub(1) as i64),
+                ];
+                self.start_frame(depth, "create".to_string(), caller, None, attributes);
+            }
+            TraceEvent::CallEnd {
+                success,
+                gas_used,
+                depth,
+                revert_reason,
+                ..
+            } => self.end_frame(depth, success, gas_used, revert_reason, None),
+            TraceEvent::CreateEnd {
+                address,
+                success,
+                gas_used,
+                depth,
+                revert_reason,
+            } => {
+                let created = address.filter(|_| success);
+                self.end_frame(depth, success, gas_used, revert_reason, created);
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+impl<T> OtelSink<T> {
+    /// Ends every open span at `depth` or deeper with an error status.
+    fn close_from(&mut self, depth: u64) {
+        while let Some((_, cx)) = self.spans.pop_if(|(open, _)| *open >= depth) {
+            let span = cx.span();
+            span.set_status(Status::error(UNFINISHED));
+            span.end();
+        }
+    }
+}
+
+impl<T> Drop for OtelSink<T> {
+    fn drop(&mut self) {
+        self.close_from(0);
+    }
+}
+
+impl<T> fmt::Debug for OtelSink<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtelSink")
+            .field("open_spans", &self.spans.len())
+            .finish_non_exhaustive()
+    }
+}
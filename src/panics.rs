@@ -0,0 +1,33 @@
+//! Panics caught in restd code while tracing.
+//!
+//! With [`catch_panics`](crate::HelloWorldInspectorConfig::catch_panics)
+//! set, a panic in a registered callback or in the inspector's own recording
+//! is recorded as an [`InternalError`] and execution continues. Panics raised
+//! by revm itself are never caught.
+
+use std::any::Any;
+
+use serde::{Deserialize, Serialize};
+
+/// A panic caught in a callback or in the inspector's recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalError {
+    /// Where the panic happened, e.g. `step` or `on_step callback #0`
+    pub source: String,
+    /// The panic message
+    pub message: String,
+    /// Index of the transaction that was executing
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns the message of a panic payload, as printed by the default hook.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
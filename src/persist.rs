@@ -0,0 +1,367 @@
+//! Saving reports and call traces to files and loading them back.
+//!
+//! [`InspectorReport::save`] and [`CallTrace::save`] write a small header
+//! followed by the value encoded as CBOR, so a trace can be analyzed later
+//! without the chain state it was recorded against:
+//!
+//! ```text
+//! "RESTD"  kind (1 byte)  format version (u16, big-endian)
+//! crate version length (1 byte)  crate version (UTF-8)  CBOR payload
+//! ```
+//!
+//! CBOR is self-describing, unlike bincode or postcard, which cannot read
+//! back the fields reports leave out when they are empty. Loading refuses
+//! files written with a newer [`FORMAT_VERSION`] than this crate reads.
+//!
+//! Neither type caches indexes: timelines, per-address rankings, frame
+//! iterators and the other derived views are computed from the saved
+//! fields, so a loaded value answers them as the original did.
+//!
+//! Requires the `persist` feature.
+
+use std::{fmt, fs, io, path::Path};
+
+use alloy_primitives::{Address, B256};
+use revm::interpreter::{CallScheme, CreateScheme};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{precompile::precompile_name, CallKind, CallTrace, InspectorReport};
+
+/// Version of the file format written by this crate.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Bytes every saved file starts with.
+const MAGIC: &[u8; 5] = b"RESTD";
+
+/// What a saved file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavedKind {
+    /// An [`InspectorReport`]
+    Report,
+    /// A [`CallTrace`]
+    CallTrace,
+}
+
+impl SavedKind {
+    fn tag(self) -> u8 {
+        match self {
+            SavedKind::Report => b'R',
+            SavedKind::CallTrace => b'T',
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            b'R' => Some(SavedKind::Report),
+            b'T' => Some(SavedKind::CallTrace),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SavedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SavedKind::Report => write!(f, "report"),
+            SavedKind::CallTrace => write!(f, "call trace"),
+        }
+    }
+}
+
+/// The header of a saved file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// What the file holds
+    pub kind: SavedKind,
+    /// Version of the file format
+    pub format_version: u16,
+    /// Version of this crate that wrote the file
+    pub crate_version: String,
+}
+
+impl Header {
+    fn new(kind: SavedKind) -> Self {
+        Self {
+            kind,
+            format_version: FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Reads the header at the start of `bytes`, returning it and the
+    /// payload after it.
+    pub fn read(bytes: &[u8]) -> Result<(Self, &[u8]), PersistError> {
+        let rest = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(PersistError::InvalidHeader("missing magic bytes"))?;
+        let [tag, major, minor, len, rest @ ..] = rest else {
+            return Err(PersistError::InvalidHeader("truncated header"));
+        };
+        let kind = SavedKind::from_tag(*tag).ok_or(PersistError::InvalidHeader("unknown kind"))?;
+        let (version, payload) = rest
+            .split_at_checked(*len as usize)
+            .ok_or(PersistError::InvalidHeader("truncated crate version"))?;
+        let crate_version = std::str::from_utf8(version)
+            .map_err(|_| PersistError::InvalidHeader("crate version is not UTF-8"))?;
+        let header = Self {
+            kind,
+            format_version: u16::from_be_bytes([*major, *minor]),
+            crate_version: crate_version.to_string(),
+        };
+        Ok((header, payload))
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(MAGIC);
+        out.push(self.kind.tag());
+        out.extend_from_slice(&self.format_version.to_be_bytes());
+        // Crate versions are far shorter than 256 bytes
+        let version = &self.crate_version.as_bytes()[..self.crate_version.len().min(255)];
+        out.push(version.len() as u8);
+        out.extend_from_slice(version);
+    }
+}
+
+/// Error saving or loading a report or call trace.
+#[derive(Debug)]
+pub enum PersistError {
+    /// The file could not be read or written
+    Io(io::Error),
+    /// The data does not start with a valid header
+    InvalidHeader(&'static str),
+    /// The file was written with a newer format than this crate reads
+    UnsupportedVersion {
+        /// Format version of the file
+        found: u16,
+        /// Newest format version this crate reads
+        supported: u16,
+        /// Version of the crate that wrote the file
+        written_by: String,
+    },
+    /// The file holds something other than what was loaded
+    WrongKind {
+        /// What was loaded
+        expected: SavedKind,
+        /// What the file holds
+        found: SavedKind,
+    },
+    /// The value could not be encoded
+    Encode(String),
+    /// The payload after the header could not be decoded
+    Decode(String),
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "{err}"),
+            PersistError::InvalidHeader(reason) => {
+                write!(f, "not a saved restd file: {reason}")
+            }
+            PersistError::UnsupportedVersion {
+                found,
+                supported,
+                written_by,
+            } => write!(
+                f,
+                "format version {found} (written by restd {written_by}) is newer than \
+                 version {supported} read by restd {}; upgrade restd to load it",
+                env!("CARGO_PKG_VERSION")
+            ),
+            PersistError::WrongKind { expected, found } => {
+                write!(f, "expected a saved {expected}, found a saved {found}")
+            }
+            PersistError::Encode(err) => write!(f, "failed to encode: {err}"),
+            PersistError::Decode(err) => write!(f, "failed to decode: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PersistError {
+    fn from(err: io::Error) -> Self {
+        PersistError::Io(err)
+    }
+}
+
+/// A value that can be saved.
+trait Persisted: Serialize + DeserializeOwned {
+    const KIND: SavedKind;
+}
+
+impl Persisted for InspectorReport {
+    const KIND: SavedKind = SavedKind::Report;
+}
+
+impl Persisted for CallTrace {
+    const KIND: SavedKind = SavedKind::CallTrace;
+}
+
+fn encode<T: Persisted>(value: &T) -> Result<Vec<u8>, PersistError> {
+    let mut out = Vec::new();
+    Header::new(T::KIND).write(&mut out);
+    ciborium::into_writer(value, &mut out).map_err(|err| PersistError::Encode(err.to_string()))?;
+    Ok(out)
+}
+
+fn decode<T: Persisted>(bytes: &[u8]) -> Result<T, PersistError> {
+    let (header, payload) = Header::read(bytes)?;
+    if header.format_version > FORMAT_VERSION {
+        return Err(PersistError::UnsupportedVersion {
+            found: header.format_version,
+            supported: FORMAT_VERSION,
+            written_by: header.crate_version,
+        });
+    }
+    if header.kind != T::KIND {
+        return Err(PersistError::WrongKind {
+            expected: T::KIND,
+            found: header.kind,
+        });
+    }
+    ciborium::from_reader(payload).map_err(|err| PersistError::Decode(err.to_string()))
+}
+
+impl InspectorReport {
+    /// Encodes the report with a header, as [`save`](Self::save) writes it.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PersistError> {
+        encode(self)
+    }
+
+    /// Decodes a report encoded by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PersistError> {
+        decode(bytes)
+    }
+
+    /// Writes the report to `path`, replacing the file if it exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        Ok(fs::write(path, self.to_bytes()?)?)
+    }
+
+    /// Reads a report written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PersistError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl CallTrace {
+    /// Encodes the trace with a header, as [`save`](Self::save) writes it.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PersistError> {
+        encode(self)
+    }
+
+    /// Decodes a trace encoded by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PersistError> {
+        decode(bytes)
+    }
+
+    /// Writes the trace to `path`, replacing the file if it exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        Ok(fs::write(path, self.to_bytes()?)?)
+    }
+
+    /// Reads a trace written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PersistError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+/// [`CallKind`] as it is saved, with the name of a precompile owned.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CallKind")]
+enum SavedCallKind {
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+    EofCreate { container_hash: Option<B256> },
+    SelfDestruct,
+    Precompile { name: String, scheme: CallScheme },
+    Transfer { scheme: CallScheme },
+    EmptyCreate { scheme: CreateScheme },
+}
+
+impl From<CallKind> for SavedCallKind {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Call => SavedCallKind::Call,
+            CallKind::StaticCall => SavedCallKind::StaticCall,
+            CallKind::DelegateCall => SavedCallKind::DelegateCall,
+            CallKind::CallCode => SavedCallKind::CallCode,
+            CallKind::Create => SavedCallKind::Create,
+            CallKind::Create2 => SavedCallKind::Create2,
+            CallKind::EofCreate { container_hash } => SavedCallKind::EofCreate { container_hash },
+            CallKind::SelfDestruct => SavedCallKind::SelfDestruct,
+            CallKind::Precompile { name, scheme } => SavedCallKind::Precompile {
+                name: name.to_string(),
+                scheme,
+            },
+            CallKind::Transfer { scheme } => SavedCallKind::Transfer { scheme },
+            CallKind::EmptyCreate { scheme } => SavedCallKind::EmptyCreate { scheme },
+        }
+    }
+}
+
+impl From<SavedCallKind> for CallKind {
+    fn from(kind: SavedCallKind) -> Self {
+        match kind {
+            SavedCallKind::Call => CallKind::Call,
+            SavedCallKind::StaticCall => CallKind::StaticCall,
+            SavedCallKind::DelegateCall => CallKind::DelegateCall,
+            SavedCallKind::CallCode => CallKind::CallCode,
+            SavedCallKind::Create => CallKind::Create,
+            SavedCallKind::Create2 => CallKind::Create2,
+            SavedCallKind::EofCreate { container_hash } => CallKind::EofCreate { container_hash },
+            SavedCallKind::SelfDestruct => CallKind::SelfDestruct,
+            // Precompile names are static; map the saved one back to the
+            // name this crate knows it by
+            SavedCallKind::Precompile { name, scheme } => CallKind::Precompile {
+                name: (1..=u8::MAX)
+                    .map(|byte| precompile_name(&Address::with_last_byte(byte)))
+                    .take_while(|known| *known != "unknown")
+                    .find(|known| *known == name)
+                    .unwrap_or("unknown"),
+                scheme,
+            },
+            SavedCallKind::Transfer { scheme } => CallKind::Transfer { scheme },
+            SavedCallKind::EmptyCreate { scheme } => CallKind::EmptyCreate { scheme },
+        }
+    }
+}
+
+impl Serialize for CallKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedCallKind::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CallKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SavedCallKind::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_round_trip() {
+        let mut bytes = Vec::new();
+        Header::new(SavedKind::CallTrace).write(&mut bytes);
+        bytes.push(0xf6);
+        let (header, payload) = Header::read(&bytes).unwrap();
+        assert_eq!(header, Header::new(SavedKind::CallTrace));
+        assert_eq!(payload, [0xf6]);
+    }
+}
@@ -1,14 +1,66 @@
-use std::fmt;
+use std::{collections::BTreeSet, fmt, path::PathBuf, sync::Arc};
+use alloy_primitives::Address;
 use revm::Database;
 use tracing::info;
+#[cfg(feature = "std")]
+use tracing::warn;
 use serde::{Deserialize, Serialize};
 
-use crate::HelloWorldInspector;
+#[cfg(feature = "std")]
+use crate::{FileTraceSink, ReportWriter};
+use crate::{
+    aggregate::AggregateCounters, health::PluginState, metrics::InspectorMetrics, memory::DEFAULT_MEMORY_EXPANSION_THRESHOLD,
+    precompile::DEFAULT_PRECOMPILE_UPPER_BOUND, redact::Redaction,
+    reload::SharedConfig, returndata::DEFAULT_MAX_OUTPUT_BYTES, unchecked_call::DEFAULT_UNCHECKED_CALL_WINDOW,
+    AddressFilter, AggregateReport, ConfigError, Error, FaultInjector, Format, HelloWorldInspector, InconsistencyAction, HelloWorldInspectorBuilder,
+    InspectorProfile, InspectorReport, Labels, PluginHealth, PluginRegistry, PluginReport, ProfilingMode, SelectorResolver, StackedInspector, TraceLimits,
+};
+
+/// A named source of inspectors that can be registered in a [`PluginRegistry`]
+pub trait InspectorPlugin<DB: Database>: fmt::Debug + Send + Sync {
+    /// Unique name the plugin is registered under
+    fn name(&self) -> &str;
+
+    /// Validates the node-supplied plugin configuration before any inspector is created
+    /// Plugins whose errors are not covered by [`Error`] wrap them with
+    /// [`Error::plugin`].
+    fn init(&self, config: &serde_json::Value) -> Result<(), Error>;
+
+    /// Creates a fresh inspector for one execution
+    fn create_inspector(&self) -> Box<dyn StackedInspector<DB>>;
+
+    /// Called when tracing begins, after [`init`](Self::init)
+    fn on_start(&self) {}
+
+    /// Called when tracing ends; flushes whatever the plugin buffered
+    fn on_shutdown(&self) {}
+
+    /// Reports whether the plugin is working, healthy by default
+    fn health(&self) -> PluginHealth {
+        PluginHealth::healthy()
+    }
+}
+
+/// Name of [`HelloWorldInspectorPlugin`], also used to label its metrics
+pub(crate) const PLUGIN_NAME: &str = "hello-world-inspector";
 
 /// Plugin that registers the HelloWorldInspector with reth
 #[derive(Debug, Default, Clone)]
 pub struct HelloWorldInspectorPlugin {
-    config: HelloWorldInspectorConfig,
+    /// Configuration shared with the inspectors it created, see
+    /// [`reload`](Self::reload)
+    config: Arc<SharedConfig>,
+    /// Sink, counters and errors shared with the inspectors it created
+    state: Arc<PluginState>,
+    /// Names of addresses used by the inspectors it created
+    labels: Option<Arc<Labels>>,
+    /// Resolves call selectors for the inspectors it created
+    selector_resolver: Option<Arc<dyn SelectorResolver>>,
+    /// Metric handles updated by the inspectors it created, registered once
+    metrics: InspectorMetrics,
+    /// Totals of the inspectors it created, see
+    /// [`aggregate_report`](Self::aggregate_report)
+    aggregate: Arc<AggregateCounters>,
 }
 
 impl fmt::Display for HelloWorldInspectorPlugin {
@@ -18,74 +70,455 @@ impl fmt::Display for HelloWorldInspectorPlugin {
 }
 
 /// Configuration for the HelloWorldInspector plugin
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+///
+/// Missing fields take their default values, or those of `profile` if one
+/// is given, and unknown fields are rejected.
+// The derived implementations are wrapped by the ones in `profile.rs`,
+// which apply the profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self", default, deny_unknown_fields)]
 pub struct HelloWorldInspectorConfig {
+    /// Preset the configuration started from, see
+    /// [`HelloWorldInspectorConfig::profile`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<InspectorProfile>,
     /// Enable verbose logging
     pub verbose: bool,
     /// Enable step-by-step execution logging
     pub log_steps: bool,
     /// Enable call tracing
     pub trace_calls: bool,
+    /// Emit a step message every this many steps; 0 never prints steps and
+    /// 1 prints every step
+    pub step_sample_interval: u64,
+    /// Stop recording steps (but not execution) after this many, marking
+    /// the report as truncated
+    pub max_steps: Option<u64>,
+    /// Where inspector messages are written
+    pub output: OutputMode,
+    /// Write at most this many stdout lines or `tracing` events per second
+    /// and summarize the rest when the transaction ends; unlimited if `None`.
+    /// Identical consecutive messages are collapsed either way
+    pub max_lines_per_second: Option<u32>,
+    /// Which address delegate calls are attributed to in per-address statistics
+    pub delegatecall_attribution: DelegateCallAttribution,
+    /// Attribute the gas spent inside subcalls and creations to the `CALL` or
+    /// `CREATE` opcode that started them in the gas profile. The instructions
+    /// of the subcall are still profiled too, so totals count that gas twice
+    pub attribute_subcall_gas: bool,
+    /// Highest address treated as a precompile; raise it for chains with
+    /// additional precompiles
+    pub precompile_upper_bound: u64,
+    /// Restricts logging and recording to calls involving specific addresses;
+    /// steps, logs and records inside filtered-out frames are skipped
+    pub filter: AddressFilter,
+    /// Caps on the collections the inspector keeps, unbounded by default
+    pub limits: TraceLimits,
+    /// Output bytes kept per call and create frame; longer outputs keep
+    /// their length and hash
+    pub max_output_bytes: usize,
+    /// Catch panics in registered callbacks and in the inspector's own
+    /// recording, report them as internal errors and keep executing instead
+    /// of unwinding through the EVM
+    pub catch_panics: bool,
+    /// Check the inspector's own bookkeeping and record the inconsistencies
+    /// found, e.g. an end hook without its start hook, instead of silently
+    /// producing a wrong report
+    pub strict: bool,
+    /// What a `strict` inspector does when it finds an inconsistency
+    pub on_inconsistency: InconsistencyAction,
+    /// Replace the calldata and return data of every frame with
+    /// `<redacted {n} bytes>` and their Keccak-256 hash in all outputs
+    pub redact_calldata: bool,
+    /// Replace every storage value read or written with
+    /// `<redacted 32 bytes>` and its Keccak-256 hash in all outputs
+    pub redact_storage_values: bool,
+    /// Redact the calldata and return data of frames calling these addresses
+    pub redact_addresses: Vec<Address>,
+    /// Record the first instruction in each frame that leaves less than this
+    /// percentage of the frame's gas limit, e.g. 5; disabled if `None`
+    pub low_gas_percent: Option<u64>,
+    /// File that plugin-created inspectors append their trace events to, as
+    /// JSON lines; opened when the plugin is initialized
+    pub sink_path: Option<PathBuf>,
+    /// Whether every step is recorded or only a sample of them
+    pub profiling: ProfilingMode,
+    /// Opcodes that raise an [`OpcodeAlert`](crate::OpcodeAlert) whenever
+    /// they execute, written as names or hex bytes, e.g.
+    /// `["DELEGATECALL", "0xff"]`
+    #[serde(with = "crate::opcodes::set")]
+    pub alert_on_opcodes: BTreeSet<u8>,
+    /// Instructions of the calling frame followed after each low-level call
+    /// to see whether its success flag is checked or popped; 0 disables the
+    /// check
+    pub unchecked_call_window: usize,
+    /// Record the wall-clock time of every call and create frame and of
+    /// every transaction. Reading the clock twice per frame is noticeable
+    /// on small calls, so it is off by default
+    pub measure_time: bool,
+    /// Keep the code of every contract that ran in `executed_contracts`,
+    /// so reports can be disassembled offline
+    pub capture_code: bool,
+    /// Count how often each `JUMPI` jumped and fell through, see
+    /// [`CoverageMap`](crate::CoverageMap)
+    pub branch_coverage: bool,
+    /// Count how often each instruction of each contract's runtime code
+    /// executed, see [`PcCoverage`](crate::PcCoverage)
+    pub pc_coverage: bool,
+    /// Leave contracts whose code is longer than this many bytes out of
+    /// `pc_coverage`, to bound its memory
+    pub coverage_max_code_size: Option<usize>,
+    /// Single instructions that grow memory by more than this many bytes
+    /// are recorded in `memory_expansions`; 0 disables them
+    pub memory_expansion_threshold: u64,
+    /// Calls forced to fail instead of executing, for testing how contracts
+    /// handle failures; none by default
+    pub faults: FaultInjector,
+    /// Format the plugin writes its merged report in when it shuts down;
+    /// nothing is written if `None`
+    pub output_format: Option<Format>,
+    /// File the report is written to, replacing it atomically; stdout if
+    /// `None`
+    pub output_path: Option<PathBuf>,
+}
+
+impl Default for HelloWorldInspectorConfig {
+    fn default() -> Self {
+        Self {
+            profile: None,
+            verbose: false,
+            log_steps: false,
+            trace_calls: false,
+            step_sample_interval: default_step_sample_interval(),
+            max_steps: None,
+            output: OutputMode::default(),
+            max_lines_per_second: None,
+            delegatecall_attribution: DelegateCallAttribution::default(),
+            attribute_subcall_gas: false,
+            precompile_upper_bound: default_precompile_upper_bound(),
+            filter: AddressFilter::default(),
+            limits: TraceLimits::default(),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            catch_panics: false,
+            strict: false,
+            on_inconsistency: InconsistencyAction::default(),
+            redact_calldata: false,
+            redact_storage_values: false,
+            redact_addresses: Vec::new(),
+            low_gas_percent: None,
+            sink_path: None,
+            profiling: ProfilingMode::default(),
+            alert_on_opcodes: BTreeSet::new(),
+            unchecked_call_window: DEFAULT_UNCHECKED_CALL_WINDOW,
+            measure_time: false,
+            capture_code: false,
+            branch_coverage: false,
+            pc_coverage: false,
+            coverage_max_code_size: None,
+            memory_expansion_threshold: DEFAULT_MEMORY_EXPANSION_THRESHOLD,
+            faults: FaultInjector::default(),
+            output_format: None,
+            output_path: None,
+        }
+    }
+}
+
+impl HelloWorldInspectorConfig {
+    /// Returns what the configuration redacts from traces.
+    pub fn redaction(&self) -> Redaction {
+        Redaction {
+            calldata: self.redact_calldata,
+            storage_values: self.redact_storage_values,
+            addresses: self.redact_addresses.clone(),
+        }
+    }
+}
+
+fn default_step_sample_interval() -> u64 {
+    100
+}
+
+fn default_precompile_upper_bound() -> u64 {
+    DEFAULT_PRECOMPILE_UPPER_BOUND
+}
+
+/// Address that a `DELEGATECALL` is counted against in per-address statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegateCallAttribution {
+    /// The address whose code is executed (the implementation)
+    #[default]
+    CodeAddress,
+    /// The address whose storage is used (the proxy)
+    StorageAddress,
+}
+
+/// Destination for the messages emitted by the inspector hooks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Print human-readable lines to stdout
+    #[default]
+    Stdout,
+    /// Emit structured `tracing` events under the `restd::inspector` target
+    Tracing,
+    /// Do not emit anything
+    Silent,
 }
 
 impl HelloWorldInspectorPlugin {
     /// Create a new plugin with the given configuration
     pub fn new(config: HelloWorldInspectorConfig) -> Self {
-        Self { config }
+        Self {
+            config: Arc::new(SharedConfig::new(config)),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the names of addresses shared by the inspectors the plugin
+    /// creates, see [`HelloWorldInspector::with_labels`].
+    pub fn with_labels(mut self, labels: Arc<Labels>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Sets the selector resolver shared by the inspectors the plugin
+    /// creates.
+    pub fn with_selector_resolver(mut self, resolver: Arc<dyn SelectorResolver>) -> Self {
+        self.selector_resolver = Some(resolver);
+        self
+    }
+
+    /// Returns the totals of every inspector created so far, counting each
+    /// transaction once its top-level frame has ended.
+    pub fn aggregate_report(&self) -> AggregateReport {
+        self.aggregate.report(self.state.inspectors_created())
     }
     
     /// Get the plugin name
     pub fn name(&self) -> &'static str {
-        "hello-world-inspector"
+        PLUGIN_NAME
     }
     
-    /// Get the plugin configuration
-    pub fn config(&self) -> &HelloWorldInspectorConfig {
-        &self.config
+    /// Get the plugin configuration, the latest one if it was reloaded
+    pub fn config(&self) -> Arc<HelloWorldInspectorConfig> {
+        self.config.get().1
+    }
+
+    /// Replace the configuration of the plugin and of the inspectors it
+    /// created, which apply it when their next transaction starts
+    ///
+    /// The configuration is validated like in [`init`](Self::init).
+    /// Changing `sink_path`, `profiling` or `limits` returns
+    /// [`Error::RequiresRestart`] and keeps the current configuration.
+    pub fn reload(&self, config: HelloWorldInspectorConfig) -> Result<(), Error> {
+        validate_plugin_config(&config)?;
+        self.config.replace(config)?;
+        info!("Reloaded HelloWorldInspector plugin config: {:?}", self.config());
+        Ok(())
     }
     
-    /// Initialize the plugin
-    pub fn init(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        info!("Initializing HelloWorldInspector plugin with config: {:?}", self.config);
+    /// Initialize the plugin: validates the configuration and opens the
+    /// sink at `sink_path`, if set
+    ///
+    /// The plugin only merges reports, so an `output_format` that renders a
+    /// call tree is rejected with [`Error::MissingCallTrace`].
+    pub fn init(&self) -> Result<(), Error> {
+        let config = self.config();
+        info!("Initializing HelloWorldInspector plugin with config: {:?}", config);
+        validate_plugin_config(&config)?;
+        self.open_sink(&config)
+    }
+
+    /// Replaces the configuration with one supplied at initialization.
+    ///
+    /// Before the plugin created an inspector every option can change;
+    /// afterwards the ones [`reload`](Self::reload) cannot change return
+    /// [`Error::RequiresRestart`] and keep the current configuration.
+    fn configure(&self, config: HelloWorldInspectorConfig) -> Result<(), Error> {
+        validate_plugin_config(&config)?;
+        if self.state.inspectors_created() == 0 {
+            self.config.set(config);
+            Ok(())
+        } else {
+            self.config.replace(config)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn open_sink(&self, config: &HelloWorldInspectorConfig) -> Result<(), Error> {
+        let sink = match &config.sink_path {
+            Some(path) => match FileTraceSink::create(path) {
+                Ok(sink) => Some(sink),
+                Err(err) => {
+                    self.state.record_error(&err);
+                    return Err(err);
+                }
+            },
+            None => None,
+        };
+        self.state.set_sink(sink);
         Ok(())
     }
+
+    /// Without the `std` feature there is no file system to open the sink on.
+    #[cfg(not(feature = "std"))]
+    fn open_sink(&self, config: &HelloWorldInspectorConfig) -> Result<(), Error> {
+        let Some(path) = &config.sink_path else {
+            return Ok(());
+        };
+        let err = Error::Sink {
+            path: path.clone(),
+            source: std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "file sinks need the `std` feature",
+            ),
+        };
+        self.state.record_error(&err);
+        Err(err)
+    }
+
+    /// Called when tracing begins
+    pub fn on_start(&self) {
+        info!("HelloWorldInspector plugin started");
+    }
+
+    /// Adds the report of an inspector to the report written on shutdown.
+    pub fn record_report(&self, report: InspectorReport) {
+        self.state.record_report(report);
+    }
+
+    /// Flushes the sink, writes the merged report if `output_format` is set
+    /// and returns the plugin's totals. Inspectors created afterwards no
+    /// longer write to the sink.
+    pub fn on_shutdown(&self) -> PluginReport {
+        #[cfg(feature = "std")]
+        if let Some(sink) = self.state.take_sink() {
+            let errors = sink.take_errors().into_iter().map(Err);
+            for result in errors.chain([sink.flush()]) {
+                if let Err(err) = result {
+                    warn!("Failed to write HelloWorldInspector trace events: {err}");
+                    self.state.record_error(format!("failed to write trace event: {err}"));
+                }
+            }
+        }
+        #[cfg(feature = "std")]
+        if let Some(writer) = ReportWriter::from_config(&self.config()) {
+            if let Err(err) = writer.write_report(&self.state.take_report()) {
+                warn!("Failed to write HelloWorldInspector report: {err}");
+                self.state.record_error(err);
+            }
+        }
+        let report = self.state.report();
+        info!("HelloWorldInspector plugin shut down: {:?}", report);
+        report
+    }
+
+    /// Returns whether the sink failed and how many events it took
+    pub fn health(&self) -> PluginHealth {
+        self.state.health()
+    }
     
-    /// Create an inspector instance
+    /// Create an inspector instance, writing to the plugin's sink if it has one
+    /// and applying reloaded configurations between transactions
+    ///
+    /// The inspector shares the plugin's labels, selector resolver and
+    /// metric handles, and adds its totals to the plugin's aggregates.
     pub fn create_inspector(&self) -> HelloWorldInspector {
         info!("Creating HelloWorldInspector instance");
-        HelloWorldInspector::default()
+        self.state.record_inspector();
+        let (version, config) = self.config.get();
+        let mut inspector = HelloWorldInspector::with_config((*config).clone())
+            .with_shared_config(self.config.clone(), version)
+            .with_metrics(self.metrics.clone())
+            .with_aggregate(self.aggregate.clone());
+        if let Some(labels) = &self.labels {
+            inspector = inspector.with_labels(labels.clone());
+        }
+        if let Some(resolver) = &self.selector_resolver {
+            inspector = inspector.with_selector_resolver(resolver.clone());
+        }
+        #[cfg(feature = "std")]
+        if let Some(sink) = self.state.inspector_sink() {
+            return inspector.with_sink(sink);
+        }
+        inspector
+    }
+}
+
+impl<DB: Database> InspectorPlugin<DB> for HelloWorldInspectorPlugin {
+    fn name(&self) -> &str {
+        HelloWorldInspectorPlugin::name(self)
     }
+
+    /// Accepts `null`, keeping the configuration the plugin was created
+    /// with, or a configuration object that deserializes into a
+    /// [`HelloWorldInspectorConfig`], which replaces it before the sink is
+    /// opened
+    fn init(&self, config: &serde_json::Value) -> Result<(), Error> {
+        if !config.is_null() {
+            let config = <HelloWorldInspectorConfig as Deserialize>::deserialize(config)
+                .map_err(|err| ConfigError::Json(err.to_string()))?;
+            self.configure(config)?;
+        }
+        HelloWorldInspectorPlugin::init(self)
+    }
+
+    fn create_inspector(&self) -> Box<dyn StackedInspector<DB>> {
+        Box::new(HelloWorldInspectorPlugin::create_inspector(self))
+    }
+
+    fn on_start(&self) {
+        HelloWorldInspectorPlugin::on_start(self)
+    }
+
+    fn on_shutdown(&self) {
+        HelloWorldInspectorPlugin::on_shutdown(self);
+    }
+
+    fn health(&self) -> PluginHealth {
+        HelloWorldInspectorPlugin::health(self)
+    }
+}
+
+/// Checks a configuration the plugin is initialized or reloaded with. The
+/// plugin only merges reports, so an `output_format` that renders a call
+/// tree is rejected with [`Error::MissingCallTrace`].
+fn validate_plugin_config(config: &HelloWorldInspectorConfig) -> Result<(), Error> {
+    config.validate()?;
+    if let Some(format) = config.output_format.filter(|format| format.needs_call_trace()) {
+        return Err(Error::MissingCallTrace(format));
+    }
+    Ok(())
 }
 
-/// Register the HelloWorldInspector with reth's EVM configuration
-pub fn register_inspector<DB: Database>() {
-    let inspector = HelloWorldInspector::default();
+/// Register the HelloWorldInspector plugin, with the default configuration, in `registry`
+pub fn register_inspector<DB: Database>(
+    registry: &mut PluginRegistry<DB>,
+) -> Result<(), Error> {
     info!("Registering HelloWorldInspector with reth EVM");
-    
-    // Note: This is a simplified registration approach
-    // In practice, you would integrate this with reth's inspector stack
-    // through the EVM configuration or inspector handle registration
+    registry.register(create_plugin())
 }
 
 /// Helper function to create and configure the plugin
 pub fn create_plugin() -> HelloWorldInspectorPlugin {
-    HelloWorldInspectorPlugin::default()
+    HelloWorldInspectorPlugin::new(HelloWorldInspectorBuilder::new().build_config())
 }
 
 /// Helper function to create plugin configuration
 pub fn create_config(verbose: bool) -> HelloWorldInspectorConfig {
-    HelloWorldInspectorConfig { 
-        verbose,
-        log_steps: true,
-        trace_calls: true,
-    }
+    HelloWorldInspectorBuilder::new()
+        .verbose(verbose)
+        .log_steps(true)
+        .trace_calls(true)
+        .build_config()
 }
 
 /// Helper function to create plugin configuration with all options
 pub fn create_detailed_config(verbose: bool, log_steps: bool, trace_calls: bool) -> HelloWorldInspectorConfig {
-    HelloWorldInspectorConfig { 
-        verbose,
-        log_steps,
-        trace_calls,
-    }
+    HelloWorldInspectorBuilder::new()
+        .verbose(verbose)
+        .log_steps(log_steps)
+        .trace_calls(trace_calls)
+        .build_config()
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+//! Precompile detection.
+
+use alloy_primitives::{Address, U160};
+
+/// Address of the last precompile known when this crate was written
+/// (`point_evaluation`, EIP-4844).
+pub const DEFAULT_PRECOMPILE_UPPER_BOUND: u64 = 0x0a;
+
+/// Returns true if `address` is in the precompile range `0x01..=upper_bound`.
+pub fn is_precompile(address: &Address, upper_bound: u64) -> bool {
+    let value = U160::from_be_bytes(address.0 .0);
+    value >= U160::from(1) && value <= U160::from(upper_bound)
+}
+
+/// Returns the name of the precompile at `address`, or `"unknown"` for
+/// addresses in a configured precompile range that this crate has no name for.
+pub fn precompile_name(address: &Address) -> &'static str {
+    if address[..19].iter().any(|byte| *byte != 0) {
+        return "unknown";
+    }
+    match address[19] {
+        0x01 => "ecrecover",
+        0x02 => "sha256",
+        0x03 => "ripemd160",
+        0x04 => "identity",
+        0x05 => "modexp",
+        0x06 => "ecadd",
+        0x07 => "ecmul",
+        0x08 => "ecpairing",
+        0x09 => "blake2f",
+        0x0a => "point_evaluation",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precompile_range() {
+        let bound = DEFAULT_PRECOMPILE_UPPER_BOUND;
+        assert!(!is_precompile(&Address::ZERO, bound));
+        assert!(is_precompile(&Address::with_last_byte(0x01), bound));
+        assert!(is_precompile(&Address::with_last_byte(0x0a), bound));
+        assert!(!is_precompile(&Address::with_last_byte(0x0b), bound));
+        assert!(is_precompile(&Address::with_last_byte(0x0b), 0x0b));
+        assert!(!is_precompile(&Address::repeat_byte(0x01), bound));
+
+        assert_eq!(precompile_name(&Address::with_last_byte(0x04)), "identity");
+        assert_eq!(precompile_name(&Address::with_last_byte(0x0b)), "unknown");
+    }
+}
@@ -0,0 +1,94 @@
+//! Keccak-256 preimages observed during execution.
+//!
+//! Solidity stores mapping entries and dynamic array elements at slots such
+//! as `keccak256(key . slot)`, so raw storage slots say little on their own.
+//! [`PreimageStore`] remembers the input of every `KECCAK256` instruction,
+//! which lets a slot be traced back to the key and base slot it came from.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use alloy_primitives::{Bytes, B256, U256};
+use revm::interpreter::Interpreter;
+
+/// Hash inputs keyed by their Keccak-256 hash.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PreimageStore {
+    /// Recorded preimages
+    preimages: HashMap<B256, Bytes>,
+    /// Input of the `KECCAK256` being executed, until its hash is pushed
+    pending: Option<Bytes>,
+    /// Bytes of the recorded preimages
+    bytes: usize,
+    /// Bytes of preimages that may be recorded, unbounded if `None`
+    max_bytes: Option<usize>,
+    /// Whether a preimage was dropped because of `max_bytes`
+    truncated: bool,
+}
+
+impl PreimageStore {
+    pub(crate) fn new(max_bytes: Option<usize>) -> Self {
+        Self {
+            max_bytes,
+            ..Self::default()
+        }
+    }
+
+    /// Reads the memory a `KECCAK256` about to run will hash.
+    ///
+    /// Memory is expanded by the instruction itself, so bytes past the
+    /// current end of memory are read as zeros.
+    pub(crate) fn before_hash(&mut self, interp: &Interpreter) {
+        let (Ok(offset), Ok(len)) = (interp.stack.peek(0), interp.stack.peek(1)) else {
+            return;
+        };
+        let Ok(len) = usize::try_from(len) else {
+            return;
+        };
+        if self.max_bytes.is_some_and(|max| self.bytes + len > max) {
+            self.truncated = true;
+            return;
+        }
+        let mut input = vec![0; len];
+        if len > 0 {
+            let Ok(offset) = usize::try_from(offset) else {
+                return;
+            };
+            let memory = &interp.shared_memory;
+            let end = memory.len().min(offset.saturating_add(len));
+            if offset < end {
+                input[..end - offset].copy_from_slice(memory.slice(offset, end - offset));
+            }
+        }
+        self.pending = Some(input.into());
+    }
+
+    /// Records the pending input under the hash the instruction pushed.
+    pub(crate) fn after_hash(&mut self, interp: &Interpreter) {
+        let Some(input) = self.pending.take() else {
+            return;
+        };
+        if !interp.instruction_result.is_ok() {
+            return;
+        }
+        let Ok(hash) = interp.stack.peek(0) else {
+            return;
+        };
+        if let Entry::Vacant(entry) = self.preimages.entry(B256::from(hash)) {
+            self.bytes += input.len();
+            entry.insert(input);
+        }
+    }
+
+    /// Returns the preimage of `slot`, if it was hashed.
+    pub(crate) fn of_slot(&self, slot: U256) -> Option<Bytes> {
+        self.preimages.get(&B256::from(slot)).cloned()
+    }
+
+    pub(crate) fn preimages(&self) -> &HashMap<B256, Bytes> {
+        &self.preimages
+    }
+
+    pub(crate) fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
@@ -0,0 +1,423 @@
+//! Human-readable call trees.
+//!
+//! [`CallTrace::pretty_print`] renders a trace as an indented tree in the
+//! style of `forge test -vvvv` and `cast run`, one line per frame:
+//!
+//! ```text
+//! [13138] CALL 0x2020202020202020202020202020202020202020::transfer(0x0000000000000000… (64 bytes)) → success
+//! ├─ emit topics: [] data: 0x
+//! ├─ [759] CALL 0x2121212121212121212121212121212121212121::fallback{value: 1}() → success
+//! └─ [54] CALL 0x2222222222222222222222222222222222222222::fallback() → revert: nope
+//! ```
+
+use std::{fmt::Write, sync::Arc};
+
+use alloy_primitives::{hex, utils::format_ether, Address, U256};
+use revm::interpreter::InstructionResult;
+
+use crate::{
+    abi::{EventDecoder, FnDecoder},
+    call_trace::{CallKind, CallTrace, CallTraceLog, CallTraceNode},
+    geth::{geth_error, geth_type},
+    redact::{Redacted, Redaction},
+    revert::RevertReason,
+    selector::{selector_hex, selector_of, SelectorResolver},
+    FailureStats, Labels,
+};
+
+/// Bytes of calldata, init code or log data shown before eliding the rest.
+pub const DEFAULT_MAX_CALLDATA_BYTES: usize = 64;
+
+/// Options for [`CallTrace::pretty_print`].
+#[derive(Debug, Clone)]
+pub struct PrettyPrintOptions {
+    /// Show the logs of each frame, interleaved with its subcalls in
+    /// execution order
+    pub show_logs: bool,
+    /// Color outcomes and events with ANSI escape codes
+    pub color: bool,
+    /// Calldata, init code and log data longer than this are elided
+    pub max_calldata_bytes: usize,
+    /// Frames deeper than this are collapsed into a count of the hidden
+    /// frames, or every frame is shown if `None`
+    pub max_depth: Option<usize>,
+    /// Names functions by their selector; unresolved selectors are shown as hex
+    pub resolver: Option<Arc<dyn SelectorResolver>>,
+    /// Decodes the logs of known events, which are otherwise shown as raw
+    /// topics and data
+    pub event_decoder: Option<Arc<EventDecoder>>,
+    /// Decodes the arguments of calls to known functions, which are
+    /// otherwise shown as raw calldata
+    pub fn_decoder: Option<Arc<FnDecoder>>,
+    /// Frames whose calldata and return data are shown as
+    /// `<redacted {n} bytes>`, none by default
+    pub redaction: Redaction,
+    /// Names shown with labeled addresses, as `WETH (0xC02a…)`
+    pub labels: Option<Arc<Labels>>,
+    /// Failures summarized below the tree, usually the report's
+    /// `failures`; no summary is printed if empty
+    pub failures: Vec<FailureStats>,
+}
+
+impl Default for PrettyPrintOptions {
+    fn default() -> Self {
+        Self {
+            show_logs: false,
+            color: false,
+            max_calldata_bytes: DEFAULT_MAX_CALLDATA_BYTES,
+            max_depth: None,
+            resolver: None,
+            event_decoder: None,
+            fn_decoder: None,
+            redaction: Redaction::default(),
+            labels: None,
+            failures: Vec::new(),
+        }
+    }
+}
+
+const GREEN: &str = "32";
+const RED: &str = "31";
+const YELLOW: &str = "33";
+const DIM: &str = "2";
+
+impl CallTrace {
+    /// Renders the trace as an indented tree, one line per frame:
+    /// `[gas used] KIND target::function{value}(args) → outcome`.
+    ///
+    /// Returns an empty string if no transaction was traced.
+    pub fn pretty_print(&self, opts: PrettyPrintOptions) -> String {
+        let mut printer = Printer {
+            opts: &opts,
+            out: String::new(),
+        };
+        if let Some(root) = self.root() {
+            printer.node(root, "", "");
+        }
+        printer.failures();
+        printer.out
+    }
+}
+
+/// An item printed under a frame.
+enum Entry<'a> {
+    Frame(&'a CallTraceNode),
+    Log(&'a CallTraceLog),
+    Collapsed(usize),
+}
+
+struct Printer<'a> {
+    opts: &'a PrettyPrintOptions,
+    out: String,
+}
+
+impl Printer<'_> {
+    /// Writes `node` after `head` and its entries below it, each prefixed
+    /// with `indent`.
+    fn node(&mut self, node: &CallTraceNode, head: &str, indent: &str) {
+        let line = self.frame_line(node) + &self.gas_capped(node);
+        self.line(head, &line);
+        let entries = self.entries(node);
+        let count = entries.len();
+        for (i, entry) in entries.into_iter().enumerate() {
+            let last = i + 1 == count;
+            let head = format!("{indent}{}", if last { "└─ " } else { "├─ " });
+            match entry {
+                Entry::Frame(child) => {
+                    let indent = format!("{indent}{}", if last { "   " } else { "│  " });
+                    self.node(child, &head, &indent);
+                }
+                Entry::Log(log) => {
+                    let line = self.log_line(log);
+                    self.line(&head, &line);
+                }
+                Entry::Collapsed(frames) => {
+                    let line = self.paint(&format!("… {frames} nested frames not shown"), DIM);
+                    self.line(&head, &line);
+                }
+            }
+        }
+    }
+
+    /// Returns the children and, if enabled, logs of `node` in execution order.
+    fn entries<'n>(&self, node: &'n CallTraceNode) -> Vec<Entry<'n>> {
+        let collapsed = self.opts.max_depth.is_some_and(|max| node.depth >= max);
+        let mut entries = Vec::new();
+        let mut logs = node
+            .logs
+            .iter()
+            .filter(|_| self.opts.show_logs && !collapsed)
+            .peekable();
+        for child in &node.children {
+            while let Some(log) = logs.next_if(|log| log.step < child.start_step) {
+                entries.push(Entry::Log(log));
+            }
+            if !collapsed {
+                entries.push(Entry::Frame(child));
+            }
+        }
+        entries.extend(logs.map(Entry::Log));
+        if collapsed && !node.children.is_empty() {
+            entries.push(Entry::Collapsed(node.iter().count() - 1));
+        }
+        entries
+    }
+
+    fn frame_line(&self, node: &CallTraceNode) -> String {
+        let gas = self.paint(&format!("[{}]", node.gas_used), DIM);
+        let kind = geth_type(node.kind);
+        if node.kind == CallKind::SelfDestruct {
+            return format!(
+                "{gas} {kind} {}{} → {}",
+                self.address(&node.caller),
+                self.value(node),
+                self.address(&node.target)
+            );
+        }
+        if node.kind.is_codeless() {
+            return format!("{gas} {kind} → {}", self.codeless(node));
+        }
+        if self.is_redacted(node) {
+            return format!(
+                "{gas} {kind} {}::{}{} → {}",
+                self.target(node),
+                Redacted::new(&node.input),
+                self.value(node),
+                self.outcome(node)
+            );
+        }
+        let (function, args) = match node.kind {
+            CallKind::Create | CallKind::Create2 | CallKind::EofCreate { .. } => {
+                ("new".to_string(), &node.input[..])
+            }
+            CallKind::Precompile { name, .. } => (name.to_string(), &node.input[..]),
+            _ => match selector_of(&node.input) {
+                Some(selector) => (self.function_name(selector), &node.input[4..]),
+                None => ("fallback".to_string(), &node.input[..]),
+            },
+        };
+        let decoded = match node.kind {
+            CallKind::Call | CallKind::StaticCall | CallKind::DelegateCall | CallKind::CallCode => {
+                self.opts
+                    .fn_decoder
+                    .as_ref()
+                    .and_then(|decoder| decoder.decode(&node.input))
+            }
+            _ => None,
+        };
+        let (function, args, error) = match decoded {
+            Some(Ok(call)) => (call.function.clone(), call.args_list(), String::new()),
+            Some(Err(err)) => (function, self.bytes(args), format!(" (decode error: {err})")),
+            None => (function, self.bytes(args), String::new()),
+        };
+        format!(
+            "{gas} {kind} {}::{function}{}({args}){error} → {}",
+            self.target(node),
+            self.value(node),
+            self.outcome(node)
+        )
+    }
+
+    /// Flags a frame granted less gas than its calling instruction asked
+    /// for, as ` ⚠ gas capped (requested 100000, granted 9843)`.
+    fn gas_capped(&self, node: &CallTraceNode) -> String {
+        match node.gas_requested.filter(|_| node.gas_capped) {
+            Some(requested) => {
+                let note = format!(
+                    "⚠ gas capped (requested {requested}, granted {})",
+                    node.gas_limit
+                );
+                format!(" {}", self.paint(&note, YELLOW))
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Describes a frame that ran no code, as
+    /// `transfer 1.5 ETH to 0x… (no code)`.
+    fn codeless(&self, node: &CallTraceNode) -> String {
+        let target = self.target(node);
+        let action = match node.kind {
+            CallKind::EmptyCreate { .. } if node.value.is_zero() => format!("create {target}"),
+            CallKind::EmptyCreate { .. } => {
+                format!("create {target} with {} ETH", ether(node.value))
+            }
+            // Only CALL and CALLCODE move value
+            _ if matches!(node.kind.base(), CallKind::Call | CallKind::CallCode) => {
+                format!("transfer {} ETH to {target}", ether(node.value))
+            }
+            _ => target,
+        };
+        let note = match node.input.len() {
+            0 => "(no code)".to_string(),
+            len => format!("(no code, {len} bytes of calldata ignored)"),
+        };
+        let outcome = if node.success {
+            String::new()
+        } else {
+            format!(" → {}", self.outcome(node))
+        };
+        format!("{action} {}{outcome}", self.paint(&note, DIM))
+    }
+
+    fn log_line(&self, log: &CallTraceLog) -> String {
+        let emit = self.paint("emit", YELLOW);
+        let decoded = self.opts.event_decoder.as_ref().and_then(|decoder| {
+            decoder.decode(log.log.topics(), &log.log.data.data)
+        });
+        let error = match decoded {
+            Some(Ok(decoded)) => return format!("{emit} {decoded}"),
+            Some(Err(err)) => format!(" (decode error: {err})"),
+            None => String::new(),
+        };
+        let topics = log
+            .log
+            .topics()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let data = match self.bytes(&log.log.data.data) {
+            data if data.is_empty() => "0x".to_string(),
+            data => data,
+        };
+        format!("{emit} topics: [{topics}] data: {data}{error}")
+    }
+
+    /// Names the called account, as `EOA(authority) ⇒ delegate` if it is
+    /// delegated with EIP-7702.
+    fn target(&self, node: &CallTraceNode) -> String {
+        match node.delegate {
+            Some(delegate) => format!(
+                "EOA({}) ⇒ {}",
+                self.address(&node.target),
+                self.address(&delegate)
+            ),
+            None => self.address(&node.target),
+        }
+    }
+
+    /// Returns the label of `address`, or the address if it has none.
+    fn address(&self, address: &Address) -> String {
+        self.opts
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.label(address))
+            .unwrap_or_else(|| address.to_string())
+    }
+
+    /// Returns the resolved function name for `selector`, or the selector in hex.
+    fn function_name(&self, selector: [u8; 4]) -> String {
+        self.opts
+            .resolver
+            .as_ref()
+            .and_then(|resolver| resolver.resolve(selector))
+            .map(|signature| match signature.split_once('(') {
+                Some((name, _)) => name.to_string(),
+                None => signature,
+            })
+            .unwrap_or_else(|| selector_hex(selector))
+    }
+
+    fn is_redacted(&self, node: &CallTraceNode) -> bool {
+        self.opts.redaction.redacts_calldata_of(&node.target)
+    }
+
+    /// Returns `{value: n}` for frames that transfer value.
+    fn value(&self, node: &CallTraceNode) -> String {
+        let transfers = !matches!(
+            node.kind.base(),
+            CallKind::StaticCall | CallKind::DelegateCall
+        );
+        if transfers && !node.value.is_zero() {
+            format!("{{value: {}}}", node.value)
+        } else {
+            String::new()
+        }
+    }
+
+    fn outcome(&self, node: &CallTraceNode) -> String {
+        if node.success {
+            self.paint("success", GREEN)
+        } else if node.result == InstructionResult::Revert {
+            let reason = if self.is_redacted(node) {
+                Redacted::new(&node.output).to_string()
+            } else {
+                RevertReason::decode(&node.output).to_string()
+            };
+            self.paint(&format!("revert: {}", self.elide(&reason)), RED)
+        } else {
+            self.paint(&format!("error: {}", geth_error(node.result)), RED)
+        }
+    }
+
+    /// Formats `data` as hex, eliding bytes beyond `max_calldata_bytes`.
+    fn bytes(&self, data: &[u8]) -> String {
+        if data.is_empty() {
+            return String::new();
+        }
+        let max = self.opts.max_calldata_bytes;
+        if data.len() <= max {
+            return hex::encode_prefixed(data);
+        }
+        format!(
+            "{}… ({} bytes)",
+            hex::encode_prefixed(&data[..max]),
+            data.len()
+        )
+    }
+
+    /// Elides a revert reason that embeds long custom error data.
+    fn elide(&self, reason: &str) -> String {
+        let max = 2 * self.opts.max_calldata_bytes + "custom error 0x".len();
+        match reason.char_indices().nth(max) {
+            Some((end, _)) => format!("{}…", &reason[..end]),
+            None => reason.to_string(),
+        }
+    }
+
+    fn paint(&self, text: &str, code: &str) -> String {
+        if self.opts.color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Writes one line per failure kind, as
+    /// `revert ×2 (1 injected), first at 0x…01 pc 12 depth 1`, or
+    /// `revert ×1, first at Token.sol:87 (0x…01 pc 12 depth 1)` if the
+    /// source location is known.
+    fn failures(&mut self) {
+        if self.opts.failures.is_empty() {
+            return;
+        }
+        let header = self.paint("Failures:", RED);
+        self.line("", &header);
+        for stats in &self.opts.failures {
+            let first = &stats.first;
+            let pc = first.pc.map(|pc| format!(" pc {pc}")).unwrap_or_default();
+            let injected = match stats.injected {
+                0 => String::new(),
+                injected => format!(" ({injected} injected)"),
+            };
+            let frame = format!("{}{pc} depth {}", self.address(&first.address), first.depth);
+            let at = match &first.source_location {
+                Some(location) => format!("{}:{} ({frame})", location.file, location.line),
+                None => frame,
+            };
+            let line = format!("{} ×{}{injected}, first at {at}", stats.kind, stats.count);
+            self.line("  ", &line);
+        }
+    }
+
+    fn line(&mut self, head: &str, line: &str) {
+        writeln!(self.out, "{head}{line}").expect("writing to a string");
+    }
+}
+
+/// Formats `wei` in ether without trailing zeros, e.g. `1.5`.
+pub(crate) fn ether(wei: U256) -> String {
+    let ether = format_ether(wei);
+    ether.trim_end_matches('0').trim_end_matches('.').to_string()
+}
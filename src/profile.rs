@@ -0,0 +1,208 @@
+//! Named configuration presets.
+//!
+//! An [`InspectorProfile`] sets the handful of fields that matter for one
+//! use, starting from the defaults; any field can still be changed
+//! afterwards. A configuration built from a profile remembers it and
+//! serializes as the profile's name followed by the fields that differ from
+//! it, so
+//!
+//! ```toml
+//! profile = "security"
+//! measure_time = true
+//! ```
+//!
+//! loads the security preset with timing turned on.
+//!
+//! | Profile        | Fields set                                                              |
+//! |----------------|-------------------------------------------------------------------------|
+//! | `minimal`      | `profiling = "counters"`, `unchecked_call_window = 0`                   |
+//! | `call_tracing` | `trace_calls`, `profiling = "counters"`, `unchecked_call_window = 0`    |
+//! | `profiling`    | `attribute_subcall_gas`, `measure_time`, `unchecked_call_window = 0`    |
+//! | `debugging`    | `verbose`, `log_steps`, `trace_calls`, `step_sample_interval = 1`, `capture_code` |
+//! | `security`     | `trace_calls`, `alert_on_opcodes = ["CALLCODE", "DELEGATECALL", "SELFDESTRUCT"]` |
+
+use std::{fmt, str::FromStr};
+
+use revm::interpreter::opcode;
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{HelloWorldInspectorConfig, ProfilingMode};
+
+/// A named configuration preset, see the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InspectorProfile {
+    /// Only count steps and calls; nothing is recorded per step
+    Minimal,
+    /// Record every call and the other frame-level records, but nothing per
+    /// step
+    CallTracing,
+    /// Record every step for the opcode and gas histograms, attribute the
+    /// gas of subcalls to the opcode that started them and time every
+    /// frame. For long transactions, switch `profiling` to `sampled`
+    Profiling,
+    /// Print every call and step and keep the code that ran, for stepping
+    /// through a transaction
+    Debugging,
+    /// Run every detector (reentrancy, `tx.origin`, unchecked calls,
+    /// static-call violations) and alert on the opcodes that run foreign
+    /// code in the caller's context or destroy contracts
+    Security,
+}
+
+impl InspectorProfile {
+    /// Every profile, in the order they are documented.
+    pub const ALL: [InspectorProfile; 5] = [
+        InspectorProfile::Minimal,
+        InspectorProfile::CallTracing,
+        InspectorProfile::Profiling,
+        InspectorProfile::Debugging,
+        InspectorProfile::Security,
+    ];
+
+    /// Returns the name the profile is configured by, e.g. `call_tracing`.
+    pub fn name(self) -> &'static str {
+        match self {
+            InspectorProfile::Minimal => "minimal",
+            InspectorProfile::CallTracing => "call_tracing",
+            InspectorProfile::Profiling => "profiling",
+            InspectorProfile::Debugging => "debugging",
+            InspectorProfile::Security => "security",
+        }
+    }
+}
+
+impl fmt::Display for InspectorProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for InspectorProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|profile| profile.name()).collect();
+                format!("unknown profile `{s}`, expected one of {}", names.join(", "))
+            })
+    }
+}
+
+impl HelloWorldInspectorConfig {
+    /// Returns the configuration of `profile`, whose fields can be changed
+    /// afterwards.
+    ///
+    /// ```
+    /// use restd::{HelloWorldInspectorConfig, InspectorProfile};
+    ///
+    /// let config = HelloWorldInspectorConfig {
+    ///     measure_time: true,
+    ///     ..HelloWorldInspectorConfig::profile(InspectorProfile::Security)
+    /// };
+    /// assert!(config.trace_calls);
+    /// ```
+    pub fn profile(profile: InspectorProfile) -> Self {
+        let base = Self {
+            profile: Some(profile),
+            ..Self::default()
+        };
+        match profile {
+            InspectorProfile::Minimal => Self {
+                profiling: ProfilingMode::Counters,
+                unchecked_call_window: 0,
+                ..base
+            },
+            InspectorProfile::CallTracing => Self {
+                trace_calls: true,
+                profiling: ProfilingMode::Counters,
+                unchecked_call_window: 0,
+                ..base
+            },
+            InspectorProfile::Profiling => Self {
+                attribute_subcall_gas: true,
+                measure_time: true,
+                unchecked_call_window: 0,
+                ..base
+            },
+            InspectorProfile::Debugging => Self {
+                verbose: true,
+                log_steps: true,
+                trace_calls: true,
+                step_sample_interval: 1,
+                capture_code: true,
+                ..base
+            },
+            InspectorProfile::Security => Self {
+                trace_calls: true,
+                alert_on_opcodes: [opcode::CALLCODE, opcode::DELEGATECALL, opcode::SELFDESTRUCT]
+                    .into(),
+                ..base
+            },
+        }
+    }
+
+    /// Serializes the fields with the derived implementation.
+    fn to_fields(&self) -> Result<Map<String, Value>, serde_json::Error> {
+        match Self::serialize(self, serde_json::value::Serializer)? {
+            Value::Object(fields) => Ok(fields),
+            _ => unreachable!("a struct serializes as an object"),
+        }
+    }
+}
+
+/// Without a profile every field is written; with one, only the profile
+/// and the fields that differ from it.
+impl Serialize for HelloWorldInspectorConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(profile) = self.profile else {
+            return Self::serialize(self, serializer);
+        };
+        let fields = self.to_fields().map_err(S::Error::custom)?;
+        let base = Self::profile(profile).to_fields().map_err(S::Error::custom)?;
+        let overrides: Map<String, Value> = fields
+            .into_iter()
+            .filter(|(key, value)| key == "profile" || base.get(key) != Some(value))
+            .collect();
+        overrides.serialize(serializer)
+    }
+}
+
+/// Fields given next to a `profile` override the profile's, merging tables
+/// such as `[limits]` field by field.
+impl<'de> Deserialize<'de> for HelloWorldInspectorConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = Map::<String, Value>::deserialize(deserializer)?;
+        let profile = match fields.get("profile") {
+            Some(profile) => Option::<InspectorProfile>::deserialize(profile)
+                .map_err(D::Error::custom)?,
+            None => None,
+        };
+        let fields = match profile {
+            Some(profile) => {
+                let mut base = Self::profile(profile).to_fields().map_err(D::Error::custom)?;
+                merge(&mut base, fields);
+                base
+            }
+            None => fields,
+        };
+        Self::deserialize(Value::Object(fields)).map_err(D::Error::custom)
+    }
+}
+
+/// Overwrites the fields of `base` with those of `overrides`, recursing
+/// into tables present in both.
+fn merge(base: &mut Map<String, Value>, overrides: Map<String, Value>) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(base)), Value::Object(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Chainable queries over the call records of a report.
+//!
+//! [`InspectorReport::calls`](crate::InspectorReport::calls) starts a
+//! [`CallQuery`], whose combinators narrow the calls down without copying
+//! them:
+//!
+//! ```
+//! # use alloy_primitives::Address;
+//! # let report = restd::InspectorReport::default();
+//! # let vault = Address::ZERO;
+//! let expensive_failures: Vec<_> = report
+//!     .calls()
+//!     .filter_target(vault)
+//!     .filter_min_gas(10_000)
+//!     .failed_only()
+//!     .collect();
+//! ```
+
+use std::{collections::BTreeMap, iter::Filter, ops::RangeBounds};
+
+use alloy_primitives::{Address, Selector};
+
+use crate::CallGasRecord;
+
+/// An iterator over call records with combinators to filter and aggregate
+/// them.
+///
+/// Every combinator is lazy and yields references into the report.
+#[derive(Debug, Clone)]
+pub struct CallQuery<I> {
+    calls: I,
+}
+
+impl<'a, I> CallQuery<I>
+where
+    I: Iterator<Item = &'a CallGasRecord>,
+{
+    /// Starts a query over `calls`.
+    pub fn new(calls: I) -> Self {
+        Self { calls }
+    }
+
+    /// Keeps the calls matching `predicate`.
+    pub fn filter_by<P>(self, predicate: P) -> CallQuery<Filter<I, P>>
+    where
+        P: FnMut(&&'a CallGasRecord) -> bool,
+    {
+        CallQuery::new(self.calls.filter(predicate))
+    }
+
+    /// Keeps the calls to `target`.
+    pub fn filter_target(
+        self,
+        target: Address,
+    ) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(move |call| call.target == target)
+    }
+
+    /// Keeps the calls made by `caller`.
+    pub fn filter_caller(
+        self,
+        caller: Address,
+    ) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(move |call| call.caller == caller)
+    }
+
+    /// Keeps the calls whose calldata starts with `selector`.
+    pub fn filter_selector(
+        self,
+        selector: impl Into<Selector>,
+    ) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        let selector = Some(selector.into());
+        self.filter_by(move |call| call.selector == selector)
+    }
+
+    /// Keeps the calls at a depth within `depths`, the top-level call is at
+    /// depth 0.
+    pub fn filter_depth(
+        self,
+        depths: impl RangeBounds<u64>,
+    ) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(move |call| depths.contains(&call.depth))
+    }
+
+    /// Keeps the calls that used an amount of gas within `gas`.
+    pub fn filter_gas(
+        self,
+        gas: impl RangeBounds<u64>,
+    ) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(move |call| gas.contains(&call.gas_used))
+    }
+
+    /// Keeps the calls that used at least `min` gas.
+    pub fn filter_min_gas(self, min: u64) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_gas(min..)
+    }
+
+    /// Keeps the calls that reverted or halted.
+    pub fn failed_only(self) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(|call| call.reverted)
+    }
+
+    /// Keeps the calls that succeeded.
+    pub fn succeeded_only(self) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(|call| !call.reverted)
+    }
+
+    /// Keeps the calls that carried value.
+    pub fn with_value(self) -> CallQuery<impl Iterator<Item = &'a CallGasRecord>> {
+        self.filter_by(|call| !call.value.is_zero())
+    }
+
+    /// Returns the gas used by the remaining calls, subcalls included in
+    /// each.
+    pub fn sum_gas(self) -> u64 {
+        self.calls.map(|call| call.gas_used).sum()
+    }
+
+    /// Groups the remaining calls by target, keeping their order.
+    pub fn group_by_target(self) -> BTreeMap<Address, Vec<&'a CallGasRecord>> {
+        let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for call in self.calls {
+            groups.entry(call.target).or_default().push(call);
+        }
+        groups
+    }
+}
+
+impl<'a, I> Iterator for CallQuery<I>
+where
+    I: Iterator<Item = &'a CallGasRecord>,
+{
+    type Item = &'a CallGasRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.calls.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.calls.size_hint()
+    }
+}
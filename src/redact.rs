@@ -0,0 +1,57 @@
+//! Redaction of sensitive data from traces.
+//!
+//! Transactions can carry data that must not end up in logs, e.g. the
+//! secret of a commit-reveal scheme. With redaction enabled, calldata,
+//! return data and storage values are replaced with `<redacted {n} bytes>`
+//! when they are recorded, so no report, CSV file, event stream or printed
+//! line contains them. The Keccak-256 hash of the original data is kept so
+//! the same values can still be correlated across traces.
+
+use std::fmt;
+
+use alloy_primitives::{keccak256, Address, B256};
+use serde::{Deserialize, Serialize};
+
+/// Data left out of a trace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Redacted {
+    /// Placeholder for the data, `<redacted {n} bytes>`
+    pub data: String,
+    /// Keccak-256 hash of the original data
+    pub keccak256: B256,
+}
+
+impl Redacted {
+    /// Redacts `bytes`.
+    pub fn new(bytes: &[u8]) -> Self {
+        Self {
+            data: format!("<redacted {} bytes>", bytes.len()),
+            keccak256: keccak256(bytes),
+        }
+    }
+}
+
+impl fmt::Display for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.data)
+    }
+}
+
+/// What to redact from a trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Redaction {
+    /// Redact the calldata and return data of every frame
+    pub calldata: bool,
+    /// Redact every storage value read or written
+    pub storage_values: bool,
+    /// Redact the calldata and return data of frames calling these addresses
+    pub addresses: Vec<Address>,
+}
+
+impl Redaction {
+    /// Returns true if the calldata and return data of a frame executing
+    /// `target` are redacted.
+    pub fn redacts_calldata_of(&self, target: &Address) -> bool {
+        self.calldata || self.addresses.contains(target)
+    }
+}
@@ -0,0 +1,163 @@
+//! Reentrancy screening of call traces.
+//!
+//! [`CallTrace::find_reentrancy`] flags every frame that calls back into a
+//! contract which is still executing further up the call tree, and checks
+//! whether that contract wrote to its storage after the call that led to the
+//! reentry returned, the pattern behind classic reentrancy exploits.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    call_trace::{CallKind, CallTrace, CallTraceNode},
+    geth::geth_type,
+    storage::{StorageAccess, StorageAccessKind},
+};
+
+/// How far up the call tree the reentered contract is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReentrancyKind {
+    /// A contract called back into its immediate caller, e.g. `A -> B -> A`
+    Direct,
+    /// A contract called back into a contract further up, e.g. `A -> B -> C -> A`
+    Indirect,
+}
+
+/// A frame that reentered a contract which was still executing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReentrancyFinding {
+    /// The reentered contract
+    pub address: Address,
+    /// Whether the reentry went back to the immediate caller
+    pub kind: ReentrancyKind,
+    /// How the reentrant frame was entered, serialized as its Geth type,
+    /// e.g. `CALL`
+    #[serde(with = "call_kind")]
+    pub call_kind: CallKind,
+    /// Depth of the reentrant frame
+    pub depth: usize,
+    /// Depth of the frame of the reentered contract that was still executing
+    pub ancestor_depth: usize,
+    /// Targets of the frames from that ancestor down to the reentrant one
+    pub path: Vec<Address>,
+    /// Whether the reentered contract wrote to its storage after the call
+    /// leading to the reentry returned; if not, the reentry could only have
+    /// observed state, not raced an update
+    pub state_changed_after: bool,
+}
+
+impl CallTrace {
+    /// Returns every frame that reentered a contract still executing further
+    /// up the trace, in pre-order.
+    ///
+    /// `storage_accesses` are the accesses recorded by a
+    /// [`HelloWorldInspector`](crate::HelloWorldInspector) that traced the
+    /// same transaction alongside the [`CallTraceInspector`](crate::CallTraceInspector)
+    /// without a filter or step limit, so that their step indices line up.
+    /// Calls of a contract to itself, including delegate calls, are
+    /// recursion rather than reentry and are not reported.
+    pub fn find_reentrancy(&self, storage_accesses: &[StorageAccess]) -> Vec<ReentrancyFinding> {
+        let writes: Vec<&StorageAccess> = storage_accesses
+            .iter()
+            .filter(|access| access.kind == StorageAccessKind::Write)
+            .collect();
+        let mut findings = Vec::new();
+        if let Some(root) = self.root() {
+            visit(root, &mut Vec::new(), &writes, &mut findings);
+        }
+        findings
+    }
+}
+
+/// Checks `node` against its ancestors, then recurses into its children.
+fn visit<'a>(
+    node: &'a CallTraceNode,
+    ancestors: &mut Vec<&'a CallTraceNode>,
+    writes: &[&StorageAccess],
+    findings: &mut Vec<ReentrancyFinding>,
+) {
+    findings.extend(check(node, ancestors, writes));
+    ancestors.push(node);
+    for child in &node.children {
+        visit(child, ancestors, writes, findings);
+    }
+    ancestors.pop();
+}
+
+/// Returns the finding for `node` if it reenters one of `ancestors`.
+fn check(
+    node: &CallTraceNode,
+    ancestors: &[&CallTraceNode],
+    writes: &[&StorageAccess],
+) -> Option<ReentrancyFinding> {
+    if node.kind.is_create() || node.kind == CallKind::SelfDestruct {
+        return None;
+    }
+    let (parent, rest) = ancestors.split_last()?;
+    if parent.target == node.target {
+        return None;
+    }
+    let index = rest
+        .iter()
+        .rposition(|ancestor| ancestor.target == node.target)?;
+    let ancestor = ancestors[index];
+    // The ancestor's call that the reentry happened inside of
+    let outgoing = ancestors[index + 1];
+    let after_return = outgoing.end_step..ancestor.end_step;
+    Some(ReentrancyFinding {
+        address: node.target,
+        kind: if index + 1 == rest.len() {
+            ReentrancyKind::Direct
+        } else {
+            ReentrancyKind::Indirect
+        },
+        call_kind: node.kind,
+        depth: node.depth,
+        ancestor_depth: ancestor.depth,
+        path: ancestors[index..]
+            .iter()
+            .chain([&node])
+            .map(|frame| frame.target)
+            .collect(),
+        state_changed_after: writes
+            .iter()
+            .any(|write| write.address == node.target && after_return.contains(&write.step_index)),
+    })
+}
+
+/// Serializes the kind of a reentrant frame as its Geth type. Reentered
+/// contracts are never precompiles, so the type maps back to the same kind.
+mod call_kind {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::{geth_type, CallKind};
+
+    pub(super) fn serialize<S: Serializer>(
+        kind: &CallKind,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(geth_type(*kind))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<CallKind, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        [
+            CallKind::Call,
+            CallKind::StaticCall,
+            CallKind::DelegateCall,
+            CallKind::CallCode,
+            CallKind::Create,
+            CallKind::Create2,
+            CallKind::EofCreate {
+                container_hash: None,
+            },
+            CallKind::SelfDestruct,
+        ]
+        .into_iter()
+        .find(|kind| geth_type(*kind) == name)
+        .ok_or_else(|| D::Error::custom(format!("unknown call kind `{name}`")))
+    }
+}
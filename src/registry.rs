@@ -0,0 +1,96 @@
+//! Registry of inspector plugins.
+//!
+//! [`PluginRegistry`] stores [`InspectorPlugin`]s by name and builds an
+//! [`InspectorStack`] from the inspectors of selected plugins.
+
+use std::{collections::BTreeMap, fmt};
+
+use revm::Database;
+
+use crate::{Error, InspectorPlugin, InspectorStack, PluginHealth};
+
+/// Inspector plugins, keyed by name.
+pub struct PluginRegistry<DB: Database> {
+    plugins: BTreeMap<String, Box<dyn InspectorPlugin<DB>>>,
+}
+
+impl<DB: Database> fmt::Debug for PluginRegistry<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginRegistry")
+            .field("plugins", &self.plugins)
+            .finish()
+    }
+}
+
+impl<DB: Database> Default for PluginRegistry<DB> {
+    fn default() -> Self {
+        Self {
+            plugins: BTreeMap::new(),
+        }
+    }
+}
+
+impl<DB: Database> PluginRegistry<DB> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `plugin` under its name, rejecting names already taken.
+    pub fn register(
+        &mut self,
+        plugin: impl InspectorPlugin<DB> + 'static,
+    ) -> Result<(), Error> {
+        let name = plugin.name().to_string();
+        if self.plugins.contains_key(&name) {
+            return Err(Error::PluginAlreadyRegistered(name));
+        }
+        self.plugins.insert(name, Box::new(plugin));
+        Ok(())
+    }
+
+    /// Returns the plugin registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&dyn InspectorPlugin<DB>> {
+        self.plugins.get(name).map(|plugin| plugin.as_ref())
+    }
+
+    /// Returns the names of the registered plugins in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.keys().map(String::as_str)
+    }
+
+    /// Calls [`InspectorPlugin::on_start`] on every plugin, in name order.
+    pub fn start_all(&self) {
+        self.plugins.values().for_each(|plugin| plugin.on_start());
+    }
+
+    /// Calls [`InspectorPlugin::on_shutdown`] on every plugin, in name order.
+    pub fn shutdown_all(&self) {
+        self.plugins.values().for_each(|plugin| plugin.on_shutdown());
+    }
+
+    /// Returns the health of every plugin by name.
+    pub fn health_all(&self) -> BTreeMap<String, PluginHealth> {
+        self.plugins
+            .iter()
+            .map(|(name, plugin)| (name.clone(), plugin.health()))
+            .collect()
+    }
+
+    /// Creates an inspector from each named plugin and stacks them in the
+    /// given order.
+    pub fn build_stack<S: AsRef<str>>(
+        &self,
+        names: &[S],
+    ) -> Result<InspectorStack<DB>, Error> {
+        let mut stack = InspectorStack::new();
+        for name in names {
+            let name = name.as_ref();
+            let plugin = self
+                .get(name)
+                .ok_or_else(|| Error::UnknownPlugin(name.to_string()))?;
+            stack.push_boxed(plugin.create_inspector());
+        }
+        Ok(stack)
+    }
+}
@@ -0,0 +1,180 @@
+//! Reloading a plugin's configuration while it runs.
+//!
+//! [`HelloWorldInspectorPlugin::reload`](crate::HelloWorldInspectorPlugin::reload)
+//! replaces the configuration the plugin shares with the inspectors it
+//! created. Each inspector applies it when its next transaction starts, so a
+//! transaction is always traced with one configuration. The sink, the
+//! profiling mode and the trace limits are set up once and cannot change
+//! without restarting the plugin.
+//!
+//! With the `watch` feature, a [`ConfigWatcher`] reloads the plugin whenever
+//! its TOML file changes.
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
+#[cfg(feature = "watch")]
+use std::{
+    path::PathBuf,
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+#[cfg(feature = "watch")]
+use tracing::{info, warn};
+
+#[cfg(feature = "watch")]
+use crate::HelloWorldInspectorPlugin;
+use crate::{Error, HelloWorldInspectorConfig};
+
+/// A configuration shared between a plugin and its inspectors, numbered so
+/// that inspectors can tell when it changed.
+#[derive(Debug, Default)]
+pub(crate) struct SharedConfig {
+    current: RwLock<(u64, Arc<HelloWorldInspectorConfig>)>,
+    /// Copy of the number in `current`, checked without locking
+    version: AtomicU64,
+}
+
+impl SharedConfig {
+    pub(crate) fn new(config: HelloWorldInspectorConfig) -> Self {
+        Self {
+            current: RwLock::new((0, Arc::new(config))),
+            version: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the current configuration and its number.
+    pub(crate) fn get(&self) -> (u64, Arc<HelloWorldInspectorConfig>) {
+        let current = self
+            .current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        (current.0, current.1.clone())
+    }
+
+    /// Returns the current configuration if it is newer than `version`.
+    pub(crate) fn newer_than(&self, version: u64) -> Option<(u64, Arc<HelloWorldInspectorConfig>)> {
+        if self.version.load(Ordering::Acquire) == version {
+            return None;
+        }
+        Some(self.get())
+    }
+
+    /// Replaces the configuration, including the options that cannot change
+    /// while running, e.g. before any inspector was created.
+    pub(crate) fn set(&self, config: HelloWorldInspectorConfig) {
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let version = current.0 + 1;
+        *current = (version, Arc::new(config));
+        self.version.store(version, Ordering::Release);
+    }
+
+    /// Replaces the configuration if only options that can change while
+    /// running differ.
+    pub(crate) fn replace(&self, config: HelloWorldInspectorConfig) -> Result<(), Error> {
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        check_reloadable(&current.1, &config)?;
+        let version = current.0 + 1;
+        *current = (version, Arc::new(config));
+        self.version.store(version, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Returns [`Error::RequiresRestart`] for the first option that differs
+/// between `old` and `new` but cannot change while running.
+fn check_reloadable(
+    old: &HelloWorldInspectorConfig,
+    new: &HelloWorldInspectorConfig,
+) -> Result<(), Error> {
+    if old.sink_path != new.sink_path {
+        return Err(Error::RequiresRestart("sink_path"));
+    }
+    // Reports would mix sampled and recorded steps
+    if old.profiling != new.profiling {
+        return Err(Error::RequiresRestart("profiling"));
+    }
+    // Byte budgets are sized when an inspector is created
+    if old.limits != new.limits {
+        return Err(Error::RequiresRestart("limits"));
+    }
+    Ok(())
+}
+
+/// Reloads a plugin from a TOML file whenever its contents change.
+///
+/// The file is read every `interval` on a background thread, with the same
+/// parsing and validation as
+/// [`from_toml_file`](HelloWorldInspectorConfig::from_toml_file). A file
+/// that does not parse or changes an option that requires a restart is
+/// logged and ignored, keeping the current configuration. The thread stops
+/// when the watcher is dropped.
+#[cfg(feature = "watch")]
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "watch")]
+impl ConfigWatcher {
+    /// Starts watching `path` for `plugin`. The current contents count as
+    /// unchanged, so the first reload happens on the first edit.
+    pub fn spawn(
+        plugin: HelloWorldInspectorPlugin,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Self {
+        let path = path.into();
+        let (stop, stopped) = mpsc::channel();
+        let mut last = std::fs::read_to_string(&path).ok();
+        let thread = std::thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let Ok(toml) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                if last.as_ref() == Some(&toml) {
+                    continue;
+                }
+                let result = HelloWorldInspectorConfig::from_toml_str(&toml)
+                    .map_err(Error::from)
+                    .and_then(|config| plugin.reload(config));
+                match result {
+                    Ok(()) => info!(
+                        "Reloaded HelloWorldInspector config from {}",
+                        path.display()
+                    ),
+                    Err(err) => warn!(
+                        "Ignoring HelloWorldInspector config {}: {err}",
+                        path.display()
+                    ),
+                }
+                last = Some(toml);
+            }
+        });
+        Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(feature = "watch")]
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        // Dropping the sender wakes the thread up
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
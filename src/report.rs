@@ -0,0 +1,585 @@
+//! Serializable summary of what an inspector observed.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io,
+    ops::{Add, AddAssign},
+};
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calls::is_false, executed, failures, memory, opcodes, selector::selector_hex, storage, AddressStats, AuthorizationRecord, BlobOpcode, BlobOpcodeUse,
+    BlobTransaction, CallGasRecord, CallQuery,
+    CallTrace, CodeSizeFinding, CollectedLog, CoverageMap, CreateRecord, ExecutedContract, FailureStats, FrameMemory, GasSummary, InjectedFault, InternalError, InternalInconsistency, LowGasAlarm, MemoryExpansion, OpcodeAlert,
+    OpcodeHistogram, OriginUsage, OutOfGasFrame, PcCoverage, ReentrancyFinding, RevertRecord,
+    ReportTimeline, SelfdestructRecord, SpecHalt, SpecWarning, StaticViolationAttempt,
+    StorageAccess, TimelineEvent, TruncatedCollections, TxTiming, UncheckedCallFinding,
+    ValueTransfer,
+};
+
+/// Snapshot of the data collected by a [`HelloWorldInspector`](crate::HelloWorldInspector).
+///
+/// Maps are ordered so the JSON output is stable and can be snapshot-tested.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InspectorReport {
+    /// Number of interpreter steps executed
+    pub step_count: u64,
+    /// Number of calls made
+    pub call_count: u64,
+    /// Number of EOF contract creations
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub eofcreate_count: u64,
+    /// Deepest nesting of frames reached, counting the top-level frame as 1
+    #[serde(default)]
+    pub max_depth: usize,
+    /// Execution count per opcode, keyed by opcode name
+    pub opcode_counts: BTreeMap<String, u64>,
+    /// Gas accounting for every call, in the order the calls started
+    pub calls: Vec<CallGasRecord>,
+    /// Call statistics per address, counting calls beyond `max_call_records`
+    #[serde(default)]
+    pub address_stats: BTreeMap<Address, AddressStats>,
+    /// Every reverted frame with its decoded reason
+    #[serde(default)]
+    pub reverts: Vec<RevertRecord>,
+    /// Every executed `SELFDESTRUCT`
+    #[serde(default)]
+    pub selfdestructs: Vec<SelfdestructRecord>,
+    /// Every contract creation, including failed ones
+    #[serde(default)]
+    pub creations: Vec<CreateRecord>,
+    /// Init code and deployed code within 10% of their size limits or over them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_size_findings: Vec<CodeSizeFinding>,
+    /// Every emitted log, including those of reverted frames
+    #[serde(default)]
+    pub logs: Vec<CollectedLog>,
+    /// Every non-zero value transfer, including reverted ones
+    #[serde(default)]
+    pub transfers: Vec<ValueTransfer>,
+    /// Every `SLOAD` and `SSTORE`, including those of reverted frames
+    #[serde(default)]
+    pub storage_accesses: Vec<StorageAccess>,
+    /// Every `TLOAD` and `TSTORE`, including those of reverted frames
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transient_accesses: Vec<StorageAccess>,
+    /// `TSTORE`s never read back before being overwritten or cleared at the
+    /// end of their transaction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unused_transient_writes: Vec<StorageAccess>,
+    /// Whether step recording stopped early because `max_steps` was reached
+    #[serde(default)]
+    pub truncated: bool,
+    /// Collections that stopped growing because a `TraceLimits` cap was reached
+    #[serde(default)]
+    pub truncated_collections: TruncatedCollections,
+    /// Panics caught with `catch_panics` in callbacks and recording
+    #[serde(default)]
+    pub internal_errors: Vec<InternalError>,
+    /// Inconsistencies in the inspector's bookkeeping found with `strict`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub internal_inconsistencies: Vec<InternalInconsistency>,
+    /// Whether `strict` found an inconsistency, so the rest of the report
+    /// cannot be trusted
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub poisoned: bool,
+    /// How often each `JUMPI` jumped and fell through, with
+    /// `branch_coverage`
+    #[serde(default, skip_serializing_if = "CoverageMap::is_empty")]
+    pub branch_coverage: CoverageMap,
+    /// How often each instruction of each contract executed, with
+    /// `pc_coverage`
+    #[serde(default, skip_serializing_if = "PcCoverage::is_empty")]
+    pub pc_coverage: PcCoverage,
+    /// Gas accounting of the last transaction, if its top-level frame ended
+    #[serde(default)]
+    pub gas_summary: Option<GasSummary>,
+    /// Wall-clock duration of every transaction, with `measure_time`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tx_timings: Vec<TxTiming>,
+    /// Every distinct code that ran, with the steps executed in it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executed_contracts: Vec<ExecutedContract>,
+    /// First instruction in each frame to leave it below `low_gas_percent`
+    /// of its gas limit
+    #[serde(default)]
+    pub low_gas_alarms: Vec<LowGasAlarm>,
+    /// Frames that ran out of gas
+    #[serde(default)]
+    pub oog_frames: Vec<OutOfGasFrame>,
+    /// Failed frames per failure kind, each with the first frame that
+    /// failed that way
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<FailureStats>,
+    /// Calls a [`FaultInjector`](crate::FaultInjector) forced to fail, in
+    /// execution order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub injected_faults: Vec<InjectedFault>,
+    /// Memory high-water mark and expansion gas of every frame that used
+    /// memory, in the order the frames ended
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_frames: Vec<FrameMemory>,
+    /// Instructions that grew memory by more than
+    /// `memory_expansion_threshold`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_expansions: Vec<MemoryExpansion>,
+    /// Patterns that commonly lead to vulnerabilities
+    #[serde(default)]
+    pub security: SecurityFindings,
+    /// Executions of the opcodes in `alert_on_opcodes`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub opcode_alerts: Vec<OpcodeAlert>,
+    /// Executed opcodes the hardfork given to
+    /// [`with_spec_awareness`](crate::HelloWorldInspector::with_spec_awareness)
+    /// does not support
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spec_warnings: Vec<SpecWarning>,
+    /// Transactions that halted on an invalid opcode after one of the
+    /// `spec_warnings`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spec_halts: Vec<SpecHalt>,
+    /// EIP-7702 authorizations of the traced transactions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authorizations: Vec<AuthorizationRecord>,
+    /// Blobs of the traced blob transactions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blob_transactions: Vec<BlobTransaction>,
+    /// Executions of `BLOBHASH` and `BLOBBASEFEE`, flagging `BLOBHASH`
+    /// indices past the last blob hash
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blob_opcodes: Vec<BlobOpcodeUse>,
+    /// Where the report came from, e.g. the thread or block range traced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<String>,
+}
+
+/// Security-relevant patterns observed during execution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityFindings {
+    /// Every executed `ORIGIN`, flagging those compared right away
+    #[serde(default)]
+    pub origin_usages: Vec<OriginUsage>,
+    /// Reentrant frames, empty unless added with
+    /// [`InspectorReport::with_reentrancy`]
+    #[serde(default)]
+    pub reentrancy: Vec<ReentrancyFinding>,
+    /// State changes attempted in static frames, which revm halted
+    #[serde(default)]
+    pub static_violations: Vec<StaticViolationAttempt>,
+    /// Low-level calls whose success flag was popped without being checked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unchecked_calls: Vec<UncheckedCallFinding>,
+}
+
+impl InspectorReport {
+    /// Tags the report and its call, log and storage records with `tag`,
+    /// keeping the tags of records merged from other reports.
+    pub fn with_source_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        self.tag_records(&tag);
+        self.source_tag = Some(tag);
+        self
+    }
+
+    /// Merges `other` into this report, e.g. the report of a transaction
+    /// traced on another thread.
+    ///
+    /// Counters, opcode counts and address statistics are summed, records
+    /// are appended after those of this report and tagged with the
+    /// `source_tag` of `other` if they have none, and the gas summary is
+    /// that of `other` if it has one.
+    pub fn merge(&mut self, mut other: InspectorReport) {
+        if let Some(tag) = other.source_tag.take() {
+            other.tag_records(&tag);
+        }
+        self.step_count += other.step_count;
+        self.call_count += other.call_count;
+        self.eofcreate_count += other.eofcreate_count;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        for (name, count) in other.opcode_counts {
+            *self.opcode_counts.entry(name).or_default() += count;
+        }
+        self.calls.append(&mut other.calls);
+        for (address, stats) in &other.address_stats {
+            self.address_stats.entry(*address).or_default().merge(stats);
+        }
+        self.reverts.append(&mut other.reverts);
+        self.selfdestructs.append(&mut other.selfdestructs);
+        self.creations.append(&mut other.creations);
+        self.code_size_findings.append(&mut other.code_size_findings);
+        self.logs.append(&mut other.logs);
+        self.transfers.append(&mut other.transfers);
+        self.storage_accesses.append(&mut other.storage_accesses);
+        self.transient_accesses.append(&mut other.transient_accesses);
+        self.unused_transient_writes.append(&mut other.unused_transient_writes);
+        self.truncated |= other.truncated;
+        self.truncated_collections.merge(&other.truncated_collections);
+        self.internal_errors.append(&mut other.internal_errors);
+        self.internal_inconsistencies.append(&mut other.internal_inconsistencies);
+        self.poisoned |= other.poisoned;
+        self.branch_coverage.merge(&other.branch_coverage);
+        self.pc_coverage.merge(&other.pc_coverage);
+        self.gas_summary = other.gas_summary.or(self.gas_summary);
+        self.tx_timings.append(&mut other.tx_timings);
+        executed::merge(&mut self.executed_contracts, other.executed_contracts);
+        self.low_gas_alarms.append(&mut other.low_gas_alarms);
+        self.oog_frames.append(&mut other.oog_frames);
+        failures::merge(&mut self.failures, other.failures);
+        self.injected_faults.append(&mut other.injected_faults);
+        self.memory_frames.append(&mut other.memory_frames);
+        self.memory_expansions.append(&mut other.memory_expansions);
+        self.opcode_alerts.append(&mut other.opcode_alerts);
+        self.spec_warnings.append(&mut other.spec_warnings);
+        self.spec_halts.append(&mut other.spec_halts);
+        self.authorizations.append(&mut other.authorizations);
+        self.blob_transactions.append(&mut other.blob_transactions);
+        self.blob_opcodes.append(&mut other.blob_opcodes);
+        let security = &mut self.security;
+        security.origin_usages.append(&mut other.security.origin_usages);
+        security.reentrancy.append(&mut other.security.reentrancy);
+        security.static_violations.append(&mut other.security.static_violations);
+        security.unchecked_calls.append(&mut other.security.unchecked_calls);
+    }
+
+    /// Returns the `n` addresses that received the most calls.
+    ///
+    /// Ties are broken by gas used, then by address, as in
+    /// [`HelloWorldInspector::busiest_addresses`](crate::HelloWorldInspector::busiest_addresses).
+    pub fn busiest_addresses(&self, n: usize) -> Vec<(Address, &AddressStats)> {
+        let mut entries: Vec<(Address, &AddressStats)> =
+            self.address_stats.iter().map(|(addr, stats)| (*addr, stats)).collect();
+        entries.sort_by(|a, b| {
+            b.1.calls
+                .cmp(&a.1.calls)
+                .then(b.1.gas_used.cmp(&a.1.gas_used))
+                .then(a.0.cmp(&b.0))
+        });
+        entries.truncate(n);
+        entries
+    }
+
+    fn tag_records(&mut self, tag: &str) {
+        let tags = self.calls.iter_mut().map(|call| &mut call.source_tag);
+        let tags = tags.chain(self.logs.iter_mut().map(|log| &mut log.source_tag));
+        let tags = tags.chain(
+            self.storage_accesses
+                .iter_mut()
+                .chain(&mut self.transient_accesses)
+                .chain(&mut self.unused_transient_writes)
+                .map(|access| &mut access.source_tag),
+        );
+        for source_tag in tags.filter(|source_tag| source_tag.is_none()) {
+            *source_tag = Some(tag.to_string());
+        }
+    }
+
+    /// Formats the storage accesses with persistent and transient storage
+    /// in separate sections, see [`storage::pretty_print`].
+    pub fn pretty_print_storage(&self) -> String {
+        storage::pretty_print(
+            &self.storage_accesses,
+            &self.transient_accesses,
+            &self.unused_transient_writes,
+        )
+    }
+
+    /// Returns how many times `opcode` was executed.
+    pub fn blob_opcode_count(&self, opcode: BlobOpcode) -> usize {
+        self.blob_opcodes.iter().filter(|used| used.opcode == opcode).count()
+    }
+
+    /// Returns the `BLOBHASH` reads past the last blob hash of their
+    /// transaction, which read zero.
+    pub fn out_of_range_blob_hashes(&self) -> impl Iterator<Item = &BlobOpcodeUse> {
+        self.blob_opcodes.iter().filter(|used| used.out_of_range)
+    }
+
+    /// Returns the `n` frames whose memory grew the largest, largest first.
+    pub fn top_memory_frames(&self, n: usize) -> Vec<&FrameMemory> {
+        memory::top_frames(&self.memory_frames, n)
+    }
+
+    /// Returns the gas paid for memory expansion by every frame, which for
+    /// the report of one transaction is the transaction's total.
+    pub fn memory_expansion_gas(&self) -> u64 {
+        self.memory_frames.iter().map(|frame| frame.expansion_gas).sum()
+    }
+
+    /// Starts a query over the call records, e.g.
+    /// `report.calls().filter_target(vault).failed_only().sum_gas()`.
+    pub fn calls(&self) -> CallQuery<std::slice::Iter<'_, CallGasRecord>> {
+        CallQuery::new(self.calls.iter())
+    }
+
+    /// Returns the calls that failed on their own, leaving out those a
+    /// [`FaultInjector`](crate::FaultInjector) forced to fail.
+    pub fn organic_failed_calls(&self) -> impl Iterator<Item = &CallGasRecord> {
+        self.calls.iter().filter(|record| record.reverted && !record.injected)
+    }
+
+    /// Returns the calls that were granted less gas than their calling
+    /// instruction asked for, because of the 63/64 rule.
+    pub fn gas_capped_frames(&self) -> impl Iterator<Item = &CallGasRecord> {
+        self.calls.iter().filter(|record| record.gas_capped)
+    }
+
+    /// Returns the `n` call and create frames that took the longest
+    /// wall-clock time, longest first.
+    ///
+    /// Frames are only timed with `measure_time`; ties are broken by the
+    /// order the frames started.
+    pub fn slowest_frames(&self, n: usize) -> Vec<TimelineEvent<'_>> {
+        let calls = self
+            .calls
+            .iter()
+            .map(|call| (call.duration_micros, TimelineEvent::Call(call)));
+        let creations = self
+            .creations
+            .iter()
+            .map(|creation| (creation.duration_micros, TimelineEvent::Creation(creation)));
+        let mut frames: Vec<_> = calls
+            .chain(creations)
+            .filter_map(|(duration, frame)| Some((duration?, frame)))
+            .collect();
+        frames.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.seq().cmp(&b.1.seq())));
+        frames.into_iter().take(n).map(|(_, frame)| frame).collect()
+    }
+
+    /// Returns the call, log, storage, transfer, creation, revert and
+    /// `SELFDESTRUCT` records in the order they were recorded.
+    pub fn timeline(&self) -> ReportTimeline<'_> {
+        ReportTimeline::new(self)
+    }
+
+    /// Adds the reentrancy findings of `trace`, a call trace of the same
+    /// transaction, checked against the storage accesses of this report.
+    pub fn with_reentrancy(mut self, trace: &CallTrace) -> Self {
+        self.security.reentrancy = trace.find_reentrancy(&self.storage_accesses);
+        self
+    }
+
+    /// Returns how often each `JUMPI` jumped and fell through, empty unless
+    /// the inspector had `branch_coverage` set.
+    pub fn coverage(&self) -> &CoverageMap {
+        &self.branch_coverage
+    }
+
+    /// Serializes the report as a JSON string.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Serializes the report as an indented JSON string.
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes one CSV row per call, in the order the calls started.
+    ///
+    /// Addresses are `0x`-prefixed lowercase hex and values are decimal.
+    pub fn to_csv<W: io::Write>(&self, mut w: W) -> io::Result<()> {
+        write_csv_row(&mut w, &CALL_CSV_HEADER)?;
+        for call in &self.calls {
+            write_csv_row(
+                &mut w,
+                &[
+                    &call.tx_index.to_string(),
+                    &call.depth.to_string(),
+                    &call.kind,
+                    &format!("{:#x}", call.caller),
+                    &format!("{:#x}", call.target),
+                    &call.selector.map(|s| selector_hex(s.0)).unwrap_or_default(),
+                    &call.value.to_string(),
+                    &call.gas_limit.to_string(),
+                    &call.gas_used.to_string(),
+                    &(!call.reverted).to_string(),
+                    call.revert_reason.as_deref().unwrap_or_default(),
+                    &call.seq.to_string(),
+                    call.label.as_deref().unwrap_or_default(),
+                ],
+            )?;
+        }
+        w.flush()
+    }
+
+    /// Writes one CSV row per executed opcode with its execution count.
+    pub fn opcode_counts_to_csv<W: io::Write>(&self, mut w: W) -> io::Result<()> {
+        write_csv_row(&mut w, &["opcode", "count"])?;
+        for (name, count) in &self.opcode_counts {
+            write_csv_row(&mut w, &[name, &count.to_string()])?;
+        }
+        w.flush()
+    }
+}
+
+impl AddAssign for InspectorReport {
+    fn add_assign(&mut self, other: InspectorReport) {
+        self.merge(other);
+    }
+}
+
+impl Add for InspectorReport {
+    type Output = InspectorReport;
+
+    fn add(mut self, other: InspectorReport) -> InspectorReport {
+        self.merge(other);
+        self
+    }
+}
+
+/// Columns written by [`InspectorReport::to_csv`].
+const CALL_CSV_HEADER: [&str; 13] = [
+    "tx_index",
+    "depth",
+    "kind",
+    "caller",
+    "target",
+    "selector",
+    "value",
+    "gas_limit",
+    "gas_used",
+    "success",
+    "revert_reason",
+    "seq",
+    "label",
+];
+
+/// Writes a CSV row terminated by CRLF, as RFC 4180 specifies.
+fn write_csv_row(w: &mut impl io::Write, fields: &[&str]) -> io::Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            w.write_all(b",")?;
+        }
+        w.write_all(csv_field(field).as_bytes())?;
+    }
+    w.write_all(b"\r\n")
+}
+
+/// Quotes `field` if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+fn is_zero(count: &u64) -> bool {
+    *count == 0
+}
+
+/// Converts a histogram into the name-keyed map used by reports.
+pub(crate) fn opcode_count_map(histogram: &OpcodeHistogram) -> BTreeMap<String, u64> {
+    histogram
+        .iter()
+        .map(|(op, count)| (opcodes::display_name(op), count))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+
+    const GOLDEN: &str = r#"{"step_count":4,"call_count":1,"max_depth":1,"opcode_counts":{"0x0c":1,"ADD":1,"PUSH1":2},"calls":[{"target":"0x3030303030303030303030303030303030303030","bytecode_address":"0x3030303030303030303030303030303030303030","caller":"0x3131313131313131313131313131313131313131","depth":0,"kind":"CALL","value":"0x5","gas_limit":100000,"gas_used":9,"reverted":false,"tx_index":0,"seq":0}],"address_stats":{},"reverts":[],"selfdestructs":[],"creations":[],"logs":[],"transfers":[],"storage_accesses":[],"truncated":false,"truncated_collections":{"calls":false,"logs":false,"storage_accesses":false,"bytes":false,"preimages":false},"internal_errors":[],"gas_summary":null,"low_gas_alarms":[],"oog_frames":[],"security":{"origin_usages":[],"reentrancy":[],"static_violations":[]}}"#;
+
+    fn sample_report() -> InspectorReport {
+        let mut histogram = OpcodeHistogram::default();
+        for op in [0x60, 0x60, 0x01, 0x0c] {
+            histogram.record(op);
+        }
+        InspectorReport {
+            step_count: 4,
+            call_count: 1,
+            eofcreate_count: 0,
+            max_depth: 1,
+            opcode_counts: opcode_count_map(&histogram),
+            calls: vec![CallGasRecord {
+                target: Address::repeat_byte(0x30),
+                label: None,
+                bytecode_address: Address::repeat_byte(0x30),
+                caller: Address::repeat_byte(0x31),
+                delegated: false,
+                delegate: None,
+                depth: 0,
+                kind: "CALL".to_string(),
+                value: U256::from(5),
+                gas_limit: 100_000,
+                gas_requested: None,
+                gas_capped: false,
+                gas_used: 9,
+                reverted: false,
+                tx_index: 0,
+                seq: 0,
+                selector: None,
+                function: None,
+                decoded: None,
+                decode_error: None,
+                revert_reason: None,
+                no_code: false,
+                injected: false,
+                mocked: false,
+                output: None,
+                redacted_calldata: None,
+                duration_micros: None,
+                source_tag: None,
+            }],
+            address_stats: BTreeMap::new(),
+            reverts: Vec::new(),
+            selfdestructs: Vec::new(),
+            creations: Vec::new(),
+            code_size_findings: Vec::new(),
+            logs: Vec::new(),
+            transfers: Vec::new(),
+            storage_accesses: Vec::new(),
+            transient_accesses: Vec::new(),
+            unused_transient_writes: Vec::new(),
+            truncated: false,
+            truncated_collections: TruncatedCollections::default(),
+            internal_errors: Vec::new(),
+            internal_inconsistencies: Vec::new(),
+            poisoned: false,
+            branch_coverage: CoverageMap::default(),
+            pc_coverage: PcCoverage::default(),
+            gas_summary: None,
+            tx_timings: Vec::new(),
+            executed_contracts: Vec::new(),
+            low_gas_alarms: Vec::new(),
+            oog_frames: Vec::new(),
+            failures: Vec::new(),
+            injected_faults: Vec::new(),
+            memory_frames: Vec::new(),
+            memory_expansions: Vec::new(),
+            security: SecurityFindings::default(),
+            opcode_alerts: Vec::new(),
+            spec_warnings: Vec::new(),
+            spec_halts: Vec::new(),
+            authorizations: Vec::new(),
+            blob_transactions: Vec::new(),
+            blob_opcodes: Vec::new(),
+            source_tag: None,
+        }
+    }
+
+    #[test]
+    fn test_report_json_matches_golden() {
+        assert_eq!(sample_report().to_json().unwrap(), GOLDEN);
+    }
+
+    #[test]
+    fn test_report_json_round_trip() {
+        let report: InspectorReport = serde_json::from_str(GOLDEN).unwrap();
+        assert_eq!(report, sample_report());
+        assert_eq!(report.to_json().unwrap(), GOLDEN);
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"no\""), "\"say \"\"no\"\"\"");
+    }
+}
@@ -0,0 +1,106 @@
+//! Data returned by call and create frames.
+//!
+//! Frames keep at most
+//! [`max_output_bytes`](crate::HelloWorldInspectorConfig::max_output_bytes) of
+//! their output, along with its full length and Keccak-256 hash so outputs
+//! too long to keep can still be told apart and correlated. Truncated output
+//! is shown as hex followed by `…(+{n} bytes)`, in JSON as well as when
+//! printed.
+
+use std::fmt;
+
+use alloy_primitives::{hex, keccak256, Bytes, B256};
+use serde::{Deserialize, Serialize};
+
+/// Output bytes kept per frame unless configured otherwise.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 1024;
+
+/// The output of a frame, possibly truncated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "ReturnDataRepr", try_from = "ReturnDataRepr")]
+pub struct ReturnData {
+    /// Leading bytes of the output
+    pub data: Bytes,
+    /// Length of the full output
+    pub len: usize,
+    /// Keccak-256 hash of the full output
+    pub keccak256: B256,
+}
+
+impl ReturnData {
+    /// Keeps the first `max` bytes of `output`, or returns `None` if the frame
+    /// returned nothing, e.g. a plain value transfer.
+    pub fn capture(output: &[u8], max: usize) -> Option<Self> {
+        if output.is_empty() {
+            return None;
+        }
+        Some(Self {
+            data: Bytes::copy_from_slice(&output[..output.len().min(max)]),
+            len: output.len(),
+            keccak256: keccak256(output),
+        })
+    }
+
+    /// Returns true if bytes of the output were left out.
+    pub fn is_truncated(&self) -> bool {
+        self.data.len() < self.len
+    }
+
+    /// Drops the kept bytes, leaving the length and hash.
+    pub(crate) fn clear(&mut self) {
+        self.data = Bytes::new();
+    }
+}
+
+impl fmt::Display for ReturnData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode_prefixed(&self.data))?;
+        if self.is_truncated() {
+            write!(f, "…(+{} bytes)", self.len - self.data.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReturnDataRepr {
+    data: String,
+    len: usize,
+    keccak256: B256,
+}
+
+impl From<ReturnData> for ReturnDataRepr {
+    fn from(output: ReturnData) -> Self {
+        Self {
+            data: output.to_string(),
+            len: output.len,
+            keccak256: output.keccak256,
+        }
+    }
+}
+
+impl TryFrom<ReturnDataRepr> for ReturnData {
+    type Error = hex::FromHexError;
+
+    fn try_from(repr: ReturnDataRepr) -> Result<Self, Self::Error> {
+        let data = repr.data.split('…').next().unwrap_or_default();
+        Ok(Self {
+            data: hex::decode(data)?.into(),
+            len: repr.len,
+            keccak256: repr.keccak256,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncated_round_trip() {
+        let output = ReturnData::capture(&[0xaa; 4], 2).unwrap();
+        let json = serde_json::to_string(&output).unwrap();
+        assert!(json.starts_with(r#"{"data":"0xaaaa…(+2 bytes)","len":4,"#));
+        assert_eq!(serde_json::from_str::<ReturnData>(&json).unwrap(), output);
+    }
+}
@@ -0,0 +1,230 @@
+//! Revert reason extraction and decoding.
+
+use std::fmt;
+
+use alloy_primitives::{hex, Address, Bytes, U256};
+use alloy_sol_types::{Panic, Revert, SolError};
+use revm::interpreter::{InstructionResult, InterpreterResult};
+use serde::{Deserialize, Serialize};
+
+use crate::{redact::Redacted, SourceLocation};
+
+/// Decoded revert data of a failed frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RevertReason {
+    /// The frame reverted without data, e.g. `revert()` or `require(cond)`
+    Empty,
+    /// A standard `Error(string)` revert
+    Error {
+        /// The revert message
+        message: String,
+    },
+    /// A compiler-inserted `Panic(uint256)` revert
+    Panic {
+        /// The panic code
+        code: U256,
+        /// Description of the panic code, if it is a known one
+        description: Option<String>,
+    },
+    /// A custom error, or data that does not decode as a known error
+    Custom {
+        /// The first four bytes of the data, if present
+        selector: Option<[u8; 4]>,
+        /// The full revert data
+        data: Bytes,
+    },
+    /// Revert data left out of the trace by redaction
+    Redacted(Redacted),
+}
+
+impl RevertReason {
+    /// Decodes raw revert data.
+    pub fn decode(data: &[u8]) -> Self {
+        if data.is_empty() {
+            return RevertReason::Empty;
+        }
+        if data.starts_with(&Revert::SELECTOR) {
+            if let Ok(revert) = Revert::abi_decode(data, true) {
+                return RevertReason::Error {
+                    message: revert.reason,
+                };
+            }
+        }
+        if data.starts_with(&Panic::SELECTOR) {
+            if let Ok(panic) = Panic::abi_decode(data, true) {
+                return RevertReason::Panic {
+                    code: panic.code,
+                    description: panic_description(panic.code).map(str::to_string),
+                };
+            }
+        }
+        RevertReason::Custom {
+            selector: data
+                .get(..4)
+                .map(|s| s.try_into().expect("slice of length 4")),
+            data: Bytes::copy_from_slice(data),
+        }
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Empty => write!(f, "<empty revert data>"),
+            RevertReason::Error { message } => write!(f, "{message}"),
+            RevertReason::Panic {
+                code,
+                description: Some(description),
+            } => write!(f, "panic: {description} ({code:#x})"),
+            RevertReason::Panic {
+                code,
+                description: None,
+            } => write!(f, "panic: unknown code ({code:#x})"),
+            RevertReason::Custom { data, .. } => {
+                write!(f, "custom error {}", hex::encode_prefixed(data))
+            }
+            RevertReason::Redacted(redacted) => write!(f, "{redacted}"),
+        }
+    }
+}
+
+/// Returns the Solidity description of a `Panic(uint256)` code.
+pub fn panic_description(code: U256) -> Option<&'static str> {
+    let code: u64 = code.try_into().ok()?;
+    Some(match code {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic underflow or overflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized internal function",
+        _ => return None,
+    })
+}
+
+/// A reverted frame together with its decoded reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevertRecord {
+    /// Address of the reverted frame; for creations this is the address
+    /// the contract would have been deployed at, or the creator if unknown
+    pub address: Address,
+    /// Call depth of the reverted frame
+    pub depth: u64,
+    /// Decoded revert reason
+    pub reason: RevertReason,
+    /// Raw revert data
+    pub raw: Bytes,
+    /// Source location of the instruction that reverted, if the inspector
+    /// has a source map of the called code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_location: Option<SourceLocation>,
+    /// Index of the transaction the frame belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+}
+
+impl RevertRecord {
+    pub(crate) fn new(address: Address, depth: u64, raw: Bytes, tx_index: u64) -> Self {
+        Self {
+            address,
+            depth,
+            reason: RevertReason::decode(&raw),
+            raw,
+            source_location: None,
+            tx_index,
+            seq: 0,
+        }
+    }
+}
+
+/// Returns the decoded revert reason of a frame that ended with `result`,
+/// `None` unless it reverted. Redacted frames get a placeholder instead.
+pub(crate) fn revert_reason(result: &InterpreterResult, redacted: bool) -> Option<String> {
+    if result.result != InstructionResult::Revert {
+        return None;
+    }
+    Some(if redacted {
+        Redacted::new(&result.output).to_string()
+    } else {
+        RevertReason::decode(&result.output).to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_error_string() {
+        let data = Revert::from("insufficient balance, try again").abi_encode();
+        let reason = RevertReason::decode(&data);
+        assert_eq!(
+            reason,
+            RevertReason::Error {
+                message: "insufficient balance, try again".into()
+            }
+        );
+        assert_eq!(reason.to_string(), "insufficient balance, try again");
+    }
+
+    #[test]
+    fn test_decode_panic_codes() {
+        let data = Panic::from(U256::from(0x11)).abi_encode();
+        let reason = RevertReason::decode(&data);
+        assert_eq!(
+            reason.to_string(),
+            "panic: arithmetic underflow or overflow (0x11)"
+        );
+
+        let data = Panic::from(U256::from(0x99)).abi_encode();
+        assert_eq!(
+            RevertReason::decode(&data).to_string(),
+            "panic: unknown code (0x99)"
+        );
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        assert_eq!(RevertReason::decode(&[]), RevertReason::Empty);
+    }
+
+    #[test]
+    fn test_decode_custom_error() {
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x00, 0x01];
+        let reason = RevertReason::decode(&data);
+        assert_eq!(
+            reason,
+            RevertReason::Custom {
+                selector: Some([0xde, 0xad, 0xbe, 0xef]),
+                data: Bytes::copy_from_slice(&data),
+            }
+        );
+        assert_eq!(reason.to_string(), "custom error 0xdeadbeef0001");
+
+        // Too short to carry a selector
+        let reason = RevertReason::decode(&[0x01, 0x02]);
+        assert!(matches!(
+            reason,
+            RevertReason::Custom { selector: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_malformed_error_string_falls_back_to_custom() {
+        let mut data = Revert::SELECTOR.to_vec();
+        data.extend_from_slice(&[0xff; 8]);
+        assert!(matches!(
+            RevertReason::decode(&data),
+            RevertReason::Custom { .. }
+        ));
+    }
+}
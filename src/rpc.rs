@@ -0,0 +1,154 @@
+//! A minimal JSON-RPC 2.0 debug endpoint for a [`TraceSession`].
+//!
+//! [`serve`] answers POST requests on `/` while the session keeps tracing, so
+//! a running node or test harness can be inspected with `curl`:
+//!
+//! | Method                | Params                        | Result                      |
+//! |-----------------------|-------------------------------|-----------------------------|
+//! | `restd_getReport`     | none                          | the session's block report  |
+//! | `restd_getCallTrace`  | `[tx_index]` or `{tx_index}`  | the transaction's calls     |
+//! | `restd_resetSession`  | none                          | `true`                      |
+//!
+//! Every request gets an HTTP 200 response; failures, including a
+//! `tx_index` past the traced transactions, are JSON-RPC error objects.
+
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use crate::session::TraceSession;
+
+/// A session shared between the code tracing transactions and the endpoint.
+pub type SharedSession = Arc<Mutex<TraceSession>>;
+
+/// Invalid JSON was received.
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object.
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters, e.g. an out of range `tx_index`.
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// A JSON-RPC error object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Returns a router serving the session on `/`.
+pub fn router(session: SharedSession) -> Router {
+    Router::new().route("/", post(handle)).with_state(session)
+}
+
+/// Serves the session on an already bound listener until the task is
+/// dropped.
+pub async fn serve_listener(listener: TcpListener, session: SharedSession) -> io::Result<()> {
+    axum::serve(listener, router(session)).await
+}
+
+/// Binds `addr` and serves the session until the task is dropped.
+pub async fn serve(addr: SocketAddr, session: SharedSession) -> io::Result<()> {
+    serve_listener(TcpListener::bind(addr).await?, session).await
+}
+
+async fn handle(State(session): State<SharedSession>, body: Bytes) -> Response {
+    let response = match serde_json::from_slice::<Value>(&body) {
+        Ok(request) => respond(&session, &request),
+        Err(err) => error_response(Value::Null, RpcError::new(PARSE_ERROR, err.to_string())),
+    };
+    ([(header::CONTENT_TYPE, "application/json")], response.to_string()).into_response()
+}
+
+/// Answers a single parsed request.
+fn respond(session: &SharedSession, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = match (request.get("jsonrpc"), request.get("method")) {
+        (Some(Value::String(version)), Some(Value::String(method))) if version == "2.0" => method,
+        _ => {
+            let err = RpcError::new(INVALID_REQUEST, "expected a JSON-RPC 2.0 request object");
+            return error_response(id, err);
+        }
+    };
+    let params = request.get("params").unwrap_or(&Value::Null);
+    match call(session, method, params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error_response(id, err),
+    }
+}
+
+fn call(session: &SharedSession, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "restd_getReport" => Ok(to_value(&lock(session).block_report())),
+        "restd_getCallTrace" => {
+            let index = tx_index(params)?;
+            let session = lock(session);
+            let traced = session.transactions().len();
+            let tx = session.transactions().get(index).ok_or_else(|| {
+                RpcError::new(
+                    INVALID_PARAMS,
+                    format!("tx_index {index} out of range, {traced} transactions traced"),
+                )
+            })?;
+            Ok(to_value(&tx.report.calls))
+        }
+        "restd_resetSession" => {
+            lock(session).reset();
+            Ok(Value::Bool(true))
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("method {method} not found"))),
+    }
+}
+
+/// Reads `tx_index` from `[tx_index]` or `{"tx_index": tx_index}`.
+fn tx_index(params: &Value) -> Result<usize, RpcError> {
+    let value = match params {
+        Value::Array(values) => values.first(),
+        Value::Object(fields) => fields.get("tx_index"),
+        _ => None,
+    };
+    value
+        .and_then(Value::as_u64)
+        .map(|index| index as usize)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected a tx_index parameter"))
+}
+
+/// Locks the session, even if a tracing thread panicked while holding it.
+fn lock(session: &SharedSession) -> MutexGuard<'_, TraceSession> {
+    session.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("reports serialize to JSON")
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
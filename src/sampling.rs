@@ -0,0 +1,238 @@
+//! Sampled profiling.
+//!
+//! With [`ProfilingMode::Sampled`] the inspector skips its per-step recording
+//! and only snapshots the executing instruction every N steps into a
+//! [`SampleRing`] allocated once up front. [`SampleProfile`] then tells
+//! approximately where the EVM spent its steps.
+
+use std::collections::BTreeMap;
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// Samples kept by [`ProfilingMode::sampled`].
+pub const DEFAULT_SAMPLE_CAPACITY: usize = 4096;
+
+/// Entries in each ranking of a [`SampleProfile`].
+const TOP_ENTRIES: usize = 10;
+
+/// How the inspector records steps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfilingMode {
+    /// Record every step: opcode counts, the gas profile, storage accesses,
+    /// step events and step callbacks
+    #[default]
+    Full,
+    /// Only sample every `every_n_steps`-th step, keeping the most recent
+    /// `capacity` samples. Steps are still counted; frame-level records such
+    /// as calls and logs are kept, step-level ones are not
+    Sampled {
+        /// Steps between samples, at least 1
+        every_n_steps: u64,
+        /// Samples kept before the oldest are overwritten
+        #[serde(default = "default_sample_capacity")]
+        capacity: usize,
+    },
+    /// Only count steps. Calls, logs and the other frame-level records are
+    /// kept; nothing is recorded per step, so tracing costs little more than
+    /// running without an inspector
+    Counters,
+}
+
+impl ProfilingMode {
+    /// Samples every `every_n_steps`-th step, keeping
+    /// [`DEFAULT_SAMPLE_CAPACITY`] samples.
+    pub fn sampled(every_n_steps: u64) -> Self {
+        ProfilingMode::Sampled {
+            every_n_steps,
+            capacity: DEFAULT_SAMPLE_CAPACITY,
+        }
+    }
+}
+
+fn default_sample_capacity() -> usize {
+    DEFAULT_SAMPLE_CAPACITY
+}
+
+/// The instruction executing when a sample was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileSample {
+    /// Address whose code was executing
+    pub address: Address,
+    /// Program counter
+    pub pc: u32,
+    /// Opcode about to be executed
+    pub opcode: u8,
+    /// Call depth, the top-level frame is at depth 0
+    pub depth: u16,
+}
+
+/// Fixed-capacity buffer of samples that overwrites the oldest once full.
+///
+/// The storage is allocated when the ring is created, so pushing never
+/// allocates.
+#[derive(Debug, Clone, Default)]
+pub struct SampleRing {
+    samples: Vec<ProfileSample>,
+    /// Index of the oldest sample once the ring is full
+    head: usize,
+    capacity: usize,
+    /// Samples pushed, including overwritten ones
+    taken: u64,
+}
+
+impl SampleRing {
+    /// Creates a ring holding up to `capacity` samples.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            samples: Vec::with_capacity(capacity),
+            head: 0,
+            capacity,
+            taken: 0,
+        }
+    }
+
+    /// Adds `sample`, overwriting the oldest one if the ring is full.
+    pub fn push(&mut self, sample: ProfileSample) {
+        self.taken += 1;
+        if self.samples.len() < self.capacity {
+            self.samples.push(sample);
+        } else if self.capacity > 0 {
+            self.samples[self.head] = sample;
+            self.head = (self.head + 1) % self.capacity;
+        }
+    }
+
+    /// Returns the number of samples held.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns true if no sample is held.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the number of samples the ring holds at most.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of samples pushed, including overwritten ones.
+    pub fn taken(&self) -> u64 {
+        self.taken
+    }
+
+    /// Returns the samples held, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &ProfileSample> {
+        let (newer, older) = self.samples.split_at(self.head);
+        older.iter().chain(newer)
+    }
+
+    /// Removes all samples, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.head = 0;
+        self.taken = 0;
+    }
+}
+
+/// Sample count of a program counter within a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcSamples {
+    /// Contract whose code was executing
+    pub address: Address,
+    /// Program counter
+    pub pc: u32,
+    /// Samples taken at the program counter
+    pub samples: u64,
+}
+
+/// Approximate distribution of steps, built from the samples of a
+/// [`SampleRing`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleProfile {
+    /// Steps between samples
+    pub every_n_steps: u64,
+    /// Samples taken, including those overwritten in the ring
+    pub samples_taken: u64,
+    /// Samples the rankings are built from, the most recent ones
+    pub samples_kept: usize,
+    /// Contracts with the most samples, most first
+    pub top_contracts: Vec<(Address, u64)>,
+    /// Program counters with the most samples, most first
+    pub top_pcs: Vec<PcSamples>,
+}
+
+impl SampleProfile {
+    /// Ranks the samples of `ring`, taken every `every_n_steps` steps.
+    pub fn from_ring(ring: &SampleRing, every_n_steps: u64) -> Self {
+        let mut contracts: BTreeMap<Address, u64> = BTreeMap::new();
+        let mut pcs: BTreeMap<(Address, u32), u64> = BTreeMap::new();
+        for sample in ring.iter() {
+            *contracts.entry(sample.address).or_default() += 1;
+            *pcs.entry((sample.address, sample.pc)).or_default() += 1;
+        }
+        let mut top_contracts: Vec<_> = contracts.into_iter().collect();
+        top_contracts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        top_contracts.truncate(TOP_ENTRIES);
+        let mut top_pcs: Vec<_> = pcs
+            .into_iter()
+            .map(|((address, pc), samples)| PcSamples {
+                address,
+                pc,
+                samples,
+            })
+            .collect();
+        top_pcs.sort_by(|a, b| {
+            b.samples
+                .cmp(&a.samples)
+                .then(a.address.cmp(&b.address))
+                .then(a.pc.cmp(&b.pc))
+        });
+        top_pcs.truncate(TOP_ENTRIES);
+        Self {
+            every_n_steps,
+            samples_taken: ring.taken(),
+            samples_kept: ring.len(),
+            top_contracts,
+            top_pcs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(pc: u32) -> ProfileSample {
+        ProfileSample {
+            address: Address::ZERO,
+            pc,
+            opcode: 0,
+            depth: 0,
+        }
+    }
+
+    #[test]
+    fn test_ring_overwrites_oldest() {
+        let mut ring = SampleRing::with_capacity(3);
+        for pc in 0..5 {
+            ring.push(sample(pc));
+        }
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.taken(), 5);
+        let pcs: Vec<_> = ring.iter().map(|sample| sample.pc).collect();
+        assert_eq!(pcs, [2, 3, 4]);
+
+        ring.clear();
+        assert!(ring.is_empty());
+        assert_eq!(ring.capacity(), 3);
+
+        let mut empty = SampleRing::with_capacity(0);
+        empty.push(sample(0));
+        assert!(empty.is_empty());
+        assert_eq!(empty.taken(), 1);
+    }
+}
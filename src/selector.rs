@@ -0,0 +1,80 @@
+//! Function selector capture and resolution.
+
+use std::{collections::HashMap, fmt};
+
+use alloy_primitives::hex;
+
+/// Resolves 4-byte function selectors to human-readable signatures such as
+/// `transfer(address,uint256)`.
+pub trait SelectorResolver: fmt::Debug + Send + Sync {
+    /// Returns the signature for `selector`, if known.
+    fn resolve(&self, selector: [u8; 4]) -> Option<String>;
+}
+
+/// A [`SelectorResolver`] backed by a fixed map of known selectors.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaticSelectorResolver {
+    selectors: HashMap<[u8; 4], String>,
+}
+
+impl StaticSelectorResolver {
+    /// Creates a resolver from a map of selectors to signatures.
+    pub fn new(selectors: HashMap<[u8; 4], String>) -> Self {
+        Self { selectors }
+    }
+
+    /// Adds or replaces the signature of `selector`.
+    pub fn insert(&mut self, selector: [u8; 4], signature: impl Into<String>) {
+        self.selectors.insert(selector, signature.into());
+    }
+}
+
+impl From<HashMap<[u8; 4], String>> for StaticSelectorResolver {
+    fn from(selectors: HashMap<[u8; 4], String>) -> Self {
+        Self::new(selectors)
+    }
+}
+
+impl SelectorResolver for StaticSelectorResolver {
+    fn resolve(&self, selector: [u8; 4]) -> Option<String> {
+        self.selectors.get(&selector).cloned()
+    }
+}
+
+/// Returns the function selector of `input`, or `None` if the input is
+/// shorter than 4 bytes (e.g. a plain ETH transfer).
+pub fn selector_of(input: &[u8]) -> Option<[u8; 4]> {
+    input.get(..4).map(|s| s.try_into().expect("slice of length 4"))
+}
+
+/// Formats a selector as `0x`-prefixed hex.
+pub(crate) fn selector_hex(selector: [u8; 4]) -> String {
+    hex::encode_prefixed(selector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_of_short_input() {
+        assert_eq!(selector_of(&[]), None);
+        assert_eq!(selector_of(&[0xa9, 0x05, 0x9c]), None);
+        assert_eq!(
+            selector_of(&[0xa9, 0x05, 0x9c, 0xbb, 0x00]),
+            Some([0xa9, 0x05, 0x9c, 0xbb])
+        );
+    }
+
+    #[test]
+    fn test_static_resolver() {
+        let mut resolver = StaticSelectorResolver::default();
+        resolver.insert([0xa9, 0x05, 0x9c, 0xbb], "transfer(address,uint256)");
+        assert_eq!(
+            resolver.resolve([0xa9, 0x05, 0x9c, 0xbb]).as_deref(),
+            Some("transfer(address,uint256)")
+        );
+        assert_eq!(resolver.resolve([0x09, 0x5e, 0xa7, 0xb3]), None);
+        assert_eq!(selector_hex([0xa9, 0x05, 0x9c, 0xbb]), "0xa9059cbb");
+    }
+}
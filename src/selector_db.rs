@@ -0,0 +1,213 @@
+//! A selector database kept in a local file.
+//!
+//! [`SelectorDb`] holds selector to signature mappings sorted by selector,
+//! so lookups are a binary search, and saves them as a JSON object from
+//! `0x`-prefixed selectors to signatures. It is seeded from the CSV exports
+//! of openchain.xyz or 4byte.directory with [`SelectorDb::import_csv`].
+//!
+//! Needs the `std` feature, since wasm32 targets have no file system.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::hex;
+
+use crate::selector::{selector_hex, SelectorResolver};
+
+/// Selector to signature mappings backed by a file.
+///
+/// When a selector has several signatures, the first one added is kept.
+///
+/// ```
+/// use restd::SelectorDb;
+///
+/// let mut db = SelectorDb::default();
+/// let csv = "selector,signature\n0xa9059cbb,transfer(address,uint256)\n";
+/// assert_eq!(db.import_csv(csv.as_bytes()).unwrap(), 1);
+/// assert_eq!(
+///     db.lookup([0xa9, 0x05, 0x9c, 0xbb]),
+///     Some("transfer(address,uint256)")
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorDb {
+    /// Mappings sorted by selector, one per selector
+    entries: Vec<([u8; 4], String)>,
+    /// File the database was opened from and is saved to
+    path: Option<PathBuf>,
+}
+
+impl SelectorDb {
+    /// Opens the database saved at `path`, or an empty one that
+    /// [`save`](Self::save) creates there if the file does not exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut db = Self {
+            entries: Vec::new(),
+            path: Some(path.to_path_buf()),
+        };
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(db),
+            Err(err) => return Err(err),
+        };
+        let saved: BTreeMap<String, String> = serde_json::from_str(&json)
+            .map_err(|err| invalid_data(format!("{}: {err}", path.display())))?;
+        let entries = saved
+            .into_iter()
+            .map(|(selector, signature)| {
+                parse_selector(&selector)
+                    .map(|selector| (selector, signature))
+                    .ok_or_else(|| {
+                        invalid_data(format!(
+                            "{}: `{selector}` is not a selector",
+                            path.display()
+                        ))
+                    })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        db.extend(entries);
+        Ok(db)
+    }
+
+    /// Writes the database to the file it was opened from.
+    pub fn save(&self) -> io::Result<()> {
+        let path = self.path.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "selector database was not opened from a file",
+            )
+        })?;
+        self.save_as(path)
+    }
+
+    /// Writes the database to `path`.
+    pub fn save_as(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let saved: BTreeMap<_, _> = self
+            .entries
+            .iter()
+            .map(|(selector, signature)| (selector_hex(*selector), signature))
+            .collect();
+        let json = serde_json::to_string_pretty(&saved).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// Returns the file the database was opened from.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Imports `selector,signature` lines, the format of the openchain.xyz
+    /// and 4byte.directory exports, and returns the number of selectors
+    /// added. A leading header line is skipped, selectors may omit the `0x`
+    /// prefix and signatures may be quoted. Selectors already known keep
+    /// their signature.
+    pub fn import_csv(&mut self, reader: impl BufRead) -> io::Result<usize> {
+        let mut imported = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parsed = line.split_once(',').and_then(|(selector, signature)| {
+                let signature = signature.trim().trim_matches('"');
+                Some((parse_selector(selector)?, signature)).filter(|_| !signature.is_empty())
+            });
+            match parsed {
+                Some((selector, signature)) => imported.push((selector, signature.to_string())),
+                None if index == 0 => continue,
+                None => {
+                    return Err(invalid_data(format!(
+                        "line {}: expected `selector,signature`, found `{line}`",
+                        index + 1
+                    )))
+                }
+            }
+        }
+        let before = self.len();
+        self.extend(imported);
+        Ok(self.len() - before)
+    }
+
+    /// Adds the signature of `selector` unless it already has one, and
+    /// returns true if it was added.
+    pub fn insert(&mut self, selector: [u8; 4], signature: impl Into<String>) -> bool {
+        match self.position(selector) {
+            Ok(_) => false,
+            Err(index) => {
+                self.entries.insert(index, (selector, signature.into()));
+                true
+            }
+        }
+    }
+
+    /// Adds the mappings of `other` whose selectors are not known yet.
+    pub fn merge(&mut self, other: SelectorDb) {
+        self.extend(other.entries);
+    }
+
+    /// Returns the signature of `selector`, if known.
+    pub fn lookup(&self, selector: [u8; 4]) -> Option<&str> {
+        let index = self.position(selector).ok()?;
+        Some(&self.entries[index].1)
+    }
+
+    /// Returns the number of known selectors.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no selector is known.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, selector: [u8; 4]) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by_key(&selector, |(known, _)| *known)
+    }
+
+    /// Adds `entries` in bulk, keeping the first signature of every
+    /// selector, existing ones first.
+    fn extend(&mut self, entries: impl IntoIterator<Item = ([u8; 4], String)>) {
+        self.entries.extend(entries);
+        // The sort is stable and `dedup_by_key` keeps the first of each run
+        self.entries.sort_by_key(|(selector, _)| *selector);
+        self.entries.dedup_by_key(|(selector, _)| *selector);
+    }
+}
+
+impl SelectorResolver for SelectorDb {
+    fn resolve(&self, selector: [u8; 4]) -> Option<String> {
+        self.lookup(selector).map(str::to_string)
+    }
+}
+
+/// Parses a selector written as 8 hex digits, with or without `0x`.
+fn parse_selector(selector: &str) -> Option<[u8; 4]> {
+    let selector = selector.trim().trim_matches('"');
+    let digits = selector.strip_prefix("0x").unwrap_or(selector);
+    hex::decode_to_array(digits).ok()
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(parse_selector("0xa9059cbb"), Some([0xa9, 0x05, 0x9c, 0xbb]));
+        assert_eq!(parse_selector(" A9059CBB "), Some([0xa9, 0x05, 0x9c, 0xbb]));
+        assert_eq!(parse_selector("0xa9059c"), None);
+        assert_eq!(parse_selector("selector"), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! Records of contracts destroyed with `SELFDESTRUCT`.
+
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+/// A single executed `SELFDESTRUCT`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfdestructRecord {
+    /// Contract that executed `SELFDESTRUCT`
+    pub contract: Address,
+    /// Address that received the contract balance
+    pub beneficiary: Address,
+    /// Balance transferred to the beneficiary
+    pub value: U256,
+    /// Call depth of the destroyed contract's frame
+    pub depth: u64,
+    /// Index into [`gas_by_call`](crate::HelloWorldInspector::gas_by_call) of
+    /// the innermost traced call enclosing the `SELFDESTRUCT`, if any
+    pub call_index: Option<usize>,
+    /// Index of the transaction the `SELFDESTRUCT` belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+}
+
+impl SelfdestructRecord {
+    /// Returns true if the contract named itself as beneficiary.
+    ///
+    /// revm only reports such a `SELFDESTRUCT` when it removes the balance,
+    /// i.e. before Cancun or for contracts created in the same transaction,
+    /// so the value is burned.
+    pub fn burns_value(&self) -> bool {
+        self.contract == self.beneficiary
+    }
+}
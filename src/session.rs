@@ -0,0 +1,185 @@
+//! Tracing a block one transaction at a time.
+//!
+//! [`TraceSession`] owns a [`HelloWorldInspector`] and resets it between
+//! transactions, so each transaction gets its own [`TxReport`] keyed by its
+//! hash, and [`TraceSession::finish`] rolls them up into a [`BlockReport`].
+
+use std::collections::BTreeSet;
+
+use alloy_primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    executed, ExecutedContract, HelloWorldInspector, HelloWorldInspectorConfig, InspectorReport,
+};
+
+/// What the inspector observed during one transaction of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxReport {
+    /// Hash the transaction was begun with
+    pub tx_hash: B256,
+    /// Position of the transaction in the session, counting from 0
+    pub index: usize,
+    /// Gas spent by the top-level frame, excluding intrinsic gas and refunds
+    pub gas_used: u64,
+    /// Addresses that received a call
+    pub addresses: BTreeSet<Address>,
+    /// Everything the inspector collected during the transaction
+    pub report: InspectorReport,
+}
+
+/// Totals across every transaction of a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockReport {
+    /// Report of each transaction, in the order they were traced
+    pub transactions: Vec<TxReport>,
+    /// Interpreter steps executed by all transactions
+    pub total_steps: u64,
+    /// Calls made by all transactions
+    pub total_calls: u64,
+    /// Gas spent by the top-level frames of all transactions
+    pub total_gas_used: u64,
+    /// Reverted frames across all transactions
+    pub total_reverts: u64,
+    /// Number of distinct addresses that received a call in any transaction
+    pub unique_addresses: usize,
+    /// Every distinct code run by any transaction, with the position of the
+    /// transaction that first ran it and the steps executed in it by all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executed_contracts: Vec<ExecutedContract>,
+}
+
+impl BlockReport {
+    /// Returns the report of the transaction with hash `tx_hash`.
+    pub fn transaction(&self, tx_hash: B256) -> Option<&TxReport> {
+        self.transactions.iter().find(|tx| tx.tx_hash == tx_hash)
+    }
+}
+
+/// Traces several transactions with one inspector, one report per transaction.
+///
+/// Call [`begin_tx`](Self::begin_tx), execute the transaction with
+/// [`inspector_mut`](Self::inspector_mut) attached to the EVM, then call
+/// [`end_tx`](Self::end_tx); [`finish`](Self::finish) returns the block report.
+#[derive(Debug, Default)]
+pub struct TraceSession {
+    /// Inspector attached to each transaction, reset between them
+    inspector: HelloWorldInspector,
+    /// Hash of the transaction between `begin_tx` and `end_tx`
+    current: Option<B256>,
+    /// Reports of the ended transactions
+    transactions: Vec<TxReport>,
+}
+
+impl TraceSession {
+    /// Creates a session around `inspector`. Its configuration, selector
+    /// resolver, sink and hooks are kept across transactions.
+    pub fn new(inspector: HelloWorldInspector) -> Self {
+        Self {
+            inspector,
+            current: None,
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Creates a session with an inspector built from `config`.
+    pub fn with_config(config: HelloWorldInspectorConfig) -> Self {
+        Self::new(HelloWorldInspector::with_config(config))
+    }
+
+    /// Starts tracing the transaction `tx_hash` with a freshly reset inspector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous transaction was not ended with [`end_tx`](Self::end_tx).
+    pub fn begin_tx(&mut self, tx_hash: B256) {
+        if let Some(open) = self.current {
+            panic!("transaction {open} was begun but not ended");
+        }
+        self.inspector.reset();
+        self.current = Some(tx_hash);
+    }
+
+    /// Returns the inspector, to inspect what the current transaction did so far.
+    pub fn inspector(&self) -> &HelloWorldInspector {
+        &self.inspector
+    }
+
+    /// Returns the inspector to attach to the EVM executing the current transaction.
+    pub fn inspector_mut(&mut self) -> &mut HelloWorldInspector {
+        &mut self.inspector
+    }
+
+    /// Returns the hash of the transaction being traced, if any.
+    pub fn current_tx(&self) -> Option<B256> {
+        self.current
+    }
+
+    /// Returns the reports of the transactions ended so far.
+    pub fn transactions(&self) -> &[TxReport] {
+        &self.transactions
+    }
+
+    /// Ends the current transaction, returning its report and resetting the
+    /// inspector for the next one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no transaction was begun with [`begin_tx`](Self::begin_tx).
+    pub fn end_tx(&mut self) -> TxReport {
+        let tx_hash = self.current.take().expect("end_tx called without begin_tx");
+        let tx = TxReport {
+            tx_hash,
+            index: self.transactions.len(),
+            gas_used: self.inspector.gas_used(),
+            addresses: self
+                .inspector
+                .busiest_addresses(usize::MAX)
+                .into_iter()
+                .map(|(address, _)| address)
+                .collect(),
+            report: self.inspector.reset_and_take_report(),
+        };
+        self.transactions.push(tx.clone());
+        tx
+    }
+
+    /// Aggregates the reports of the transactions ended so far.
+    pub fn block_report(&self) -> BlockReport {
+        let mut block = BlockReport::default();
+        let mut addresses = BTreeSet::<Address>::new();
+        for tx in &self.transactions {
+            block.total_steps += tx.report.step_count;
+            block.total_calls += tx.report.call_count;
+            block.total_gas_used += tx.gas_used;
+            block.total_reverts += tx.report.reverts.len() as u64;
+            addresses.extend(&tx.addresses);
+            // Each transaction's report counts its own transaction as 0
+            let mut executed_contracts = tx.report.executed_contracts.clone();
+            for contract in &mut executed_contracts {
+                contract.first_tx_index = tx.index as u64;
+            }
+            executed::merge(&mut block.executed_contracts, executed_contracts);
+        }
+        block.unique_addresses = addresses.len();
+        block.transactions = self.transactions.clone();
+        block
+    }
+
+    /// Drops the reports of the ended transactions and any transaction in
+    /// progress, and resets the inspector.
+    pub fn reset(&mut self) {
+        self.inspector.reset();
+        self.current = None;
+        self.transactions.clear();
+    }
+
+    /// Ends the session and aggregates the reports of its transactions. A
+    /// transaction that was begun but not ended is ended first.
+    pub fn finish(mut self) -> BlockReport {
+        if self.current.is_some() {
+            self.end_tx();
+        }
+        self.block_report()
+    }
+}
@@ -0,0 +1,186 @@
+//! Sharing an inspector with other threads while it executes.
+//!
+//! [`SharedInspector`] runs a [`HelloWorldInspector`] behind a mutex that only
+//! the executing thread takes per hook, and mirrors its counters into atomics
+//! after every hook. [`SharedInspector::snapshot`] reads the atomics alone, so
+//! a tokio task can poll progress of a transaction running on a blocking
+//! thread without ever waiting on it.
+
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard,
+};
+
+use alloy_primitives::{Address, Log, U256};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Interpreter,
+    },
+    Database, EvmContext, Inspector,
+};
+
+use crate::{HelloWorldInspector, InspectorReport};
+
+/// Counters of a [`SharedInspector`] as of its last completed hook.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InspectorSnapshot {
+    /// Number of interpreter steps executed
+    pub steps: u64,
+    /// Number of calls made
+    pub calls: u64,
+    /// Number of call and create frames open
+    pub depth: usize,
+}
+
+/// Counters mirrored from the inner inspector.
+#[derive(Debug, Default)]
+struct Counters {
+    steps: AtomicU64,
+    calls: AtomicU64,
+    depth: AtomicUsize,
+}
+
+/// A cloneable handle to a [`HelloWorldInspector`] whose counters can be read
+/// from any thread while a transaction executes.
+///
+/// Attach one clone to the EVM and keep another to call
+/// [`snapshot`](Self::snapshot), or [`lock`](Self::lock) for everything else
+/// once execution is done.
+#[derive(Debug, Clone, Default)]
+pub struct SharedInspector {
+    inner: Arc<Mutex<HelloWorldInspector>>,
+    counters: Arc<Counters>,
+}
+
+impl SharedInspector {
+    /// Wraps `inspector` for sharing.
+    pub fn new(inspector: HelloWorldInspector) -> Self {
+        let shared = Self {
+            inner: Arc::new(Mutex::new(inspector)),
+            counters: Arc::default(),
+        };
+        shared.sync(&shared.lock());
+        shared
+    }
+
+    /// Returns the step and call counts without taking the lock.
+    pub fn snapshot(&self) -> InspectorSnapshot {
+        InspectorSnapshot {
+            steps: self.counters.steps.load(Ordering::Relaxed),
+            calls: self.counters.calls.load(Ordering::Relaxed),
+            depth: self.counters.depth.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Locks the inner inspector. Execution on another thread waits until
+    /// the guard is dropped, so hold it briefly.
+    pub fn lock(&self) -> MutexGuard<'_, HelloWorldInspector> {
+        // A panic in another holder does not leave the inspector inconsistent
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the report of everything collected so far.
+    pub fn report(&self) -> InspectorReport {
+        self.lock().report()
+    }
+
+    /// Returns the report and resets the inspector for the next transaction.
+    pub fn reset_and_take_report(&self) -> InspectorReport {
+        let mut inner = self.lock();
+        let report = inner.reset_and_take_report();
+        self.sync(&inner);
+        report
+    }
+
+    /// Runs `f` on the locked inspector, then publishes its counters.
+    fn with<R>(&self, f: impl FnOnce(&mut HelloWorldInspector) -> R) -> R {
+        let mut inner = self.lock();
+        let result = f(&mut inner);
+        self.sync(&inner);
+        result
+    }
+
+    fn sync(&self, inner: &HelloWorldInspector) {
+        let counters = &self.counters;
+        counters.steps.store(inner.steps(), Ordering::Relaxed);
+        counters.calls.store(inner.calls(), Ordering::Relaxed);
+        counters
+            .depth
+            .store(inner.current_depth(), Ordering::Relaxed);
+    }
+}
+
+impl<DB: Database> Inspector<DB> for SharedInspector {
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.with(|inner| inner.initialize_interp(interp, context))
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.with(|inner| inner.step(interp, context))
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.with(|inner| inner.step_end(interp, context))
+    }
+
+    fn log(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>, log: &Log) {
+        self.with(|inner| inner.log(interp, context, log))
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.with(|inner| inner.call(context, inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.with(|inner| inner.call_end(context, inputs, outcome))
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.with(|inner| inner.create(context, inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.with(|inner| inner.create_end(context, inputs, outcome))
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.with(|inner| inner.eofcreate(context, inputs))
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.with(|inner| inner.eofcreate_end(context, inputs, outcome))
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        self.with(|inner| Inspector::<DB>::selfdestruct(inner, contract, target, value))
+    }
+}
@@ -0,0 +1,163 @@
+//! Streaming trace events out of the inspector as they occur.
+//!
+//! A [`TraceSink`] attached with [`HelloWorldInspector::with_sink`] receives a
+//! [`TraceEvent`] from every hook, so events can be consumed live, for example
+//! on another thread.
+//!
+//! [`HelloWorldInspector::with_sink`]: crate::HelloWorldInspector::with_sink
+
+use std::{fmt, sync::mpsc};
+
+use alloy_primitives::{Address, Selector, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{CollectedLog, OpcodeAlert, SelfdestructRecord};
+
+#[cfg(feature = "std")]
+pub use crate::file_sink::FileTraceSink;
+
+/// An event emitted by [`HelloWorldInspector`](crate::HelloWorldInspector).
+///
+/// Depths count from 1 for the top-level frame, as in the journal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    /// An instruction is about to be executed
+    Step {
+        /// Number of steps recorded so far, including this one
+        step: u64,
+        /// Program counter
+        pc: usize,
+        /// Opcode about to be executed
+        opcode: u8,
+        /// Address whose storage the frame uses
+        address: Address,
+        /// Depth of the executing frame
+        depth: u64,
+    },
+    /// A call frame started
+    CallStart {
+        /// Called address
+        address: Address,
+        /// Calling address
+        caller: Address,
+        /// Value sent with the call
+        value: U256,
+        /// Gas limit of the call
+        gas_limit: u64,
+        /// Depth of the new frame
+        depth: u64,
+        /// Sequence number of the call record
+        #[serde(default)]
+        seq: u64,
+        /// First four bytes of the calldata, absent for shorter or redacted
+        /// calldata
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector: Option<Selector>,
+        /// Name of the called address in the inspector's
+        /// [`Labels`](crate::Labels), if it has one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    /// A call frame ended
+    CallEnd {
+        /// Called address
+        address: Address,
+        /// Whether the call succeeded
+        success: bool,
+        /// Gas spent by the call
+        gas_used: u64,
+        /// Depth of the ended frame
+        depth: u64,
+        /// Decoded revert reason, if the call reverted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        revert_reason: Option<String>,
+    },
+    /// A create frame started
+    CreateStart {
+        /// Creating address
+        caller: Address,
+        /// Endowment of the new contract
+        value: U256,
+        /// Length of the init code
+        init_code_len: usize,
+        /// Gas limit of the creation
+        #[serde(default)]
+        gas_limit: u64,
+        /// Depth of the new frame
+        depth: u64,
+        /// Sequence number of the creation record
+        #[serde(default)]
+        seq: u64,
+    },
+    /// A create frame ended
+    CreateEnd {
+        /// Address of the created contract, if creation got that far
+        address: Option<Address>,
+        /// Whether the creation succeeded
+        success: bool,
+        /// Gas spent by the creation
+        #[serde(default)]
+        gas_used: u64,
+        /// Depth of the ended frame
+        depth: u64,
+        /// Decoded revert reason, if the creation reverted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        revert_reason: Option<String>,
+    },
+    /// A log was emitted
+    Log(CollectedLog),
+    /// A contract self-destructed
+    Selfdestruct(SelfdestructRecord),
+    /// An opcode in `alert_on_opcodes` is about to be executed
+    OpcodeAlert(OpcodeAlert),
+}
+
+/// Destination for [`TraceEvent`]s.
+pub trait TraceSink: fmt::Debug + Send {
+    /// Delivers `event`, returning false if the sink can no longer accept
+    /// events. The inspector then stops sending to it.
+    fn send(&mut self, event: TraceEvent) -> bool;
+
+    /// Delivers a [`TraceEvent::OpcodeAlert`] that must not wait in a
+    /// buffer. Sinks that buffer events deliver it, and everything before
+    /// it, right away; by default it is sent like any other event.
+    fn send_alert(&mut self, event: TraceEvent) -> bool {
+        self.send(event)
+    }
+}
+
+impl TraceSink for mpsc::Sender<TraceEvent> {
+    fn send(&mut self, event: TraceEvent) -> bool {
+        mpsc::Sender::send(self, event).is_ok()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl TraceSink for tokio::sync::mpsc::UnboundedSender<TraceEvent> {
+    fn send(&mut self, event: TraceEvent) -> bool {
+        tokio::sync::mpsc::UnboundedSender::send(self, event).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sender_reports_dropped_receiver() {
+        let (mut tx, rx) = mpsc::channel();
+        let event = TraceEvent::CreateEnd {
+            address: None,
+            success: false,
+            gas_used: 0,
+            depth: 1,
+            revert_reason: None,
+        };
+        assert!(TraceSink::send(&mut tx, event.clone()));
+        assert_eq!(rx.recv().unwrap(), event);
+
+        drop(rx);
+        assert!(!TraceSink::send(&mut tx, event));
+    }
+}
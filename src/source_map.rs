@@ -0,0 +1,284 @@
+//! Mapping program counters to Solidity source locations.
+//!
+//! A [`SourceMapRegistry`] holds the compressed source map the compiler
+//! emitted for a contract's runtime code, together with the sources it
+//! refers to. The map has one entry per instruction, so a program counter is
+//! first turned into an instruction index with
+//! [`instruction_indices`](crate::disasm::instruction_indices), which skips
+//! push operands. Inspectors given a registry annotate struct logs, reverts
+//! and failures of registered contracts with a [`SourceLocation`].
+
+use std::{collections::HashMap, fmt};
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::disasm;
+
+/// A position in a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Name of the source file, as given when the map was registered
+    pub file: String,
+    /// Line of the first character, starting at 1
+    pub line: u32,
+    /// Column of the first character in bytes, starting at 1
+    pub column: u32,
+}
+
+impl fmt::Display for SourceLocation {
+    /// Formats the location as `Token.sol:87:9`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// How an instruction jumps, the `j` field of a source map entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Jump {
+    /// Not a jump, or a jump within a function (`-`)
+    #[default]
+    Regular,
+    /// A jump into a function (`i`)
+    Into,
+    /// A return from a function (`o`)
+    Out,
+}
+
+/// The source range of one instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// Byte offset of the range in its source file
+    pub offset: usize,
+    /// Length of the range in bytes
+    pub length: usize,
+    /// Index of the source file in the source list, `None` for
+    /// compiler-generated code (`-1`)
+    pub file: Option<usize>,
+    /// How the instruction jumps
+    pub jump: Jump,
+    /// Depth of the modifier the instruction belongs to
+    pub modifier_depth: u32,
+}
+
+/// A source map that could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMapError {
+    /// Index of the offending entry
+    pub entry: usize,
+    /// What is wrong with it
+    pub reason: String,
+}
+
+impl fmt::Display for SourceMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid source map entry #{}: {}",
+            self.entry, self.reason
+        )
+    }
+}
+
+impl std::error::Error for SourceMapError {}
+
+/// Decodes a compressed source map such as `0:120:0:-;;25:6::i`.
+///
+/// Entries are separated by `;` and hold the fields `s:l:f:j:m`; fields
+/// that are empty or missing keep the value of the previous entry.
+pub fn decode(srcmap: &str) -> Result<Vec<SourceMapEntry>, SourceMapError> {
+    let mut entries = Vec::new();
+    if srcmap.is_empty() {
+        return Ok(entries);
+    }
+    let mut current = SourceMapEntry::default();
+    for (index, entry) in srcmap.split(';').enumerate() {
+        let error = |reason: &str| SourceMapError {
+            entry: index,
+            reason: reason.to_string(),
+        };
+        for (field, value) in entry.split(':').enumerate() {
+            if value.is_empty() {
+                continue;
+            }
+            let number = || value.parse::<usize>().map_err(|_| error("not a number"));
+            match field {
+                0 => current.offset = number()?,
+                1 => current.length = number()?,
+                2 if value == "-1" => current.file = None,
+                2 => current.file = Some(number()?),
+                3 => {
+                    current.jump = match value {
+                        "i" => Jump::Into,
+                        "o" => Jump::Out,
+                        "-" => Jump::Regular,
+                        _ => return Err(error("jump is not `i`, `o` or `-`")),
+                    }
+                }
+                4 => {
+                    current.modifier_depth = value
+                        .parse()
+                        .map_err(|_| error("modifier depth is not a number"))?
+                }
+                _ => return Err(error("more than five fields")),
+            }
+        }
+        entries.push(current);
+    }
+    Ok(entries)
+}
+
+/// A source file and where its lines start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Source {
+    name: String,
+    /// Byte offset of every line, the first at 0
+    line_starts: Vec<usize>,
+}
+
+impl Source {
+    fn new(name: String, contents: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { name, line_starts }
+    }
+
+    fn location(&self, offset: usize) -> SourceLocation {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        SourceLocation {
+            file: self.name.clone(),
+            line: line as u32,
+            column: (offset - self.line_starts[line - 1] + 1) as u32,
+        }
+    }
+}
+
+/// The decoded source map of one contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ContractSources {
+    entries: Vec<SourceMapEntry>,
+    sources: Vec<Source>,
+}
+
+/// Source maps of contracts by address.
+///
+/// ```
+/// use alloy_primitives::Address;
+/// use restd::SourceMapRegistry;
+///
+/// let token = Address::repeat_byte(0x70);
+/// let mut maps = SourceMapRegistry::new();
+/// maps.register(token, "0:9:0;10:7", [("Token.sol", "revert();\nstop();\n")])
+///     .unwrap();
+/// // PUSH1 0, STOP: the second instruction is at pc 2
+/// let location = maps.locate(token, &[0x60, 0x00, 0x00], 2).unwrap();
+/// assert_eq!(location.to_string(), "Token.sol:2:1");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMapRegistry {
+    contracts: HashMap<Address, ContractSources>,
+}
+
+impl SourceMapRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the source map of the runtime code at `address`, with the
+    /// names and contents of the sources in the order of the compiler's
+    /// source list. Registering an address again replaces its map.
+    pub fn register<N, C>(
+        &mut self,
+        address: Address,
+        srcmap: &str,
+        sources: impl IntoIterator<Item = (N, C)>,
+    ) -> Result<(), SourceMapError>
+    where
+        N: Into<String>,
+        C: AsRef<str>,
+    {
+        let entries = decode(srcmap)?;
+        let sources = sources
+            .into_iter()
+            .map(|(name, contents)| Source::new(name.into(), contents.as_ref()))
+            .collect();
+        self.contracts
+            .insert(address, ContractSources { entries, sources });
+        Ok(())
+    }
+
+    /// Returns true if a source map is registered for `address`.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.contracts.contains_key(address)
+    }
+
+    /// Returns the source location of the `instruction`th instruction of
+    /// the code at `address`, `None` if it has no map or the instruction is
+    /// compiler-generated.
+    pub fn location(&self, address: &Address, instruction: usize) -> Option<SourceLocation> {
+        let contract = self.contracts.get(address)?;
+        let entry = contract.entries.get(instruction)?;
+        let source = contract.sources.get(entry.file?)?;
+        Some(source.location(entry.offset))
+    }
+
+    /// Returns the source location of the instruction at `pc` in `code`,
+    /// the code deployed at `address`.
+    ///
+    /// This disassembles `code` on every call; inspectors keep the
+    /// instruction indices of each contract instead.
+    pub fn locate(&self, address: Address, code: &[u8], pc: usize) -> Option<SourceLocation> {
+        let instruction = disasm::instruction_indices(code)
+            .get(pc)
+            .copied()
+            .flatten()?;
+        self.location(&address, instruction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_fields_inherit() {
+        let entries = decode("1:2:0:i;;3::-1;:4::o:2").unwrap();
+        let expected = |offset, length, file, jump, modifier_depth| SourceMapEntry {
+            offset,
+            length,
+            file,
+            jump,
+            modifier_depth,
+        };
+        assert_eq!(
+            entries,
+            vec![
+                expected(1, 2, Some(0), Jump::Into, 0),
+                expected(1, 2, Some(0), Jump::Into, 0),
+                expected(3, 2, None, Jump::Into, 0),
+                expected(3, 4, None, Jump::Out, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_entries() {
+        let err = decode("1:2:0;x").unwrap_err();
+        assert_eq!(err.entry, 1);
+        assert_eq!(err.to_string(), "invalid source map entry #1: not a number");
+        assert!(decode("1:2:0:q").is_err());
+        assert!(decode("1:2:0:i:0:9").is_err());
+    }
+
+    #[test]
+    fn test_offsets_to_lines_and_columns() {
+        let source = Source::new("A.sol".into(), "ab\ncd\n\nef");
+        assert_eq!(source.location(0).to_string(), "A.sol:1:1");
+        assert_eq!(source.location(2).to_string(), "A.sol:1:3");
+        assert_eq!(source.location(4).to_string(), "A.sol:2:2");
+        assert_eq!(source.location(6).to_string(), "A.sol:3:1");
+        assert_eq!(source.location(8).to_string(), "A.sol:4:2");
+    }
+}
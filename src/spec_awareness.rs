@@ -0,0 +1,156 @@
+//! Warnings about opcodes the traced hardfork does not support.
+//!
+//! Code compiled for a newer hardfork than the one it runs under halts the
+//! first time it executes an opcode the older hardfork lacks, e.g. `TLOAD`
+//! before Cancun, and revm only reports an invalid opcode. [`SpecAwareness`]
+//! checks every executed opcode against the hardfork given when the inspector
+//! is built, warns about those it does not support and ties an invalid opcode
+//! halt of the transaction to the first of them.
+
+use std::fmt;
+
+use alloy_primitives::Address;
+use revm::{
+    interpreter::{InstructionResult, Interpreter},
+    primitives::SpecId,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::opcodes;
+
+/// An executed opcode introduced after the traced hardfork.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecWarning {
+    /// The opcode
+    pub opcode: u8,
+    /// Hardfork that introduced the opcode
+    #[serde(with = "spec_name")]
+    pub introduced_in: SpecId,
+    /// Contract executing the opcode
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// Call depth of the executing frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// A transaction that halted on an invalid opcode after executing an opcode
+/// the traced hardfork does not support.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecHalt {
+    /// Why the transaction halted, e.g. `NotActivated`
+    pub reason: String,
+    /// The hardfork the transaction was traced under
+    #[serde(with = "spec_name")]
+    pub spec: SpecId,
+    /// First unsupported opcode the transaction executed
+    pub first_offender: SpecWarning,
+}
+
+impl fmt::Display for SpecHalt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offender = &self.first_offender;
+        write!(
+            f,
+            "{}: {} at pc {} of {} was introduced in {}, after {}",
+            self.reason,
+            opcodes::display_name(offender.opcode),
+            offender.pc,
+            offender.address,
+            <&str>::from(offender.introduced_in),
+            <&str>::from(self.spec),
+        )
+    }
+}
+
+/// Checks executed opcodes against a hardfork.
+#[derive(Debug, Clone)]
+pub struct SpecAwareness {
+    spec: SpecId,
+    warnings: Vec<SpecWarning>,
+    halts: Vec<SpecHalt>,
+}
+
+impl SpecAwareness {
+    /// Checks opcodes against `spec`.
+    pub fn new(spec: SpecId) -> Self {
+        Self {
+            spec,
+            warnings: Vec::new(),
+            halts: Vec::new(),
+        }
+    }
+
+    /// Returns the hardfork opcodes are checked against.
+    pub fn spec(&self) -> SpecId {
+        self.spec
+    }
+
+    /// Returns every executed opcode the hardfork does not support, in
+    /// execution order.
+    pub fn warnings(&self) -> &[SpecWarning] {
+        &self.warnings
+    }
+
+    /// Returns the transactions that halted on an invalid opcode after an
+    /// unsupported one.
+    pub fn halts(&self) -> &[SpecHalt] {
+        &self.halts
+    }
+
+    /// Warns if the opcode about to execute is not supported.
+    pub(crate) fn check(&mut self, interp: &Interpreter, depth: u64, tx_index: u64) {
+        let opcode = interp.current_opcode();
+        let Some(introduced_in) = opcodes::introduced_in(opcode) else {
+            return;
+        };
+        if !self.spec.is_enabled_in(introduced_in) {
+            self.warnings.push(SpecWarning {
+                opcode,
+                introduced_in,
+                address: interp.contract.target_address,
+                pc: interp.program_counter(),
+                depth,
+                tx_index,
+            });
+        }
+    }
+
+    /// Annotates an invalid opcode halt of the transaction with its first
+    /// unsupported opcode.
+    pub(crate) fn end_tx(&mut self, result: InstructionResult, tx_index: u64) {
+        use InstructionResult::*;
+        if !matches!(result, NotActivated | OpcodeNotFound | InvalidFEOpcode) {
+            return;
+        }
+        if let Some(first) = self.warnings.iter().find(|warning| warning.tx_index == tx_index) {
+            self.halts.push(SpecHalt {
+                reason: format!("{result:?}"),
+                spec: self.spec,
+                first_offender: first.clone(),
+            });
+        }
+    }
+}
+
+/// Serde representation of a hardfork by its revm name, e.g. `Cancun`.
+mod spec_name {
+    use revm::primitives::SpecId;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        spec: &SpecId,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str((*spec).into())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SpecId, D::Error> {
+        Ok(SpecId::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
@@ -0,0 +1,233 @@
+//! Running several inspectors in one transaction.
+//!
+//! [`InspectorStack`] owns a list of inspectors and forwards every hook to
+//! each of them in the order they were added.
+
+use std::{any::Any, fmt};
+
+use alloy_primitives::{Address, Log, U256};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Interpreter,
+    },
+    Database, EvmContext, Inspector,
+};
+
+/// An inspector that can be stored in an [`InspectorStack`].
+///
+/// revm's [`Inspector`] is generic over the database, so the stack stores
+/// children as `dyn StackedInspector<DB>`; the `Any` supertrait lets them be
+/// downcast back to their concrete type. Implemented for every `'static`
+/// inspector.
+pub trait StackedInspector<DB: Database>: Inspector<DB> + Any + fmt::Debug {}
+
+impl<DB: Database, T: Inspector<DB> + Any + fmt::Debug> StackedInspector<DB> for T {}
+
+/// Inspector that forwards every hook to a list of child inspectors.
+///
+/// Children see each hook in the order they were added. If a child returns an
+/// outcome from `call`, `create` or `eofcreate`, the frame is skipped and the
+/// children after it do not see the hook; the matching `*_end` hook then only
+/// goes to the children that saw the start, each receiving the outcome
+/// returned by the previous one.
+pub struct InspectorStack<DB: Database> {
+    /// Children in the order hooks are forwarded to them
+    inspectors: Vec<Box<dyn StackedInspector<DB>>>,
+    /// Number of children that saw the start of each open frame, innermost last
+    frame_widths: Vec<usize>,
+}
+
+impl<DB: Database> fmt::Debug for InspectorStack<DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InspectorStack")
+            .field("inspectors", &self.inspectors)
+            .field("frame_widths", &self.frame_widths)
+            .finish()
+    }
+}
+
+impl<DB: Database> Default for InspectorStack<DB> {
+    fn default() -> Self {
+        Self {
+            inspectors: Vec::new(),
+            frame_widths: Vec::new(),
+        }
+    }
+}
+
+impl<DB: Database> InspectorStack<DB> {
+    /// Creates an empty stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `inspector` after the existing children.
+    pub fn with(mut self, inspector: impl StackedInspector<DB>) -> Self {
+        self.push(inspector);
+        self
+    }
+
+    /// Adds `inspector` after the existing children.
+    pub fn push(&mut self, inspector: impl StackedInspector<DB>) {
+        self.inspectors.push(Box::new(inspector));
+    }
+
+    /// Adds an already boxed `inspector` after the existing children.
+    pub fn push_boxed(&mut self, inspector: Box<dyn StackedInspector<DB>>) {
+        self.inspectors.push(inspector);
+    }
+
+    /// Returns the number of children.
+    pub fn len(&self) -> usize {
+        self.inspectors.len()
+    }
+
+    /// Returns true if the stack has no children.
+    pub fn is_empty(&self) -> bool {
+        self.inspectors.is_empty()
+    }
+
+    /// Returns the first child of type `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.inspectors.iter().find_map(|inspector| {
+            let inspector: &dyn Any = inspector.as_ref();
+            inspector.downcast_ref()
+        })
+    }
+
+    /// Returns the first child of type `T` mutably.
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.inspectors.iter_mut().find_map(|inspector| {
+            let inspector: &mut dyn Any = inspector.as_mut();
+            inspector.downcast_mut()
+        })
+    }
+
+    /// Removes the first child of type `T` and returns it, e.g. to call
+    /// consuming methods such as `into_trace`.
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        let index = self.inspectors.iter().position(|inspector| {
+            let inspector: &dyn Any = inspector.as_ref();
+            inspector.is::<T>()
+        })?;
+        let inspector: Box<dyn Any> = self.inspectors.remove(index);
+        inspector.downcast().ok().map(|inspector| *inspector)
+    }
+
+    /// Forwards the start of a frame until a child returns an outcome, and
+    /// records how many children saw it.
+    fn start_frame<O>(
+        &mut self,
+        mut hook: impl FnMut(&mut dyn StackedInspector<DB>) -> Option<O>,
+    ) -> Option<O> {
+        for (index, inspector) in self.inspectors.iter_mut().enumerate() {
+            if let Some(outcome) = hook(inspector.as_mut()) {
+                self.frame_widths.push(index + 1);
+                return Some(outcome);
+            }
+        }
+        self.frame_widths.push(self.inspectors.len());
+        None
+    }
+
+    /// Passes the outcome of a frame through the children that saw its start.
+    fn end_frame<O>(
+        &mut self,
+        outcome: O,
+        mut hook: impl FnMut(&mut dyn StackedInspector<DB>, O) -> O,
+    ) -> O {
+        let width = self.frame_widths.pop().unwrap_or(self.inspectors.len());
+        self.inspectors[..width]
+            .iter_mut()
+            .fold(outcome, |outcome, inspector| hook(inspector.as_mut(), outcome))
+    }
+}
+
+impl<DB: Database> Inspector<DB> for InspectorStack<DB> {
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        for inspector in &mut self.inspectors {
+            inspector.initialize_interp(interp, context);
+        }
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        for inspector in &mut self.inspectors {
+            inspector.step(interp, context);
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        for inspector in &mut self.inspectors {
+            inspector.step_end(interp, context);
+        }
+    }
+
+    fn log(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>, log: &Log) {
+        for inspector in &mut self.inspectors {
+            inspector.log(interp, context, log);
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.start_frame(|inspector| inspector.call(context, inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.end_frame(outcome, |inspector, outcome| {
+            inspector.call_end(context, inputs, outcome)
+        })
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start_frame(|inspector| inspector.create(context, inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end_frame(outcome, |inspector, outcome| {
+            inspector.create_end(context, inputs, outcome)
+        })
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.start_frame(|inspector| inspector.eofcreate(context, inputs))
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.end_frame(outcome, |inspector, outcome| {
+            inspector.eofcreate_end(context, inputs, outcome)
+        })
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        for inspector in &mut self.inspectors {
+            inspector.selfdestruct(contract, target, value);
+        }
+    }
+}
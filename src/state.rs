@@ -0,0 +1,192 @@
+//! Seeding an [`InMemoryDB`] with accounts.
+//!
+//! A [`Prestate`] holds balances, nonces, code and storage and writes them
+//! into a database with [`Prestate::apply`]. It is built in code with a
+//! [`PrestateBuilder`] or parsed from the output of Geth's `prestateTracer`
+//! with [`Prestate::from_json`], so a captured transaction can be replayed
+//! locally.
+//!
+//! ```
+//! use alloy_primitives::{Address, Bytes, U256};
+//! use restd::state::Prestate;
+//! use revm::InMemoryDB;
+//!
+//! let mut db = InMemoryDB::default();
+//! Prestate::builder()
+//!     .account(Address::repeat_byte(0x01))
+//!     .balance(U256::from(10u64.pow(18)))
+//!     .account(Address::repeat_byte(0xaa))
+//!     .code(Bytes::from_static(&[0x00]))
+//!     .storage(U256::ZERO, U256::from(1))
+//!     .apply(&mut db);
+//! ```
+
+use std::collections::BTreeMap;
+
+use alloy_primitives::{keccak256, Address, Bytes, U256};
+use revm::{
+    primitives::{AccountInfo, Bytecode, KECCAK_EMPTY},
+    InMemoryDB,
+};
+use serde::{Deserialize, Serialize};
+
+/// An account of a [`Prestate`], serialized like an account of Geth's
+/// `prestateTracer` output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrestateAccount {
+    /// Balance in wei
+    #[serde(skip_serializing_if = "U256::is_zero")]
+    pub balance: U256,
+    /// Account nonce
+    #[serde(skip_serializing_if = "is_zero")]
+    pub nonce: u64,
+    /// Deployed code; `Some` but empty if the dump lists empty code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Storage slots and their values; zero values are kept and written as
+    /// well
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+fn is_zero(nonce: &u64) -> bool {
+    *nonce == 0
+}
+
+impl PrestateAccount {
+    /// Returns the account info written to the database, with the code hash
+    /// of the code or of empty code.
+    pub fn info(&self) -> AccountInfo {
+        let code = self.code.clone().unwrap_or_default();
+        if code.is_empty() {
+            AccountInfo::new(self.balance, self.nonce, KECCAK_EMPTY, Bytecode::new())
+        } else {
+            AccountInfo::new(self.balance, self.nonce, keccak256(&code), Bytecode::new_raw(code))
+        }
+    }
+}
+
+/// Accounts to load into a database before executing a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Prestate {
+    /// The accounts, by address
+    pub accounts: BTreeMap<Address, PrestateAccount>,
+}
+
+impl Prestate {
+    /// Returns a builder for a prestate.
+    pub fn builder() -> PrestateBuilder {
+        PrestateBuilder::default()
+    }
+
+    /// Parses the output of Geth's `prestateTracer` in its default mode: an
+    /// object mapping addresses to their `balance`, `nonce`, `code` and
+    /// `storage`, each of which may be missing.
+    ///
+    /// ```
+    /// use alloy_primitives::{Address, U256};
+    /// use restd::state::Prestate;
+    ///
+    /// let prestate = Prestate::from_json(
+    ///     r#"{
+    ///         "0x0101010101010101010101010101010101010101": {
+    ///             "balance": "0xde0b6b3a7640000",
+    ///             "nonce": 1
+    ///         }
+    ///     }"#,
+    /// )
+    /// .unwrap();
+    /// let account = &prestate.accounts[&Address::repeat_byte(0x01)];
+    /// assert_eq!(account.balance, U256::from(10u64.pow(18)));
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serializes the prestate in the format read by [`from_json`](Self::from_json).
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Writes every account into `db`, replacing accounts already there.
+    ///
+    /// Storage slots are added to the slots `db` already holds for the
+    /// account.
+    pub fn apply(&self, db: &mut InMemoryDB) {
+        for (address, account) in &self.accounts {
+            db.insert_account_info(*address, account.info());
+            for (slot, value) in &account.storage {
+                db.insert_account_storage(*address, *slot, *value)
+                    .expect("an in-memory database cannot fail");
+            }
+        }
+    }
+}
+
+/// Builds a [`Prestate`] one account at a time.
+///
+/// [`account`](Self::account) selects the account the following calls set
+/// fields of; setting a field before selecting an account panics.
+#[derive(Debug, Clone, Default)]
+pub struct PrestateBuilder {
+    prestate: Prestate,
+    current: Option<Address>,
+}
+
+impl PrestateBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `address`, if it is not there yet, and selects it.
+    pub fn account(mut self, address: Address) -> Self {
+        self.prestate.accounts.entry(address).or_default();
+        self.current = Some(address);
+        self
+    }
+
+    /// Sets the balance of the selected account.
+    pub fn balance(mut self, balance: U256) -> Self {
+        self.current().balance = balance;
+        self
+    }
+
+    /// Sets the nonce of the selected account.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.current().nonce = nonce;
+        self
+    }
+
+    /// Sets the code of the selected account.
+    pub fn code(mut self, code: impl Into<Bytes>) -> Self {
+        self.current().code = Some(code.into());
+        self
+    }
+
+    /// Sets a storage slot of the selected account.
+    pub fn storage(mut self, slot: U256, value: U256) -> Self {
+        self.current().storage.insert(slot, value);
+        self
+    }
+
+    /// Returns the prestate.
+    pub fn build(self) -> Prestate {
+        self.prestate
+    }
+
+    /// Writes the accounts into `db`, see [`Prestate::apply`].
+    pub fn apply(self, db: &mut InMemoryDB) -> Prestate {
+        self.prestate.apply(db);
+        self.prestate
+    }
+
+    fn current(&mut self) -> &mut PrestateAccount {
+        let address = self
+            .current
+            .expect("select an account with `PrestateBuilder::account` first");
+        self.prestate.accounts.entry(address).or_default()
+    }
+}
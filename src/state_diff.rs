@@ -0,0 +1,169 @@
+//! Diffing the state before and after a transaction.
+//!
+//! [`compute`] compares the state returned by `transact()` with the database
+//! the transaction ran against, so it must be called before the state is
+//! committed. The result has the shape of Geth's `prestateTracer` in diff
+//! mode: `pre` holds the old values of what changed and `post` the new ones.
+
+use std::collections::BTreeMap;
+
+use alloy_primitives::{Address, Bytes, U256};
+use revm::{
+    primitives::{Account, AccountInfo, ResultAndState, KECCAK_EMPTY},
+    DatabaseRef,
+};
+use serde::{Deserialize, Serialize};
+
+/// Changed fields of an account on one side of a [`StateDiff`].
+///
+/// Fields that did not change are `None`. An account that did not exist on a
+/// side is missing from it instead, and has every field set on the other.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountDiff {
+    /// Balance in wei
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Account nonce
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// Deployed code, empty if the account has none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Changed storage slots
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl AccountDiff {
+    /// Every field of `info`, with the given storage slots.
+    fn full(info: &AccountInfo, code: Bytes, storage: BTreeMap<U256, U256>) -> Self {
+        Self {
+            balance: Some(info.balance),
+            nonce: Some(info.nonce),
+            code: (!code.is_empty()).then_some(code),
+            storage,
+        }
+    }
+}
+
+/// Accounts changed by a transaction, in the diff mode format of Geth's
+/// `prestateTracer`.
+///
+/// Accounts that were only read do not appear.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Old values of the changed fields; accounts created by the transaction
+    /// are missing
+    pub pre: BTreeMap<Address, AccountDiff>,
+    /// New values of the changed fields; accounts destroyed by the
+    /// transaction are missing
+    pub post: BTreeMap<Address, AccountDiff>,
+}
+
+impl StateDiff {
+    /// Returns true if the transaction changed no account.
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_empty() && self.post.is_empty()
+    }
+
+    /// Returns the addresses of the changed accounts.
+    pub fn changed_accounts(&self) -> impl Iterator<Item = &Address> {
+        let mut addresses: Vec<_> = self.pre.keys().chain(self.post.keys()).collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses.into_iter()
+    }
+
+    /// Serializes the diff as a compact JSON string.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Serializes the diff as an indented JSON string.
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Diffs the state `result` leaves behind against `db`, which must still
+/// hold the state the transaction started from.
+pub fn compute<DB: DatabaseRef>(
+    result: &ResultAndState,
+    db: &DB,
+) -> Result<StateDiff, DB::Error> {
+    let mut diff = StateDiff::default();
+    for (address, account) in &result.state {
+        let pre = db.basic_ref(*address)?;
+        let pre_code = match &pre {
+            Some(info) => code_of(info, db)?,
+            None => Bytes::new(),
+        };
+        let post = (!account.is_selfdestructed()).then_some(&account.info);
+        let (old_storage, new_storage) = changed_storage(account);
+
+        match (pre, post) {
+            (None, None) => {}
+            (Some(pre), None) => {
+                diff.pre.insert(*address, AccountDiff::full(&pre, pre_code, old_storage));
+            }
+            (None, Some(post)) => {
+                if post.is_empty() && new_storage.is_empty() {
+                    // Touched but still empty, e.g. by a call without value
+                    continue;
+                }
+                let post_code = code_of(post, db)?;
+                diff.post.insert(*address, AccountDiff::full(post, post_code, new_storage));
+            }
+            (Some(pre), Some(post)) => {
+                let mut old = AccountDiff {
+                    storage: old_storage,
+                    ..Default::default()
+                };
+                let mut new = AccountDiff {
+                    storage: new_storage,
+                    ..Default::default()
+                };
+                if pre.balance != post.balance {
+                    old.balance = Some(pre.balance);
+                    new.balance = Some(post.balance);
+                }
+                if pre.nonce != post.nonce {
+                    old.nonce = Some(pre.nonce);
+                    new.nonce = Some(post.nonce);
+                }
+                if pre.code_hash != post.code_hash {
+                    new.code = Some(code_of(post, db)?);
+                    old.code = Some(pre_code);
+                }
+                if old != AccountDiff::default() {
+                    diff.pre.insert(*address, old);
+                    diff.post.insert(*address, new);
+                }
+            }
+        }
+    }
+    Ok(diff)
+}
+
+/// Returns the old and new values of the slots the transaction changed.
+fn changed_storage(account: &Account) -> (BTreeMap<U256, U256>, BTreeMap<U256, U256>) {
+    account
+        .storage
+        .iter()
+        .filter(|(_, slot)| slot.is_changed())
+        .map(|(key, slot)| ((*key, slot.original_value), (*key, slot.present_value)))
+        .unzip()
+}
+
+/// Returns the code of `info`, loading it from `db` if `info` only has its
+/// hash.
+fn code_of<DB: DatabaseRef>(info: &AccountInfo, db: &DB) -> Result<Bytes, DB::Error> {
+    if info.code_hash == KECCAK_EMPTY {
+        return Ok(Bytes::new());
+    }
+    Ok(match &info.code {
+        Some(code) => code.original_bytes(),
+        None => db.code_by_hash_ref(info.code_hash)?.original_bytes(),
+    })
+}
@@ -0,0 +1,46 @@
+//! State changes attempted inside static frames.
+//!
+//! revm halts a frame that tries to change state while static, i.e. inside a
+//! `STATICCALL` or any of its subcalls, with a bare
+//! `StateChangeDuringStaticCall`. Each attempt is recorded with the
+//! instruction that made it.
+
+use alloy_primitives::Address;
+use revm::interpreter::{opcode, Interpreter};
+use serde::{Deserialize, Serialize};
+
+/// A state-changing instruction executed in a static frame.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticViolationAttempt {
+    /// Contract executing the instruction; under `DELEGATECALL` this is the
+    /// calling proxy
+    pub address: Address,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// The opcode: `SSTORE`, `LOG0`–`LOG4`, `CREATE`, `CREATE2`,
+    /// `SELFDESTRUCT` or a `CALL` transferring value
+    pub opcode: u8,
+    /// Call depth of the executing frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the transaction the instruction belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// Returns true if the instruction about to execute changes state and the
+/// frame is static.
+pub(crate) fn is_violation(interp: &Interpreter) -> bool {
+    if !interp.is_static {
+        return false;
+    }
+    match interp.current_opcode() {
+        opcode::SSTORE
+        | opcode::LOG0..=opcode::LOG4
+        | opcode::CREATE
+        | opcode::CREATE2
+        | opcode::SELFDESTRUCT => true,
+        // CALL takes the value third, after the gas and the address
+        opcode::CALL => interp.stack.peek(2).is_ok_and(|value| !value.is_zero()),
+        _ => false,
+    }
+}
@@ -0,0 +1,155 @@
+//! What the inspector does on each step, worked out once from the
+//! configuration.
+//!
+//! `step` runs for every instruction, so rather than consulting the
+//! configuration each time it checks a single flags word: with nothing
+//! enabled beyond counting, it returns without decoding the opcode. Steps
+//! that are due for a sample or a console line are found by comparing
+//! against the next due step, not with a division per step.
+
+use crate::{HelloWorldInspectorConfig, OutputMode, ProfilingMode};
+
+// Flags for the per-step work that is enabled
+const ALERTS: u8 = 1 << 0;
+const SPEC: u8 = 1 << 1;
+const SAMPLE: u8 = 1 << 2;
+const RECORD: u8 = 1 << 3;
+const CONSOLE: u8 = 1 << 4;
+const COVERAGE: u8 = 1 << 5;
+const HITS: u8 = 1 << 6;
+
+/// The per-step work enabled for an inspector.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StepPlan {
+    flags: u8,
+    /// Steps between samples in [`ProfilingMode::Sampled`]
+    sample_every: u64,
+    next_sample: u64,
+    /// Steps between console lines
+    console_every: u64,
+    next_console: u64,
+}
+
+impl StepPlan {
+    /// Plans the steps of an inspector with `config`, with or without spec
+    /// awareness.
+    pub(crate) fn new(config: &HelloWorldInspectorConfig, spec_awareness: bool) -> Self {
+        let mut plan = Self::default();
+        if !config.alert_on_opcodes.is_empty() {
+            plan.flags |= ALERTS;
+        }
+        if spec_awareness {
+            plan.flags |= SPEC;
+        }
+        if config.branch_coverage {
+            plan.flags |= COVERAGE;
+        }
+        if config.pc_coverage {
+            plan.flags |= HITS;
+        }
+        match config.profiling {
+            ProfilingMode::Full => {
+                plan.flags |= RECORD;
+                if config.step_sample_interval != 0 && config.output != OutputMode::Silent {
+                    plan.flags |= CONSOLE;
+                    plan.console_every = config.step_sample_interval;
+                    plan.next_console = config.step_sample_interval;
+                }
+            }
+            ProfilingMode::Sampled { every_n_steps, .. } => {
+                plan.flags |= SAMPLE;
+                plan.sample_every = every_n_steps.max(1);
+                plan.next_sample = plan.sample_every;
+            }
+            ProfilingMode::Counters => {}
+        }
+        plan
+    }
+
+    /// Returns true if steps need more than counting.
+    #[inline(always)]
+    pub(crate) fn any(&self) -> bool {
+        self.flags != 0
+    }
+
+    /// Returns true if executed opcodes are checked for alerts.
+    pub(crate) fn alerts(&self) -> bool {
+        self.flags & ALERTS != 0
+    }
+
+    /// Returns true if executed opcodes are checked against a hardfork.
+    pub(crate) fn spec(&self) -> bool {
+        self.flags & SPEC != 0
+    }
+
+    /// Returns true if `JUMPI`s are counted for branch coverage.
+    pub(crate) fn coverage(&self) -> bool {
+        self.flags & COVERAGE != 0
+    }
+
+    /// Returns true if executed instructions are counted per program counter.
+    pub(crate) fn hits(&self) -> bool {
+        self.flags & HITS != 0
+    }
+
+    /// Returns true if steps are sampled rather than recorded.
+    pub(crate) fn sampled(&self) -> bool {
+        self.flags & SAMPLE != 0
+    }
+
+    /// Returns true if every step is recorded.
+    pub(crate) fn records(&self) -> bool {
+        self.flags & RECORD != 0
+    }
+
+    /// Returns true if `step` is sampled, moving on to the next sample.
+    pub(crate) fn sample_due(&mut self, step: u64) -> bool {
+        due(step, self.sample_every, &mut self.next_sample)
+    }
+
+    /// Returns true if `step` is written to the console, moving on to the
+    /// next line.
+    pub(crate) fn console_due(&mut self, step: u64) -> bool {
+        self.flags & CONSOLE != 0 && due(step, self.console_every, &mut self.next_console)
+    }
+}
+
+/// Returns true if `step` is a multiple of `every` at or past `next`,
+/// advancing `next` to the following multiple.
+fn due(step: u64, every: u64, next: &mut u64) -> bool {
+    if step < *next {
+        return false;
+    }
+    // Only reached once every `every` steps, unless the step count moved
+    // by more than one
+    *next = (step / every + 1) * every;
+    step.is_multiple_of(every)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_steps_are_multiples() {
+        let mut next = 3;
+        let steps: Vec<u64> = (1..=10).filter(|step| due(*step, 3, &mut next)).collect();
+        assert_eq!(steps, [3, 6, 9]);
+        // A step count that skips ahead lines up with the multiples again
+        assert!(!due(17, 3, &mut next));
+        assert!(due(18, 3, &mut next));
+    }
+
+    #[test]
+    fn test_counters_mode_plans_nothing() {
+        let config = HelloWorldInspectorConfig {
+            profiling: ProfilingMode::Counters,
+            ..Default::default()
+        };
+        assert!(!StepPlan::new(&config, false).any());
+        assert!(StepPlan::new(&config, true).spec());
+        let mut full = StepPlan::new(&HelloWorldInspectorConfig::default(), false);
+        assert!(full.records());
+        assert!(!full.console_due(1));
+    }
+}
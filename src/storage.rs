@@ -0,0 +1,180 @@
+//! Storage reads and writes observed during execution.
+//!
+//! Persistent storage (`SLOAD`/`SSTORE`) and Cancun's transient storage
+//! (`TLOAD`/`TSTORE`) are recorded separately. Transient storage is cleared
+//! at the end of every transaction, so a transient write that is never read
+//! back before then only cost gas; [`TransientWrites`] finds those.
+
+use std::{collections::HashMap, fmt::Write as _};
+
+use alloy_primitives::{Address, Bytes, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::redact::Redacted;
+
+/// Whether a storage slot was read or written, and in which storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageAccessKind {
+    /// An `SLOAD`
+    Read,
+    /// An `SSTORE`
+    Write,
+    /// A `TLOAD`
+    TransientRead,
+    /// A `TSTORE`
+    TransientWrite,
+}
+
+impl StorageAccessKind {
+    /// Returns true for `TLOAD` and `TSTORE`.
+    pub fn is_transient(self) -> bool {
+        matches!(self, Self::TransientRead | Self::TransientWrite)
+    }
+
+    /// Returns true for `SSTORE` and `TSTORE`.
+    pub fn is_write(self) -> bool {
+        matches!(self, Self::Write | Self::TransientWrite)
+    }
+}
+
+/// A single `SLOAD`, `SSTORE`, `TLOAD` or `TSTORE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageAccess {
+    /// Account whose storage was accessed; under `DELEGATECALL` this is the
+    /// calling proxy, not the code address
+    pub address: Address,
+    /// Accessed slot
+    pub slot: U256,
+    /// Whether the slot was read or written
+    pub kind: StorageAccessKind,
+    /// Value read, or new value written
+    pub value: U256,
+    /// Program counter of the instruction
+    pub pc: usize,
+    /// Index of the instruction among the steps counted so far
+    #[serde(default)]
+    pub step_index: u64,
+    /// Whether the accessing frame, or one of its parents, reverted; reverted
+    /// writes do not persist
+    pub reverted: bool,
+    /// Index of the transaction the access belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+    /// Input of the `KECCAK256` that produced the slot, e.g. the key and base
+    /// slot of a mapping entry, if it was hashed earlier in the trace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_preimage: Option<Bytes>,
+    /// Placeholder for the value if storage values are redacted, in which
+    /// case `value` is zero
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted_value: Option<Redacted>,
+    /// Source of the report the record was merged from, see
+    /// [`InspectorReport::with_source_tag`](crate::InspectorReport::with_source_tag)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_tag: Option<String>,
+}
+
+impl StorageAccess {
+    /// Completes the access once its instruction ran: a read takes the value
+    /// left on top of the stack.
+    pub(crate) fn finish(&mut self, top_of_stack: U256, redact_value: bool) {
+        if !self.kind.is_write() {
+            self.value = top_of_stack;
+        }
+        if redact_value {
+            self.redact_value();
+        }
+    }
+
+    /// Replaces the value with a placeholder keeping its hash.
+    pub(crate) fn redact_value(&mut self) {
+        self.redacted_value = Some(Redacted::new(&self.value.to_be_bytes::<32>()));
+        self.value = U256::ZERO;
+    }
+}
+
+/// Transient writes of the current transaction that were not read since,
+/// per address and slot.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TransientWrites {
+    unread: HashMap<Address, HashMap<U256, StorageAccess>>,
+}
+
+impl TransientWrites {
+    /// Applies an executed `TLOAD` or `TSTORE`, and returns the write a
+    /// `TSTORE` overwrote without it being read.
+    pub(crate) fn record(&mut self, access: StorageAccess) -> Option<StorageAccess> {
+        let slots = self.unread.entry(access.address).or_default();
+        if access.kind.is_write() {
+            slots.insert(access.slot, access)
+        } else {
+            slots.remove(&access.slot);
+            None
+        }
+    }
+
+    /// Flags the writes from `step_index` on as reverted.
+    pub(crate) fn revert_since(&mut self, step_index: u64) {
+        let writes = self.unread.values_mut().flat_map(HashMap::values_mut);
+        for write in writes.filter(|write| write.step_index >= step_index) {
+            write.reverted = true;
+        }
+    }
+
+    /// Ends the transaction, which clears transient storage, and returns the
+    /// writes never read, in execution order.
+    pub(crate) fn end_tx(&mut self) -> Vec<StorageAccess> {
+        let mut unread: Vec<_> = self
+            .unread
+            .drain()
+            .flat_map(|(_, slots)| slots.into_values())
+            .collect();
+        unread.sort_by_key(|write| write.step_index);
+        unread
+    }
+}
+
+/// Formats persistent and transient storage accesses as two sections, one
+/// access per line, marking the transient writes in `unused` as such.
+pub fn pretty_print(
+    persistent: &[StorageAccess],
+    transient: &[StorageAccess],
+    unused: &[StorageAccess],
+) -> String {
+    let mut out = String::new();
+    for (title, accesses) in [("Storage", persistent), ("Transient storage", transient)] {
+        if accesses.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "{title}:");
+        for access in accesses {
+            let op = match access.kind {
+                StorageAccessKind::Read => "SLOAD",
+                StorageAccessKind::Write => "SSTORE",
+                StorageAccessKind::TransientRead => "TLOAD",
+                StorageAccessKind::TransientWrite => "TSTORE",
+            };
+            let value = match &access.redacted_value {
+                Some(redacted) => redacted.to_string(),
+                None => format!("{:#x}", access.value),
+            };
+            let _ = write!(out, "  {op} {} [{:#x}] = {value}", access.address, access.slot);
+            if access.reverted {
+                out.push_str(" (reverted)");
+            }
+            let never_read = |write: &StorageAccess| {
+                write.tx_index == access.tx_index && write.step_index == access.step_index
+            };
+            if unused.iter().any(never_read) {
+                out.push_str(" (never read)");
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
@@ -0,0 +1,79 @@
+//! Checks of the inspector's own bookkeeping.
+//!
+//! With [`strict`](crate::HelloWorldInspectorConfig::strict) set, the
+//! inspector checks that its hooks arrive in an order it can account for:
+//! every end hook closes a frame its start hook opened, the frame stack and
+//! the depth move together, and sequence numbers only grow. A violation,
+//! e.g. from calling `reset()` in the middle of a transaction, is recorded
+//! as an [`InternalInconsistency`] and handled as
+//! [`on_inconsistency`](crate::HelloWorldInspectorConfig::on_inconsistency)
+//! says. Without `strict` the inspector carries on as best it can.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Which invariant of the inspector's bookkeeping was violated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InconsistencyKind {
+    /// The stack of open frames does not match the call depth
+    UnbalancedFrames,
+    /// A frame ended while no frame was open
+    DepthUnderflow,
+    /// The sequence counter is lower than when the ending frame started
+    SeqRegression,
+    /// An end hook ran for a frame whose start hook did not
+    EndWithoutStart,
+}
+
+impl fmt::Display for InconsistencyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnbalancedFrames => "unbalanced frame stack",
+            Self::DepthUnderflow => "depth underflow",
+            Self::SeqRegression => "sequence counter regression",
+            Self::EndWithoutStart => "end hook without start record",
+        })
+    }
+}
+
+/// What a strict inspector does when its bookkeeping is inconsistent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InconsistencyAction {
+    /// Record the inconsistency, mark the report as poisoned and keep going
+    #[default]
+    Poison,
+    /// Record the inconsistency and panic, e.g. to fail a test at the hook
+    /// that went wrong. With `catch_panics` the panic is recorded as an
+    /// internal error instead
+    Panic,
+}
+
+/// A violated invariant of the inspector's bookkeeping.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalInconsistency {
+    /// Which invariant was violated
+    pub kind: InconsistencyKind,
+    /// Hook that noticed it, e.g. `call_end`
+    pub hook: String,
+    /// What the inspector found, e.g. the depth and the open frames
+    pub context: String,
+    /// Depth of the innermost open frame when it was noticed, 0 outside
+    /// of any frame
+    pub depth: u64,
+    /// Index of the transaction that was executing
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+impl fmt::Display for InternalInconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in {} (transaction {}, depth {}): {}",
+            self.kind, self.hook, self.tx_index, self.depth, self.context
+        )
+    }
+}
@@ -0,0 +1,214 @@
+//! Step-level struct logs.
+//!
+//! [`StructLogInspector`] records one [`StructLog`] per executed instruction,
+//! in the shape of Geth's struct logger and EIP-3155 traces.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use alloy_primitives::{Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{opcode, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{
+    disasm,
+    limits::{has_room, ByteBudget},
+    opcodes, SourceLocation, SourceMapRegistry, TraceLimits,
+};
+
+/// Controls how much state [`StructLogInspector`] captures per step.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StructLogConfig {
+    /// Capture the full memory of the frame at every step. This copies the
+    /// memory on each instruction, so it is off by default
+    pub capture_memory: bool,
+    /// Only capture the top `n` stack items, or the whole stack if `None`
+    pub stack_top_n: Option<usize>,
+}
+
+/// The state of the interpreter before a single instruction.
+///
+/// Serializes with Geth's struct logger field names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    /// Program counter
+    pub pc: u64,
+    /// Executed opcode, serialized by name
+    #[serde(serialize_with = "serialize_opcode")]
+    pub op: u8,
+    /// Gas remaining before the instruction
+    pub gas: u64,
+    /// Gas charged by the instruction, including gas forwarded to subcalls
+    pub gas_cost: u64,
+    /// Call depth, the top-level frame is at depth 1 as in Geth
+    pub depth: u64,
+    /// Stack before the instruction, bottom first
+    pub stack: Vec<U256>,
+    /// Frame memory before the instruction, if memory capture is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Bytes>,
+    /// Storage slot written by an `SSTORE` and its new value
+    #[serde(rename = "storage", skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage_changes: BTreeMap<B256, B256>,
+    /// Source location of the instruction, if the inspector has a source
+    /// map of the executing code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_location: Option<SourceLocation>,
+}
+
+fn serialize_opcode<S: Serializer>(op: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&opcodes::display_name(*op))
+}
+
+/// Inspector that records a [`StructLog`] for every executed instruction.
+///
+/// The log is pushed in `step` and its gas cost is filled in by the matching
+/// `step_end`.
+#[derive(Debug, Default)]
+pub struct StructLogInspector {
+    /// Recorded steps, in execution order
+    logs: Vec<StructLog>,
+    /// What to capture per step
+    config: StructLogConfig,
+    /// Most steps to record
+    max_struct_logs: Option<usize>,
+    /// Whether the last step pushed a log that `step_end` has to complete
+    pending: bool,
+    /// Whether steps were dropped because `max_struct_logs` was reached
+    truncated: bool,
+    /// Estimate of the bytes retained, for `max_total_bytes`
+    bytes: ByteBudget,
+    /// Source maps to annotate steps with
+    source_maps: Option<Arc<SourceMapRegistry>>,
+    /// Instruction index of every program counter in the code of the
+    /// contracts with a source map
+    instruction_indices: HashMap<Address, Vec<Option<usize>>>,
+}
+
+impl StructLogInspector {
+    /// Creates a struct logger with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a struct logger with the given configuration.
+    pub fn with_config(config: StructLogConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Stops recording steps after `limits.max_struct_logs` of them, and
+    /// stops copying stacks and memory once the logs retain about
+    /// `limits.max_total_bytes`.
+    pub fn with_limits(mut self, limits: TraceLimits) -> Self {
+        self.max_struct_logs = limits.max_struct_logs;
+        self.bytes = ByteBudget::new(limits.max_total_bytes);
+        self
+    }
+
+    /// Annotates the steps of contracts with a source map in `source_maps`
+    /// with their source location.
+    pub fn with_source_maps(mut self, source_maps: impl Into<Arc<SourceMapRegistry>>) -> Self {
+        self.source_maps = Some(source_maps.into());
+        self
+    }
+
+    /// Returns the steps recorded so far.
+    pub fn struct_logs(&self) -> &[StructLog] {
+        &self.logs
+    }
+
+    /// Returns true if steps were dropped because `max_struct_logs` was
+    /// reached, or stacks and memory because `max_total_bytes` was.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated || self.bytes.is_exhausted()
+    }
+
+    /// Consumes the inspector and returns the recorded steps.
+    pub fn into_struct_logs(self) -> Vec<StructLog> {
+        self.logs
+    }
+}
+
+impl StructLogInspector {
+    /// Returns the source location of the instruction `interp` is about to
+    /// execute.
+    fn source_location(&mut self, interp: &Interpreter) -> Option<SourceLocation> {
+        let source_maps = self.source_maps.as_ref()?;
+        // Creations run init code, which the runtime source maps do not cover
+        let address = interp.contract.bytecode_address?;
+        if !source_maps.contains(&address) {
+            return None;
+        }
+        let indices = self.instruction_indices.entry(address).or_insert_with(|| {
+            disasm::instruction_indices(interp.contract.bytecode.original_byte_slice())
+        });
+        let instruction = indices.get(interp.program_counter()).copied().flatten()?;
+        source_maps.location(&address, instruction)
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StructLogInspector {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.pending = has_room(
+            self.logs.len(),
+            self.max_struct_logs,
+            &mut self.truncated,
+        );
+        if !self.pending {
+            return;
+        }
+        let op = interp.current_opcode();
+        let stack = interp.stack.data();
+        let skip = match self.config.stack_top_n {
+            Some(n) => stack.len().saturating_sub(n),
+            None => 0,
+        };
+        let captured = &stack[skip..];
+        let memory = interp.shared_memory.context_memory();
+        self.bytes.charge::<StructLog>();
+
+        let mut storage_changes = BTreeMap::new();
+        if op == opcode::SSTORE && stack.len() >= 2 {
+            let key = stack[stack.len() - 1];
+            let value = stack[stack.len() - 2];
+            storage_changes.insert(B256::from(key), B256::from(value));
+        }
+
+        let source_location = self.source_location(interp);
+        self.logs.push(StructLog {
+            pc: interp.program_counter() as u64,
+            op,
+            gas: interp.gas.remaining(),
+            gas_cost: 0,
+            depth: context.journaled_state.depth(),
+            stack: if self.bytes.reserve(captured.len() * 32) {
+                captured.to_vec()
+            } else {
+                Vec::new()
+            },
+            memory: (self.config.capture_memory && self.bytes.reserve(memory.len()))
+                .then(|| Bytes::copy_from_slice(memory)),
+            storage_changes,
+            source_location,
+        });
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if !std::mem::take(&mut self.pending) {
+            return;
+        }
+        if let Some(log) = self.logs.last_mut() {
+            log.gas_cost = log.gas.saturating_sub(interp.gas.remaining());
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Rate limiting and deduplication of console output.
+//!
+//! [`OutputMode::Stdout`](crate::OutputMode::Stdout) and
+//! [`OutputMode::Tracing`](crate::OutputMode::Tracing) write a line per
+//! event, which floods a terminal on long transactions. The
+//! [`ConsoleThrottle`] collapses identical consecutive lines into
+//! `(last message repeated N times)` and drops lines beyond
+//! `max_lines_per_second`, summarizing what it dropped when the transaction
+//! ends. Reports, trace sinks and file outputs are unaffected.
+
+use std::{
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+/// What a [`ConsoleThrottle`] held back, to be written in place of the
+/// lines themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Suppressed {
+    /// The last written line occurred this many more times in a row
+    Repeated(u64),
+    /// This many lines were dropped by the rate limit
+    RateLimited(u64),
+}
+
+impl Suppressed {
+    /// Returns the line written in place of the suppressed ones.
+    pub(crate) fn summary(self) -> String {
+        match self {
+            Self::Repeated(n) => format!("(last message repeated {n} times)"),
+            Self::RateLimited(n) => {
+                format!("({n} messages suppressed by max_lines_per_second)")
+            }
+        }
+    }
+}
+
+/// Decides which console lines are written.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConsoleThrottle {
+    /// Lines written per second at most, unlimited if `None`
+    max_lines_per_second: Option<u32>,
+    /// Start of the current one second window
+    window_start: Option<Instant>,
+    /// Lines written in the current window
+    lines_in_window: u32,
+    /// Hash of the last line offered, and whether it was written
+    last: Option<(u64, bool)>,
+    /// Repetitions of the last line since it was written
+    repeated: u64,
+    /// Lines dropped by the rate limit since the last flush
+    rate_limited: u64,
+}
+
+impl ConsoleThrottle {
+    pub(crate) fn new(max_lines_per_second: Option<u32>) -> Self {
+        Self {
+            max_lines_per_second,
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether the line with hash `hash` is written.
+    ///
+    /// `now` is only called if a rate limit is set, so that unlimited output
+    /// works without a clock. A summary of the repetitions of the previous
+    /// line is passed to `summary` before the next distinct line.
+    pub(crate) fn admit(
+        &mut self,
+        hash: u64,
+        now: impl FnOnce() -> Instant,
+        mut summary: impl FnMut(Suppressed),
+    ) -> bool {
+        match self.last {
+            Some((last, written)) if last == hash => {
+                if written {
+                    self.repeated += 1;
+                } else {
+                    self.rate_limited += 1;
+                }
+                return false;
+            }
+            _ => {}
+        }
+        if self.repeated > 0 {
+            summary(Suppressed::Repeated(self.repeated));
+            self.repeated = 0;
+        }
+        let written = self.has_room(now);
+        self.last = Some((hash, written));
+        if !written {
+            self.rate_limited += 1;
+        }
+        written
+    }
+
+    /// Counts a line against the current window if it has room left.
+    fn has_room(&mut self, now: impl FnOnce() -> Instant) -> bool {
+        let Some(max) = self.max_lines_per_second else {
+            return true;
+        };
+        let now = now();
+        let window_over = self
+            .window_start
+            .is_none_or(|start| now.saturating_duration_since(start) >= Duration::from_secs(1));
+        if window_over {
+            self.window_start = Some(now);
+            self.lines_in_window = 0;
+        }
+        if self.lines_in_window >= max {
+            return false;
+        }
+        self.lines_in_window += 1;
+        true
+    }
+
+    /// Passes the summaries of everything held back to `summary`, e.g. at the
+    /// end of a transaction, and starts over.
+    pub(crate) fn flush(&mut self, mut summary: impl FnMut(Suppressed)) {
+        if self.repeated > 0 {
+            summary(Suppressed::Repeated(self.repeated));
+        }
+        if self.rate_limited > 0 {
+            summary(Suppressed::RateLimited(self.rate_limited));
+        }
+        self.last = None;
+        self.repeated = 0;
+        self.rate_limited = 0;
+    }
+}
+
+/// Returns the FNV-1a hash of `value`, cheap enough to compute for every line.
+pub(crate) fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = Fnv1a::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 64-bit FNV-1a; lines are short, so a SipHash key buys nothing.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Offers `lines` at a fixed instant and returns what would be written.
+    fn run(throttle: &mut ConsoleThrottle, lines: impl IntoIterator<Item = String>) -> Vec<String> {
+        let now = Instant::now();
+        let mut out = Vec::new();
+        for line in lines {
+            if throttle.admit(hash_of(&line), || now, |s| out.push(s.summary())) {
+                out.push(line);
+            }
+        }
+        throttle.flush(|s| out.push(s.summary()));
+        out
+    }
+
+    #[test]
+    fn test_identical_lines_are_collapsed() {
+        let mut throttle = ConsoleThrottle::new(Some(100));
+        let out = run(&mut throttle, (0..10_000).map(|_| "step".to_string()));
+        assert_eq!(out, ["step", "(last message repeated 9999 times)"]);
+    }
+
+    #[test]
+    fn test_lines_over_the_limit_are_counted() {
+        let mut throttle = ConsoleThrottle::new(Some(100));
+        let out = run(&mut throttle, (0..10_000).map(|i| format!("step {i}")));
+        assert_eq!(out.len(), 101);
+        assert_eq!(out[99], "step 99");
+        assert_eq!(
+            out[100],
+            "(9900 messages suppressed by max_lines_per_second)"
+        );
+    }
+
+    #[test]
+    fn test_window_resets_after_a_second() {
+        let mut throttle = ConsoleThrottle::new(Some(1));
+        let start = Instant::now();
+        assert!(throttle.admit(1, || start, |_| {}));
+        assert!(!throttle.admit(2, || start, |_| {}));
+        assert!(throttle.admit(3, || start + Duration::from_secs(1), |_| {}));
+    }
+}
@@ -0,0 +1,109 @@
+//! The records of a report merged back into execution order.
+//!
+//! The inspector numbers every call, log, storage access, transfer,
+//! creation, revert and `SELFDESTRUCT` it records with a `seq` that grows
+//! across transactions until [`reset`](crate::HelloWorldInspector::reset).
+//! [`ReportTimeline`] sorts the records of all collections by it, e.g. to
+//! check whether a log was emitted before or after an `SSTORE`.
+
+use serde::Serialize;
+
+use crate::{
+    CallGasRecord, CollectedLog, CreateRecord, InspectorReport, RevertRecord, SelfdestructRecord,
+    StorageAccess, ValueTransfer,
+};
+
+/// A record of an [`InspectorReport`] in the timeline.
+///
+/// Serializes as the record with a `record` field naming its kind, e.g.
+/// `"record": "storage_access"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum TimelineEvent<'a> {
+    /// A call started
+    Call(&'a CallGasRecord),
+    /// A log was emitted
+    Log(&'a CollectedLog),
+    /// A persistent or transient storage slot was read or written
+    StorageAccess(&'a StorageAccess),
+    /// Value was transferred
+    Transfer(&'a ValueTransfer),
+    /// A creation started
+    Creation(&'a CreateRecord),
+    /// A frame reverted
+    Revert(&'a RevertRecord),
+    /// A `SELFDESTRUCT` was executed
+    Selfdestruct(&'a SelfdestructRecord),
+}
+
+impl TimelineEvent<'_> {
+    /// Returns the sequence number of the record.
+    pub fn seq(&self) -> u64 {
+        match self {
+            Self::Call(call) => call.seq,
+            Self::Log(log) => log.seq,
+            Self::StorageAccess(access) => access.seq,
+            Self::Transfer(transfer) => transfer.seq,
+            Self::Creation(creation) => creation.seq,
+            Self::Revert(revert) => revert.seq,
+            Self::Selfdestruct(selfdestruct) => selfdestruct.seq,
+        }
+    }
+
+    /// Returns the index of the transaction the record belongs to.
+    pub fn tx_index(&self) -> u64 {
+        match self {
+            Self::Call(call) => call.tx_index,
+            Self::Log(log) => log.tx_index,
+            Self::StorageAccess(access) => access.tx_index,
+            Self::Transfer(transfer) => transfer.tx_index,
+            Self::Creation(creation) => creation.tx_index,
+            Self::Revert(revert) => revert.tx_index,
+            Self::Selfdestruct(selfdestruct) => selfdestruct.tx_index,
+        }
+    }
+}
+
+/// The records of a report in the order they were recorded.
+///
+/// Reports merged from several inspectors each number their records from 0,
+/// so their records interleave by `seq` rather than follow one another.
+#[derive(Debug, Clone)]
+pub struct ReportTimeline<'a> {
+    events: Vec<TimelineEvent<'a>>,
+}
+
+impl<'a> ReportTimeline<'a> {
+    /// Collects the records of `report`, ordered by `seq`.
+    pub fn new(report: &'a InspectorReport) -> Self {
+        let mut events: Vec<TimelineEvent<'a>> = report
+            .calls
+            .iter()
+            .map(TimelineEvent::Call)
+            .chain(report.logs.iter().map(TimelineEvent::Log))
+            .chain(report.storage_accesses.iter().map(TimelineEvent::StorageAccess))
+            .chain(report.transient_accesses.iter().map(TimelineEvent::StorageAccess))
+            .chain(report.transfers.iter().map(TimelineEvent::Transfer))
+            .chain(report.creations.iter().map(TimelineEvent::Creation))
+            .chain(report.reverts.iter().map(TimelineEvent::Revert))
+            .chain(report.selfdestructs.iter().map(TimelineEvent::Selfdestruct))
+            .collect();
+        events.sort_by_key(TimelineEvent::seq);
+        Self { events }
+    }
+
+    /// Returns the records in `seq` order.
+    pub fn iter(&self) -> impl Iterator<Item = TimelineEvent<'a>> + '_ {
+        self.events.iter().copied()
+    }
+
+    /// Returns the number of records.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if the report has no records.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
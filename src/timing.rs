@@ -0,0 +1,45 @@
+//! Wall-clock timing of frames and transactions.
+//!
+//! With `measure_time` enabled the inspector reads the monotonic clock when
+//! each call or create frame starts and ends, recording the elapsed time in
+//! whole microseconds on the frame's record and, for top-level frames, per
+//! transaction. Durations are truncated rather than rounded, so a frame is
+//! never reported as shorter than the sum of its children, and a clock that
+//! is coarser than a frame reports it as taking 0 µs. On wasm32 there is no
+//! clock and nothing is timed.
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+/// Wall-clock duration of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxTiming {
+    /// Index of the transaction
+    pub tx_index: u64,
+    /// Time from the start of the top-level frame to its end
+    pub duration_micros: u64,
+}
+
+/// Returns the current time, or `None` where there is no clock.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> Option<Instant> {
+    Some(Instant::now())
+}
+
+/// `Instant::now` panics on wasm32-unknown-unknown.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> Option<Instant> {
+    None
+}
+
+/// Returns the whole microseconds from `start` to `end`.
+pub(crate) fn micros_between(start: Instant, end: Instant) -> u64 {
+    end.saturating_duration_since(start).as_micros() as u64
+}
+
+/// Returns the whole microseconds since `start`, if it was taken.
+pub(crate) fn elapsed_micros(start: Option<Instant>) -> Option<u64> {
+    let start = start?;
+    Some(micros_between(start, now()?))
+}
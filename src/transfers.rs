@@ -0,0 +1,83 @@
+//! Value transfers made during execution.
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, I256, U256};
+use serde::{Deserialize, Serialize};
+
+/// How value was moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    /// A value-bearing `CALL` or `CALLCODE`, including the top-level call
+    Call,
+    /// Value endowed to a contract by `CREATE` or `CREATE2`
+    Create,
+    /// A balance sent to the beneficiary of a `SELFDESTRUCT`
+    Selfdestruct,
+}
+
+/// A single non-zero movement of ETH.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueTransfer {
+    /// Sender of the value
+    pub from: Address,
+    /// Recipient of the value, or the created contract
+    pub to: Address,
+    /// Amount transferred in wei
+    pub value: U256,
+    /// How the value was moved
+    pub kind: TransferKind,
+    /// Whether the frame that made the transfer, or one of its parents,
+    /// reverted, undoing the transfer
+    pub reverted: bool,
+    /// Index of the transaction the transfer belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+    /// Position of the record among everything the inspector recorded, see
+    /// [`ReportTimeline`](crate::ReportTimeline)
+    #[serde(default)]
+    pub seq: u64,
+}
+
+impl ValueTransfer {
+    pub(crate) fn new(
+        from: Address,
+        to: Address,
+        value: U256,
+        kind: TransferKind,
+        tx_index: u64,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            value,
+            kind,
+            reverted: false,
+            tx_index,
+            seq: 0,
+        }
+    }
+}
+
+/// Computes the signed net flow per address from `transfers`.
+///
+/// Reverted transfers are skipped. A `SELFDESTRUCT` naming the destroyed
+/// contract as beneficiary burns the value, so it only counts as outflow.
+/// Gas fees are not value transfers and are not included.
+pub fn net_balance_changes<'a>(
+    transfers: impl IntoIterator<Item = &'a ValueTransfer>,
+) -> HashMap<Address, I256> {
+    let mut changes: HashMap<Address, I256> = HashMap::new();
+    for transfer in transfers.into_iter().filter(|transfer| !transfer.reverted) {
+        if transfer.from == transfer.to && transfer.kind != TransferKind::Selfdestruct {
+            continue;
+        }
+        let value = I256::try_from(transfer.value).unwrap_or(I256::MAX);
+        *changes.entry(transfer.from).or_default() -= value;
+        if transfer.from != transfer.to {
+            *changes.entry(transfer.to).or_default() += value;
+        }
+    }
+    changes
+}
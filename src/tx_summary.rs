@@ -0,0 +1,228 @@
+//! One-paragraph summary of a transaction.
+//!
+//! [`TxSummary`] combines the report of an inspector with the result revm
+//! returned for the transaction: who sent it, where it went, whether it
+//! succeeded, what it cost and what it touched.
+
+use std::{collections::BTreeMap, fmt};
+
+use alloy_primitives::{Address, U256};
+use revm::primitives::{ExecutionResult, Output, ResultAndState};
+use serde::{Deserialize, Serialize};
+
+use crate::{pretty::ether, CreateKind, InspectorReport, RevertReason};
+
+/// How a transaction ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    /// The transaction succeeded
+    Success,
+    /// The top-level frame reverted, see [`TxSummary::revert_reason`]
+    Reverted,
+    /// The top-level frame halted, e.g. out of gas, for this reason
+    Halted(String),
+}
+
+/// Compact summary of one transaction.
+///
+/// Only the records of the last transaction in the report are counted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxSummary {
+    /// Account that sent the transaction, zero if the report holds no
+    /// top-level frame
+    pub sender: Address,
+    /// Called address, `None` for creations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Address of the deployed contract, for successful creations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<Address>,
+    /// How the transaction ended
+    pub status: TxStatus,
+    /// Gas used, after the refund
+    pub gas_used: u64,
+    /// Gas limit of the transaction, if the inspector saw it begin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    /// Gas refunded, 0 if the transaction failed
+    pub gas_refunded: u64,
+    /// Wei moved by transfers that were not reverted
+    pub value_moved: U256,
+    /// Number of frames by Geth frame type, e.g. `CALL` or `CREATE2`, the
+    /// top-level frame included
+    pub calls: BTreeMap<String, u64>,
+    /// Logs the transaction emitted, 0 if it failed
+    pub logs: usize,
+    /// Storage slots whose value changed
+    pub slots_written: usize,
+    /// Depth of the deepest frame, the top-level frame is at depth 0
+    pub max_depth: u64,
+    /// Decoded revert data if the transaction reverted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<RevertReason>,
+}
+
+impl TxSummary {
+    /// Summarizes the last transaction in `report`, whose outcome is
+    /// `result`.
+    pub fn from(report: &InspectorReport, result: &ResultAndState) -> Self {
+        let tx_index = report
+            .calls
+            .iter()
+            .map(|call| call.tx_index)
+            .chain(report.creations.iter().map(|create| create.tx_index))
+            .max()
+            .unwrap_or_default();
+        let calls = report.calls.iter().filter(|call| call.tx_index == tx_index);
+        let creations = report
+            .creations
+            .iter()
+            .filter(|create| create.tx_index == tx_index);
+
+        let top_call = calls.clone().find(|call| call.depth == 0);
+        let top_create = creations.clone().find(|create| create.depth == 0);
+        let sender = top_call
+            .map(|call| call.caller)
+            .or(top_create.map(|create| create.creator))
+            .unwrap_or_default();
+
+        let mut kinds = BTreeMap::new();
+        for call in calls.clone() {
+            *kinds.entry(call.kind.clone()).or_default() += 1;
+        }
+        for create in creations.clone() {
+            let kind = match create.scheme {
+                CreateKind::Create => "CREATE",
+                CreateKind::Create2 { .. } => "CREATE2",
+            };
+            *kinds.entry(kind.to_string()).or_default() += 1;
+        }
+        let max_depth = calls
+            .map(|call| call.depth)
+            .chain(creations.map(|create| create.depth))
+            .max()
+            .unwrap_or_default();
+        let value_moved = report
+            .transfers
+            .iter()
+            .filter(|transfer| transfer.tx_index == tx_index && !transfer.reverted)
+            .map(|transfer| transfer.value)
+            .sum();
+
+        let (status, gas_refunded, revert_reason, created) = match &result.result {
+            ExecutionResult::Success {
+                gas_refunded,
+                output,
+                ..
+            } => {
+                let created = match output {
+                    Output::Create(_, address) => *address,
+                    Output::Call(_) => None,
+                };
+                (TxStatus::Success, *gas_refunded, None, created)
+            }
+            ExecutionResult::Revert { output, .. } => (
+                TxStatus::Reverted,
+                0,
+                Some(RevertReason::decode(output)),
+                None,
+            ),
+            ExecutionResult::Halt { reason, .. } => {
+                (TxStatus::Halted(format!("{reason:?}")), 0, None, None)
+            }
+        };
+        let slots_written = result
+            .state
+            .values()
+            .map(|account| {
+                account
+                    .storage
+                    .values()
+                    .filter(|slot| slot.is_changed())
+                    .count()
+            })
+            .sum();
+
+        Self {
+            sender,
+            to: top_call.map(|call| call.target),
+            created,
+            status,
+            gas_used: result.result.gas_used(),
+            gas_limit: report.gas_summary.map(|summary| summary.gas_limit),
+            gas_refunded,
+            value_moved,
+            calls: kinds,
+            logs: result.result.logs().len(),
+            slots_written,
+            max_depth,
+            revert_reason,
+        }
+    }
+
+    /// Returns the number of frames, the top-level frame included.
+    pub fn call_count(&self) -> u64 {
+        self.calls.values().sum()
+    }
+}
+
+impl fmt::Display for TxSummary {
+    /// Formats the summary as one paragraph, e.g. `0x01…01 called 0x0a…0a
+    /// and succeeded. Gas 23512/1000000 used, 0 refunded. 0 ETH moved in 2
+    /// calls (1 CALL, 1 STATICCALL), 1 log, 1 storage slot written, max
+    /// depth 1.`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.to, self.created) {
+            (Some(to), _) => write!(f, "{} called {to}", self.sender)?,
+            (None, Some(created)) => write!(f, "{} created {created}", self.sender)?,
+            (None, None) => write!(f, "{} tried to create a contract", self.sender)?,
+        }
+        match &self.status {
+            TxStatus::Success => write!(f, " and succeeded.")?,
+            TxStatus::Reverted => match &self.revert_reason {
+                Some(reason) => write!(f, " and reverted: {reason}.")?,
+                None => write!(f, " and reverted.")?,
+            },
+            TxStatus::Halted(reason) => write!(f, " and halted: {reason}.")?,
+        }
+        write!(f, " Gas {}", self.gas_used)?;
+        if let Some(limit) = self.gas_limit {
+            write!(f, "/{limit}")?;
+        }
+        write!(f, " used, {} refunded.", self.gas_refunded)?;
+
+        let count = self.call_count();
+        write!(
+            f,
+            " {} ETH moved in {count} {}",
+            ether(self.value_moved),
+            plural(count as usize, "call", "calls")
+        )?;
+        if !self.calls.is_empty() {
+            let kinds: Vec<_> = self
+                .calls
+                .iter()
+                .map(|(kind, count)| format!("{count} {kind}"))
+                .collect();
+            write!(f, " ({})", kinds.join(", "))?;
+        }
+        write!(
+            f,
+            ", {} {}, {} storage {} written, max depth {}.",
+            self.logs,
+            plural(self.logs, "log", "logs"),
+            self.slots_written,
+            plural(self.slots_written, "slot", "slots"),
+            self.max_depth
+        )
+    }
+}
+
+fn plural(count: usize, one: &'static str, many: &'static str) -> &'static str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
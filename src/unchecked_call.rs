@@ -0,0 +1,163 @@
+//! Low-level calls whose success flag is discarded.
+//!
+//! `CALL`, `CALLCODE`, `DELEGATECALL` and `STATICCALL` push 1 or 0 rather
+//! than reverting when the callee fails, so a contract that never looks at
+//! the flag carries on as if the call succeeded. After each of them the
+//! calling frame's next instructions are followed until the flag is
+//! consumed: by a `POP`, which is recorded as a finding, or by anything
+//! else, e.g. an `ISZERO` or `JUMPI`, which counts as checking it. Flags
+//! still on the stack after the window are not reported.
+
+use alloy_primitives::Address;
+use revm::interpreter::{opcode, Interpreter, InterpreterAction, OpCode};
+use serde::{Deserialize, Serialize};
+
+/// Instructions of the calling frame followed after a call by default.
+pub const DEFAULT_UNCHECKED_CALL_WINDOW: usize = 8;
+
+/// A low-level call whose success flag was popped without being checked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UncheckedCallFinding {
+    /// Contract making the call; under `DELEGATECALL` this is the calling
+    /// proxy
+    pub caller_contract: Address,
+    /// Program counter of the call instruction
+    pub pc: usize,
+    /// Address whose code was called
+    pub callee: Address,
+    /// The call opcode
+    pub opcode: u8,
+    /// Call depth of the calling frame, the top-level frame is at depth 0
+    pub depth: u64,
+    /// Index of the transaction the call belongs to
+    #[serde(default)]
+    pub tx_index: u64,
+}
+
+/// A call whose flag is being followed.
+#[derive(Debug, Clone)]
+struct Watch {
+    finding: UncheckedCallFinding,
+    /// Frame count of the calling frame, as in the inspector's `current_depth`
+    frame: usize,
+    /// Stack index of the flag, known once the frame resumes
+    position: Option<usize>,
+    /// Instructions left in the window
+    remaining: usize,
+}
+
+/// Follows the success flags of the calls in progress, one per frame.
+#[derive(Debug, Clone)]
+pub(crate) struct UncheckedCallDetector {
+    /// Instructions followed after each call, disabled if 0
+    window: usize,
+    watches: Vec<Watch>,
+}
+
+impl Default for UncheckedCallDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNCHECKED_CALL_WINDOW)
+    }
+}
+
+impl UncheckedCallDetector {
+    pub(crate) fn new(window: usize) -> Self {
+        Self {
+            window,
+            watches: Vec::new(),
+        }
+    }
+
+    /// Returns true if `op` pushes a success flag.
+    fn is_call(op: u8) -> bool {
+        matches!(
+            op,
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL
+        )
+    }
+
+    /// Starts following the flag of the call `interp` just made, if the
+    /// instruction that executed was a call that did not fail.
+    ///
+    /// `depth` is the call depth of the calling frame and `frame` its frame
+    /// count, as in the inspector's `current_depth`.
+    pub(crate) fn after_step(
+        &mut self,
+        interp: &Interpreter,
+        frame: usize,
+        depth: u64,
+        tx_index: u64,
+    ) {
+        if self.window == 0 {
+            return;
+        }
+        let InterpreterAction::Call { inputs } = &interp.next_action else {
+            return;
+        };
+        // The instruction pointer already points past the call
+        let pc = interp.program_counter().saturating_sub(1);
+        let Some(&op) = interp.bytecode.get(pc).filter(|op| Self::is_call(**op)) else {
+            return;
+        };
+        self.watches.retain(|watch| watch.frame < frame);
+        self.watches.push(Watch {
+            finding: UncheckedCallFinding {
+                caller_contract: interp.contract.target_address,
+                pc,
+                callee: inputs.bytecode_address,
+                opcode: op,
+                depth,
+                tx_index,
+            },
+            frame,
+            position: None,
+            remaining: self.window,
+        });
+    }
+
+    /// Follows the flag of the frame `interp` executes in through the
+    /// instruction about to execute, returning a finding if it pops it.
+    pub(crate) fn step(
+        &mut self,
+        interp: &Interpreter,
+        frame: usize,
+    ) -> Option<UncheckedCallFinding> {
+        // Frames that ended without resuming, e.g. after an inspector
+        // stopped them, leave their watches behind
+        while self.watches.last().is_some_and(|watch| watch.frame > frame) {
+            self.watches.pop();
+        }
+        let watch = self.watches.last_mut().filter(|watch| {
+            watch.frame == frame && watch.finding.caller_contract == interp.contract.target_address
+        })?;
+        let len = interp.stack.len();
+        let top = len.checked_sub(1)?;
+        let position = *watch.position.get_or_insert(top);
+        let op = interp.current_opcode();
+        let consumed = match op {
+            opcode::SWAP1..=opcode::SWAP16 => {
+                let other = top.checked_sub((op - opcode::SWAP1 + 1) as usize)?;
+                if position == top {
+                    watch.position = Some(other);
+                } else if position == other {
+                    watch.position = Some(top);
+                }
+                false
+            }
+            // A copy of the flag is used later
+            opcode::DUP1..=opcode::DUP16 => {
+                top.checked_sub((op - opcode::DUP1) as usize) == Some(position)
+            }
+            _ => {
+                let inputs = OpCode::new(op).map_or(0, |op| op.inputs() as usize);
+                position + inputs >= len
+            }
+        };
+        watch.remaining -= 1;
+        if consumed || watch.remaining == 0 {
+            let watch = self.watches.pop()?;
+            return (consumed && op == opcode::POP).then_some(watch.finding);
+        }
+        None
+    }
+}
@@ -0,0 +1,25 @@
+//! Compile check of the API wasm32 embedders use.
+//!
+//! Only built for `target_arch = "wasm32"`, so checking the crate for
+//! `wasm32-unknown-unknown` without default features fails once the recording
+//! core or the JSON export stops building there, without needing a wasm
+//! runtime.
+
+#![allow(dead_code)]
+
+use crate::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+
+fn report_json() -> serde_json::Result<String> {
+    let config = HelloWorldInspectorConfig {
+        output: OutputMode::Stdout,
+        trace_calls: true,
+        ..Default::default()
+    };
+    HelloWorldInspector::with_config(config).report().to_json()
+}
+
+#[cfg(feature = "trace-core")]
+fn trace_call_json() -> Result<String, crate::export::ExportError> {
+    // PUSH1 0, PUSH1 0, REVERT
+    crate::export::trace_call_json(&[0x60, 0x00, 0x60, 0x00, 0xfd], &[], Default::default())
+}
@@ -0,0 +1,225 @@
+//! Writing a report in the format chosen by the configuration.
+//!
+//! [`ReportWriter`] writes an [`InspectorReport`], and for the call tree
+//! formats the [`CallTrace`] recorded next to it, to stdout or to a file.
+//! Files are written to a temporary file next to the destination and renamed
+//! over it, so readers never see a partial report. Writing needs the `std`
+//! feature.
+
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::{
+    CallTrace, DotOptions, Error, HelloWorldInspectorConfig, InspectorReport, PrettyPrintOptions,
+};
+
+/// Format a [`ReportWriter`] writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// The call tree, indented as by `forge test -vvvv`
+    PrettyText,
+    /// The report as a JSON object
+    #[default]
+    Json,
+    /// The call tree in Geth's `callTracer` format
+    GethCallTracer,
+    /// The report's calls, one row each
+    Csv,
+    /// The report's records in execution order, one JSON object per line
+    Jsonl,
+    /// The call graph for Graphviz
+    Dot,
+}
+
+impl Format {
+    /// Returns true if the format renders a [`CallTrace`] rather than the
+    /// report.
+    pub fn needs_call_trace(self) -> bool {
+        matches!(
+            self,
+            Format::PrettyText | Format::GethCallTracer | Format::Dot
+        )
+    }
+
+    /// Returns the usual file extension of the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::PrettyText => "txt",
+            Format::Json | Format::GethCallTracer => "json",
+            Format::Csv => "csv",
+            Format::Jsonl => "jsonl",
+            Format::Dot => "dot",
+        }
+    }
+}
+
+/// Writes reports in one [`Format`] to stdout or a file.
+///
+/// ```no_run
+/// use restd::{Format, InspectorReport, ReportWriter};
+///
+/// let writer = ReportWriter::new(Format::Csv).with_path("reports/calls.csv");
+/// writer.write_report(&InspectorReport::default())?;
+/// # Ok::<(), restd::Error>(())
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct ReportWriter {
+    format: Format,
+    path: Option<PathBuf>,
+    pretty: PrettyPrintOptions,
+    dot: DotOptions,
+}
+
+#[cfg(feature = "std")]
+impl ReportWriter {
+    /// Creates a writer of `format` to stdout.
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+
+    /// Creates the writer set by `output_format` and `output_path`, or
+    /// `None` if no format is set.
+    pub fn from_config(config: &HelloWorldInspectorConfig) -> Option<Self> {
+        let writer = Self::new(config.output_format?);
+        Some(match &config.output_path {
+            Some(path) => writer.with_path(path),
+            None => writer,
+        })
+    }
+
+    /// Writes to `path` instead of stdout, creating its parent directories.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the options of [`Format::PrettyText`].
+    pub fn with_pretty_options(mut self, options: PrettyPrintOptions) -> Self {
+        self.pretty = options;
+        self
+    }
+
+    /// Sets the options of [`Format::Dot`].
+    pub fn with_dot_options(mut self, options: DotOptions) -> Self {
+        self.dot = options;
+        self
+    }
+
+    /// Returns the format written.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the file written, or `None` for stdout.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Writes `report`.
+    ///
+    /// Fails with [`Error::MissingCallTrace`] for the formats that render a
+    /// call tree, see [`write_with_trace`](Self::write_with_trace).
+    pub fn write_report(&self, report: &InspectorReport) -> Result<(), Error> {
+        if self.format.needs_call_trace() {
+            return Err(Error::MissingCallTrace(self.format));
+        }
+        self.write(report, None)
+    }
+
+    /// Writes `report`, or `trace` for the formats that render a call tree.
+    pub fn write_with_trace(
+        &self,
+        report: &InspectorReport,
+        trace: &CallTrace,
+    ) -> Result<(), Error> {
+        self.write(report, Some(trace))
+    }
+
+    fn write(&self, report: &InspectorReport, trace: Option<&CallTrace>) -> Result<(), Error> {
+        let mut out = Vec::new();
+        self.render(report, trace, &mut out)
+            .map_err(|source| self.error(source))?;
+        match &self.path {
+            Some(path) => write_atomically(path, &out),
+            None => io::stdout().lock().write_all(&out),
+        }
+        .map_err(|source| self.error(source))
+    }
+
+    fn render(
+        &self,
+        report: &InspectorReport,
+        trace: Option<&CallTrace>,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let trace = || trace.ok_or_else(|| io::Error::other("no call trace to render"));
+        match self.format {
+            Format::PrettyText => {
+                let mut options = self.pretty.clone();
+                if options.failures.is_empty() {
+                    options.failures = report.failures.clone();
+                }
+                out.extend(trace()?.pretty_print(options).bytes())
+            }
+            Format::Json => {
+                serde_json::to_writer_pretty(&mut *out, report)?;
+                out.push(b'\n');
+            }
+            Format::GethCallTracer => {
+                serde_json::to_writer_pretty(&mut *out, &trace()?.to_geth_call_trace())?;
+                out.push(b'\n');
+            }
+            Format::Csv => report.to_csv(out)?,
+            Format::Jsonl => {
+                for event in report.timeline().iter() {
+                    serde_json::to_writer(&mut *out, &event)?;
+                    out.push(b'\n');
+                }
+            }
+            Format::Dot => out.extend(trace()?.to_dot(self.dot.clone()).bytes()),
+        }
+        Ok(())
+    }
+
+    fn error(&self, source: io::Error) -> Error {
+        Error::Output {
+            path: self.path.clone(),
+            source,
+        }
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it to
+/// `path`, creating the parent directories first.
+#[cfg(feature = "std")]
+fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = dir.join(temp_name);
+    let result = fs::write(&temp, contents).and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
@@ -0,0 +1,207 @@
+//! Tests for splitting account abstraction bundles into user operations.
+
+use alloy_primitives::{hex, Address, Bytes, Log, LogData, B256, U256};
+use restd::{AAAnalyzer, CallKind, CallTrace, CallTraceLog, CallTraceNode};
+use revm::interpreter::InstructionResult;
+
+const BUNDLER: Address = Address::repeat_byte(0x40);
+const ENTRYPOINT: Address = Address::repeat_byte(0x41);
+const ALICE: Address = Address::repeat_byte(0x42);
+const BOB: Address = Address::repeat_byte(0x43);
+const CAROL: Address = Address::repeat_byte(0x44);
+const PAYEE: Address = Address::repeat_byte(0x45);
+const INNER_HANDLE_OP: [u8; 4] = hex!("1d732756");
+
+fn frame(
+    caller: Address,
+    target: Address,
+    depth: usize,
+    steps: (u64, u64),
+    gas_used: u64,
+    children: Vec<CallTraceNode>,
+) -> CallTraceNode {
+    CallTraceNode {
+        kind: CallKind::Call,
+        caller,
+        target,
+        delegate: None,
+        value: U256::ZERO,
+        input: Bytes::new(),
+        output: Bytes::new(),
+        gas_limit: 1_000_000,
+        gas_requested: None,
+        gas_capped: false,
+        gas_used,
+        success: true,
+        result: InstructionResult::Stop,
+        depth,
+        start_step: steps.0,
+        end_step: steps.1,
+        start_micros: None,
+        end_micros: None,
+        children,
+        logs: Vec::new(),
+    }
+}
+
+fn log(address: Address, topic: u8, step: u64) -> CallTraceLog {
+    CallTraceLog {
+        log: Log {
+            address,
+            data: LogData::new_unchecked(vec![B256::repeat_byte(topic)], Bytes::new()),
+        },
+        step,
+    }
+}
+
+fn reverted(mut node: CallTraceNode) -> CallTraceNode {
+    node.success = false;
+    node.result = InstructionResult::Revert;
+    node
+}
+
+fn with_value(mut node: CallTraceNode, value: u64) -> CallTraceNode {
+    node.value = U256::from(value);
+    node
+}
+
+/// The entrypoint calls three accounts, one user operation each, and emits
+/// an event after each. Bob's operation reverts after paying and logging.
+fn bundle() -> CallTraceNode {
+    let mut alice = frame(
+        ENTRYPOINT,
+        ALICE,
+        1,
+        (10, 40),
+        20_000,
+        vec![with_value(
+            frame(ALICE, PAYEE, 2, (20, 20), 9_000, Vec::new()),
+            3,
+        )],
+    );
+    alice.logs.push(log(ALICE, 0xa1, 30));
+    let mut bob = reverted(frame(
+        ENTRYPOINT,
+        BOB,
+        1,
+        (50, 70),
+        7_000,
+        vec![with_value(
+            frame(BOB, PAYEE, 2, (60, 60), 2_000, Vec::new()),
+            5,
+        )],
+    ));
+    bob.logs.push(log(BOB, 0xb1, 65));
+    let mut carol = with_value(frame(ENTRYPOINT, CAROL, 1, (80, 90), 4_000, Vec::new()), 2);
+    carol.logs.push(log(CAROL, 0xc1, 85));
+    let mut entrypoint = frame(
+        BUNDLER,
+        ENTRYPOINT,
+        0,
+        (0, 100),
+        40_000,
+        vec![alice, bob, carol],
+    );
+    entrypoint.logs = vec![
+        log(ENTRYPOINT, 0xe0, 5),
+        log(ENTRYPOINT, 0xe1, 45),
+        log(ENTRYPOINT, 0xe2, 75),
+        log(ENTRYPOINT, 0xe3, 95),
+    ];
+    entrypoint
+}
+
+fn topics(logs: &[Log]) -> Vec<u8> {
+    logs.iter().map(|log| log.topics()[0][0]).collect()
+}
+
+#[test]
+fn test_each_child_call_is_a_user_op() {
+    let trace = CallTrace::new(bundle());
+    let report = AAAnalyzer::new(ENTRYPOINT).analyze(&trace).unwrap();
+
+    assert!(report.bundle_success);
+    assert_eq!(report.bundle_gas_used, 40_000);
+    assert_eq!(report.overhead_gas, 40_000 - 20_000 - 7_000 - 4_000);
+    let summary: Vec<_> = report
+        .user_ops
+        .iter()
+        .map(|op| {
+            (
+                op.index,
+                op.target,
+                op.frames,
+                op.gas_used,
+                op.success,
+                op.value.to::<u64>(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (0, ALICE, 1, 20_000, true, 3),
+            (1, BOB, 1, 7_000, false, 0),
+            (2, CAROL, 1, 4_000, true, 2),
+        ]
+    );
+
+    // Each operation gets its kept logs and the entrypoint's events after
+    // it; the event before the first operation is overhead
+    assert_eq!(topics(&report.user_ops[0].logs), [0xa1, 0xe1]);
+    assert_eq!(topics(&report.user_ops[1].logs), [0xe2]);
+    assert_eq!(topics(&report.user_ops[2].logs), [0xc1, 0xe3]);
+
+    assert_eq!(report.reverted_ops, [1]);
+    assert_eq!(
+        report.reverted().map(|op| op.target).collect::<Vec<_>>(),
+        [BOB]
+    );
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["user_ops"][1]["success"], false);
+    assert_eq!(json["reverted_ops"], serde_json::json!([1]));
+
+    // The entrypoint is found below the bundler's own frame, and a trace
+    // without it has no bundle
+    let wrapped = CallTrace::new(frame(PAYEE, BUNDLER, 0, (0, 100), 50_000, vec![bundle()]));
+    assert_eq!(AAAnalyzer::new(ENTRYPOINT).analyze(&wrapped), Some(report));
+    assert_eq!(AAAnalyzer::new(BUNDLER).analyze(&trace), None);
+}
+
+#[test]
+fn test_split_selector_and_failed_bundles() {
+    // Validation calls to the accounts, then one `innerHandleOp` self-call
+    // per operation, the second of which also pays out separately
+    let handle = |steps, gas_used| {
+        let mut node = frame(ENTRYPOINT, ENTRYPOINT, 1, steps, gas_used, Vec::new());
+        node.input = Bytes::copy_from_slice(&INNER_HANDLE_OP);
+        node
+    };
+    let children = vec![
+        frame(ENTRYPOINT, ALICE, 1, (5, 10), 1_000, Vec::new()),
+        frame(ENTRYPOINT, BOB, 1, (10, 15), 1_000, Vec::new()),
+        handle((20, 30), 5_000),
+        reverted(handle((30, 40), 6_000)),
+        with_value(frame(ENTRYPOINT, PAYEE, 1, (40, 40), 9_000, Vec::new()), 4),
+    ];
+    let mut entrypoint = frame(BUNDLER, ENTRYPOINT, 0, (0, 50), 25_000, children);
+    let analyzer = AAAnalyzer::new(ENTRYPOINT).split_on(INNER_HANDLE_OP);
+    let report = analyzer.analyze_frame(&entrypoint);
+
+    assert_eq!(report.user_ops.len(), 2);
+    assert_eq!(report.user_ops[0].frames, 1);
+    assert_eq!(report.user_ops[1].frames, 2);
+    assert_eq!(report.user_ops[1].gas_used, 15_000);
+    assert_eq!(report.user_ops[1].value, U256::from(4));
+    assert!(!report.user_ops[1].success);
+    assert_eq!(report.overhead_gas, 25_000 - 5_000 - 15_000);
+    assert_eq!(report.reverted_ops, [1]);
+
+    // When the bundle itself reverts nothing is kept, and no operation
+    // reverted on its own
+    entrypoint = reverted(entrypoint);
+    let report = analyzer.analyze_frame(&entrypoint);
+    assert!(!report.bundle_success);
+    assert!(report.reverted_ops.is_empty());
+    assert_eq!(report.user_ops[1].value, U256::ZERO);
+}
@@ -0,0 +1,68 @@
+//! Integration tests for `AccessListInspector`.
+
+mod common;
+
+use alloy_primitives::{Address, B256, U256};
+use restd::{AccessListConfig, AccessListInspector};
+use revm::{
+    primitives::{AccessList, AccessListItem},
+    InMemoryDB,
+};
+
+const A: Address = Address::repeat_byte(0x20);
+const B: Address = Address::repeat_byte(0x30);
+const IDENTITY: Address = Address::with_last_byte(0x04);
+
+fn slot(n: u64) -> B256 {
+    B256::from(U256::from(n))
+}
+
+fn trace(config: AccessListConfig) -> AccessList {
+    // A: SLOAD(1), SSTORE(2, 1), BALANCE(caller), CALL B, CALL identity, STOP
+    let mut code_a = vec![0x60, 0x01, 0x54, 0x50, 0x60, 0x01, 0x60, 0x02, 0x55];
+    code_a.push(0x73);
+    code_a.extend_from_slice(common::CALLER.as_slice());
+    code_a.extend_from_slice(&[0x31, 0x50]);
+    code_a.extend(common::call_asm(B, 0));
+    code_a.extend(common::call_asm(IDENTITY, 0));
+    code_a.push(0x00);
+    // B: SLOAD(3), STOP
+    let code_b = vec![0x60, 0x03, 0x54, 0x50, 0x00];
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, A, code_a);
+    common::deploy(&mut db, B, code_b);
+
+    let mut inspector = AccessListInspector::with_config(config);
+    let result = common::run(&mut db, common::call_env(A, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector.into_access_list()
+}
+
+#[test]
+fn test_access_list_of_two_contracts_and_three_slots() {
+    let list = trace(AccessListConfig::default());
+    assert_eq!(
+        list,
+        AccessList(vec![
+            AccessListItem {
+                address: A,
+                storage_keys: vec![slot(1), slot(2)],
+            },
+            AccessListItem {
+                address: B,
+                storage_keys: vec![slot(3)],
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_access_list_without_exclusions() {
+    let list = trace(AccessListConfig {
+        exclude_sender_and_recipient: false,
+        exclude_precompiles: false,
+    });
+    let addresses: Vec<Address> = list.iter().map(|item| item.address).collect();
+    assert_eq!(addresses, vec![IDENTITY, common::CALLER, A, B]);
+}
@@ -0,0 +1,83 @@
+//! Integration tests for the address filter.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{AddressFilter, FilterMatch, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+const ENTRY: Address = Address::repeat_byte(0x11);
+const NOISY: Address = Address::repeat_byte(0x22);
+const LEAF: Address = Address::repeat_byte(0x33);
+
+/// ENTRY calls NOISY twice; NOISY calls LEAF each time.
+fn setup() -> InMemoryDB {
+    let mut entry = common::call_asm(NOISY, 0);
+    entry.extend(common::call_asm(NOISY, 0));
+    entry.push(0x00);
+    let mut noisy = common::call_asm(LEAF, 0);
+    noisy.push(0x00);
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, ENTRY, entry);
+    common::deploy(&mut db, NOISY, noisy);
+    common::deploy(&mut db, LEAF, vec![0x00]);
+    db
+}
+
+fn trace(filter: AddressFilter) -> HelloWorldInspector {
+    let mut db = setup();
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        filter,
+        ..Default::default()
+    });
+    common::run(&mut db, common::call_env(ENTRY, vec![]), &mut inspector);
+    inspector
+}
+
+fn targets(inspector: &HelloWorldInspector) -> Vec<Address> {
+    inspector.gas_by_call().iter().map(|record| record.target).collect()
+}
+
+#[test]
+fn test_unfiltered_trace_records_every_call() {
+    let inspector = trace(AddressFilter::default());
+    assert_eq!(targets(&inspector), vec![ENTRY, NOISY, LEAF, NOISY, LEAF]);
+}
+
+#[test]
+fn test_excluded_contract_produces_no_records() {
+    let unfiltered = trace(AddressFilter::default());
+    let inspector = trace(AddressFilter {
+        exclude: vec![NOISY],
+        ..Default::default()
+    });
+
+    assert_eq!(targets(&inspector), vec![ENTRY, LEAF, LEAF]);
+    assert!(inspector.stats_for(NOISY).is_none());
+    assert_eq!(inspector.calls(), 3);
+    // Steps executed inside the excluded frames are not recorded either.
+    assert!(inspector.steps() < unfiltered.steps());
+}
+
+#[test]
+fn test_include_list_limits_scope() {
+    let inspector = trace(AddressFilter {
+        include: vec![LEAF],
+        ..Default::default()
+    });
+    assert_eq!(targets(&inspector), vec![LEAF, LEAF]);
+    // LEAF only executes a single STOP per call.
+    assert_eq!(inspector.steps(), 2);
+}
+
+#[test]
+fn test_match_on_caller() {
+    let inspector = trace(AddressFilter {
+        include: vec![NOISY],
+        match_on: FilterMatch::Caller,
+        ..Default::default()
+    });
+    assert_eq!(targets(&inspector), vec![LEAF, LEAF]);
+}
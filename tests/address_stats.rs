@@ -0,0 +1,71 @@
+//! Integration tests for per-address call statistics.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{DelegateCallAttribution, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+const PROXY: Address = Address::repeat_byte(0xa0);
+const IMPLEMENTATION: Address = Address::repeat_byte(0xb0);
+const SINK: Address = Address::repeat_byte(0xc0);
+
+/// Proxy delegatecalls the implementation twice and sends 5 wei to the sink;
+/// the implementation reverts.
+fn setup() -> InMemoryDB {
+    let mut proxy = common::delegate_call_asm(IMPLEMENTATION);
+    proxy.extend(common::delegate_call_asm(IMPLEMENTATION));
+    proxy.extend(common::call_asm(SINK, 5));
+    proxy.push(0x00);
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, PROXY, proxy, U256::from(100));
+    common::deploy(&mut db, IMPLEMENTATION, vec![0x60, 0x00, 0x60, 0x00, 0xfd]);
+    common::deploy(&mut db, SINK, vec![0x00]);
+    db
+}
+
+fn trace(attribution: DelegateCallAttribution) -> HelloWorldInspector {
+    let mut db = setup();
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        delegatecall_attribution: attribution,
+        ..Default::default()
+    });
+    common::run(&mut db, common::call_env(PROXY, vec![]), &mut inspector);
+    inspector
+}
+
+#[test]
+fn test_delegatecalls_attributed_to_code_address() {
+    let inspector = trace(DelegateCallAttribution::CodeAddress);
+
+    let implementation = inspector.stats_for(IMPLEMENTATION).expect("implementation stats");
+    assert_eq!(implementation.calls, 2);
+    assert_eq!(implementation.reverts, 2);
+    assert!(implementation.gas_used > 0);
+
+    let proxy = inspector.stats_for(PROXY).expect("proxy stats");
+    assert_eq!((proxy.calls, proxy.reverts), (1, 0));
+
+    let sink = inspector.stats_for(SINK).expect("sink stats");
+    assert_eq!(sink.calls, 1);
+    assert_eq!(sink.value_in, U256::from(5));
+
+    let busiest: Vec<Address> = inspector
+        .busiest_addresses(2)
+        .into_iter()
+        .map(|(addr, _)| addr)
+        .collect();
+    assert_eq!(busiest, vec![IMPLEMENTATION, PROXY]);
+}
+
+#[test]
+fn test_delegatecalls_attributed_to_storage_address() {
+    let inspector = trace(DelegateCallAttribution::StorageAddress);
+
+    assert!(inspector.stats_for(IMPLEMENTATION).is_none());
+    let proxy = inspector.stats_for(PROXY).expect("proxy stats");
+    assert_eq!((proxy.calls, proxy.reverts), (3, 2));
+    assert_eq!(inspector.busiest_addresses(10).len(), 2);
+}
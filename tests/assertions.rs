@@ -0,0 +1,95 @@
+//! Integration tests for assertions on a traced transaction.
+
+mod common;
+
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::{Revert, SolError};
+use restd::{
+    evm::build_inspected_evm, state_diff, HelloWorldInspector, HelloWorldInspectorConfig,
+    InspectorReport, OutputMode, StateDiff, TraceAssertions,
+};
+use revm::InMemoryDB;
+
+const ROUTER: Address = Address::repeat_byte(0x50);
+const POOL: Address = Address::repeat_byte(0x51);
+const GUARD: Address = Address::repeat_byte(0x52);
+const TRANSFER: B256 = B256::repeat_byte(0xdd);
+
+/// Traces a call to a router that calls a pool, which writes slot 0 and
+/// emits [`TRANSFER`], and a guard, which reverts with "not owner".
+fn trace() -> (InspectorReport, StateDiff) {
+    let mut router = common::call_asm(POOL, 0);
+    router.extend(common::call_asm(GUARD, 0));
+    let mut pool = vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x7f]; // SSTORE(0, 1), PUSH32
+    pool.extend_from_slice(TRANSFER.as_slice());
+    pool.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xa1, 0x00]); // LOG1(0, 0, TRANSFER)
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, ROUTER, router);
+    common::deploy(&mut db, POOL, pool);
+    common::deploy(&mut db, GUARD, common::revert_asm(&Revert::from("not owner").abi_encode()));
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let env = common::call_env(ROUTER, Vec::new());
+    let result = build_inspected_evm(&mut db, env, &mut inspector).transact().unwrap();
+    let diff = state_diff::compute(&result, &db).unwrap();
+    (inspector.report(), diff)
+}
+
+/// An address one hex digit away from [`POOL`].
+fn near_pool() -> Address {
+    let mut address = POOL;
+    address.0[19] = 0x50;
+    address
+}
+
+#[test]
+fn test_passing_assertions() {
+    let (report, diff) = trace();
+    TraceAssertions::new(&report, &diff)
+        .assert_called(ROUTER)
+        .assert_called(POOL)
+        .assert_call_count(GUARD, 1)
+        .assert_not_called(near_pool())
+        .assert_reverted_with("not owner")
+        .assert_emitted(TRANSFER)
+        .assert_storage_changed(POOL, U256::ZERO);
+}
+
+#[test]
+fn test_failures_name_closest_matches() {
+    let (report, diff) = trace();
+    let assertions = TraceAssertions::new(&report, &diff);
+
+    let err = assertions.check_called(near_pool()).unwrap_err();
+    assert_eq!(err.expected, format!("a call to {}", near_pool()));
+    let closest = format!("it was not called; the closest calls were to {POOL} (1 call)");
+    assert!(err.found.starts_with(&closest), "{err}");
+
+    let err = assertions.check_call_count(POOL, 2).unwrap_err();
+    assert_eq!(err.to_string(), format!("expected 2 calls to {POOL}, but it was called once"));
+
+    let err = assertions.check_not_called(GUARD).unwrap_err();
+    assert_eq!(err.found, format!("it was called once, first by {ROUTER} at depth 1"));
+
+    let err = assertions.check_reverted_with("not the owner").unwrap_err();
+    assert_eq!(err.found, format!("the closest revert reasons were \"not owner\" in {GUARD}"));
+
+    let err = assertions.check_emitted(B256::repeat_byte(0xde)).unwrap_err();
+    assert!(err.found.contains(&format!("{TRANSFER} by {POOL}")), "{err}");
+
+    let err = assertions.check_storage_changed(POOL, U256::from(1)).unwrap_err();
+    assert_eq!(err.found, format!("{POOL} only changed slots 0x0"));
+
+    let err = assertions.check_storage_changed(near_pool(), U256::ZERO).unwrap_err();
+    assert!(err.found.ends_with(&format!("the closest accounts that did were {POOL}")), "{err}");
+}
+
+#[test]
+#[should_panic(expected = "the closest calls were to")]
+fn test_assert_panics_with_message() {
+    let (report, diff) = trace();
+    TraceAssertions::new(&report, &diff).assert_called(near_pool());
+}
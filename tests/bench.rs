@@ -0,0 +1,88 @@
+//! Integration tests for comparing the gas of two transactions.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    bench::{self, Side},
+    GasCategory, HelloWorldInspectorConfig, OutputMode,
+};
+use revm::InMemoryDB;
+
+const WITH_SSTORE: Address = Address::repeat_byte(0x5a);
+const WITH_MSTORE: Address = Address::repeat_byte(0x3a);
+const BRANCHING: Address = Address::repeat_byte(0xb0);
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    // SSTORE(0, 1)
+    common::deploy(&mut db, WITH_SSTORE, vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+    // MSTORE(0, 1)
+    common::deploy(&mut db, WITH_MSTORE, vec![0x60, 0x01, 0x60, 0x00, 0x52, 0x00]);
+    // SSTORE(0, 1) only with calldata: CALLDATASIZE ISZERO PUSH1 10 JUMPI
+    // SSTORE(0, 1) JUMPDEST STOP
+    common::deploy(
+        &mut db,
+        BRANCHING,
+        vec![0x36, 0x15, 0x60, 0x0a, 0x57, 0x60, 0x01, 0x60, 0x00, 0x55, 0x5b, 0x00],
+    );
+    db
+}
+
+fn silent() -> HelloWorldInspectorConfig {
+    HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_sstore_against_mstore() {
+    let comparison = bench::compare(
+        db,
+        common::call_env(WITH_SSTORE, vec![]),
+        common::call_env(WITH_MSTORE, vec![]),
+        silent(),
+    )
+    .unwrap();
+
+    let storage = comparison.by_category[&GasCategory::Storage];
+    assert_eq!(storage.b, 0);
+    assert!(storage.delta < 0);
+    assert!(comparison.by_category[&GasCategory::Memory].delta > 0);
+    assert!(comparison.total.delta < 0);
+    assert_eq!(comparison.storage_writes.larger(), Some(Side::A));
+    assert_eq!(comparison.logs.larger(), None);
+    assert!(comparison.success_a && comparison.success_b);
+
+    // Different targets do not align
+    assert_eq!(comparison.frames.len(), 2);
+    assert!(comparison.frames.iter().all(|frame| frame.delta.is_none()));
+
+    let table = comparison.to_string();
+    assert!(table.contains("storage"), "{table}");
+    assert!(table.contains("total gas"), "{table}");
+
+    let json = serde_json::to_value(&comparison).unwrap();
+    assert_eq!(json["storage_writes"]["delta"], -1);
+    assert_eq!(json["by_category"]["storage"]["b"], 0);
+}
+
+#[test]
+fn test_matching_frames_are_aligned() {
+    let comparison = bench::compare(
+        db,
+        common::call_env(BRANCHING, vec![0x01]),
+        common::call_env(BRANCHING, vec![]),
+        silent(),
+    )
+    .unwrap();
+
+    assert_eq!(comparison.frames.len(), 1);
+    let frame = &comparison.frames[0];
+    assert_eq!(frame.target, BRANCHING);
+    assert_eq!(frame.kind, "CALL");
+    assert_eq!(frame.function, "fallback");
+    assert!(frame.delta.unwrap() < 0);
+    assert_eq!(comparison.storage_writes.delta, -1);
+}
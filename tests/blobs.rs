@@ -0,0 +1,114 @@
+//! Integration tests for tracing EIP-4844 blob transactions.
+
+mod common;
+
+use alloy_primitives::{Address, B256, U256};
+use restd::{
+    BlobOpcode, BlobTransaction, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+};
+use revm::{primitives::Env, InMemoryDB};
+
+const CONTRACT: Address = Address::repeat_byte(0x80);
+
+/// Reads the blob hashes at indices 0, 1 and 2, then the blob base fee.
+fn blob_reader() -> Vec<u8> {
+    vec![
+        0x60, 0x00, 0x49, 0x50, // BLOBHASH(0), POP
+        0x60, 0x01, 0x49, 0x50, // BLOBHASH(1), POP
+        0x60, 0x02, 0x49, 0x50, // BLOBHASH(2), POP
+        0x4a, 0x50, 0x00, // BLOBBASEFEE, POP, STOP
+    ]
+}
+
+/// Versioned hashes start with the KZG version byte.
+fn versioned_hash(byte: u8) -> B256 {
+    let mut hash = B256::repeat_byte(byte);
+    hash[0] = 0x01;
+    hash
+}
+
+fn blob_env(blob_hashes: Vec<B256>) -> Env {
+    let mut env = common::call_env(CONTRACT, Vec::new());
+    env.tx.max_fee_per_blob_gas = Some(U256::from(7));
+    env.tx.blob_hashes = blob_hashes;
+    env
+}
+
+fn trace(env: Env) -> HelloWorldInspector {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, blob_reader());
+    // The sender pays for the blob gas up front
+    common::deploy_with_balance(
+        &mut db,
+        common::CALLER,
+        Vec::new(),
+        U256::from(10u64.pow(18)),
+    );
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, env, &mut inspector);
+    assert!(result.is_success(), "{result:?}");
+    inspector
+}
+
+#[test]
+fn test_blob_hashes_and_opcodes_are_recorded() {
+    let hashes = vec![versioned_hash(0xaa), versioned_hash(0xbb)];
+    let inspector = trace(blob_env(hashes.clone()));
+
+    assert_eq!(
+        inspector.blob_transactions(),
+        [BlobTransaction {
+            blob_hashes: hashes,
+            max_fee_per_blob_gas: Some(U256::from(7)),
+            tx_index: 0,
+        }]
+    );
+    let report = inspector.report();
+    assert_eq!(report.blob_opcode_count(BlobOpcode::BlobHash), 3);
+    assert_eq!(report.blob_opcode_count(BlobOpcode::BlobBaseFee), 1);
+
+    let uses = inspector.blob_opcodes();
+    let indices: Vec<_> = uses.iter().map(|used| used.index).collect();
+    assert_eq!(
+        indices,
+        [
+            Some(U256::ZERO),
+            Some(U256::from(1)),
+            Some(U256::from(2)),
+            None
+        ]
+    );
+    assert_eq!(uses[0].address, CONTRACT);
+    assert_eq!(uses[0].pc, 2);
+    assert_eq!(uses[3].pc, 12);
+
+    // Only the third read is past the two blob hashes
+    let flagged: Vec<_> = report.out_of_range_blob_hashes().collect();
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].index, Some(U256::from(2)));
+    assert_eq!(flagged[0].pc, 10);
+}
+
+#[test]
+fn test_gas_summary_includes_blobs() {
+    let inspector = trace(blob_env(vec![versioned_hash(0xaa), versioned_hash(0xbb)]));
+    let summary = inspector.gas_summary().unwrap();
+    assert_eq!(summary.blob_count, 2);
+    assert_eq!(summary.max_fee_per_blob_gas, Some(U256::from(7)));
+}
+
+#[test]
+fn test_every_blobhash_is_out_of_range_without_blobs() {
+    let inspector = trace(common::call_env(CONTRACT, Vec::new()));
+    assert!(inspector.blob_transactions().is_empty());
+    let report = inspector.report();
+    assert_eq!(report.out_of_range_blob_hashes().count(), 3);
+    let summary = report.gas_summary.unwrap();
+    assert_eq!(summary.blob_count, 0);
+    assert_eq!(summary.max_fee_per_blob_gas, None);
+    // Nothing about blobs is serialized for other transactions
+    assert!(!serde_json::to_string(&summary).unwrap().contains("blob"));
+}
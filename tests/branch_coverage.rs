@@ -0,0 +1,116 @@
+//! Integration tests for `JUMPI` branch coverage.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{BranchCoverage, CoverageMap, HelloWorldInspector, InspectorReport, OutputMode};
+use revm::InMemoryDB;
+
+const BRANCHY: Address = Address::repeat_byte(0x3b);
+/// Program counter of the `JUMPI` in the contract
+const JUMPI_PC: usize = 5;
+
+/// Jumps over a `STOP` if the first calldata word is not zero.
+fn db() -> InMemoryDB {
+    // PUSH1 0, CALLDATALOAD, PUSH1 7, JUMPI, STOP, JUMPDEST, STOP
+    let code = vec![0x60, 0x00, 0x35, 0x60, 0x07, 0x57, 0x00, 0x5b, 0x00];
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, BRANCHY, code);
+    db
+}
+
+fn calldata(word: u64) -> Vec<u8> {
+    U256::from(word).to_be_bytes::<32>().to_vec()
+}
+
+/// Runs the contract with the first calldata word set to each of `words`.
+fn run(words: &[u64]) -> InspectorReport {
+    let mut db = db();
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .branch_coverage(true)
+        .build();
+    for word in words {
+        common::run(
+            &mut db,
+            common::call_env(BRANCHY, calldata(*word)),
+            &mut inspector,
+        );
+    }
+    inspector.report()
+}
+
+#[test]
+fn test_both_branch_directions_are_recorded() {
+    let report = run(&[0, 1]);
+    let coverage = report.coverage();
+    assert_eq!(coverage.len(), 1);
+    assert_eq!(
+        coverage.get(BRANCHY, JUMPI_PC),
+        Some(BranchCoverage {
+            taken: 1,
+            not_taken: 1,
+        })
+    );
+    assert_eq!(coverage.covered(), 1);
+    assert_eq!(coverage.get(BRANCHY, 0), None);
+
+    let mut text = Vec::new();
+    coverage.to_text(&mut text).unwrap();
+    assert_eq!(
+        String::from_utf8(text).unwrap(),
+        format!("{BRANCHY:#x} {JUMPI_PC} 1 1\n")
+    );
+
+    let json = report.to_json().unwrap();
+    assert!(
+        json.contains(&format!(
+            r#""branch_coverage":[{{"address":"{BRANCHY:#x}","pc":5,"taken":1,"not_taken":1}}]"#
+        )),
+        "{json}"
+    );
+    let parsed: InspectorReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.coverage(), coverage);
+
+    // Off by default
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .build();
+    common::run(
+        &mut db(),
+        common::call_env(BRANCHY, calldata(1)),
+        &mut inspector,
+    );
+    assert!(inspector.coverage().is_empty());
+    assert!(!inspector.report_json().unwrap().contains("branch_coverage"));
+}
+
+#[test]
+fn test_coverage_accumulates_across_runs() {
+    let mut campaign = CoverageMap::new();
+    for word in [3, 0, 7] {
+        campaign.merge(run(&[word]).coverage());
+    }
+    assert_eq!(
+        campaign.get(BRANCHY, JUMPI_PC),
+        Some(BranchCoverage {
+            taken: 2,
+            not_taken: 1,
+        })
+    );
+
+    // Merging reports merges their coverage
+    let mut report = run(&[0]);
+    assert!(!report
+        .coverage()
+        .get(BRANCHY, JUMPI_PC)
+        .unwrap()
+        .is_covered());
+    report.merge(run(&[9, 9]));
+    assert_eq!(report.coverage().get(BRANCHY, JUMPI_PC).unwrap().taken, 2);
+    assert!(report
+        .coverage()
+        .get(BRANCHY, JUMPI_PC)
+        .unwrap()
+        .is_covered());
+}
@@ -0,0 +1,113 @@
+//! Integration tests for breakpoints on addresses, program counters and opcodes.
+
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use alloy_primitives::{Address, U256};
+use restd::{BreakAction, BreakpointHit, BreakpointInspector, Breakpoints, InterpSnapshot};
+use revm::{interpreter::opcode, primitives::ExecutionResult, InMemoryDB};
+
+type Hits = Rc<RefCell<Vec<(BreakpointHit, InterpSnapshot)>>>;
+
+/// Creates an inspector that records every hit and aborts on those matching `abort`.
+fn recording_inspector(
+    breakpoints: Breakpoints,
+    abort: impl Fn(&BreakpointHit) -> bool + 'static,
+) -> (BreakpointInspector, Hits) {
+    let hits = Hits::default();
+    let recorded = hits.clone();
+    let inspector = BreakpointInspector::new(breakpoints, move |hit, snapshot| {
+        recorded.borrow_mut().push((hit, snapshot.clone()));
+        if abort(&hit) {
+            BreakAction::Abort
+        } else {
+            BreakAction::Continue
+        }
+    });
+    (inspector, hits)
+}
+
+#[test]
+fn test_abort_on_pc_reverts_transaction() {
+    let contract = Address::repeat_byte(0x20);
+    // PUSH1 2, PUSH1 3, ADD, PUSH1 0, SSTORE, STOP
+    let code = vec![0x60, 0x02, 0x60, 0x03, 0x01, 0x60, 0x00, 0x55, 0x00];
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, code);
+
+    let (mut inspector, hits) =
+        recording_inspector(Breakpoints::new().on_pc(contract, 4), |_| true);
+    let result = common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+
+    match result {
+        ExecutionResult::Revert { gas_used, output } => {
+            assert!(output.is_empty());
+            // Only the intrinsic gas and the two pushes were charged
+            assert_eq!(gas_used, 21_000 + 6);
+        }
+        other => panic!("expected a revert, got {other:?}"),
+    }
+    let hits = hits.borrow();
+    assert_eq!(hits.len(), 1);
+    let (hit, snapshot) = &hits[0];
+    assert_eq!(*hit, BreakpointHit::Pc { address: contract, pc: 4 });
+    assert_eq!(snapshot.address, contract);
+    assert_eq!(snapshot.pc, 4);
+    assert_eq!(snapshot.opcode, opcode::ADD);
+    assert_eq!(snapshot.stack_top, vec![U256::from(3), U256::from(2)]);
+    assert_eq!(snapshot.depth, 1);
+}
+
+#[test]
+fn test_abort_on_address_fails_only_that_call() {
+    let outer = Address::repeat_byte(0x20);
+    let callee = Address::repeat_byte(0x21);
+
+    // CALL(gas, callee, 0, 0, 0, 0, 0), then return the success flag
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+    code.extend_from_slice(callee.as_slice());
+    code.extend_from_slice(&[0x5a, 0xf1, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, code);
+    // PUSH1 1, PUSH1 0, SSTORE, STOP
+    common::deploy(&mut db, callee, vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+
+    let breakpoints = Breakpoints::new()
+        .on_address(callee)
+        .on_opcode(opcode::SSTORE);
+    let (mut inspector, hits) =
+        recording_inspector(breakpoints, |hit| matches!(hit, BreakpointHit::Address(_)));
+    let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+
+    assert!(result.is_success());
+    assert_eq!(result.output().unwrap().as_ref(), [0u8; 32]);
+    // The callee never ran, so its SSTORE breakpoint was not reached
+    let hits = hits.borrow();
+    assert_eq!(hits.len(), 1);
+    let (hit, snapshot) = &hits[0];
+    assert_eq!(*hit, BreakpointHit::Address(callee));
+    assert_eq!(snapshot.pc, 0);
+    assert_eq!(snapshot.opcode, 0x60);
+    assert_eq!(snapshot.depth, 2);
+    assert!(snapshot.stack_top.is_empty());
+}
+
+#[test]
+fn test_continue_leaves_execution_unchanged() {
+    let contract = Address::repeat_byte(0x20);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, common::countdown_loop());
+
+    let (mut inspector, hits) =
+        recording_inspector(Breakpoints::new().on_opcode(opcode::SUB), |_| false);
+    let result = common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+
+    assert!(result.is_success());
+    assert_eq!(result.gas_used(), 21_000 + common::COUNTDOWN_LOOP_GAS);
+    assert_eq!(inspector.hits(), 3);
+    assert!(hits
+        .borrow()
+        .iter()
+        .all(|(hit, _)| *hit == BreakpointHit::Opcode(opcode::SUB)));
+}
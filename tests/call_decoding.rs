@@ -0,0 +1,129 @@
+//! Integration tests for decoding call arguments with function signatures.
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_primitives::{keccak256, Address, U256};
+use alloy_sol_types::SolValue;
+use restd::{
+    CallTraceInspector, FnDecoder, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    PrettyPrintOptions,
+};
+use revm::InMemoryDB;
+
+const TARGET: Address = Address::repeat_byte(0x20);
+
+const SUBMIT: &str = "submit(string memo, uint256[] amounts)";
+
+fn decoder() -> Arc<FnDecoder> {
+    Arc::new(FnDecoder::new().with_signatures([SUBMIT]).unwrap())
+}
+
+/// Calldata of `submit("hi", [1, 2, 3])`.
+fn submit_calldata() -> Vec<u8> {
+    let mut data = keccak256("submit(string,uint256[])")[..4].to_vec();
+    let amounts = vec![U256::from(1), U256::from(2), U256::from(3)];
+    data.extend(("hi".to_string(), amounts).abi_encode_params());
+    data
+}
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    // STOP
+    common::deploy(&mut db, TARGET, vec![0x00]);
+    db
+}
+
+fn inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+    .with_fn_decoder(decoder())
+}
+
+#[test]
+fn test_dynamic_arguments_are_decoded() {
+    let mut inspector = inspector();
+    let result = common::run(
+        &mut db(),
+        common::call_env(TARGET, submit_calldata()),
+        &mut inspector,
+    );
+    assert!(result.is_success());
+
+    let record = &inspector.gas_by_call()[0];
+    let decoded = record.decoded.as_ref().expect("submit should decode");
+    assert_eq!(decoded.function, "submit");
+    assert_eq!(decoded.signature(), "submit(string,uint256[])");
+    assert_eq!(decoded.args[0].name, "memo");
+    assert_eq!(decoded.args[0].ty, "string");
+    assert_eq!(decoded.args[0].value, "\"hi\"");
+    assert_eq!(decoded.args[1].value, "[1, 2, 3]");
+    assert_eq!(
+        decoded.to_string(),
+        "submit(memo: \"hi\", amounts: [1, 2, 3])"
+    );
+    assert_eq!(record.decode_error, None);
+
+    let json: serde_json::Value = serde_json::from_str(&inspector.report_json().unwrap()).unwrap();
+    let args = &json["calls"][0]["decoded"]["args"];
+    assert_eq!(json["calls"][0]["decoded"]["function"], "submit");
+    assert_eq!(args[1]["name"], "amounts");
+    assert_eq!(args[1]["type"], "uint256[]");
+    assert_eq!(args[1]["value"], "[1, 2, 3]");
+}
+
+#[test]
+fn test_truncated_calldata_keeps_tracing() {
+    let mut calldata = submit_calldata();
+    calldata.truncate(4 + 40);
+    let mut inspector = inspector();
+    let result = common::run(
+        &mut db(),
+        common::call_env(TARGET, calldata),
+        &mut inspector,
+    );
+    assert!(result.is_success());
+
+    let record = &inspector.gas_by_call()[0];
+    assert_eq!(record.decoded, None);
+    assert!(record.decode_error.is_some());
+    assert_eq!(inspector.calls(), 1);
+}
+
+#[test]
+fn test_unknown_selector_is_not_decoded() {
+    let mut inspector = inspector();
+    common::run(
+        &mut db(),
+        common::call_env(TARGET, vec![0xde, 0xad, 0xbe, 0xef]),
+        &mut inspector,
+    );
+
+    let record = &inspector.gas_by_call()[0];
+    assert_eq!(record.decoded, None);
+    assert_eq!(record.decode_error, None);
+}
+
+#[test]
+fn test_pretty_print_shows_decoded_arguments() {
+    let mut truncated = submit_calldata();
+    truncated.truncate(4 + 40);
+    for (calldata, expected) in [
+        (
+            submit_calldata(),
+            "::submit(memo: \"hi\", amounts: [1, 2, 3]) → success",
+        ),
+        (truncated, "(decode error:"),
+    ] {
+        let mut tracer = CallTraceInspector::new();
+        common::run(&mut db(), common::call_env(TARGET, calldata), &mut tracer);
+        let out = tracer.into_trace().pretty_print(PrettyPrintOptions {
+            fn_decoder: Some(decoder()),
+            ..Default::default()
+        });
+        assert!(out.contains(expected), "{out}");
+    }
+}
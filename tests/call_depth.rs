@@ -0,0 +1,59 @@
+//! Integration tests for call depth tracking.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+/// Calls itself with `calldata - 1` until the calldata word is zero.
+fn recursive_code() -> Vec<u8> {
+    vec![
+        0x60, 0x00, 0x35, // PUSH1 0, CALLDATALOAD
+        0x80, 0x15, 0x60, 0x1e, 0x57, // DUP1, ISZERO, PUSH1 end, JUMPI
+        0x60, 0x01, 0x90, 0x03, // PUSH1 1, SWAP1, SUB
+        0x60, 0x00, 0x52, // PUSH1 0, MSTORE
+        // CALL(gas, address, 0, 0, 32, 0, 0), POP, STOP
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x20, 0x60, 0x00, 0x60, 0x00, 0x30, 0x5a, 0xf1, 0x50, 0x00,
+        0x5b, 0x00, // end: JUMPDEST, STOP
+    ]
+}
+
+#[test]
+fn test_max_depth_of_recursive_contract() {
+    let target = Address::repeat_byte(0x20);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, target, recursive_code());
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let calldata = U256::from(2).to_be_bytes::<32>().to_vec();
+    let result = common::run(&mut db, common::call_env(target, calldata), &mut inspector);
+    assert!(result.is_success());
+
+    assert_eq!(inspector.call_count, 3);
+    assert_eq!(inspector.max_depth(), 3);
+    assert_eq!(inspector.current_depth(), 0);
+}
+
+#[test]
+fn test_depth_is_balanced_for_filtered_frames() {
+    let target = Address::repeat_byte(0x20);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, target, recursive_code());
+
+    let mut config = HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    };
+    config.filter.exclude.push(target);
+    let mut inspector = HelloWorldInspector::with_config(config);
+    let calldata = U256::from(1).to_be_bytes::<32>().to_vec();
+    common::run(&mut db, common::call_env(target, calldata), &mut inspector);
+
+    assert_eq!(inspector.call_count, 0);
+    assert_eq!(inspector.max_depth(), 2);
+    assert_eq!(inspector.current_depth(), 0);
+}
@@ -0,0 +1,71 @@
+//! Integration tests for per-call gas accounting.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_gas_by_call_reports_loop_gas() {
+    let looper = Address::repeat_byte(0x10);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, looper, common::countdown_loop());
+
+    let mut inspector = silent_inspector();
+    let env = common::call_env(looper, vec![]);
+    let tx_gas_limit = env.tx.gas_limit;
+    common::run(&mut db, env, &mut inspector);
+
+    let records = inspector.gas_by_call();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].target, looper);
+    assert_eq!(records[0].gas_limit, tx_gas_limit - 21_000);
+    assert_eq!(records[0].gas_used, common::COUNTDOWN_LOOP_GAS);
+    assert!(!records[0].reverted);
+}
+
+#[test]
+fn test_gas_by_call_nested_and_out_of_gas() {
+    let caller = Address::repeat_byte(0x20);
+    let looper = Address::repeat_byte(0x10);
+
+    // Call the loop with plenty of gas, then with too little to finish it.
+    let mut code = common::call_asm_with_gas(looper, 10_000);
+    code.extend(common::call_asm_with_gas(looper, 50));
+    code.push(0x00);
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, caller, code);
+    common::deploy(&mut db, looper, common::countdown_loop());
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(caller, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let records = inspector.gas_by_call();
+    assert_eq!(records.len(), 3);
+
+    let (outer, full, starved) = (&records[0], &records[1], &records[2]);
+    assert_eq!(outer.target, caller);
+    assert!(!outer.reverted);
+
+    assert_eq!(full.target, looper);
+    assert_eq!(full.gas_limit, 10_000);
+    assert_eq!(full.gas_used, common::COUNTDOWN_LOOP_GAS);
+    assert!(!full.reverted);
+
+    assert_eq!(starved.target, looper);
+    assert_eq!(starved.gas_limit, 50);
+    assert_eq!(starved.gas_used, starved.gas_limit);
+    assert!(starved.reverted);
+
+    assert!(outer.gas_used > full.gas_used + starved.gas_used);
+}
@@ -0,0 +1,134 @@
+//! Integration tests for chained queries over the call records of a report.
+
+use alloy_primitives::{Address, U256};
+use restd::{CallGasRecord, InspectorReport};
+use serde_json::json;
+
+const ROUTER: Address = Address::repeat_byte(0x90);
+const POOL: Address = Address::repeat_byte(0x91);
+const TOKEN: Address = Address::repeat_byte(0x92);
+const EOA: Address = Address::repeat_byte(0x93);
+const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+fn call(
+    caller: Address,
+    target: Address,
+    depth: u64,
+    gas_used: u64,
+    reverted: bool,
+    value: u64,
+) -> CallGasRecord {
+    serde_json::from_value(json!({
+        "target": target,
+        "caller": caller,
+        "depth": depth,
+        "kind": "CALL",
+        "value": U256::from(value),
+        "gas_limit": 1_000_000,
+        "gas_used": gas_used,
+        "reverted": reverted,
+    }))
+    .unwrap()
+}
+
+/// Twenty calls: the router calls the pool five times, each pool call
+/// transfers tokens twice, and the router pays five EOAs.
+fn report() -> InspectorReport {
+    let mut calls = Vec::new();
+    for round in 0..5u64 {
+        let failed = round == 3;
+        calls.push(call(ROUTER, POOL, 1, 40_000 + round * 1_000, failed, 0));
+        for _ in 0..2 {
+            let mut transfer = call(POOL, TOKEN, 2, 12_000, failed, 0);
+            transfer.selector = Some(TRANSFER.into());
+            calls.push(transfer);
+        }
+        calls.push(call(ROUTER, EOA, 1, 9_000, false, round));
+    }
+    assert_eq!(calls.len(), 20);
+    InspectorReport {
+        calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_chained_filters() {
+    let report = report();
+    assert_eq!(report.calls().count(), 20);
+
+    let expensive: Vec<_> = report
+        .calls()
+        .filter_target(POOL)
+        .filter_min_gas(42_000)
+        .collect();
+    assert_eq!(
+        expensive
+            .iter()
+            .map(|call| call.gas_used)
+            .collect::<Vec<_>>(),
+        [42_000, 43_000, 44_000]
+    );
+    assert!(std::ptr::eq(expensive[0], &report.calls[8]));
+
+    let failed = report
+        .calls()
+        .filter_target(POOL)
+        .failed_only()
+        .collect::<Vec<_>>();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].gas_used, 43_000);
+    assert_eq!(report.calls().failed_only().count(), 3);
+    assert_eq!(report.calls().succeeded_only().count(), 17);
+
+    assert_eq!(
+        report
+            .calls()
+            .filter_caller(POOL)
+            .filter_selector(TRANSFER)
+            .succeeded_only()
+            .count(),
+        8
+    );
+    assert_eq!(report.calls().filter_selector([0; 4]).count(), 0);
+    assert_eq!(report.calls().filter_depth(2..).count(), 10);
+    assert_eq!(
+        report
+            .calls()
+            .filter_depth(..=1)
+            .filter_gas(..10_000)
+            .count(),
+        5
+    );
+    // Round 0 pays nothing
+    assert_eq!(report.calls().with_value().filter_target(EOA).count(), 4);
+}
+
+#[test]
+fn test_aggregates() {
+    let report = report();
+    assert_eq!(report.calls().filter_target(TOKEN).sum_gas(), 10 * 12_000);
+    assert_eq!(
+        report.calls().filter_target(POOL).sum_gas(),
+        40_000 + 41_000 + 42_000 + 43_000 + 44_000
+    );
+    assert_eq!(report.calls().filter_target(ROUTER).sum_gas(), 0);
+
+    let groups = report.calls().filter_depth(1..=1).group_by_target();
+    assert_eq!(groups.keys().copied().collect::<Vec<_>>(), [POOL, EOA]);
+    assert_eq!(groups[&POOL].len(), 5);
+    assert_eq!(
+        groups[&EOA]
+            .iter()
+            .map(|call| call.value)
+            .collect::<Vec<_>>(),
+        (0..5u64).map(U256::from).collect::<Vec<_>>()
+    );
+
+    // Any predicate composes with the built-in filters
+    let large_transfers = report
+        .calls()
+        .filter_by(|call| call.value > U256::from(2))
+        .sum_gas();
+    assert_eq!(large_transfers, 2 * 9_000);
+}
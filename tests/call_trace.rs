@@ -0,0 +1,72 @@
+//! Integration tests for the call tree recorded by `CallTraceInspector`.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{CallKind, CallTraceInspector};
+use revm::InMemoryDB;
+
+#[test]
+fn test_call_tree_records_nested_reverted_and_create_frames() {
+    let a = Address::repeat_byte(0xaa);
+    let b = Address::repeat_byte(0xbb);
+    let c = Address::repeat_byte(0xcc);
+    let d = Address::repeat_byte(0xdd);
+
+    // A: CALL B, CALL C, CREATE(empty runtime), STOP
+    let mut code_a = common::call_asm(b, 0);
+    code_a.extend(common::call_asm(c, 0));
+    // PUSH5 <PUSH1 0 PUSH1 0 RETURN>, PUSH1 0, MSTORE, CREATE(0, 27, 5), POP
+    code_a.extend_from_slice(&[0x64, 0x60, 0x00, 0x60, 0x00, 0xf3, 0x60, 0x00, 0x52]);
+    code_a.extend_from_slice(&[0x60, 0x05, 0x60, 0x1b, 0x60, 0x00, 0xf0, 0x50, 0x00]);
+    // B: CALL D, STOP
+    let mut code_b = common::call_asm(d, 0);
+    code_b.push(0x00);
+    // C: REVERT(0, 0)
+    let code_c = vec![0x60, 0x00, 0x60, 0x00, 0xfd];
+    // D: STOP
+    let code_d = vec![0x00];
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, a, code_a);
+    common::deploy(&mut db, b, code_b);
+    common::deploy(&mut db, c, code_c);
+    common::deploy(&mut db, d, code_d);
+
+    let mut inspector = CallTraceInspector::new();
+    let result = common::run(&mut db, common::call_env(a, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let trace = inspector.into_trace();
+    assert!(trace.max_depth().unwrap() >= 2);
+
+    let root = trace.root().expect("root frame");
+    assert_eq!(root.target, a);
+    assert_eq!(root.caller, common::CALLER);
+    assert_eq!(root.depth, 0);
+    assert!(root.success);
+    assert_eq!(root.children.len(), 3);
+
+    let call_b = &root.children[0];
+    assert_eq!((call_b.kind, call_b.caller, call_b.target), (CallKind::Call, a, b));
+    assert_eq!(call_b.depth, 1);
+    assert!(call_b.success);
+    assert_eq!(call_b.children.len(), 1);
+    let call_d = &call_b.children[0];
+    assert_eq!((call_d.caller, call_d.target, call_d.depth), (b, d, 2));
+
+    let call_c = &root.children[1];
+    assert_eq!(call_c.target, c);
+    assert!(!call_c.success);
+    assert!(call_c.children.is_empty());
+
+    let create = &root.children[2];
+    assert_eq!(create.kind, CallKind::Create);
+    assert_eq!(create.caller, a);
+    assert_ne!(create.target, Address::ZERO);
+    assert!(create.success);
+    assert_eq!(create.input.len(), 5);
+
+    assert!(root.gas_used >= call_b.gas_used + call_c.gas_used + create.gas_used);
+    assert_eq!(trace.nodes().count(), 5);
+}
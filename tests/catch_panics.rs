@@ -0,0 +1,113 @@
+//! Integration tests for catching panics in callbacks and recording.
+
+mod common;
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, TraceEvent, TraceSink};
+use revm::InMemoryDB;
+
+const TARGET: Address = Address::repeat_byte(0x20);
+
+/// Steps executed by [`common::countdown_loop`]
+const LOOP_STEPS: u64 = 1 + 3 * 7 + 1;
+
+fn inspector(catch_panics: bool) -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        catch_panics,
+        ..Default::default()
+    })
+}
+
+/// Adds a step callback that panics on its 5th call, returning its call count.
+fn panic_on_fifth_step(inspector: HelloWorldInspector) -> (HelloWorldInspector, Arc<AtomicU64>) {
+    let calls = Arc::new(AtomicU64::new(0));
+    let counter = calls.clone();
+    let inspector = inspector.on_step(move |_| {
+        if counter.fetch_add(1, Ordering::Relaxed) + 1 == 5 {
+            panic!("step callback failed");
+        }
+    });
+    (inspector, calls)
+}
+
+fn run_loop(inspector: &mut HelloWorldInspector) -> bool {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, TARGET, common::countdown_loop());
+    common::run(&mut db, common::call_env(TARGET, vec![]), inspector).is_success()
+}
+
+#[test]
+fn test_panicking_callback_is_reported_and_disabled() {
+    let (inspector, calls) = panic_on_fifth_step(inspector(true));
+    let after = Arc::new(AtomicU64::new(0));
+    let counter = after.clone();
+    let mut inspector = inspector.on_step(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    assert!(run_loop(&mut inspector));
+    assert_eq!(inspector.steps(), LOOP_STEPS);
+    assert_eq!(calls.load(Ordering::Relaxed), 5);
+    // Later callbacks of the same hook are unaffected
+    assert_eq!(after.load(Ordering::Relaxed), LOOP_STEPS);
+
+    let errors = &inspector.report().internal_errors;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].source, "on_step callback #0");
+    assert_eq!(errors[0].message, "step callback failed");
+    assert_eq!(errors[0].tx_index, 0);
+}
+
+#[test]
+fn test_panicking_callback_is_enabled_again_for_next_transaction() {
+    let (mut inspector, calls) = panic_on_fifth_step(inspector(true));
+    assert!(run_loop(&mut inspector));
+    assert!(run_loop(&mut inspector));
+
+    assert_eq!(calls.load(Ordering::Relaxed), 5 + LOOP_STEPS);
+    assert_eq!(inspector.internal_errors().len(), 1);
+}
+
+#[test]
+fn test_panics_unwind_without_catch_panics() {
+    let (mut inspector, _) = panic_on_fifth_step(inspector(false));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| run_loop(&mut inspector)));
+    assert!(result.is_err());
+}
+
+/// Panics on the third step event.
+#[derive(Debug, Default)]
+struct PanickingSink {
+    steps: u64,
+}
+
+impl TraceSink for PanickingSink {
+    fn send(&mut self, event: TraceEvent) -> bool {
+        if let TraceEvent::Step { .. } = event {
+            self.steps += 1;
+            assert_ne!(self.steps, 3, "sink failed");
+        }
+        true
+    }
+}
+
+#[test]
+fn test_panic_in_recording_is_reported() {
+    let mut inspector = inspector(true).with_sink(PanickingSink::default());
+    assert!(run_loop(&mut inspector));
+
+    let errors = inspector.internal_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].source, "step");
+    assert!(errors[0].message.contains("sink failed"));
+    assert!(inspector.report().calls[0].gas_used > 0);
+}
@@ -0,0 +1,37 @@
+//! Compares the Chrome `trace_event` export against a checked-in golden file.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{CallTraceInspector, StaticSelectorResolver};
+use revm::InMemoryDB;
+
+const GOLDEN: &str = include_str!("fixtures/chrome_trace.json");
+
+#[test]
+fn test_chrome_trace_matches_golden() {
+    let a = Address::repeat_byte(0xaa);
+    let b = Address::repeat_byte(0xbb);
+
+    // A: CALL B, STOP
+    let mut code_a = common::call_asm(b, 0);
+    code_a.push(0x00);
+    // B: JUMPDEST, JUMPDEST, STOP
+    let code_b = vec![0x5b, 0x5b, 0x00];
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, a, code_a);
+    common::deploy(&mut db, b, code_b);
+
+    let mut inspector = CallTraceInspector::new();
+    let calldata = vec![0x12, 0x34, 0x56, 0x78];
+    common::run(&mut db, common::call_env(a, calldata), &mut inspector);
+
+    let mut resolver = StaticSelectorResolver::default();
+    resolver.insert([0x12, 0x34, 0x56, 0x78], "run()");
+    let trace = inspector.into_trace().to_chrome_trace_with_resolver(&resolver);
+
+    let actual: serde_json::Value = serde_json::from_str(&trace).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(GOLDEN).unwrap();
+    assert_eq!(actual, expected, "actual: {}", serde_json::to_string_pretty(&actual).unwrap());
+}
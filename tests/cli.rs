@@ -0,0 +1,115 @@
+//! Integration tests for the `restd-trace` binary.
+
+use assert_cmd::Command;
+use serde_json::Value;
+
+/// Stores 42 in memory and returns it.
+const RETURN_42: &str = "0x602a60005260206000f3";
+/// Adds the first calldata word to slot 0.
+const ADD_TO_SLOT: &str = "0x6000546000350160005500";
+/// Reverts with empty data.
+const REVERT: &str = "0x60006000fd";
+
+fn restd_trace() -> Command {
+    Command::cargo_bin("restd-trace").unwrap()
+}
+
+fn stdout_json(args: &[&str]) -> Value {
+    let output = restd_trace().args(args).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_formats() {
+    let output = restd_trace()
+        .args(["--code", RETURN_42, "--calldata", "0xdeadbeef"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let pretty = String::from_utf8(output.stdout).unwrap();
+    assert!(pretty.contains("CALL 0xC0C0c0c0C0C0c0c0c0C0c0C0C0C0C0C0C0C0c0c0"), "{pretty}");
+    assert!(pretty.contains("→ success"), "{pretty}");
+
+    let report = stdout_json(&["--code", RETURN_42, "--format", "json"]);
+    assert_eq!(report["step_count"], 6);
+    assert_eq!(report["call_count"], 1);
+
+    let geth = stdout_json(&["--code", RETURN_42, "--calldata", "0xdeadbeef", "--format", "geth"]);
+    assert_eq!(geth["type"], "CALL");
+    assert_eq!(geth["input"], "0xdeadbeef");
+    assert_eq!(geth["output"], format!("0x{:064x}", 42));
+}
+
+#[test]
+fn test_revert_exit_code() {
+    restd_trace().args(["--code", REVERT]).assert().code(1);
+    restd_trace().args(["--code", REVERT, "--allow-revert"]).assert().success();
+    restd_trace().args(["--code", RETURN_42, "--format", "yaml"]).assert().code(2);
+    restd_trace().args(["--calldata", "0x"]).assert().code(2);
+}
+
+#[test]
+fn test_prestate_and_out_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("report.json");
+    let caller = "0x1111111111111111111111111111111111111111";
+    let prestate = format!(
+        r#"{{"{caller}": {{"balance": "0xde0b6b3a7640000", "nonce": 3}},
+            "0xc0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0": {{"storage": {{"0x0": "0x7"}}}}}}"#
+    );
+    restd_trace()
+        .args(["--code", ADD_TO_SLOT, "--calldata", &format!("0x{:064x}", 9), "--value", "1000"])
+        .args(["--caller", caller, "--prestate", &prestate, "--format", "json"])
+        .arg("--out")
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout("");
+
+    let report: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(report["calls"][0]["caller"], caller);
+    assert_eq!(report["calls"][0]["value"], "0x3e8");
+    let accesses = &report["storage_accesses"];
+    assert_eq!(accesses[0]["kind"], "read");
+    assert_eq!(accesses[0]["value"], "0x7");
+    assert_eq!(accesses[1]["kind"], "write");
+    assert_eq!(accesses[1]["value"], "0x10");
+}
+
+#[test]
+fn test_create() {
+    // Init code returning RETURN_42 as runtime code
+    let init_code = "0x69602a60005260206000f3600052600a6016f3";
+    let geth = stdout_json(&["--create", init_code, "--format", "geth"]);
+    assert_eq!(geth["type"], "CREATE");
+    assert_eq!(geth["output"], RETURN_42);
+
+    restd_trace()
+        .args(["--create", init_code, "--calldata", "0x01"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_profile_and_config_file() {
+    // The minimal profile records nothing per step
+    let report = stdout_json(&["--code", RETURN_42, "--format", "json", "--profile", "minimal"]);
+    assert_eq!(report["step_count"], 6);
+    assert_eq!(report["opcode_counts"], serde_json::json!({}));
+    restd_trace()
+        .args(["--code", RETURN_42, "--profile", "fastest"])
+        .assert()
+        .code(2);
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("restd.toml");
+    std::fs::write(&config, "profile = \"security\"\nalert_on_opcodes = [\"MSTORE\"]\n").unwrap();
+    let config = config.to_str().unwrap();
+    let report = stdout_json(&["--code", RETURN_42, "--format", "json", "--config", config]);
+    assert_eq!(report["opcode_alerts"][0]["opcode"], 0x52);
+    restd_trace()
+        .args(["--code", RETURN_42, "--config", config, "--profile", "minimal"])
+        .assert()
+        .code(2);
+}
@@ -0,0 +1,103 @@
+//! Integration tests for the EIP-170 and EIP-3860 code size checks.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{CodeSizeKind, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{
+    primitives::{ExecutionResult, HaltReason, TxKind},
+    InMemoryDB,
+};
+
+fn silent() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+/// Assembles init code of `len` bytes that deploys `deployed` zero bytes:
+/// `RETURN(0, deployed)` padded with `STOP`s.
+fn init_code(len: usize, deployed: u16) -> Vec<u8> {
+    let [hi, lo] = deployed.to_be_bytes();
+    let mut code = vec![0x61, hi, lo, 0x60, 0x00, 0xf3];
+    code.resize(len, 0x00);
+    code
+}
+
+fn create_tx(init_code: Vec<u8>, inspector: &mut HelloWorldInspector) -> ExecutionResult {
+    let mut db = InMemoryDB::default();
+    let mut env = common::call_env(Address::ZERO, init_code);
+    env.tx.transact_to = TxKind::Create;
+    env.tx.gas_limit = 30_000_000;
+    common::run(&mut db, env, inspector)
+}
+
+#[test]
+fn test_init_code_near_limit_is_reported() {
+    let mut inspector = silent();
+    assert!(create_tx(init_code(44_236, 1), &mut inspector).is_success());
+    assert!(inspector.code_size_findings().is_empty());
+
+    let mut inspector = silent();
+    assert!(create_tx(init_code(44_237, 1), &mut inspector).is_success());
+    let findings = inspector.code_size_findings();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, CodeSizeKind::InitCode);
+    assert_eq!(findings[0].length, 44_237);
+    assert_eq!(findings[0].limit, 49_152);
+    assert_eq!(findings[0].address, Some(common::CALLER.create(0)));
+    assert!(!findings[0].exceeds_limit());
+}
+
+#[test]
+fn test_deployed_code_near_limit_is_reported() {
+    let mut inspector = silent();
+    assert!(create_tx(init_code(6, 22_118), &mut inspector).is_success());
+    assert!(inspector.code_size_findings().is_empty());
+
+    let mut inspector = silent();
+    assert!(create_tx(init_code(6, 22_119), &mut inspector).is_success());
+    let findings = inspector.report().code_size_findings;
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, CodeSizeKind::DeployedCode);
+    assert_eq!(findings[0].length, 22_119);
+    assert_eq!(findings[0].limit, 24_576);
+    assert_eq!(findings[0].address, Some(common::CALLER.create(0)));
+}
+
+#[test]
+fn test_deployed_code_over_limit_is_reported() {
+    let mut inspector = silent();
+    let result = create_tx(init_code(6, 24_577), &mut inspector);
+    assert!(matches!(
+        result,
+        ExecutionResult::Halt { reason: HaltReason::CreateContractSizeLimit, .. }
+    ));
+
+    let findings = inspector.code_size_findings();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].kind, CodeSizeKind::DeployedCode);
+    assert!(findings[0].exceeds_limit());
+    assert!(!inspector.creations()[0].success);
+}
+
+#[test]
+fn test_eof_prefixed_deployment_is_flagged() {
+    // MSTORE8(0, 0xEF), RETURN(0, 2): returns 0xEF00, the EOF magic
+    let code = vec![0x60, 0xef, 0x60, 0x00, 0x53, 0x60, 0x02, 0x60, 0x00, 0xf3];
+    let mut inspector = silent();
+    // EIP-3541 rejects the code, which revm reports as a size limit halt
+    assert!(!create_tx(code, &mut inspector).is_success());
+
+    let record = &inspector.creations()[0];
+    assert!(!record.success);
+    assert_eq!(record.deployed_code_prefix.as_ref(), [0xef, 0x00]);
+    assert!(record.is_eof());
+    assert_eq!(inspector.eof_deployments().count(), 1);
+
+    let mut inspector = silent();
+    create_tx(init_code(6, 3), &mut inspector);
+    assert_eq!(inspector.creations()[0].deployed_code_prefix.as_ref(), [0, 0, 0]);
+    assert_eq!(inspector.eof_deployments().count(), 0);
+}
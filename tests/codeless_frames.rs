@@ -0,0 +1,138 @@
+//! Integration tests for frames that run no code: calls to accounts without
+//! code and creations with empty init code.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{
+    CallKind, CallTraceInspector, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    PrettyPrintOptions,
+};
+use revm::{
+    interpreter::{CallScheme, CreateScheme},
+    primitives::{Env, TxKind},
+    InMemoryDB,
+};
+
+const EOA: Address = Address::repeat_byte(0xc0);
+const PAYER: Address = Address::repeat_byte(0xc1);
+
+/// 1.5 ETH in wei.
+const ONE_AND_A_HALF_ETH: u128 = 1_500_000_000_000_000_000;
+
+/// A funded sender, and PAYER, which sends 1 wei to EOA.
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    let balance = U256::from(10u128.pow(19));
+    common::deploy_with_balance(&mut db, common::CALLER, Vec::new(), balance);
+    let mut payer = common::call_asm(EOA, 1);
+    payer.push(0x00);
+    common::deploy_with_balance(&mut db, PAYER, payer, U256::from(1));
+    db
+}
+
+fn trace(env: Env) -> (HelloWorldInspector, CallTraceInspector) {
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db(), env.clone(), &mut inspector);
+    assert!(result.is_success(), "{result:?}");
+    let mut tracer = CallTraceInspector::new();
+    common::run(&mut db(), env, &mut tracer);
+    (inspector, tracer)
+}
+
+#[test]
+fn test_eoa_call_with_value_is_a_transfer() {
+    let mut env = common::call_env(EOA, Vec::new());
+    env.tx.value = U256::from(ONE_AND_A_HALF_ETH);
+    let (inspector, tracer) = trace(env);
+
+    assert!(inspector.gas_by_call()[0].no_code);
+    assert_eq!(inspector.steps(), 0);
+    assert!(inspector.report().opcode_counts.is_empty());
+    assert!(inspector.executed_contracts().is_empty());
+
+    let trace = tracer.into_trace();
+    let root = trace.root().unwrap();
+    assert_eq!(
+        root.kind,
+        CallKind::Transfer {
+            scheme: CallScheme::Call
+        }
+    );
+    assert_eq!(
+        trace.pretty_print(PrettyPrintOptions::default()),
+        format!("[0] CALL → transfer 1.5 ETH to {EOA} (no code)\n")
+    );
+    // Geth still sees a plain call
+    assert_eq!(trace.to_geth_call_trace().unwrap().kind, "CALL");
+}
+
+#[test]
+fn test_eoa_call_with_calldata_ignores_it() {
+    let (inspector, tracer) = trace(common::call_env(EOA, vec![0xa9, 0x05, 0x9c, 0xbb]));
+    assert!(inspector.gas_by_call()[0].no_code);
+    let trace = tracer.into_trace();
+    assert_eq!(
+        trace.pretty_print(PrettyPrintOptions::default()),
+        format!("[0] CALL → transfer 0 ETH to {EOA} (no code, 4 bytes of calldata ignored)\n")
+    );
+}
+
+#[test]
+fn test_nested_transfer_to_eoa() {
+    let (inspector, tracer) = trace(common::call_env(PAYER, Vec::new()));
+    let no_code: Vec<_> = inspector
+        .gas_by_call()
+        .iter()
+        .map(|call| call.no_code)
+        .collect();
+    assert_eq!(no_code, [false, true]);
+    // Only PAYER's code ran
+    let [payer] = &inspector.executed_contracts()[..] else {
+        panic!("{:?}", inspector.executed_contracts());
+    };
+    assert_eq!(payer.address, PAYER);
+    assert_eq!(payer.steps, inspector.steps());
+
+    let trace = tracer.into_trace();
+    let out = trace.pretty_print(PrettyPrintOptions::default());
+    assert!(
+        out.contains(&format!(
+            "└─ [0] CALL → transfer 0.000000000000000001 ETH to {EOA} (no code)"
+        )),
+        "{out}"
+    );
+}
+
+#[test]
+fn test_empty_create_runs_nothing() {
+    let mut env = common::call_env(Address::ZERO, Vec::new());
+    env.tx.transact_to = TxKind::Create;
+    let (inspector, tracer) = trace(env);
+
+    assert_eq!(inspector.steps(), 0);
+    assert!(inspector.report().opcode_counts.is_empty());
+    assert!(inspector.executed_contracts().is_empty());
+    assert!(inspector.creations()[0].init_code.is_empty());
+
+    let trace = tracer.into_trace();
+    let root = trace.root().unwrap();
+    assert_eq!(
+        root.kind,
+        CallKind::EmptyCreate {
+            scheme: CreateScheme::Create
+        }
+    );
+    assert_eq!(root.start_step, root.end_step);
+    // `deploy_with_balance` starts accounts at nonce 1
+    let created = common::CALLER.create(1);
+    assert_eq!(root.target, created);
+    assert_eq!(
+        trace.pretty_print(PrettyPrintOptions::default()),
+        format!("[0] CREATE → create {created} (no code)\n")
+    );
+    assert_eq!(trace.to_geth_call_trace().unwrap().kind, "CREATE");
+}
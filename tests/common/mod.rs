@@ -0,0 +1,121 @@
+//! Shared helpers for the integration tests.
+
+#![allow(dead_code)]
+
+use alloy_primitives::{keccak256, Address, Bytes, U256};
+use restd::evm::build_inspected_evm;
+use revm::{
+    primitives::{AccountInfo, Bytecode, Env, ExecutionResult, TxKind},
+    InMemoryDB, Inspector,
+};
+
+/// Default sender used by the tests.
+pub const CALLER: Address = Address::repeat_byte(0x01);
+
+/// Installs `code` at `address` in the given database.
+pub fn deploy(db: &mut InMemoryDB, address: Address, code: Vec<u8>) {
+    deploy_with_balance(db, address, code, U256::ZERO);
+}
+
+/// Installs `code` at `address` with the given balance.
+pub fn deploy_with_balance(db: &mut InMemoryDB, address: Address, code: Vec<u8>, balance: U256) {
+    let bytecode = Bytecode::new_raw(Bytes::from(code));
+    let info = AccountInfo::new(balance, 1, keccak256(bytecode.original_byte_slice()), bytecode);
+    db.insert_account_info(address, info);
+}
+
+/// Builds an environment for a plain call from [`CALLER`] to `to`.
+pub fn call_env(to: Address, data: Vec<u8>) -> Env {
+    let mut env = Env::default();
+    env.tx.caller = CALLER;
+    env.tx.gas_limit = 1_000_000;
+    env.tx.transact_to = TxKind::Call(to);
+    env.tx.data = Bytes::from(data);
+    env
+}
+
+/// Executes `env` against `db` with `inspector` attached and returns the result.
+pub fn run<I>(db: &mut InMemoryDB, env: Env, inspector: &mut I) -> ExecutionResult
+where
+    I: for<'a> Inspector<&'a mut InMemoryDB>,
+{
+    let mut evm = build_inspected_evm(db, env, inspector);
+    evm.transact_commit().expect("transaction should execute")
+}
+
+/// Assembles `CALL(gas, target, value, 0, 0, 0, 0)` followed by `POP`.
+pub fn call_asm(target: Address, value: u8) -> Vec<u8> {
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, value, 0x73];
+    code.extend_from_slice(target.as_slice());
+    code.extend_from_slice(&[0x5a, 0xf1, 0x50]);
+    code
+}
+
+/// Assembles `CALL(gas, target, 0, 0, 0, 0, 0)` with a fixed gas amount, followed by `POP`.
+pub fn call_asm_with_gas(target: Address, gas: u16) -> Vec<u8> {
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+    code.extend_from_slice(target.as_slice());
+    code.push(0x61);
+    code.extend_from_slice(&gas.to_be_bytes());
+    code.extend_from_slice(&[0xf1, 0x50]);
+    code
+}
+
+/// A loop that counts down from 3 and then stops, burning exactly
+/// [`COUNTDOWN_LOOP_GAS`] gas.
+pub fn countdown_loop() -> Vec<u8> {
+    // PUSH1 3, JUMPDEST, PUSH1 1, SWAP1, SUB, DUP1, PUSH1 2, JUMPI, STOP
+    vec![0x60, 0x03, 0x5b, 0x60, 0x01, 0x90, 0x03, 0x80, 0x60, 0x02, 0x57, 0x00]
+}
+
+/// Gas burned by [`countdown_loop`]: PUSH1 plus three iterations of 26 gas.
+pub const COUNTDOWN_LOOP_GAS: u64 = 3 + 3 * 26;
+
+/// Assembles `STATICCALL(gas, target, 0, 0, 0, 0)` followed by `POP`.
+pub fn static_call_asm(target: Address) -> Vec<u8> {
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+    code.extend_from_slice(target.as_slice());
+    code.extend_from_slice(&[0x5a, 0xfa, 0x50]);
+    code
+}
+
+/// Assembles `DELEGATECALL(gas, target, 0, 0, 0, 0)` followed by `POP`.
+pub fn delegate_call_asm(target: Address) -> Vec<u8> {
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+    code.extend_from_slice(target.as_slice());
+    code.extend_from_slice(&[0x5a, 0xf4, 0x50]);
+    code
+}
+
+/// Assembles code that writes `payload` to memory at offset 0.
+pub fn mstore_asm(payload: &[u8]) -> Vec<u8> {
+    let mut code = Vec::new();
+    for (i, chunk) in payload.chunks(32).enumerate() {
+        let mut word = [0u8; 32];
+        word[..chunk.len()].copy_from_slice(chunk);
+        code.push(0x7f);
+        code.extend_from_slice(&word);
+        code.push(0x61);
+        code.extend_from_slice(&((i * 32) as u16).to_be_bytes());
+        code.push(0x52);
+    }
+    code
+}
+
+/// Assembles code that returns `payload`.
+pub fn return_asm(payload: &[u8]) -> Vec<u8> {
+    let mut code = mstore_asm(payload);
+    code.push(0x61);
+    code.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    code.extend_from_slice(&[0x60, 0x00, 0xf3]);
+    code
+}
+
+/// Assembles code that reverts with `payload`.
+pub fn revert_asm(payload: &[u8]) -> Vec<u8> {
+    let mut code = mstore_asm(payload);
+    code.push(0x61);
+    code.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    code.extend_from_slice(&[0x60, 0x00, 0xfd]);
+    code
+}
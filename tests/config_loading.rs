@@ -0,0 +1,112 @@
+//! Integration tests for loading the configuration from TOML and the environment.
+
+use std::env;
+
+use alloy_primitives::Address;
+use restd::{ConfigError, HelloWorldInspectorConfig, OutputMode};
+
+// Each test uses its own prefix since tests run concurrently in one process.
+
+#[test]
+fn test_toml_file_with_nested_filter() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        file.path(),
+        r#"
+        log_steps = true
+        output = "tracing"
+        max_steps = 5000
+
+        [filter]
+        include = ["0x1111111111111111111111111111111111111111"]
+        match_on = "caller"
+        "#,
+    )
+    .unwrap();
+
+    let config = HelloWorldInspectorConfig::from_toml_file(file.path()).unwrap();
+    assert!(config.log_steps && !config.verbose);
+    assert_eq!(config.output, OutputMode::Tracing);
+    assert_eq!(config.max_steps, Some(5000));
+    assert_eq!(config.step_sample_interval, 100);
+    assert_eq!(config.filter.include, vec![Address::repeat_byte(0x11)]);
+}
+
+#[test]
+fn test_environment_overrides_file_values() {
+    env::set_var("RESTD_PRECEDENCE_VERBOSE", "true");
+    env::set_var("RESTD_PRECEDENCE_STEP_SAMPLE_INTERVAL", "7");
+    env::set_var(
+        "RESTD_PRECEDENCE_FILTER_EXCLUDE",
+        "0x2222222222222222222222222222222222222222, 0x3333333333333333333333333333333333333333",
+    );
+
+    let config = HelloWorldInspectorConfig::from_toml_str(
+        "verbose = false\nstep_sample_interval = 50\ntrace_calls = true",
+    )
+    .unwrap()
+    .with_env_overrides("RESTD_PRECEDENCE")
+    .unwrap();
+
+    assert!(config.verbose);
+    assert_eq!(config.step_sample_interval, 7);
+    // Not set in the environment, so the file value stays
+    assert!(config.trace_calls);
+    assert_eq!(
+        config.filter.exclude,
+        vec![Address::repeat_byte(0x22), Address::repeat_byte(0x33)]
+    );
+}
+
+#[test]
+fn test_invalid_boolean_is_rejected() {
+    env::set_var("RESTD_INVALID_LOG_STEPS", "yes");
+    let err = HelloWorldInspectorConfig::from_env("RESTD_INVALID").unwrap_err();
+    assert_eq!(
+        err,
+        ConfigError::InvalidEnvValue {
+            name: "RESTD_INVALID_LOG_STEPS".to_string(),
+            value: "yes".to_string(),
+        }
+    );
+
+    let err = HelloWorldInspectorConfig::from_toml_str("verbose = \"yes\"").unwrap_err();
+    assert!(matches!(err, ConfigError::Toml(_)), "{err:?}");
+}
+
+#[test]
+fn test_unknown_keys_are_rejected() {
+    let err = HelloWorldInspectorConfig::from_toml_str("verbose = true\nbogus = 1").unwrap_err();
+    assert_eq!(err, ConfigError::UnknownKey("bogus".to_string()));
+
+    let err = HelloWorldInspectorConfig::from_toml_str("[filter]\ninclud = []").unwrap_err();
+    assert_eq!(err, ConfigError::UnknownKey("includ".to_string()));
+
+    env::set_var("RESTD_UNKNOWN_VERBOSITY", "1");
+    let err = HelloWorldInspectorConfig::from_env("RESTD_UNKNOWN").unwrap_err();
+    assert_eq!(err, ConfigError::UnknownKey("RESTD_UNKNOWN_VERBOSITY".to_string()));
+    assert!(err.to_string().contains("unknown configuration key"));
+}
+
+#[test]
+fn test_loaded_config_is_validated() {
+    let err = HelloWorldInspectorConfig::from_toml_str("verbose = true\noutput = \"silent\"")
+        .unwrap_err();
+    assert_eq!(err, ConfigError::VerboseWhileSilent);
+}
+
+#[test]
+fn test_limits_from_file_and_environment() {
+    env::set_var("RESTD_BOUNDS_LIMITS_MAX_LOGS", "20");
+
+    let config = HelloWorldInspectorConfig::from_toml_str(
+        "[limits]\nmax_call_records = 100\nmax_logs = 10",
+    )
+    .unwrap()
+    .with_env_overrides("RESTD_BOUNDS")
+    .unwrap();
+
+    assert_eq!(config.limits.max_call_records, Some(100));
+    assert_eq!(config.limits.max_logs, Some(20));
+    assert_eq!(config.limits.max_total_bytes, None);
+}
@@ -0,0 +1,154 @@
+//! Integration tests for reloading a plugin's configuration while it runs.
+
+mod common;
+
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+
+use alloy_primitives::Address;
+use restd::{
+    Error, HelloWorldInspectorConfig, HelloWorldInspectorPlugin, OutputMode, ProfilingMode,
+    TraceLimits,
+};
+use revm::{interpreter::opcode, InMemoryDB};
+
+const CONTRACT: Address = Address::repeat_byte(0x20);
+
+fn plugin() -> HelloWorldInspectorPlugin {
+    HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, common::countdown_loop());
+    db
+}
+
+#[test]
+fn test_reload_applies_at_next_transaction() {
+    let plugin = plugin();
+    let reloaded = HelloWorldInspectorConfig {
+        log_steps: true,
+        alert_on_opcodes: BTreeSet::from([opcode::JUMPI]),
+        ..(*plugin.config()).clone()
+    };
+    // Reload halfway through the first transaction
+    let reloader = plugin.clone();
+    let pending = Arc::new(Mutex::new(Some(reloaded)));
+    let mut inspector = plugin.create_inspector().on_step(move |step| {
+        if step.step == 10 {
+            if let Some(config) = pending.lock().unwrap().take() {
+                reloader.reload(config).unwrap();
+            }
+        }
+    });
+
+    let mut db = db();
+    common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(plugin.config().log_steps);
+    assert!(!inspector.config().log_steps);
+    assert!(inspector.opcode_alerts().is_empty());
+
+    common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(inspector.config().log_steps);
+    // The loop jumps back three times
+    let alerts = inspector.opcode_alerts();
+    assert_eq!(alerts.len(), 3);
+    assert!(alerts.iter().all(|alert| alert.tx_index == 1));
+
+    // Flipping it back applies to the inspector after a reset too
+    plugin
+        .reload(HelloWorldInspectorConfig {
+            log_steps: false,
+            ..(*plugin.config()).clone()
+        })
+        .unwrap();
+    inspector.reset();
+    common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(!inspector.config().log_steps);
+}
+
+#[test]
+fn test_structural_changes_require_restart() {
+    let plugin = plugin();
+    let current = (*plugin.config()).clone();
+    let changes = [
+        (
+            HelloWorldInspectorConfig {
+                sink_path: Some("trace.jsonl".into()),
+                ..current.clone()
+            },
+            "sink_path",
+        ),
+        (
+            HelloWorldInspectorConfig {
+                profiling: ProfilingMode::Counters,
+                ..current.clone()
+            },
+            "profiling",
+        ),
+        (
+            HelloWorldInspectorConfig {
+                limits: TraceLimits {
+                    max_logs: Some(10),
+                    ..Default::default()
+                },
+                ..current.clone()
+            },
+            "limits",
+        ),
+    ];
+    for (config, option) in changes {
+        match plugin.reload(config) {
+            Err(Error::RequiresRestart(name)) => assert_eq!(name, option),
+            other => panic!("expected {option} to require a restart, got {other:?}"),
+        }
+    }
+    // Invalid configurations are rejected before anything changes
+    let invalid = HelloWorldInspectorConfig {
+        verbose: true,
+        ..current.clone()
+    };
+    assert!(matches!(plugin.reload(invalid), Err(Error::Config(_))));
+    assert!(!plugin.config().verbose);
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn test_watcher_reloads_on_file_change() {
+    use std::time::{Duration, Instant};
+
+    use restd::ConfigWatcher;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("restd.toml");
+    std::fs::write(&path, "output = \"silent\"\n").unwrap();
+    let plugin = plugin();
+    let watcher = ConfigWatcher::spawn(plugin.clone(), &path, Duration::from_millis(5));
+
+    let wait_for = |done: &dyn Fn() -> bool| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        done()
+    };
+    std::fs::write(&path, "output = \"silent\"\nlog_steps = true\n").unwrap();
+    assert!(wait_for(&|| plugin.config().log_steps));
+
+    // A file that needs a restart leaves the configuration as it is
+    std::fs::write(&path, "output = \"silent\"\nprofiling = \"counters\"\n").unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(plugin.config().log_steps);
+    assert_eq!(plugin.config().profiling, ProfilingMode::Full);
+
+    drop(watcher);
+    std::fs::write(&path, "output = \"silent\"\n").unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(plugin.config().log_steps);
+}
@@ -0,0 +1,105 @@
+//! Integration tests for recording contract creations.
+
+mod common;
+
+use alloy_primitives::{keccak256, Address, U256};
+use restd::{CreateKind, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{
+    primitives::{ExecutionResult, Output, TxKind},
+    InMemoryDB,
+};
+
+const FACTORY: Address = Address::repeat_byte(0x20);
+
+/// MSTORE8(0, 0), RETURN(0, 1): deploys a single STOP
+const INIT_CODE: [u8; 10] = [0x60, 0x00, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3];
+
+fn silent() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+fn create_tx(init_code: &[u8], inspector: &mut HelloWorldInspector) -> ExecutionResult {
+    let mut db = InMemoryDB::default();
+    let mut env = common::call_env(Address::ZERO, init_code.to_vec());
+    env.tx.transact_to = TxKind::Create;
+    common::run(&mut db, env, inspector)
+}
+
+#[test]
+fn test_create_transaction_records_deployed_address() {
+    let mut inspector = silent();
+    let result = create_tx(&INIT_CODE, &mut inspector);
+    let ExecutionResult::Success {
+        output: Output::Create(code, Some(address)),
+        gas_used,
+        ..
+    } = result
+    else {
+        panic!("creation should succeed: {result:?}");
+    };
+
+    let creations = inspector.creations();
+    assert_eq!(creations.len(), 1);
+    let record = &creations[0];
+    assert_eq!(record.creator, common::CALLER);
+    assert_eq!(record.scheme, CreateKind::Create);
+    assert_eq!(record.init_code_hash, keccak256(INIT_CODE));
+    let disassembly: Vec<String> = record.disassembly().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        disassembly,
+        ["PUSH1 0x00", "PUSH1 0x00", "MSTORE8", "PUSH1 0x01", "PUSH1 0x00", "RETURN"]
+    );
+    assert_eq!(record.deployed_address, Some(address));
+    assert_eq!(record.deployed_address, Some(common::CALLER.create(0)));
+    assert_eq!(record.deployed_code_len, code.len());
+    assert!(record.success);
+    assert!(record.gas_used > 0 && record.gas_used < gas_used);
+    assert_eq!(record.depth, 0);
+    assert_eq!(record.predicted_create2_address(), None);
+    assert!(!record.has_address_mismatch());
+    assert_eq!(inspector.report().creations, creations);
+}
+
+#[test]
+fn test_failed_creation_has_no_address() {
+    let mut inspector = silent();
+    // REVERT(0, 0)
+    let result = create_tx(&[0x60, 0x00, 0x60, 0x00, 0xfd], &mut inspector);
+    assert!(!result.is_success());
+
+    let record = &inspector.creations()[0];
+    assert!(!record.success);
+    assert_eq!(record.deployed_address, None);
+    assert_eq!(record.deployed_code_len, 0);
+}
+
+#[test]
+fn test_create2_address_matches_prediction() {
+    // MSTORE(0, INIT_CODE), CREATE2(0, 22, 10, 5), POP, STOP
+    let mut factory = vec![0x69];
+    factory.extend_from_slice(&INIT_CODE);
+    factory.extend_from_slice(&[
+        0x60, 0x00, 0x52, 0x60, 0x05, 0x60, 0x0a, 0x60, 0x16, 0x60, 0x00, 0xf5, 0x50, 0x00,
+    ]);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, FACTORY, factory);
+    let mut inspector = silent();
+    let result = common::run(&mut db, common::call_env(FACTORY, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let creations = inspector.creations();
+    assert_eq!(creations.len(), 1);
+    let record = &creations[0];
+    let expected = FACTORY.create2(U256::from(5).to_be_bytes::<32>(), keccak256(INIT_CODE));
+    assert_eq!(record.creator, FACTORY);
+    assert_eq!(record.scheme, CreateKind::Create2 { salt: U256::from(5) });
+    assert_eq!(record.depth, 1);
+    assert_eq!(record.deployed_address, Some(expected));
+    assert_eq!(record.predicted_create2_address(), Some(expected));
+    assert!(!record.has_address_mismatch());
+    assert_eq!(record.deployed_code_len, 1);
+    assert!(db.accounts[&expected].info.code.is_some());
+}
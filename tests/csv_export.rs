@@ -0,0 +1,99 @@
+//! Integration tests for the CSV export of inspector reports.
+
+mod common;
+
+use alloy_primitives::Address;
+use alloy_sol_types::{Revert, SolError};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, InspectorReport, OutputMode};
+use revm::InMemoryDB;
+
+fn traced_report() -> InspectorReport {
+    let outer = Address::repeat_byte(0x20);
+    let callee = Address::repeat_byte(0x21);
+    let reverter = Address::repeat_byte(0x22);
+
+    let mut code = common::call_asm(callee, 0);
+    code.extend(common::call_asm(reverter, 0));
+    code.push(0x00);
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, code);
+    common::deploy(&mut db, callee, vec![0x00]);
+    common::deploy(
+        &mut db,
+        reverter,
+        common::revert_asm(&Revert::from("no, \"not\" today").abi_encode()),
+    );
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let env = common::call_env(outer, vec![0xa9, 0x05, 0x9c, 0xbb]);
+    let result = common::run(&mut db, env, &mut inspector);
+    assert!(result.is_success());
+    inspector.report()
+}
+
+#[test]
+fn test_calls_csv_round_trips_through_csv_reader() {
+    let mut out = Vec::new();
+    traced_report().to_csv(&mut out).unwrap();
+
+    let mut reader = csv::Reader::from_reader(out.as_slice());
+    assert_eq!(
+        reader.headers().unwrap(),
+        vec![
+            "tx_index",
+            "depth",
+            "kind",
+            "caller",
+            "target",
+            "selector",
+            "value",
+            "gas_limit",
+            "gas_used",
+            "success",
+            "revert_reason",
+            "seq",
+            "label",
+        ]
+    );
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 3);
+
+    assert_eq!(&rows[0][1], "0");
+    assert_eq!(&rows[0][2], "CALL");
+    assert_eq!(&rows[0][3], "0x0101010101010101010101010101010101010101");
+    assert_eq!(&rows[0][4], "0x2020202020202020202020202020202020202020");
+    assert_eq!(&rows[0][5], "0xa9059cbb");
+    assert_eq!(&rows[0][6], "0");
+    assert_eq!(&rows[0][9], "true");
+
+    assert_eq!(&rows[1][1], "1");
+    assert_eq!(&rows[1][3], "0x2020202020202020202020202020202020202020");
+    assert_eq!(&rows[1][5], "");
+    assert_eq!(&rows[1][10], "");
+
+    assert_eq!(&rows[2][4], "0x2222222222222222222222222222222222222222");
+    assert_eq!(&rows[2][9], "false");
+    assert_eq!(&rows[2][10], "no, \"not\" today");
+    assert!(String::from_utf8(out).unwrap().contains(r#","no, ""not"" today""#));
+}
+
+#[test]
+fn test_opcode_counts_csv() {
+    let report = traced_report();
+    let mut out = Vec::new();
+    report.opcode_counts_to_csv(&mut out).unwrap();
+
+    let mut reader = csv::Reader::from_reader(out.as_slice());
+    assert_eq!(reader.headers().unwrap(), vec!["opcode", "count"]);
+    let rows: Vec<(String, u64)> = reader.deserialize().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), report.opcode_counts.len());
+    assert!(rows.contains(&("CALL".to_string(), 2)));
+    assert_eq!(
+        rows.iter().map(|(_, count)| count).sum::<u64>(),
+        report.step_count
+    );
+}
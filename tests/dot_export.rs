@@ -0,0 +1,84 @@
+//! Compares the Graphviz DOT export against a checked-in golden file.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{CallTrace, CallTraceInspector, DotOptions};
+use revm::InMemoryDB;
+
+const GOLDEN: &str = include_str!("fixtures/call_graph.dot");
+
+const A: Address = Address::repeat_byte(0xaa);
+const B: Address = Address::repeat_byte(0xbb);
+const C: Address = Address::repeat_byte(0xcc);
+const IDENTITY: Address = Address::with_last_byte(0x04);
+const SHA256: Address = Address::with_last_byte(0x02);
+
+/// A calls B, which calls C without value, then calls C with 1 wei. C
+/// reverts unless it receives value.
+fn three_contract_trace() -> CallTrace {
+    let mut code_a = common::call_asm(B, 0);
+    code_a.extend(common::call_asm(C, 1));
+    code_a.push(0x00);
+    let mut code_b = common::call_asm(C, 0);
+    code_b.push(0x00);
+    // JUMPI(8, CALLVALUE), REVERT(0, 0), JUMPDEST, STOP
+    let code_c = vec![0x34, 0x60, 0x08, 0x57, 0x60, 0x00, 0x80, 0xfd, 0x5b, 0x00];
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, A, code_a, U256::from(1));
+    common::deploy(&mut db, B, code_b);
+    common::deploy(&mut db, C, code_c);
+    let mut inspector = CallTraceInspector::new();
+    common::run(&mut db, common::call_env(A, vec![]), &mut inspector);
+    inspector.into_trace()
+}
+
+#[test]
+fn test_dot_matches_golden() {
+    let opts = DotOptions {
+        labels: [(A, "Router")].into(),
+        ..Default::default()
+    };
+    let dot = three_contract_trace().to_dot(opts);
+    assert_eq!(dot, GOLDEN, "actual:\n{dot}");
+}
+
+#[test]
+fn test_max_nodes_keeps_busiest_addresses() {
+    let opts = DotOptions {
+        max_nodes: Some(2),
+        ..Default::default()
+    };
+    let dot = three_contract_trace().to_dot(opts);
+    // A received the gas of the whole transaction, B that of its own call
+    assert!(dot.contains(&format!("label=\"{A}\"")));
+    assert!(dot.contains(&format!("label=\"{B}\"")));
+    assert!(!dot.contains(&C.to_string()));
+    assert!(!dot.contains(&common::CALLER.to_string()));
+    assert_eq!(dot.matches(" -> ").count(), 1);
+}
+
+#[test]
+fn test_collapse_precompiles() {
+    let mut code = common::call_asm(IDENTITY, 0);
+    code.extend(common::call_asm(SHA256, 0));
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, A, code);
+    let mut inspector = CallTraceInspector::new();
+    common::run(&mut db, common::call_env(A, vec![]), &mut inspector);
+    let trace = inspector.into_trace();
+
+    let separate = trace.to_dot(DotOptions::default());
+    assert!(separate.contains("[label=\"identity\"]"));
+    assert!(separate.contains("[label=\"sha256\"]"));
+
+    let collapsed = trace.to_dot(DotOptions {
+        collapse_precompiles: true,
+        ..Default::default()
+    });
+    assert!(collapsed.contains("[label=\"precompiles\"]"));
+    assert!(collapsed.contains("[label=\"2 calls\\ngas "));
+    assert!(!collapsed.contains("identity"));
+}
@@ -0,0 +1,110 @@
+//! Integration tests for tracing EIP-7702 delegations and authorizations.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{
+    eip7702::DELEGATION_DESIGNATOR, CallTraceInspector, HelloWorldInspector,
+    HelloWorldInspectorConfig, OutputMode, PrettyPrintOptions,
+};
+use revm::{
+    primitives::{Authorization, AuthorizationList, RecoveredAuthorization},
+    InMemoryDB,
+};
+
+const ENTRY: Address = Address::repeat_byte(0x70);
+const AUTHORITY: Address = Address::repeat_byte(0x71);
+const DELEGATE: Address = Address::repeat_byte(0x72);
+
+/// SSTORE(0, 1), STOP
+const DELEGATE_CODE: [u8; 6] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+
+fn designator(delegate: Address) -> Vec<u8> {
+    let mut code = DELEGATION_DESIGNATOR.to_vec();
+    code.extend_from_slice(delegate.as_slice());
+    code
+}
+
+/// An authorization by [`AUTHORITY`] with nonce 0, recovered without
+/// checking its dummy signature.
+// revm's authorizations still take the deprecated signature type
+#[allow(deprecated)]
+fn authorize(delegate: Address) -> RecoveredAuthorization {
+    let authorization = Authorization {
+        chain_id: U256::ZERO,
+        address: delegate,
+        nonce: 0,
+    };
+    let signature =
+        revm::primitives::Signature::from_rs_and_parity(U256::from(1), U256::from(1), false)
+            .unwrap();
+    RecoveredAuthorization::new_unchecked(authorization.into_signed(signature), Some(AUTHORITY))
+}
+
+#[test]
+fn test_call_to_delegated_account_is_flagged() {
+    let mut db = InMemoryDB::default();
+    let mut entry = common::call_asm(AUTHORITY, 0);
+    entry.push(0x00);
+    common::deploy(&mut db, ENTRY, entry);
+    common::deploy(&mut db, AUTHORITY, designator(DELEGATE));
+    common::deploy(&mut db, DELEGATE, DELEGATE_CODE.to_vec());
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let mut tracer = CallTraceInspector::new();
+    let env = common::call_env(ENTRY, vec![]);
+    assert!(common::run(&mut db, env.clone(), &mut inspector).is_success());
+
+    let calls = inspector.gas_by_call();
+    assert_eq!(calls.len(), 2);
+    assert!(!calls[0].delegated);
+    assert_eq!(calls[0].delegate, None);
+    assert_eq!(calls[1].target, AUTHORITY);
+    assert!(calls[1].delegated);
+    assert_eq!(calls[1].delegate, Some(DELEGATE));
+    // The delegate's code ran on the authority's storage
+    assert_eq!(inspector.storage_accesses()[0].address, AUTHORITY);
+    assert!(inspector.authorizations().is_empty());
+
+    let report = inspector.report().to_json().unwrap();
+    assert!(report.contains(&format!(r#""delegated":true,"delegate":"{DELEGATE}""#)));
+
+    common::run(&mut db, env, &mut tracer);
+    let trace = tracer.into_trace();
+    let child = &trace.root().unwrap().children[0];
+    assert_eq!(child.delegate, Some(DELEGATE));
+    let printed = trace.pretty_print(PrettyPrintOptions::default());
+    assert!(
+        printed.contains(&format!("CALL EOA({AUTHORITY}) ⇒ {DELEGATE}::fallback()")),
+        "{printed}"
+    );
+}
+
+#[test]
+fn test_authorization_list_is_recorded() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, DELEGATE, DELEGATE_CODE.to_vec());
+    let mut env = common::call_env(AUTHORITY, vec![]);
+    env.tx.authorization_list = Some(AuthorizationList::Recovered(vec![authorize(DELEGATE)]));
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    assert!(common::run(&mut db, env, &mut inspector).is_success());
+
+    let authorizations = inspector.authorizations();
+    assert_eq!(authorizations.len(), 1);
+    assert_eq!(authorizations[0].authority, Some(AUTHORITY));
+    assert_eq!(authorizations[0].delegate, DELEGATE);
+    assert_eq!(authorizations[0].nonce, 0);
+    assert_eq!(inspector.report().authorizations, authorizations);
+
+    // The authorization took effect before the call
+    let call = &inspector.gas_by_call()[0];
+    assert_eq!(call.target, AUTHORITY);
+    assert_eq!(call.delegate, Some(DELEGATE));
+}
@@ -0,0 +1,112 @@
+//! Tests for EOF contract creations, calling the hooks directly with
+//! constructed inputs.
+
+use alloy_primitives::{keccak256, Address, Bytes, U256};
+use restd::{
+    CallKind, CallTraceInspector, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+};
+use revm::{
+    interpreter::{
+        CreateOutcome, EOFCreateInputs, EOFCreateKind, Gas, InstructionResult, InterpreterResult,
+    },
+    primitives::Eof,
+    EvmContext, InMemoryDB, Inspector,
+};
+
+const CREATOR: Address = Address::repeat_byte(0x01);
+const CREATED: Address = Address::repeat_byte(0xc0);
+
+fn inputs() -> EOFCreateInputs {
+    EOFCreateInputs::new(
+        CREATOR,
+        U256::ZERO,
+        100_000,
+        EOFCreateKind::Opcode {
+            initcode: Eof::default(),
+            input: Bytes::from_static(&[0x2a]),
+            created_address: CREATED,
+        },
+    )
+}
+
+fn outcome() -> CreateOutcome {
+    CreateOutcome::new(
+        InterpreterResult::new(
+            InstructionResult::ReturnContract,
+            Bytes::new(),
+            Gas::new(100_000),
+        ),
+        Some(CREATED),
+    )
+}
+
+/// Runs the EOF create hooks of `inspector` for [`inputs`] and [`outcome`].
+fn eofcreate<I: Inspector<InMemoryDB>>(inspector: &mut I) {
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let mut inputs = inputs();
+    assert!(inspector.eofcreate(&mut context, &mut inputs).is_none());
+    inspector.eofcreate_end(&mut context, &inputs, outcome());
+}
+
+#[test]
+fn test_eofcreate_is_in_the_call_tree() {
+    let mut tracer = CallTraceInspector::new();
+    eofcreate(&mut tracer);
+
+    let root = tracer.root().expect("EOF create frame");
+    assert_eq!(
+        root.kind,
+        CallKind::EofCreate {
+            container_hash: Some(keccak256(&Eof::default().raw)),
+        }
+    );
+    assert!(root.kind.is_create());
+    assert_eq!(root.target, CREATED);
+    assert_eq!(root.caller, CREATOR);
+    assert!(root.success);
+    assert_eq!(root.input.last(), Some(&0x2a));
+    assert_eq!(root.input.len(), Eof::default().raw.len() + 1);
+}
+
+#[test]
+fn test_eofcreate_is_counted() {
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    eofcreate(&mut inspector);
+
+    assert_eq!(inspector.eofcreates(), 1);
+    assert_eq!(inspector.current_depth(), 0);
+    assert_eq!(inspector.calls(), 0);
+    assert_eq!(inspector.report().eofcreate_count, 1);
+}
+
+#[test]
+fn test_creation_tx_without_container_has_no_hash() {
+    let mut tracer = CallTraceInspector::new();
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let mut inputs = EOFCreateInputs::new(
+        CREATOR,
+        U256::ZERO,
+        100_000,
+        EOFCreateKind::Tx {
+            initdata: Bytes::from_static(&[0xef, 0x00]),
+        },
+    );
+    tracer.eofcreate(&mut context, &mut inputs);
+    let failed = CreateOutcome::new(
+        InterpreterResult::new(
+            InstructionResult::InvalidEOFInitCode,
+            Bytes::new(),
+            Gas::new(100_000),
+        ),
+        None,
+    );
+    tracer.eofcreate_end(&mut context, &inputs, failed);
+
+    let root = tracer.root().unwrap();
+    assert_eq!(root.kind, CallKind::EofCreate { container_hash: None });
+    assert_eq!(root.target, Address::ZERO);
+    assert!(!root.success);
+}
@@ -0,0 +1,127 @@
+//! Integration tests for decoding logs with event declarations.
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_primitives::{keccak256, Address, B256};
+use restd::{
+    CallTraceInspector, EventDecoder, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    PrettyPrintOptions,
+};
+use revm::InMemoryDB;
+
+const TOKEN: Address = Address::repeat_byte(0x20);
+const FROM: Address = Address::repeat_byte(0xaa);
+const TO: Address = Address::repeat_byte(0xbb);
+
+const TRANSFER: &str = "Transfer(address indexed from, address indexed to, uint256 value)";
+
+fn decoder() -> Arc<EventDecoder> {
+    Arc::new(EventDecoder::new().with_events([TRANSFER]).unwrap())
+}
+
+/// Assembles `LOG3(0, size, topic0, from, to)` after storing 1000 at memory 0.
+fn transfer_log_asm(size: u8) -> Vec<u8> {
+    // MSTORE(0, 1000)
+    let mut code = vec![0x61, 0x03, 0xe8, 0x60, 0x00, 0x52];
+    for topic in [
+        B256::left_padding_from(TO.as_slice()),
+        B256::left_padding_from(FROM.as_slice()),
+    ] {
+        code.push(0x7f);
+        code.extend_from_slice(topic.as_slice());
+    }
+    code.push(0x7f);
+    code.extend_from_slice(keccak256("Transfer(address,address,uint256)").as_slice());
+    code.extend_from_slice(&[0x60, size, 0x60, 0x00, 0xa3, 0x00]);
+    code
+}
+
+fn db(size: u8) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, TOKEN, transfer_log_asm(size));
+    db
+}
+
+fn inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+    .with_event_decoder(decoder())
+}
+
+#[test]
+fn test_transfer_log_is_decoded() {
+    let mut inspector = inspector();
+    common::run(&mut db(32), common::call_env(TOKEN, vec![]), &mut inspector);
+
+    let log = &inspector.logs()[0];
+    let decoded = log.decoded.as_ref().expect("Transfer should decode");
+    assert_eq!(decoded.event, "Transfer");
+    assert_eq!(decoded.fields[0].name, "from");
+    assert_eq!(decoded.fields[0].ty, "address");
+    assert!(decoded.fields[0].indexed);
+    assert_eq!(decoded.fields[2].value, "1000");
+    assert!(!decoded.fields[2].indexed);
+    assert_eq!(
+        decoded.to_string(),
+        format!("Transfer(from: {FROM}, to: {TO}, value: 1000)")
+    );
+    assert_eq!(log.decode_error, None);
+
+    let json: serde_json::Value = serde_json::from_str(&inspector.report_json().unwrap()).unwrap();
+    let fields = &json["logs"][0]["decoded"]["fields"];
+    assert_eq!(json["logs"][0]["decoded"]["event"], "Transfer");
+    assert_eq!(fields[1]["name"], "to");
+    assert_eq!(fields[1]["type"], "address");
+    assert_eq!(fields[2]["value"], "1000");
+}
+
+#[test]
+fn test_malformed_log_keeps_raw_form() {
+    // The value is missing from the data
+    let mut inspector = inspector();
+    common::run(&mut db(0), common::call_env(TOKEN, vec![]), &mut inspector);
+
+    let log = &inspector.logs()[0];
+    assert_eq!(log.decoded, None);
+    assert!(log.decode_error.is_some());
+    assert_eq!(log.topics.len(), 3);
+    assert!(log.data.is_empty());
+}
+
+#[test]
+fn test_unknown_event_is_not_decoded() {
+    let mut inspector = HelloWorldInspector::default().with_event_decoder(Arc::new(
+        EventDecoder::new()
+            .with_events(["Approval(uint256)"])
+            .unwrap(),
+    ));
+    common::run(&mut db(32), common::call_env(TOKEN, vec![]), &mut inspector);
+
+    let log = &inspector.logs()[0];
+    assert_eq!(log.decoded, None);
+    assert_eq!(log.decode_error, None);
+}
+
+#[test]
+fn test_pretty_print_shows_decoded_logs() {
+    for (size, expected) in [
+        (
+            32,
+            format!("emit Transfer(from: {FROM}, to: {TO}, value: 1000)"),
+        ),
+        (0, "data: 0x (decode error:".to_string()),
+    ] {
+        let mut tracer = CallTraceInspector::new();
+        common::run(&mut db(size), common::call_env(TOKEN, vec![]), &mut tracer);
+        let out = tracer.into_trace().pretty_print(PrettyPrintOptions {
+            show_logs: true,
+            event_decoder: Some(decoder()),
+            ..Default::default()
+        });
+        assert!(out.contains(&expected), "{out}");
+    }
+}
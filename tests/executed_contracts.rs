@@ -0,0 +1,144 @@
+//! Integration tests for discovering the contracts whose code ran.
+
+mod common;
+
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, TraceSession};
+use revm::InMemoryDB;
+
+const FIRST: Address = Address::repeat_byte(0xb0);
+const SECOND: Address = Address::repeat_byte(0xb1);
+const SHARED: Address = Address::repeat_byte(0xb2);
+const PROXY: Address = Address::repeat_byte(0xb3);
+
+/// Steps of `call_asm`.
+const CALL_STEPS: u64 = 9;
+/// Steps of `countdown_loop`.
+const LOOP_STEPS: u64 = 23;
+
+/// FIRST calls SHARED; SECOND calls SHARED twice; PROXY delegates to SHARED.
+fn db() -> InMemoryDB {
+    let mut first = common::call_asm(SHARED, 0);
+    first.push(0x00);
+    let mut second = common::call_asm(SHARED, 0);
+    second.extend(common::call_asm(SHARED, 0));
+    second.push(0x00);
+    let mut proxy = common::delegate_call_asm(SHARED);
+    proxy.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, FIRST, first);
+    common::deploy(&mut db, SECOND, second);
+    common::deploy(&mut db, SHARED, common::countdown_loop());
+    common::deploy(&mut db, PROXY, proxy);
+    db
+}
+
+fn inspector(capture_code: bool) -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        capture_code,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_overlapping_transactions_are_deduplicated() {
+    let mut db = db();
+    let mut inspector = inspector(false);
+    common::run(&mut db, common::call_env(FIRST, vec![]), &mut inspector);
+    common::run(&mut db, common::call_env(SECOND, vec![]), &mut inspector);
+
+    let executed = inspector.executed_contracts();
+    let summary: Vec<_> = executed
+        .iter()
+        .map(|contract| {
+            (
+                contract.address,
+                contract.first_tx_index,
+                contract.frames,
+                contract.steps,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (FIRST, 0, 1, CALL_STEPS + 1),
+            (SHARED, 0, 3, 3 * LOOP_STEPS),
+            (SECOND, 1, 1, 2 * CALL_STEPS + 1),
+        ]
+    );
+    // Every step is attributed to exactly one contract
+    let steps: u64 = executed.iter().map(|contract| contract.steps).sum();
+    assert_eq!(steps, inspector.steps());
+    assert_eq!(executed[1].code_hash, keccak256(common::countdown_loop()));
+    assert!(executed.iter().all(|contract| contract.code.is_none()));
+    assert_eq!(inspector.report().executed_contracts, executed);
+}
+
+#[test]
+fn test_delegatecall_is_attributed_to_the_library() {
+    let mut inspector = inspector(false);
+    common::run(&mut db(), common::call_env(PROXY, vec![]), &mut inspector);
+    let addresses: Vec<_> = inspector
+        .executed_contracts()
+        .iter()
+        .map(|contract| (contract.address, contract.steps))
+        .collect();
+    assert_eq!(addresses, [(PROXY, 9), (SHARED, LOOP_STEPS)]);
+}
+
+#[test]
+fn test_capture_code_keeps_the_bytecode() {
+    let mut inspector = inspector(true);
+    common::run(&mut db(), common::call_env(FIRST, vec![]), &mut inspector);
+    let executed = inspector.executed_contracts();
+    assert_eq!(
+        executed[1].code,
+        Some(Bytes::from(common::countdown_loop()))
+    );
+    let json = serde_json::to_value(inspector.report()).unwrap();
+    assert!(json["executed_contracts"][0]["code"].is_string());
+    assert!(
+        HelloWorldInspector::builder()
+            .capture_code(true)
+            .build()
+            .config()
+            .capture_code
+    );
+}
+
+#[test]
+fn test_block_report_merges_transactions() {
+    let mut db = db();
+    let mut session = TraceSession::new(inspector(false));
+    for (i, to) in [FIRST, SECOND].into_iter().enumerate() {
+        session.begin_tx(B256::with_last_byte(i as u8 + 1));
+        common::run(
+            &mut db,
+            common::call_env(to, vec![]),
+            session.inspector_mut(),
+        );
+        session.end_tx();
+    }
+    let block = session.finish();
+    let summary: Vec<_> = block
+        .executed_contracts
+        .iter()
+        .map(|contract| (contract.address, contract.first_tx_index, contract.steps))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (FIRST, 0, CALL_STEPS + 1),
+            (SHARED, 0, 3 * LOOP_STEPS),
+            (SECOND, 1, 2 * CALL_STEPS + 1),
+        ]
+    );
+    let steps: u64 = block
+        .executed_contracts
+        .iter()
+        .map(|contract| contract.steps)
+        .sum();
+    assert_eq!(steps, block.total_steps);
+}
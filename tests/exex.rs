@@ -0,0 +1,110 @@
+//! Smoke tests for the ExEx with a mocked notification stream.
+
+#![cfg(feature = "reth")]
+
+mod common;
+
+use std::collections::VecDeque;
+
+use alloy_primitives::{Address, U256};
+use async_trait::async_trait;
+use restd::exex::{ChainNotification, ChainNotifications, CommittedBlock, RestdExEx};
+use revm::{
+    primitives::{AccountInfo, Env, TxEnv, TxKind},
+    InMemoryDB,
+};
+
+/// Replays queued notifications and records the reported heights.
+#[derive(Debug, Default)]
+struct MockNotifications {
+    queue: VecDeque<ChainNotification<InMemoryDB>>,
+    finished: Vec<u64>,
+}
+
+#[async_trait]
+impl ChainNotifications for MockNotifications {
+    type Db = InMemoryDB;
+
+    async fn next_notification(&mut self) -> Option<ChainNotification<InMemoryDB>> {
+        self.queue.pop_front()
+    }
+
+    fn finished_height(&mut self, number: u64) {
+        self.finished.push(number);
+    }
+}
+
+const CONTRACT: Address = Address::repeat_byte(0x90);
+
+/// A block with `txs` calls to a contract running `JUMPDEST, STOP`.
+fn block(number: u64, txs: usize) -> CommittedBlock<InMemoryDB> {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, vec![0x5b, 0x00]);
+    db.insert_account_info(common::CALLER, AccountInfo::from_balance(U256::from(10u64.pow(18))));
+    let transactions = (0..txs)
+        .map(|nonce| TxEnv {
+            caller: common::CALLER,
+            gas_limit: 100_000,
+            transact_to: TxKind::Call(CONTRACT),
+            nonce: Some(nonce as u64),
+            ..Default::default()
+        })
+        .collect();
+    CommittedBlock {
+        number,
+        env: Env::default(),
+        transactions,
+        db,
+    }
+}
+
+#[tokio::test]
+async fn test_summary_per_committed_block() {
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    tx.send(ChainNotification::Committed(vec![block(1, 2), block(2, 1)]))
+        .await
+        .unwrap();
+    tx.send(ChainNotification::Reverted { first_block: 2 }).await.unwrap();
+    tx.send(ChainNotification::Committed(vec![block(2, 0)])).await.unwrap();
+    drop(tx);
+
+    let mut exex = RestdExEx::new(rx);
+    let mut summaries = exex.summary_channel(8);
+    exex.run().await.unwrap();
+    // Close the channel so that draining it terminates
+    drop(exex);
+
+    let mut received = Vec::new();
+    while let Some(summary) = summaries.recv().await {
+        received.push(summary);
+    }
+    let numbers: Vec<_> = received.iter().map(|s| (s.number, s.tx_count)).collect();
+    assert_eq!(numbers, [(1, 2), (2, 1), (2, 0)]);
+
+    let first = &received[0];
+    assert_eq!(first.failed_txs, 0);
+    assert_eq!(first.total_calls, 2);
+    assert_eq!(first.total_steps, 4);
+    assert_eq!(first.top_contracts, [(CONTRACT, 2)]);
+    assert_eq!(received[2].total_steps, 0);
+}
+
+#[tokio::test]
+async fn test_full_channel_drops_summaries_and_reports_heights() {
+    let mut notifications = MockNotifications::default();
+    notifications
+        .queue
+        .push_back(ChainNotification::Committed(vec![block(5, 1), block(6, 1)]));
+    notifications
+        .queue
+        .push_back(ChainNotification::Committed(vec![block(7, 1)]));
+
+    let mut exex = RestdExEx::new(notifications);
+    let mut summaries = exex.summary_channel(1);
+    exex.run().await.unwrap();
+
+    // Nobody read the channel while the blocks were traced
+    assert_eq!(summaries.recv().await.unwrap().number, 5);
+    assert_eq!(exex.dropped_summaries(), 2);
+    assert_eq!(exex.notifications().finished, [6, 7]);
+}
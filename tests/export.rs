@@ -0,0 +1,33 @@
+//! Integration tests for tracing a call straight to JSON.
+
+use restd::{
+    export::{trace_call_json, ExportError, TARGET},
+    ConfigError, HelloWorldInspectorConfig, OutputMode,
+};
+use serde_json::Value;
+
+#[test]
+fn test_trace_call_json_reports_the_call() {
+    let config = HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        trace_calls: true,
+        ..Default::default()
+    };
+    // PUSH1 0, PUSH1 0, REVERT
+    let json = trace_call_json(&[0x60, 0x00, 0x60, 0x00, 0xfd], &[0xab; 4], config).unwrap();
+    let report: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(report["step_count"], 3);
+    assert_eq!(report["calls"][0]["target"], TARGET.to_string().to_lowercase());
+    assert_eq!(report["calls"][0]["reverted"], true);
+    assert_eq!(report["reverts"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_trace_call_json_rejects_invalid_config() {
+    let config = HelloWorldInspectorConfig {
+        low_gas_percent: Some(101),
+        ..Default::default()
+    };
+    let err = trace_call_json(&[], &[], config).unwrap_err();
+    assert_eq!(err, ExportError::Config(ConfigError::LowGasPercentOutOfRange(101)));
+}
@@ -0,0 +1,142 @@
+//! Integration tests for classifying and summarizing failed frames.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    CallTraceInspector, FailureKind, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    PrettyPrintOptions,
+};
+use revm::InMemoryDB;
+
+const OUTER: Address = Address::repeat_byte(0xa0);
+const REVERTER: Address = Address::repeat_byte(0xa1);
+const SPINNER: Address = Address::repeat_byte(0xa2);
+const BAD_JUMP: Address = Address::repeat_byte(0xa3);
+
+/// Jumps back to its first instruction until the gas runs out.
+fn spin() -> Vec<u8> {
+    // JUMPDEST, PUSH1 0, JUMP
+    vec![0x5b, 0x60, 0x00, 0x56]
+}
+
+/// Jumps to a `PUSH1` instead of a `JUMPDEST`.
+fn bad_jump() -> Vec<u8> {
+    // PUSH1 0, JUMP
+    vec![0x60, 0x00, 0x56]
+}
+
+/// OUTER calls REVERTER twice, then SPINNER with little gas, then BAD_JUMP,
+/// and succeeds although every call fails.
+fn db() -> InMemoryDB {
+    let mut outer = common::call_asm(REVERTER, 0);
+    outer.extend(common::call_asm(REVERTER, 0));
+    outer.extend(common::call_asm_with_gas(SPINNER, 1_000));
+    outer.extend(common::call_asm(BAD_JUMP, 0));
+    outer.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, outer);
+    common::deploy(&mut db, REVERTER, common::revert_asm(b"nope"));
+    common::deploy(&mut db, SPINNER, spin());
+    common::deploy(&mut db, BAD_JUMP, bad_jump());
+    db
+}
+
+fn trace(to: Address) -> HelloWorldInspector {
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    common::run(&mut db(), common::call_env(to, vec![]), &mut inspector);
+    inspector
+}
+
+#[test]
+fn test_failures_are_counted_per_kind() {
+    let inspector = trace(OUTER);
+    let failures = inspector.failures();
+    let kinds: Vec<_> = failures.iter().map(|stats| stats.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        [
+            FailureKind::Revert,
+            FailureKind::OutOfGas,
+            FailureKind::InvalidJump
+        ]
+    );
+    let counts: Vec<_> = failures.iter().map(|stats| stats.count).collect();
+    assert_eq!(counts, [2, 1, 1]);
+
+    let revert = &failures[0].first;
+    assert_eq!(revert.address, REVERTER);
+    assert_eq!(revert.depth, 1);
+    assert_eq!(failures[1].first.address, SPINNER);
+    // The failing JUMP is the last instruction BAD_JUMP executed
+    let jump = &failures[2].first;
+    assert_eq!(jump.address, BAD_JUMP);
+    assert_eq!(jump.pc, Some(2));
+    assert_eq!(jump.depth, 1);
+}
+
+#[test]
+fn test_top_level_failure_is_counted() {
+    let inspector = trace(BAD_JUMP);
+    let [stats] = inspector.failures() else {
+        panic!("{:?}", inspector.failures());
+    };
+    assert_eq!(stats.kind, FailureKind::InvalidJump);
+    assert_eq!(stats.first.depth, 0);
+    assert_eq!(inspector.report().failures, inspector.failures());
+}
+
+#[test]
+fn test_report_merge_adds_counts() {
+    let mut report = trace(OUTER).report();
+    report.merge(trace(REVERTER).report());
+    assert_eq!(report.failures[0].kind, FailureKind::Revert);
+    assert_eq!(report.failures[0].count, 3);
+    assert_eq!(report.failures[0].first.depth, 1);
+}
+
+#[test]
+fn test_successful_run_reports_no_failures() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, common::countdown_loop());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(inspector.failures().is_empty());
+    let json = serde_json::to_string(&inspector.report()).unwrap();
+    assert!(!json.contains("failures"));
+}
+
+#[test]
+fn test_pretty_print_summarizes_failures() {
+    let failures = trace(OUTER).report().failures;
+    let mut tracer = CallTraceInspector::new();
+    common::run(&mut db(), common::call_env(OUTER, vec![]), &mut tracer);
+    let trace = tracer.into_trace();
+
+    let out = trace.pretty_print(PrettyPrintOptions {
+        failures,
+        ..Default::default()
+    });
+    let footer: Vec<_> = out
+        .lines()
+        .skip_while(|line| *line != "Failures:")
+        .collect();
+    assert_eq!(
+        footer,
+        [
+            "Failures:".to_string(),
+            format!("  revert ×2, first at {REVERTER} pc 42 depth 1"),
+            format!("  out of gas ×1, first at {SPINNER} pc 3 depth 1"),
+            format!("  invalid jump ×1, first at {BAD_JUMP} pc 2 depth 1"),
+        ]
+    );
+    assert!(!trace
+        .pretty_print(PrettyPrintOptions::default())
+        .contains("Failures:"));
+}
@@ -0,0 +1,171 @@
+//! Integration tests for forcing calls to fail with a fault injector.
+
+mod common;
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{
+    ConfigError, Failure, FailureKind, FaultRule, HelloWorldInspector, HelloWorldInspectorConfig,
+    OutputMode,
+};
+use revm::{
+    primitives::{ExecutionResult, HaltReason},
+    DatabaseRef, InMemoryDB,
+};
+
+const PARENT: Address = Address::repeat_byte(0x50);
+const TARGET: Address = Address::repeat_byte(0x51);
+const ORGANIC: Address = Address::repeat_byte(0x52);
+
+/// Calls each of `targets` in turn, storing whether the call succeeded in
+/// storage slot 0, 1, 2, ...
+fn parent_code(targets: &[Address]) -> Vec<u8> {
+    let mut code = Vec::new();
+    for (slot, target) in targets.iter().enumerate() {
+        let mut call = common::call_asm(*target, 0);
+        // Keep the success flag instead of popping it
+        call.pop();
+        code.extend(call);
+        code.extend([0x60, slot as u8, 0x55]);
+    }
+    code.push(0x00);
+    code
+}
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(
+        &mut db,
+        PARENT,
+        parent_code(&[TARGET, TARGET, ORGANIC, TARGET]),
+    );
+    common::deploy(&mut db, TARGET, vec![0x00]);
+    common::deploy(&mut db, ORGANIC, common::revert_asm(&[]));
+    db
+}
+
+fn inspector(rule: FaultRule) -> HelloWorldInspector {
+    HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .fail_call(rule)
+        .build()
+}
+
+fn success_flags(db: &InMemoryDB) -> Vec<u64> {
+    (0..4u64)
+        .map(|slot| {
+            db.storage_ref(PARENT, U256::from(slot))
+                .unwrap()
+                .to::<u64>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_revert_injected_into_second_call() {
+    let data = Bytes::from_static(&[0xde, 0xad]);
+    let mut inspector =
+        inspector(FaultRule::fail_call(TARGET, Failure::Revert(data.clone())).nth(2));
+    let mut db = db();
+    let result = common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector);
+    assert!(result.is_success());
+    // The parent saw the second call to TARGET fail, and the organic revert
+    assert_eq!(success_flags(&db), vec![1, 0, 0, 1]);
+
+    let injected = inspector.injected_faults();
+    assert_eq!(injected.len(), 1);
+    assert_eq!(injected[0].target, TARGET);
+    assert_eq!(injected[0].depth, 1);
+    assert_eq!(injected[0].rule, 0);
+    assert_eq!(injected[0].failure, Failure::Revert(data));
+
+    let report = inspector.report();
+    let calls = &report.calls;
+    assert_eq!(calls.len(), 5);
+    assert!(calls[2].reverted && calls[2].injected);
+    assert!(calls[3].reverted && !calls[3].injected);
+    assert!(!calls[1].injected && !calls[4].injected);
+    let organic: Vec<_> = report.organic_failed_calls().collect();
+    assert_eq!(organic.len(), 1);
+    assert_eq!(organic[0].target, ORGANIC);
+
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].kind, FailureKind::Revert);
+    assert_eq!(
+        (report.failures[0].count, report.failures[0].injected),
+        (2, 1)
+    );
+    let value = serde_json::to_value(&report).unwrap();
+    assert_eq!(value["calls"][2]["injected"], true);
+    assert!(value["calls"][3].get("injected").is_none());
+    assert_eq!(value["injected_faults"][0]["failure"]["revert"], "0xdead");
+
+    // The count starts over with every transaction
+    let mut db = self::db();
+    common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector);
+    assert_eq!(success_flags(&db), vec![1, 0, 0, 1]);
+    assert_eq!(inspector.injected_faults().len(), 2);
+    assert_eq!(inspector.injected_faults()[1].tx_index, 1);
+}
+
+#[test]
+fn test_out_of_gas_injected_by_selector() {
+    let rule = FaultRule::fail_call(TARGET, Failure::OutOfGas).selector([0xa9, 0x05, 0x9c, 0xbb]);
+    let mut inspector = inspector(rule);
+    let mut db = db();
+
+    let env = common::call_env(TARGET, vec![0xa9, 0x05, 0x9c, 0xbb, 0x00]);
+    match common::run(&mut db, env, &mut inspector) {
+        ExecutionResult::Halt { reason, gas_used } => {
+            assert!(matches!(reason, HaltReason::OutOfGas(_)));
+            assert_eq!(gas_used, 1_000_000);
+        }
+        other => panic!("expected an injected out of gas halt, got {other:?}"),
+    }
+    // The target's code never ran
+    assert_eq!(inspector.step_count, 0);
+
+    // Other functions and calls without calldata execute normally
+    let env = common::call_env(TARGET, vec![0x12, 0x34, 0x56, 0x78]);
+    assert!(common::run(&mut db, env, &mut inspector).is_success());
+    assert!(common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector).is_success());
+    assert_eq!(success_flags(&db), vec![1, 1, 0, 1]);
+
+    assert_eq!(inspector.injected_faults().len(), 1);
+    assert_eq!(inspector.injected_faults()[0].depth, 0);
+    let failures = inspector.failures();
+    let oog = failures
+        .iter()
+        .find(|stats| stats.kind == FailureKind::OutOfGas)
+        .unwrap();
+    assert_eq!((oog.count, oog.injected), (1, 1));
+}
+
+#[test]
+fn test_rules_from_toml() {
+    let config = HelloWorldInspectorConfig::from_toml_str(
+        r#"
+        output = "silent"
+
+        [[faults.rules]]
+        target = "0x5151515151515151515151515151515151515151"
+        nth = 2
+        with = { revert = "0x" }
+        "#,
+    )
+    .unwrap();
+    let mut inspector = HelloWorldInspector::with_config(config);
+    let mut db = db();
+    common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector);
+    assert_eq!(success_flags(&db), vec![1, 0, 0, 1]);
+
+    let err = HelloWorldInspectorConfig::from_toml_str(
+        r#"
+        [[faults.rules]]
+        target = "0x5151515151515151515151515151515151515151"
+        nth = 0
+        with = "out_of_gas"
+        "#,
+    )
+    .unwrap_err();
+    assert_eq!(err, ConfigError::ZeroFaultNth(0));
+}
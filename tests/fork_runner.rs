@@ -0,0 +1,85 @@
+//! Integration tests for running a transaction against variants of a prestate.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{Revert, SolError};
+use restd::{ForkRunner, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+const VAULT: Address = Address::repeat_byte(0x70);
+
+/// The vault reverts with "paused" if slot 0 is set and emits a log
+/// otherwise.
+fn base() -> InMemoryDB {
+    // SLOAD(0), JUMPI to the revert if set, LOG0(0, 0), STOP
+    let mut code = vec![
+        0x60, 0x00, 0x54, 0x60, 0x0c, 0x57, 0x60, 0x00, 0x60, 0x00, 0xa0, 0x00, 0x5b,
+    ];
+    code.extend(common::revert_asm(&Revert::from("paused").abi_encode()));
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, VAULT, code);
+    db
+}
+
+fn config() -> HelloWorldInspectorConfig {
+    HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_variants_with_different_storage_diverge() {
+    let mut runner = ForkRunner::new(base());
+    runner.add_variant("open", |_| {});
+    runner.add_variant("paused", |db| {
+        db.insert_account_storage(VAULT, U256::ZERO, U256::from(1))
+            .unwrap();
+    });
+    assert_eq!(
+        runner.variant_names().collect::<Vec<_>>(),
+        ["open", "paused"]
+    );
+
+    let env = common::call_env(VAULT, Vec::new());
+    let fork = runner.run(env.clone(), config()).unwrap();
+    let open = fork.variant("open").unwrap();
+    let paused = fork.variant("paused").unwrap();
+    assert_eq!((open.index, paused.index), (0, 1));
+    assert_eq!(open.report.logs.len(), 1);
+    assert!(open.report.reverts.is_empty());
+    assert!(paused.report.logs.is_empty());
+    assert_eq!(paused.report.reverts.len(), 1);
+    assert_eq!(paused.report.reverts[0].reason.to_string(), "paused");
+
+    let comparison = &fork.comparison;
+    assert_eq!(comparison.succeeded, ["open"]);
+    assert_eq!(comparison.failed, ["paused"]);
+    assert!(comparison.success_differs());
+    assert!(comparison.gas_differs);
+    assert!(comparison.logs_differ);
+    assert!(comparison.diverged());
+
+    // Runs commit nothing, to the variants or the base
+    assert_eq!(runner.run(env, config()).unwrap(), fork);
+    assert!(runner.base().accounts[&VAULT].storage.is_empty());
+}
+
+#[test]
+fn test_identical_variants_agree() {
+    let mut runner = ForkRunner::new(base());
+    for name in ["a", "b"] {
+        runner.add_variant(name, |db| {
+            db.insert_account_storage(VAULT, U256::from(1), U256::from(7))
+                .unwrap();
+        });
+    }
+    let fork = runner
+        .run(common::call_env(VAULT, Vec::new()), config())
+        .unwrap();
+    assert_eq!(fork.variants.len(), 2);
+    assert_eq!(fork.comparison.succeeded, ["a", "b"]);
+    assert!(!fork.comparison.diverged());
+    assert_eq!(fork.variants[0].1.report, fork.variants[1].1.report);
+}
@@ -0,0 +1,92 @@
+//! Integration tests for low-gas alarms and out-of-gas frames.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, LowGasAlarm, OutputMode};
+use revm::{interpreter::opcode, primitives::ExecutionResult, InMemoryDB};
+
+const PARENT: Address = Address::repeat_byte(0x20);
+const LOOP: Address = Address::repeat_byte(0x21);
+
+/// Gas given to the loop, 21 short of [`common::COUNTDOWN_LOOP_GAS`]
+const LOOP_GAS: u16 = 60;
+
+fn inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        low_gas_percent: Some(10),
+        ..Default::default()
+    })
+}
+
+/// The loop's second `JUMPI` leaves 5 of its 60 gas, below 10%.
+fn loop_alarm(depth: u64) -> LowGasAlarm {
+    LowGasAlarm {
+        address: LOOP,
+        pc: 10,
+        opcode: opcode::JUMPI,
+        depth,
+        gas_limit: LOOP_GAS as u64,
+        gas_remaining: 5,
+        tx_index: 0,
+    }
+}
+
+#[test]
+fn test_starved_subcall_raises_alarm_and_runs_out_of_gas() {
+    let mut parent = common::call_asm_with_gas(LOOP, LOOP_GAS);
+    parent.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, PARENT, parent);
+    common::deploy(&mut db, LOOP, common::countdown_loop());
+
+    let mut inspector = inspector();
+    let result = common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    // Forwarding gas to the loop does not count against the parent
+    assert_eq!(inspector.low_gas_alarms(), [loop_alarm(1)]);
+    let oog = inspector.oog_frames();
+    assert_eq!(oog.len(), 1);
+    assert_eq!((oog[0].address, oog[0].depth), (LOOP, 1));
+    assert_eq!(oog[0].gas_limit, LOOP_GAS as u64);
+    assert_eq!(oog[0].reason, "OutOfGas");
+    let report = inspector.report();
+    assert_eq!(report.low_gas_alarms, inspector.low_gas_alarms());
+    assert_eq!(report.oog_frames, oog);
+}
+
+#[test]
+fn test_tight_transaction_gas_limit() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, LOOP, common::countdown_loop());
+    let mut env = common::call_env(LOOP, vec![]);
+    env.tx.gas_limit = 21_000 + LOOP_GAS as u64;
+
+    let mut inspector = inspector();
+    let result = common::run(&mut db, env, &mut inspector);
+    assert!(matches!(result, ExecutionResult::Halt { .. }));
+
+    // Only the first crossing in the frame is recorded
+    assert_eq!(inspector.low_gas_alarms(), [loop_alarm(0)]);
+    assert_eq!(inspector.oog_frames().len(), 1);
+    assert_eq!(inspector.oog_frames()[0].depth, 0);
+}
+
+#[test]
+fn test_no_alarm_without_threshold() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, LOOP, common::countdown_loop());
+    let mut env = common::call_env(LOOP, vec![]);
+    env.tx.gas_limit = 21_000 + common::COUNTDOWN_LOOP_GAS;
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, env, &mut inspector);
+    assert!(result.is_success());
+    assert!(inspector.low_gas_alarms().is_empty());
+    assert!(inspector.oog_frames().is_empty());
+}
@@ -0,0 +1,103 @@
+//! Integration tests for calls granted less gas than they asked for by the
+//! 63/64 rule.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    CallTraceInspector, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    PrettyPrintOptions, ProfilingMode,
+};
+use revm::InMemoryDB;
+
+const OUTER: Address = Address::repeat_byte(0x30);
+const MIDDLE: Address = Address::repeat_byte(0x31);
+const INNER: Address = Address::repeat_byte(0x32);
+
+/// Gas each call asks for; MIDDLE is granted about this much, so it cannot
+/// pass all of it on.
+const REQUESTED: u16 = 50_000;
+
+/// OUTER calls MIDDLE, which calls INNER, each asking for [`REQUESTED`] gas.
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, common::call_asm_with_gas(MIDDLE, REQUESTED));
+    common::deploy(&mut db, MIDDLE, common::call_asm_with_gas(INNER, REQUESTED));
+    common::deploy(&mut db, INNER, vec![0x00]);
+    db
+}
+
+fn inspector(profiling: ProfilingMode) -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        profiling,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_nested_call_is_capped() {
+    let mut inspector = inspector(ProfilingMode::Full);
+    let result = common::run(&mut db(), common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let calls = inspector.gas_by_call();
+    assert_eq!(calls.len(), 3);
+    // The transaction's call has no calling instruction
+    assert_eq!(calls[0].gas_requested, None);
+    assert!(!calls[0].gas_capped);
+    // OUTER has plenty of gas left to forward
+    assert_eq!(calls[1].gas_requested, Some(REQUESTED.into()));
+    assert_eq!(calls[1].gas_limit, u64::from(REQUESTED));
+    assert!(!calls[1].gas_capped);
+    // MIDDLE spent some of its 50000 before calling and keeps 1/64 of the rest
+    assert_eq!(calls[2].gas_requested, Some(REQUESTED.into()));
+    assert!(calls[2].gas_limit < u64::from(REQUESTED) * 63 / 64);
+    assert!(calls[2].gas_capped);
+
+    let report = inspector.report();
+    let capped: Vec<_> = report.gas_capped_frames().collect();
+    assert_eq!(capped.len(), 1);
+    assert_eq!(capped[0].target, INNER);
+    assert_eq!(capped[0].depth, calls[1].depth + 1);
+    let value = serde_json::to_value(&report).unwrap();
+    assert_eq!(value["calls"][2]["gas_capped"], true);
+    assert!(value["calls"][0].get("gas_capped").is_none());
+}
+
+#[test]
+fn test_pretty_print_marks_capped_frame() {
+    let mut tracer = CallTraceInspector::new();
+    common::run(&mut db(), common::call_env(OUTER, vec![]), &mut tracer);
+    let trace = tracer.into_trace();
+    let root = trace.root().unwrap();
+    let inner = &root.children[0].children[0];
+    assert!(inner.gas_capped);
+    assert!(!root.gas_capped && !root.children[0].gas_capped);
+
+    let printed = trace.pretty_print(PrettyPrintOptions::default());
+    let marked: Vec<_> = printed
+        .lines()
+        .filter(|line| line.contains("⚠ gas capped"))
+        .collect();
+    assert_eq!(marked.len(), 1, "{printed}");
+    assert!(marked[0].contains(&INNER.to_string()), "{printed}");
+    assert!(
+        marked[0].ends_with(&format!(
+            "(requested {REQUESTED}, granted {})",
+            inner.gas_limit
+        )),
+        "{printed}"
+    );
+}
+
+#[test]
+fn test_counters_mode_does_not_read_gas_arguments() {
+    let mut inspector = inspector(ProfilingMode::Counters);
+    common::run(&mut db(), common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(inspector
+        .gas_by_call()
+        .iter()
+        .all(|call| call.gas_requested.is_none()));
+    assert_eq!(inspector.gas_capped_frames().count(), 0);
+}
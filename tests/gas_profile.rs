@@ -0,0 +1,90 @@
+//! Integration tests for gas attribution per opcode and category.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{GasCategory, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+fn inspector(attribute_subcall_gas: bool) -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        attribute_subcall_gas,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_storage_heavy_code() {
+    let contract = Address::repeat_byte(0x70);
+    let mut db = InMemoryDB::default();
+    // SSTORE(0, 1), SSTORE(1, 1), SLOAD(0), POP, STOP
+    let code = vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x01, 0x60, 0x01, 0x55, 0x60, 0x00, 0x54, 0x50, 0x00,
+    ];
+    common::deploy(&mut db, contract, code);
+
+    let mut inspector = inspector(false);
+    common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    let profile = inspector.gas_profile();
+
+    // Two cold zero-to-nonzero writes and a warm read
+    assert_eq!(profile.by_opcode["SSTORE"], 2 * 22_100);
+    assert_eq!(profile.by_opcode["SLOAD"], 100);
+    assert_eq!(profile.category(GasCategory::Storage), 2 * 22_100 + 100);
+    assert_eq!(profile.category(GasCategory::Arithmetic), 0);
+    assert_eq!(profile.total(), inspector.gas_by_call()[0].gas_used);
+}
+
+#[test]
+fn test_arithmetic_heavy_code() {
+    let contract = Address::repeat_byte(0x70);
+    let mut db = InMemoryDB::default();
+    // PUSH1 2, then 10 x (DUP1, MUL), then 10 x (PUSH1 1, ADD), POP, STOP
+    let mut code = vec![0x60, 0x02];
+    for _ in 0..10 {
+        code.extend_from_slice(&[0x80, 0x02]);
+    }
+    for _ in 0..10 {
+        code.extend_from_slice(&[0x60, 0x01, 0x01]);
+    }
+    code.extend_from_slice(&[0x50, 0x00]);
+    common::deploy(&mut db, contract, code);
+
+    let mut inspector = inspector(false);
+    common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    let profile = inspector.gas_profile();
+
+    assert_eq!(profile.by_opcode["MUL"], 10 * 5);
+    assert_eq!(profile.by_opcode["ADD"], 10 * 3);
+    assert_eq!(profile.category(GasCategory::Arithmetic), 80);
+    assert_eq!(profile.category(GasCategory::Storage), 0);
+    assert!(profile.category(GasCategory::Arithmetic) > profile.category(GasCategory::Other));
+}
+
+#[test]
+fn test_subcall_gas_is_only_attributed_when_enabled() {
+    let outer = Address::repeat_byte(0x70);
+    let inner = Address::repeat_byte(0x71);
+    let mut db = InMemoryDB::default();
+    let mut code = common::call_asm(inner, 0);
+    code.push(0x00);
+    common::deploy(&mut db, outer, code);
+    common::deploy(&mut db, inner, common::countdown_loop());
+
+    let mut excluded = inspector(false);
+    common::run(&mut db.clone(), common::call_env(outer, vec![]), &mut excluded);
+    let mut attributed = inspector(true);
+    common::run(&mut db, common::call_env(outer, vec![]), &mut attributed);
+
+    let excluded = excluded.gas_profile();
+    let attributed = attributed.gas_profile();
+    // A cold account access, without the gas forwarded to the callee
+    assert_eq!(excluded.by_opcode["CALL"], 2_600);
+    assert_eq!(
+        attributed.by_opcode["CALL"],
+        excluded.by_opcode["CALL"] + common::COUNTDOWN_LOOP_GAS
+    );
+    // The callee's own instructions are profiled either way
+    assert_eq!(excluded.by_opcode["JUMPI"], attributed.by_opcode["JUMPI"]);
+}
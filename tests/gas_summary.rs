@@ -0,0 +1,88 @@
+//! Integration tests for the end-of-transaction gas summary.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    gas_summary::refund_quotient, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+};
+use revm::{
+    primitives::{ExecutionResult, SpecId},
+    InMemoryDB,
+};
+
+const TARGET: Address = Address::repeat_byte(0x20);
+
+/// SSTORE(0, 1), SSTORE(0, 0), STOP: sets a slot and clears it again
+const SET_AND_CLEAR: [u8; 11] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55, 0x00];
+
+fn trace(code: &[u8], data: Vec<u8>) -> (HelloWorldInspector, ExecutionResult) {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, TARGET, code.to_vec());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(TARGET, data), &mut inspector);
+    (inspector, result)
+}
+
+#[test]
+fn test_cleared_slot_refund_is_capped() {
+    let (inspector, result) = trace(&SET_AND_CLEAR, vec![]);
+    let ExecutionResult::Success {
+        gas_used,
+        gas_refunded,
+        ..
+    } = result
+    else {
+        panic!("transaction should succeed: {result:?}");
+    };
+
+    let summary = inspector.gas_summary().unwrap();
+    assert_eq!(summary.gas_limit, 1_000_000);
+    assert_eq!(summary.intrinsic_gas, 21_000);
+    assert_eq!(summary.gas_used, summary.intrinsic_gas + summary.execution_gas);
+    assert_eq!(summary.execution_gas, inspector.gas_used());
+    // Setting a fresh slot and clearing it in the same transaction refunds
+    // all but the warm read
+    assert_eq!(summary.gas_refunded, 19_900);
+    assert_eq!(summary.refund_cap, summary.gas_used / 5);
+    assert_eq!(summary.effective_refund(), summary.refund_cap);
+    assert_eq!(summary.effective_refund(), gas_refunded);
+    assert_eq!(summary.effective_gas_used, gas_used);
+    assert_eq!(inspector.report().gas_summary, Some(summary));
+}
+
+#[test]
+fn test_intrinsic_gas_counts_calldata_bytes() {
+    let (inspector, result) = trace(&[0x00], vec![0, 0, 1, 2, 0]);
+    assert!(result.is_success());
+
+    let summary = inspector.gas_summary().unwrap();
+    assert_eq!(summary.calldata_zero_bytes, 3);
+    assert_eq!(summary.calldata_nonzero_bytes, 2);
+    assert_eq!(summary.intrinsic_gas, 21_000 + 3 * 4 + 2 * 16);
+    assert_eq!(summary.gas_refunded, 0);
+    assert_eq!(summary.effective_gas_used, result.gas_used());
+}
+
+#[test]
+fn test_failed_transaction_gets_no_refund() {
+    // SET_AND_CLEAR, then REVERT(0, 0) instead of STOP
+    let mut code = SET_AND_CLEAR[..10].to_vec();
+    code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xfd]);
+    let (inspector, result) = trace(&code, vec![]);
+    assert!(!result.is_success());
+
+    let summary = inspector.gas_summary().unwrap();
+    assert_eq!(summary.gas_refunded, 0);
+    assert_eq!(summary.effective_gas_used, result.gas_used());
+}
+
+#[test]
+fn test_refund_quotient_follows_london() {
+    assert_eq!(refund_quotient(SpecId::BERLIN), 2);
+    assert_eq!(refund_quotient(SpecId::LONDON), 5);
+    assert_eq!(refund_quotient(SpecId::CANCUN), 5);
+}
@@ -0,0 +1,44 @@
+//! Compares the Geth `callTracer` output against a checked-in golden file.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{Revert, SolError};
+use restd::CallTraceInspector;
+use revm::InMemoryDB;
+
+const GOLDEN: &str = include_str!("fixtures/geth_call_trace.json");
+
+#[test]
+fn test_geth_call_trace_matches_golden() {
+    let a = Address::repeat_byte(0xaa);
+    let b = Address::repeat_byte(0xbb);
+    let c = Address::repeat_byte(0xcc);
+    let d = Address::repeat_byte(0xdd);
+
+    // A: CALL B with 1 wei, STATICCALL D, STOP
+    let mut code_a = common::call_asm(b, 1);
+    code_a.extend(common::static_call_asm(d));
+    code_a.push(0x00);
+    // B: CALL C, STOP
+    let mut code_b = common::call_asm(c, 0);
+    code_b.push(0x00);
+    // C: revert with Error("nope")
+    let code_c = common::revert_asm(&Revert::from("nope").abi_encode());
+    // D: return 0x2a as a word
+    let code_d = common::return_asm(&U256::from(42).to_be_bytes::<32>());
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, a, code_a, U256::from(10));
+    common::deploy(&mut db, b, code_b);
+    common::deploy(&mut db, c, code_c);
+    common::deploy(&mut db, d, code_d);
+
+    let mut inspector = CallTraceInspector::new();
+    common::run(&mut db, common::call_env(a, vec![0x12, 0x34]), &mut inspector);
+
+    let frame = inspector.into_trace().to_geth_call_trace().expect("traced frame");
+    let actual: serde_json::Value = serde_json::to_value(&frame).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(GOLDEN).unwrap();
+    assert_eq!(actual, expected, "actual: {}", serde_json::to_string_pretty(&frame).unwrap());
+}
@@ -0,0 +1,78 @@
+//! Integration tests for closures attached to the inspector hooks.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use alloy_primitives::{Address, B256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_call_callbacks_run_in_registration_order() {
+    let outer = Address::repeat_byte(0x20);
+    let inner = Address::repeat_byte(0x21);
+    let mut code = common::call_asm(inner, 0);
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, code);
+    common::deploy(&mut db, inner, vec![0x00]);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (first, second) = (seen.clone(), seen.clone());
+    let mut inspector = silent_inspector()
+        .on_call(move |call| first.lock().unwrap().push(("first", call.target, call.depth)))
+        .on_call(move |call| second.lock().unwrap().push(("second", call.target, call.depth)));
+    let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            ("first", outer, 1),
+            ("second", outer, 1),
+            ("first", inner, 2),
+            ("second", inner, 2),
+        ]
+    );
+}
+
+#[test]
+fn test_step_call_end_and_log_callbacks() {
+    let contract = Address::repeat_byte(0x20);
+    let topic = B256::repeat_byte(0xaa);
+    // LOG1(0, 0, topic), STOP
+    let mut code = vec![0x7f];
+    code.extend_from_slice(topic.as_slice());
+    code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xa1, 0x00]);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, code);
+
+    let steps = Arc::new(Mutex::new(Vec::new()));
+    let ends = Arc::new(Mutex::new(Vec::new()));
+    let logs = Arc::new(Mutex::new(Vec::new()));
+    let (s, e, l) = (steps.clone(), ends.clone(), logs.clone());
+    let mut inspector = silent_inspector()
+        .on_step(move |step| s.lock().unwrap().push(step.opcode))
+        .on_call_end(move |end| e.lock().unwrap().push((end.target, end.success)))
+        .on_log(move |log| l.lock().unwrap().push(log.topic0()));
+    let result = common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    assert_eq!(*steps.lock().unwrap(), vec![0x7f, 0x60, 0x60, 0xa1, 0x00]);
+    assert_eq!(*ends.lock().unwrap(), vec![(contract, true)]);
+    assert_eq!(*logs.lock().unwrap(), vec![Some(topic)]);
+
+    // Callbacks survive a reset
+    inspector.reset();
+    let result = common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    assert!(result.is_success());
+    assert_eq!(steps.lock().unwrap().len(), 10);
+}
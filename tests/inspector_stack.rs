@@ -0,0 +1,152 @@
+//! Integration tests for running several inspectors through an `InspectorStack`.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use alloy_primitives::{Address, Bytes};
+use restd::{
+    CallTraceInspector, HelloWorldInspector, HelloWorldInspectorConfig, InspectorStack,
+    OutputMode, StructLogInspector,
+};
+use revm::{
+    inspector_handle_register,
+    interpreter::{CallInputs, CallOutcome, Gas, InstructionResult, InterpreterResult},
+    primitives::{Env, ExecutionResult},
+    Database, Evm, EvmContext, InMemoryDB, Inspector,
+};
+
+/// Appends `name:hook` to a shared journal for every call hook it sees.
+#[derive(Debug)]
+struct Recorder {
+    name: &'static str,
+    journal: Arc<Mutex<Vec<String>>>,
+}
+
+impl<DB: Database> Inspector<DB> for Recorder {
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.journal.lock().unwrap().push(format!("{}:call", self.name));
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.journal.lock().unwrap().push(format!("{}:call_end", self.name));
+        outcome
+    }
+}
+
+/// Reverts every call without executing it.
+#[derive(Debug)]
+struct Blocker;
+
+impl<DB: Database> Inspector<DB> for Blocker {
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        Some(CallOutcome::new(
+            InterpreterResult::new(
+                InstructionResult::Revert,
+                Bytes::new(),
+                Gas::new(inputs.gas_limit),
+            ),
+            inputs.return_memory_offset.clone(),
+        ))
+    }
+}
+
+/// Executes `env` against an owned database, since the stack is tied to a
+/// single database type rather than every `&mut InMemoryDB` lifetime.
+fn run(db: InMemoryDB, env: Env, stack: &mut InspectorStack<InMemoryDB>) -> ExecutionResult {
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_env(Box::new(env))
+        .with_external_context(stack)
+        .append_handler_register(inspector_handle_register)
+        .build();
+    evm.transact_commit().expect("transaction should execute")
+}
+
+fn recorder(name: &'static str, journal: &Arc<Mutex<Vec<String>>>) -> Recorder {
+    Recorder {
+        name,
+        journal: journal.clone(),
+    }
+}
+
+#[test]
+fn test_stack_fans_out_to_every_inspector() {
+    let outer = Address::repeat_byte(0x60);
+    let inner = Address::repeat_byte(0x61);
+    let mut db = InMemoryDB::default();
+    let mut code = common::call_asm(inner, 0);
+    code.push(0x00);
+    common::deploy(&mut db, outer, code);
+    common::deploy(&mut db, inner, vec![0x5b, 0x00]);
+
+    let mut stack = InspectorStack::new()
+        .with(HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+            output: OutputMode::Silent,
+            ..Default::default()
+        }))
+        .with(CallTraceInspector::new())
+        .with(StructLogInspector::new());
+    run(db, common::call_env(outer, vec![]), &mut stack);
+
+    let counter = stack.get::<HelloWorldInspector>().unwrap();
+    assert_eq!(counter.calls(), 2);
+    let steps = counter.steps();
+    assert_eq!(stack.get::<StructLogInspector>().unwrap().struct_logs().len() as u64, steps);
+
+    let trace = stack.remove::<CallTraceInspector>().unwrap().into_trace();
+    assert_eq!(trace.nodes().count(), 2);
+    assert_eq!(stack.len(), 2);
+    assert!(stack.get::<CallTraceInspector>().is_none());
+}
+
+#[test]
+fn test_hooks_run_in_insertion_order() {
+    let contract = Address::repeat_byte(0x60);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, vec![0x00]);
+
+    let journal = Arc::default();
+    let mut stack = InspectorStack::new()
+        .with(recorder("first", &journal))
+        .with(recorder("second", &journal));
+    run(db, common::call_env(contract, vec![]), &mut stack);
+
+    assert_eq!(
+        *journal.lock().unwrap(),
+        ["first:call", "second:call", "first:call_end", "second:call_end"]
+    );
+}
+
+#[test]
+fn test_outcome_from_call_short_circuits_later_inspectors() {
+    let contract = Address::repeat_byte(0x60);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, vec![0x5b, 0x00]);
+
+    let journal = Arc::default();
+    let mut stack = InspectorStack::new()
+        .with(recorder("before", &journal))
+        .with(Blocker)
+        .with(recorder("after", &journal))
+        .with(StructLogInspector::new());
+    let result = run(db, common::call_env(contract, vec![]), &mut stack);
+
+    assert!(!result.is_success());
+    assert_eq!(*journal.lock().unwrap(), ["before:call", "before:call_end"]);
+    assert!(stack.get::<StructLogInspector>().unwrap().struct_logs().is_empty());
+}
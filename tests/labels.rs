@@ -0,0 +1,179 @@
+//! Integration tests for address labels.
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_primitives::Address;
+use restd::{
+    AddressFilter, CallTraceInspector, ConfigError, DotOptions, HelloWorldInspector,
+    HelloWorldInspectorConfig, Labels, OutputMode, PrettyPrintOptions,
+};
+use revm::InMemoryDB;
+
+const ENTRY: Address = Address::repeat_byte(0x11);
+const NOISY: Address = Address::repeat_byte(0x22);
+const LEAF: Address = Address::repeat_byte(0x33);
+
+/// ENTRY calls NOISY, which calls LEAF.
+fn setup() -> InMemoryDB {
+    let mut entry = common::call_asm(NOISY, 0);
+    entry.push(0x00);
+    let mut noisy = common::call_asm(LEAF, 0);
+    noisy.push(0x00);
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, ENTRY, entry);
+    common::deploy(&mut db, NOISY, noisy);
+    common::deploy(&mut db, LEAF, vec![0x00]);
+    db
+}
+
+fn labels() -> Labels {
+    [(ENTRY, "Entry"), (NOISY, "Noisy"), (LEAF, "Leaf")].into()
+}
+
+fn targets(inspector: &HelloWorldInspector) -> Vec<Address> {
+    inspector
+        .gas_by_call()
+        .iter()
+        .map(|record| record.target)
+        .collect()
+}
+
+#[test]
+fn test_labels_appear_in_pretty_output() {
+    let mut db = setup();
+    let mut inspector = CallTraceInspector::new();
+    common::run(&mut db, common::call_env(ENTRY, vec![]), &mut inspector);
+    let printed = inspector.into_trace().pretty_print(PrettyPrintOptions {
+        labels: Some(Arc::new(labels())),
+        ..Default::default()
+    });
+
+    assert!(
+        printed.contains("CALL Entry (0x1111…)::fallback()"),
+        "{printed}"
+    );
+    assert!(
+        printed.contains("CALL Noisy (0x2222…)::fallback()"),
+        "{printed}"
+    );
+    assert!(
+        printed.contains("CALL Leaf (0x3333…)::fallback()"),
+        "{printed}"
+    );
+}
+
+#[test]
+fn test_labels_appear_in_dot_output() {
+    let mut db = setup();
+    let mut inspector = CallTraceInspector::new();
+    common::run(&mut db, common::call_env(ENTRY, vec![]), &mut inspector);
+    let dot = inspector.into_trace().to_dot(DotOptions {
+        labels: labels(),
+        ..Default::default()
+    });
+
+    assert!(dot.contains("[label=\"Noisy (0x2222…)\"]"), "{dot}");
+    assert!(
+        dot.contains(&format!("[label=\"{}\"]", common::CALLER)),
+        "{dot}"
+    );
+}
+
+#[test]
+fn test_calls_record_their_label() {
+    let mut db = setup();
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .labels(labels())
+        .build();
+    common::run(&mut db, common::call_env(ENTRY, vec![]), &mut inspector);
+
+    let labels: Vec<_> = inspector
+        .gas_by_call()
+        .iter()
+        .map(|record| record.label.as_deref())
+        .collect();
+    assert_eq!(labels, vec![Some("Entry"), Some("Noisy"), Some("Leaf")]);
+    let json = serde_json::to_value(inspector.report()).unwrap();
+    assert_eq!(json["calls"][1]["label"], "Noisy");
+}
+
+#[test]
+fn test_builder_resolves_filter_labels() {
+    let mut db = setup();
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .labels(labels())
+        .exclude_label("Noisy")
+        .build();
+    common::run(&mut db, common::call_env(ENTRY, vec![]), &mut inspector);
+
+    assert_eq!(targets(&inspector), vec![ENTRY, LEAF]);
+}
+
+#[test]
+fn test_config_filter_labels_resolve_with_labels() {
+    let mut db = setup();
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        filter: AddressFilter {
+            include_labels: vec!["Leaf".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .with_labels(Arc::new(labels()));
+    common::run(&mut db, common::call_env(ENTRY, vec![]), &mut inspector);
+
+    assert_eq!(targets(&inspector), vec![LEAF]);
+}
+
+#[test]
+fn test_unknown_filter_label_is_rejected() {
+    let err = HelloWorldInspector::builder()
+        .labels(labels())
+        .include_label("Missing")
+        .try_build()
+        .unwrap_err();
+    assert!(matches!(err, ConfigError::UnknownLabel(name) if name == "Missing"));
+
+    // Filter labels need labels to resolve against
+    let err = HelloWorldInspector::builder()
+        .include_label("Leaf")
+        .try_build()
+        .unwrap_err();
+    assert!(matches!(err, ConfigError::UnknownLabel(name) if name == "Leaf"));
+}
+
+#[test]
+fn test_labels_load_from_json_and_toml_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let json = dir.path().join("labels.json");
+    std::fs::write(&json, format!(r#"{{"{NOISY}": "Noisy"}}"#)).unwrap();
+    let toml = dir.path().join("labels.toml");
+    std::fs::write(&toml, format!(r#""{NOISY}" = "Noisy""#)).unwrap();
+
+    let from_json = Labels::from_file(&json).unwrap();
+    assert_eq!(from_json.get(&NOISY), Some("Noisy"));
+    assert_eq!(Labels::from_file(&toml).unwrap(), from_json);
+    assert!(matches!(
+        Labels::from_file(dir.path().join("missing.json")),
+        Err(ConfigError::Labels(_))
+    ));
+}
+
+#[test]
+fn test_labels_are_shared_between_inspectors() {
+    let shared = Arc::new(labels());
+    let first = HelloWorldInspector::builder()
+        .labels(shared.clone())
+        .build();
+    let second = HelloWorldInspector::new().with_labels(shared.clone());
+
+    assert!(Arc::ptr_eq(first.labels().unwrap(), &shared));
+    assert!(Arc::ptr_eq(second.labels().unwrap(), &shared));
+    assert_eq!(Arc::strong_count(&shared), 3);
+}
@@ -0,0 +1,83 @@
+//! Integration tests for log collection.
+
+mod common;
+
+use alloy_primitives::{Address, B256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+/// Assembles `LOG1(0, 0, topic)`.
+fn log1_asm(topic: B256) -> Vec<u8> {
+    let mut code = vec![0x7f];
+    code.extend_from_slice(topic.as_slice());
+    code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xa1]);
+    code
+}
+
+#[test]
+fn test_logs_of_reverted_subcall_are_flagged() {
+    let outer = Address::repeat_byte(0x20);
+    let child = Address::repeat_byte(0x30);
+    let kept = B256::repeat_byte(0xaa);
+    let discarded = B256::repeat_byte(0xbb);
+
+    // Outer: LOG1(kept), CALL child, STOP
+    let mut code_outer = log1_asm(kept);
+    code_outer.extend(common::call_asm(child, 0));
+    code_outer.push(0x00);
+    // Child: LOG1(discarded), REVERT(0, 0)
+    let mut code_child = log1_asm(discarded);
+    code_child.extend(common::revert_asm(&[]));
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, code_outer);
+    common::deploy(&mut db, child, code_child);
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+    assert!(result.is_success());
+    assert_eq!(result.logs().len(), 1);
+
+    let logs = inspector.logs();
+    assert_eq!(logs.len(), 2);
+
+    assert_eq!(logs[0].address, outer);
+    assert_eq!(logs[0].topics, vec![kept]);
+    assert_eq!(logs[0].call_depth, 0);
+    assert_eq!(logs[0].step_index, 3);
+    assert!(!logs[0].reverted);
+
+    assert_eq!(logs[1].address, child);
+    assert_eq!(logs[1].call_depth, 1);
+    assert!(logs[1].reverted);
+
+    let by_topic: Vec<_> = inspector.logs_by_topic0(discarded).collect();
+    assert_eq!(by_topic, vec![&logs[1]]);
+    let by_address: Vec<_> = inspector.logs_by_address(outer).collect();
+    assert_eq!(by_address, vec![&logs[0]]);
+    assert_eq!(inspector.logs_by_topic0(B256::ZERO).count(), 0);
+}
+
+#[test]
+fn test_top_level_revert_flags_all_logs() {
+    let target = Address::repeat_byte(0x20);
+    let mut code = log1_asm(B256::repeat_byte(0xaa));
+    code.extend(common::revert_asm(&[]));
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, target, code);
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(target, vec![]), &mut inspector);
+    assert!(!result.is_success());
+
+    assert_eq!(inspector.logs().len(), 1);
+    assert!(inspector.logs()[0].reverted);
+}
@@ -0,0 +1,64 @@
+//! Integration tests for the `max_steps` recording limit.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+/// 10 x JUMPDEST, then `SSTORE(0, 1)` and STOP: 14 steps in total.
+fn code() -> Vec<u8> {
+    let mut code = vec![0x5b; 10];
+    code.extend_from_slice(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+    code
+}
+
+fn inspector(max_steps: Option<u64>) -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        max_steps,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_recording_stops_at_max_steps() {
+    let contract = Address::repeat_byte(0x40);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, code());
+
+    let mut inspector = inspector(Some(5));
+    let result = common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+
+    // Execution is not affected, only recording.
+    assert!(result.is_success());
+    assert_eq!(
+        db.accounts[&contract].storage.get(&U256::ZERO),
+        Some(&U256::from(1))
+    );
+
+    assert_eq!(inspector.steps(), 5);
+    assert_eq!(inspector.opcode_count(0x5b), 5);
+    assert!(inspector.storage_accesses().is_empty());
+    assert!(inspector.is_truncated());
+
+    let report = inspector.report();
+    assert!(report.truncated);
+    assert_eq!(report.step_count, 5);
+    assert_eq!(inspector.calls(), 1);
+}
+
+#[test]
+fn test_no_truncation_below_max_steps() {
+    let contract = Address::repeat_byte(0x40);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, code());
+
+    let mut inspector = inspector(Some(14));
+    common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+
+    assert_eq!(inspector.steps(), 14);
+    assert_eq!(inspector.storage_accesses().len(), 1);
+    assert!(!inspector.is_truncated());
+    assert!(!inspector.report().truncated);
+}
@@ -0,0 +1,101 @@
+//! Integration tests for per-frame memory expansion tracking.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{interpreter::opcode, InMemoryDB};
+
+const OUTER: Address = Address::repeat_byte(0x40);
+const BIG: Address = Address::repeat_byte(0x41);
+
+/// `MSTORE(0x10000, 42)`, `STOP`: memory jumps to 0x10000 + 32 bytes.
+const BIG_MSTORE: [u8; 8] = [0x60, 0x2a, 0x62, 0x01, 0x00, 0x00, 0x52, 0x00];
+const BIG_MEMORY: u64 = 0x10000 + 32;
+/// 3 gas per word plus words² / 512 for 2049 words.
+const BIG_MEMORY_GAS: u64 = 3 * 2049 + 2049 * 2049 / 512;
+
+fn trace(threshold: Option<u64>) -> HelloWorldInspector {
+    // MSTORE(0, 1), then CALL BIG
+    let mut outer = vec![0x60, 0x01, 0x60, 0x00, 0x52];
+    outer.extend(common::call_asm(BIG, 0));
+    outer.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, outer);
+    common::deploy(&mut db, BIG, BIG_MSTORE.to_vec());
+
+    let mut config = HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    };
+    if let Some(threshold) = threshold {
+        config.memory_expansion_threshold = threshold;
+    }
+    let mut inspector = HelloWorldInspector::with_config(config);
+    let result = common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_high_water_mark_and_expansion_gas() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, BIG, BIG_MSTORE.to_vec());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(BIG, vec![]), &mut inspector);
+    // Intrinsic gas, two pushes and MSTORE, and the expansion
+    assert_eq!(result.gas_used(), 21_000 + 3 + 3 + 3 + BIG_MEMORY_GAS);
+
+    let frames = inspector.memory_frames();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].address, BIG);
+    assert_eq!(frames[0].depth, 0);
+    assert_eq!(frames[0].high_water_bytes, BIG_MEMORY);
+    assert_eq!(frames[0].expansion_gas, BIG_MEMORY_GAS);
+    assert_eq!(inspector.report().memory_expansion_gas(), BIG_MEMORY_GAS);
+
+    // 64 KiB in one instruction is above the default 32 KiB
+    let expansions = inspector.memory_expansions();
+    assert_eq!(expansions.len(), 1);
+    assert_eq!(expansions[0].pc, 6);
+    assert_eq!(expansions[0].opcode, opcode::MSTORE);
+    assert_eq!(
+        (expansions[0].from_bytes, expansions[0].to_bytes),
+        (0, BIG_MEMORY)
+    );
+    assert_eq!(expansions[0].gas, BIG_MEMORY_GAS);
+}
+
+#[test]
+fn test_frames_are_tracked_separately() {
+    let inspector = trace(None);
+    let report = inspector.report();
+    // The subcall ends first
+    let frames = &report.memory_frames;
+    assert_eq!(frames.len(), 2);
+    assert_eq!((frames[0].address, frames[0].depth), (BIG, 1));
+    assert_eq!(frames[0].high_water_bytes, BIG_MEMORY);
+    assert_eq!((frames[1].address, frames[1].depth), (OUTER, 0));
+    assert_eq!(frames[1].high_water_bytes, 32);
+    assert_eq!(frames[1].expansion_gas, 3);
+
+    let top = report.top_memory_frames(1);
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].address, BIG);
+    assert_eq!(report.memory_expansion_gas(), BIG_MEMORY_GAS + 3);
+    assert_eq!(report.memory_expansions.len(), 1);
+    assert_eq!(report.memory_expansions[0].depth, 1);
+}
+
+#[test]
+fn test_threshold() {
+    assert!(trace(Some(0)).memory_expansions().is_empty());
+    assert!(trace(Some(BIG_MEMORY)).memory_expansions().is_empty());
+    let flagged = trace(Some(16));
+    // MSTORE(0, 1) adds exactly 32 bytes
+    assert_eq!(flagged.memory_expansions().len(), 2);
+    assert_eq!(flagged.memory_expansions()[0].address, OUTER);
+}
@@ -0,0 +1,63 @@
+//! Integration tests for the metrics recorded with the `metrics` feature.
+
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use std::collections::HashMap;
+
+use alloy_primitives::Address;
+use metrics_util::{
+    debugging::{DebugValue, DebuggingRecorder},
+    MetricKind,
+};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+#[test]
+fn test_counters_increase_after_tracing() {
+    let outer = Address::repeat_byte(0x20);
+    let reverter = Address::repeat_byte(0x21);
+    let mut code = common::call_asm(reverter, 0);
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, code);
+    common::deploy(&mut db, reverter, common::revert_asm(&[]));
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let steps = metrics::with_local_recorder(&recorder, || {
+        let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+            output: OutputMode::Silent,
+            ..Default::default()
+        });
+        let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+        assert!(result.is_success());
+        inspector.steps()
+    });
+
+    let metrics: HashMap<(MetricKind, String), DebugValue> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let (kind, key) = key.into_parts();
+            let labels: Vec<_> = key.labels().map(|l| (l.key(), l.value())).collect();
+            assert_eq!(labels, [("plugin", "hello-world-inspector")]);
+            ((kind, key.name().to_string()), value)
+        })
+        .collect();
+    let counter = |name: &str| match metrics.get(&(MetricKind::Counter, name.to_string())) {
+        Some(DebugValue::Counter(value)) => *value,
+        other => panic!("expected counter {name}, got {other:?}"),
+    };
+
+    assert_eq!(counter("restd_steps_total"), steps);
+    assert_eq!(counter("restd_calls_total"), 2);
+    assert_eq!(counter("restd_reverts_total"), 1);
+    assert_eq!(counter("restd_selfdestructs_total"), 0);
+    match &metrics[&(MetricKind::Histogram, "restd_call_gas_used".to_string())] {
+        DebugValue::Histogram(values) => assert_eq!(values.len(), 2),
+        other => panic!("expected a histogram, got {other:?}"),
+    }
+}
@@ -0,0 +1,127 @@
+//! Integration tests for answering calls with mocked return data.
+
+mod common;
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{HelloWorldInspector, MockRegistry, OutputMode};
+use revm::{DatabaseRef, InMemoryDB};
+
+const CONSUMER: Address = Address::repeat_byte(0x60);
+/// Oracle that is never deployed
+const ORACLE: Address = Address::repeat_byte(0x61);
+/// `latestAnswer()`
+const LATEST_ANSWER: [u8; 4] = [0x50, 0xd2, 0x5b, 0xcd];
+const MOCK_GAS: u64 = 2_600;
+
+/// Calls `selector` on [`ORACLE`] `rounds` times, storing twice each answer
+/// in storage slot 0, 1, 2, ...
+fn consumer_code(selector: [u8; 4], rounds: u8) -> Vec<u8> {
+    // MSTORE(0, selector << 224)
+    let mut code = vec![0x63];
+    code.extend_from_slice(&selector);
+    code.extend([0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52]);
+    for slot in 0..rounds {
+        // CALL(gas, ORACLE, 0, 0, 4, 32, 32), POP
+        code.extend([
+            0x60, 0x20, 0x60, 0x20, 0x60, 0x04, 0x60, 0x00, 0x60, 0x00, 0x73,
+        ]);
+        code.extend_from_slice(ORACLE.as_slice());
+        code.extend([0x5a, 0xf1, 0x50]);
+        // SSTORE(slot, MLOAD(32) * 2)
+        code.extend([0x60, 0x20, 0x51, 0x60, 0x02, 0x02, 0x60, slot, 0x55]);
+    }
+    code.push(0x00);
+    code
+}
+
+fn answer(value: u64) -> Bytes {
+    Bytes::from(U256::from(value).to_be_bytes::<32>().to_vec())
+}
+
+fn inspector(mocks: MockRegistry) -> HelloWorldInspector {
+    HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .mocks(mocks)
+        .build()
+}
+
+fn slot(db: &InMemoryDB, slot: u64) -> U256 {
+    db.storage_ref(CONSUMER, U256::from(slot)).unwrap()
+}
+
+#[test]
+fn test_caller_uses_mocked_answers_in_sequence() {
+    let mocks = MockRegistry::new()
+        .mock(ORACLE, LATEST_ANSWER, answer(100))
+        .mock(ORACLE, LATEST_ANSWER, answer(250))
+        .gas(MOCK_GAS);
+    let mut inspector = inspector(mocks);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONSUMER, consumer_code(LATEST_ANSWER, 3));
+
+    let result = common::run(&mut db, common::call_env(CONSUMER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    // The last answer repeats once the sequence is used up
+    assert_eq!(slot(&db, 0), U256::from(200));
+    assert_eq!(slot(&db, 1), U256::from(500));
+    assert_eq!(slot(&db, 2), U256::from(500));
+    let mocks = inspector.mocks().unwrap();
+    assert_eq!(mocks.times_called(ORACLE, LATEST_ANSWER), 3);
+    assert_eq!(mocks.times_called(ORACLE, [0; 4]), 0);
+
+    let report = inspector.report();
+    assert_eq!(report.calls.len(), 4);
+    assert!(!report.calls[0].mocked);
+    for call in &report.calls[1..] {
+        assert!(call.mocked && !call.no_code && !call.reverted);
+        assert_eq!(call.gas_used, MOCK_GAS);
+    }
+    let value = serde_json::to_value(&report).unwrap();
+    assert_eq!(value["calls"][1]["mocked"], true);
+    assert!(value["calls"][0].get("mocked").is_none());
+}
+
+#[test]
+fn test_unmocked_functions_execute_and_reset_rewinds() {
+    let mocks = MockRegistry::new().mock_sequence(ORACLE, LATEST_ANSWER, [answer(1), answer(2)]);
+    let mut inspector = inspector(mocks);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONSUMER, consumer_code(LATEST_ANSWER, 1));
+    common::run(&mut db, common::call_env(CONSUMER, vec![]), &mut inspector);
+    assert_eq!(slot(&db, 0), U256::from(2));
+
+    // The sequence goes on in the next transaction, unless the inspector is reset
+    common::run(&mut db, common::call_env(CONSUMER, vec![]), &mut inspector);
+    assert_eq!(slot(&db, 0), U256::from(4));
+    inspector.reset();
+    assert_eq!(
+        inspector
+            .mocks()
+            .unwrap()
+            .times_called(ORACLE, LATEST_ANSWER),
+        0
+    );
+    common::run(&mut db, common::call_env(CONSUMER, vec![]), &mut inspector);
+    assert_eq!(slot(&db, 0), U256::from(2));
+
+    // Another function of the oracle is called for real and has no code,
+    // so it returns nothing
+    let mut db = InMemoryDB::default();
+    common::deploy(
+        &mut db,
+        CONSUMER,
+        consumer_code([0x9a, 0x6f, 0xc8, 0xf5], 1),
+    );
+    common::run(&mut db, common::call_env(CONSUMER, vec![]), &mut inspector);
+    assert_eq!(slot(&db, 0), U256::ZERO);
+    assert_eq!(
+        inspector
+            .mocks()
+            .unwrap()
+            .times_called(ORACLE, LATEST_ANSWER),
+        1
+    );
+    let report = inspector.report();
+    let call = report.calls.last().unwrap();
+    assert!(!call.mocked && call.no_code);
+}
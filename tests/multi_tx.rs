@@ -0,0 +1,75 @@
+//! Integration tests for reusing one inspector across transactions.
+
+mod common;
+
+use alloy_primitives::Address;
+use alloy_sol_types::{Revert, SolError};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+fn setup() -> (InMemoryDB, Address, Address) {
+    let looper = Address::repeat_byte(0x10);
+    let reverter = Address::repeat_byte(0x30);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, looper, common::countdown_loop());
+    common::deploy(
+        &mut db,
+        reverter,
+        common::revert_asm(&Revert::from("second").abi_encode()),
+    );
+    (db, looper, reverter)
+}
+
+#[test]
+fn test_reset_and_take_report_gives_independent_reports() {
+    let (mut db, looper, reverter) = setup();
+    let mut inspector = silent_inspector();
+
+    common::run(&mut db, common::call_env(looper, vec![]), &mut inspector);
+    let first = inspector.reset_and_take_report();
+    common::run(&mut db, common::call_env(reverter, vec![]), &mut inspector);
+    let second = inspector.reset_and_take_report();
+
+    assert_eq!(first.call_count, 1);
+    assert_eq!(first.calls[0].target, looper);
+    assert_eq!(first.calls[0].gas_used, common::COUNTDOWN_LOOP_GAS);
+    assert!(first.reverts.is_empty());
+
+    assert_eq!(second.call_count, 1);
+    assert_eq!(second.calls[0].target, reverter);
+    assert!(second.calls[0].reverted);
+    assert_eq!(second.reverts.len(), 1);
+    assert_ne!(first.step_count, second.step_count);
+
+    // Nothing is left over after the last take
+    assert_eq!(inspector.report(), Default::default());
+    assert_eq!(inspector.config().output, OutputMode::Silent);
+}
+
+#[test]
+fn test_tx_index_attributes_records_without_reset() {
+    let (mut db, looper, reverter) = setup();
+    let mut inspector = silent_inspector();
+
+    common::run(&mut db, common::call_env(looper, vec![]), &mut inspector);
+    assert_eq!(inspector.tx_index, 0);
+    common::run(&mut db, common::call_env(reverter, vec![]), &mut inspector);
+    assert_eq!(inspector.tx_index, 1);
+
+    let calls = inspector.gas_by_call();
+    assert_eq!(calls.len(), 2);
+    assert_eq!((calls[0].target, calls[0].tx_index), (looper, 0));
+    assert_eq!((calls[1].target, calls[1].tx_index), (reverter, 1));
+    assert_eq!(inspector.reverts()[0].tx_index, 1);
+
+    inspector.reset();
+    assert_eq!(inspector.tx_index, 0);
+    assert!(inspector.gas_by_call().is_empty());
+}
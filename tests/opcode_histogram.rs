@@ -0,0 +1,37 @@
+//! Integration tests for the opcode histogram.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{interpreter::opcode, InMemoryDB};
+
+#[test]
+fn test_opcode_counts_for_push_push_add_stop() {
+    let contract = Address::repeat_byte(0x30);
+    let mut db = InMemoryDB::default();
+    common::deploy(
+        &mut db,
+        contract,
+        vec![opcode::PUSH1, 0x01, opcode::PUSH1, 0x02, opcode::ADD, opcode::STOP],
+    );
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+
+    assert_eq!(inspector.opcode_count(opcode::PUSH1), 2);
+    assert_eq!(inspector.opcode_count(opcode::ADD), 1);
+    assert_eq!(inspector.opcode_count(opcode::STOP), 1);
+    assert_eq!(inspector.opcode_count(opcode::MUL), 0);
+    assert_eq!(inspector.opcode_histogram().total(), inspector.steps());
+
+    let top: Vec<(&str, u64)> = inspector
+        .top_opcodes(2)
+        .into_iter()
+        .map(|(op, count)| (op.as_str(), count))
+        .collect();
+    assert_eq!(top, vec![("PUSH1", 2), ("STOP", 1)]);
+}
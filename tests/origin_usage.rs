@@ -0,0 +1,76 @@
+//! Integration tests for recording `tx.origin` reads.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    CallKind, HelloWorldInspector, HelloWorldInspectorConfig, InspectorReport, OriginUsageKind,
+    OutputMode, ReentrancyFinding, ReentrancyKind,
+};
+use revm::InMemoryDB;
+
+const TARGET: Address = Address::repeat_byte(0x20);
+
+/// ORIGIN, POP, CALLER, ORIGIN, EQ, POP, STOP: a plain read, then a read
+/// compared right away as in `require(msg.sender == tx.origin)`
+const READ_THEN_COMPARE: [u8; 7] = [0x32, 0x50, 0x33, 0x32, 0x14, 0x50, 0x00];
+
+fn trace(code: &[u8]) -> HelloWorldInspector {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, TARGET, code.to_vec());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(TARGET, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_plain_read_and_comparison_are_distinguished() {
+    let inspector = trace(&READ_THEN_COMPARE);
+
+    let usages = inspector.origin_usages();
+    assert_eq!(usages.len(), 2);
+    assert_eq!((usages[0].pc, usages[0].kind), (0, OriginUsageKind::Read));
+    assert_eq!((usages[1].pc, usages[1].kind), (3, OriginUsageKind::Comparison));
+    assert!(usages.iter().all(|usage| usage.address == TARGET && usage.depth == 0));
+}
+
+#[test]
+fn test_origin_not_followed_by_eq_is_a_read() {
+    // ORIGIN, CALLER, EQ, POP, STOP
+    let inspector = trace(&[0x32, 0x33, 0x14, 0x50, 0x00]);
+    let usages = inspector.origin_usages();
+    assert_eq!(usages.len(), 1);
+    assert_eq!(usages[0].kind, OriginUsageKind::Read);
+}
+
+#[test]
+fn test_security_section_in_json_report() {
+    let report = trace(&READ_THEN_COMPARE).report();
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    let usages = &json["security"]["origin_usages"];
+    assert_eq!(usages[0]["kind"], "read");
+    assert_eq!(usages[1]["kind"], "comparison");
+    assert_eq!(json["security"]["reentrancy"], serde_json::json!([]));
+}
+
+#[test]
+fn test_reentrancy_findings_round_trip() {
+    let mut report = InspectorReport::default();
+    report.security.reentrancy.push(ReentrancyFinding {
+        address: TARGET,
+        kind: ReentrancyKind::Direct,
+        call_kind: CallKind::StaticCall,
+        depth: 2,
+        ancestor_depth: 0,
+        path: vec![TARGET, Address::repeat_byte(0x21), TARGET],
+        state_changed_after: true,
+    });
+    let json = report.to_json().unwrap();
+    assert!(json.contains(r#""kind":"direct","call_kind":"STATICCALL""#));
+    let parsed: InspectorReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, report);
+}
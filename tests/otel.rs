@@ -0,0 +1,212 @@
+//! Integration tests for exporting call frames as OpenTelemetry spans.
+
+mod common;
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{Revert, SolError};
+use opentelemetry::trace::{SpanId, Status, TracerProvider as _};
+use opentelemetry_sdk::{
+    export::trace::SpanData, testing::trace::InMemorySpanExporter, trace::TracerProvider,
+};
+use restd::{
+    otel::OtelSink, CallTraceInspector, CallTraceNode, HelloWorldInspector, Labels, OutputMode,
+    TraceEvent, TraceSink,
+};
+use revm::{primitives::TxKind, InMemoryDB};
+
+const ROUTER: Address = Address::repeat_byte(0xa0);
+const POOL: Address = Address::repeat_byte(0xa1);
+const ORACLE: Address = Address::repeat_byte(0xa2);
+
+/// Returns a provider exporting to memory; shutting it down, which
+/// dropping it does, clears the exporter.
+fn in_memory_provider() -> (TracerProvider, InMemorySpanExporter) {
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    (provider, exporter)
+}
+
+/// The router calls the pool, which calls the oracle, and then calls the
+/// oracle itself; the oracle always reverts.
+fn db() -> InMemoryDB {
+    let mut router = common::call_asm(POOL, 0);
+    router.extend(common::call_asm(ORACLE, 0));
+    router.push(0x00);
+    let mut pool = common::call_asm(ORACLE, 0);
+    pool.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, ROUTER, router);
+    common::deploy(&mut db, POOL, pool);
+    common::deploy(
+        &mut db,
+        ORACLE,
+        common::revert_asm(&Revert::from("stale price").abi_encode()),
+    );
+    db
+}
+
+/// A frame and its children, with children sorted so that shapes compare
+/// regardless of the order spans ended in.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Shape {
+    name: String,
+    children: Vec<Shape>,
+}
+
+fn call_shape(node: &CallTraceNode, labels: &Labels) -> Shape {
+    let mut children: Vec<_> = node
+        .children
+        .iter()
+        .map(|child| call_shape(child, labels))
+        .collect();
+    children.sort();
+    Shape {
+        name: labels
+            .get(&node.target)
+            .map_or_else(|| node.target.to_string(), String::from),
+        children,
+    }
+}
+
+fn span_shape(span: &SpanData, children_of: &HashMap<SpanId, Vec<&SpanData>>) -> Shape {
+    let mut children: Vec<_> = children_of
+        .get(&span.span_context.span_id())
+        .into_iter()
+        .flatten()
+        .map(|child| span_shape(child, children_of))
+        .collect();
+    children.sort();
+    Shape {
+        name: span.name.to_string(),
+        children,
+    }
+}
+
+fn attribute(span: &SpanData, key: &str) -> Option<String> {
+    span.attributes
+        .iter()
+        .find(|attribute| attribute.key.as_str() == key)
+        .map(|attribute| attribute.value.to_string())
+}
+
+#[test]
+fn test_span_tree_matches_call_tree() {
+    let mut labels = Labels::new();
+    labels.insert(ROUTER, "Router");
+    let (provider, exporter) = in_memory_provider();
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .labels(labels.clone())
+        .build()
+        .with_sink(OtelSink::new(provider.tracer("restd")));
+    let input = vec![0x12, 0x34, 0x56, 0x78];
+    common::run(
+        &mut db(),
+        common::call_env(ROUTER, input.clone()),
+        &mut inspector,
+    );
+
+    let mut calls = CallTraceInspector::new();
+    common::run(&mut db(), common::call_env(ROUTER, input), &mut calls);
+    let trace = calls.into_trace();
+    let expected = Shape {
+        name: "transaction".to_string(),
+        children: vec![call_shape(trace.root().unwrap(), &labels)],
+    };
+
+    let spans = exporter.get_finished_spans().unwrap();
+    assert_eq!(spans.len(), 5);
+    let mut children_of: HashMap<SpanId, Vec<&SpanData>> = HashMap::new();
+    for span in &spans {
+        children_of
+            .entry(span.parent_span_id)
+            .or_default()
+            .push(span);
+    }
+    let roots = &children_of[&SpanId::INVALID];
+    assert_eq!(roots.len(), 1);
+    assert_eq!(span_shape(roots[0], &children_of), expected);
+
+    let transaction = roots[0];
+    assert_eq!(transaction.status, Status::Ok);
+    assert_eq!(
+        attribute(transaction, "from"),
+        Some(common::CALLER.to_string())
+    );
+    assert_eq!(attribute(transaction, "to"), Some(ROUTER.to_string()));
+
+    let router = spans.iter().find(|span| span.name == "Router").unwrap();
+    assert_eq!(router.status, Status::Ok);
+    assert_eq!(attribute(router, "selector").as_deref(), Some("0x12345678"));
+    assert_eq!(attribute(router, "depth").as_deref(), Some("0"));
+    assert!(attribute(router, "gas_used").is_some());
+
+    let oracle_calls: Vec<_> = spans
+        .iter()
+        .filter(|span| span.name == ORACLE.to_string())
+        .collect();
+    assert_eq!(oracle_calls.len(), 2);
+    for span in oracle_calls {
+        assert_eq!(span.status, Status::error("stale price"));
+        assert_eq!(
+            attribute(span, "revert_reason").as_deref(),
+            Some("stale price")
+        );
+    }
+}
+
+#[test]
+fn test_creations_and_unbalanced_frames() {
+    let (provider, exporter) = in_memory_provider();
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .build()
+        .with_sink(OtelSink::new(provider.tracer("restd")));
+    let mut env = common::call_env(Address::ZERO, common::return_asm(&[0x00]));
+    env.tx.transact_to = TxKind::Create;
+    common::run(&mut InMemoryDB::default(), env, &mut inspector);
+    let spans = exporter.get_finished_spans().unwrap();
+    let created = common::CALLER.create(0);
+    assert_eq!(
+        spans
+            .iter()
+            .map(|span| span.name.to_string())
+            .collect::<Vec<_>>(),
+        [created.to_string(), "transaction".to_string()]
+    );
+    assert_eq!(attribute(&spans[1], "created"), Some(created.to_string()));
+    exporter.reset();
+
+    // A transaction aborted inside a subcall leaves its spans open until the
+    // next transaction starts
+    let (provider, exporter) = in_memory_provider();
+    let mut sink = OtelSink::new(provider.tracer("restd"));
+    let start = |address, depth| TraceEvent::CallStart {
+        address,
+        caller: common::CALLER,
+        value: U256::ZERO,
+        gas_limit: 100_000,
+        depth,
+        seq: 0,
+        selector: None,
+        label: None,
+    };
+    assert!(sink.send(start(ROUTER, 1)));
+    sink.send(start(POOL, 2));
+    assert_eq!(sink.open_spans(), 3);
+    sink.send(start(ROUTER, 1));
+    assert_eq!(sink.open_spans(), 2);
+    let spans = exporter.get_finished_spans().unwrap();
+    assert_eq!(spans.len(), 3);
+    assert!(spans
+        .iter()
+        .all(|span| span.status == Status::error("frame did not end")));
+
+    // Dropping the sink ends the rest
+    drop(sink);
+    assert_eq!(exporter.get_finished_spans().unwrap().len(), 5);
+}
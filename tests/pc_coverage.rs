@@ -0,0 +1,161 @@
+//! Integration tests for per-instruction hit counts and their lcov export.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, Labels, OutputMode, PcCoverage, SourceMapRegistry};
+use revm::InMemoryDB;
+
+const LOOP: Address = Address::repeat_byte(0x4c);
+const BRANCHY: Address = Address::repeat_byte(0x3b);
+
+/// Hits of one run of the countdown loop per code offset: the loop body
+/// runs three times and push operands never execute.
+const LOOP_HITS: [u32; 12] = [1, 0, 3, 3, 0, 3, 3, 3, 3, 0, 3, 1];
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, LOOP, common::countdown_loop());
+    // PUSH1 0, CALLDATALOAD, PUSH1 7, JUMPI, STOP, JUMPDEST, STOP
+    common::deploy(
+        &mut db,
+        BRANCHY,
+        vec![0x60, 0x00, 0x35, 0x60, 0x07, 0x57, 0x00, 0x5b, 0x00],
+    );
+    db
+}
+
+/// Calls each of `targets` in its own transaction.
+fn run(inspector: HelloWorldInspector, targets: &[Address]) -> HelloWorldInspector {
+    let mut inspector = inspector;
+    let mut db = db();
+    for target in targets {
+        common::run(
+            &mut db,
+            common::call_env(*target, Vec::new()),
+            &mut inspector,
+        );
+    }
+    inspector
+}
+
+fn inspector() -> HelloWorldInspector {
+    HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .pc_coverage(true)
+        .build()
+}
+
+#[test]
+fn test_loop_hit_counts() {
+    let inspector = run(inspector(), &[LOOP, LOOP]);
+    let coverage = inspector.pc_coverage();
+    assert_eq!(coverage.len(), 1);
+    assert_eq!(coverage.hits(LOOP).unwrap(), LOOP_HITS.map(|hits| hits * 2));
+    assert_eq!(
+        coverage.code(LOOP).unwrap().as_ref(),
+        common::countdown_loop()
+    );
+
+    // The report round-trips, keeping only the offsets that executed
+    let json = inspector.report_json().unwrap();
+    assert!(json.contains(r#""hits":[[0,2],[2,6],"#), "{json}");
+    let report: restd::InspectorReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(&report.pc_coverage, coverage);
+
+    // Counters saturate when merged
+    let mut saturated: PcCoverage = serde_json::from_str(&format!(
+        r#"[{{"address":"{LOOP}","code":"0x60035b600190038060025700","hits":[[0,{}]]}}]"#,
+        u32::MAX - 1
+    ))
+    .unwrap();
+    saturated.merge(coverage);
+    assert_eq!(saturated.hits(LOOP).unwrap()[0], u32::MAX);
+    assert_eq!(saturated.hits(LOOP).unwrap()[2], 6);
+    let err = serde_json::from_str::<PcCoverage>(&format!(
+        r#"[{{"address":"{LOOP}","code":"0x00","hits":[[1,1]]}}]"#
+    ))
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("offset 1 is past the end"),
+        "{err}"
+    );
+
+    // Contracts over the size threshold are skipped
+    let inspector = run(
+        HelloWorldInspector::builder()
+            .output(OutputMode::Silent)
+            .pc_coverage(true)
+            .coverage_max_code_size(10)
+            .build(),
+        &[LOOP, BRANCHY],
+    );
+    let coverage = inspector.pc_coverage();
+    assert!(coverage.hits(LOOP).is_none());
+    assert_eq!(coverage.hits(BRANCHY).unwrap().len(), 9);
+}
+
+#[test]
+fn test_lcov_export() {
+    let inspector = run(inspector(), &[LOOP, BRANCHY]);
+    let coverage = inspector.pc_coverage();
+
+    // Without source maps every contract is a pseudo-file of code offsets
+    let mut labels = Labels::new();
+    labels.insert(LOOP, "Loop");
+    let mut lcov = Vec::new();
+    coverage
+        .to_lcov(&labels, &SourceMapRegistry::new(), &mut lcov)
+        .unwrap();
+    let expected = format!(
+        "TN:
+SF:{BRANCHY:#x}.evm
+DA:1,1
+DA:3,1
+DA:4,1
+DA:6,1
+DA:7,1
+DA:8,0
+DA:9,0
+LF:7
+LH:5
+end_of_record
+TN:
+SF:Loop.evm
+DA:1,1
+DA:3,3
+DA:4,3
+DA:6,3
+DA:7,3
+DA:8,3
+DA:9,3
+DA:11,3
+DA:12,1
+LF:9
+LH:9
+end_of_record
+"
+    );
+    assert_eq!(String::from_utf8(lcov).unwrap(), expected);
+
+    // With a source map the loop's instructions count towards their lines;
+    // the JUMPDEST is compiler-generated
+    let mut source_maps = SourceMapRegistry::new();
+    source_maps
+        .register(
+            LOOP,
+            "0:11:0;12:18:-1;12:18:0;;;;;;31:7:0",
+            [("Loop.sol", "uint i = 3;\nwhile (--i > 0) {}\nreturn;\n")],
+        )
+        .unwrap();
+    let mut lcov = Vec::new();
+    coverage.to_lcov(&labels, &source_maps, &mut lcov).unwrap();
+    let lcov = String::from_utf8(lcov).unwrap();
+    let records: Vec<&str> = lcov.split_inclusive("end_of_record\n").collect();
+    assert_eq!(records.len(), 2);
+    assert!(records[0].contains(&format!("SF:{BRANCHY:#x}.evm\n")));
+    assert_eq!(
+        records[1],
+        "TN:\nSF:Loop.sol\nDA:1,1\nDA:2,3\nDA:3,1\nLF:3\nLH:3\nend_of_record\n"
+    );
+}
@@ -0,0 +1,143 @@
+//! Integration tests for saving reports and call traces and loading them
+//! back.
+#![cfg(feature = "persist")]
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    evm::transact_inspected,
+    persist::{Header, PersistError, SavedKind, FORMAT_VERSION},
+    CallKind, CallTrace, CallTraceInspector, HelloWorldInspector, HelloWorldInspectorConfig,
+    InspectorReport, InspectorStack, OutputMode, PrettyPrintOptions,
+};
+use revm::InMemoryDB;
+
+const VAULT: Address = Address::repeat_byte(0x20);
+const REVERTER: Address = Address::repeat_byte(0x21);
+const IDENTITY: Address = Address::with_last_byte(0x04);
+
+/// Traces a call to a contract that writes storage, emits a log, calls the
+/// identity precompile and a contract that reverts.
+fn trace() -> (InspectorReport, CallTrace) {
+    // SSTORE(0, 1), LOG1(0, 0, 7)
+    let mut vault = vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x07, 0x60, 0x00, 0x80, 0xa1,
+    ];
+    vault.extend(common::call_asm(IDENTITY, 0));
+    vault.extend(common::call_asm(REVERTER, 0));
+    vault.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, VAULT, vault);
+    common::deploy(&mut db, REVERTER, common::revert_asm(b"nope"));
+
+    let mut stack = InspectorStack::new()
+        .with(HelloWorldInspector::with_config(
+            HelloWorldInspectorConfig {
+                output: OutputMode::Silent,
+                ..Default::default()
+            },
+        ))
+        .with(CallTraceInspector::new());
+    transact_inspected(db, common::call_env(VAULT, vec![0xab; 4]), &mut stack).unwrap();
+    let report = stack.remove::<HelloWorldInspector>().unwrap().report();
+    let trace = stack.remove::<CallTraceInspector>().unwrap().into_trace();
+    (report.with_reentrancy(&trace), trace)
+}
+
+#[test]
+fn test_report_round_trip() {
+    let (report, _) = trace();
+    assert!(!report.storage_accesses.is_empty());
+    assert!(!report.logs.is_empty());
+    assert!(!report.reverts.is_empty());
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    report.save(file.path()).unwrap();
+    let loaded = InspectorReport::load(file.path()).unwrap();
+
+    assert_eq!(loaded, report);
+    assert!(loaded.timeline().iter().eq(report.timeline().iter()));
+    assert_eq!(loaded.busiest_addresses(3), report.busiest_addresses(3));
+    assert_eq!(loaded.to_json().unwrap(), report.to_json().unwrap());
+}
+
+#[test]
+fn test_call_trace_round_trip() {
+    let (_, trace) = trace();
+    let file = tempfile::NamedTempFile::new().unwrap();
+    trace.save(file.path()).unwrap();
+    let loaded = CallTrace::load(file.path()).unwrap();
+
+    assert_eq!(loaded, trace);
+    assert_eq!(loaded.nodes().count(), 3);
+    let precompile = loaded.nodes().find(|node| node.target == IDENTITY).unwrap();
+    assert!(matches!(
+        precompile.kind,
+        CallKind::Precompile {
+            name: "identity",
+            ..
+        }
+    ));
+    assert_eq!(
+        loaded.pretty_print(PrettyPrintOptions {
+            show_logs: true,
+            ..Default::default()
+        }),
+        trace.pretty_print(PrettyPrintOptions {
+            show_logs: true,
+            ..Default::default()
+        })
+    );
+    assert_eq!(loaded.to_geth_call_trace(), trace.to_geth_call_trace());
+}
+
+#[test]
+fn test_header_records_the_versions() {
+    let (report, _) = trace();
+    let bytes = report.to_bytes().unwrap();
+    let (header, _) = Header::read(&bytes).unwrap();
+    assert_eq!(header.kind, SavedKind::Report);
+    assert_eq!(header.format_version, FORMAT_VERSION);
+    assert_eq!(header.crate_version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_corrupted_files_error_cleanly() {
+    let (report, trace) = trace();
+    let bytes = report.to_bytes().unwrap();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(
+        InspectorReport::from_bytes(&bad_magic),
+        Err(PersistError::InvalidHeader(_))
+    ));
+    assert!(matches!(
+        InspectorReport::from_bytes(&bytes[..7]),
+        Err(PersistError::InvalidHeader(_))
+    ));
+
+    let mut newer = bytes.clone();
+    newer[6..8].copy_from_slice(&(FORMAT_VERSION + 1).to_be_bytes());
+    let err = InspectorReport::from_bytes(&newer).unwrap_err();
+    assert!(
+        matches!(err, PersistError::UnsupportedVersion { found, .. } if found == FORMAT_VERSION + 1)
+    );
+    assert!(err.to_string().contains("newer"), "{err}");
+
+    assert!(matches!(
+        CallTrace::from_bytes(&bytes),
+        Err(PersistError::WrongKind {
+            expected: SavedKind::CallTrace,
+            found: SavedKind::Report,
+        })
+    ));
+
+    let mut truncated = trace.to_bytes().unwrap();
+    truncated.truncate(truncated.len() - 10);
+    assert!(matches!(
+        CallTrace::from_bytes(&truncated),
+        Err(PersistError::Decode(_))
+    ));
+}
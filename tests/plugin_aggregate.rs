@@ -0,0 +1,149 @@
+//! Tests for the aggregates and decoding resources a plugin shares with the
+//! inspectors it creates.
+
+mod common;
+
+use std::{collections::HashMap, sync::Arc};
+
+use alloy_primitives::{hex, Address, Bytes, U256};
+use restd::{
+    AggregateReport, HelloWorldInspector, HelloWorldInspectorConfig, HelloWorldInspectorPlugin,
+    Labels, OutputMode, StaticSelectorResolver,
+};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CallValue, Gas, InstructionResult, InterpreterResult,
+    },
+    EvmContext, InMemoryDB, Inspector,
+};
+
+const ROUTER: Address = Address::repeat_byte(0x61);
+const POOL: Address = Address::repeat_byte(0x62);
+const TRANSFER: [u8; 4] = hex!("a9059cbb");
+
+fn plugin() -> HelloWorldInspectorPlugin {
+    let mut labels = Labels::new();
+    labels.insert(ROUTER, "Router");
+    let resolver = StaticSelectorResolver::new(HashMap::from([(
+        TRANSFER,
+        "transfer(address,uint256)".to_string(),
+    )]));
+    HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+    .with_labels(Arc::new(labels))
+    .with_selector_resolver(Arc::new(resolver))
+}
+
+fn inputs(target: Address, input: &[u8]) -> CallInputs {
+    CallInputs {
+        input: Bytes::copy_from_slice(input),
+        return_memory_offset: 0..0,
+        gas_limit: 100_000,
+        bytecode_address: target,
+        target_address: target,
+        caller: common::CALLER,
+        value: CallValue::Transfer(U256::ZERO),
+        scheme: CallScheme::Call,
+        is_static: false,
+        is_eof: false,
+    }
+}
+
+fn outcome(result: InstructionResult, gas_spent: u64) -> CallOutcome {
+    let mut gas = Gas::new(100_000);
+    assert!(gas.record_cost(gas_spent));
+    CallOutcome::new(InterpreterResult::new(result, Bytes::new(), gas), 0..0)
+}
+
+/// The router is called with `transfer` and calls the pool, which reverts.
+fn router_transaction(inspector: &mut HelloWorldInspector) {
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let (mut router, mut pool) = (inputs(ROUTER, &TRANSFER), inputs(POOL, &[]));
+    inspector.call(&mut context, &mut router);
+    inspector.call(&mut context, &mut pool);
+    inspector.call_end(&mut context, &pool, outcome(InstructionResult::Revert, 300));
+    inspector.call_end(
+        &mut context,
+        &router,
+        outcome(InstructionResult::Stop, 1_000),
+    );
+}
+
+/// The pool is called directly and succeeds.
+fn pool_transaction(inspector: &mut HelloWorldInspector) {
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let mut pool = inputs(POOL, &[]);
+    inspector.call(&mut context, &mut pool);
+    inspector.call_end(&mut context, &pool, outcome(InstructionResult::Stop, 500));
+}
+
+#[test]
+fn test_aggregate_sees_every_inspector() {
+    let plugin = plugin();
+    let mut first = plugin.create_inspector();
+    let mut second = plugin.create_inspector();
+    router_transaction(&mut first);
+    pool_transaction(&mut second);
+
+    assert_eq!(
+        plugin.aggregate_report(),
+        AggregateReport {
+            inspectors_created: 2,
+            transactions: 2,
+            steps: 0,
+            calls: 3,
+            creations: 0,
+            reverts: 1,
+            gas_used: 1_500,
+        }
+    );
+
+    // Both inspectors decode with the plugin's labels and resolver
+    assert!(Arc::ptr_eq(
+        first.labels().unwrap(),
+        second.labels().unwrap()
+    ));
+    let router = &first.gas_by_call()[0];
+    assert_eq!(router.label.as_deref(), Some("Router"));
+    assert_eq!(
+        router.function.as_deref(),
+        Some("transfer(address,uint256)")
+    );
+    assert_eq!(second.gas_by_call()[0].label, None);
+}
+
+#[test]
+fn test_aggregate_counts_finished_transactions_once() {
+    let plugin = plugin();
+    let mut inspector = plugin.create_inspector();
+    router_transaction(&mut inspector);
+
+    // A transaction counts once its top-level frame has ended
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let mut pool = inputs(POOL, &[]);
+    inspector.call(&mut context, &mut pool);
+    assert_eq!(plugin.aggregate_report().transactions, 1);
+    inspector.call_end(&mut context, &pool, outcome(InstructionResult::Stop, 500));
+    assert_eq!(plugin.aggregate_report().transactions, 2);
+
+    // Resetting or dropping the inspector keeps what it added
+    inspector.reset();
+    pool_transaction(&mut inspector);
+    drop(inspector);
+    let report = plugin.aggregate_report();
+    assert_eq!(report.transactions, 3);
+    assert_eq!(report.calls, 4);
+    assert_eq!(report.gas_used, 2_000);
+
+    // Executed instructions count as steps
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, POOL, common::countdown_loop());
+    let mut inspector = plugin.create_inspector();
+    assert!(common::run(&mut db, common::call_env(POOL, vec![]), &mut inspector).is_success());
+    let report = plugin.aggregate_report();
+    assert_eq!(report.inspectors_created, 2);
+    assert_eq!(report.steps, inspector.steps());
+    assert!(report.steps > 0);
+}
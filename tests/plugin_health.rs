@@ -0,0 +1,99 @@
+//! Integration tests for the plugin lifecycle and health reporting.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    ConfigError, Error, HelloWorldInspectorConfig, HelloWorldInspectorPlugin, OutputMode,
+    PluginHealth, PluginRegistry,
+};
+use revm::InMemoryDB;
+
+const CONTRACT: Address = Address::repeat_byte(0x20);
+
+fn plugin(sink_path: &str) -> HelloWorldInspectorPlugin {
+    HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        sink_path: Some(sink_path.into()),
+        ..Default::default()
+    })
+}
+
+/// Runs a transaction through an inspector created by `plugin`.
+fn trace(plugin: &HelloWorldInspectorPlugin) {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, common::countdown_loop());
+    let mut inspector = plugin.create_inspector();
+    let result = common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(result.is_success());
+}
+
+#[test]
+fn test_shutdown_flushes_sink_and_reports_totals() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let plugin = plugin(file.path().to_str().unwrap());
+    plugin.init().unwrap();
+    plugin.on_start();
+    trace(&plugin);
+    trace(&plugin);
+
+    let report = plugin.on_shutdown();
+    let lines = std::fs::read_to_string(file.path()).unwrap().lines().count() as u64;
+    // Call start and end around the 23 steps of each transaction
+    assert_eq!(lines, 2 * 25);
+    assert_eq!(report.inspectors_created, 2);
+    assert_eq!(
+        report.health,
+        PluginHealth {
+            healthy: true,
+            last_error: None,
+            events_processed: lines,
+        }
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_sink_write_failure_makes_plugin_unhealthy() {
+    // Every write to /dev/full fails with ENOSPC once the buffer is flushed
+    let plugin = plugin("/dev/full");
+    let mut registry = PluginRegistry::<InMemoryDB>::new();
+    registry.register(plugin.clone()).unwrap();
+    plugin.init().unwrap();
+    registry.start_all();
+    trace(&plugin);
+    assert!(plugin.health().healthy);
+
+    registry.shutdown_all();
+    let health = plugin.health();
+    assert!(!health.healthy);
+    let error = health.last_error.as_deref().unwrap();
+    assert!(error.starts_with("failed to write trace event"), "{error}");
+    assert_eq!(registry.health_all()["hello-world-inspector"], health);
+
+    // The error is kept after more inspectors are created
+    trace(&plugin);
+    assert_eq!(plugin.health().last_error.as_deref(), Some(error));
+}
+
+#[test]
+fn test_init_rejects_invalid_config_and_unopenable_sink() {
+    let invalid = HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig {
+        verbose: true,
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let err = invalid.init().unwrap_err();
+    assert!(matches!(err, Error::Config(ConfigError::VerboseWhileSilent)), "{err:?}");
+    assert!(invalid.health().healthy);
+
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing").join("events.jsonl");
+    let plugin = plugin(missing.to_str().unwrap());
+    let err = plugin.init().unwrap_err();
+    assert!(matches!(&err, Error::Sink { path, .. } if *path == missing), "{err:?}");
+    let err = err.to_string();
+    assert!(err.starts_with("failed to open sink"), "{err}");
+    assert_eq!(plugin.health().last_error, Some(err));
+    assert!(!plugin.health().healthy);
+}
@@ -0,0 +1,115 @@
+//! Integration tests for registering plugins and stacking their inspectors.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    register_inspector, CallTraceInspector, HelloWorldInspector, HelloWorldInspectorPlugin,
+    ConfigError, Error, InspectorPlugin, OutputMode, PluginRegistry, StackedInspector,
+};
+use revm::{inspector_handle_register, Database, Evm, InMemoryDB};
+
+/// A third-party plugin providing the call tracer.
+#[derive(Debug)]
+struct CallTracePlugin;
+
+impl<DB: Database> InspectorPlugin<DB> for CallTracePlugin {
+    fn name(&self) -> &str {
+        "call-trace"
+    }
+
+    fn init(&self, _config: &serde_json::Value) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn create_inspector(&self) -> Box<dyn StackedInspector<DB>> {
+        Box::new(CallTraceInspector::new())
+    }
+}
+
+#[test]
+fn test_stack_built_from_registered_plugins() {
+    let mut registry = PluginRegistry::<InMemoryDB>::new();
+    register_inspector(&mut registry).unwrap();
+    registry.register(CallTracePlugin).unwrap();
+    assert_eq!(
+        registry.names().collect::<Vec<_>>(),
+        ["call-trace", "hello-world-inspector"]
+    );
+
+    let mut stack = registry
+        .build_stack(&["hello-world-inspector", "call-trace"])
+        .unwrap();
+    assert_eq!(stack.len(), 2);
+
+    let contract = Address::repeat_byte(0x80);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, vec![0x5b, 0x00]);
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_env(Box::new(common::call_env(contract, vec![])))
+        .with_external_context(&mut stack)
+        .append_handler_register(inspector_handle_register)
+        .build();
+    evm.transact_commit().unwrap();
+    drop(evm);
+
+    assert_eq!(stack.get::<HelloWorldInspector>().unwrap().steps(), 2);
+    assert_eq!(stack.get::<CallTraceInspector>().unwrap().root().unwrap().target, contract);
+}
+
+#[test]
+fn test_duplicate_and_unknown_names_are_rejected() {
+    let mut registry = PluginRegistry::<InMemoryDB>::new();
+    registry.register(HelloWorldInspectorPlugin::default()).unwrap();
+
+    let err = register_inspector(&mut registry).unwrap_err();
+    assert!(
+        matches!(&err, Error::PluginAlreadyRegistered(name) if name == "hello-world-inspector"),
+        "{err:?}"
+    );
+    assert!(err.to_string().contains("already registered"));
+
+    let err = registry.build_stack(&["missing"]).unwrap_err();
+    assert!(matches!(&err, Error::UnknownPlugin(name) if name == "missing"), "{err:?}");
+}
+
+#[test]
+fn test_init_validates_plugin_config() {
+    let plugin = HelloWorldInspectorPlugin::default();
+    let init = |config| InspectorPlugin::<InMemoryDB>::init(&plugin, &config);
+
+    assert!(init(serde_json::Value::Null).is_ok());
+    assert!(init(serde_json::json!({
+        "verbose": false,
+        "log_steps": true,
+        "trace_calls": true
+    }))
+    .is_ok());
+    let err = init(serde_json::json!({ "verbose": "yes" })).unwrap_err();
+    assert!(matches!(err, Error::Config(ConfigError::Json(_))), "{err:?}");
+}
+
+#[test]
+fn test_init_applies_plugin_config() {
+    let plugin = HelloWorldInspectorPlugin::default();
+    let init = |config| InspectorPlugin::<InMemoryDB>::init(&plugin, &config);
+
+    init(serde_json::json!({
+        "output": "silent",
+        "max_steps": 5,
+        "sink_path": null
+    }))
+    .unwrap();
+    let inspector = plugin.create_inspector();
+    assert_eq!(inspector.config().output, OutputMode::Silent);
+    assert_eq!(inspector.config().max_steps, Some(5));
+
+    // `null` keeps the configuration, and once inspectors exist only
+    // options that can change while running are applied
+    init(serde_json::Value::Null).unwrap();
+    assert_eq!(plugin.config().max_steps, Some(5));
+    let err = init(serde_json::json!({ "profiling": "counters" })).unwrap_err();
+    assert!(matches!(err, Error::RequiresRestart("profiling")), "{err:?}");
+    assert_eq!(plugin.config().max_steps, Some(5));
+}
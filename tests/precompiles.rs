@@ -0,0 +1,77 @@
+//! Integration tests for precompile detection.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    CallKind, CallTraceInspector, GethCallFrame, HelloWorldInspector, HelloWorldInspectorConfig,
+    OutputMode,
+};
+use revm::{interpreter::CallScheme, InMemoryDB};
+
+const IDENTITY: Address = Address::with_last_byte(0x04);
+
+fn setup() -> (InMemoryDB, Address) {
+    let caller = Address::repeat_byte(0x20);
+    // CALL identity, STATICCALL identity, STOP
+    let mut code = common::call_asm(IDENTITY, 0);
+    code.extend(common::static_call_asm(IDENTITY));
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, caller, code);
+    (db, caller)
+}
+
+#[test]
+fn test_identity_precompile_is_counted() {
+    let (mut db, caller) = setup();
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(caller, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    assert_eq!(inspector.call_count, 3);
+    assert_eq!(inspector.precompile_calls().len(), 1);
+    assert_eq!(inspector.precompile_calls()[&IDENTITY], 2);
+}
+
+#[test]
+fn test_identity_precompile_is_labelled_in_call_trace() {
+    let (mut db, caller) = setup();
+    let mut inspector = CallTraceInspector::new();
+    common::run(&mut db, common::call_env(caller, vec![]), &mut inspector);
+
+    let trace = inspector.into_trace();
+    let root = trace.root().unwrap();
+    assert_eq!(root.kind, CallKind::Call);
+    assert_eq!(root.children.len(), 2);
+
+    let call = &root.children[0];
+    assert_eq!(
+        call.kind,
+        CallKind::Precompile {
+            name: "identity",
+            scheme: CallScheme::Call
+        }
+    );
+    assert!(call.kind.is_precompile());
+    assert_eq!(call.kind.base(), CallKind::Call);
+
+    let static_call = &root.children[1];
+    assert_eq!(static_call.kind.base(), CallKind::StaticCall);
+    let frame = GethCallFrame::from(static_call);
+    assert_eq!(frame.kind, "STATICCALL");
+    assert_eq!(frame.value, None);
+}
+
+#[test]
+fn test_precompile_upper_bound_is_configurable() {
+    let (mut db, caller) = setup();
+    let mut inspector = CallTraceInspector::new().with_precompile_upper_bound(0x03);
+    common::run(&mut db, common::call_env(caller, vec![]), &mut inspector);
+
+    let trace = inspector.into_trace();
+    assert!(trace.nodes().all(|node| !node.kind.is_precompile()));
+}
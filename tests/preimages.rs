@@ -0,0 +1,78 @@
+//! Integration tests for recording `KECCAK256` preimages of storage slots.
+
+mod common;
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, TraceLimits};
+use revm::InMemoryDB;
+
+const TARGET: Address = Address::repeat_byte(0x20);
+
+/// MSTORE(0, 0x42), MSTORE(32, 1), SSTORE(KECCAK256(0, 64), 7), STOP: writes
+/// `balances[0x42]` of a mapping at slot 1.
+const MAPPING_WRITE: [u8; 19] = [
+    0x60, 0x42, 0x60, 0x00, 0x52, 0x60, 0x01, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60, 0x00, 0x20,
+    0x60, 0x07, 0x90, 0x55,
+];
+
+fn trace(code: &[u8], limits: TraceLimits) -> HelloWorldInspector {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, TARGET, code.to_vec());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        limits,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(TARGET, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+fn mapping_key() -> Bytes {
+    let mut preimage = [0u8; 64];
+    preimage[31] = 0x42;
+    preimage[63] = 0x01;
+    Bytes::copy_from_slice(&preimage)
+}
+
+#[test]
+fn test_mapping_slot_preimage_is_attached() {
+    let inspector = trace(&MAPPING_WRITE, TraceLimits::default());
+    let preimage = mapping_key();
+    let slot = keccak256(&preimage);
+
+    let accesses = inspector.storage_accesses();
+    assert_eq!(accesses.len(), 1);
+    assert_eq!(accesses[0].slot, U256::from_be_bytes(slot.0));
+    assert_eq!(accesses[0].value, U256::from(7));
+    assert_eq!(accesses[0].slot_preimage.as_ref(), Some(&preimage));
+    assert_eq!(inspector.preimage(slot), Some(&preimage));
+    assert_eq!(inspector.preimages().len(), 1);
+}
+
+#[test]
+fn test_unexpanded_memory_hashes_as_zeros() {
+    // SSTORE(KECCAK256(0, 32), 1), STOP with nothing written to memory
+    let code = [0x60, 0x01, 0x60, 0x20, 0x60, 0x00, 0x20, 0x55, 0x00];
+    let inspector = trace(&code, TraceLimits::default());
+
+    let zeros = Bytes::from(vec![0u8; 32]);
+    assert_eq!(inspector.preimage(keccak256(&zeros)), Some(&zeros));
+    assert_eq!(inspector.storage_accesses()[0].slot_preimage, Some(zeros));
+    assert_eq!(inspector.preimage(B256::ZERO), None);
+}
+
+#[test]
+fn test_preimages_over_budget_are_dropped() {
+    let inspector = trace(
+        &MAPPING_WRITE,
+        TraceLimits {
+            max_preimage_bytes: Some(63),
+            ..Default::default()
+        },
+    );
+
+    assert!(inspector.preimages().is_empty());
+    assert_eq!(inspector.storage_accesses()[0].slot_preimage, None);
+    assert!(inspector.truncated_collections().preimages);
+}
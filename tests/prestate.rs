@@ -0,0 +1,93 @@
+//! Integration tests for seeding an in-memory database from a prestate.
+
+mod common;
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, Prestate};
+use revm::{primitives::KECCAK_EMPTY, Database, InMemoryDB};
+
+const COUNTER: Address = Address::repeat_byte(0x30);
+const EOA: Address = Address::repeat_byte(0x31);
+
+/// Geth `prestateTracer` output for a counter that adds 1 to slot 0, with an
+/// empty code entry and a zero storage value.
+const PRESTATE: &str = r#"{
+    "0x0101010101010101010101010101010101010101": {
+        "balance": "0xde0b6b3a7640000",
+        "nonce": 7
+    },
+    "0x3030303030303030303030303030303030303030": {
+        "balance": "0x0",
+        "nonce": 1,
+        "code": "0x60016000540160005500",
+        "storage": {
+            "0x0000000000000000000000000000000000000000000000000000000000000000":
+                "0x0000000000000000000000000000000000000000000000000000000000000029",
+            "0x0000000000000000000000000000000000000000000000000000000000000001":
+                "0x0000000000000000000000000000000000000000000000000000000000000000"
+        }
+    },
+    "0x3131313131313131313131313131313131313131": {
+        "balance": "0x1",
+        "code": "0x"
+    }
+}"#;
+
+#[test]
+fn test_replay_geth_prestate() {
+    let prestate = Prestate::from_json(PRESTATE).unwrap();
+    assert_eq!(prestate.accounts.len(), 3);
+    assert_eq!(prestate.accounts[&common::CALLER].nonce, 7);
+
+    let mut db = InMemoryDB::default();
+    prestate.apply(&mut db);
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(COUNTER, Vec::new()), &mut inspector);
+    assert!(result.is_success());
+    assert!(inspector.step_count > 0);
+    assert_eq!(db.storage(COUNTER, U256::ZERO).unwrap(), U256::from(42));
+
+    // The zero value still creates the slot
+    let storage = &db.accounts[&COUNTER].storage;
+    assert_eq!(storage.get(&U256::from(1)), Some(&U256::ZERO));
+
+    // Empty code is loaded as an account without code
+    let eoa = db.basic(EOA).unwrap().unwrap();
+    assert_eq!(eoa.code_hash, KECCAK_EMPTY);
+    assert_eq!(prestate.accounts[&EOA].code, Some(Bytes::new()));
+    assert_eq!(Prestate::from_json(&prestate.to_json().unwrap()).unwrap(), prestate);
+}
+
+#[test]
+fn test_builder_matches_json() {
+    let built = Prestate::builder()
+        .account(common::CALLER)
+        .balance(U256::from(10u64.pow(18)))
+        .nonce(7)
+        .account(COUNTER)
+        .nonce(1)
+        .code(vec![0x60, 0x01, 0x60, 0x00, 0x54, 0x01, 0x60, 0x00, 0x55, 0x00])
+        .storage(U256::ZERO, U256::from(0x29))
+        .storage(U256::from(1), U256::ZERO)
+        .account(EOA)
+        .balance(U256::from(1))
+        .code(Bytes::new())
+        .build();
+    assert_eq!(built, Prestate::from_json(PRESTATE).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "select an account")]
+fn test_builder_requires_an_account() {
+    Prestate::builder().balance(U256::from(1));
+}
+
+#[test]
+fn test_rejects_malformed_prestate() {
+    assert!(Prestate::from_json(r#"{"0x01": {}}"#).is_err());
+    let bad_nonce = r#"{"0x0101010101010101010101010101010101010101": {"nonce": "x"}}"#;
+    assert!(Prestate::from_json(bad_nonce).is_err());
+}
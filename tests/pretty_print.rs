@@ -0,0 +1,153 @@
+//! Snapshot tests for `CallTrace::pretty_print`.
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{Revert, SolError};
+use restd::{
+    CallKind, CallTrace, CallTraceInspector, CallTraceNode, PrettyPrintOptions,
+    StaticSelectorResolver,
+};
+use revm::{interpreter::InstructionResult, InMemoryDB};
+
+const PARENT: Address = Address::repeat_byte(0x20);
+const CHILD: Address = Address::repeat_byte(0x21);
+const REVERTER: Address = Address::repeat_byte(0x22);
+
+/// Traces a parent that emits a log, sends 1 wei to a child that emits a
+/// log, then calls a contract that reverts with `Error("nope")`.
+fn traced() -> CallTrace {
+    // LOG0(0, 0), CALL child with 1 wei, CALL reverter, STOP
+    let mut parent = vec![0x60, 0x00, 0x60, 0x00, 0xa0];
+    parent.extend(common::call_asm(CHILD, 1));
+    parent.extend(common::call_asm(REVERTER, 0));
+    parent.push(0x00);
+    // LOG1(0, 0, 0x2a), STOP
+    let child = vec![0x60, 0x2a, 0x60, 0x00, 0x60, 0x00, 0xa1, 0x00];
+    let reverter = common::revert_asm(&Revert::from("nope").abi_encode());
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, PARENT, parent, U256::from(10));
+    common::deploy(&mut db, CHILD, child);
+    common::deploy(&mut db, REVERTER, reverter);
+
+    // transfer(address,uint256) with a zero recipient and amount
+    let mut input = vec![0xa9, 0x05, 0x9c, 0xbb];
+    input.extend([0; 64]);
+    let mut inspector = CallTraceInspector::new();
+    let result = common::run(&mut db, common::call_env(PARENT, input), &mut inspector);
+    assert!(result.is_success());
+    inspector.into_trace()
+}
+
+fn resolver() -> Arc<StaticSelectorResolver> {
+    let mut resolver = StaticSelectorResolver::default();
+    resolver.insert([0xa9, 0x05, 0x9c, 0xbb], "transfer(address,uint256)");
+    Arc::new(resolver)
+}
+
+#[test]
+fn test_pretty_print_snapshot() {
+    let out = traced().pretty_print(PrettyPrintOptions {
+        max_calldata_bytes: 8,
+        resolver: Some(resolver()),
+        ..Default::default()
+    });
+    assert_eq!(
+        out,
+        "\
+[13138] CALL 0x2020202020202020202020202020202020202020::transfer(0x0000000000000000… (64 bytes)) → success
+├─ [759] CALL 0x2121212121212121212121212121212121212121::fallback{value: 1}() → success ⚠ gas capped (requested 978279, granted 953875)
+└─ [54] CALL 0x2222222222222222222222222222222222222222::fallback() → revert: nope ⚠ gas capped (requested 968198, granted 950511)
+"
+    );
+}
+
+#[test]
+fn test_pretty_print_with_logs() {
+    let out = traced().pretty_print(PrettyPrintOptions {
+        show_logs: true,
+        ..Default::default()
+    });
+    assert_eq!(
+        out,
+        "\
+[13138] CALL 0x2020202020202020202020202020202020202020::0xa9059cbb(0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000) → success
+├─ emit topics: [] data: 0x
+├─ [759] CALL 0x2121212121212121212121212121212121212121::fallback{value: 1}() → success ⚠ gas capped (requested 978279, granted 953875)
+│  └─ emit topics: [0x000000000000000000000000000000000000000000000000000000000000002a] data: 0x
+└─ [54] CALL 0x2222222222222222222222222222222222222222::fallback() → revert: nope ⚠ gas capped (requested 968198, granted 950511)
+"
+    );
+}
+
+fn frame(kind: CallKind, target: u8, depth: usize, children: Vec<CallTraceNode>) -> CallTraceNode {
+    CallTraceNode {
+        kind,
+        caller: Address::ZERO,
+        target: Address::repeat_byte(target),
+        delegate: None,
+        value: U256::ZERO,
+        input: Bytes::new(),
+        output: Bytes::new(),
+        gas_limit: 100_000,
+        gas_requested: None,
+        gas_capped: false,
+        gas_used: 100,
+        success: true,
+        result: InstructionResult::Stop,
+        depth,
+        start_step: 0,
+        end_step: 0,
+        start_micros: None,
+        end_micros: None,
+        children,
+        logs: Vec::new(),
+    }
+}
+
+#[test]
+fn test_deep_frames_are_collapsed() {
+    // A chain of 50 nested calls
+    let mut node = frame(CallKind::Call, 0x49, 49, vec![]);
+    for depth in (0..49).rev() {
+        node = frame(CallKind::Call, depth as u8, depth, vec![node]);
+    }
+    let trace = CallTrace::new(node);
+
+    let full = trace.pretty_print(PrettyPrintOptions::default());
+    assert_eq!(full.lines().count(), 50);
+    let last = full.lines().last().unwrap();
+    assert!(last.starts_with(&format!("{}└─ [100] CALL", "   ".repeat(48))));
+
+    let out = trace.pretty_print(PrettyPrintOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    });
+    assert_eq!(
+        out,
+        "\
+[100] CALL 0x0000000000000000000000000000000000000000::fallback() → success
+└─ [100] CALL 0x0101010101010101010101010101010101010101::fallback() → success
+   └─ … 48 nested frames not shown
+"
+    );
+}
+
+#[test]
+fn test_colored_outcomes() {
+    let mut root = frame(CallKind::Call, 0x20, 0, vec![]);
+    root.success = false;
+    root.result = InstructionResult::OutOfGas;
+    let out = CallTrace::new(root).pretty_print(PrettyPrintOptions {
+        color: true,
+        ..Default::default()
+    });
+    assert_eq!(
+        out,
+        "\x1b[2m[100]\x1b[0m CALL 0x2020202020202020202020202020202020202020::fallback() → \x1b[31merror: out of gas\x1b[0m\n"
+    );
+    assert_eq!(CallTrace::default().pretty_print(PrettyPrintOptions::default()), "");
+}
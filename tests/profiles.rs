@@ -0,0 +1,148 @@
+//! Integration tests for the named configuration profiles.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    ConfigError, HelloWorldInspector, HelloWorldInspectorConfig, HelloWorldInspectorPlugin,
+    InspectorPlugin, InspectorProfile, OutputMode, ProfilingMode,
+};
+use revm::InMemoryDB;
+use serde_json::{json, Map, Value};
+
+const CONTRACT: Address = Address::repeat_byte(0x20);
+
+/// Returns the fields `profile` changes from the defaults.
+fn preset_fields(profile: InspectorProfile) -> Map<String, Value> {
+    let config = HelloWorldInspectorConfig {
+        profile: None,
+        ..HelloWorldInspectorConfig::profile(profile)
+    };
+    let Value::Object(fields) = serde_json::to_value(config).unwrap() else {
+        unreachable!()
+    };
+    let Value::Object(defaults) =
+        serde_json::to_value(HelloWorldInspectorConfig::default()).unwrap()
+    else {
+        unreachable!()
+    };
+    fields
+        .into_iter()
+        .filter(|(key, value)| defaults.get(key) != Some(value))
+        .collect()
+}
+
+#[test]
+fn test_presets_change_only_their_fields() {
+    let expected = [
+        (
+            InspectorProfile::Minimal,
+            json!({"profiling": "counters", "unchecked_call_window": 0}),
+        ),
+        (
+            InspectorProfile::CallTracing,
+            json!({"trace_calls": true, "profiling": "counters", "unchecked_call_window": 0}),
+        ),
+        (
+            InspectorProfile::Profiling,
+            json!({"attribute_subcall_gas": true, "measure_time": true, "unchecked_call_window": 0}),
+        ),
+        (
+            InspectorProfile::Debugging,
+            json!({
+                "verbose": true,
+                "log_steps": true,
+                "trace_calls": true,
+                "step_sample_interval": 1,
+                "capture_code": true,
+            }),
+        ),
+        (
+            InspectorProfile::Security,
+            json!({
+                "trace_calls": true,
+                "alert_on_opcodes": ["CALLCODE", "DELEGATECALL", "SELFDESTRUCT"],
+            }),
+        ),
+    ];
+    for (profile, fields) in expected {
+        assert_eq!(Value::Object(preset_fields(profile)), fields, "{profile}");
+    }
+}
+
+#[test]
+fn test_overrides_and_round_trip() {
+    let config = HelloWorldInspectorConfig::from_toml_str(
+        r#"
+        profile = "security"
+        measure_time = true
+        trace_calls = false
+
+        [limits]
+        max_logs = 100
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.profile, Some(InspectorProfile::Security));
+    assert!(config.measure_time);
+    assert!(!config.trace_calls);
+    assert_eq!(config.alert_on_opcodes.len(), 3);
+    assert_eq!(config.limits.max_logs, Some(100));
+    assert_eq!(
+        config.limits.max_call_records,
+        HelloWorldInspectorConfig::default().limits.max_call_records
+    );
+
+    // Only the profile and the overrides are written back
+    let value = serde_json::to_value(&config).unwrap();
+    assert_eq!(value["profile"], "security");
+    assert_eq!(value["measure_time"], true);
+    assert_eq!(value["trace_calls"], false);
+    assert_eq!(value["limits"]["max_logs"], 100);
+    assert!(value.get("alert_on_opcodes").is_none());
+    let reloaded: HelloWorldInspectorConfig = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&reloaded).unwrap(), value);
+
+    // An untouched preset is just its name
+    for profile in InspectorProfile::ALL {
+        let config = HelloWorldInspectorConfig::profile(profile);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            json!({"profile": profile.name()})
+        );
+    }
+}
+
+#[test]
+fn test_invalid_profiles_and_fields_are_rejected() {
+    assert!(matches!(
+        HelloWorldInspectorConfig::from_toml_str(r#"profile = "fastest""#),
+        Err(ConfigError::Toml(message)) if message.contains("fastest")
+    ));
+    assert!(matches!(
+        HelloWorldInspectorConfig::from_toml_str("profile = \"minimal\"\nverbos = true"),
+        Err(ConfigError::UnknownKey(key)) if key == "verbos"
+    ));
+    let err = "fastest".parse::<InspectorProfile>().unwrap_err();
+    assert!(err.contains("call_tracing"), "{err}");
+    assert_eq!("call_tracing".parse(), Ok(InspectorProfile::CallTracing));
+}
+
+#[test]
+fn test_plugin_and_builder_accept_profiles() {
+    let plugin = HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig::default());
+    InspectorPlugin::<InMemoryDB>::init(&plugin, &json!({"profile": "security"})).unwrap();
+    assert!(InspectorPlugin::<InMemoryDB>::init(&plugin, &json!({"profile": "fastest"})).is_err());
+
+    let mut inspector = HelloWorldInspector::builder()
+        .profile(InspectorProfile::Minimal)
+        .output(OutputMode::Silent)
+        .build();
+    assert_eq!(inspector.config().profiling, ProfilingMode::Counters);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, common::countdown_loop());
+    let result = common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(result.is_success());
+    assert_eq!(inspector.steps(), 23);
+    assert!(inspector.report().opcode_counts.is_empty());
+}
@@ -0,0 +1,44 @@
+//! Integration tests for tracking proxies and the code they delegate to.
+
+mod common;
+
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{Database, InMemoryDB};
+
+#[test]
+fn test_delegatecall_records_proxy_and_implementation() {
+    let proxy = Address::repeat_byte(0x20);
+    let implementation = Address::repeat_byte(0x21);
+    let mut code = common::delegate_call_asm(implementation);
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, proxy, code);
+    // SSTORE(0, 1), STOP
+    common::deploy(&mut db, implementation, vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(proxy, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let calls = inspector.gas_by_call();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].kind, "CALL");
+    assert_eq!((calls[0].target, calls[0].bytecode_address), (proxy, proxy));
+    assert_eq!(calls[1].kind, "DELEGATECALL");
+    assert_eq!(calls[1].target, proxy);
+    assert_eq!(calls[1].bytecode_address, implementation);
+    assert_eq!(calls[1].caller, common::CALLER);
+
+    assert_eq!(
+        inspector.resolve_implementations(),
+        HashMap::from([(proxy, implementation)])
+    );
+    // The implementation's code wrote to the proxy's storage
+    assert_eq!(db.storage(proxy, U256::ZERO).unwrap(), U256::from(1));
+}
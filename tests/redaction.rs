@@ -0,0 +1,123 @@
+//! Integration tests for redacting calldata, return data and storage values.
+
+mod common;
+
+use alloy_primitives::{hex, keccak256, Address, U256};
+use restd::{
+    CallTraceInspector, FileTraceSink, HelloWorldInspector, HelloWorldInspectorConfig,
+    OutputMode, PrettyPrintOptions, Redaction, RevertReason,
+};
+use revm::InMemoryDB;
+
+const VAULT: Address = Address::repeat_byte(0x20);
+const OTHER: Address = Address::repeat_byte(0x21);
+const SECRET: [u8; 32] = [0x5e; 32];
+
+/// Stores the first calldata word at slot 0, then reverts with the calldata.
+fn vault_code() -> Vec<u8> {
+    vec![
+        0x60, 0x00, 0x35, 0x60, 0x00, 0x55, // SSTORE(0, CALLDATALOAD(0))
+        0x36, 0x60, 0x00, 0x60, 0x00, 0x37, // CALLDATACOPY(0, 0, CALLDATASIZE)
+        0x36, 0x60, 0x00, 0xfd, // REVERT(0, CALLDATASIZE)
+    ]
+}
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, VAULT, vault_code());
+    db
+}
+
+fn redacting_config() -> HelloWorldInspectorConfig {
+    HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        trace_calls: true,
+        redact_calldata: true,
+        redact_storage_values: true,
+        ..Default::default()
+    }
+}
+
+fn assert_no_secret(output: &str) {
+    assert!(!output.contains(&hex::encode(SECRET)), "secret leaked: {output}");
+}
+
+#[test]
+fn test_secret_is_absent_from_every_output() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let sink = FileTraceSink::create(file.path()).unwrap();
+    let mut inspector =
+        HelloWorldInspector::with_config(redacting_config()).with_sink(sink.clone());
+    let env = common::call_env(VAULT, SECRET.to_vec());
+    let result = common::run(&mut db(), env, &mut inspector);
+    assert!(!result.is_success());
+    sink.flush().unwrap();
+
+    let hash = keccak256(SECRET);
+    let report = inspector.report();
+    assert_eq!(report.storage_accesses.len(), 1);
+    let access = &report.storage_accesses[0];
+    assert!(access.value.is_zero());
+    assert_eq!(access.redacted_value.as_ref().unwrap().keccak256, hash);
+    let call = &report.calls[0];
+    assert_eq!(call.selector, None);
+    assert_eq!(call.revert_reason.as_deref(), Some("<redacted 32 bytes>"));
+    assert_eq!(call.redacted_calldata.as_ref().unwrap().keccak256, hash);
+    let revert = &report.reverts[0];
+    assert!(revert.raw.is_empty());
+    assert!(matches!(&revert.reason, RevertReason::Redacted(r) if r.keccak256 == hash));
+
+    let json = report.to_json().unwrap();
+    assert_no_secret(&json);
+    assert!(json.contains(&hash.to_string()));
+
+    let mut csv = Vec::new();
+    report.to_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_no_secret(&csv);
+    assert!(csv.contains("<redacted 32 bytes>"));
+
+    assert_no_secret(&std::fs::read_to_string(file.path()).unwrap());
+
+    let mut tracer = CallTraceInspector::new();
+    common::run(&mut db(), common::call_env(VAULT, SECRET.to_vec()), &mut tracer);
+    let pretty = tracer.into_trace().pretty_print(PrettyPrintOptions {
+        redaction: redacting_config().redaction(),
+        ..Default::default()
+    });
+    assert_no_secret(&pretty);
+    assert!(pretty.contains(&format!(
+        "{VAULT}::<redacted 32 bytes> → revert: <redacted 32 bytes>"
+    )));
+}
+
+#[test]
+fn test_redacted_addresses_only_hide_their_frames() {
+    let mut db = db();
+    common::deploy(&mut db, OTHER, vault_code());
+    let config = HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        redact_addresses: vec![OTHER],
+        ..Default::default()
+    };
+    let mut inspector = HelloWorldInspector::with_config(config.clone());
+    common::run(&mut db, common::call_env(VAULT, SECRET.to_vec()), &mut inspector);
+    let report = inspector.report();
+    assert!(report.calls[0].redacted_calldata.is_none());
+    assert!(report.to_json().unwrap().contains(&hex::encode(SECRET)));
+
+    let mut inspector = HelloWorldInspector::with_config(config);
+    common::run(&mut db, common::call_env(OTHER, SECRET.to_vec()), &mut inspector);
+    let report = inspector.report();
+    assert!(report.calls[0].redacted_calldata.is_some());
+    // Storage values are only redacted with `redact_storage_values`
+    assert_eq!(report.storage_accesses[0].value, U256::from_be_bytes(SECRET));
+    assert!(matches!(report.reverts[0].reason, RevertReason::Redacted(_)));
+
+    let redaction = Redaction {
+        addresses: vec![OTHER],
+        ..Default::default()
+    };
+    assert!(redaction.redacts_calldata_of(&OTHER));
+    assert!(!redaction.redacts_calldata_of(&VAULT));
+}
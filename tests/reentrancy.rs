@@ -0,0 +1,205 @@
+//! Integration tests for reentrancy screening of call traces.
+
+mod common;
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{
+    CallKind, CallTrace, CallTraceInspector, CallTraceNode, HelloWorldInspector,
+    HelloWorldInspectorConfig, InspectorStack, OutputMode, ReentrancyKind, StorageAccess,
+    StorageAccessKind,
+};
+use revm::{inspector_handle_register, interpreter::InstructionResult, Evm, InMemoryDB};
+
+const VAULT: Address = Address::repeat_byte(0x20);
+const ATTACKER: Address = Address::repeat_byte(0x21);
+
+fn frame(
+    kind: CallKind,
+    target: Address,
+    depth: usize,
+    steps: (u64, u64),
+    children: Vec<CallTraceNode>,
+) -> CallTraceNode {
+    CallTraceNode {
+        kind,
+        caller: Address::ZERO,
+        target,
+        delegate: None,
+        value: U256::ZERO,
+        input: Bytes::new(),
+        output: Bytes::new(),
+        gas_limit: 100_000,
+        gas_requested: None,
+        gas_capped: false,
+        gas_used: 0,
+        success: true,
+        result: InstructionResult::Stop,
+        depth,
+        start_step: steps.0,
+        end_step: steps.1,
+        start_micros: None,
+        end_micros: None,
+        children,
+        logs: Vec::new(),
+    }
+}
+
+fn write(address: Address, step_index: u64) -> StorageAccess {
+    StorageAccess {
+        address,
+        slot: U256::ZERO,
+        kind: StorageAccessKind::Write,
+        value: U256::ZERO,
+        pc: 0,
+        step_index,
+        reverted: false,
+        tx_index: 0,
+        seq: 0,
+        slot_preimage: None,
+        redacted_value: None,
+        source_tag: None,
+    }
+}
+
+#[test]
+fn test_vault_reentry_with_late_state_update() {
+    // Vault.withdraw sends funds to the attacker, whose fallback calls
+    // withdraw again before the vault zeroes the balance at step 40
+    let trace = CallTrace::new(frame(
+        CallKind::Call,
+        VAULT,
+        0,
+        (0, 45),
+        vec![frame(
+            CallKind::Call,
+            ATTACKER,
+            1,
+            (10, 38),
+            vec![frame(CallKind::Call, VAULT, 2, (15, 30), vec![])],
+        )],
+    ));
+    let accesses = [write(VAULT, 28), write(VAULT, 40)];
+
+    let findings = trace.find_reentrancy(&accesses);
+    assert_eq!(findings.len(), 1);
+    let finding = &findings[0];
+    assert_eq!(finding.address, VAULT);
+    assert_eq!(finding.kind, ReentrancyKind::Direct);
+    assert_eq!(finding.call_kind, CallKind::Call);
+    assert_eq!((finding.ancestor_depth, finding.depth), (0, 2));
+    assert_eq!(finding.path, vec![VAULT, ATTACKER, VAULT]);
+    assert!(finding.state_changed_after);
+
+    // Updating the balance before the external call is the safe pattern
+    let findings = trace.find_reentrancy(&[write(VAULT, 5), write(VAULT, 28)]);
+    assert!(!findings[0].state_changed_after);
+}
+
+#[test]
+fn test_static_callback_without_state_change() {
+    let a = Address::repeat_byte(0x30);
+    let b = Address::repeat_byte(0x31);
+    let c = Address::repeat_byte(0x32);
+    // A -> B -> A (static), then A -> B -> C -> A (static)
+    let trace = CallTrace::new(frame(
+        CallKind::Call,
+        a,
+        0,
+        (0, 60),
+        vec![
+            frame(
+                CallKind::Call,
+                b,
+                1,
+                (5, 20),
+                vec![frame(CallKind::StaticCall, a, 2, (10, 15), vec![])],
+            ),
+            frame(
+                CallKind::Call,
+                b,
+                1,
+                (25, 50),
+                vec![frame(
+                    CallKind::Call,
+                    c,
+                    2,
+                    (30, 45),
+                    vec![frame(CallKind::StaticCall, a, 3, (35, 40), vec![])],
+                )],
+            ),
+        ],
+    ));
+
+    let findings = trace.find_reentrancy(&[write(a, 2), write(b, 22)]);
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].kind, ReentrancyKind::Direct);
+    assert_eq!(findings[0].call_kind, CallKind::StaticCall);
+    assert!(!findings[0].state_changed_after);
+    assert_eq!(findings[1].kind, ReentrancyKind::Indirect);
+    assert_eq!(findings[1].path, vec![a, b, c, a]);
+    assert!(!findings[1].state_changed_after);
+}
+
+#[test]
+fn test_self_calls_are_not_reentry() {
+    let trace = CallTrace::new(frame(
+        CallKind::Call,
+        VAULT,
+        0,
+        (0, 20),
+        vec![frame(
+            CallKind::DelegateCall,
+            VAULT,
+            1,
+            (5, 15),
+            vec![frame(CallKind::Call, VAULT, 2, (8, 12), vec![])],
+        )],
+    ));
+    assert!(trace.find_reentrancy(&[]).is_empty());
+}
+
+#[test]
+fn test_traced_vault_reentry() {
+    // Vault: CALL attacker, then SSTORE(0, 1)
+    let mut vault = common::call_asm(ATTACKER, 0);
+    vault.extend_from_slice(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+    // Attacker: if SLOAD(0) == 0 { SSTORE(0, 1); CALL vault }
+    let mut attacker = vec![0x60, 0x00, 0x54, 0x60, 0x2d, 0x57, 0x60, 0x01, 0x60, 0x00, 0x55];
+    attacker.extend(common::call_asm(VAULT, 0));
+    attacker.extend_from_slice(&[0x5b, 0x00]);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, VAULT, vault);
+    common::deploy(&mut db, ATTACKER, attacker);
+
+    let mut stack = InspectorStack::new()
+        .with(HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+            output: OutputMode::Silent,
+            ..Default::default()
+        }))
+        .with(CallTraceInspector::new());
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_env(Box::new(common::call_env(VAULT, vec![])))
+        .with_external_context(&mut stack)
+        .append_handler_register(inspector_handle_register)
+        .build();
+    assert!(evm.transact_commit().unwrap().is_success());
+    drop(evm);
+
+    let trace = stack.remove::<CallTraceInspector>().unwrap().into_trace();
+    let inspector = stack.get::<HelloWorldInspector>().unwrap();
+    let findings = trace.find_reentrancy(inspector.storage_accesses());
+
+    let summary: Vec<_> = findings
+        .iter()
+        .map(|f| (f.address, f.kind, f.state_changed_after))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (VAULT, ReentrancyKind::Direct, true),
+            (ATTACKER, ReentrancyKind::Direct, false),
+        ]
+    );
+    assert_eq!(inspector.report().with_reentrancy(&trace).security.reentrancy, findings);
+}
@@ -0,0 +1,143 @@
+//! Integration tests for merging reports traced on several threads.
+
+mod common;
+
+use std::{collections::BTreeMap, thread};
+
+use alloy_primitives::{Address, U256};
+use restd::{
+    AddressStats, HelloWorldInspector, HelloWorldInspectorConfig, InspectorReport, OutputMode,
+    ReportCollector,
+};
+use revm::InMemoryDB;
+
+const OUTER: Address = Address::repeat_byte(0x20);
+const INNER: Address = Address::repeat_byte(0x21);
+
+fn stats(calls: u64, gas_used: u64) -> AddressStats {
+    AddressStats {
+        calls,
+        gas_used,
+        reverts: 0,
+        value_in: U256::ZERO,
+    }
+}
+
+/// A report with `steps` steps, one `PUSH1` per step and calls to `called`.
+fn synthetic(steps: u64, max_depth: usize, called: &[(Address, u64)]) -> InspectorReport {
+    InspectorReport {
+        step_count: steps,
+        call_count: called.iter().map(|(_, calls)| calls).sum(),
+        max_depth,
+        opcode_counts: BTreeMap::from([("PUSH1".to_string(), steps)]),
+        address_stats: called
+            .iter()
+            .map(|(address, calls)| (*address, stats(*calls, 100 * calls)))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Traces a call from `OUTER` into `INNER`, which emits a log.
+fn traced_report() -> InspectorReport {
+    let mut outer = common::call_asm(INNER, 0);
+    outer.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, outer);
+    // LOG0(0, 0), STOP
+    common::deploy(&mut db, INNER, vec![0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]);
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        trace_calls: true,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector.report()
+}
+
+#[test]
+fn test_merging_three_reports_sums_totals() {
+    let a = Address::repeat_byte(0x30);
+    let b = Address::repeat_byte(0x31);
+    let mut report = synthetic(10, 2, &[(a, 1)]);
+    report.merge(synthetic(20, 4, &[(a, 2), (b, 1)]));
+    let report = report + synthetic(5, 1, &[(b, 3)]);
+
+    assert_eq!(report.step_count, 35);
+    assert_eq!(report.call_count, 7);
+    assert_eq!(report.max_depth, 4);
+    assert_eq!(report.opcode_counts["PUSH1"], 35);
+    assert_eq!(report.address_stats[&a], stats(3, 300));
+    assert_eq!(report.address_stats[&b], stats(4, 400));
+    let busiest: Vec<_> = report
+        .busiest_addresses(2)
+        .into_iter()
+        .map(|(address, stats)| (address, stats.calls))
+        .collect();
+    assert_eq!(busiest, [(b, 4), (a, 3)]);
+}
+
+#[test]
+fn test_merged_records_keep_their_source_tag() {
+    let mut report = traced_report().with_source_tag("first");
+    report += traced_report().with_source_tag("second");
+    report += traced_report();
+
+    assert_eq!(report.source_tag.as_deref(), Some("first"));
+    let call_tags: Vec<_> = report.calls.iter().map(|call| call.source_tag.as_deref()).collect();
+    assert_eq!(
+        call_tags,
+        [Some("first"), Some("first"), Some("second"), Some("second"), None, None]
+    );
+    let log_tags: Vec<_> = report.logs.iter().map(|log| log.source_tag.as_deref()).collect();
+    assert_eq!(log_tags, [Some("first"), Some("second"), None]);
+    assert_eq!(report.address_stats[&INNER].calls, 3);
+    assert_eq!(report.max_depth, 2);
+}
+
+#[test]
+fn test_collector_merges_reports_from_four_threads() {
+    let single = traced_report();
+    let (collector, sender) = ReportCollector::new();
+    let handles: Vec<_> = (0..4)
+        .map(|thread| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for tx in 0..5 {
+                    let report = traced_report().with_source_tag(format!("{thread}-{tx}"));
+                    sender.send(report).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+    let report = collector.finish();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(report.step_count, 20 * single.step_count);
+    assert_eq!(report.call_count, 20 * single.call_count);
+    assert_eq!(report.calls.len(), 20 * single.calls.len());
+    assert_eq!(report.logs.len(), 20);
+    assert_eq!(report.address_stats[&OUTER].calls, 20);
+    assert_eq!(report.opcode_counts["LOG0"], 20);
+    for thread in 0..4 {
+        for tx in 0..5 {
+            let tag = format!("{thread}-{tx}");
+            assert!(report.logs.iter().any(|log| log.source_tag == Some(tag.clone())));
+        }
+    }
+}
+
+#[test]
+fn test_try_merge_does_not_wait() {
+    let (mut collector, sender) = ReportCollector::new();
+    assert_eq!(collector.try_merge(), 0);
+    sender.send(synthetic(3, 1, &[])).unwrap();
+    sender.send(synthetic(4, 1, &[])).unwrap();
+    assert_eq!(collector.try_merge(), 2);
+    assert_eq!(collector.merged(), 2);
+    assert_eq!(collector.report().step_count, 7);
+}
@@ -0,0 +1,174 @@
+//! Integration tests for writing reports in each output format.
+
+mod common;
+
+use std::{fs, path::Path};
+
+use alloy_primitives::Address;
+use restd::{
+    CallTrace, CallTraceInspector, Error, Format, HelloWorldInspector, HelloWorldInspectorConfig,
+    HelloWorldInspectorPlugin, InspectorReport, OutputMode, ReportWriter,
+};
+use revm::InMemoryDB;
+
+const OUTER: Address = Address::repeat_byte(0x30);
+const INNER: Address = Address::repeat_byte(0x31);
+
+fn db() -> InMemoryDB {
+    let mut code = common::call_asm(INNER, 0);
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, code);
+    common::deploy(&mut db, INNER, common::countdown_loop());
+    db
+}
+
+fn traced() -> (InspectorReport, CallTrace) {
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db(), common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    let mut tracer = CallTraceInspector::new();
+    common::run(&mut db(), common::call_env(OUTER, vec![]), &mut tracer);
+    (inspector.report(), tracer.into_trace())
+}
+
+/// Writes the traced transaction as `format` into `dir` and returns the file.
+fn write(dir: &Path, format: Format) -> String {
+    let (report, trace) = traced();
+    let path = dir
+        .join(format!("nested/{format:?}"))
+        .join(format!("report.{}", format.extension()));
+    let writer = ReportWriter::new(format).with_path(&path);
+    writer.write_with_trace(&report, &trace).unwrap();
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(!contents.is_empty());
+    // The temporary file was renamed over the report
+    assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    contents
+}
+
+#[test]
+fn test_json_parses_back_into_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let json = write(dir.path(), Format::Json);
+    let report: InspectorReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(report, traced().0);
+}
+
+#[test]
+fn test_jsonl_has_one_record_per_line_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let jsonl = write(dir.path(), Format::Jsonl);
+    let records: Vec<serde_json::Value> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), traced().0.timeline().len());
+    assert_eq!(records[0]["record"], "call");
+    assert_eq!(records[1]["record"], "call");
+    let seqs: Vec<_> = records
+        .iter()
+        .map(|record| record["seq"].as_u64().unwrap())
+        .collect();
+    assert!(seqs.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_csv_has_a_row_per_call() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv = write(dir.path(), Format::Csv);
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(&reader.headers().unwrap()[0], "tx_index");
+    assert_eq!(reader.records().map(Result::unwrap).count(), 2);
+}
+
+#[test]
+fn test_call_tree_formats() {
+    let dir = tempfile::tempdir().unwrap();
+    let pretty = write(dir.path(), Format::PrettyText);
+    assert!(pretty.contains(&INNER.to_string()), "{pretty}");
+
+    let geth = write(dir.path(), Format::GethCallTracer);
+    let frame: serde_json::Value = serde_json::from_str(&geth).unwrap();
+    assert_eq!(frame["type"], "CALL");
+    assert_eq!(frame["calls"].as_array().unwrap().len(), 1);
+
+    let dot = write(dir.path(), Format::Dot);
+    assert!(dot.starts_with("digraph"), "{dot}");
+}
+
+#[test]
+fn test_call_tree_formats_need_a_trace() {
+    let writer = ReportWriter::new(Format::Dot);
+    let err = writer
+        .write_report(&InspectorReport::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::MissingCallTrace(Format::Dot)));
+}
+
+#[test]
+fn test_failed_write_keeps_the_path() {
+    let dir = tempfile::tempdir().unwrap();
+    // A directory cannot be replaced by the report
+    fs::create_dir(dir.path().join("taken")).unwrap();
+    let path = dir.path().join("taken");
+    let err = ReportWriter::new(Format::Json)
+        .with_path(&path)
+        .write_report(&InspectorReport::default())
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::Output { path: Some(p), .. } if *p == path),
+        "{err}"
+    );
+    assert!(path.is_dir());
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_plugin_writes_merged_report_on_shutdown() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out/report.json");
+    let plugin = HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        output_format: Some(Format::Json),
+        output_path: Some(path.clone()),
+        ..Default::default()
+    });
+    plugin.init().unwrap();
+    for _ in 0..2 {
+        let mut inspector = plugin.create_inspector();
+        common::run(&mut db(), common::call_env(OUTER, vec![]), &mut inspector);
+        plugin.record_report(inspector.report());
+    }
+
+    let report = plugin.on_shutdown();
+    assert!(report.health.healthy);
+    let written: InspectorReport =
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(written.call_count, 4);
+}
+
+#[test]
+fn test_plugin_rejects_call_tree_format() {
+    let plugin = HelloWorldInspectorPlugin::new(HelloWorldInspectorConfig {
+        output_format: Some(Format::GethCallTracer),
+        ..Default::default()
+    });
+    assert!(matches!(
+        plugin.init(),
+        Err(Error::MissingCallTrace(Format::GethCallTracer))
+    ));
+}
+
+#[test]
+fn test_output_format_from_toml() {
+    let config = HelloWorldInspectorConfig::from_toml_str(
+        "output_format = \"geth_call_tracer\"\noutput_path = \"trace.json\"\n",
+    )
+    .unwrap();
+    assert_eq!(config.output_format, Some(Format::GethCallTracer));
+    assert_eq!(config.output_path.as_deref(), Some(Path::new("trace.json")));
+}
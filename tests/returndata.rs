@@ -0,0 +1,86 @@
+//! Integration tests for capturing the output of call and create frames.
+
+mod common;
+
+use alloy_primitives::{keccak256, Address, Bytes};
+use restd::{HelloWorldInspector, OutputMode, ReturnData};
+use revm::{primitives::TxKind, InMemoryDB};
+
+const CONTRACT: Address = Address::repeat_byte(0x80);
+const LIMIT: usize = 32;
+
+fn payload(len: usize) -> Vec<u8> {
+    (1..=len as u8).collect()
+}
+
+/// Runs a call to a contract returning `output` and returns its frame's output.
+fn capture(output: &[u8]) -> Option<ReturnData> {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, common::return_asm(output));
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .max_output_bytes(LIMIT)
+        .build();
+    let result = common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector.report().calls[0].output.clone()
+}
+
+#[test]
+fn test_output_below_limit_is_kept() {
+    let output = capture(&payload(LIMIT - 1)).unwrap();
+    assert_eq!(output.data, Bytes::from(payload(LIMIT - 1)));
+    assert_eq!(output.len, LIMIT - 1);
+    assert!(!output.is_truncated());
+    assert!(!output.to_string().contains('…'));
+}
+
+#[test]
+fn test_output_at_limit_is_kept() {
+    let output = capture(&payload(LIMIT)).unwrap();
+    assert_eq!(output.data, Bytes::from(payload(LIMIT)));
+    assert_eq!(output.len, LIMIT);
+    assert!(!output.is_truncated());
+    assert_eq!(output.keccak256, keccak256(payload(LIMIT)));
+}
+
+#[test]
+fn test_output_above_limit_is_truncated() {
+    let output = capture(&payload(LIMIT + 1)).unwrap();
+    assert_eq!(output.data, Bytes::from(payload(LIMIT)));
+    assert_eq!(output.len, LIMIT + 1);
+    assert!(output.is_truncated());
+    assert_eq!(output.keccak256, keccak256(payload(LIMIT + 1)));
+    assert!(output.to_string().ends_with("1f20…(+1 bytes)"));
+
+    let json = serde_json::to_value(&output).unwrap();
+    assert_eq!(json["data"], output.to_string());
+    assert_eq!(serde_json::from_value::<ReturnData>(json).unwrap(), output);
+}
+
+#[test]
+fn test_plain_transfer_has_no_output() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, vec![]);
+    let mut inspector = HelloWorldInspector::builder().output(OutputMode::Silent).build();
+    common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+
+    let calls = inspector.report().calls;
+    assert_eq!(calls[0].output, None);
+    assert!(!inspector.report_json().unwrap().contains("output"));
+}
+
+#[test]
+fn test_creation_keeps_runtime_code() {
+    let runtime = payload(4);
+    let mut env = common::call_env(Address::ZERO, common::return_asm(&runtime));
+    env.tx.transact_to = TxKind::Create;
+    let mut db = InMemoryDB::default();
+    let mut inspector = HelloWorldInspector::builder().output(OutputMode::Silent).build();
+    let result = common::run(&mut db, env, &mut inspector);
+    assert!(result.is_success());
+
+    let output = inspector.creations()[0].output.clone().unwrap();
+    assert_eq!(output.data, Bytes::from(runtime));
+    assert_eq!(output.len, 4);
+}
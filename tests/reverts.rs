@@ -0,0 +1,103 @@
+//! Integration tests for revert reason decoding.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{Panic, Revert, SolError};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, RevertReason};
+use revm::{primitives::ExecutionResult, InMemoryDB};
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_reverts_decode_nested_reasons() {
+    let outer = Address::repeat_byte(0x20);
+    let string_revert = Address::repeat_byte(0x31);
+    let panic_revert = Address::repeat_byte(0x32);
+    let empty_revert = Address::repeat_byte(0x33);
+    let custom_revert = Address::repeat_byte(0x34);
+
+    let mut code = Vec::new();
+    for target in [string_revert, panic_revert, empty_revert, custom_revert] {
+        code.extend(common::call_asm(target, 0));
+    }
+    code.push(0x00);
+
+    let custom = [0xde, 0xad, 0xbe, 0xef, 0x00, 0x2a];
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, code);
+    common::deploy(
+        &mut db,
+        string_revert,
+        common::revert_asm(&Revert::from("not enough tokens").abi_encode()),
+    );
+    common::deploy(
+        &mut db,
+        panic_revert,
+        common::revert_asm(&Panic::from(U256::from(0x12)).abi_encode()),
+    );
+    common::deploy(&mut db, empty_revert, common::revert_asm(&[]));
+    common::deploy(&mut db, custom_revert, common::revert_asm(&custom));
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let reverts = inspector.reverts();
+    assert_eq!(reverts.len(), 4);
+    assert!(reverts.iter().all(|r| r.depth == 1));
+
+    assert_eq!(reverts[0].address, string_revert);
+    assert_eq!(
+        reverts[0].reason,
+        RevertReason::Error {
+            message: "not enough tokens".into()
+        }
+    );
+
+    assert_eq!(reverts[1].address, panic_revert);
+    assert_eq!(
+        reverts[1].reason.to_string(),
+        "panic: division or modulo by zero (0x12)"
+    );
+
+    assert_eq!(reverts[2].address, empty_revert);
+    assert_eq!(reverts[2].reason, RevertReason::Empty);
+    assert!(reverts[2].raw.is_empty());
+
+    assert_eq!(reverts[3].address, custom_revert);
+    assert_eq!(
+        reverts[3].reason,
+        RevertReason::Custom {
+            selector: Some([0xde, 0xad, 0xbe, 0xef]),
+            data: custom.to_vec().into(),
+        }
+    );
+    assert_eq!(reverts[3].raw.as_ref(), &custom);
+}
+
+#[test]
+fn test_reverts_top_level_frame() {
+    let target = Address::repeat_byte(0x31);
+    let mut db = InMemoryDB::default();
+    common::deploy(
+        &mut db,
+        target,
+        common::revert_asm(&Revert::from("nope").abi_encode()),
+    );
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(target, vec![]), &mut inspector);
+    assert!(matches!(result, ExecutionResult::Revert { .. }));
+
+    let reverts = inspector.reverts();
+    assert_eq!(reverts.len(), 1);
+    assert_eq!(reverts[0].depth, 0);
+    assert_eq!(reverts[0].reason.to_string(), "nope");
+    assert_eq!(inspector.report().reverts, reverts);
+}
@@ -0,0 +1,123 @@
+//! Integration tests for the JSON-RPC debug endpoint.
+
+#![cfg(feature = "rpc")]
+
+mod common;
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use alloy_primitives::{Address, B256};
+use restd::{
+    rpc::{self, SharedSession},
+    HelloWorldInspectorConfig, OutputMode, TraceSession,
+};
+use revm::InMemoryDB;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+const A: Address = Address::repeat_byte(0xaa);
+const B: Address = Address::repeat_byte(0xbb);
+
+/// Traces one call from A into B and serves the session on an ephemeral port.
+async fn start() -> (SocketAddr, SharedSession) {
+    let mut db = InMemoryDB::default();
+    let mut code_a = common::call_asm(B, 0);
+    code_a.push(0x00);
+    common::deploy(&mut db, A, code_a);
+    common::deploy(&mut db, B, vec![0x00]);
+
+    let session = Arc::new(Mutex::new(TraceSession::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })));
+    {
+        let mut session = session.lock().unwrap();
+        session.begin_tx(B256::with_last_byte(1));
+        common::run(&mut db, common::call_env(A, vec![]), session.inspector_mut());
+        session.end_tx();
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(rpc::serve_listener(listener, session.clone()));
+    (addr, session)
+}
+
+async fn post(addr: SocketAddr, body: impl Into<reqwest::Body>) -> Value {
+    let response = reqwest::Client::new()
+        .post(format!("http://{addr}/"))
+        .header("content-type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.unwrap()
+}
+
+async fn request(addr: SocketAddr, method: &str, params: Value) -> Value {
+    let body = json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params });
+    let response = post(addr, body.to_string()).await;
+    assert_eq!(response["jsonrpc"], "2.0");
+    assert_eq!(response["id"], 7);
+    response
+}
+
+#[tokio::test]
+async fn test_get_report_returns_block_report() {
+    let (addr, session) = start().await;
+    let response = request(addr, "restd_getReport", json!([])).await;
+
+    let expected = serde_json::to_value(session.lock().unwrap().block_report()).unwrap();
+    assert_eq!(response["result"], expected);
+    assert_eq!(response["result"]["total_calls"], 2);
+}
+
+#[tokio::test]
+async fn test_get_call_trace_returns_calls_of_transaction() {
+    let (addr, _session) = start().await;
+    for params in [json!([0]), json!({ "tx_index": 0 })] {
+        let response = request(addr, "restd_getCallTrace", params).await;
+        let calls = response["result"].as_array().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0]["target"], json!(A));
+        assert_eq!(calls[1]["target"], json!(B));
+    }
+}
+
+#[tokio::test]
+async fn test_out_of_range_tx_index_is_an_rpc_error() {
+    let (addr, _session) = start().await;
+    let response = request(addr, "restd_getCallTrace", json!([1])).await;
+    assert_eq!(response["error"]["code"], rpc::INVALID_PARAMS);
+    assert_eq!(response["error"]["message"], "tx_index 1 out of range, 1 transactions traced");
+    assert!(response.get("result").is_none());
+}
+
+#[tokio::test]
+async fn test_reset_session_drops_transactions() {
+    let (addr, session) = start().await;
+    let response = request(addr, "restd_resetSession", Value::Null).await;
+    assert_eq!(response["result"], true);
+    assert!(session.lock().unwrap().transactions().is_empty());
+
+    let response = request(addr, "restd_getCallTrace", json!([0])).await;
+    assert_eq!(response["error"]["code"], rpc::INVALID_PARAMS);
+}
+
+#[tokio::test]
+async fn test_malformed_requests_are_rpc_errors() {
+    let (addr, _session) = start().await;
+    let response = request(addr, "restd_getBlock", json!([])).await;
+    assert_eq!(response["error"]["code"], rpc::METHOD_NOT_FOUND);
+
+    let response = post(addr, "{not json").await;
+    assert_eq!(response["error"]["code"], rpc::PARSE_ERROR);
+    assert_eq!(response["id"], Value::Null);
+
+    let response = post(addr, json!({ "id": 1, "method": "restd_getReport" }).to_string()).await;
+    assert_eq!(response["error"]["code"], rpc::INVALID_REQUEST);
+}
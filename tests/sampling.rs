@@ -0,0 +1,120 @@
+//! Integration tests for sampled and counters-only profiling.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, ProfilingMode};
+use revm::InMemoryDB;
+
+const ENTRY: Address = Address::repeat_byte(0x60);
+const LOOP: Address = Address::repeat_byte(0x61);
+
+/// Counts down from 1000 in a seven-instruction loop starting at pc 3.
+fn hot_loop() -> Vec<u8> {
+    vec![
+        0x61, 0x03, 0xe8, // PUSH2 1000
+        0x5b, // JUMPDEST
+        0x60, 0x01, 0x90, 0x03, // PUSH1 1, SWAP1, SUB
+        0x80, 0x60, 0x03, 0x57, // DUP1, PUSH1 3, JUMPI
+        0x00,
+    ]
+}
+
+fn trace(profiling: ProfilingMode) -> HelloWorldInspector {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, ENTRY, common::call_asm(LOOP, 0));
+    common::deploy(&mut db, LOOP, hot_loop());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        profiling,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(ENTRY, Vec::new()), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_samples_reflect_dominant_loop() {
+    let full = trace(ProfilingMode::Full);
+    assert!(full.sample_profile().is_none());
+
+    let sampled = trace(ProfilingMode::sampled(5));
+    assert_eq!(sampled.step_count, full.step_count);
+    // Step-level recording is skipped
+    assert!(sampled.report().opcode_counts.is_empty());
+    assert_eq!(sampled.report().call_count, 2);
+
+    let profile = sampled.sample_profile().unwrap();
+    assert_eq!(profile.samples_taken, full.step_count / 5);
+    assert_eq!(profile.samples_kept as u64, profile.samples_taken);
+    let (hottest, samples) = profile.top_contracts[0];
+    assert_eq!(hottest, LOOP);
+    assert!(samples * 100 >= profile.samples_taken * 99, "{profile:?}");
+    // Every loop instruction is sampled about as often
+    assert_eq!(profile.top_pcs.len(), 7 + 2);
+    for pc in &profile.top_pcs[..7] {
+        assert_eq!(pc.address, LOOP);
+        assert!((3..=11).contains(&pc.pc));
+        assert!(pc.samples.abs_diff(profile.samples_taken / 7) <= 1, "{pc:?}");
+    }
+}
+
+#[test]
+fn test_ring_capacity_bounds_samples() {
+    let inspector = trace(ProfilingMode::Sampled {
+        every_n_steps: 1,
+        capacity: 64,
+    });
+    let samples = inspector.samples();
+    assert_eq!(samples.capacity(), 64);
+    assert_eq!(samples.len(), 64);
+    assert_eq!(samples.taken(), inspector.step_count);
+    // The oldest samples were discarded: only the end of the trace is left
+    let last: Vec<_> = samples.iter().map(|sample| sample.pc).collect();
+    // The implicit STOP past the end of the entry code
+    assert_eq!(last[63] as usize, common::call_asm(LOOP, 0).len());
+    assert!(samples.iter().take(60).all(|sample| sample.address == LOOP));
+
+    let profile = inspector.sample_profile().unwrap();
+    assert_eq!(profile.samples_kept, 64);
+    assert_eq!(profile.top_contracts.iter().map(|(_, n)| n).sum::<u64>(), 64);
+}
+
+#[test]
+fn test_counters_mode_only_counts() {
+    let full = trace(ProfilingMode::Full);
+    let counters = trace(ProfilingMode::Counters);
+    assert_eq!(counters.step_count, full.step_count);
+    assert!(counters.sample_profile().is_none());
+
+    let report = counters.report();
+    assert_eq!(report.call_count, 2);
+    assert_eq!(report.calls.len(), 2);
+    assert!(report.opcode_counts.is_empty());
+    assert!(counters.samples().is_empty());
+}
+
+#[test]
+fn test_counters_mode_from_toml() {
+    let config = HelloWorldInspectorConfig::from_toml_str("profiling = \"counters\"\n").unwrap();
+    assert_eq!(config.profiling, ProfilingMode::Counters);
+}
+
+#[test]
+fn test_zero_interval_is_rejected() {
+    let err = HelloWorldInspector::builder()
+        .profiling(ProfilingMode::sampled(0))
+        .try_build()
+        .unwrap_err();
+    assert_eq!(err, restd::ConfigError::ZeroSampleInterval);
+}
+
+#[test]
+fn test_sampled_mode_from_toml() {
+    let config = HelloWorldInspectorConfig::from_toml_str(
+        "[profiling.sampled]\nevery_n_steps = 100\n",
+    )
+    .unwrap();
+    assert_eq!(config.profiling, ProfilingMode::sampled(100));
+}
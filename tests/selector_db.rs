@@ -0,0 +1,101 @@
+//! Integration tests for the file-backed selector database.
+
+mod common;
+
+use alloy_primitives::{hex, Address};
+use restd::{HelloWorldInspector, OutputMode, SelectorDb};
+use revm::InMemoryDB;
+
+const TRANSFER: [u8; 4] = hex!("a9059cbb");
+const APPROVE: [u8; 4] = hex!("095ea7b3");
+const BALANCE_OF: [u8; 4] = hex!("70a08231");
+
+/// An openchain export with a colliding signature for `transfer`.
+const EXPORT: &str = "selector,signature
+0xa9059cbb,transfer(address,uint256)
+0x095ea7b3,\"approve(address,uint256)\"
+a9059cbb,many_msg_babbage(bytes1)
+
+0x70a08231,balanceOf(address)
+";
+
+#[test]
+fn test_import_keeps_first_signature() {
+    let mut db = SelectorDb::default();
+    assert_eq!(db.import_csv(EXPORT.as_bytes()).unwrap(), 3);
+    assert_eq!(db.len(), 3);
+    assert_eq!(db.lookup(TRANSFER), Some("transfer(address,uint256)"));
+    assert_eq!(db.lookup(APPROVE), Some("approve(address,uint256)"));
+    assert_eq!(db.lookup(BALANCE_OF), Some("balanceOf(address)"));
+    assert_eq!(db.lookup([0; 4]), None);
+
+    // Known selectors keep their signature in later imports and merges
+    let csv = "0xa9059cbb,other(uint256)\n0x18160ddd,totalSupply()\n";
+    assert_eq!(db.import_csv(csv.as_bytes()).unwrap(), 1);
+    let mut other = SelectorDb::default();
+    assert!(other.insert(APPROVE, "other(address)"));
+    assert!(!other.insert(APPROVE, "another(address)"));
+    other.insert(hex!("dd62ed3e"), "allowance(address,address)");
+    db.merge(other);
+    assert_eq!(db.len(), 5);
+    assert_eq!(db.lookup(APPROVE), Some("approve(address,uint256)"));
+    assert_eq!(
+        db.lookup(hex!("dd62ed3e")),
+        Some("allowance(address,address)")
+    );
+
+    let err = db
+        .import_csv("0xa9059cbb,transfer()\nnot a line\n".as_bytes())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().starts_with("line 2:"), "{err}");
+}
+
+#[test]
+fn test_database_round_trips_through_its_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("selectors.json");
+
+    let mut db = SelectorDb::open(&path).unwrap();
+    assert!(db.is_empty());
+    assert_eq!(db.path(), Some(path.as_path()));
+    db.import_csv(EXPORT.as_bytes()).unwrap();
+    db.save().unwrap();
+
+    let reopened = SelectorDb::open(&path).unwrap();
+    assert_eq!(reopened, db);
+    let json = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        json.contains(r#""0xa9059cbb": "transfer(address,uint256)""#),
+        "{json}"
+    );
+
+    std::fs::write(&path, r#"{"transfer": "transfer(address,uint256)"}"#).unwrap();
+    let err = SelectorDb::open(&path).unwrap_err();
+    assert!(
+        err.to_string().contains("`transfer` is not a selector"),
+        "{err}"
+    );
+    assert!(SelectorDb::default().save().is_err());
+}
+
+#[test]
+fn test_database_resolves_selectors_of_calls() {
+    let mut db = SelectorDb::default();
+    db.import_csv(EXPORT.as_bytes()).unwrap();
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .selector_resolver(db)
+        .build();
+
+    let target = Address::repeat_byte(0x20);
+    let mut state = InMemoryDB::default();
+    common::deploy(&mut state, target, vec![0x00]);
+    common::run(
+        &mut state,
+        common::call_env(target, APPROVE.to_vec()),
+        &mut inspector,
+    );
+    let call = &inspector.gas_by_call()[0];
+    assert_eq!(call.function.as_deref(), Some("approve(address,uint256)"));
+}
@@ -0,0 +1,71 @@
+//! Integration tests for selector capture and resolution.
+
+mod common;
+
+use std::collections::HashMap;
+
+use alloy_primitives::{hex, Address};
+use restd::{HelloWorldInspector, OutputMode, StaticSelectorResolver};
+use revm::InMemoryDB;
+
+const TRANSFER: [u8; 4] = hex!("a9059cbb");
+const APPROVE: [u8; 4] = hex!("095ea7b3");
+
+fn run_with_input(inspector: &mut HelloWorldInspector, input: Vec<u8>) {
+    let target = Address::repeat_byte(0x20);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, target, vec![0x00]);
+    let result = common::run(&mut db, common::call_env(target, input), inspector);
+    assert!(result.is_success());
+}
+
+fn resolving_inspector() -> HelloWorldInspector {
+    let resolver = StaticSelectorResolver::new(HashMap::from([(
+        TRANSFER,
+        "transfer(address,uint256)".to_string(),
+    )]));
+    HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .selector_resolver(resolver)
+        .build()
+}
+
+#[test]
+fn test_known_selector_is_resolved() {
+    let mut inspector = resolving_inspector();
+    let mut input = TRANSFER.to_vec();
+    input.extend_from_slice(&[0u8; 64]);
+    run_with_input(&mut inspector, input);
+
+    let call = &inspector.gas_by_call()[0];
+    assert_eq!(call.selector.map(|s| s.0), Some(TRANSFER));
+    assert_eq!(call.function.as_deref(), Some("transfer(address,uint256)"));
+    assert_eq!(call.function_label().as_deref(), Some("transfer(address,uint256)"));
+
+    let json = inspector.report_json().unwrap();
+    assert!(json.contains(r#""selector":"0xa9059cbb","function":"transfer(address,uint256)""#));
+}
+
+#[test]
+fn test_unknown_selector_falls_back_to_hex() {
+    let mut inspector = resolving_inspector();
+    run_with_input(&mut inspector, APPROVE.to_vec());
+
+    let call = &inspector.gas_by_call()[0];
+    assert_eq!(call.selector.map(|s| s.0), Some(APPROVE));
+    assert_eq!(call.function, None);
+    assert_eq!(call.function_label().as_deref(), Some("0x095ea7b3"));
+}
+
+#[test]
+fn test_short_input_has_no_selector() {
+    for input in [vec![], vec![0xa9, 0x05]] {
+        let mut inspector = resolving_inspector();
+        run_with_input(&mut inspector, input);
+
+        let call = &inspector.gas_by_call()[0];
+        assert_eq!(call.selector, None);
+        assert_eq!(call.function_label(), None);
+        assert!(!inspector.report_json().unwrap().contains("selector"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Integration tests for the selfdestruct registry.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+/// Assembles `SELFDESTRUCT(beneficiary)`.
+fn selfdestruct_asm(beneficiary: Address) -> Vec<u8> {
+    let mut code = vec![0x73];
+    code.extend_from_slice(beneficiary.as_slice());
+    code.push(0xff);
+    code
+}
+
+#[test]
+fn test_selfdestruct_records_beneficiary_and_value() {
+    let outer = Address::repeat_byte(0x20);
+    let doomed = Address::repeat_byte(0x40);
+    let beneficiary = Address::repeat_byte(0x50);
+
+    // Call the doomed contract, then create a contract with 7 wei whose
+    // constructor destroys it in favour of itself:
+    // PUSH2 <ADDRESS SELFDESTRUCT>, PUSH1 0, MSTORE, CREATE(7, 30, 2), POP, STOP
+    let mut code = common::call_asm(doomed, 0);
+    code.extend_from_slice(&[0x61, 0x30, 0xff, 0x60, 0x00, 0x52]);
+    code.extend_from_slice(&[0x60, 0x02, 0x60, 0x1e, 0x60, 0x07, 0xf0, 0x50, 0x00]);
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, outer, code, U256::from(7));
+    common::deploy_with_balance(
+        &mut db,
+        doomed,
+        selfdestruct_asm(beneficiary),
+        U256::from(1_000),
+    );
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let records = inspector.selfdestructs();
+    assert_eq!(records.len(), 2);
+
+    let destroyed = &records[0];
+    assert_eq!(destroyed.contract, doomed);
+    assert_eq!(destroyed.beneficiary, beneficiary);
+    assert_eq!(destroyed.value, U256::from(1_000));
+    assert_eq!(destroyed.depth, 1);
+    assert!(!destroyed.burns_value());
+    let call = &inspector.gas_by_call()[destroyed.call_index.unwrap()];
+    assert_eq!(call.target, doomed);
+
+    let burned = &records[1];
+    assert_eq!(burned.contract, burned.beneficiary);
+    assert_eq!(burned.value, U256::from(7));
+    assert_eq!(burned.depth, 1);
+    assert_eq!(burned.call_index, Some(0));
+    assert!(burned.burns_value());
+
+    assert_eq!(inspector.total_selfdestruct_value(), U256::from(1_007));
+    let burning: Vec<_> = inspector.burning_selfdestructs().collect();
+    assert_eq!(burning, vec![burned]);
+}
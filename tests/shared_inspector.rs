@@ -0,0 +1,65 @@
+//! Integration tests for reading a `SharedInspector` while it executes.
+
+mod common;
+
+use std::time::Duration;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, SharedInspector};
+use revm::InMemoryDB;
+
+const TARGET: Address = Address::repeat_byte(0x20);
+
+/// Counts down from 0x4000: PUSH2 0x4000, JUMPDEST, PUSH1 1, SWAP1, SUB,
+/// DUP1, PUSH1 3, JUMPI, STOP
+const LONG_LOOP: [u8; 13] = [
+    0x61, 0x40, 0x00, 0x5b, 0x60, 0x01, 0x90, 0x03, 0x80, 0x60, 0x03, 0x57, 0x00,
+];
+
+fn shared() -> SharedInspector {
+    SharedInspector::new(HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    }))
+}
+
+#[tokio::test]
+async fn test_snapshot_while_transacting_on_blocking_thread() {
+    let inspector = shared();
+    let mut executing = inspector.clone();
+    let handle = tokio::task::spawn_blocking(move || {
+        let mut db = InMemoryDB::default();
+        common::deploy(&mut db, TARGET, LONG_LOOP.to_vec());
+        common::run(&mut db, common::call_env(TARGET, vec![]), &mut executing)
+    });
+
+    let mut observed = inspector.snapshot();
+    while observed.steps == 0 {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        observed = inspector.snapshot();
+    }
+    assert!(observed.steps > 0);
+
+    let result = handle.await.unwrap();
+    assert!(result.is_success());
+    let last = inspector.snapshot();
+    assert!(last.steps >= observed.steps);
+    // The PUSH2, seven steps per iteration and the final STOP
+    assert_eq!(last.steps, 1 + 7 * 0x4000 + 1);
+    assert_eq!(last.steps, inspector.lock().steps());
+    assert_eq!(last.calls, 1);
+    assert_eq!(last.depth, 0);
+}
+
+#[test]
+fn test_reset_clears_snapshot() {
+    let mut inspector = shared();
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, TARGET, common::countdown_loop());
+    common::run(&mut db, common::call_env(TARGET, vec![]), &mut inspector);
+    assert_eq!(inspector.snapshot().calls, 1);
+
+    let report = inspector.reset_and_take_report();
+    assert!(report.step_count > 0);
+    assert_eq!(inspector.snapshot(), Default::default());
+}
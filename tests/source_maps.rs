@@ -0,0 +1,125 @@
+//! Integration tests for annotating steps and failures with source locations.
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_primitives::Address;
+use restd::{
+    CallTraceInspector, HelloWorldInspector, OutputMode, PrettyPrintOptions, SourceLocation,
+    SourceMapRegistry, StructLogInspector,
+};
+use revm::InMemoryDB;
+
+const CALLER_CONTRACT: Address = Address::repeat_byte(0x70);
+const TOKEN: Address = Address::repeat_byte(0x71);
+
+const SOURCE: &str = "contract Token {
+    function f() external {
+        x = 1;
+        revert();
+    }
+}
+";
+
+/// `SSTORE(0, 1)` then `REVERT(0, 0)`, at pcs 0, 2, 4, 5, 7 and 8.
+const TOKEN_CODE: [u8; 9] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x80, 0xfd];
+
+/// Maps the first three instructions to `x = 1;` and the rest to `revert();`.
+fn source_maps() -> Arc<SourceMapRegistry> {
+    let store = SOURCE.find("x = 1").unwrap();
+    let revert = SOURCE.find("revert").unwrap();
+    let srcmap = format!("{store}:5:0:-;;;{revert}:8;;");
+    let mut maps = SourceMapRegistry::new();
+    maps.register(TOKEN, &srcmap, [("Token.sol", SOURCE)])
+        .unwrap();
+    Arc::new(maps)
+}
+
+fn location(line: u32, column: u32) -> SourceLocation {
+    SourceLocation {
+        file: "Token.sol".to_string(),
+        line,
+        column,
+    }
+}
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CALLER_CONTRACT, common::call_asm(TOKEN, 0));
+    common::deploy(&mut db, TOKEN, TOKEN_CODE.to_vec());
+    db
+}
+
+#[test]
+fn test_pcs_map_to_source_lines() {
+    let maps = source_maps();
+    // pc 2 is the second instruction, pc 8 the sixth
+    assert_eq!(maps.locate(TOKEN, &TOKEN_CODE, 2), Some(location(3, 9)));
+    assert_eq!(maps.locate(TOKEN, &TOKEN_CODE, 8), Some(location(4, 9)));
+    // Push operands and unknown contracts have no location
+    assert_eq!(maps.locate(TOKEN, &TOKEN_CODE, 1), None);
+    assert_eq!(maps.locate(CALLER_CONTRACT, &TOKEN_CODE, 2), None);
+
+    let mut inspector = StructLogInspector::new().with_source_maps(maps);
+    common::run(&mut db(), common::call_env(TOKEN, vec![]), &mut inspector);
+    let logs = inspector.struct_logs();
+    let pcs: Vec<_> = logs.iter().map(|log| log.pc).collect();
+    assert_eq!(pcs, [0, 2, 4, 5, 7, 8]);
+    assert_eq!(logs[1].source_location, Some(location(3, 9)));
+    assert_eq!(logs[5].source_location, Some(location(4, 9)));
+    let json = serde_json::to_value(&logs[5]).unwrap();
+    assert_eq!(json["sourceLocation"]["line"], 4);
+}
+
+#[test]
+fn test_reverts_and_failures_carry_locations() {
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .source_maps(source_maps())
+        .build();
+    common::run(
+        &mut db(),
+        common::call_env(CALLER_CONTRACT, vec![]),
+        &mut inspector,
+    );
+
+    let reverts = inspector.reverts();
+    assert_eq!(reverts.len(), 1);
+    assert_eq!(reverts[0].address, TOKEN);
+    assert_eq!(reverts[0].source_location, Some(location(4, 9)));
+    let failures = inspector.report().failures;
+    assert_eq!(failures[0].first.pc, Some(8));
+    assert_eq!(failures[0].first.source_location, Some(location(4, 9)));
+
+    let mut tracer = CallTraceInspector::new();
+    common::run(
+        &mut db(),
+        common::call_env(CALLER_CONTRACT, vec![]),
+        &mut tracer,
+    );
+    let out = tracer.into_trace().pretty_print(PrettyPrintOptions {
+        failures,
+        ..Default::default()
+    });
+    assert!(
+        out.contains(&format!(
+            "  revert ×1, first at Token.sol:4 ({TOKEN} pc 8 depth 1)"
+        )),
+        "{out}"
+    );
+
+    // Contracts without a source map are not annotated
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .source_maps(SourceMapRegistry::new())
+        .build();
+    common::run(
+        &mut db(),
+        common::call_env(CALLER_CONTRACT, vec![]),
+        &mut inspector,
+    );
+    assert_eq!(inspector.reverts()[0].source_location, None);
+    let json = serde_json::to_string(&inspector.report()).unwrap();
+    assert!(!json.contains("source_location"));
+}
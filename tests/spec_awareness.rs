@@ -0,0 +1,76 @@
+//! Integration tests for warnings about opcodes the traced hardfork lacks.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{evm::build_inspected_evm, HelloWorldInspector, OutputMode};
+use revm::{
+    interpreter::opcode,
+    primitives::{ExecutionResult, HaltReason, SpecId},
+    InMemoryDB,
+};
+
+const CONTRACT: Address = Address::repeat_byte(0x90);
+
+fn trace(code: Vec<u8>, spec: SpecId) -> (HelloWorldInspector, ExecutionResult) {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, code);
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .spec_awareness(spec)
+        .build();
+    let env = common::call_env(CONTRACT, vec![]);
+    let mut evm = build_inspected_evm(&mut db, env, &mut inspector);
+    evm.modify_spec_id(spec);
+    let result = evm.transact_commit().unwrap();
+    drop(evm);
+    (inspector, result)
+}
+
+#[test]
+fn test_tload_before_cancun_is_flagged() {
+    // TLOAD, STOP
+    let (inspector, result) = trace(vec![0x5c, 0x00], SpecId::SHANGHAI);
+    assert!(matches!(
+        result,
+        ExecutionResult::Halt { reason: HaltReason::NotActivated, .. }
+    ));
+
+    let warnings = inspector.spec_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].opcode, opcode::TLOAD);
+    assert_eq!(warnings[0].introduced_in, SpecId::CANCUN);
+    assert_eq!(warnings[0].address, CONTRACT);
+    assert_eq!(warnings[0].pc, 0);
+    assert_eq!(warnings[0].depth, 0);
+
+    let halts = inspector.spec_halts();
+    assert_eq!(halts.len(), 1);
+    assert_eq!(halts[0].first_offender, warnings[0]);
+    let message = format!("TLOAD at pc 0 of {CONTRACT} was introduced in Cancun, after Shanghai");
+    assert_eq!(halts[0].to_string(), format!("NotActivated: {message}"));
+
+    let report = inspector.report();
+    assert_eq!(report.spec_warnings, warnings);
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["spec_warnings"][0]["introduced_in"], "Cancun");
+    assert_eq!(json["spec_halts"][0]["spec"], "Shanghai");
+}
+
+#[test]
+fn test_push0_under_shanghai_is_not_flagged() {
+    // PUSH0, POP, STOP
+    let (inspector, result) = trace(vec![0x5f, 0x50, 0x00], SpecId::SHANGHAI);
+    assert!(result.is_success());
+    assert!(inspector.spec_warnings().is_empty());
+    assert!(inspector.spec_halts().is_empty());
+    assert!(!inspector.report_json().unwrap().contains("spec_warnings"));
+}
+
+#[test]
+fn test_tload_under_cancun_is_not_flagged() {
+    // PUSH1 0, TLOAD, POP, STOP
+    let (inspector, result) = trace(vec![0x60, 0x00, 0x5c, 0x50, 0x00], SpecId::CANCUN);
+    assert!(result.is_success());
+    assert!(inspector.spec_warnings().is_empty());
+}
@@ -0,0 +1,87 @@
+//! Integration tests for diffing the state changed by a transaction.
+
+mod common;
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{evm::build_inspected_evm, state_diff, CallTraceInspector, Prestate};
+use revm::{DatabaseCommit, InMemoryDB};
+
+const VAULT: Address = Address::repeat_byte(0x40);
+const WATCHED: Address = Address::repeat_byte(0x41);
+
+/// Reads the balance of [`WATCHED`] and slot 1, then stores 5 at slot 0.
+fn vault_code() -> Vec<u8> {
+    let mut code = vec![0x73];
+    code.extend_from_slice(WATCHED.as_slice());
+    code.extend_from_slice(&[
+        0x31, 0x50, // POP(BALANCE(WATCHED))
+        0x60, 0x01, 0x54, 0x50, // POP(SLOAD(1))
+        0x60, 0x05, 0x60, 0x00, 0x55, // SSTORE(0, 5)
+        0x00,
+    ]);
+    code
+}
+
+fn db() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    Prestate::builder()
+        .account(common::CALLER)
+        .balance(U256::from(1_000_000))
+        .account(VAULT)
+        .nonce(1)
+        .code(vault_code())
+        .storage(U256::from(1), U256::from(9))
+        .account(WATCHED)
+        .balance(U256::from(7))
+        .apply(&mut db);
+    db
+}
+
+#[test]
+fn test_value_transfer_and_slot_write() {
+    let mut db = db();
+    let mut env = common::call_env(VAULT, Vec::new());
+    env.tx.value = U256::from(1000);
+    let mut tracer = CallTraceInspector::new();
+    let result = build_inspected_evm(&mut db, env, &mut tracer).transact().unwrap();
+    assert!(result.result.is_success());
+
+    let diff = state_diff::compute(&result, &db).unwrap();
+    let caller = common::CALLER.to_string().to_lowercase();
+    let vault = VAULT.to_string().to_lowercase();
+    assert_eq!(
+        diff.to_json().unwrap(),
+        format!(concat!(
+            r#"{{"pre":{{"{caller}":{{"balance":"0xf4240","nonce":0}},"#,
+            r#""{vault}":{{"balance":"0x0","storage":{{"0x0":"0x0"}}}}}},"#,
+            r#""post":{{"{caller}":{{"balance":"0xf3e58","nonce":1}},"#,
+            r#""{vault}":{{"balance":"0x3e8","storage":{{"0x0":"0x5"}}}}}}}}"#,
+        ), caller = caller, vault = vault)
+    );
+    // Only read: the balance of WATCHED and slot 1 of the vault
+    assert_eq!(diff.changed_accounts().count(), 2);
+
+    db.commit(result.state);
+    assert_eq!(db.accounts[&VAULT].storage[&U256::ZERO], U256::from(5));
+}
+
+#[test]
+fn test_created_account_is_only_in_post() {
+    let mut db = db();
+    // Init code returning the single byte STOP as runtime code
+    let init_code = vec![0x60, 0x00, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3];
+    let mut env = common::call_env(Address::ZERO, init_code);
+    env.tx.transact_to = revm::primitives::TxKind::Create;
+    let result = build_inspected_evm(&mut db, env, CallTraceInspector::new()).transact().unwrap();
+    assert!(result.result.is_success());
+
+    let diff = state_diff::compute(&result, &db).unwrap();
+    let created = common::CALLER.create(0);
+    assert!(!diff.pre.contains_key(&created));
+    let account = &diff.post[&created];
+    assert_eq!(account.balance, Some(U256::ZERO));
+    assert_eq!(account.nonce, Some(1));
+    assert_eq!(account.code, Some(Bytes::from_static(&[0x00])));
+    assert_eq!(diff.pre[&common::CALLER].nonce, Some(0));
+    assert!(diff.pre[&common::CALLER].balance.is_none());
+}
@@ -0,0 +1,84 @@
+//! Integration tests for recording state changes attempted in static frames.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{interpreter::opcode, InMemoryDB};
+
+const OUTER: Address = Address::repeat_byte(0x20);
+const WRITER: Address = Address::repeat_byte(0x21);
+const RELAY: Address = Address::repeat_byte(0x22);
+const LOGGER: Address = Address::repeat_byte(0x23);
+
+/// SSTORE(0, 1), STOP
+const WRITER_CODE: [u8; 6] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+/// LOG0(0, 0), STOP
+const LOGGER_CODE: [u8; 6] = [0x60, 0x00, 0x60, 0x00, 0xa0, 0x00];
+
+fn trace(target: Address) -> HelloWorldInspector {
+    let mut relay = common::call_asm(LOGGER, 0);
+    relay.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, WRITER, WRITER_CODE.to_vec());
+    common::deploy(&mut db, RELAY, relay);
+    common::deploy(&mut db, LOGGER, LOGGER_CODE.to_vec());
+    let mut outer = common::static_call_asm(target);
+    outer.push(0x00);
+    common::deploy(&mut db, OUTER, outer);
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_sstore_inside_staticcall_is_recorded() {
+    let inspector = trace(WRITER);
+
+    let violations = inspector.static_violations();
+    assert_eq!(violations.len(), 1);
+    let violation = &violations[0];
+    assert_eq!(violation.address, WRITER);
+    assert_eq!(violation.pc, 4);
+    assert_eq!(violation.opcode, opcode::SSTORE);
+    assert_eq!(violation.depth, 1);
+
+    let calls = inspector.report().calls;
+    let writer = calls.iter().find(|call| call.target == WRITER).unwrap();
+    assert_eq!(writer.kind, "STATICCALL");
+    assert!(writer.reverted);
+    assert!(inspector.storage_accesses().is_empty());
+    assert_eq!(inspector.report().security.static_violations, violations);
+}
+
+#[test]
+fn test_subcalls_of_a_static_frame_are_static() {
+    let inspector = trace(RELAY);
+
+    // The relay's CALL carries no value, so only the logger's LOG0 counts
+    let violations = inspector.static_violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].address, LOGGER);
+    assert_eq!(violations[0].opcode, opcode::LOG0);
+    assert_eq!(violations[0].depth, 2);
+    assert!(inspector.logs().is_empty());
+}
+
+#[test]
+fn test_state_changes_outside_static_frames_are_not_recorded() {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, WRITER, WRITER_CODE.to_vec());
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(WRITER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    assert!(inspector.static_violations().is_empty());
+    assert_eq!(inspector.storage_accesses().len(), 1);
+}
@@ -0,0 +1,92 @@
+//! Integration tests for SLOAD/SSTORE recording.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, StorageAccessKind};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+/// Assembles `SSTORE(slot, value)`.
+fn sstore_asm(slot: u8, value: u8) -> Vec<u8> {
+    vec![0x60, value, 0x60, slot, 0x55]
+}
+
+#[test]
+fn test_storage_writes_and_final_values() {
+    let target = Address::repeat_byte(0x20);
+    let mut code = sstore_asm(1, 7);
+    code.extend(sstore_asm(2, 9));
+    code.extend(sstore_asm(1, 8));
+    // SLOAD(2), POP, STOP
+    code.extend_from_slice(&[0x60, 0x02, 0x54, 0x50, 0x00]);
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, target, code);
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(target, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let writes: Vec<_> = inspector
+        .storage_writes_for(target)
+        .iter()
+        .map(|w| (w.slot.to::<u64>(), w.value.to::<u64>(), w.pc))
+        .collect();
+    assert_eq!(writes, vec![(1, 7, 4), (2, 9, 9), (1, 8, 14)]);
+
+    let read = inspector.storage_accesses().last().unwrap();
+    assert_eq!(read.kind, StorageAccessKind::Read);
+    assert_eq!((read.slot, read.value, read.pc), (U256::from(2), U256::from(9), 17));
+
+    let values = inspector.final_storage_values();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[&(target, U256::from(1))], U256::from(8));
+    assert_eq!(values[&(target, U256::from(2))], U256::from(9));
+}
+
+#[test]
+fn test_delegatecall_writes_use_storage_context_and_reverts_are_ignored() {
+    let proxy = Address::repeat_byte(0x20);
+    let implementation = Address::repeat_byte(0x30);
+    let reverter = Address::repeat_byte(0x40);
+
+    // Proxy: DELEGATECALL implementation, CALL reverter, STOP
+    let mut code_proxy = common::delegate_call_asm(implementation);
+    code_proxy.extend(common::call_asm(reverter, 0));
+    code_proxy.push(0x00);
+    // Implementation: SSTORE(1, 5), STOP
+    let mut code_implementation = sstore_asm(1, 5);
+    code_implementation.push(0x00);
+    // Reverter: SSTORE(3, 6), REVERT(0, 0)
+    let mut code_reverter = sstore_asm(3, 6);
+    code_reverter.extend(common::revert_asm(&[]));
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, proxy, code_proxy);
+    common::deploy(&mut db, implementation, code_implementation);
+    common::deploy(&mut db, reverter, code_reverter);
+
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(proxy, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    assert!(inspector.storage_writes_for(implementation).is_empty());
+    let proxy_writes = inspector.storage_writes_for(proxy);
+    assert_eq!(proxy_writes.len(), 1);
+    assert_eq!(proxy_writes[0].value, U256::from(5));
+
+    let reverted = inspector.storage_writes_for(reverter);
+    assert_eq!(reverted.len(), 1);
+    assert!(reverted[0].reverted);
+
+    let values = inspector.final_storage_values();
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[&(proxy, U256::from(1))], U256::from(5));
+}
@@ -0,0 +1,175 @@
+//! Tests for strict mode, calling the hooks directly and out of order.
+
+mod common;
+
+use std::panic::{self, AssertUnwindSafe};
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{
+    HelloWorldInspector, InconsistencyAction, InconsistencyKind, InspectorReport, OutputMode,
+};
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CallValue, CreateInputs, CreateOutcome, CreateScheme,
+        Gas, InstructionResult, InterpreterResult,
+    },
+    EvmContext, InMemoryDB, Inspector,
+};
+
+const OUTER: Address = Address::repeat_byte(0x51);
+const INNER: Address = Address::repeat_byte(0x52);
+
+fn inputs(target: Address) -> CallInputs {
+    CallInputs {
+        input: Bytes::new(),
+        return_memory_offset: 0..0,
+        gas_limit: 100_000,
+        bytecode_address: target,
+        target_address: target,
+        caller: common::CALLER,
+        value: CallValue::Transfer(U256::ZERO),
+        scheme: CallScheme::Call,
+        is_static: false,
+        is_eof: false,
+    }
+}
+
+fn outcome() -> CallOutcome {
+    CallOutcome::new(
+        InterpreterResult::new(InstructionResult::Stop, Bytes::new(), Gas::new(100_000)),
+        0..0,
+    )
+}
+
+/// Opens the outer and inner call, resets the inspector as if between
+/// transactions, then ends both calls.
+fn reset_mid_transaction(inspector: &mut HelloWorldInspector) {
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let (mut outer, mut inner) = (inputs(OUTER), inputs(INNER));
+    inspector.call(&mut context, &mut outer);
+    inspector.call(&mut context, &mut inner);
+    inspector.reset();
+    inspector.call_end(&mut context, &inner, outcome());
+    inspector.call_end(&mut context, &outer, outcome());
+}
+
+fn strict_inspector(strict: Option<InconsistencyAction>) -> HelloWorldInspector {
+    let builder = HelloWorldInspector::builder().output(OutputMode::Silent);
+    match strict {
+        Some(action) => builder.strict(action),
+        None => builder,
+    }
+    .build()
+}
+
+#[test]
+fn test_inconsistencies_poison_the_report() {
+    let mut inspector = strict_inspector(Some(InconsistencyAction::Poison));
+    reset_mid_transaction(&mut inspector);
+
+    assert!(inspector.is_poisoned());
+    let report = inspector.report();
+    assert!(report.poisoned);
+    assert_eq!(
+        report
+            .internal_inconsistencies
+            .iter()
+            .map(|inconsistency| inconsistency.kind)
+            .collect::<Vec<_>>(),
+        [
+            InconsistencyKind::DepthUnderflow,
+            InconsistencyKind::EndWithoutStart,
+        ]
+        .repeat(2)
+    );
+    let first = &report.internal_inconsistencies[0];
+    assert_eq!(first.hook, "call_end");
+    assert_eq!(
+        first.to_string(),
+        "depth underflow in call_end (transaction 0, depth 0): no frame is open"
+    );
+
+    // Poisoning survives serialization and merging
+    let json = report.to_json().unwrap();
+    assert!(json.contains(r#""poisoned":true"#), "{json}");
+    let mut merged = InspectorReport::default();
+    merged.merge(serde_json::from_str(&json).unwrap());
+    assert!(merged.poisoned);
+    assert_eq!(merged.internal_inconsistencies.len(), 4);
+
+    // Without strict, the same misuse goes unnoticed
+    let mut permissive = strict_inspector(None);
+    reset_mid_transaction(&mut permissive);
+    assert!(!permissive.is_poisoned());
+    assert!(permissive.inconsistencies().is_empty());
+    assert!(!permissive.report_json().unwrap().contains("poisoned"));
+}
+
+#[test]
+fn test_unmatched_create_end_with_value_is_recorded() {
+    let mut inspector = strict_inspector(Some(InconsistencyAction::Poison));
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let inputs = CreateInputs {
+        caller: common::CALLER,
+        scheme: CreateScheme::Create,
+        value: U256::from(1_000),
+        init_code: Bytes::new(),
+        gas_limit: 100_000,
+    };
+    let created = common::CALLER.create(0);
+    let outcome = CreateOutcome::new(
+        InterpreterResult::new(InstructionResult::Return, Bytes::new(), Gas::new(100_000)),
+        Some(created),
+    );
+    inspector.create_end(&mut context, &inputs, outcome);
+
+    assert!(inspector.is_poisoned());
+    assert!(inspector.transfers().is_empty());
+    let missing = inspector.inconsistencies().last().unwrap();
+    assert_eq!(missing.kind, InconsistencyKind::EndWithoutStart);
+    assert_eq!(missing.hook, "create_end");
+    assert!(missing.context.contains("no transfer of 1000"), "{missing}");
+}
+
+#[test]
+fn test_inconsistencies_panic_at_the_hook() {
+    let mut inspector = strict_inspector(Some(InconsistencyAction::Panic));
+    let mut context = EvmContext::new(InMemoryDB::default());
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+        inspector.call_end(&mut context, &inputs(OUTER), outcome());
+    }))
+    .unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "depth underflow in call_end (transaction 0, depth 0): no frame is open"
+    );
+
+    // With catch_panics the panic is recorded and the report poisoned instead
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .strict(InconsistencyAction::Panic)
+        .catch_panics(true)
+        .build();
+    inspector.call_end(&mut context, &inputs(OUTER), outcome());
+    assert!(inspector.is_poisoned());
+    assert_eq!(inspector.inconsistencies().len(), 1);
+    assert_eq!(inspector.internal_errors().len(), 1);
+    assert_eq!(inspector.internal_errors()[0].source, "call_end");
+}
+
+#[test]
+fn test_balanced_transactions_are_consistent() {
+    let mut db = InMemoryDB::default();
+    let mut outer = common::call_asm(INNER, 0);
+    outer.push(0x00);
+    common::deploy(&mut db, OUTER, outer);
+    common::deploy(&mut db, INNER, common::revert_asm(&[]));
+    let mut inspector = strict_inspector(Some(InconsistencyAction::Panic));
+    for _ in 0..2 {
+        common::run(&mut db, common::call_env(OUTER, Vec::new()), &mut inspector);
+    }
+    assert_eq!(inspector.calls(), 4);
+    assert!(inspector.inconsistencies().is_empty());
+    assert!(!inspector.report().poisoned);
+}
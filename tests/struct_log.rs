@@ -0,0 +1,78 @@
+//! Integration tests for `StructLogInspector`.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{StructLogConfig, StructLogInspector};
+use revm::InMemoryDB;
+
+/// PUSH1 2, PUSH1 3, ADD, PUSH1 0, MSTORE, PUSH1 7, PUSH1 1, SSTORE, STOP
+const CODE: [u8; 14] = [
+    0x60, 0x02, 0x60, 0x03, 0x01, 0x60, 0x00, 0x52, 0x60, 0x07, 0x60, 0x01, 0x55, 0x00,
+];
+
+fn trace(config: StructLogConfig) -> Vec<restd::StructLog> {
+    let target = Address::repeat_byte(0x20);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, target, CODE.to_vec());
+
+    let mut inspector = StructLogInspector::with_config(config);
+    let result = common::run(&mut db, common::call_env(target, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector.into_struct_logs()
+}
+
+#[test]
+fn test_struct_logs_match_bytecode() {
+    let logs = trace(StructLogConfig::default());
+
+    let steps: Vec<(u64, u8)> = logs.iter().map(|log| (log.pc, log.op)).collect();
+    assert_eq!(
+        steps,
+        vec![
+            (0, 0x60),
+            (2, 0x60),
+            (4, 0x01),
+            (5, 0x60),
+            (7, 0x52),
+            (8, 0x60),
+            (10, 0x60),
+            (12, 0x55),
+            (13, 0x00),
+        ]
+    );
+    assert!(logs.iter().all(|log| log.depth == 1 && log.memory.is_none()));
+
+    let add = &logs[2];
+    assert_eq!(add.stack, vec![U256::from(2), U256::from(3)]);
+    assert_eq!(add.gas_cost, 3);
+    assert_eq!(logs[3].gas, add.gas - 3);
+
+    let sstore = &logs[7];
+    assert_eq!(sstore.storage_changes.len(), 1);
+    assert_eq!(logs[8].gas_cost, 0);
+
+    let json = serde_json::to_value(add).unwrap();
+    assert_eq!(json["op"], "ADD");
+    assert_eq!(json["gasCost"], 3);
+    assert_eq!(json["depth"], 1);
+    assert_eq!(json["stack"], serde_json::json!(["0x2", "0x3"]));
+    assert!(json.get("memory").is_none());
+    assert!(json.get("storage").is_none());
+}
+
+#[test]
+fn test_struct_logs_memory_and_stack_limit() {
+    let logs = trace(StructLogConfig {
+        capture_memory: true,
+        stack_top_n: Some(1),
+    });
+
+    // ADD sees only the top of the stack
+    assert_eq!(logs[2].stack, vec![U256::from(3)]);
+    // Memory is empty before MSTORE and holds the sum afterwards
+    assert_eq!(logs[4].memory.as_ref().unwrap().len(), 0);
+    let memory = logs[5].memory.as_ref().unwrap();
+    assert_eq!(memory.len(), 32);
+    assert_eq!(memory[31], 5);
+}
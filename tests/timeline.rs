@@ -0,0 +1,97 @@
+//! Integration tests for ordering records by their sequence numbers.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, TimelineEvent};
+use revm::InMemoryDB;
+
+const OUTER: Address = Address::repeat_byte(0x20);
+const INNER: Address = Address::repeat_byte(0x30);
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+/// Deploys an outer contract that writes slot 0, logs, calls the inner
+/// contract (which logs) and writes slot 1.
+fn setup() -> InMemoryDB {
+    // SSTORE(0, 1), LOG0(0, 0)
+    let mut code_outer = vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0xa0];
+    code_outer.extend(common::call_asm(INNER, 0));
+    // SSTORE(1, 2), STOP
+    code_outer.extend([0x60, 0x02, 0x60, 0x01, 0x55, 0x00]);
+    // LOG0(0, 0), STOP
+    let code_inner = vec![0x60, 0x00, 0x60, 0x00, 0xa0, 0x00];
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, code_outer);
+    common::deploy(&mut db, INNER, code_inner);
+    db
+}
+
+/// Describes an event by its kind and address, and the slot of storage accesses.
+fn describe(event: TimelineEvent<'_>) -> (&'static str, Address, Option<U256>) {
+    match event {
+        TimelineEvent::Call(call) => ("call", call.target, None),
+        TimelineEvent::Log(log) => ("log", log.address, None),
+        TimelineEvent::StorageAccess(access) => ("storage", access.address, Some(access.slot)),
+        other => panic!("unexpected event {other:?}"),
+    }
+}
+
+#[test]
+fn test_timeline_follows_execution_order() {
+    let mut db = setup();
+    let mut inspector = silent_inspector();
+    common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+
+    let report = inspector.report();
+    let timeline = report.timeline();
+    let events: Vec<_> = timeline.iter().map(describe).collect();
+    assert_eq!(
+        events,
+        vec![
+            ("call", OUTER, None),
+            ("storage", OUTER, Some(U256::ZERO)),
+            ("log", OUTER, None),
+            ("call", INNER, None),
+            ("log", INNER, None),
+            ("storage", OUTER, Some(U256::from(1))),
+        ]
+    );
+    let seqs: Vec<u64> = timeline.iter().map(|event| event.seq()).collect();
+    assert!(seqs.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_seq_continues_across_transactions_until_reset() {
+    let mut db = setup();
+    let mut inspector = silent_inspector();
+    common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+    common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+
+    let report = inspector.report();
+    assert_eq!(report.timeline().len(), 12);
+    assert_eq!(report.calls[2].seq, 6);
+    assert_eq!(report.calls[2].tx_index, 1);
+
+    inspector.reset();
+    common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+    assert_eq!(inspector.report().calls[0].seq, 0);
+}
+
+#[test]
+fn test_seq_is_serialized() {
+    let mut db = setup();
+    let mut inspector = silent_inspector();
+    common::run(&mut db, common::call_env(OUTER, vec![]), &mut inspector);
+
+    let json: serde_json::Value = serde_json::from_str(&inspector.report_json().unwrap()).unwrap();
+    assert_eq!(json["calls"][1]["seq"], 3);
+    assert_eq!(json["logs"][0]["seq"], 2);
+    assert_eq!(json["storage_accesses"][1]["seq"], 5);
+}
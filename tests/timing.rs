@@ -0,0 +1,159 @@
+//! Integration tests for wall-clock timing of frames and transactions.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    CallTraceInspector, CallTraceNode, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    TimelineEvent,
+};
+use revm::InMemoryDB;
+
+const OUTER: Address = Address::repeat_byte(0x90);
+const MIDDLE: Address = Address::repeat_byte(0x91);
+const LOOP: Address = Address::repeat_byte(0x92);
+
+/// Counts down from 10000 in a seven-instruction loop.
+fn slow_loop() -> Vec<u8> {
+    vec![
+        0x61, 0x27, 0x10, // PUSH2 10000
+        0x5b, // JUMPDEST
+        0x60, 0x01, 0x90, 0x03, // PUSH1 1, SWAP1, SUB
+        0x80, 0x60, 0x03, 0x57, // DUP1, PUSH1 3, JUMPI
+        0x00,
+    ]
+}
+
+/// OUTER calls MIDDLE twice, which calls LOOP each time.
+fn db() -> InMemoryDB {
+    let mut outer = common::call_asm(MIDDLE, 0);
+    outer.extend(common::call_asm(MIDDLE, 0));
+    outer.push(0x00);
+    let mut middle = common::call_asm(LOOP, 0);
+    middle.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, OUTER, outer);
+    common::deploy(&mut db, MIDDLE, middle);
+    common::deploy(&mut db, LOOP, slow_loop());
+    db
+}
+
+fn trace(measure_time: bool) -> HelloWorldInspector {
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        measure_time,
+        ..Default::default()
+    });
+    let result = common::run(&mut db(), common::call_env(OUTER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_timing_is_off_by_default() {
+    let report = trace(false).report();
+    assert!(report
+        .calls
+        .iter()
+        .all(|call| call.duration_micros.is_none()));
+    assert!(report.tx_timings.is_empty());
+    assert!(report.slowest_frames(3).is_empty());
+}
+
+#[test]
+fn test_parent_frames_take_at_least_their_children() {
+    let inspector = trace(true);
+    let report = inspector.report();
+    let durations: Vec<u64> = report
+        .calls
+        .iter()
+        .map(|call| call.duration_micros.unwrap())
+        .collect();
+    // OUTER, MIDDLE, LOOP, MIDDLE, LOOP in the order the calls started
+    assert_eq!(durations.len(), 5);
+    assert!(durations[0] >= durations[1] + durations[3], "{durations:?}");
+    assert!(durations[1] >= durations[2], "{durations:?}");
+    assert!(durations[3] >= durations[4], "{durations:?}");
+
+    let [tx] = inspector.tx_timings() else {
+        panic!("{:?}", inspector.tx_timings());
+    };
+    assert_eq!(tx.tx_index, 0);
+    assert!(tx.duration_micros >= durations[1] + durations[3]);
+}
+
+#[test]
+fn test_slowest_frames_are_sorted() {
+    let report = trace(true).report();
+    let slowest = report.slowest_frames(2);
+    assert_eq!(slowest.len(), 2);
+    let TimelineEvent::Call(first) = slowest[0] else {
+        panic!("{slowest:?}");
+    };
+    // The top-level call contains every other frame
+    assert_eq!(first.target, OUTER);
+    let TimelineEvent::Call(second) = slowest[1] else {
+        panic!("{slowest:?}");
+    };
+    assert_eq!(second.target, MIDDLE);
+    assert!(first.duration_micros >= second.duration_micros);
+    assert_eq!(report.slowest_frames(10).len(), 5);
+}
+
+/// Checks that every child starts and ends within its parent, after its
+/// previous sibling, and that the children take no longer than the parent.
+fn assert_nested(node: &CallTraceNode) {
+    let (start, end) = (node.start_micros.unwrap(), node.end_micros.unwrap());
+    assert!(start <= end);
+    let mut previous_end = start;
+    let mut children_micros = 0;
+    for child in &node.children {
+        assert!(child.start_micros.unwrap() >= previous_end, "{node:?}");
+        assert!(child.end_micros.unwrap() <= end, "{node:?}");
+        previous_end = child.end_micros.unwrap();
+        children_micros += child.duration_micros().unwrap();
+        assert_nested(child);
+    }
+    assert!(node.duration_micros().unwrap() >= children_micros);
+}
+
+#[test]
+fn test_call_trace_times_are_monotonic() {
+    let mut tracer = CallTraceInspector::new().with_timing();
+    common::run(&mut db(), common::call_env(OUTER, vec![]), &mut tracer);
+    let trace = tracer.into_trace();
+    let root = trace.root().unwrap();
+    assert_eq!(root.start_micros, Some(0));
+    assert_nested(root);
+
+    // Chrome timestamps are the measured microseconds
+    let chrome: serde_json::Value = serde_json::from_str(&trace.to_chrome_trace()).unwrap();
+    let events = chrome["traceEvents"].as_array().unwrap();
+    assert_eq!(events[0]["ts"], 0);
+    assert_eq!(events.last().unwrap()["ts"], root.end_micros.unwrap());
+}
+
+#[test]
+fn test_call_trace_without_timing_uses_steps() {
+    let mut tracer = CallTraceInspector::new();
+    common::run(&mut db(), common::call_env(OUTER, vec![]), &mut tracer);
+    let trace = tracer.into_trace();
+    let root = trace.root().unwrap();
+    assert_eq!(root.duration_micros(), None);
+    let chrome: serde_json::Value = serde_json::from_str(&trace.to_chrome_trace()).unwrap();
+    let events = chrome["traceEvents"].as_array().unwrap();
+    assert_eq!(events.last().unwrap()["ts"], root.end_step);
+}
+
+#[test]
+fn test_measure_time_from_toml() {
+    let config = HelloWorldInspectorConfig::from_toml_str("measure_time = true\n").unwrap();
+    assert!(config.measure_time);
+    assert!(
+        HelloWorldInspector::builder()
+            .measure_time(true)
+            .build()
+            .config()
+            .measure_time
+    );
+}
@@ -0,0 +1,140 @@
+//! Tests for aligning two call traces with `CallTrace::diff`.
+
+use alloy_primitives::{Address, Bytes, U256};
+use restd::{CallKind, CallTrace, CallTraceNode, DiffStatus};
+use revm::interpreter::InstructionResult;
+
+const ROOT: Address = Address::repeat_byte(0x20);
+const TOKEN: Address = Address::repeat_byte(0x21);
+const ORACLE: Address = Address::repeat_byte(0x22);
+const HOOK: Address = Address::repeat_byte(0x23);
+
+const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+fn frame(
+    target: Address,
+    selector: Option<[u8; 4]>,
+    depth: usize,
+    gas_used: u64,
+    children: Vec<CallTraceNode>,
+) -> CallTraceNode {
+    CallTraceNode {
+        kind: CallKind::Call,
+        caller: Address::ZERO,
+        target,
+        delegate: None,
+        value: U256::ZERO,
+        input: selector.map(|s| Bytes::copy_from_slice(&s)).unwrap_or_default(),
+        output: Bytes::new(),
+        gas_limit: 100_000,
+        gas_requested: None,
+        gas_capped: false,
+        gas_used,
+        success: true,
+        result: InstructionResult::Stop,
+        depth,
+        start_step: 0,
+        end_step: 0,
+        start_micros: None,
+        end_micros: None,
+        children,
+        logs: Vec::new(),
+    }
+}
+
+fn before() -> CallTrace {
+    CallTrace::new(frame(
+        ROOT,
+        None,
+        0,
+        30_000,
+        vec![
+            frame(TOKEN, Some(TRANSFER), 1, 9_000, vec![]),
+            frame(ORACLE, None, 1, 2_000, vec![]),
+        ],
+    ))
+}
+
+/// `before` with a more expensive oracle call and an extra hook call.
+fn after() -> CallTrace {
+    CallTrace::new(frame(
+        ROOT,
+        None,
+        0,
+        33_500,
+        vec![
+            frame(TOKEN, Some(TRANSFER), 1, 9_000, vec![]),
+            frame(HOOK, None, 1, 1_000, vec![]),
+            frame(ORACLE, None, 1, 2_500, vec![]),
+        ],
+    ))
+}
+
+#[test]
+fn test_diff_reports_added_frame_and_gas_change() {
+    let diff = before().diff(&after());
+
+    let added: Vec<_> = diff.added().collect();
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].target, HOOK);
+    assert_eq!(added[0].gas_after, Some(1_000));
+    assert_eq!(diff.removed().count(), 0);
+
+    let changes: Vec<_> = diff
+        .gas_changes()
+        .map(|frame| (frame.target, frame.gas_delta()))
+        .collect();
+    assert_eq!(changes, vec![(ROOT, Some(3_500)), (ORACLE, Some(500))]);
+    assert_eq!(diff.success_changes().count(), 0);
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn test_diff_pretty_print() {
+    assert_eq!(
+        before().diff(&after()).pretty_print(),
+        "  CALL 0x2020202020202020202020202020202020202020::fallback [30000 → 33500 (+3500)]\n\
+        \x20 ├─ CALL 0x2121212121212121212121212121212121212121::0xa9059cbb [9000]\n\
+         + ├─ CALL 0x2323232323232323232323232323232323232323::fallback [1000]\n\
+        \x20 └─ CALL 0x2222222222222222222222222222222222222222::fallback [2000 → 2500 (+500)]\n"
+    );
+    assert_eq!(
+        after().diff(&before()).removed().next().map(|frame| frame.target),
+        Some(HOOK)
+    );
+}
+
+#[test]
+fn test_reordered_siblings_are_matched() {
+    let mut root = before().root().cloned().unwrap();
+    root.children.reverse();
+
+    let diff = before().diff(&CallTrace::new(root));
+    assert!(diff.iter().all(|frame| frame.status == DiffStatus::Matched));
+    assert!(diff.is_empty());
+    let targets: Vec<_> = diff.frames[0].children.iter().map(|frame| frame.target).collect();
+    assert_eq!(targets, vec![ORACLE, TOKEN]);
+}
+
+#[test]
+fn test_success_change_is_reported() {
+    let mut failing = before().root().cloned().unwrap();
+    failing.children[1].success = false;
+    failing.children[1].result = InstructionResult::Revert;
+
+    let diff = before().diff(&CallTrace::new(failing));
+    let changed: Vec<_> = diff.success_changes().collect();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].target, ORACLE);
+    assert_eq!(changed[0].success_after, Some(false));
+    assert!(diff
+        .pretty_print()
+        .contains("::fallback [2000] (success → failure)"));
+}
+
+#[test]
+fn test_identical_traces_have_empty_diff() {
+    let diff = before().diff(&before());
+    assert!(diff.is_empty());
+    assert_eq!(diff.iter().count(), 3);
+}
@@ -0,0 +1,140 @@
+//! Integration tests for bounding collections with `TraceLimits`.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{
+    CallTraceInspector, Error, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode,
+    StructLogInspector, TraceLimits, TruncatedCollections,
+};
+use revm::InMemoryDB;
+
+const PARENT: Address = Address::repeat_byte(0x20);
+const CHILD: Address = Address::repeat_byte(0x21);
+
+/// Deploys a parent calling the child twice; the child emits a log with
+/// 32 bytes of data and writes two slots.
+fn setup() -> InMemoryDB {
+    let mut parent = common::call_asm(CHILD, 0);
+    parent.extend(common::call_asm(CHILD, 0));
+    parent.push(0x00);
+    let mut child = common::mstore_asm(&[0xab; 32]);
+    // PUSH1 32, PUSH1 0, LOG0
+    child.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xa0]);
+    // SSTORE(0, 1), SSTORE(1, 2), STOP
+    child.extend_from_slice(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x02, 0x60, 0x01, 0x55, 0x00]);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, PARENT, parent);
+    common::deploy(&mut db, CHILD, child);
+    db
+}
+
+fn trace(limits: TraceLimits) -> HelloWorldInspector {
+    let mut db = setup();
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        limits,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_unbounded_by_default() {
+    let inspector = trace(TraceLimits::default());
+    assert_eq!(inspector.gas_by_call().len(), 3);
+    assert_eq!(inspector.logs().len(), 2);
+    assert_eq!(inspector.storage_accesses().len(), 4);
+    assert!(!inspector.truncated_collections().any());
+    assert!(inspector.truncated_collections().ensure_complete().is_ok());
+}
+
+#[test]
+fn test_collections_stop_at_their_caps() {
+    let inspector = trace(TraceLimits {
+        max_call_records: Some(1),
+        max_logs: Some(1),
+        max_storage_accesses: Some(1),
+        ..Default::default()
+    });
+
+    assert_eq!(inspector.gas_by_call().len(), 1);
+    assert_eq!(inspector.gas_by_call()[0].target, PARENT);
+    assert_eq!(inspector.logs().len(), 1);
+    assert_eq!(inspector.storage_accesses().len(), 1);
+    assert_eq!(
+        inspector.truncated_collections(),
+        TruncatedCollections {
+            calls: true,
+            logs: true,
+            storage_accesses: true,
+            transient_accesses: false,
+            bytes: false,
+            preimages: false,
+        }
+    );
+    assert!(inspector.report().truncated_collections.any());
+    let err = inspector.truncated_collections().ensure_complete().unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(truncated) if truncated.logs), "{err:?}");
+    assert_eq!(
+        err.to_string(),
+        "trace limits exceeded, truncated calls, logs, storage accesses"
+    );
+
+    // Counters and per-address stats still cover every call
+    let unbounded = trace(TraceLimits::default());
+    assert_eq!(inspector.calls(), 3);
+    assert_eq!(inspector.steps(), unbounded.steps());
+    assert_eq!(inspector.stats_for(CHILD), unbounded.stats_for(CHILD));
+    assert_eq!(inspector.stats_for(CHILD).unwrap().calls, 2);
+}
+
+#[test]
+fn test_byte_budget_drops_payloads() {
+    let inspector = trace(TraceLimits {
+        max_total_bytes: Some(0),
+        ..Default::default()
+    });
+
+    assert_eq!(inspector.logs().len(), 2);
+    assert!(inspector.logs().iter().all(|log| log.data.is_empty()));
+    assert!(inspector.truncated_collections().bytes);
+}
+
+#[test]
+fn test_struct_logs_capped() {
+    let mut db = setup();
+    let mut inspector = StructLogInspector::new().with_limits(TraceLimits {
+        max_struct_logs: Some(3),
+        max_total_bytes: Some(0),
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(PARENT, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    assert!(inspector.is_truncated());
+    let logs = inspector.into_struct_logs();
+    assert_eq!(logs.len(), 3);
+    assert!(logs.iter().all(|log| log.stack.is_empty()));
+}
+
+#[test]
+fn test_call_trace_drops_calldata_over_budget() {
+    let mut db = setup();
+    let mut inspector = CallTraceInspector::new().with_limits(TraceLimits {
+        max_total_bytes: Some(0),
+        ..Default::default()
+    });
+    let env = common::call_env(PARENT, vec![0x12, 0x34, 0x56, 0x78]);
+    assert!(common::run(&mut db, env, &mut inspector).is_success());
+
+    assert!(inspector.is_truncated());
+    let trace = inspector.into_trace();
+    let root = trace.root().unwrap();
+    assert!(root.input.is_empty());
+    assert_eq!(root.children.len(), 2);
+    assert_eq!(trace.nodes().filter(|node| node.target == CHILD).count(), 2);
+    assert!(trace.nodes().all(|node| node.input.is_empty()));
+}
@@ -0,0 +1,111 @@
+//! Integration tests for tracing several transactions with `TraceSession`.
+
+mod common;
+
+use alloy_primitives::{Address, B256};
+use restd::{HelloWorldInspectorConfig, OutputMode, TraceSession};
+use revm::InMemoryDB;
+
+const A: Address = Address::repeat_byte(0xaa);
+const B: Address = Address::repeat_byte(0xbb);
+const C: Address = Address::repeat_byte(0xcc);
+
+/// Intrinsic gas of a call without calldata
+const INTRINSIC_GAS: u64 = 21_000;
+
+fn setup() -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    // A: CALL B, STOP
+    let mut code_a = common::call_asm(B, 0);
+    code_a.push(0x00);
+    common::deploy(&mut db, A, code_a);
+    // B: the countdown loop
+    common::deploy(&mut db, B, common::countdown_loop());
+    // C: REVERT(0, 0)
+    common::deploy(&mut db, C, vec![0x60, 0x00, 0x60, 0x00, 0xfd]);
+    db
+}
+
+fn session() -> TraceSession {
+    TraceSession::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_block_report_aggregates_transactions() {
+    let mut db = setup();
+    let mut session = session();
+    let mut tx_gas = Vec::new();
+    for (i, to) in [A, C, B].into_iter().enumerate() {
+        session.begin_tx(B256::with_last_byte(i as u8 + 1));
+        let result = common::run(&mut db, common::call_env(to, vec![]), session.inspector_mut());
+        tx_gas.push(result.gas_used());
+        let tx = session.end_tx();
+        assert_eq!(tx.index, i);
+        assert_eq!(tx.gas_used + INTRINSIC_GAS, result.gas_used());
+    }
+    let block = session.finish();
+
+    let steps: Vec<u64> = block.transactions.iter().map(|tx| tx.report.step_count).collect();
+    assert_eq!(block.total_steps, steps.iter().sum::<u64>());
+    assert_eq!(block.total_calls, 2 + 1 + 1);
+    assert_eq!(block.total_reverts, 1);
+    assert_eq!(
+        block.total_gas_used,
+        tx_gas.iter().sum::<u64>() - 3 * INTRINSIC_GAS
+    );
+    // B is called by the first and third transactions but counted once
+    assert_eq!(block.unique_addresses, 3);
+
+    let reverting = block.transaction(B256::with_last_byte(2)).unwrap();
+    assert_eq!(reverting.index, 1);
+    assert_eq!(reverting.addresses.iter().copied().collect::<Vec<_>>(), vec![C]);
+    assert!(block.transaction(B256::with_last_byte(4)).is_none());
+}
+
+#[test]
+fn test_transaction_reports_do_not_leak() {
+    let mut db = setup();
+    let mut session = session();
+
+    session.begin_tx(B256::with_last_byte(1));
+    common::run(&mut db, common::call_env(A, vec![]), session.inspector_mut());
+    let first = session.end_tx();
+
+    session.begin_tx(B256::with_last_byte(2));
+    assert_eq!(session.inspector().steps(), 0);
+    common::run(&mut db, common::call_env(C, vec![]), session.inspector_mut());
+    let second = session.end_tx();
+
+    assert_eq!(first.report.calls.len(), 2);
+    assert!(first.report.reverts.is_empty());
+    assert_eq!(second.report.calls.len(), 1);
+    assert_eq!(second.report.calls[0].target, C);
+    assert_eq!(second.report.reverts.len(), 1);
+    assert_eq!(second.report.reverts[0].tx_index, 0);
+    assert_eq!(second.report.step_count, 3);
+    assert_eq!(session.transactions(), &[first, second]);
+}
+
+#[test]
+fn test_finish_ends_open_transaction() {
+    let mut db = setup();
+    let mut session = session();
+    session.begin_tx(B256::with_last_byte(7));
+    common::run(&mut db, common::call_env(B, vec![]), session.inspector_mut());
+    assert_eq!(session.current_tx(), Some(B256::with_last_byte(7)));
+
+    let block = session.finish();
+    assert_eq!(block.transactions.len(), 1);
+    assert_eq!(block.total_gas_used, common::COUNTDOWN_LOOP_GAS);
+}
+
+#[test]
+#[should_panic(expected = "was begun but not ended")]
+fn test_begin_tx_twice_panics() {
+    let mut session = session();
+    session.begin_tx(B256::with_last_byte(1));
+    session.begin_tx(B256::with_last_byte(2));
+}
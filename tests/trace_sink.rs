@@ -0,0 +1,108 @@
+//! Integration tests for streaming trace events through a sink.
+
+mod common;
+
+use std::{sync::mpsc, thread};
+
+use alloy_primitives::Address;
+use restd::{
+    FileTraceSink, HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, TraceEvent,
+};
+use revm::InMemoryDB;
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_consumer_thread_receives_events_in_order() {
+    let outer = Address::repeat_byte(0x50);
+    let inner = Address::repeat_byte(0x51);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, outer, common::call_asm(inner, 0));
+    // LOG0(0, 0), STOP
+    common::deploy(&mut db, inner, vec![0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]);
+
+    let (tx, rx) = mpsc::channel();
+    let consumer = thread::spawn(move || rx.into_iter().collect::<Vec<TraceEvent>>());
+
+    let mut inspector = silent_inspector().with_sink(tx);
+    common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+    // Dropping the inspector drops the sender and ends the consumer's loop.
+    let steps = inspector.steps();
+    drop(inspector);
+    let events = consumer.join().unwrap();
+
+    let calls: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::CallStart { address, depth, .. } => Some(("start", *address, *depth)),
+            TraceEvent::CallEnd { address, depth, .. } => Some(("end", *address, *depth)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            ("start", outer, 1),
+            ("start", inner, 2),
+            ("end", inner, 2),
+            ("end", outer, 1)
+        ]
+    );
+
+    let step_events = events
+        .iter()
+        .filter(|event| matches!(event, TraceEvent::Step { .. }))
+        .count();
+    assert_eq!(step_events as u64, steps);
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, TraceEvent::Log(log) if log.address == inner)));
+}
+
+#[test]
+fn test_dropped_receiver_disables_sink() {
+    let contract = Address::repeat_byte(0x50);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, vec![0x5b, 0x5b, 0x00]);
+
+    let (tx, rx) = mpsc::channel();
+    drop(rx);
+    let mut inspector = silent_inspector().with_sink(tx);
+    let result = common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+
+    assert!(result.is_success());
+    assert_eq!(inspector.steps(), 3);
+    assert_eq!(inspector.calls(), 1);
+}
+
+#[test]
+fn test_file_sink_writes_one_event_per_line() {
+    let contract = Address::repeat_byte(0x50);
+    let mut db = InMemoryDB::default();
+    // LOG0(0, 0), STOP
+    common::deploy(&mut db, contract, vec![0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]);
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let sink = FileTraceSink::create(file.path()).unwrap();
+    let mut inspector = silent_inspector().with_sink(sink.clone());
+    common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    sink.flush().unwrap();
+    assert!(sink.take_errors().is_empty());
+
+    let contents = std::fs::read_to_string(file.path()).unwrap();
+    let events: Vec<TraceEvent> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // Call start and end, four steps and the log emitted by the third
+    assert_eq!(events.len(), 7);
+    assert!(matches!(events[0], TraceEvent::CallStart { address, .. } if address == contract));
+    assert!(matches!(events[4], TraceEvent::Log(ref log) if log.address == contract));
+    assert!(matches!(events[6], TraceEvent::CallEnd { success: true, .. }));
+    assert!(contents.lines().next().unwrap().starts_with(r#"{"event":"call_start""#));
+}
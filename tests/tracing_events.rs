@@ -0,0 +1,212 @@
+//! Verifies that the inspector emits structured `tracing` events.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::InMemoryDB;
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+/// A recorded event: level, target, and `name=value` pairs of every field.
+#[derive(Debug, Clone)]
+struct Captured {
+    level: Level,
+    target: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Captured {
+    fn message(&self) -> Option<&str> {
+        self.field("message")
+    }
+
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Default)]
+struct FieldCollector(Vec<(String, String)>);
+
+impl Visit for FieldCollector {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name().to_string(), format!("{value:?}")));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_string(), value.to_string()));
+    }
+}
+
+#[derive(Clone, Default)]
+struct CaptureLayer {
+    events: Arc<Mutex<Vec<Captured>>>,
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = FieldCollector::default();
+        event.record(&mut fields);
+        self.events.lock().unwrap().push(Captured {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            fields: fields.0,
+        });
+    }
+}
+
+#[test]
+fn test_tracing_mode_emits_structured_events() {
+    let contract = Address::repeat_byte(0xaa);
+    // 120 x JUMPDEST, then LOG0(0, 0) and STOP: 124 steps in total.
+    let mut code = vec![0x5b; 120];
+    code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]);
+
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, code);
+
+    let layer = CaptureLayer::default();
+    let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Tracing,
+        ..Default::default()
+    });
+    tracing::subscriber::with_default(subscriber, || {
+        common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    });
+
+    let events = layer.events.lock().unwrap().clone();
+    assert!(events.iter().all(|e| e.target == "restd::inspector"));
+
+    let call = events
+        .iter()
+        .find(|e| e.message() == Some("call"))
+        .expect("call event");
+    assert_eq!(call.level, Level::DEBUG);
+    assert_eq!(call.field("address"), Some(contract.to_string().as_str()));
+    assert_eq!(call.field("depth"), Some("0"));
+
+    let step = events
+        .iter()
+        .find(|e| e.message() == Some("step"))
+        .expect("sampled step event");
+    assert_eq!(step.level, Level::TRACE);
+    assert_eq!(step.field("step"), Some("100"));
+    assert_eq!(step.field("opcode"), Some("91"));
+    assert_eq!(step.field("depth"), Some("1"));
+
+    let log = events
+        .iter()
+        .find(|e| e.message() == Some("log"))
+        .expect("log event");
+    assert_eq!(log.level, Level::DEBUG);
+    assert_eq!(log.field("topics"), Some("0"));
+
+    assert!(events.iter().any(|e| e.message() == Some("call ended")));
+    assert_eq!(inspector.steps(), 124);
+}
+
+#[test]
+fn test_silent_mode_emits_nothing() {
+    let contract = Address::repeat_byte(0xaa);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, vec![0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]);
+
+    let layer = CaptureLayer::default();
+    let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    tracing::subscriber::with_default(subscriber, || {
+        common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    });
+
+    assert!(layer.events.lock().unwrap().is_empty());
+    assert_eq!(inspector.calls(), 1);
+}
+
+/// Runs `JUMPDEST JUMPDEST STOP` in tracing mode and returns the step events.
+fn step_events(step_sample_interval: u64) -> Vec<Captured> {
+    let contract = Address::repeat_byte(0xaa);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, vec![0x5b, 0x5b, 0x00]);
+
+    let layer = CaptureLayer::default();
+    let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Tracing,
+        step_sample_interval,
+        ..Default::default()
+    });
+    tracing::subscriber::with_default(subscriber, || {
+        common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    });
+    assert_eq!(inspector.steps(), 3);
+
+    let events = layer.events.lock().unwrap().clone();
+    events
+        .into_iter()
+        .filter(|e| e.message() == Some("step"))
+        .collect()
+}
+
+#[test]
+fn test_interval_one_emits_every_step() {
+    let steps = step_events(1);
+    let numbers: Vec<_> = steps.iter().filter_map(|e| e.field("step")).collect();
+    assert_eq!(numbers, ["1", "2", "3"]);
+}
+
+#[test]
+fn test_interval_zero_never_emits_steps() {
+    assert!(step_events(0).is_empty());
+}
+
+#[test]
+fn test_rate_limit_summarizes_suppressed_events() {
+    let contract = Address::repeat_byte(0xaa);
+    // 10,000 x JUMPDEST, then STOP
+    let mut code = vec![0x5b; 10_000];
+    code.push(0x00);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, contract, code);
+
+    let layer = CaptureLayer::default();
+    let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Tracing,
+        step_sample_interval: 1,
+        max_lines_per_second: Some(50),
+        ..Default::default()
+    });
+    tracing::subscriber::with_default(subscriber, || {
+        common::run(&mut db, common::call_env(contract, vec![]), &mut inspector);
+    });
+    assert_eq!(inspector.steps(), 10_001);
+
+    let events = layer.events.lock().unwrap().clone();
+    let (summaries, lines): (Vec<_>, Vec<_>) = events
+        .iter()
+        .partition(|e| e.message().is_some_and(|m| m.starts_with('(')));
+    assert!(lines.len() <= 50, "{} events written", lines.len());
+    let summary = summaries.last().and_then(|e| e.message()).expect("summary event");
+    let suppressed = 10_004 - lines.len();
+    assert_eq!(
+        summary,
+        format!("({suppressed} messages suppressed by max_lines_per_second)")
+    );
+}
@@ -0,0 +1,160 @@
+//! Integration tests for recording transient storage accesses.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use restd::{
+    HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, StorageAccessKind, TraceLimits,
+};
+use revm::InMemoryDB;
+
+const CONTRACT: Address = Address::repeat_byte(0xa0);
+
+fn silent_inspector() -> HelloWorldInspector {
+    HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    })
+}
+
+fn trace(code: Vec<u8>) -> HelloWorldInspector {
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, code);
+    let mut inspector = silent_inspector();
+    let result = common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_tstore_and_tload_are_recorded() {
+    let inspector = trace(vec![
+        0x60, 0x2a, 0x60, 0x01, 0x5d, // TSTORE(1, 0x2a)
+        0x60, 0x01, 0x5c, 0x50, // POP(TLOAD(1))
+        0x60, 0x07, 0x60, 0x02, 0x5d, // TSTORE(2, 7)
+        0x00,
+    ]);
+
+    assert!(inspector.storage_accesses().is_empty());
+    let accesses = inspector.transient_accesses();
+    let summary: Vec<_> = accesses
+        .iter()
+        .map(|access| (access.kind, access.slot.to::<u64>(), access.value.to::<u64>(), access.pc))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (StorageAccessKind::TransientWrite, 1, 0x2a, 4),
+            (StorageAccessKind::TransientRead, 1, 0x2a, 7),
+            (StorageAccessKind::TransientWrite, 2, 7, 13),
+        ]
+    );
+    assert!(accesses.iter().all(|access| access.address == CONTRACT && !access.reverted));
+
+    let unused = inspector.unused_transient_writes();
+    assert_eq!(unused.len(), 1);
+    assert_eq!(unused[0], accesses[2]);
+}
+
+#[test]
+fn test_overwritten_write_is_unused() {
+    let inspector = trace(vec![
+        0x60, 0x01, 0x60, 0x05, 0x5d, // TSTORE(5, 1)
+        0x60, 0x02, 0x60, 0x05, 0x5d, // TSTORE(5, 2)
+        0x60, 0x05, 0x5c, 0x50, // POP(TLOAD(5))
+        0x00,
+    ]);
+
+    let unused = inspector.unused_transient_writes();
+    assert_eq!(unused.len(), 1);
+    assert_eq!(unused[0].pc, 4);
+    assert_eq!(unused[0].value, U256::from(1));
+}
+
+#[test]
+fn test_transient_storage_is_cleared_between_transactions() {
+    let mut db = InMemoryDB::default();
+    // TSTORE(calldatasize, 1) if there is calldata, else POP(TLOAD(1))
+    common::deploy(
+        &mut db,
+        CONTRACT,
+        vec![
+            0x36, 0x60, 0x0c, 0x57, // JUMPI(12, calldatasize)
+            0x60, 0x01, 0x5c, 0x50, 0x00, // POP(TLOAD(1)), STOP
+            0x00, 0x00, 0x00, 0x5b, // padding, JUMPDEST
+            0x60, 0x01, 0x36, 0x5d, 0x00, // TSTORE(calldatasize, 1), STOP
+        ],
+    );
+    let mut inspector = silent_inspector();
+    common::run(&mut db, common::call_env(CONTRACT, vec![0xff]), &mut inspector);
+    common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+
+    let accesses = inspector.transient_accesses();
+    assert_eq!(accesses.len(), 2);
+    assert_eq!(accesses[1].kind, StorageAccessKind::TransientRead);
+    assert_eq!(accesses[1].value, U256::ZERO);
+    let unused = inspector.unused_transient_writes();
+    assert_eq!(unused.len(), 1);
+    assert_eq!(unused[0].tx_index, 0);
+}
+
+#[test]
+fn test_report_separates_persistent_and_transient_storage() {
+    let inspector = trace(vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, // SSTORE(0, 1)
+        0x60, 0x02, 0x60, 0x00, 0x5d, // TSTORE(0, 2)
+        0x00,
+    ]);
+
+    let report = inspector.report();
+    assert_eq!(report.storage_accesses.len(), 1);
+    assert_eq!(report.transient_accesses.len(), 1);
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["storage_accesses"][0]["kind"], "write");
+    assert_eq!(json["transient_accesses"][0]["kind"], "transient_write");
+    assert_eq!(json["unused_transient_writes"][0]["pc"], 9);
+
+    let printed = report.pretty_print_storage();
+    assert_eq!(
+        printed,
+        format!(
+            "Storage:\n  SSTORE {CONTRACT} [0x0] = 0x1\n\
+             Transient storage:\n  TSTORE {CONTRACT} [0x0] = 0x2 (never read)\n"
+        )
+    );
+}
+
+#[test]
+fn test_transient_accesses_have_their_own_limit() {
+    let code = vec![
+        0x60, 0x01, 0x60, 0x00, 0x5d, // TSTORE(0, 1)
+        0x60, 0x02, 0x60, 0x01, 0x5d, // TSTORE(1, 2)
+        0x60, 0x03, 0x60, 0x00, 0x55, // SSTORE(0, 3)
+        0x00,
+    ];
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CONTRACT, code);
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        limits: TraceLimits {
+            max_storage_accesses: Some(1),
+            max_transient_accesses: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    common::run(&mut db, common::call_env(CONTRACT, vec![]), &mut inspector);
+
+    // The second TSTORE is cut off without crowding out the SSTORE
+    assert_eq!(inspector.transient_accesses().len(), 1);
+    assert_eq!(inspector.storage_accesses().len(), 1);
+    let truncated = inspector.truncated_collections();
+    assert!(truncated.transient_accesses);
+    assert!(!truncated.storage_accesses);
+    assert_eq!(
+        truncated.ensure_complete().unwrap_err().to_string(),
+        "trace limits exceeded, truncated transient accesses"
+    );
+    let json = serde_json::to_value(inspector.report()).unwrap();
+    assert_eq!(json["truncated_collections"]["transient_accesses"], true);
+}
@@ -0,0 +1,91 @@
+//! Snapshot tests for the one-paragraph transaction summary.
+
+mod common;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{Revert, SolError};
+use restd::{
+    evm::transact_inspected, HelloWorldInspector, OutputMode, RevertReason, TxStatus, TxSummary,
+};
+use revm::{
+    primitives::{AccountInfo, Env, TxKind},
+    DatabaseCommit, InMemoryDB,
+};
+
+const ROUTER: Address = Address::repeat_byte(0x80);
+const VAULT: Address = Address::repeat_byte(0x81);
+
+/// Runs `env` against `db` and summarizes it.
+fn summarize(db: &mut InMemoryDB, env: Env) -> TxSummary {
+    let mut inspector = HelloWorldInspector::builder()
+        .output(OutputMode::Silent)
+        .build();
+    let result = transact_inspected(&mut *db, env, &mut inspector).unwrap();
+    let summary = TxSummary::from(&inspector.report(), &result);
+    db.commit(result.state);
+    summary
+}
+
+#[test]
+fn test_successful_create() {
+    // SSTORE(0, 1), LOG0(0, 0), then deploy a single STOP
+    let mut init_code = vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0xa0];
+    init_code.extend(common::return_asm(&[0x00]));
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        common::CALLER,
+        AccountInfo::from_balance(U256::from(10u128.pow(18))),
+    );
+    let mut env = common::call_env(Address::ZERO, init_code);
+    env.tx.transact_to = TxKind::Create;
+    env.tx.value = U256::from(5 * 10u128.pow(17));
+
+    let summary = summarize(&mut db, env);
+    let created = common::CALLER.create(0);
+    assert_eq!(summary.created, Some(created));
+    assert_eq!(summary.status, TxStatus::Success);
+    assert_eq!(
+        summary.to_string(),
+        format!(
+            "{} created {created} and succeeded. Gas 76089/1000000 used, 0 refunded. \
+             0.5 ETH moved in 1 call (1 CREATE), 1 log, 1 storage slot written, max depth 0.",
+            common::CALLER
+        )
+    );
+
+    let json = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["status"], "success");
+    assert!(json.get("to").is_none());
+    let parsed: TxSummary = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, summary);
+}
+
+#[test]
+fn test_reverted_call() {
+    // The router sends 1 wei to the vault, reads from it and reverts
+    let mut router = common::call_asm(VAULT, 1);
+    router.extend(common::static_call_asm(VAULT));
+    router.extend(common::revert_asm(&Revert::from("slippage").abi_encode()));
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, ROUTER, router, U256::from(1));
+    common::deploy(&mut db, VAULT, vec![0x00]);
+
+    let summary = summarize(&mut db, common::call_env(ROUTER, vec![]));
+    assert_eq!(summary.status, TxStatus::Reverted);
+    assert_eq!(
+        summary.revert_reason,
+        Some(RevertReason::Error {
+            message: "slippage".to_string()
+        })
+    );
+    assert_eq!(
+        summary.to_string(),
+        format!(
+            "{} called {ROUTER} and reverted: slippage. Gas 30495/1000000 used, 0 refunded. \
+             0 ETH moved in 3 calls (2 CALL, 1 STATICCALL), 0 logs, 0 storage slots written, \
+             max depth 1.",
+            common::CALLER
+        )
+    );
+    assert_eq!(summary.value_moved, U256::ZERO);
+}
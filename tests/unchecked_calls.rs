@@ -0,0 +1,82 @@
+//! Integration tests for flagging low-level calls whose success flag is
+//! popped without being checked.
+
+mod common;
+
+use alloy_primitives::Address;
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode};
+use revm::{interpreter::opcode, InMemoryDB};
+
+const CALLER: Address = Address::repeat_byte(0x20);
+const CALLEE: Address = Address::repeat_byte(0x21);
+
+/// Program counter of the `CALL` assembled by [`common::call_asm`].
+const CALL_PC: usize = 32;
+
+/// Runs `CALL(gas, CALLEE, 0, 0, 0, 0, 0)` followed by `after`, with the
+/// flag followed for `window` instructions.
+fn trace(after: &[u8], window: usize) -> HelloWorldInspector {
+    let mut code = common::call_asm(CALLEE, 0);
+    // The helper pops the flag right away
+    code.pop();
+    code.extend_from_slice(after);
+    let mut db = InMemoryDB::default();
+    common::deploy(&mut db, CALLER, code);
+    common::deploy(&mut db, CALLEE, vec![0x00]);
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        unchecked_call_window: window,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(CALLER, vec![]), &mut inspector);
+    assert!(result.is_success());
+    inspector
+}
+
+#[test]
+fn test_popped_flag_is_flagged() {
+    // POP, STOP
+    let inspector = trace(&[0x50, 0x00], 8);
+
+    let findings = inspector.unchecked_calls();
+    assert_eq!(findings.len(), 1);
+    let finding = &findings[0];
+    assert_eq!(finding.caller_contract, CALLER);
+    assert_eq!(finding.pc, CALL_PC);
+    assert_eq!(finding.callee, CALLEE);
+    assert_eq!(finding.opcode, opcode::CALL);
+    assert_eq!(finding.depth, 0);
+    assert_eq!(inspector.report().security.unchecked_calls, findings);
+}
+
+#[test]
+fn test_checked_flag_is_not_flagged() {
+    // ISZERO, PUSH1 38, JUMPI, STOP, JUMPDEST, REVERT(0, 0)
+    let checked = [0x15, 0x60, 0x26, 0x57, 0x00, 0x5b, 0x60, 0x00, 0x80, 0xfd];
+    let inspector = trace(&checked, 8);
+
+    assert!(inspector.unchecked_calls().is_empty());
+    let json = inspector.report_json().unwrap();
+    assert!(!json.contains("unchecked_calls"), "{json}");
+}
+
+#[test]
+fn test_flag_is_followed_through_swaps_within_the_window() {
+    // PUSH1 1, SWAP1, POP, POP, STOP: the second instruction after the
+    // call moves the flag under the pushed 1, the third pops it
+    let after = [0x60, 0x01, 0x90, 0x50, 0x50, 0x00];
+
+    let inspector = trace(&after, 8);
+    assert_eq!(inspector.unchecked_calls().len(), 1);
+    assert_eq!(inspector.unchecked_calls()[0].pc, CALL_PC);
+
+    let inspector = trace(&after, 2);
+    assert!(inspector.unchecked_calls().is_empty());
+}
+
+#[test]
+fn test_zero_window_disables_the_check() {
+    let inspector = trace(&[0x50, 0x00], 0);
+    assert!(inspector.unchecked_calls().is_empty());
+}
@@ -0,0 +1,85 @@
+//! Integration tests for the value transfer graph.
+
+mod common;
+
+use alloy_primitives::{Address, I256, U256};
+use restd::{HelloWorldInspector, HelloWorldInspectorConfig, OutputMode, TransferKind};
+use revm::InMemoryDB;
+
+#[test]
+fn test_nested_value_forwarding_and_reverted_transfer() {
+    let outer = Address::repeat_byte(0x20);
+    let middle = Address::repeat_byte(0x30);
+    let sink = Address::repeat_byte(0x40);
+    let reverter = Address::repeat_byte(0x50);
+    let idle = Address::repeat_byte(0x60);
+
+    // Outer: CALL middle with 10 wei, CALL idle without value
+    let mut code_outer = common::call_asm(middle, 10);
+    code_outer.extend(common::call_asm(idle, 0));
+    code_outer.push(0x00);
+    // Middle: forward 4 wei to sink, then 3 wei to a contract that reverts
+    let mut code_middle = common::call_asm(sink, 4);
+    code_middle.extend(common::call_asm(reverter, 3));
+    code_middle.push(0x00);
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, outer, code_outer, U256::from(100));
+    common::deploy(&mut db, middle, code_middle);
+    common::deploy(&mut db, sink, vec![0x00]);
+    common::deploy(&mut db, reverter, common::revert_asm(&[]));
+    common::deploy(&mut db, idle, vec![0x00]);
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    let result = common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+    assert!(result.is_success());
+
+    let transfers = inspector.transfers();
+    let summary: Vec<_> = transfers
+        .iter()
+        .map(|t| (t.from, t.to, t.value.to::<u64>(), t.kind, t.reverted))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (outer, middle, 10, TransferKind::Call, false),
+            (middle, sink, 4, TransferKind::Call, false),
+            (middle, reverter, 3, TransferKind::Call, true),
+        ]
+    );
+
+    let net = inspector.net_balance_changes();
+    assert_eq!(net.len(), 3);
+    assert_eq!(net[&outer], I256::try_from(-10).unwrap());
+    assert_eq!(net[&middle], I256::try_from(6).unwrap());
+    assert_eq!(net[&sink], I256::try_from(4).unwrap());
+    assert!(!net.contains_key(&reverter));
+}
+
+#[test]
+fn test_create_endowment_records_created_address() {
+    let outer = Address::repeat_byte(0x20);
+    // PUSH5 <PUSH1 0 PUSH1 0 RETURN>, PUSH1 0, MSTORE, CREATE(5, 27, 5), POP, STOP
+    let mut code = vec![0x64, 0x60, 0x00, 0x60, 0x00, 0xf3, 0x60, 0x00, 0x52];
+    code.extend_from_slice(&[0x60, 0x05, 0x60, 0x1b, 0x60, 0x05, 0xf0, 0x50, 0x00]);
+
+    let mut db = InMemoryDB::default();
+    common::deploy_with_balance(&mut db, outer, code, U256::from(5));
+
+    let mut inspector = HelloWorldInspector::with_config(HelloWorldInspectorConfig {
+        output: OutputMode::Silent,
+        ..Default::default()
+    });
+    common::run(&mut db, common::call_env(outer, vec![]), &mut inspector);
+
+    let transfers = inspector.transfers();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].kind, TransferKind::Create);
+    assert_eq!(transfers[0].from, outer);
+    assert_eq!(transfers[0].to, outer.create(1));
+    assert_eq!(transfers[0].value, U256::from(5));
+    assert!(!transfers[0].reverted);
+}